@@ -36,7 +36,7 @@ fn main() {
     };
 
     // Try to select best chain
-    let selected_chain = chain_manager.select_best_chain(&docker_content, Some("docker-container.log"));
+    let selected_chain = chain_manager.select_best_chain(&docker_content, Some("docker-container.log"), 0).0;
     match selected_chain {
         Some(chain) => println!("Selected chain: {}", chain.name),
         None => println!("No chain selected"),
@@ -0,0 +1,134 @@
+/// 条目锚点
+///
+/// 为日志条目生成稳定的锚点（文件 + 行号 + 内容哈希）。书签、固定条目和分享的会话文件
+/// 保存锚点后，即使重新解析后行号发生偏移（文件头部被截断、合并折行设置变化等），
+/// 也能按内容哈希重新找到原来的条目。
+///
+/// 锚点格式为 `<文件哈希>-<行号>-<内容哈希>`，哈希为16位十六进制的FNV-1a，跨版本和平台稳定。
+/// 内容哈希基于消息正文，严格模式开关不影响锚点。
+
+use crate::i18n::Message;
+use crate::models::LogLine;
+use crate::utils::string_utils::truncate_chars;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// FNV-1a 64位哈希
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325u64, |h, &b| (h ^ b as u64).wrapping_mul(0x100000001b3))
+}
+
+/// 条目锚点
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EntryAnchor {
+    /// 文件路径的哈希
+    pub file_hash: u64,
+    /// 生成锚点时的行号
+    pub line_number: usize,
+    /// 条目内容的哈希
+    pub content_hash: u64,
+}
+
+impl EntryAnchor {
+    /// 为条目生成锚点
+    pub fn new(file_path: &str, entry: &LogLine) -> Self {
+        Self {
+            file_hash: fnv1a(file_path.as_bytes()),
+            line_number: entry.line_number,
+            content_hash: content_hash(entry),
+        }
+    }
+
+    /// 解析锚点字符串
+    ///
+    /// # Returns
+    /// - `Err(String)`: 格式不是 `<文件哈希>-<行号>-<内容哈希>` 时的错误信息
+    pub fn parse(anchor: &str) -> Result<Self, String> {
+        let invalid = || Message::new("anchor.invalid").with("anchor", anchor).text();
+        let mut parts = anchor.trim().splitn(3, '-');
+        let (Some(file), Some(line), Some(content)) = (parts.next(), parts.next(), parts.next()) else {
+            return Err(invalid());
+        };
+        Ok(Self {
+            file_hash: u64::from_str_radix(file, 16).map_err(|_| invalid())?,
+            line_number: line.parse().map_err(|_| invalid())?,
+            content_hash: u64::from_str_radix(content, 16).map_err(|_| invalid())?,
+        })
+    }
+}
+
+impl fmt::Display for EntryAnchor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}-{}-{:016x}", self.file_hash, self.line_number, self.content_hash)
+    }
+}
+
+/// 条目内容的哈希（忽略行尾空白）
+pub fn content_hash(entry: &LogLine) -> u64 {
+    fnv1a(entry.message_text().trim_end().as_bytes())
+}
+
+/// 锚点的定位结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResolvedAnchor {
+    /// 条目当前的行号
+    pub line_number: usize,
+    /// 相对生成锚点时的行号偏移（正数表示下移）
+    pub line_offset: i64,
+    /// 条目内容预览
+    pub preview: String,
+}
+
+/// 在解析结果中定位锚点对应的条目
+///
+/// 行号和内容哈希都一致时直接命中；否则取内容哈希相同、离原行号最近的条目。
+///
+/// # 参数
+/// - `anchor`: 条目锚点
+/// - `file_path`: 日志文件路径（须与生成锚点时一致）
+/// - `entries`: 当前的解析结果
+///
+/// # Returns
+/// - `Ok(Some(ResolvedAnchor))`: 找到的条目
+/// - `Ok(None)`: 文件中已没有内容相同的条目
+/// - `Err(String)`: 锚点不属于该文件时的错误信息
+pub fn resolve_anchor(anchor: &EntryAnchor, file_path: &str, entries: &[LogLine]) -> Result<Option<ResolvedAnchor>, String> {
+    if anchor.file_hash != fnv1a(file_path.as_bytes()) {
+        return Err(Message::new("anchor.wrong_file").with("path", file_path).text());
+    }
+
+    let found = entries.iter()
+        .filter(|entry| content_hash(entry) == anchor.content_hash)
+        .min_by_key(|entry| entry.line_number.abs_diff(anchor.line_number));
+    Ok(found.map(|entry| ResolvedAnchor {
+        line_number: entry.line_number,
+        line_offset: entry.line_number as i64 - anchor.line_number as i64,
+        preview: truncate_chars(entry.formatted_content.as_deref().unwrap_or(entry.message_text()), 200),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_anchor_survives_line_shift() {
+        let original = [LogLine::new(10, "retry"), LogLine::new(11, "Connection refused"), LogLine::new(40, "retry")];
+        let anchor = EntryAnchor::new("/var/log/app.log", &original[1]);
+        let parsed = EntryAnchor::parse(&anchor.to_string()).unwrap();
+        assert_eq!(parsed, anchor);
+
+        // 文件头部被截断后行号整体上移
+        let shifted = vec![LogLine::new(3, "retry"), LogLine::new(4, "Connection refused  "), LogLine::new(33, "retry")];
+        let resolved = resolve_anchor(&parsed, "/var/log/app.log", &shifted).unwrap().unwrap();
+        assert_eq!((resolved.line_number, resolved.line_offset), (4, -7));
+
+        // 重复内容取离原行号最近的条目
+        let retry = EntryAnchor::new("/var/log/app.log", &original[2]);
+        assert_eq!(resolve_anchor(&retry, "/var/log/app.log", &shifted).unwrap().unwrap().line_number, 33);
+
+        assert!(resolve_anchor(&anchor, "/var/log/other.log", &shifted).is_err());
+        assert!(resolve_anchor(&anchor, "/var/log/app.log", &shifted[..1]).unwrap().is_none());
+        assert!(EntryAnchor::parse("not-an-anchor").is_err());
+    }
+}
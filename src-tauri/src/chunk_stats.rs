@@ -0,0 +1,179 @@
+/// 分块解析的累计统计
+///
+/// 分块解析时每个响应中的 `ParseStats` 只包含当前块，界面在逐块加载过程中无法显示准确的总数。
+/// `ChunkStatsRegistry` 按文件累计已解析各块的条目数、错误行数和级别分布：
+/// - 同一块重新请求时替换该块之前的统计，不会重复计数
+/// - 从重叠区延续过来的条目（`chunk_continued=true`）已计入上一块，不再计数
+/// - 请求第0块表示重新加载文件，清空之前的统计
+
+use crate::models::LogLine;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+/// 一块的统计
+#[derive(Debug, Clone, Default)]
+struct ChunkContribution {
+    lines: usize,
+    success_lines: usize,
+    error_lines: usize,
+    levels: BTreeMap<String, usize>,
+}
+
+/// 文件的累计统计
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AccumulatedStats {
+    /// 文件路径
+    pub file_path: String,
+    /// 已解析的块数
+    pub chunks_parsed: usize,
+    /// 总块数（最近一块响应中的值，自适应分块时为估算值）
+    pub total_chunks: usize,
+    /// 文件中的非空行总数
+    pub total_lines: usize,
+    /// 已解析块中的行数
+    pub parsed_lines: usize,
+    /// 已解析的条目数
+    pub success_lines: usize,
+    /// 解析失败的行数
+    pub error_lines: usize,
+    /// 级别分布（级别 -> 条目数，没有级别的条目不计入）
+    pub levels: BTreeMap<String, usize>,
+    /// 是否所有块都已解析
+    pub complete: bool,
+}
+
+/// 一块的解析结果摘要
+#[derive(Debug, Clone, Copy)]
+pub struct ChunkReport<'a> {
+    /// 块索引（全量解析时为0）
+    pub chunk_index: usize,
+    /// 总块数（全量解析时为1）
+    pub total_chunks: usize,
+    /// 文件中的非空行总数
+    pub total_lines: usize,
+    /// 块中的行数
+    pub chunk_lines: usize,
+    /// 解析失败的行数
+    pub error_lines: usize,
+    /// 块的解析条目
+    pub entries: &'a [LogLine],
+}
+
+#[derive(Debug, Default)]
+struct FileStats {
+    total_chunks: usize,
+    total_lines: usize,
+    chunks: HashMap<usize, ChunkContribution>,
+}
+
+/// 按文件累计分块解析统计
+pub struct ChunkStatsRegistry {
+    files: Mutex<HashMap<String, FileStats>>,
+}
+
+impl ChunkStatsRegistry {
+    /// 创建空的统计
+    pub fn new() -> Self {
+        Self { files: Mutex::new(HashMap::new()) }
+    }
+
+    /// 记录一块的解析结果
+    ///
+    /// # 参数
+    /// - `file_path`: 文件路径
+    /// - `report`: 块的解析结果摘要
+    pub fn record(&self, file_path: &str, report: ChunkReport) {
+        let Ok(mut files) = self.files.lock() else { return };
+        if report.chunk_index == 0 {
+            files.remove(file_path);
+        }
+
+        let mut contribution = ChunkContribution {
+            lines: report.chunk_lines,
+            error_lines: report.error_lines,
+            ..Default::default()
+        };
+        for entry in report.entries {
+            if entry.metadata.get("chunk_continued").is_some_and(|v| v == "true") {
+                continue;
+            }
+            contribution.success_lines += 1;
+            if let Some(level) = &entry.level {
+                *contribution.levels.entry(level.clone()).or_insert(0) += 1;
+            }
+        }
+
+        let stats = files.entry(file_path.to_string()).or_default();
+        stats.total_chunks = report.total_chunks;
+        stats.total_lines = report.total_lines;
+        stats.chunks.insert(report.chunk_index, contribution);
+    }
+
+    /// 获取文件的累计统计（没有解析记录时返回None）
+    pub fn get(&self, file_path: &str) -> Option<AccumulatedStats> {
+        let files = self.files.lock().ok()?;
+        let stats = files.get(file_path)?;
+
+        let mut accumulated = AccumulatedStats {
+            file_path: file_path.to_string(),
+            chunks_parsed: stats.chunks.len(),
+            total_chunks: stats.total_chunks,
+            total_lines: stats.total_lines,
+            ..Default::default()
+        };
+        for chunk in stats.chunks.values() {
+            accumulated.parsed_lines += chunk.lines;
+            accumulated.success_lines += chunk.success_lines;
+            accumulated.error_lines += chunk.error_lines;
+            for (level, count) in &chunk.levels {
+                *accumulated.levels.entry(level.clone()).or_insert(0) += count;
+            }
+        }
+        accumulated.complete = accumulated.parsed_lines >= stats.total_lines;
+        Some(accumulated)
+    }
+}
+
+impl Default for ChunkStatsRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(line_number: usize, level: &str, continued: bool) -> LogLine {
+        let metadata = if continued { HashMap::from([("chunk_continued".to_string(), "true".to_string())]) } else { HashMap::new() };
+        LogLine { level: Some(level.to_string()), metadata, ..LogLine::new(line_number, "") }
+    }
+
+    fn report(chunk_index: usize, error_lines: usize, entries: &[LogLine]) -> ChunkReport<'_> {
+        ChunkReport { chunk_index, total_chunks: 2, total_lines: 4, chunk_lines: 2, error_lines, entries }
+    }
+
+    #[test]
+    fn test_accumulates_chunks_without_double_counting() {
+        let registry = ChunkStatsRegistry::new();
+        let first = [entry(1, "INFO", false), entry(2, "ERROR", false)];
+        let second = [entry(2, "ERROR", true), entry(3, "INFO", false), entry(4, "WARN", false)];
+
+        registry.record("app.log", report(0, 0, &first));
+        registry.record("app.log", report(1, 1, &second));
+        // 重新请求同一块只替换该块的统计
+        registry.record("app.log", report(1, 1, &second));
+
+        let stats = registry.get("app.log").unwrap();
+        assert_eq!((stats.chunks_parsed, stats.parsed_lines, stats.success_lines, stats.error_lines), (2, 4, 4, 1));
+        assert_eq!(stats.levels, BTreeMap::from([("ERROR".to_string(), 1), ("INFO".to_string(), 2), ("WARN".to_string(), 1)]));
+        assert!(stats.complete);
+
+        // 第0块表示重新加载
+        registry.record("app.log", report(0, 0, &first[..1]));
+        let stats = registry.get("app.log").unwrap();
+        assert_eq!((stats.chunks_parsed, stats.success_lines, stats.complete), (1, 1, false));
+        assert!(registry.get("other.log").is_none());
+    }
+}
@@ -0,0 +1,53 @@
+use crate::i18n::Message;
+use regex::RegexBuilder;
+use serde::{Deserialize, Serialize};
+
+/// 用户错误解释规则的存储键
+pub const EXPLAIN_RULES_KEY: &str = "explain.rules";
+
+/// 错误解释规则
+///
+/// 内置规则来自 `explain/knowledge_base.yaml`，用户规则保存在配置中；
+/// 条目内容匹配任一规则（不区分大小写）即附上该规则的解释和处理建议。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExplainRule {
+    /// 规则ID（唯一，用户规则与内置规则ID相同时覆盖内置规则）
+    pub id: String,
+    /// 标题
+    pub title: String,
+    /// 匹配规则（正则表达式）
+    pub patterns: Vec<String>,
+    /// 原因解释
+    pub explanation: String,
+    /// 处理建议
+    #[serde(default)]
+    pub next_steps: Vec<String>,
+}
+
+impl ExplainRule {
+    /// 校验规则
+    ///
+    /// ID和标题不能为空，至少需要一个匹配规则，且所有匹配规则都必须是有效的正则表达式。
+    pub fn validate(&self) -> Result<(), String> {
+        if self.id.trim().is_empty() || self.title.trim().is_empty() {
+            return Err(Message::new("explain_rule.missing_id").text());
+        }
+        if self.patterns.is_empty() {
+            return Err(Message::new("explain_rule.no_patterns").with("id", &self.id).text());
+        }
+        for pattern in &self.patterns {
+            RegexBuilder::new(pattern)
+                .case_insensitive(true)
+                .build()
+                .map_err(|e| Message::new("explain_rule.invalid_pattern").with("id", &self.id).with("error", e).text())?;
+        }
+        Ok(())
+    }
+}
+
+/// 添加或替换（按ID）用户规则，列表按ID排序
+pub fn upsert_rule(rules: &mut Vec<ExplainRule>, rule: ExplainRule) {
+    rules.retain(|r| r.id != rule.id);
+    rules.push(rule);
+    rules.sort_by(|a, b| a.id.cmp(&b.id));
+}
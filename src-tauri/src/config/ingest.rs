@@ -0,0 +1,96 @@
+use crate::i18n::Message;
+use serde::{Deserialize, Serialize};
+
+/// 目录采集设置的存储键
+pub const INGEST_SETTINGS_KEY: &str = "ingest.settings";
+
+/// 目录采集设置（本地日志服务模式）
+///
+/// 启用后后台持续扫描配置的目录，把新增或变化的日志文件解析后写入会话数据库，
+/// 并按保留天数和容量上限删除最旧的文件，重启应用后仍可查询最近一段时间的日志。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IngestSettings {
+    /// 是否启用目录采集
+    #[serde(default)]
+    pub enabled: bool,
+    /// 采集的目录（递归扫描）
+    #[serde(default)]
+    pub folders: Vec<String>,
+    /// 文件名匹配规则，支持 `*` 和 `?` 通配符（为空表示全部文件）
+    #[serde(default = "default_patterns")]
+    pub patterns: Vec<String>,
+    /// 写入的会话数据库名称
+    #[serde(default = "default_session")]
+    pub session: String,
+    /// 扫描间隔（秒）
+    #[serde(default = "default_poll_interval_secs")]
+    pub poll_interval_secs: u64,
+    /// 保留天数，超过天数未更新的文件被删除（0表示不限）
+    #[serde(default = "default_retention_days")]
+    pub retention_days: u32,
+    /// 容量上限（GB，按原始文件大小合计），超过时删除最旧的文件（0表示不限）
+    #[serde(default = "default_retention_gb")]
+    pub retention_gb: f64,
+}
+
+fn default_patterns() -> Vec<String> {
+    vec!["*.log".to_string(), "*.log.*".to_string()]
+}
+
+fn default_session() -> String {
+    "ingest".to_string()
+}
+
+fn default_poll_interval_secs() -> u64 {
+    30
+}
+
+fn default_retention_days() -> u32 {
+    7
+}
+
+fn default_retention_gb() -> f64 {
+    2.0
+}
+
+impl Default for IngestSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            folders: Vec::new(),
+            patterns: default_patterns(),
+            session: default_session(),
+            poll_interval_secs: default_poll_interval_secs(),
+            retention_days: default_retention_days(),
+            retention_gb: default_retention_gb(),
+        }
+    }
+}
+
+impl IngestSettings {
+    /// 校验设置
+    ///
+    /// 启用时至少需要一个目录；会话名称不能为空，容量上限不能为负数。
+    pub fn validate(&self) -> Result<(), String> {
+        if self.enabled && self.folders.iter().all(|folder| folder.trim().is_empty()) {
+            return Err(Message::new("ingest.no_directories").text());
+        }
+        if self.session.trim().is_empty() {
+            return Err(Message::new("ingest.empty_session").text());
+        }
+        if !self.retention_gb.is_finite() || self.retention_gb < 0.0 {
+            return Err(Message::new("ingest.invalid_retention").with("value", self.retention_gb).text());
+        }
+        Ok(())
+    }
+
+    /// 容量上限（字节），未限制时返回None
+    pub fn max_bytes(&self) -> Option<u64> {
+        (self.retention_gb > 0.0).then_some((self.retention_gb * 1024.0 * 1024.0 * 1024.0) as u64)
+    }
+
+    /// 保留截止时间，早于该时间保存的文件被删除；未限制时返回None
+    pub fn retention_cutoff(&self, now: chrono::DateTime<chrono::Utc>) -> Option<chrono::DateTime<chrono::Utc>> {
+        (self.retention_days > 0).then(|| now - chrono::Duration::days(self.retention_days as i64))
+    }
+}
@@ -0,0 +1,84 @@
+use crate::i18n::Message;
+use serde::{Deserialize, Serialize};
+
+/// issue跟踪系统列表的存储键
+pub const ISSUE_TRACKERS_KEY: &str = "issues.trackers";
+
+/// issue跟踪系统类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrackerKind {
+    /// GitHub Issues
+    Github,
+    /// Jira
+    Jira,
+}
+
+/// 已配置的issue跟踪系统
+///
+/// 与远程主机一样不保存令牌本身，只保存存放令牌的环境变量名，创建issue时从环境变量读取。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IssueTracker {
+    /// 显示名称（唯一）
+    pub name: String,
+    /// 系统类型
+    pub kind: TrackerKind,
+    /// 服务地址（GitHub默认 `https://api.github.com`，GitHub Enterprise为 `https://<host>/api/v3`；Jira为站点地址）
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    /// 项目：GitHub为 `owner/repo`，Jira为项目键（如 `OPS`）
+    pub project: String,
+    /// Jira账号邮箱（Jira Cloud的基本认证需要，GitHub不使用）
+    #[serde(default)]
+    pub user: Option<String>,
+    /// 存放访问令牌的环境变量名
+    pub token_env: String,
+    /// Jira的issue类型（默认 `Bug`）或GitHub的标签（逗号分隔）
+    #[serde(default)]
+    pub issue_type: Option<String>,
+}
+
+impl IssueTracker {
+    /// 校验配置
+    ///
+    /// 名称、项目和环境变量名不能为空；GitHub项目必须是 `owner/repo`，Jira需要服务地址；
+    /// 服务地址必须是HTTP(S)链接。
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err(Message::new("issue_config.empty_name").text());
+        }
+        if self.token_env.trim().is_empty() {
+            return Err(Message::new("issue_config.missing_token_env").text());
+        }
+        match self.kind {
+            TrackerKind::Github if self.project.split('/').filter(|part| !part.trim().is_empty()).count() != 2 => {
+                return Err(Message::new("issue_config.invalid_github_project").with("project", format!("{:?}", self.project)).text());
+            }
+            TrackerKind::Jira if self.project.trim().is_empty() => return Err(Message::new("issue_config.empty_jira_project").text()),
+            TrackerKind::Jira if self.endpoint.is_none() => return Err(Message::new("issue_config.missing_jira_endpoint").text()),
+            _ => {}
+        }
+        if let Some(endpoint) = &self.endpoint {
+            if !endpoint.starts_with("https://") && !endpoint.starts_with("http://") {
+                return Err(Message::new("config.invalid_endpoint").with("endpoint", endpoint).text());
+            }
+        }
+        Ok(())
+    }
+
+    /// 服务地址（去掉末尾的 `/`）
+    pub fn base_url(&self) -> String {
+        let default = match self.kind {
+            TrackerKind::Github => "https://api.github.com",
+            TrackerKind::Jira => "",
+        };
+        self.endpoint.as_deref().unwrap_or(default).trim_end_matches('/').to_string()
+    }
+}
+
+/// 添加或替换（按名称）issue跟踪系统，列表按名称排序
+pub fn upsert_tracker(trackers: &mut Vec<IssueTracker>, tracker: IssueTracker) {
+    trackers.retain(|t| t.name != tracker.name);
+    trackers.push(tracker);
+    trackers.sort_by(|a, b| a.name.cmp(&b.name));
+}
@@ -0,0 +1,43 @@
+use crate::i18n::Message;
+use serde::{Deserialize, Serialize};
+
+/// 内存预算设置的存储键
+pub const MEMORY_BUDGET_KEY: &str = "memory.budget";
+
+/// 最小内存预算（MB）
+pub const MIN_BUDGET_MB: u64 = 64;
+
+/// 缓存内存预算设置
+///
+/// 增量解析缓存和行索引缓存的总占用超过预算时，按最近最少使用淘汰文件的缓存。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryBudgetSettings {
+    /// 预算（MB）
+    #[serde(default = "default_budget_mb")]
+    pub budget_mb: u64,
+}
+
+fn default_budget_mb() -> u64 {
+    2048
+}
+
+impl Default for MemoryBudgetSettings {
+    fn default() -> Self {
+        Self { budget_mb: default_budget_mb() }
+    }
+}
+
+impl MemoryBudgetSettings {
+    /// 校验设置
+    pub fn validate(&self) -> Result<(), String> {
+        if self.budget_mb < MIN_BUDGET_MB {
+            return Err(Message::new("memory.budget_too_small").with("min", MIN_BUDGET_MB).text());
+        }
+        Ok(())
+    }
+
+    /// 预算（字节）
+    pub fn budget_bytes(&self) -> u64 {
+        self.budget_mb.saturating_mul(1024 * 1024)
+    }
+}
@@ -3,14 +3,35 @@ pub mod parse;
 pub mod plugin;
 pub mod window;
 pub mod storage;
+pub mod pins;
+pub mod remote;
+pub mod ingest;
+pub mod issues;
+pub mod summarizer;
+pub mod explain;
+pub mod profiles;
+pub mod memory;
+pub mod recent;
+pub mod palette;
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 // Re-export commonly used types
-pub use theme::{ThemeConfig, ThemeMode};
+pub use theme::{LevelStyle, ThemeConfig, ThemeMode};
 pub use parse::ParseConfig;
 pub use plugin::PluginConfig;
 pub use window::WindowConfig;
+pub use pins::{PinnedEntry, PinnedFile};
+pub use remote::RemoteHost;
+pub use ingest::IngestSettings;
+pub use issues::IssueTracker;
+pub use summarizer::SummarizerSettings;
+pub use explain::ExplainRule;
+pub use profiles::ParsingProfile;
+pub use memory::MemoryBudgetSettings;
+pub use recent::RecentFile;
+pub use palette::PaletteSettings;
 pub use storage::{ConfigType};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -214,6 +235,265 @@ impl ConfigService {
         Ok(())
     }
 
+    pub fn get_pinned_entries(&self, file_path: &str, session_id: Option<&str>) -> Result<Vec<PinnedEntry>, String> {
+        let key = pins::pins_key(file_path, session_id);
+        match self.storage.get_config(&key)
+            .map_err(|e| format!("Failed to load pinned entries: {}", e))?
+        {
+            Some(value) => serde_json::from_str(&value)
+                .map_err(|e| format!("Failed to parse pinned entries: {}", e)),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    pub fn set_pinned_entries(&mut self, file_path: &str, session_id: Option<&str>, pinned: &[PinnedEntry]) -> Result<(), String> {
+        let key = pins::pins_key(file_path, session_id);
+        if pinned.is_empty() {
+            self.storage.delete_config(&key)
+                .map_err(|e| format!("Failed to delete pinned entries: {}", e))?;
+            return Ok(());
+        }
+
+        let value = serde_json::to_string(pinned)
+            .map_err(|e| format!("Failed to serialize pinned entries: {}", e))?;
+        self.storage.set_config(&key, &value, ConfigType::General)
+            .map_err(|e| format!("Failed to save pinned entries: {}", e))?;
+        Ok(())
+    }
+
+    /// 所有文件（及会话）的固定条目，按文件路径和会话名称排序
+    pub fn all_pinned_entries(&self) -> Result<Vec<PinnedFile>, String> {
+        let configs = self.storage.get_configs_by_type(&ConfigType::General)
+            .map_err(|e| format!("Failed to load pinned entries: {}", e))?;
+        let mut all = Vec::new();
+        for (key, value) in configs {
+            let Some((file_path, session_id)) = pins::parse_pins_key(&key) else { continue };
+            let pinned: Vec<PinnedEntry> = serde_json::from_str(&value)
+                .map_err(|e| format!("Failed to parse pinned entries: {}", e))?;
+            all.push(PinnedFile { file_path: file_path.to_string(), session_id: session_id.map(str::to_string), pinned });
+        }
+        all.sort_by(|a, b| (&a.file_path, &a.session_id).cmp(&(&b.file_path, &b.session_id)));
+        Ok(all)
+    }
+
+    pub fn get_recent_files(&self) -> Result<Vec<RecentFile>, String> {
+        match self.storage.get_config(recent::RECENT_FILES_KEY)
+            .map_err(|e| format!("Failed to load recent files: {}", e))?
+        {
+            Some(value) => serde_json::from_str(&value)
+                .map_err(|e| format!("Failed to parse recent files: {}", e)),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    pub fn record_recent_file(&mut self, file_path: &str) -> Result<(), String> {
+        let mut files = self.get_recent_files()?;
+        recent::touch_recent(&mut files, file_path);
+        let value = serde_json::to_string(&files)
+            .map_err(|e| format!("Failed to serialize recent files: {}", e))?;
+        self.storage.set_config(recent::RECENT_FILES_KEY, &value, ConfigType::General)
+            .map_err(|e| format!("Failed to save recent files: {}", e))?;
+        Ok(())
+    }
+
+    pub fn get_palette_settings(&self) -> Result<PaletteSettings, String> {
+        match self.storage.get_config(palette::PALETTE_SETTINGS_KEY)
+            .map_err(|e| format!("Failed to load palette settings: {}", e))?
+        {
+            Some(value) => serde_json::from_str(&value)
+                .map_err(|e| format!("Failed to parse palette settings: {}", e)),
+            None => Ok(PaletteSettings::default()),
+        }
+    }
+
+    pub fn set_palette_settings(&mut self, settings: &PaletteSettings) -> Result<(), String> {
+        settings.validate()?;
+        let value = serde_json::to_string(settings)
+            .map_err(|e| format!("Failed to serialize palette settings: {}", e))?;
+        self.storage.set_config(palette::PALETTE_SETTINGS_KEY, &value, ConfigType::General)
+            .map_err(|e| format!("Failed to save palette settings: {}", e))?;
+        Ok(())
+    }
+
+    pub fn get_format_overrides(&self) -> Result<HashMap<String, String>, String> {
+        match self.storage.get_config(parse::FORMAT_OVERRIDES_KEY)
+            .map_err(|e| format!("Failed to load format overrides: {}", e))?
+        {
+            Some(value) => serde_json::from_str(&value)
+                .map_err(|e| format!("Failed to parse format overrides: {}", e)),
+            None => Ok(HashMap::new()),
+        }
+    }
+
+    pub fn get_format_override(&self, file_path: &str) -> Result<Option<String>, String> {
+        Ok(self.get_format_overrides()?.remove(file_path))
+    }
+
+    pub fn set_format_override(&mut self, file_path: &str, format: Option<&str>) -> Result<(), String> {
+        let mut overrides = self.get_format_overrides()?;
+        match format {
+            Some(format) => overrides.insert(file_path.to_string(), format.to_string()),
+            None => overrides.remove(file_path),
+        };
+
+        let value = serde_json::to_string(&overrides)
+            .map_err(|e| format!("Failed to serialize format overrides: {}", e))?;
+        self.storage.set_config(parse::FORMAT_OVERRIDES_KEY, &value, ConfigType::Parse)
+            .map_err(|e| format!("Failed to save format overrides: {}", e))?;
+        Ok(())
+    }
+
+    pub fn get_remote_hosts(&self) -> Result<Vec<RemoteHost>, String> {
+        match self.storage.get_config(remote::REMOTE_HOSTS_KEY)
+            .map_err(|e| format!("Failed to load remote hosts: {}", e))?
+        {
+            Some(value) => serde_json::from_str(&value)
+                .map_err(|e| format!("Failed to parse remote hosts: {}", e)),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    pub fn set_remote_hosts(&mut self, hosts: &[RemoteHost]) -> Result<(), String> {
+        let value = serde_json::to_string(hosts)
+            .map_err(|e| format!("Failed to serialize remote hosts: {}", e))?;
+        self.storage.set_config(remote::REMOTE_HOSTS_KEY, &value, ConfigType::General)
+            .map_err(|e| format!("Failed to save remote hosts: {}", e))?;
+        Ok(())
+    }
+
+    pub fn get_ingest_settings(&self) -> Result<IngestSettings, String> {
+        match self.storage.get_config(ingest::INGEST_SETTINGS_KEY)
+            .map_err(|e| format!("Failed to load ingest settings: {}", e))?
+        {
+            Some(value) => serde_json::from_str(&value)
+                .map_err(|e| format!("Failed to parse ingest settings: {}", e)),
+            None => Ok(IngestSettings::default()),
+        }
+    }
+
+    pub fn set_ingest_settings(&mut self, settings: &IngestSettings) -> Result<(), String> {
+        let value = serde_json::to_string(settings)
+            .map_err(|e| format!("Failed to serialize ingest settings: {}", e))?;
+        self.storage.set_config(ingest::INGEST_SETTINGS_KEY, &value, ConfigType::General)
+            .map_err(|e| format!("Failed to save ingest settings: {}", e))?;
+        Ok(())
+    }
+
+    pub fn get_issue_trackers(&self) -> Result<Vec<IssueTracker>, String> {
+        match self.storage.get_config(issues::ISSUE_TRACKERS_KEY)
+            .map_err(|e| format!("Failed to load issue trackers: {}", e))?
+        {
+            Some(value) => serde_json::from_str(&value)
+                .map_err(|e| format!("Failed to parse issue trackers: {}", e)),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    pub fn set_issue_trackers(&mut self, trackers: &[IssueTracker]) -> Result<(), String> {
+        let value = serde_json::to_string(trackers)
+            .map_err(|e| format!("Failed to serialize issue trackers: {}", e))?;
+        self.storage.set_config(issues::ISSUE_TRACKERS_KEY, &value, ConfigType::General)
+            .map_err(|e| format!("Failed to save issue trackers: {}", e))?;
+        Ok(())
+    }
+
+    pub fn get_summarizer_settings(&self) -> Result<SummarizerSettings, String> {
+        match self.storage.get_config(summarizer::SUMMARIZER_SETTINGS_KEY)
+            .map_err(|e| format!("Failed to load summarizer settings: {}", e))?
+        {
+            Some(value) => serde_json::from_str(&value)
+                .map_err(|e| format!("Failed to parse summarizer settings: {}", e)),
+            None => Ok(SummarizerSettings::default()),
+        }
+    }
+
+    pub fn set_summarizer_settings(&mut self, settings: &SummarizerSettings) -> Result<(), String> {
+        let value = serde_json::to_string(settings)
+            .map_err(|e| format!("Failed to serialize summarizer settings: {}", e))?;
+        self.storage.set_config(summarizer::SUMMARIZER_SETTINGS_KEY, &value, ConfigType::General)
+            .map_err(|e| format!("Failed to save summarizer settings: {}", e))?;
+        Ok(())
+    }
+
+    pub fn get_memory_budget(&self) -> Result<MemoryBudgetSettings, String> {
+        match self.storage.get_config(memory::MEMORY_BUDGET_KEY)
+            .map_err(|e| format!("Failed to load memory budget: {}", e))?
+        {
+            Some(value) => serde_json::from_str(&value)
+                .map_err(|e| format!("Failed to parse memory budget: {}", e)),
+            None => Ok(MemoryBudgetSettings::default()),
+        }
+    }
+
+    pub fn set_memory_budget(&mut self, settings: &MemoryBudgetSettings) -> Result<(), String> {
+        let value = serde_json::to_string(settings)
+            .map_err(|e| format!("Failed to serialize memory budget: {}", e))?;
+        self.storage.set_config(memory::MEMORY_BUDGET_KEY, &value, ConfigType::General)
+            .map_err(|e| format!("Failed to save memory budget: {}", e))?;
+        Ok(())
+    }
+
+    pub fn get_explain_rules(&self) -> Result<Vec<ExplainRule>, String> {
+        match self.storage.get_config(explain::EXPLAIN_RULES_KEY)
+            .map_err(|e| format!("Failed to load explain rules: {}", e))?
+        {
+            Some(value) => serde_json::from_str(&value)
+                .map_err(|e| format!("Failed to parse explain rules: {}", e)),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    pub fn set_explain_rules(&mut self, rules: &[ExplainRule]) -> Result<(), String> {
+        let value = serde_json::to_string(rules)
+            .map_err(|e| format!("Failed to serialize explain rules: {}", e))?;
+        self.storage.set_config(explain::EXPLAIN_RULES_KEY, &value, ConfigType::General)
+            .map_err(|e| format!("Failed to save explain rules: {}", e))?;
+        Ok(())
+    }
+
+    pub fn get_profiles(&self) -> Result<Vec<ParsingProfile>, String> {
+        match self.storage.get_config(profiles::PROFILES_KEY)
+            .map_err(|e| format!("Failed to load profiles: {}", e))?
+        {
+            Some(value) => serde_json::from_str(&value)
+                .map_err(|e| format!("Failed to parse profiles: {}", e)),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    pub fn set_profiles(&mut self, profiles: &[ParsingProfile]) -> Result<(), String> {
+        let value = serde_json::to_string(profiles)
+            .map_err(|e| format!("Failed to serialize profiles: {}", e))?;
+        self.storage.set_config(profiles::PROFILES_KEY, &value, ConfigType::Parse)
+            .map_err(|e| format!("Failed to save profiles: {}", e))?;
+        Ok(())
+    }
+
+    pub fn get_profile_assignments(&self) -> Result<HashMap<String, String>, String> {
+        match self.storage.get_config(profiles::PROFILE_ASSIGNMENTS_KEY)
+            .map_err(|e| format!("Failed to load profile assignments: {}", e))?
+        {
+            Some(value) => serde_json::from_str(&value)
+                .map_err(|e| format!("Failed to parse profile assignments: {}", e)),
+            None => Ok(HashMap::new()),
+        }
+    }
+
+    pub fn set_profile_assignments(&mut self, assignments: &HashMap<String, String>) -> Result<(), String> {
+        let value = serde_json::to_string(assignments)
+            .map_err(|e| format!("Failed to serialize profile assignments: {}", e))?;
+        self.storage.set_config(profiles::PROFILE_ASSIGNMENTS_KEY, &value, ConfigType::Parse)
+            .map_err(|e| format!("Failed to save profile assignments: {}", e))?;
+        Ok(())
+    }
+
+    /// 文件使用的解析配置（手动指定优先，否则按文件名通配符匹配）
+    pub fn get_file_profile(&self, file_path: &str) -> Result<Option<ParsingProfile>, String> {
+        let assignments = self.get_profile_assignments()?;
+        let profiles = self.get_profiles()?;
+        Ok(profiles::find_profile(&profiles, assignments.get(file_path).map(String::as_str), file_path).cloned())
+    }
+
     pub fn get_all_configs(&self) -> Result<AppConfig, String> {
         Ok(self.config.clone())
     }
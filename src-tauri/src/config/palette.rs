@@ -0,0 +1,47 @@
+use crate::i18n::Message;
+use serde::{Deserialize, Serialize};
+
+/// 快速搜索面板设置的存储键
+pub const PALETTE_SETTINGS_KEY: &str = "palette.settings";
+
+/// 快速搜索面板设置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PaletteSettings {
+    /// 是否注册全局快捷键
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// 全局快捷键（Tauri加速键格式，如 "CmdOrCtrl+Alt+L"）
+    #[serde(default = "default_shortcut")]
+    pub shortcut: String,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_shortcut() -> String {
+    "CmdOrCtrl+Alt+L".to_string()
+}
+
+impl Default for PaletteSettings {
+    fn default() -> Self {
+        Self { enabled: default_enabled(), shortcut: default_shortcut() }
+    }
+}
+
+impl PaletteSettings {
+    /// 校验快捷键：需要至少一个修饰键和一个普通键，避免全局占用单个按键
+    pub fn validate(&self) -> Result<(), String> {
+        let keys: Vec<&str> = self.shortcut.split('+').map(str::trim).collect();
+        if keys.iter().any(|key| key.is_empty()) {
+            return Err(Message::new("palette.invalid_shortcut").with("shortcut", &self.shortcut).text());
+        }
+        let is_modifier = |key: &str| matches!(key.to_ascii_lowercase().as_str(),
+            "cmd" | "command" | "ctrl" | "control" | "cmdorctrl" | "commandorcontrol" | "alt" | "option" | "shift" | "super" | "meta");
+        let modifiers = keys.iter().filter(|key| is_modifier(key)).count();
+        if modifiers == 0 || modifiers == keys.len() {
+            return Err(Message::new("palette.incomplete_shortcut").with("shortcut", &self.shortcut).text());
+        }
+        Ok(())
+    }
+}
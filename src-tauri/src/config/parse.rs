@@ -1,5 +1,11 @@
+use crate::plugins::chain::DetectionSample;
+use crate::plugins::compact::CompactSettings;
+use crate::plugins::formatter::FormattingProfile;
 use serde::{Deserialize, Serialize};
 
+/// 按文件指定格式的存储键（文件路径 -> 格式名称）
+pub const FORMAT_OVERRIDES_KEY: &str = "parse.format_overrides";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ParseConfig {
     pub chunk_size: usize,
@@ -9,6 +15,42 @@ pub struct ParseConfig {
     pub auto_parse: bool,
     pub show_line_numbers: bool,
     pub timeout_seconds: u64,
+    #[serde(default = "default_max_line_length")]
+    pub max_line_length: usize, // chars, 0 = unlimited
+    #[serde(default = "default_detect_sample_kb")]
+    pub detect_sample_kb: usize, // 自动检测采样的KB数，0 = unlimited
+    #[serde(default = "default_detect_sample_lines")]
+    pub detect_sample_lines: usize, // 自动检测采样的行数，0 = unlimited
+    #[serde(default)]
+    pub merge_wrapped_lines: bool, // 合并终端按宽度折断的续行
+    #[serde(default)]
+    pub compact: CompactSettings, // 紧凑格式开关和线程名缩略规则
+    #[serde(default)]
+    pub strict_content: bool, // 严格模式：保持原始内容不变，提取的消息写入 message 字段
+    #[serde(default)]
+    pub formatting: FormattingProfile, // 格式化风格：plain 不输出emoji图标和类型标签
+}
+
+fn default_max_line_length() -> usize {
+    10_000
+}
+
+fn default_detect_sample_kb() -> usize {
+    DetectionSample::default().max_bytes / 1024
+}
+
+fn default_detect_sample_lines() -> usize {
+    DetectionSample::default().max_lines
+}
+
+impl ParseConfig {
+    /// 自动检测的采样范围
+    pub fn detection_sample(&self) -> DetectionSample {
+        DetectionSample {
+            max_bytes: self.detect_sample_kb.saturating_mul(1024),
+            max_lines: self.detect_sample_lines,
+        }
+    }
 }
 
 impl Default for ParseConfig {
@@ -21,6 +63,13 @@ impl Default for ParseConfig {
             auto_parse: true,
             show_line_numbers: true,
             timeout_seconds: 30,
+            max_line_length: default_max_line_length(),
+            detect_sample_kb: default_detect_sample_kb(),
+            detect_sample_lines: default_detect_sample_lines(),
+            merge_wrapped_lines: false,
+            compact: CompactSettings::default(),
+            strict_content: false,
+            formatting: FormattingProfile::default(),
         }
     }
 }
\ No newline at end of file
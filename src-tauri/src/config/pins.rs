@@ -0,0 +1,165 @@
+use crate::anchor::{self, EntryAnchor};
+use crate::models::LogLine;
+use serde::{Deserialize, Serialize};
+
+/// 固定条目预览的最大字符数
+const PREVIEW_MAX_CHARS: usize = 200;
+
+/// 标记固定条目的元数据键
+pub const PINNED_METADATA_KEY: &str = "pinned";
+
+/// 固定（置顶）的日志条目
+///
+/// 只保存行引用和少量预览信息，不保存完整内容；
+/// 界面据此渲染独立的固定面板，不受筛选和分页影响。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PinnedEntry {
+    /// 条目在原文件中的行号
+    pub line_number: usize,
+    /// 内容预览（超长时截断）
+    pub preview: String,
+    /// 日志级别
+    pub level: Option<String>,
+    /// 时间戳
+    pub timestamp: Option<String>,
+    /// 用户备注
+    #[serde(default)]
+    pub note: Option<String>,
+    /// 固定时间（RFC 3339）
+    pub pinned_at: String,
+    /// 条目锚点，行号偏移后据此重新定位（旧版本保存的固定条目没有锚点）
+    #[serde(default)]
+    pub anchor: Option<String>,
+}
+
+impl PinnedEntry {
+    /// 从日志条目创建固定引用
+    pub fn from_entry(file_path: &str, entry: &LogLine, note: Option<String>) -> Self {
+        let text = entry.formatted_content.as_deref().unwrap_or(&entry.content);
+        let mut preview: String = text.chars().take(PREVIEW_MAX_CHARS).collect();
+        if text.chars().nth(PREVIEW_MAX_CHARS).is_some() {
+            preview.push('…');
+        }
+
+        Self {
+            line_number: entry.line_number,
+            preview,
+            level: entry.level.clone(),
+            timestamp: entry.timestamp.clone(),
+            note,
+            pinned_at: chrono::Utc::now().to_rfc3339(),
+            anchor: Some(EntryAnchor::new(file_path, entry).to_string()),
+        }
+    }
+}
+
+/// 一个文件（及会话）的固定列表
+#[derive(Debug, Clone, PartialEq)]
+pub struct PinnedFile {
+    /// 文件路径
+    pub file_path: String,
+    /// 会话名称（None表示不属于会话）
+    pub session_id: Option<String>,
+    /// 固定条目
+    pub pinned: Vec<PinnedEntry>,
+}
+
+/// 固定条目的存储键
+///
+/// 按文件（以及可选的会话）区分，同一文件在不同会话中可以有各自的固定列表。
+pub fn pins_key(file_path: &str, session_id: Option<&str>) -> String {
+    match session_id {
+        Some(session_id) => format!("pins.{}#{}", file_path, session_id),
+        None => format!("pins.{}", file_path),
+    }
+}
+
+/// 解析固定条目的存储键，返回文件路径和会话名称
+///
+/// 文件路径本身包含 `#` 时无法与会话名称区分，按最后一个 `#` 拆分。
+pub fn parse_pins_key(key: &str) -> Option<(&str, Option<&str>)> {
+    let rest = key.strip_prefix("pins.")?;
+    Some(match rest.rsplit_once('#') {
+        Some((file_path, session_id)) => (file_path, Some(session_id)),
+        None => (rest, None),
+    })
+}
+
+/// 添加固定条目，同一行重复固定时替换旧记录，列表按行号排序
+pub fn insert_pin(pins: &mut Vec<PinnedEntry>, pin: PinnedEntry) {
+    match pins.binary_search_by_key(&pin.line_number, |p| p.line_number) {
+        Ok(index) => pins[index] = pin,
+        Err(index) => pins.insert(index, pin),
+    }
+}
+
+/// 按锚点把固定条目移动到条目当前的行号
+///
+/// 重新解析后行号可能发生偏移，按锚点中的内容哈希找回条目并更新行号；
+/// 找不到的条目保持原行号。
+///
+/// # Returns
+/// - `bool`: 是否有固定条目的行号发生变化（需要重新保存）
+pub fn relocate_pins(pins: &mut [PinnedEntry], file_path: &str, entries: &[LogLine]) -> bool {
+    let mut changed = false;
+    for pin in pins.iter_mut() {
+        let Some(parsed) = pin.anchor.as_deref().and_then(|a| EntryAnchor::parse(a).ok()) else { continue };
+        if let Ok(Some(resolved)) = anchor::resolve_anchor(&parsed, file_path, entries) {
+            if resolved.line_number != pin.line_number {
+                pin.line_number = resolved.line_number;
+                changed = true;
+            }
+        }
+    }
+    if changed {
+        pins.sort_by_key(|pin| pin.line_number);
+    }
+    changed
+}
+
+/// 在解析结果中标记固定的条目（`metadata.pinned = "true"`）
+pub fn annotate_pinned(entries: &mut [LogLine], pins: &[PinnedEntry]) {
+    if pins.is_empty() {
+        return;
+    }
+    for entry in entries.iter_mut() {
+        if pins.binary_search_by_key(&entry.line_number, |p| p.line_number).is_ok() {
+            entry.metadata.insert(PINNED_METADATA_KEY.to_string(), "true".to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_and_annotate_pins() {
+        let mut pins = Vec::new();
+        insert_pin(&mut pins, PinnedEntry::from_entry("app.log", &LogLine::new(7, "line 7"), None));
+        insert_pin(&mut pins, PinnedEntry::from_entry("app.log", &LogLine::new(2, "line 2"), None));
+        insert_pin(&mut pins, PinnedEntry::from_entry("app.log", &LogLine::new(7, "line 7"), Some("根因".to_string())));
+
+        let lines: Vec<usize> = pins.iter().map(|p| p.line_number).collect();
+        assert_eq!(lines, vec![2, 7]);
+        assert_eq!(parse_pins_key(&pins_key("app.log", Some("s1"))), Some(("app.log", Some("s1"))));
+        assert_eq!(parse_pins_key(&pins_key("app.log", None)), Some(("app.log", None)));
+        assert_eq!(parse_pins_key("remote.hosts"), None);
+        assert_eq!(pins[1].note.as_deref(), Some("根因"));
+
+        let mut entries: Vec<LogLine> = (1..=8).map(|n| LogLine::new(n, format!("line {}", n))).collect();
+        annotate_pinned(&mut entries, &pins);
+        let pinned: Vec<usize> = entries.iter()
+            .filter(|e| e.metadata.contains_key(PINNED_METADATA_KEY))
+            .map(|e| e.line_number)
+            .collect();
+        assert_eq!(pinned, vec![2, 7]);
+
+        // 文件头部新增两行后，固定条目随内容移动
+        let shifted: Vec<LogLine> = (1..=8).map(|n| LogLine::new(n + 2, format!("line {}", n))).collect();
+        assert!(relocate_pins(&mut pins, "app.log", &shifted));
+        let lines: Vec<usize> = pins.iter().map(|p| p.line_number).collect();
+        assert_eq!(lines, vec![4, 9]);
+        assert!(!relocate_pins(&mut pins, "app.log", &shifted));
+    }
+}
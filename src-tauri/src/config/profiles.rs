@@ -0,0 +1,393 @@
+use crate::i18n::Message;
+use crate::ingest::wildcard_match;
+use crate::models::LogLine;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// 解析配置列表的存储键
+pub const PROFILES_KEY: &str = "profiles.list";
+
+/// 按文件指定解析配置的存储键（文件路径 -> 配置名称）
+pub const PROFILE_ASSIGNMENTS_KEY: &str = "profiles.assignments";
+
+/// 共享配置文件（`.lwprofile`）的格式标识
+pub const PROFILE_BUNDLE_FORMAT: &str = "lwprofile";
+
+/// 共享配置文件的当前版本
+pub const PROFILE_BUNDLE_VERSION: u32 = 1;
+
+/// 派生字段：用正则从内容或元数据中提取值，写入条目元数据
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DerivedField {
+    /// 字段名称（写入的元数据键）
+    pub name: String,
+    /// 来源元数据键（None表示原始内容）
+    #[serde(default)]
+    pub source: Option<String>,
+    /// 正则表达式：取名为 `value` 的捕获组，没有时取第一个捕获组，再没有时取整个匹配
+    pub pattern: String,
+}
+
+/// 高亮规则：内容匹配正则的部分按颜色高亮（由界面渲染）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HighlightRule {
+    /// 正则表达式
+    pub pattern: String,
+    /// 颜色（CSS颜色值，如 `#e5534b`、`orange`）
+    pub color: String,
+    /// 是否高亮整行（默认只高亮匹配部分）
+    #[serde(default)]
+    pub whole_line: bool,
+}
+
+/// 解析配置（profile）
+///
+/// 把格式、插件设置、派生字段和高亮规则打包在一起，按文件名通配符自动应用，
+/// 例如 `backend-*.log` 总是使用Backend配置；也可以为单个文件手动指定。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParsingProfile {
+    /// 配置名称（唯一）
+    pub name: String,
+    /// 匹配的文件名通配符（`*` / `?`，只匹配文件名部分，为空表示只能手动指定）
+    #[serde(default)]
+    pub file_patterns: Vec<String>,
+    /// 解析格式（插件链或插件名称，None表示自动检测）
+    #[serde(default)]
+    pub format: Option<String>,
+    /// 插件设置（覆盖全局设置中的同名插件）
+    #[serde(default)]
+    pub plugin_settings: HashMap<String, serde_json::Value>,
+    /// 派生字段
+    #[serde(default)]
+    pub derived_fields: Vec<DerivedField>,
+    /// 高亮规则
+    #[serde(default)]
+    pub highlight_rules: Vec<HighlightRule>,
+}
+
+impl ParsingProfile {
+    /// 校验配置
+    ///
+    /// 名称不能为空；派生字段需要名称，派生字段和高亮规则的正则必须有效，高亮颜色不能为空。
+    /// 格式和插件设置需要插件管理器校验，由调用方负责。
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err(Message::new("profile.empty_name").text());
+        }
+        for field in &self.derived_fields {
+            if field.name.trim().is_empty() {
+                return Err(Message::new("profile.empty_field_name").with("name", &self.name).text());
+            }
+            Regex::new(&field.pattern)
+                .map_err(|e| Message::new("profile.invalid_field_pattern").with("field", &field.name).with("error", e).text())?;
+        }
+        for rule in &self.highlight_rules {
+            if rule.color.trim().is_empty() {
+                return Err(Message::new("profile.highlight_missing_color").with("pattern", &rule.pattern).text());
+            }
+            Regex::new(&rule.pattern).map_err(|e| Message::new("profile.invalid_highlight_pattern").with("error", e).text())?;
+        }
+        Ok(())
+    }
+
+    /// 文件名是否匹配任一通配符
+    pub fn matches_file(&self, file_path: &str) -> bool {
+        let file_name = std::path::Path::new(file_path)
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| file_path.to_string());
+        self.file_patterns.iter().any(|pattern| wildcard_match(pattern.trim(), &file_name))
+    }
+
+    /// 为条目写入派生字段（已有同名元数据时不覆盖）
+    pub fn apply_derived_fields(&self, entries: &mut [LogLine]) {
+        let fields: Vec<(&DerivedField, Regex)> = self.derived_fields.iter()
+            .filter_map(|field| Regex::new(&field.pattern).ok().map(|regex| (field, regex)))
+            .collect();
+        if fields.is_empty() {
+            return;
+        }
+        for entry in entries.iter_mut() {
+            for (field, regex) in &fields {
+                if entry.metadata.contains_key(&field.name) {
+                    continue;
+                }
+                let text = match &field.source {
+                    Some(key) => match entry.metadata.get(key) {
+                        Some(value) => value.as_str(),
+                        None => continue,
+                    },
+                    None => entry.content.as_str(),
+                };
+                let Some(captures) = regex.captures(text) else {
+                    continue;
+                };
+                let value = captures.name("value")
+                    .or_else(|| captures.get(1))
+                    .or_else(|| captures.get(0))
+                    .map(|m| m.as_str().to_string());
+                if let Some(value) = value {
+                    entry.metadata.insert(field.name.clone(), value);
+                }
+            }
+        }
+    }
+}
+
+/// 查找文件使用的解析配置
+///
+/// 手动指定的配置优先，否则使用第一个文件名通配符匹配的配置（按名称顺序）。
+///
+/// # 参数
+/// - `profiles`: 所有解析配置
+/// - `assigned`: 为该文件手动指定的配置名称
+/// - `file_path`: 文件路径
+pub fn find_profile<'a>(profiles: &'a [ParsingProfile], assigned: Option<&str>, file_path: &str) -> Option<&'a ParsingProfile> {
+    match assigned {
+        Some(name) => profiles.iter().find(|profile| profile.name == name),
+        None => profiles.iter().find(|profile| profile.matches_file(file_path)),
+    }
+}
+
+/// 添加或替换（按名称）解析配置，列表按名称排序
+pub fn upsert_profile(profiles: &mut Vec<ParsingProfile>, profile: ParsingProfile) {
+    profiles.retain(|p| p.name != profile.name);
+    profiles.push(profile);
+    profiles.sort_by(|a, b| a.name.cmp(&b.name));
+}
+
+/// 共享配置文件（`.lwprofile`，JSON）
+///
+/// 包含解析配置（连同其中的派生字段和高亮规则）以及自定义解析器的插件设置
+/// （如 `pattern_layout` 的布局模式），同事导入后即可用相同的规则查看日志。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProfileBundle {
+    /// 格式标识，固定为 `lwprofile`
+    pub format: String,
+    /// 文件版本
+    pub version: u32,
+    /// 导出时间（RFC 3339）
+    #[serde(default)]
+    pub exported_at: Option<String>,
+    /// 解析配置
+    #[serde(default)]
+    pub profiles: Vec<ParsingProfile>,
+    /// 自定义解析器等插件设置（插件名称 -> 设置）
+    #[serde(default)]
+    pub plugin_settings: HashMap<String, serde_json::Value>,
+}
+
+impl ProfileBundle {
+    /// 创建当前版本的共享配置
+    pub fn new(profiles: Vec<ParsingProfile>, plugin_settings: HashMap<String, serde_json::Value>) -> Self {
+        Self {
+            format: PROFILE_BUNDLE_FORMAT.to_string(),
+            version: PROFILE_BUNDLE_VERSION,
+            exported_at: Some(chrono::Utc::now().to_rfc3339()),
+            profiles,
+            plugin_settings,
+        }
+    }
+
+    /// 解析并校验共享配置文件
+    ///
+    /// 检查格式标识和版本（不接受比当前版本新的文件），配置名称不能重复，每个配置都必须有效。
+    /// 格式名称和插件设置需要插件管理器校验，由调用方负责。
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let value: serde_json::Value = serde_json::from_str(text.trim_start_matches('\u{feff}'))
+            .map_err(|e| Message::new("profile.bundle_invalid_json").with("error", e).text())?;
+        if value.get("format").and_then(|format| format.as_str()) != Some(PROFILE_BUNDLE_FORMAT) {
+            return Err(Message::new("profile.bundle_wrong_format").with("format", PROFILE_BUNDLE_FORMAT).text());
+        }
+        let bundle: Self = serde_json::from_value(value).map_err(|e| Message::new("profile.bundle_invalid").with("error", e).text())?;
+        if bundle.version == 0 || bundle.version > PROFILE_BUNDLE_VERSION {
+            return Err(Message::new("profile.bundle_version_unsupported")
+                .with("version", bundle.version)
+                .with("supported", PROFILE_BUNDLE_VERSION)
+                .text());
+        }
+        for (i, profile) in bundle.profiles.iter().enumerate() {
+            profile.validate()?;
+            if bundle.profiles[..i].iter().any(|other| other.name == profile.name) {
+                return Err(Message::new("profile.bundle_duplicate").with("name", &profile.name).text());
+            }
+        }
+        if let Some((plugin, _)) = bundle.plugin_settings.iter().find(|(_, settings)| !settings.is_object()) {
+            return Err(Message::new("profile.plugin_settings_not_object").with("plugin", plugin).text());
+        }
+        Ok(bundle)
+    }
+}
+
+/// 导入时名称冲突的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictStrategy {
+    /// 保留本地的配置，跳过导入的同名配置
+    #[default]
+    Skip,
+    /// 用导入的配置覆盖本地的同名配置
+    Overwrite,
+    /// 导入的配置改名后保存（如 `Backend (2)`），插件设置冲突时保留本地设置
+    Rename,
+}
+
+/// 导入结果
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ImportReport {
+    /// 新增的解析配置
+    pub added: Vec<String>,
+    /// 覆盖的解析配置
+    pub replaced: Vec<String>,
+    /// 改名导入的解析配置（原名称, 新名称）
+    pub renamed: Vec<(String, String)>,
+    /// 因冲突跳过的解析配置
+    pub skipped: Vec<String>,
+    /// 与本地不同的插件设置（冲突）
+    pub conflicting_plugins: Vec<String>,
+    /// 导入（新增或覆盖）的插件设置
+    pub imported_plugins: Vec<String>,
+    /// 是否只是预览（未保存）
+    pub dry_run: bool,
+}
+
+/// 把共享配置合并到本地配置
+///
+/// 内容完全相同的配置和插件设置不算冲突，直接跳过且不出现在报告中。
+///
+/// # 参数
+/// - `profiles`: 本地解析配置（合并后按名称排序）
+/// - `plugin_settings`: 本地插件设置
+/// - `bundle`: 已校验的共享配置
+/// - `strategy`: 名称冲突的处理方式
+///
+/// # Returns
+/// - `ImportReport`: 每个配置和插件设置的处理结果
+pub fn merge_bundle(
+    profiles: &mut Vec<ParsingProfile>,
+    plugin_settings: &mut HashMap<String, serde_json::Value>,
+    bundle: ProfileBundle,
+    strategy: ConflictStrategy,
+) -> ImportReport {
+    let mut report = ImportReport::default();
+    for mut profile in bundle.profiles {
+        match profiles.iter().find(|existing| existing.name == profile.name) {
+            None => report.added.push(profile.name.clone()),
+            Some(existing) if *existing == profile => continue,
+            Some(_) => match strategy {
+                ConflictStrategy::Skip => {
+                    report.skipped.push(profile.name);
+                    continue;
+                }
+                ConflictStrategy::Overwrite => report.replaced.push(profile.name.clone()),
+                ConflictStrategy::Rename => {
+                    let original = profile.name.clone();
+                    profile.name = (2..)
+                        .map(|n| format!("{} ({})", original, n))
+                        .find(|name| !profiles.iter().any(|existing| existing.name == *name))
+                        .unwrap_or_default();
+                    report.renamed.push((original, profile.name.clone()));
+                }
+            },
+        }
+        upsert_profile(profiles, profile);
+    }
+
+    let mut plugins: Vec<(String, serde_json::Value)> = bundle.plugin_settings.into_iter().collect();
+    plugins.sort_by(|a, b| a.0.cmp(&b.0));
+    for (plugin, settings) in plugins {
+        match plugin_settings.get(&plugin) {
+            Some(existing) if *existing == settings => continue,
+            Some(_) => {
+                report.conflicting_plugins.push(plugin.clone());
+                if strategy != ConflictStrategy::Overwrite {
+                    continue;
+                }
+            }
+            None => {}
+        }
+        report.imported_plugins.push(plugin.clone());
+        plugin_settings.insert(plugin, settings);
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_matching_and_derived_fields() {
+        let backend = ParsingProfile {
+            name: "Backend".to_string(),
+            file_patterns: vec!["backend-*.log".to_string()],
+            format: Some("springboot".to_string()),
+            plugin_settings: HashMap::new(),
+            derived_fields: vec![
+                DerivedField { name: "order_id".to_string(), source: None, pattern: r"order=(?P<value>\d+)".to_string() },
+                DerivedField { name: "tenant".to_string(), source: Some("logger".to_string()), pattern: r"^t\.(\w+)".to_string() },
+            ],
+            highlight_rules: Vec::new(),
+        };
+        let manual = ParsingProfile { name: "Manual".to_string(), file_patterns: Vec::new(), ..backend.clone() };
+        let profiles = vec![backend.clone(), manual];
+
+        assert_eq!(find_profile(&profiles, None, "/var/log/backend-2024.log").map(|p| p.name.as_str()), Some("Backend"));
+        assert!(find_profile(&profiles, None, "/var/log/backend/app.log").is_none());
+        assert_eq!(find_profile(&profiles, Some("Manual"), "/var/log/backend-1.log").map(|p| p.name.as_str()), Some("Manual"));
+
+        let mut entries = vec![LogLine {
+            metadata: HashMap::from([("logger".to_string(), "t.acme.OrderService".to_string())]),
+            ..LogLine::new(1, "created order=42")
+        }];
+        backend.apply_derived_fields(&mut entries);
+        assert_eq!(entries[0].metadata.get("order_id").map(String::as_str), Some("42"));
+        assert_eq!(entries[0].metadata.get("tenant").map(String::as_str), Some("acme"));
+
+        let invalid = ParsingProfile { highlight_rules: vec![HighlightRule { pattern: "(".to_string(), color: "red".to_string(), whole_line: false }], ..backend };
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_bundle_validation_and_conflicts() {
+        let profile = |name: &str, format: &str| ParsingProfile {
+            name: name.to_string(),
+            file_patterns: vec!["*.log".to_string()],
+            format: Some(format.to_string()),
+            plugin_settings: HashMap::new(),
+            derived_fields: Vec::new(),
+            highlight_rules: Vec::new(),
+        };
+        let bundle = ProfileBundle::new(
+            vec![profile("Backend", "springboot"), profile("Nginx", "nginx")],
+            HashMap::from([("pattern_layout".to_string(), serde_json::json!({"pattern": "%d %msg%n"}))]),
+        );
+        let text = serde_json::to_string(&bundle).unwrap();
+        assert_eq!(ProfileBundle::parse(&text).unwrap(), bundle);
+        assert!(ProfileBundle::parse(r#"{"profiles": []}"#).unwrap_err().contains("lwprofile"));
+        assert!(ProfileBundle::parse(r#"{"format": "lwprofile", "version": 99}"#).unwrap_err().contains("99"));
+        let duplicate = text.replace("Nginx", "Backend");
+        assert!(ProfileBundle::parse(&duplicate).unwrap_err().contains("重名"));
+
+        let local = vec![profile("Backend", "logfmt"), profile("Nginx", "nginx")];
+        let local_settings = HashMap::from([("pattern_layout".to_string(), serde_json::json!({"pattern": "%m%n"}))]);
+
+        let (mut profiles, mut settings) = (local.clone(), local_settings.clone());
+        let report = merge_bundle(&mut profiles, &mut settings, bundle.clone(), ConflictStrategy::Skip);
+        assert_eq!((report.skipped, report.conflicting_plugins, report.imported_plugins.len()), (vec!["Backend".to_string()], vec!["pattern_layout".to_string()], 0));
+        assert_eq!(profiles, local);
+
+        let (mut profiles, mut settings) = (local.clone(), local_settings.clone());
+        let report = merge_bundle(&mut profiles, &mut settings, bundle.clone(), ConflictStrategy::Rename);
+        assert_eq!(report.renamed, vec![("Backend".to_string(), "Backend (2)".to_string())]);
+        assert_eq!(profiles.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(), vec!["Backend", "Backend (2)", "Nginx"]);
+        assert_eq!(settings, local_settings);
+
+        let (mut profiles, mut settings) = (local, local_settings);
+        let report = merge_bundle(&mut profiles, &mut settings, bundle.clone(), ConflictStrategy::Overwrite);
+        assert_eq!(report.replaced, vec!["Backend".to_string()]);
+        assert_eq!(profiles[0].format.as_deref(), Some("springboot"));
+        assert_eq!(settings, bundle.plugin_settings);
+    }
+}
@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+
+/// 最近打开文件列表的存储键
+pub const RECENT_FILES_KEY: &str = "recent.files";
+
+/// 最多保留的最近打开文件数
+pub const MAX_RECENT_FILES: usize = 50;
+
+/// 最近打开的文件
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecentFile {
+    /// 文件路径
+    pub path: String,
+    /// 最近一次打开的时间（RFC 3339）
+    pub opened_at: String,
+}
+
+/// 把文件移到最近列表最前面，超出上限时丢弃最旧的
+pub fn touch_recent(files: &mut Vec<RecentFile>, path: &str) {
+    files.retain(|file| file.path != path);
+    files.insert(0, RecentFile { path: path.to_string(), opened_at: chrono::Utc::now().to_rfc3339() });
+    files.truncate(MAX_RECENT_FILES);
+}
@@ -0,0 +1,59 @@
+use crate::i18n::Message;
+use serde::{Deserialize, Serialize};
+
+/// 远程主机列表的存储键
+pub const REMOTE_HOSTS_KEY: &str = "remote.hosts";
+
+/// 已配置的远程主机（SSH）
+///
+/// 只保存连接参数和私钥文件路径，不保存密码或私钥内容；
+/// 认证由系统的OpenSSH客户端完成（私钥、ssh-agent或 `~/.ssh/config`）。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemoteHost {
+    /// 显示名称（唯一）
+    pub name: String,
+    /// 主机名或IP地址
+    pub host: String,
+    /// SSH端口
+    #[serde(default = "default_port")]
+    pub port: u16,
+    /// 登录用户名（可选，不指定则使用 `~/.ssh/config` 或当前用户）
+    #[serde(default)]
+    pub user: Option<String>,
+    /// 私钥文件路径（可选）
+    #[serde(default)]
+    pub identity_file: Option<String>,
+}
+
+fn default_port() -> u16 {
+    22
+}
+
+impl RemoteHost {
+    /// 校验主机配置
+    ///
+    /// 名称、主机名和用户名不能为空，也不能以 `-` 开头（避免被ssh当作选项）。
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err(Message::new("remote_config.empty_name").text());
+        }
+        let fields = std::iter::once(("remote_config.label.host", self.host.as_str()))
+            .chain(self.user.as_deref().map(|user| ("remote_config.label.user", user)));
+        for (label, value) in fields {
+            if value.trim().is_empty() || value.starts_with('-') || value.contains(char::is_whitespace) {
+                return Err(Message::new("remote_config.invalid_field")
+                    .with("label", Message::new(label).text())
+                    .with("value", format!("{:?}", value))
+                    .text());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 添加或替换（按名称）远程主机，列表按名称排序
+pub fn upsert_host(hosts: &mut Vec<RemoteHost>, host: RemoteHost) {
+    hosts.retain(|h| h.name != host.name);
+    hosts.push(host);
+    hosts.sort_by(|a, b| a.name.cmp(&b.name));
+}
@@ -0,0 +1,111 @@
+use crate::i18n::Message;
+use serde::{Deserialize, Serialize};
+
+/// AI摘要设置的存储键
+pub const SUMMARIZER_SETTINGS_KEY: &str = "summarizer.settings";
+
+/// 大模型服务类型
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SummarizerProvider {
+    /// 本地Ollama（`POST /api/chat`）
+    #[default]
+    Ollama,
+    /// OpenAI兼容接口（`POST /chat/completions`，包括vLLM、LM Studio等本地服务）
+    Openai,
+}
+
+/// AI摘要设置
+///
+/// 默认关闭；启用后选中的日志条目经过脱敏、分批后发送到配置的大模型服务生成摘要。
+/// 与issue跟踪系统一样不保存API密钥本身，只保存存放密钥的环境变量名。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SummarizerSettings {
+    /// 是否启用
+    #[serde(default)]
+    pub enabled: bool,
+    /// 服务类型
+    #[serde(default)]
+    pub provider: SummarizerProvider,
+    /// 服务地址（默认Ollama为 `http://localhost:11434`，OpenAI为 `https://api.openai.com/v1`）
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    /// 模型名称
+    #[serde(default = "default_model")]
+    pub model: String,
+    /// 存放API密钥的环境变量名（本地服务通常不需要）
+    #[serde(default)]
+    pub api_key_env: Option<String>,
+    /// 每批发送的最大字符数，超过时分批摘要后再合并
+    #[serde(default = "default_batch_chars")]
+    pub batch_chars: usize,
+    /// 单次最多发送的条目数
+    #[serde(default = "default_max_entries")]
+    pub max_entries: usize,
+    /// 请求超时（秒）
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_model() -> String {
+    "llama3.1".to_string()
+}
+
+fn default_batch_chars() -> usize {
+    12_000
+}
+
+fn default_max_entries() -> usize {
+    500
+}
+
+fn default_timeout_secs() -> u64 {
+    120
+}
+
+impl Default for SummarizerSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            provider: SummarizerProvider::default(),
+            endpoint: None,
+            model: default_model(),
+            api_key_env: None,
+            batch_chars: default_batch_chars(),
+            max_entries: default_max_entries(),
+            timeout_secs: default_timeout_secs(),
+        }
+    }
+}
+
+impl SummarizerSettings {
+    /// 校验设置
+    ///
+    /// 模型名称不能为空，服务地址必须是HTTP(S)链接，每批字符数不能小于1000。
+    pub fn validate(&self) -> Result<(), String> {
+        if self.model.trim().is_empty() {
+            return Err(Message::new("summary_config.empty_model").text());
+        }
+        if let Some(endpoint) = &self.endpoint {
+            if !endpoint.starts_with("https://") && !endpoint.starts_with("http://") {
+                return Err(Message::new("config.invalid_endpoint").with("endpoint", endpoint).text());
+            }
+        }
+        if self.batch_chars < 1000 {
+            return Err(Message::new("summary_config.batch_too_small").with("value", self.batch_chars).text());
+        }
+        if self.max_entries == 0 || self.timeout_secs == 0 {
+            return Err(Message::new("summary_config.invalid_limits").text());
+        }
+        Ok(())
+    }
+
+    /// 服务地址（去掉末尾的 `/`）
+    pub fn base_url(&self) -> String {
+        let default = match self.provider {
+            SummarizerProvider::Ollama => "http://localhost:11434",
+            SummarizerProvider::Openai => "https://api.openai.com/v1",
+        };
+        self.endpoint.as_deref().unwrap_or(default).trim_end_matches('/').to_string()
+    }
+}
@@ -1,6 +1,9 @@
+use crate::i18n::Message;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ThemeMode {
     Light,
     Dark,
@@ -13,6 +16,127 @@ impl Default for ThemeMode {
     }
 }
 
+impl ThemeMode {
+    /// 前端使用的模式名称（light/dark/auto）
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ThemeMode::Light => "light",
+            ThemeMode::Dark => "dark",
+            ThemeMode::Auto => "auto",
+        }
+    }
+
+    /// 实际生效的明暗主题：Auto模式跟随系统主题（系统主题未知时使用浅色）
+    pub fn resolve(&self, system: Option<EffectiveTheme>) -> EffectiveTheme {
+        match self {
+            ThemeMode::Light => EffectiveTheme::Light,
+            ThemeMode::Dark => EffectiveTheme::Dark,
+            ThemeMode::Auto => system.unwrap_or(EffectiveTheme::Light),
+        }
+    }
+}
+
+/// 实际生效的明暗主题
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EffectiveTheme {
+    Light,
+    Dark,
+}
+
+/// 当前主题状态，也是 `theme_changed` 事件的载荷
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThemeState {
+    /// 配置的主题模式（light/dark/auto）
+    pub mode: String,
+    /// 系统主题（尚未检测到时为None）
+    pub system: Option<EffectiveTheme>,
+    /// 实际生效的主题
+    pub effective: EffectiveTheme,
+}
+
+/// 跟踪配置的主题模式和系统主题，计算实际生效的主题
+///
+/// 系统明暗切换或用户修改模式时，只有实际生效的主题发生变化才返回新状态，
+/// 调用方据此向所有窗口发送 `theme_changed` 事件。
+pub struct ThemeTracker {
+    state: Mutex<(ThemeMode, Option<EffectiveTheme>)>,
+}
+
+impl ThemeTracker {
+    /// 创建主题跟踪器
+    ///
+    /// # 参数
+    /// - `mode`: 配置的主题模式
+    pub fn new(mode: ThemeMode) -> Self {
+        Self { state: Mutex::new((mode, None)) }
+    }
+
+    /// 当前主题状态
+    pub fn current(&self) -> ThemeState {
+        let (mode, system) = *self.state.lock().unwrap();
+        ThemeState { mode: mode.as_str().to_string(), system, effective: mode.resolve(system) }
+    }
+
+    /// 记录系统主题
+    ///
+    /// # Returns
+    /// - `Option<ThemeState>`: 实际生效的主题变化时返回新状态
+    pub fn set_system(&self, system: EffectiveTheme) -> Option<ThemeState> {
+        self.update(|state| state.1 = Some(system))
+    }
+
+    /// 记录用户修改的主题模式
+    ///
+    /// # Returns
+    /// - `Option<ThemeState>`: 实际生效的主题变化时返回新状态
+    pub fn set_mode(&self, mode: ThemeMode) -> Option<ThemeState> {
+        self.update(|state| state.0 = mode)
+    }
+
+    fn update(&self, f: impl FnOnce(&mut (ThemeMode, Option<EffectiveTheme>))) -> Option<ThemeState> {
+        let before = self.current().effective;
+        f(&mut self.state.lock().unwrap());
+        let after = self.current();
+        (after.effective != before).then_some(after)
+    }
+}
+
+/// 日志级别的显示样式
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LevelStyle {
+    /// 颜色（#rgb 或 #rrggbb）
+    pub color: String,
+    /// 默认是否显示该级别的条目
+    #[serde(default = "default_visible")]
+    pub visible: bool,
+}
+
+fn default_visible() -> bool {
+    true
+}
+
+/// 默认的级别样式（键为大写级别名，TRACE默认隐藏）
+pub fn default_level_styles() -> BTreeMap<String, LevelStyle> {
+    [
+        ("FATAL", "#82071e", true),
+        ("ERROR", "#cf222e", true),
+        ("WARN", "#9a6700", true),
+        ("INFO", "#1a7f37", true),
+        ("DEBUG", "#6e7781", true),
+        ("TRACE", "#8c959f", false),
+    ]
+    .into_iter()
+    .map(|(level, color, visible)| (level.to_string(), LevelStyle { color: color.to_string(), visible }))
+    .collect()
+}
+
+/// 是否为有效的颜色（#rgb 或 #rrggbb）
+pub fn is_valid_color(color: &str) -> bool {
+    color.strip_prefix('#')
+        .is_some_and(|hex| matches!(hex.len(), 3 | 6) && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ThemeConfig {
     pub mode: ThemeMode,
@@ -20,6 +144,8 @@ pub struct ThemeConfig {
     pub accent_color: String,
     pub font_size: u32,
     pub font_family: String,
+    #[serde(default = "default_level_styles")]
+    pub level_styles: BTreeMap<String, LevelStyle>,
 }
 
 impl Default for ThemeConfig {
@@ -30,6 +156,89 @@ impl Default for ThemeConfig {
             accent_color: "#10b981".to_string(),
             font_size: 14,
             font_family: "system-ui".to_string(),
+            level_styles: default_level_styles(),
+        }
+    }
+}
+
+impl ThemeConfig {
+    /// 默认隐藏的级别
+    pub fn hidden_levels(&self) -> Vec<String> {
+        self.level_styles.iter()
+            .filter(|(_, style)| !style.visible)
+            .map(|(level, _)| level.clone())
+            .collect()
+    }
+
+    /// 设置级别的颜色和默认可见性（未配置过的级别需要指定颜色）
+    ///
+    /// # 参数
+    /// - `level`: 级别名（不区分大小写）
+    /// - `color`: 新颜色（None保持不变）
+    /// - `visible`: 默认是否显示（None保持不变）
+    ///
+    /// # Returns
+    /// - `Ok(LevelStyle)`: 更新后的样式
+    /// - `Err(String)`: 级别名或颜色无效、新级别未指定颜色
+    pub fn set_level_style(&mut self, level: &str, color: Option<String>, visible: Option<bool>) -> Result<LevelStyle, String> {
+        let level = level.trim().to_uppercase();
+        if level.is_empty() || !level.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+            return Err(Message::new("theme.invalid_level").with("level", level).text());
+        }
+        if let Some(color) = &color {
+            if !is_valid_color(color) {
+                return Err(Message::new("theme.invalid_color").with("color", color).text());
+            }
         }
+
+        let style = match (self.level_styles.get(&level), color) {
+            (Some(style), color) => LevelStyle {
+                color: color.unwrap_or_else(|| style.color.clone()),
+                visible: visible.unwrap_or(style.visible),
+            },
+            (None, Some(color)) => LevelStyle { color, visible: visible.unwrap_or(true) },
+            (None, None) => return Err(Message::new("theme.level_missing_color").with("level", level).text()),
+        };
+        self.level_styles.insert(level, style.clone());
+        Ok(style)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_styles_default_and_update() {
+        // 旧版本保存的主题配置没有级别样式，读取时使用默认样式
+        let stored = r##"{"mode":"Dark","primary_color":"#3b82f6","accent_color":"#10b981","font_size":14,"font_family":"system-ui"}"##;
+        let mut theme: ThemeConfig = serde_json::from_str(stored).unwrap();
+        assert_eq!(theme.hidden_levels(), vec!["TRACE"]);
+
+        let style = theme.set_level_style("trace", None, Some(true)).unwrap();
+        assert_eq!(style, LevelStyle { color: "#8c959f".to_string(), visible: true });
+        assert!(theme.hidden_levels().is_empty());
+        theme.set_level_style("Notice", Some("#0af".to_string()), Some(false)).unwrap();
+        assert_eq!(theme.hidden_levels(), vec!["NOTICE"]);
+
+        assert!(theme.set_level_style("ERROR", Some("red".to_string()), None).is_err());
+        assert!(theme.set_level_style("AUDIT", None, Some(true)).is_err());
+        assert!(theme.set_level_style(" ", Some("#fff".to_string()), None).is_err());
+        assert!(theme.set_level_style("a}b", Some("#fff".to_string()), None).is_err());
+    }
+
+    #[test]
+    fn test_theme_tracker_follows_system_in_auto_mode() {
+        let tracker = ThemeTracker::new(ThemeMode::Auto);
+        assert_eq!(tracker.current().effective, EffectiveTheme::Light);
+        assert_eq!(tracker.set_system(EffectiveTheme::Dark).map(|state| state.effective), Some(EffectiveTheme::Dark));
+        // 系统主题没有变化时不产生事件
+        assert_eq!(tracker.set_system(EffectiveTheme::Dark), None);
+
+        // 固定模式下系统切换不影响实际主题
+        assert_eq!(tracker.set_mode(ThemeMode::Dark), None);
+        assert_eq!(tracker.set_system(EffectiveTheme::Light), None);
+        let state = tracker.set_mode(ThemeMode::Auto).unwrap();
+        assert_eq!((state.mode.as_str(), state.system, state.effective), ("auto", Some(EffectiveTheme::Light), EffectiveTheme::Light));
+    }
+}
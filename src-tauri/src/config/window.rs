@@ -14,6 +14,17 @@ pub struct WindowConfig {
     pub theme: String,
     pub maximized: bool,
     pub remember_position: bool,
+    /// 上次关闭前的窗口位置（逻辑像素，从未记录过为None）
+    #[serde(default)]
+    pub x: Option<i32>,
+    #[serde(default)]
+    pub y: Option<i32>,
+    #[serde(default = "default_locale")]
+    pub locale: String, // zh-CN, en-US, code
+}
+
+fn default_locale() -> String {
+    "zh-CN".to_string()
 }
 
 impl Default for WindowConfig {
@@ -31,6 +42,9 @@ impl Default for WindowConfig {
             theme: "system".to_string(),
             maximized: false,
             remember_position: true,
+            x: None,
+            y: None,
+            locale: default_locale(),
         }
     }
 }
\ No newline at end of file
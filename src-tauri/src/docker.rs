@@ -0,0 +1,311 @@
+/// Docker容器日志
+///
+/// 直接通过Docker Engine API获取容器列表和容器日志，用户不需要在磁盘上找到
+/// `<container-id>-json.log` 的位置。默认连接 `/var/run/docker.sock`（Windows为
+/// `\\.\pipe\docker_engine` 命名管道），也支持 `DOCKER_HOST` 指定的 `unix://`、`npipe://`、`tcp://` 地址。
+///
+/// 获取到的日志转换为Docker JSON日志格式（每行 `{"log","stream","time"}`），
+/// 交给与 `json.log` 文件相同的Docker JSON解析流程。
+
+use crate::i18n::Message;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::time::Duration;
+
+/// 默认的Docker守护进程地址
+#[cfg(windows)]
+const DEFAULT_DOCKER_HOST: &str = r"npipe:////./pipe/docker_engine";
+#[cfg(not(windows))]
+const DEFAULT_DOCKER_HOST: &str = "unix:///var/run/docker.sock";
+
+/// Docker Engine API读写超时
+const DOCKER_IO_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 容器摘要
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContainerSummary {
+    /// 容器ID（前12位）
+    pub id: String,
+    /// 容器名称（去掉开头的 `/`）
+    pub name: String,
+    /// 镜像
+    pub image: String,
+    /// 状态（running/exited等）
+    pub state: String,
+    /// 状态描述（如 `Up 2 hours`）
+    pub status: String,
+    /// 创建时间（Unix时间戳，秒）
+    pub created: i64,
+}
+
+/// 容器日志中的一行
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct LogFrame {
+    stream: &'static str,
+    text: String,
+}
+
+/// 读写Docker守护进程连接的抽象（Unix套接字、命名管道或TCP）
+trait DockerStream: Read + Write {}
+impl<T: Read + Write> DockerStream for T {}
+
+/// Docker Engine API客户端
+pub struct DockerClient {
+    host: String,
+}
+
+impl DockerClient {
+    /// 按 `DOCKER_HOST` 环境变量创建客户端，未设置时使用平台默认地址
+    pub fn from_env() -> Self {
+        let host = std::env::var("DOCKER_HOST")
+            .ok()
+            .filter(|h| !h.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_DOCKER_HOST.to_string());
+        Self { host }
+    }
+
+    fn connect(&self) -> Result<Box<dyn DockerStream>, String> {
+        let unavailable = |e: std::io::Error| Message::new("docker.connect_failed").with("host", &self.host).with("error", e).text();
+
+        if let Some(address) = self.host.strip_prefix("tcp://") {
+            let stream = std::net::TcpStream::connect(address).map_err(unavailable)?;
+            stream.set_read_timeout(Some(DOCKER_IO_TIMEOUT)).ok();
+            return Ok(Box::new(stream));
+        }
+        #[cfg(unix)]
+        if let Some(path) = self.host.strip_prefix("unix://") {
+            let stream = std::os::unix::net::UnixStream::connect(path).map_err(unavailable)?;
+            stream.set_read_timeout(Some(DOCKER_IO_TIMEOUT)).ok();
+            return Ok(Box::new(stream));
+        }
+        #[cfg(windows)]
+        if let Some(path) = self.host.strip_prefix("npipe://") {
+            let pipe = std::fs::OpenOptions::new()
+                .read(true)
+                .write(true)
+                .open(path.replace('/', "\\"))
+                .map_err(unavailable)?;
+            return Ok(Box::new(pipe));
+        }
+        Err(Message::new("docker.unsupported_host").with("host", &self.host).text())
+    }
+
+    /// 发送GET请求，返回响应体
+    fn get(&self, path: &str) -> Result<Vec<u8>, String> {
+        log::debug!("🐳 Docker API: GET {}", path);
+        let mut stream = self.connect()?;
+        let request = format!("GET {} HTTP/1.1\r\nHost: docker\r\nConnection: close\r\n\r\n", path);
+        stream.write_all(request.as_bytes()).map_err(|e| Message::new("docker.request_failed").with("error", e).text())?;
+
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).map_err(|e| Message::new("docker.response_failed").with("error", e).text())?;
+        let (status, body) = parse_http_response(&response)?;
+        if status >= 400 {
+            let message = serde_json::from_slice::<serde_json::Value>(&body).ok()
+                .and_then(|v| v.get("message").and_then(|m| m.as_str()).map(str::to_string))
+                .unwrap_or_else(|| String::from_utf8_lossy(&body).trim().to_string());
+            return Err(Message::new("docker.error_status").with("status", status).with("message", message).text());
+        }
+        Ok(body)
+    }
+
+    /// 列出容器
+    ///
+    /// # 参数
+    /// - `all`: 是否包括已停止的容器
+    pub fn list_containers(&self, all: bool) -> Result<Vec<ContainerSummary>, String> {
+        let body = self.get(&format!("/containers/json?all={}", all as u8))?;
+        let values: Vec<serde_json::Value> = serde_json::from_slice(&body)
+            .map_err(|e| Message::new("docker.invalid_container_list").with("error", e).text())?;
+
+        Ok(values.iter().map(|v| {
+            let text = |key: &str| v.get(key).and_then(|s| s.as_str()).unwrap_or_default().to_string();
+            ContainerSummary {
+                id: text("Id").chars().take(12).collect(),
+                name: v.get("Names")
+                    .and_then(|names| names.get(0))
+                    .and_then(|n| n.as_str())
+                    .map(|n| n.trim_start_matches('/').to_string())
+                    .unwrap_or_default(),
+                image: text("Image"),
+                state: text("State"),
+                status: text("Status"),
+                created: v.get("Created").and_then(|c| c.as_i64()).unwrap_or(0),
+            }
+        }).collect())
+    }
+
+    /// 获取容器日志并转换为Docker JSON日志格式
+    ///
+    /// # 参数
+    /// - `container_id`: 容器ID或名称
+    /// - `since`: 起始时间（可选）：Unix时间戳、RFC 3339时间或相对时长（如 `10m`、`2h`）
+    /// - `tail`: 只获取最后N行（可选）
+    ///
+    /// # Returns
+    /// - `Ok(String)`: 每行一个 `{"log","stream","time"}` JSON对象
+    /// - `Err(String)`: 容器不存在或Docker不可用时的错误信息
+    pub fn container_logs(&self, container_id: &str, since: Option<&str>, tail: Option<usize>) -> Result<String, String> {
+        if container_id.is_empty() || !container_id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-')) {
+            return Err(Message::new("docker.invalid_container_id").with("id", container_id).text());
+        }
+
+        // 使用TTY的容器日志没有多路复用帧头，需要先查询
+        let inspect: serde_json::Value = serde_json::from_slice(&self.get(&format!("/containers/{}/json", container_id))?)
+            .map_err(|e| Message::new("docker.invalid_container_info").with("error", e).text())?;
+        let tty = inspect.pointer("/Config/Tty").and_then(|t| t.as_bool()).unwrap_or(false);
+
+        let mut query = "stdout=1&stderr=1&timestamps=1".to_string();
+        if let Some(since) = since {
+            query.push_str(&format!("&since={}", parse_since(since, chrono::Utc::now())?));
+        }
+        if let Some(tail) = tail {
+            query.push_str(&format!("&tail={}", tail));
+        }
+        let body = self.get(&format!("/containers/{}/logs?{}", container_id, query))?;
+        let frames = demux_log_stream(&body, tty);
+        log::info!("🐳 容器 {} 返回 {} 行日志", container_id, frames.len());
+        Ok(to_docker_json_lines(&frames))
+    }
+}
+
+/// 把 `since` 参数转换为Docker API使用的Unix时间戳
+fn parse_since(since: &str, now: chrono::DateTime<chrono::Utc>) -> Result<i64, String> {
+    let since = since.trim();
+    if let Ok(timestamp) = since.parse::<i64>() {
+        return Ok(timestamp);
+    }
+    if let Ok(time) = chrono::DateTime::parse_from_rfc3339(since) {
+        return Ok(time.timestamp());
+    }
+    let (number, unit) = since.split_at(since.find(|c: char| !c.is_ascii_digit()).unwrap_or(since.len()));
+    let amount: i64 = number.parse().map_err(|_| Message::new("logs.invalid_since").with("since", since).text())?;
+    let seconds = match unit {
+        "s" => amount,
+        "m" => amount * 60,
+        "h" => amount * 3600,
+        "d" => amount * 86400,
+        _ => return Err(Message::new("logs.invalid_since").with("since", since).text()),
+    };
+    Ok(now.timestamp() - seconds)
+}
+
+/// 解析HTTP响应，返回状态码和（解除分块编码后的）响应体
+fn parse_http_response(response: &[u8]) -> Result<(u16, Vec<u8>), String> {
+    let header_end = response.windows(4).position(|w| w == b"\r\n\r\n")
+        .ok_or_else(|| Message::new("docker.invalid_response").text())?;
+    let head = String::from_utf8_lossy(&response[..header_end]);
+    let body = &response[header_end + 4..];
+
+    let status = head.split_whitespace().nth(1)
+        .and_then(|code| code.parse().ok())
+        .ok_or_else(|| Message::new("docker.invalid_response").text())?;
+    let chunked = head.lines().any(|line| {
+        let line = line.to_ascii_lowercase();
+        line.starts_with("transfer-encoding:") && line.contains("chunked")
+    });
+    if !chunked {
+        return Ok((status, body.to_vec()));
+    }
+
+    let mut decoded = Vec::with_capacity(body.len());
+    let mut rest = body;
+    while let Some(line_end) = rest.windows(2).position(|w| w == b"\r\n") {
+        let size_text = String::from_utf8_lossy(&rest[..line_end]);
+        let size = usize::from_str_radix(size_text.split(';').next().unwrap_or("").trim(), 16)
+            .map_err(|_| Message::new("docker.invalid_chunk").text())?;
+        rest = &rest[line_end + 2..];
+        if size == 0 || rest.len() < size {
+            break;
+        }
+        decoded.extend_from_slice(&rest[..size]);
+        rest = rest.get(size + 2..).unwrap_or_default();
+    }
+    Ok((status, decoded))
+}
+
+/// 拆分容器日志流
+///
+/// 非TTY容器的日志是多路复用流：每帧8字节帧头（流类型、3字节保留、4字节大端长度）加内容；
+/// TTY容器的日志是原始输出，全部视为stdout。
+fn demux_log_stream(body: &[u8], tty: bool) -> Vec<LogFrame> {
+    let mut frames = Vec::new();
+    let mut push_lines = |stream: &'static str, bytes: &[u8]| {
+        for line in String::from_utf8_lossy(bytes).lines() {
+            frames.push(LogFrame { stream, text: line.to_string() });
+        }
+    };
+
+    if tty {
+        push_lines("stdout", body);
+        return frames;
+    }
+
+    let mut rest = body;
+    while rest.len() >= 8 {
+        let stream = if rest[0] == 2 { "stderr" } else { "stdout" };
+        let size = u32::from_be_bytes([rest[4], rest[5], rest[6], rest[7]]) as usize;
+        let end = (8 + size).min(rest.len());
+        push_lines(stream, &rest[8..end]);
+        rest = &rest[end..];
+    }
+    frames
+}
+
+/// 转换为Docker JSON日志格式（时间戳取自 `timestamps=1` 添加的行首RFC 3339时间）
+fn to_docker_json_lines(frames: &[LogFrame]) -> String {
+    frames.iter().map(|frame| {
+        let (time, text) = match frame.text.split_once(' ') {
+            Some((time, text)) if chrono::DateTime::parse_from_rfc3339(time).is_ok() => (Some(time), text),
+            _ => (None, frame.text.as_str()),
+        };
+        serde_json::json!({
+            "log": format!("{}\n", text),
+            "stream": frame.stream,
+            "time": time,
+        }).to_string()
+    }).collect::<Vec<_>>().join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(stream: u8, text: &str) -> Vec<u8> {
+        let mut bytes = vec![stream, 0, 0, 0];
+        bytes.extend_from_slice(&(text.len() as u32).to_be_bytes());
+        bytes.extend_from_slice(text.as_bytes());
+        bytes
+    }
+
+    #[test]
+    fn test_demux_and_convert_to_docker_json() {
+        let mut body = frame(1, "2024-01-15T10:30:45.123456789Z started\n");
+        body.extend(frame(2, "2024-01-15T10:30:46.000000000Z ERROR boom\n"));
+
+        let frames = demux_log_stream(&body, false);
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[1].stream, "stderr");
+
+        let lines = to_docker_json_lines(&frames);
+        let second: serde_json::Value = serde_json::from_str(lines.lines().nth(1).unwrap()).unwrap();
+        assert_eq!(second["log"], "ERROR boom\n");
+        assert_eq!(second["stream"], "stderr");
+        assert_eq!(second["time"], "2024-01-15T10:30:46.000000000Z");
+
+        assert_eq!(demux_log_stream(b"a\nb\n", true).len(), 2);
+    }
+
+    #[test]
+    fn test_parse_chunked_response_and_since() {
+        let response = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nhello\r\n6\r\n world\r\n0\r\n\r\n";
+        let (status, body) = parse_http_response(response).unwrap();
+        assert_eq!((status, body.as_slice()), (200, b"hello world".as_slice()));
+
+        let now = chrono::DateTime::parse_from_rfc3339("2024-01-15T12:00:00Z").unwrap().with_timezone(&chrono::Utc);
+        assert_eq!(parse_since("10m", now).unwrap(), now.timestamp() - 600);
+        assert_eq!(parse_since("1700000000", now).unwrap(), 1700000000);
+        assert_eq!(parse_since("2024-01-15T11:00:00Z", now).unwrap(), now.timestamp() - 3600);
+        assert!(parse_since("yesterday", now).is_err());
+    }
+}
@@ -0,0 +1,558 @@
+/// 解析结果持久化
+///
+/// 多GB的排查会话中，解析结果保存到会话对应的SQLite数据库（会话目录下 `<会话名>.entries.db`），
+/// 重启应用后可以直接从数据库查询或恢复到解析缓存，不需要重新花几个小时解析。
+///
+/// # 表结构
+/// - `files`: 每个已保存文件一行（路径、保存时的文件大小和前缀指纹、检测到的格式）
+/// - `entries`: 日志条目（文件ID + 行号为主键）
+/// - `entry_metadata`: 条目元数据，每个键值一行，按键值建索引
+///
+/// 查询时级别、元数据和普通文本搜索转换为SQL条件，分页和总数也由SQL计算（`LIMIT/OFFSET`、`COUNT(*)`）；
+/// 只有正则、全词和非ASCII的不区分大小写搜索需要在SQL预筛选后由 `Searcher` 精确匹配，再在内存中分页。
+
+use crate::i18n::Message;
+use crate::models::LogLine;
+use crate::search::{EntryFilter, SearchMode, Searcher};
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+
+/// 当前数据库结构版本
+pub const SCHEMA_VERSION: i64 = 2;
+
+/// 每页默认条目数
+pub const DEFAULT_PAGE_SIZE: usize = 500;
+
+/// 已保存的文件
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PersistedFile {
+    /// 文件路径
+    pub path: String,
+    /// 保存时的文件大小（字节）
+    pub file_size: u64,
+    /// 保存时的前缀指纹
+    pub fingerprint: u64,
+    /// 检测到的日志格式
+    pub detected_format: Option<String>,
+    /// 条目数
+    pub entry_count: usize,
+    /// 保存时间（RFC 3339）
+    pub saved_at: String,
+}
+
+/// 查询结果分页
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntryPage {
+    /// 文件路径
+    pub file_path: String,
+    /// 当前页的条目（按行号排序）
+    pub entries: Vec<LogLine>,
+    /// 满足条件的条目总数
+    pub total_matches: usize,
+    /// 当前页第一条条目的序号（从0开始）
+    pub offset: usize,
+    /// 是否还有后续条目
+    pub has_more: bool,
+}
+
+/// 会话的解析结果数据库
+pub struct EntryStore {
+    connection: Mutex<Connection>,
+}
+
+impl EntryStore {
+    /// 打开（不存在时创建）数据库
+    ///
+    /// # 参数
+    /// - `db_path`: 数据库文件路径
+    pub fn open<P: AsRef<Path>>(db_path: P) -> Result<Self, String> {
+        let db_path = db_path.as_ref();
+        if let Some(dir) = db_path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| Message::new("store.create_directory_failed").with("error", e).text())?;
+        }
+        let connection = Connection::open(db_path)
+            .map_err(|e| Message::new("store.open_failed").with("path", db_path.display()).with("error", e).text())?;
+        let store = Self { connection: Mutex::new(connection) };
+        store.init_schema()?;
+        Ok(store)
+    }
+
+    fn init_schema(&self) -> Result<(), String> {
+        let conn = self.lock()?;
+        let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0)).map_err(sql_error)?;
+        if version > SCHEMA_VERSION {
+            return Err(Message::new("store.version_too_new").with("version", version).with("supported", SCHEMA_VERSION).text());
+        }
+        // 版本1的条目表没有提取出的消息正文
+        if version == 1 {
+            conn.execute("ALTER TABLE entries ADD COLUMN message TEXT", []).map_err(sql_error)?;
+        }
+
+        conn.execute_batch(&format!(
+            "PRAGMA journal_mode = WAL;
+             PRAGMA synchronous = NORMAL;
+             CREATE TABLE IF NOT EXISTS files (
+                 id INTEGER PRIMARY KEY AUTOINCREMENT,
+                 path TEXT NOT NULL UNIQUE,
+                 file_size INTEGER NOT NULL,
+                 fingerprint INTEGER NOT NULL,
+                 detected_format TEXT,
+                 entry_count INTEGER NOT NULL,
+                 saved_at TEXT NOT NULL
+             );
+             CREATE TABLE IF NOT EXISTS entries (
+                 file_id INTEGER NOT NULL REFERENCES files(id) ON DELETE CASCADE,
+                 line_number INTEGER NOT NULL,
+                 level TEXT,
+                 timestamp TEXT,
+                 content TEXT NOT NULL,
+                 formatted_content TEXT,
+                 processed_by TEXT NOT NULL,
+                 message TEXT,
+                 PRIMARY KEY (file_id, line_number)
+             );
+             CREATE TABLE IF NOT EXISTS entry_metadata (
+                 file_id INTEGER NOT NULL,
+                 line_number INTEGER NOT NULL,
+                 key TEXT NOT NULL,
+                 value TEXT NOT NULL,
+                 PRIMARY KEY (file_id, line_number, key)
+             );
+             CREATE INDEX IF NOT EXISTS idx_entries_level ON entries(file_id, level);
+             CREATE INDEX IF NOT EXISTS idx_metadata_key_value ON entry_metadata(file_id, key, value);
+             PRAGMA user_version = {};",
+            SCHEMA_VERSION
+        )).map_err(sql_error)
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Connection>, String> {
+        self.connection.lock().map_err(|_| Message::new("store.lock_failed").text())
+    }
+
+    /// 保存文件的解析结果（替换该文件之前保存的结果）
+    ///
+    /// # 参数
+    /// - `file_path`: 文件路径
+    /// - `file_size`: 解析时的文件大小
+    /// - `fingerprint`: 解析时的前缀指纹（恢复时据此判断文件是否变化）
+    /// - `detected_format`: 检测到的日志格式
+    /// - `entries`: 解析出的条目
+    pub fn save_file(
+        &self,
+        file_path: &str,
+        file_size: u64,
+        fingerprint: u64,
+        detected_format: Option<&str>,
+        entries: &[LogLine],
+    ) -> Result<PersistedFile, String> {
+        let mut conn = self.lock()?;
+        let tx = conn.transaction().map_err(sql_error)?;
+        let saved_at = chrono::Utc::now().to_rfc3339();
+
+        if let Some(file_id) = file_id(&tx, file_path)? {
+            tx.execute("DELETE FROM entry_metadata WHERE file_id = ?", params![file_id]).map_err(sql_error)?;
+            tx.execute("DELETE FROM entries WHERE file_id = ?", params![file_id]).map_err(sql_error)?;
+            tx.execute("DELETE FROM files WHERE id = ?", params![file_id]).map_err(sql_error)?;
+        }
+        tx.execute(
+            "INSERT INTO files (path, file_size, fingerprint, detected_format, entry_count, saved_at) VALUES (?, ?, ?, ?, ?, ?)",
+            // SQLite的INTEGER是有符号的，指纹按位原样存储
+            params![file_path, file_size as i64, fingerprint as i64, detected_format, entries.len() as i64, saved_at],
+        ).map_err(sql_error)?;
+        let file_id = tx.last_insert_rowid();
+
+        {
+            let mut insert_entry = tx.prepare(
+                "INSERT OR REPLACE INTO entries (file_id, line_number, level, timestamp, content, formatted_content, processed_by, message)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?)"
+            ).map_err(sql_error)?;
+            let mut insert_metadata = tx.prepare(
+                "INSERT OR REPLACE INTO entry_metadata (file_id, line_number, key, value) VALUES (?, ?, ?, ?)"
+            ).map_err(sql_error)?;
+
+            for entry in entries {
+                let processed_by = serde_json::to_string(&entry.processed_by)
+                    .map_err(|e| Message::new("store.serialize_entry_failed").with("error", e).text())?;
+                insert_entry.execute(params![
+                    file_id,
+                    entry.line_number as i64,
+                    entry.level,
+                    entry.timestamp,
+                    entry.content,
+                    entry.formatted_content,
+                    processed_by,
+                    entry.message,
+                ]).map_err(sql_error)?;
+                for (key, value) in &entry.metadata {
+                    insert_metadata.execute(params![file_id, entry.line_number as i64, key, value]).map_err(sql_error)?;
+                }
+            }
+        }
+        tx.commit().map_err(sql_error)?;
+
+        Ok(PersistedFile {
+            path: file_path.to_string(),
+            file_size,
+            fingerprint,
+            detected_format: detected_format.map(str::to_string),
+            entry_count: entries.len(),
+            saved_at,
+        })
+    }
+
+    /// 列出已保存的文件（按路径排序）
+    pub fn files(&self) -> Result<Vec<PersistedFile>, String> {
+        let conn = self.lock()?;
+        let mut stmt = conn.prepare(
+            "SELECT path, file_size, fingerprint, detected_format, entry_count, saved_at FROM files ORDER BY path"
+        ).map_err(sql_error)?;
+        let files = stmt.query_map([], persisted_file).map_err(sql_error)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(sql_error)?;
+        Ok(files)
+    }
+
+    /// 获取已保存文件的信息（未保存时返回None）
+    pub fn file(&self, file_path: &str) -> Result<Option<PersistedFile>, String> {
+        let conn = self.lock()?;
+        conn.query_row(
+            "SELECT path, file_size, fingerprint, detected_format, entry_count, saved_at FROM files WHERE path = ?",
+            params![file_path],
+            persisted_file,
+        ).optional().map_err(sql_error)
+    }
+
+    /// 读取文件保存的全部条目（按行号排序）
+    pub fn load_entries(&self, file_path: &str) -> Result<Vec<LogLine>, String> {
+        let conn = self.lock()?;
+        let Some(file_id) = file_id(&conn, file_path)? else {
+            return Err(Message::new("store.not_saved").with("path", file_path).text());
+        };
+        let mut entries = select_entries(&conn, file_id, "", &[], None)?;
+        attach_metadata(&conn, file_id, &mut entries)?;
+        Ok(entries)
+    }
+
+    /// 删除文件保存的解析结果
+    pub fn remove_file(&self, file_path: &str) -> Result<bool, String> {
+        let conn = self.lock()?;
+        let Some(file_id) = file_id(&conn, file_path)? else {
+            return Ok(false);
+        };
+        conn.execute("DELETE FROM entry_metadata WHERE file_id = ?", params![file_id]).map_err(sql_error)?;
+        conn.execute("DELETE FROM entries WHERE file_id = ?", params![file_id]).map_err(sql_error)?;
+        conn.execute("DELETE FROM files WHERE id = ?", params![file_id]).map_err(sql_error)?;
+        Ok(true)
+    }
+
+    /// 按保留策略删除旧文件的解析结果
+    ///
+    /// 先删除保存时间早于 `saved_before` 的文件，再按保存时间从旧到新删除，
+    /// 直到剩余文件的原始大小合计不超过 `max_bytes`。有文件被删除时压缩数据库释放磁盘空间。
+    ///
+    /// # 参数
+    /// - `saved_before`: 保存时间早于该时间的文件被删除（None表示不限）
+    /// - `max_bytes`: 剩余文件原始大小的上限（None表示不限）
+    ///
+    /// # Returns
+    /// - `Ok(Vec<String>)`: 被删除的文件路径
+    /// - `Err(String)`: 读取或删除失败时的错误信息
+    pub fn prune(&self, saved_before: Option<chrono::DateTime<chrono::Utc>>, max_bytes: Option<u64>) -> Result<Vec<String>, String> {
+        let mut files: Vec<_> = self.files()?
+            .into_iter()
+            .map(|file| {
+                let saved_at = chrono::DateTime::parse_from_rfc3339(&file.saved_at).ok().map(|t| t.with_timezone(&chrono::Utc));
+                (saved_at, file)
+            })
+            .collect();
+        // 保存时间无法解析的视为最旧
+        files.sort_by_key(|(saved_at, _)| *saved_at);
+
+        let mut total: u64 = files.iter().map(|(_, file)| file.file_size).sum();
+        let mut removed = Vec::new();
+        for (saved_at, file) in files {
+            let expired = saved_before.is_some_and(|cutoff| saved_at.is_none_or(|t| t < cutoff));
+            let over_size = max_bytes.is_some_and(|max| total > max);
+            if !expired && !over_size {
+                continue;
+            }
+            self.remove_file(&file.path)?;
+            total -= file.file_size;
+            removed.push(file.path);
+        }
+
+        if !removed.is_empty() {
+            self.lock()?.execute_batch("VACUUM").map_err(sql_error)?;
+        }
+        Ok(removed)
+    }
+
+    /// 按筛选条件分页查询保存的条目
+    ///
+    /// # 参数
+    /// - `file_path`: 文件路径
+    /// - `filter`: 筛选条件（级别、元数据、内容搜索）
+    /// - `offset`: 起始序号（从0开始）
+    /// - `limit`: 每页最多条目数
+    ///
+    /// # Returns
+    /// - `Ok(EntryPage)`: 当前页的条目和满足条件的总数
+    /// - `Err(String)`: 文件未保存、搜索条件无效或查询失败时的错误信息
+    pub fn query(&self, file_path: &str, filter: &EntryFilter, offset: usize, limit: usize) -> Result<EntryPage, String> {
+        let conn = self.lock()?;
+        let Some(file_id) = file_id(&conn, file_path)? else {
+            return Err(Message::new("store.not_saved").with("path", file_path).text());
+        };
+
+        let (conditions, args, searcher) = translate_filter(filter)?;
+        let (total_matches, mut entries) = match searcher {
+            // 需要精确匹配时只能取出SQL预筛选的全部结果，匹配后再分页
+            Some(searcher) => {
+                let mut matches = select_entries(&conn, file_id, &conditions, &args, None)?;
+                matches.retain(|entry| searcher.is_match(entry.formatted_content.as_deref().unwrap_or(&entry.content)));
+                let total_matches = matches.len();
+                (total_matches, matches.into_iter().skip(offset).take(limit).collect())
+            }
+            None => {
+                let total_matches = count_entries(&conn, file_id, &conditions, &args)?;
+                (total_matches, select_entries(&conn, file_id, &conditions, &args, Some((offset, limit)))?)
+            }
+        };
+        attach_metadata(&conn, file_id, &mut entries)?;
+
+        Ok(EntryPage {
+            file_path: file_path.to_string(),
+            has_more: offset + entries.len() < total_matches,
+            entries,
+            total_matches,
+            offset,
+        })
+    }
+}
+
+fn sql_error(e: rusqlite::Error) -> String {
+    Message::new("store.sql_failed").with("error", e).text()
+}
+
+fn file_id(conn: &Connection, file_path: &str) -> Result<Option<i64>, String> {
+    conn.query_row("SELECT id FROM files WHERE path = ?", params![file_path], |row| row.get(0))
+        .optional()
+        .map_err(sql_error)
+}
+
+fn persisted_file(row: &rusqlite::Row) -> rusqlite::Result<PersistedFile> {
+    Ok(PersistedFile {
+        path: row.get(0)?,
+        file_size: row.get::<_, i64>(1)? as u64,
+        fingerprint: row.get::<_, i64>(2)? as u64,
+        detected_format: row.get(3)?,
+        entry_count: row.get::<_, i64>(4)? as usize,
+        saved_at: row.get(5)?,
+    })
+}
+
+/// 把筛选条件转换为SQL条件
+///
+/// # Returns
+/// - SQL条件（以 ` AND ` 开头，没有条件时为空）
+/// - 条件参数
+/// - 需要在SQL预筛选后精确匹配的搜索（普通文本搜索完全由SQL处理时为None）
+fn translate_filter(filter: &EntryFilter) -> Result<(String, Vec<String>, Option<Searcher>), String> {
+    let mut conditions = String::new();
+    let mut args = Vec::new();
+
+    if !filter.levels.is_empty() {
+        let placeholders = vec!["?"; filter.levels.len()].join(", ");
+        conditions.push_str(&format!(" AND UPPER(e.level) IN ({})", placeholders));
+        args.extend(filter.levels.iter().map(|level| level.to_uppercase()));
+    }
+
+    let mut metadata: Vec<(&String, &String)> = filter.metadata.iter().collect();
+    metadata.sort();
+    for (key, value) in metadata {
+        conditions.push_str(
+            " AND EXISTS (SELECT 1 FROM entry_metadata m
+                          WHERE m.file_id = e.file_id AND m.line_number = e.line_number AND m.key = ? AND m.value = ?)"
+        );
+        args.push(key.clone());
+        args.push(value.clone());
+    }
+
+    let mut searcher = None;
+    if let Some(search) = &filter.search {
+        let compiled = Searcher::new(search)?;
+        let text = "COALESCE(e.formatted_content, e.content)";
+        if search.mode == SearchMode::Text && !search.whole_word {
+            if search.case_sensitive {
+                conditions.push_str(&format!(" AND instr({}, ?) > 0", text));
+                args.push(search.query.clone());
+            } else if search.query.is_ascii() {
+                // SQLite的lower()只转换ASCII字母，关键字为ASCII时结果与正则的不区分大小写一致
+                conditions.push_str(&format!(" AND instr(lower({}), ?) > 0", text));
+                args.push(search.query.to_lowercase());
+            } else {
+                searcher = Some(compiled);
+            }
+        } else {
+            searcher = Some(compiled);
+        }
+    }
+
+    Ok((conditions, args, searcher))
+}
+
+/// 查询参数：文件ID和条件参数
+fn query_params(file_id: i64, args: &[String]) -> Vec<Value> {
+    std::iter::once(Value::Integer(file_id))
+        .chain(args.iter().cloned().map(Value::Text))
+        .collect()
+}
+
+/// 统计满足条件的条目数
+fn count_entries(conn: &Connection, file_id: i64, conditions: &str, args: &[String]) -> Result<usize, String> {
+    let sql = format!("SELECT COUNT(*) FROM entries e WHERE e.file_id = ?{}", conditions);
+    let count: i64 = conn.query_row(&sql, params_from_iter(query_params(file_id, args)), |row| row.get(0))
+        .map_err(sql_error)?;
+    Ok(count as usize)
+}
+
+/// 查询满足条件的条目（不含元数据）
+///
+/// # 参数
+/// - `page`: 分页 `(offset, limit)`，None时返回全部条目
+fn select_entries(conn: &Connection, file_id: i64, conditions: &str, args: &[String], page: Option<(usize, usize)>) -> Result<Vec<LogLine>, String> {
+    let mut sql = format!(
+        "SELECT e.line_number, e.level, e.timestamp, e.content, e.formatted_content, e.processed_by, e.message
+         FROM entries e WHERE e.file_id = ?{} ORDER BY e.line_number",
+        conditions
+    );
+    let mut params = query_params(file_id, args);
+    if let Some((offset, limit)) = page {
+        sql.push_str(" LIMIT ? OFFSET ?");
+        params.push(Value::Integer(limit.min(i64::MAX as usize) as i64));
+        params.push(Value::Integer(offset.min(i64::MAX as usize) as i64));
+    }
+    let mut stmt = conn.prepare(&sql).map_err(sql_error)?;
+    let entries = stmt.query_map(params_from_iter(params), |row| {
+        let processed_by: String = row.get(5)?;
+        Ok(LogLine {
+            line_number: row.get::<_, i64>(0)? as usize,
+            level: row.get(1)?,
+            timestamp: row.get(2)?,
+            content: row.get(3)?,
+            formatted_content: row.get(4)?,
+            message: row.get(6)?,
+            metadata: HashMap::new(),
+            processed_by: serde_json::from_str(&processed_by).unwrap_or_default(),
+        })
+    }).map_err(sql_error)?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(sql_error)?;
+    Ok(entries)
+}
+
+/// 为条目填充元数据
+fn attach_metadata(conn: &Connection, file_id: i64, entries: &mut [LogLine]) -> Result<(), String> {
+    let (Some(first), Some(last)) = (entries.first(), entries.last()) else {
+        return Ok(());
+    };
+    let mut stmt = conn.prepare(
+        "SELECT line_number, key, value FROM entry_metadata WHERE file_id = ? AND line_number BETWEEN ? AND ?"
+    ).map_err(sql_error)?;
+    let rows = stmt.query_map(params![file_id, first.line_number as i64, last.line_number as i64], |row| {
+        Ok((row.get::<_, i64>(0)? as usize, row.get::<_, String>(1)?, row.get::<_, String>(2)?))
+    }).map_err(sql_error)?;
+
+    let positions: HashMap<usize, usize> = entries.iter().enumerate().map(|(i, entry)| (entry.line_number, i)).collect();
+    for row in rows {
+        let (line_number, key, value) = row.map_err(sql_error)?;
+        if let Some(&i) = positions.get(&line_number) {
+            entries[i].metadata.insert(key, value);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::SearchOptions;
+
+    fn entry(line_number: usize, level: &str, content: &str, pod: &str) -> LogLine {
+        LogLine {
+            level: Some(level.to_string()),
+            timestamp: Some(format!("2024-01-15 10:30:{:02}", line_number)),
+            metadata: HashMap::from([("pod".to_string(), pod.to_string())]),
+            processed_by: vec!["springboot_parser".to_string()],
+            ..LogLine::new(line_number, content)
+        }
+    }
+
+    #[test]
+    fn test_save_query_and_reload_entries() {
+        let path = std::env::temp_dir().join(format!("log_whisper_entries_{}.entries.db", std::process::id()));
+        std::fs::remove_file(&path).ok();
+        let entries = vec![
+            entry(1, "INFO", "started", "api-1"),
+            entry(2, "ERROR", "Connection TIMEOUT to db", "api-1"),
+            entry(4, "ERROR", "连接超时", "api-2"),
+            entry(5, "WARN", "slow query timeout", "api-1"),
+        ];
+
+        let store = EntryStore::open(&path).unwrap();
+        let saved = store.save_file("/var/log/app.log", 1234, u64::MAX - 1, Some("SpringBoot"), &entries).unwrap();
+        assert_eq!(saved.entry_count, 4);
+        drop(store);
+
+        // 重新打开（模拟重启应用）
+        let store = EntryStore::open(&path).unwrap();
+        let file = store.file("/var/log/app.log").unwrap().unwrap();
+        assert_eq!((file.file_size, file.fingerprint), (1234, u64::MAX - 1));
+        let loaded = store.load_entries("/var/log/app.log").unwrap();
+        assert_eq!(serde_json::to_value(&loaded).unwrap(), serde_json::to_value(&entries).unwrap());
+
+        let search = |query: &str, mode: SearchMode| Some(SearchOptions { query: query.to_string(), mode, ..Default::default() });
+        let filter = EntryFilter {
+            levels: vec!["error".to_string(), "warn".to_string()],
+            search: search("timeout", SearchMode::Text),
+            metadata: HashMap::from([("pod".to_string(), "api-1".to_string())]),
+        };
+        let page = store.query("/var/log/app.log", &filter, 0, 1).unwrap();
+        assert_eq!((page.total_matches, page.has_more), (2, true));
+        assert_eq!(page.entries[0].line_number, 2);
+        assert_eq!(page.entries[0].metadata.get("pod").map(String::as_str), Some("api-1"));
+        // 分页由SQL完成，总数不受分页影响
+        let page = store.query("/var/log/app.log", &filter, 1, 1).unwrap();
+        assert_eq!((page.total_matches, page.has_more, page.entries[0].line_number), (2, false, 5));
+        assert!(store.query("/var/log/app.log", &filter, 5, 1).unwrap().entries.is_empty());
+
+        let filter = EntryFilter { search: search(r"超时|^started$", SearchMode::Regex), ..Default::default() };
+        let page = store.query("/var/log/app.log", &filter, 0, DEFAULT_PAGE_SIZE).unwrap();
+        assert_eq!(page.entries.iter().map(|e| e.line_number).collect::<Vec<_>>(), vec![1, 4]);
+        let page = store.query("/var/log/app.log", &filter, 1, 1).unwrap();
+        assert_eq!((page.total_matches, page.entries[0].line_number), (2, 4));
+
+        // 再次保存替换旧结果
+        store.save_file("/var/log/app.log", 10, 1, None, &entries[..1]).unwrap();
+        assert_eq!(store.files().unwrap()[0].entry_count, 1);
+        assert!(store.remove_file("/var/log/app.log").unwrap());
+        assert!(store.query("/var/log/app.log", &EntryFilter::default(), 0, 10).is_err());
+
+        // 保留策略：超出大小上限时先删除最早保存的文件
+        store.save_file("/var/log/old.log", 600, 1, None, &entries[..1]).unwrap();
+        store.save_file("/var/log/new.log", 500, 2, None, &entries[..1]).unwrap();
+        assert_eq!(store.prune(None, Some(1000)).unwrap(), vec!["/var/log/old.log".to_string()]);
+        assert!(store.prune(None, Some(1000)).unwrap().is_empty());
+        let cutoff = chrono::Utc::now() + chrono::Duration::seconds(1);
+        assert_eq!(store.prune(Some(cutoff), None).unwrap(), vec!["/var/log/new.log".to_string()]);
+
+        drop(store);
+        for suffix in ["", "-wal", "-shm"] {
+            std::fs::remove_file(format!("{}{}", path.display(), suffix)).ok();
+        }
+    }
+}
@@ -0,0 +1,341 @@
+/// Windows事件日志
+///
+/// 在Windows上通过 `EvtSubscribe` 订阅事件日志通道（默认Application和System），
+/// 新事件渲染为XML后转换为 `LogLine`，与文件跟踪共用 `TailRegistry` 的批次、背压和确认机制，
+/// 因此前端对跟踪结果的过滤和告警同样适用于事件日志。
+///
+/// 事件消息优先使用发布者提供的格式化消息（`EvtFormatMessage`），
+/// 发布者元数据不可用时退回到拼接事件的 `EventData` 字段。
+/// 其他平台上订阅会直接返回错误；XML到 `LogLine` 的转换与平台无关。
+
+use crate::i18n::Message;
+use crate::models::LogLine;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::HashMap;
+
+/// 默认订阅的通道
+pub const DEFAULT_CHANNELS: [&str; 2] = ["Application", "System"];
+
+static PROVIDER_NAME: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"<Provider\s[^>]*?Name=['"]([^'"]*)['"]"#).unwrap()
+});
+pub(crate) static TIME_CREATED: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"<TimeCreated\s[^>]*?SystemTime=['"]([^'"]*)['"]"#).unwrap()
+});
+pub(crate) static EVENT_DATA: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"<Data(?:\s+Name=['"]([^'"]*)['"])?\s*>([^<]*)</Data>"#).unwrap()
+});
+
+/// 订阅回调送出的原始事件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawEvent {
+    /// `EvtRender` 渲染出的事件XML
+    pub xml: String,
+    /// 发布者格式化后的消息（可选）
+    pub message: Option<String>,
+}
+
+/// 构建订阅多个通道的结构化XML查询
+///
+/// 通道名称只允许字母、数字、空格以及 `-`、`_`、`.`、`/`（如 `Microsoft-Windows-Sysmon/Operational`）。
+pub fn build_query(channels: &[String]) -> Result<String, String> {
+    if channels.is_empty() {
+        return Err(Message::new("eventlog.no_channels").text());
+    }
+    let mut query = String::from("<QueryList><Query Id=\"0\">");
+    for channel in channels {
+        let valid = !channel.trim().is_empty()
+            && channel.chars().all(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '_' | '.' | '/'));
+        if !valid {
+            return Err(Message::new("eventlog.invalid_channel").with("channel", format!("{:?}", channel)).text());
+        }
+        query.push_str(&format!("<Select Path=\"{}\">*</Select>", channel));
+    }
+    query.push_str("</Query></QueryList>");
+    Ok(query)
+}
+
+/// 把事件级别（`<Level>`）映射为日志级别
+pub(crate) fn event_level(level: u32) -> &'static str {
+    match level {
+        1 | 2 => "ERROR",
+        3 => "WARN",
+        5 => "DEBUG",
+        _ => "INFO",
+    }
+}
+
+pub(crate) fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// 提取 `<tag ...>value</tag>` 的内容
+pub(crate) fn element<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    let open = xml.find(&format!("<{}", tag))?;
+    let start = open + xml[open..].find('>')? + 1;
+    if xml[..start].ends_with("/>") {
+        return None;
+    }
+    let end = start + xml[start..].find(&format!("</{}>", tag))?;
+    Some(&xml[start..end])
+}
+
+/// 把事件转换为日志条目
+///
+/// # 参数
+/// - `event`: 订阅送出的原始事件
+/// - `line_number`: 条目行号（从开始订阅时计为1）
+///
+/// # Returns
+/// - `Some(LogLine)`: 显示内容为 `<时间> <级别> [<来源>] <事件ID>: <消息>`，
+///   通道、来源、事件ID、记录ID和计算机名记录在元数据中
+/// - `None`: XML中没有 `<System>` 部分
+pub fn event_to_line(event: &RawEvent, line_number: usize) -> Option<LogLine> {
+    let system = element(&event.xml, "System")?;
+    let provider = PROVIDER_NAME.captures(system).map(|c| unescape_xml(&c[1])).unwrap_or_default();
+    let event_id = element(system, "EventID").unwrap_or_default().trim().to_string();
+    let level = event_level(element(system, "Level").and_then(|l| l.trim().parse().ok()).unwrap_or(4));
+    let timestamp = TIME_CREATED.captures(system).map(|c| c[1].to_string());
+
+    let message = event.message.as_deref()
+        .map(|m| m.trim().to_string())
+        .filter(|m| !m.is_empty())
+        .unwrap_or_else(|| {
+            let data = element(&event.xml, "EventData").unwrap_or_default();
+            EVENT_DATA.captures_iter(data)
+                .map(|c| match c.get(1) {
+                    Some(name) => format!("{}={}", name.as_str(), unescape_xml(&c[2])),
+                    None => unescape_xml(&c[2]),
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        });
+
+    let mut metadata = HashMap::new();
+    for (key, tag) in [("channel", "Channel"), ("computer", "Computer"), ("record_id", "EventRecordID")] {
+        if let Some(value) = element(system, tag) {
+            metadata.insert(key.to_string(), unescape_xml(value.trim()));
+        }
+    }
+    metadata.insert("provider".to_string(), provider.clone());
+    metadata.insert("event_id".to_string(), event_id.clone());
+
+    let content = format!(
+        "{} {} [{}] {}: {}",
+        timestamp.as_deref().unwrap_or_default(), level, provider, event_id, message
+    );
+    Some(LogLine {
+        line_number,
+        content,
+        level: Some(level.to_string()),
+        timestamp,
+        formatted_content: None,
+        message: None,
+        metadata,
+        processed_by: vec!["eventlog".to_string()],
+    })
+}
+
+#[cfg(windows)]
+pub use win::EventLogSubscription;
+
+/// 非Windows平台的占位实现，订阅总是失败
+#[cfg(not(windows))]
+pub struct EventLogSubscription;
+
+#[cfg(not(windows))]
+impl EventLogSubscription {
+    /// 订阅事件日志通道（仅Windows可用）
+    pub fn start(channels: &[String]) -> Result<(Self, std::sync::mpsc::Receiver<RawEvent>), String> {
+        build_query(channels)?;
+        Err(Message::new("eventlog.unsupported_platform").text())
+    }
+}
+
+#[cfg(windows)]
+mod win {
+    use super::{build_query, RawEvent, PROVIDER_NAME};
+    use std::collections::HashMap;
+    use std::ffi::c_void;
+    use std::sync::mpsc::{self, Receiver, Sender};
+    use std::sync::Mutex;
+    use windows_sys::Win32::System::EventLog::{
+        EvtClose, EvtFormatMessage, EvtFormatMessageEvent, EvtOpenPublisherMetadata, EvtRender,
+        EvtRenderEventXml, EvtSubscribe, EvtSubscribeActionDeliver, EvtSubscribeToFutureEvents,
+        EVT_HANDLE, EVT_SUBSCRIBE_NOTIFY_ACTION,
+    };
+
+    /// 回调上下文：事件发送端和按来源缓存的发布者元数据句柄
+    struct SubscriptionContext {
+        sender: Mutex<Sender<RawEvent>>,
+        publishers: Mutex<HashMap<String, EVT_HANDLE>>,
+    }
+
+    /// 事件日志订阅，Drop时取消订阅并释放句柄
+    pub struct EventLogSubscription {
+        handle: EVT_HANDLE,
+        context: *mut SubscriptionContext,
+    }
+
+    // 句柄和上下文只在创建和Drop时由持有者访问，回调线程通过EvtClose同步结束
+    unsafe impl Send for EventLogSubscription {}
+
+    fn wide(text: &str) -> Vec<u16> {
+        text.encode_utf16().chain(std::iter::once(0)).collect()
+    }
+
+    /// 两次调用的Win32缓冲区模式：先取所需字符数，再读取UTF-16文本
+    unsafe fn read_wide(mut call: impl FnMut(u32, *mut u16, *mut u32) -> i32) -> Option<String> {
+        let mut used = 0u32;
+        call(0, std::ptr::null_mut(), &mut used);
+        if used == 0 {
+            return None;
+        }
+        let mut buffer = vec![0u16; used as usize];
+        if call(used, buffer.as_mut_ptr(), &mut used) == 0 {
+            return None;
+        }
+        let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+        Some(String::from_utf16_lossy(&buffer[..len]))
+    }
+
+    unsafe fn render_xml(event: EVT_HANDLE) -> Option<String> {
+        // EvtRender的缓冲区大小按字节计算
+        let mut used = 0u32;
+        let mut property_count = 0u32;
+        EvtRender(0, event, EvtRenderEventXml as u32, 0, std::ptr::null_mut(), &mut used, &mut property_count);
+        if used == 0 {
+            return None;
+        }
+        let mut buffer = vec![0u16; (used as usize).div_ceil(2)];
+        if EvtRender(0, event, EvtRenderEventXml as u32, used, buffer.as_mut_ptr() as *mut c_void, &mut used, &mut property_count) == 0 {
+            return None;
+        }
+        let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+        Some(String::from_utf16_lossy(&buffer[..len]))
+    }
+
+    unsafe fn format_message(context: &SubscriptionContext, provider: &str, event: EVT_HANDLE) -> Option<String> {
+        let mut publishers = context.publishers.lock().ok()?;
+        let publisher = *publishers.entry(provider.to_string()).or_insert_with(|| {
+            let name = wide(provider);
+            EvtOpenPublisherMetadata(0, name.as_ptr(), std::ptr::null(), 0, 0)
+        });
+        if publisher == 0 {
+            return None;
+        }
+        read_wide(|size, buffer, used| {
+            EvtFormatMessage(publisher, event, 0, 0, std::ptr::null(), EvtFormatMessageEvent as u32, size, buffer, used)
+        })
+    }
+
+    unsafe extern "system" fn on_event(action: EVT_SUBSCRIBE_NOTIFY_ACTION, user_context: *const c_void, event: EVT_HANDLE) -> u32 {
+        if action != EvtSubscribeActionDeliver || user_context.is_null() {
+            return 0;
+        }
+        let context = &*(user_context as *const SubscriptionContext);
+        if let Some(xml) = render_xml(event) {
+            let message = PROVIDER_NAME.captures(&xml)
+                .and_then(|c| format_message(context, &c[1], event));
+            if let Ok(sender) = context.sender.lock() {
+                let _ = sender.send(RawEvent { xml, message });
+            }
+        }
+        0
+    }
+
+    impl EventLogSubscription {
+        /// 订阅事件日志通道的新事件
+        ///
+        /// # Returns
+        /// - `Ok((EventLogSubscription, Receiver<RawEvent>))`: 订阅句柄和事件接收端
+        /// - `Err(String)`: 通道不存在或无权限时的错误信息
+        pub fn start(channels: &[String]) -> Result<(Self, Receiver<RawEvent>), String> {
+            let query = wide(&build_query(channels)?);
+            let (sender, receiver) = mpsc::channel();
+            let context = Box::into_raw(Box::new(SubscriptionContext {
+                sender: Mutex::new(sender),
+                publishers: Mutex::new(HashMap::new()),
+            }));
+
+            let handle = unsafe {
+                EvtSubscribe(
+                    0,
+                    0,
+                    std::ptr::null(),
+                    query.as_ptr(),
+                    0,
+                    context as *const c_void,
+                    Some(on_event),
+                    EvtSubscribeToFutureEvents as u32,
+                )
+            };
+            if handle == 0 {
+                let error = std::io::Error::last_os_error();
+                drop(unsafe { Box::from_raw(context) });
+                return Err(Message::new("eventlog.subscribe_failed").with("error", error).text());
+            }
+            Ok((Self { handle, context }, receiver))
+        }
+    }
+
+    impl Drop for EventLogSubscription {
+        fn drop(&mut self) {
+            unsafe {
+                // 先关闭订阅，确保不再有回调访问上下文
+                EvtClose(self.handle);
+                let context = Box::from_raw(self.context);
+                if let Ok(publishers) = context.publishers.lock() {
+                    for &publisher in publishers.values().filter(|&&p| p != 0) {
+                        EvtClose(publisher);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "<Event xmlns='http://schemas.microsoft.com/win/2004/08/events/event'><System>\
+        <Provider Name='Service Control Manager' Guid='{555908d1}' EventSourceName='Service Control Manager'/>\
+        <EventID Qualifiers='16384'>7036</EventID><Level>3</Level>\
+        <TimeCreated SystemTime='2024-01-15T10:30:45.1234567Z'/><EventRecordID>4711</EventRecordID>\
+        <Channel>System</Channel><Computer>WS-01</Computer></System>\
+        <EventData><Data Name='param1'>Windows Update</Data><Data Name='param2'>stopped &amp; disabled</Data></EventData></Event>";
+
+    #[test]
+    fn test_event_to_line_maps_system_fields() {
+        let event = RawEvent { xml: SAMPLE.to_string(), message: None };
+        let line = event_to_line(&event, 3).unwrap();
+        assert_eq!(line.level.as_deref(), Some("WARN"));
+        assert_eq!(line.timestamp.as_deref(), Some("2024-01-15T10:30:45.1234567Z"));
+        assert_eq!(line.metadata["event_id"], "7036");
+        assert_eq!(line.metadata["channel"], "System");
+        assert_eq!(line.metadata["record_id"], "4711");
+        assert!(line.content.ends_with("[Service Control Manager] 7036: param1=Windows Update param2=stopped & disabled"));
+
+        let formatted = RawEvent { xml: SAMPLE.to_string(), message: Some("The Windows Update service entered the stopped state.\r\n".to_string()) };
+        assert!(event_to_line(&formatted, 1).unwrap().content.ends_with("7036: The Windows Update service entered the stopped state."));
+        assert!(event_to_line(&RawEvent { xml: "<Event/>".to_string(), message: None }, 1).is_none());
+    }
+
+    #[test]
+    fn test_build_query_validates_channels() {
+        let channels: Vec<String> = DEFAULT_CHANNELS.iter().map(|c| c.to_string()).collect();
+        assert_eq!(
+            build_query(&channels).unwrap(),
+            "<QueryList><Query Id=\"0\"><Select Path=\"Application\">*</Select><Select Path=\"System\">*</Select></Query></QueryList>"
+        );
+        assert!(build_query(&["Microsoft-Windows-Sysmon/Operational".to_string()]).is_ok());
+        assert!(build_query(&["System\"><Select Path=\"Security".to_string()]).is_err());
+        assert!(build_query(&[]).is_err());
+    }
+}
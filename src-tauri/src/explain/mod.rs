@@ -0,0 +1,236 @@
+/// 错误解释知识库（离线规则）
+///
+/// 内置知识库 `knowledge_base.yaml` 把常见的异常和错误特征（OutOfMemoryError、Connection refused、
+/// TLS握手失败等）映射到原因解释和处理建议；分析文件时用这些规则匹配条目内容，
+/// 按规则汇总命中的条目。完全离线运行，用户可以在配置中添加规则或覆盖内置规则。
+
+use crate::config::explain::ExplainRule;
+use crate::i18n::Message;
+use crate::models::LogLine;
+use once_cell::sync::Lazy;
+use regex::{RegexSet, RegexSetBuilder};
+use serde::{Deserialize, Serialize};
+
+/// 每条规则最多返回的示例条目数
+pub const MAX_SAMPLE_ENTRIES: usize = 20;
+
+/// 内置规则
+pub static BUNDLED_RULES: Lazy<Vec<ExplainRule>> = Lazy::new(|| {
+    parse_knowledge_base(include_str!("knowledge_base.yaml")).expect("内置错误知识库格式错误")
+});
+
+/// 规则命中的汇总
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorExplanation {
+    /// 规则ID
+    pub rule_id: String,
+    /// 标题
+    pub title: String,
+    /// 原因解释
+    pub explanation: String,
+    /// 处理建议
+    pub next_steps: Vec<String>,
+    /// 是否为用户规则
+    pub user_rule: bool,
+    /// 命中的条目数
+    pub count: usize,
+    /// 首次出现的行号
+    pub first_line: usize,
+    /// 命中的条目（最多 `MAX_SAMPLE_ENTRIES` 条）
+    pub entries: Vec<LogLine>,
+}
+
+/// 去掉YAML标量的引号（单引号中 `''` 表示一个单引号，双引号中支持 `\"` 和 `\\`）
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    if value.len() >= 2 && value.starts_with('\'') && value.ends_with('\'') {
+        value[1..value.len() - 1].replace("''", "'")
+    } else if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+        value[1..value.len() - 1].replace("\\\"", "\"").replace("\\\\", "\\")
+    } else {
+        value.to_string()
+    }
+}
+
+/// 解析知识库YAML
+///
+/// 只支持知识库使用的YAML子集：顶层为规则列表（`- id: ...`），规则的字段为单行字符串值，
+/// `patterns` 和 `next_steps` 为缩进的字符串列表；`#` 开头的行为注释。
+///
+/// # Returns
+/// - `Ok(Vec<ExplainRule>)`: 按文件顺序排列的规则
+/// - `Err(String)`: 格式错误（带行号）或规则无效时的错误信息
+pub fn parse_knowledge_base(text: &str) -> Result<Vec<ExplainRule>, String> {
+    let mut rules: Vec<ExplainRule> = Vec::new();
+    let mut list_key: Option<String> = None;
+
+    for (index, raw) in text.lines().enumerate() {
+        let line_number = index + 1;
+        let trimmed = raw.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let indent = raw.len() - raw.trim_start().len();
+        let (field, item) = match (indent, trimmed.strip_prefix("- ")) {
+            (0, Some(rest)) => {
+                rules.push(ExplainRule {
+                    id: String::new(),
+                    title: String::new(),
+                    patterns: Vec::new(),
+                    explanation: String::new(),
+                    next_steps: Vec::new(),
+                });
+                list_key = None;
+                (Some(rest), None)
+            }
+            (2, None) => (Some(trimmed), None),
+            (4, Some(rest)) => (None, Some(rest)),
+            _ => return Err(Message::new("knowledge_base.invalid_line").with("line", line_number).text()),
+        };
+        let rule = rules.last_mut().ok_or_else(|| Message::new("knowledge_base.rule_not_started").with("line", line_number).text())?;
+
+        if let Some(item) = item {
+            let value = unquote(item);
+            match list_key.as_deref() {
+                Some("patterns") => rule.patterns.push(value),
+                Some("next_steps") => rule.next_steps.push(value),
+                _ => return Err(Message::new("knowledge_base.orphan_item").with("line", line_number).text()),
+            }
+            continue;
+        }
+        let field = field.unwrap_or_default();
+        let (key, value) = match field.split_once(':') {
+            Some((key, value)) => (key.trim(), value.trim()),
+            None => return Err(Message::new("knowledge_base.expected_key_value").with("line", line_number).text()),
+        };
+        list_key = None;
+        match key {
+            "id" => rule.id = unquote(value),
+            "title" => rule.title = unquote(value),
+            "explanation" => rule.explanation = unquote(value),
+            "patterns" | "next_steps" if value.is_empty() => list_key = Some(key.to_string()),
+            _ => return Err(Message::new("knowledge_base.unknown_field").with("line", line_number).with("key", key).text()),
+        }
+    }
+
+    for rule in &rules {
+        rule.validate()?;
+    }
+    Ok(rules)
+}
+
+/// 合并内置规则和用户规则（用户规则在前，ID相同时覆盖内置规则）
+///
+/// # Returns
+/// - `Vec<(ExplainRule, bool)>`: 规则及是否为用户规则
+pub fn merged_rules(user_rules: &[ExplainRule]) -> Vec<(ExplainRule, bool)> {
+    user_rules.iter()
+        .map(|rule| (rule.clone(), true))
+        .chain(BUNDLED_RULES.iter()
+            .filter(|rule| !user_rules.iter().any(|user| user.id == rule.id))
+            .map(|rule| (rule.clone(), false)))
+        .collect()
+}
+
+/// 用规则匹配条目并按规则汇总
+///
+/// 每个条目只归入第一条命中的规则（用户规则优先），避免同一错误被重复解释。
+///
+/// # 参数
+/// - `entries`: 日志条目
+/// - `user_rules`: 用户规则
+///
+/// # Returns
+/// - `Ok(Vec<ErrorExplanation>)`: 按命中条目数降序排列的汇总
+/// - `Err(String)`: 用户规则无效时的错误信息
+pub fn explain_errors(entries: &[LogLine], user_rules: &[ExplainRule]) -> Result<Vec<ErrorExplanation>, String> {
+    for rule in user_rules {
+        rule.validate()?;
+    }
+    let rules = merged_rules(user_rules);
+    // 所有规则的所有匹配规则放在一个RegexSet里，每个条目只扫描一次
+    let mut owners = Vec::new();
+    let mut patterns = Vec::new();
+    for (index, (rule, _)) in rules.iter().enumerate() {
+        for pattern in &rule.patterns {
+            owners.push(index);
+            patterns.push(pattern.as_str());
+        }
+    }
+    let set: RegexSet = RegexSetBuilder::new(&patterns)
+        .case_insensitive(true)
+        .build()
+        .map_err(|e| Message::new("knowledge_base.compile_failed").with("error", e).text())?;
+
+    let mut explanations: Vec<Option<ErrorExplanation>> = vec![None; rules.len()];
+    for entry in entries {
+        let Some(matched) = set.matches(&entry.content).iter().next() else {
+            continue;
+        };
+        let index = owners[matched];
+        let (rule, user_rule) = &rules[index];
+        let explanation = explanations[index].get_or_insert_with(|| ErrorExplanation {
+            rule_id: rule.id.clone(),
+            title: rule.title.clone(),
+            explanation: rule.explanation.clone(),
+            next_steps: rule.next_steps.clone(),
+            user_rule: *user_rule,
+            count: 0,
+            first_line: entry.line_number,
+            entries: Vec::new(),
+        });
+        explanation.count += 1;
+        if explanation.entries.len() < MAX_SAMPLE_ENTRIES {
+            explanation.entries.push(entry.clone());
+        }
+    }
+
+    let mut explanations: Vec<ErrorExplanation> = explanations.into_iter().flatten().collect();
+    explanations.sort_by(|a, b| b.count.cmp(&a.count).then(a.first_line.cmp(&b.first_line)));
+    Ok(explanations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_knowledge_base() {
+        assert!(BUNDLED_RULES.len() >= 10);
+        assert!(BUNDLED_RULES.iter().all(|rule| !rule.explanation.is_empty() && !rule.next_steps.is_empty()));
+
+        let rules = parse_knowledge_base("# c\n- id: a\n  title: 'It''s: A'\n  patterns:\n    - 'x\\d+'\n  explanation: why: because\n").unwrap();
+        assert_eq!(rules[0].title, "It's: A");
+        assert_eq!(rules[0].patterns, vec!["x\\d+"]);
+        assert_eq!(rules[0].explanation, "why: because");
+        assert!(parse_knowledge_base("- id: a\n  color: red\n").unwrap_err().contains("第 2 行"));
+        assert!(parse_knowledge_base("- id: a\n  title: A\n  patterns:\n    - '('\n").is_err());
+    }
+
+    #[test]
+    fn test_explain_errors_with_user_rules() {
+        let entries = vec![
+            LogLine::new(1, "ERROR java.net.ConnectException: Connection refused (Connection refused)"),
+            LogLine::new(2, "INFO retrying"),
+            LogLine::new(3, "Caused by: javax.net.ssl.SSLHandshakeException: PKIX path building failed"),
+            LogLine::new(4, "redis: connect ECONNREFUSED 10.0.0.5:6379"),
+            LogLine::new(5, "ERROR ORDER-42 quota exceeded"),
+        ];
+        let explanations = explain_errors(&entries, &[]).unwrap();
+        let summary: Vec<(&str, usize)> = explanations.iter().map(|e| (e.rule_id.as_str(), e.count)).collect();
+        assert_eq!(summary, vec![("connection-refused", 2), ("tls-handshake", 1)]);
+        assert_eq!(explanations[0].entries.iter().map(|e| e.line_number).collect::<Vec<_>>(), vec![1, 4]);
+
+        let user = ExplainRule {
+            id: "order-quota".to_string(),
+            title: "Order quota".to_string(),
+            patterns: vec![r"ORDER-\d+ quota exceeded".to_string()],
+            explanation: "Tenant hit its daily order quota.".to_string(),
+            next_steps: Vec::new(),
+        };
+        let explanations = explain_errors(&entries, &[user]).unwrap();
+        let quota = explanations.iter().find(|e| e.rule_id == "order-quota").unwrap();
+        assert!(quota.user_rule);
+        assert_eq!(quota.first_line, 5);
+    }
+}
@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+
+/// 导出文本编码
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextEncoding {
+    /// UTF-8（无BOM）
+    #[default]
+    Utf8,
+    /// 带BOM的UTF-8，Windows下的Excel/记事本可正确识别
+    Utf8Bom,
+    /// GBK（简体中文Windows的默认编码）
+    Gbk,
+}
+
+/// 换行符风格
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NewlineStyle {
+    /// `\n`（Linux/macOS）
+    #[default]
+    Lf,
+    /// `\r\n`（Windows）
+    Crlf,
+}
+
+/// UTF-8 BOM
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+
+/// 统一文本的换行符
+///
+/// 先把 `\r\n` 归一为 `\n`，再按需要转换为 `\r\n`。
+pub fn normalize_newlines(text: &str, newline: NewlineStyle) -> String {
+    let unified = text.replace("\r\n", "\n");
+    match newline {
+        NewlineStyle::Lf => unified,
+        NewlineStyle::Crlf => unified.replace('\n', "\r\n"),
+    }
+}
+
+/// 按指定编码编码文本
+///
+/// # Returns
+/// - `(Vec<u8>, bool)`: 编码后的字节，以及是否有字符无法用目标编码表示
+///   （GBK下这些字符会被替换为 `&#NNNN;` 形式的数字字符引用）
+pub fn encode_text(text: &str, encoding: TextEncoding) -> (Vec<u8>, bool) {
+    match encoding {
+        TextEncoding::Utf8 => (text.as_bytes().to_vec(), false),
+        TextEncoding::Utf8Bom => {
+            let mut bytes = Vec::with_capacity(UTF8_BOM.len() + text.len());
+            bytes.extend_from_slice(UTF8_BOM);
+            bytes.extend_from_slice(text.as_bytes());
+            (bytes, false)
+        }
+        TextEncoding::Gbk => {
+            let (bytes, _, had_unmappable) = encoding_rs::GBK.encode(text);
+            (bytes.into_owned(), had_unmappable)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_text() {
+        assert_eq!(encode_text("日志", TextEncoding::Utf8Bom).0, [UTF8_BOM, "日志".as_bytes()].concat());
+
+        let (gbk, lossy) = encode_text("日志", TextEncoding::Gbk);
+        assert_eq!(gbk, vec![0xC8, 0xD5, 0xD6, 0xBE]);
+        assert!(!lossy);
+        assert!(encode_text("😀", TextEncoding::Gbk).1);
+    }
+
+    #[test]
+    fn test_normalize_newlines() {
+        assert_eq!(normalize_newlines("a\r\nb\nc", NewlineStyle::Crlf), "a\r\nb\r\nc");
+        assert_eq!(normalize_newlines("a\r\nb\nc", NewlineStyle::Lf), "a\nb\nc");
+    }
+}
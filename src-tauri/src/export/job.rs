@@ -0,0 +1,520 @@
+/// 可续传的大文件导出任务
+///
+/// 导出数百万条筛选后的条目时，按固定条目数分块编码并追加写入 `<目标文件>.partial`，
+/// 每写完一块就更新任务清单（应用数据目录下 `export-jobs/<任务ID>.json`），
+/// 清单记录每块的条目范围、字节偏移和CRC32校验和。
+/// 应用重启后续传时先校验已写入的块，从第一个缺失或损坏的块继续；
+/// 全部完成后把临时文件重命名为目标文件，并在目标文件旁写入 `<目标文件>.manifest.json`。
+
+use super::{export_entries, ExportOptions};
+use super::encoding::TextEncoding;
+use crate::i18n::Message;
+use crate::models::LogLine;
+use crate::plugins::formatter::RendererRegistry;
+use crate::search::EntryFilter;
+use crate::write_guard::atomic_write;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// 任务清单格式版本
+pub const MANIFEST_VERSION: u32 = 1;
+
+/// 每块的条目数
+pub const DEFAULT_CHUNK_ENTRIES: usize = 50_000;
+
+/// 导出任务状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportJobStatus {
+    /// 正在写入
+    Running,
+    /// 上次运行中断（应用退出或崩溃），可以续传
+    Interrupted,
+    /// 写入失败，可以续传
+    Failed,
+    /// 已完成
+    Completed,
+}
+
+/// 已写入的一块
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportChunk {
+    /// 第一个条目在筛选结果中的下标
+    pub first_entry: usize,
+    /// 条目数
+    pub entries: usize,
+    /// 在输出文件中的字节偏移
+    pub offset: u64,
+    /// 字节数
+    pub bytes: u64,
+    /// CRC32校验和（8位十六进制）
+    pub crc32: String,
+}
+
+/// 导出任务清单
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportManifest {
+    /// 清单格式版本
+    pub version: u32,
+    /// 任务ID
+    pub job_id: String,
+    /// 日志文件路径
+    pub source: String,
+    /// 创建任务时日志文件的大小和修改时间（续传时校验，虚拟路径为None）
+    #[serde(default)]
+    pub source_fingerprint: Option<(u64, u64)>,
+    /// 筛选条件
+    #[serde(default)]
+    pub filter: Option<EntryFilter>,
+    /// 导出选项
+    pub options: ExportOptions,
+    /// 目标文件路径
+    pub target: String,
+    /// 筛选后的条目总数
+    pub total_entries: usize,
+    /// 每块的条目数
+    pub chunk_entries: usize,
+    /// 已写入的块
+    pub chunks: Vec<ExportChunk>,
+    /// 任务状态
+    pub status: ExportJobStatus,
+    /// 失败时的错误信息
+    #[serde(default)]
+    pub error: Option<String>,
+    /// 完成后整个文件的字节数和CRC32
+    #[serde(default)]
+    pub bytes: Option<u64>,
+    #[serde(default)]
+    pub crc32: Option<String>,
+    /// 创建和最后更新时间（RFC 3339）
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// 导出进度（`export-progress` 事件的内容）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportProgress {
+    /// 任务ID
+    pub job_id: String,
+    /// 任务状态
+    pub status: ExportJobStatus,
+    /// 已写入的条目数
+    pub written_entries: usize,
+    /// 条目总数
+    pub total_entries: usize,
+    /// 已写入的字节数
+    pub bytes: u64,
+    /// 失败时的错误信息
+    pub error: Option<String>,
+}
+
+impl ExportManifest {
+    /// 创建新任务的清单
+    ///
+    /// # 参数
+    /// - `source`: 日志文件路径
+    /// - `filter`: 筛选条件
+    /// - `options`: 导出选项（JSON、HTML和按记录导出无法分块拼接，不支持）
+    /// - `target`: 目标文件路径（已通过写入守卫检查）
+    /// - `total_entries`: 筛选后的条目总数
+    pub fn new(
+        source: &str,
+        filter: Option<EntryFilter>,
+        options: ExportOptions,
+        target: &Path,
+        total_entries: usize,
+    ) -> Result<Self, String> {
+        check_chunkable(&options)?;
+        let now = chrono::Utc::now().to_rfc3339();
+        Ok(Self {
+            version: MANIFEST_VERSION,
+            job_id: uuid::Uuid::new_v4().simple().to_string(),
+            source: source.to_string(),
+            source_fingerprint: source_fingerprint(source),
+            filter,
+            options,
+            target: target.to_string_lossy().into_owned(),
+            total_entries,
+            chunk_entries: DEFAULT_CHUNK_ENTRIES,
+            chunks: Vec::new(),
+            status: ExportJobStatus::Running,
+            error: None,
+            bytes: None,
+            crc32: None,
+            created_at: now.clone(),
+            updated_at: now,
+        })
+    }
+
+    /// 写入中的临时文件路径
+    pub fn partial_path(&self) -> PathBuf {
+        PathBuf::from(format!("{}.partial", self.target))
+    }
+
+    /// 目标文件旁的清单路径
+    pub fn sidecar_path(&self) -> PathBuf {
+        PathBuf::from(format!("{}.manifest.json", self.target))
+    }
+
+    /// 已写入的条目数
+    pub fn written_entries(&self) -> usize {
+        self.chunks.iter().map(|chunk| chunk.entries).sum()
+    }
+
+    /// 已写入的字节数
+    pub fn written_bytes(&self) -> u64 {
+        self.chunks.last().map(|chunk| chunk.offset + chunk.bytes).unwrap_or(0)
+    }
+
+    /// 当前进度
+    pub fn progress(&self) -> ExportProgress {
+        ExportProgress {
+            job_id: self.job_id.clone(),
+            status: self.status,
+            written_entries: self.written_entries(),
+            total_entries: self.total_entries,
+            bytes: self.bytes.unwrap_or_else(|| self.written_bytes()),
+            error: self.error.clone(),
+        }
+    }
+
+    /// 校验日志文件自创建任务以来没有变化
+    pub fn check_source(&self) -> Result<(), String> {
+        if self.source_fingerprint.is_some() && source_fingerprint(&self.source) != self.source_fingerprint {
+            return Err(Message::new("export.source_changed").with("path", &self.source).text());
+        }
+        Ok(())
+    }
+}
+
+/// 检查导出选项能否分块追加写入
+fn check_chunkable(options: &ExportOptions) -> Result<(), String> {
+    let format = options.format.to_lowercase();
+    if options.records || format == "json" || format == "html" {
+        return Err(Message::new("export.job_unsupported_format").with("format", &options.format).text());
+    }
+    Ok(())
+}
+
+/// 日志文件的大小和修改时间（秒）
+fn source_fingerprint(path: &str) -> Option<(u64, u64)> {
+    let metadata = std::fs::metadata(path).ok()?;
+    let modified = metadata.modified().ok()?.duration_since(std::time::UNIX_EPOCH).ok()?.as_secs();
+    Some((metadata.len(), modified))
+}
+
+/// 第 `index` 块使用的导出选项：只有第一块输出CSV表头和UTF-8 BOM
+fn chunk_options(options: &ExportOptions, index: usize) -> ExportOptions {
+    let mut options = options.clone();
+    if index > 0 {
+        options.include_header = false;
+        if options.encoding == TextEncoding::Utf8Bom {
+            options.encoding = TextEncoding::Utf8;
+        }
+    }
+    options
+}
+
+/// 计算CRC32校验和（8位十六进制）
+pub fn crc32_hex(bytes: &[u8]) -> String {
+    let mut crc = flate2::Crc::new();
+    crc.update(bytes);
+    format!("{:08x}", crc.sum())
+}
+
+/// 校验临时文件中已写入的块
+///
+/// # Returns
+/// - `usize`: 从头开始连续校验通过的块数（文件不存在时为0）
+pub fn verify_chunks(manifest: &ExportManifest) -> usize {
+    let Ok(mut file) = File::open(manifest.partial_path()) else {
+        return 0;
+    };
+    let mut buffer = Vec::new();
+    for (index, chunk) in manifest.chunks.iter().enumerate() {
+        buffer.resize(chunk.bytes as usize, 0);
+        let valid = file.seek(SeekFrom::Start(chunk.offset)).is_ok()
+            && file.read_exact(&mut buffer).is_ok()
+            && crc32_hex(&buffer) == chunk.crc32;
+        if !valid {
+            log::warn!("⚠️ 导出任务 {} 的第 {} 块校验失败，从该块重新写入", manifest.job_id, index + 1);
+            return index;
+        }
+    }
+    manifest.chunks.len()
+}
+
+/// 计算整个文件的CRC32
+fn file_crc32(path: &Path) -> Result<String, String> {
+    let mut file = File::open(path).map_err(|e| Message::new("export.read_output_failed").with("error", e).text())?;
+    let mut crc = flate2::Crc::new();
+    let mut buffer = vec![0u8; 1 << 20];
+    loop {
+        let n = file.read(&mut buffer).map_err(|e| Message::new("export.read_output_failed").with("error", e).text())?;
+        if n == 0 {
+            break;
+        }
+        crc.update(&buffer[..n]);
+    }
+    Ok(format!("{:08x}", crc.sum()))
+}
+
+/// 导出任务清单存储，同时记录本次运行中正在执行的任务
+pub struct ExportJobStore {
+    dir: PathBuf,
+    running: Mutex<HashSet<String>>,
+}
+
+impl ExportJobStore {
+    /// 创建任务存储
+    ///
+    /// # 参数
+    /// - `dir`: 清单目录（不存在时在首次保存时创建）
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir, running: Mutex::new(HashSet::new()) }
+    }
+
+    /// 任务ID对应的清单路径
+    fn manifest_path(&self, job_id: &str) -> Result<PathBuf, String> {
+        if job_id.is_empty() || !job_id.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(Message::new("export.invalid_job_id").with("id", job_id).text());
+        }
+        Ok(self.dir.join(format!("{}.json", job_id)))
+    }
+
+    /// 保存清单（更新时间）
+    pub fn save(&self, manifest: &mut ExportManifest) -> Result<(), String> {
+        manifest.updated_at = chrono::Utc::now().to_rfc3339();
+        let json = serde_json::to_string_pretty(manifest)
+            .map_err(|e| Message::new("export.serialize_job_failed").with("error", e).text())?;
+        atomic_write(self.manifest_path(&manifest.job_id)?, json.as_bytes())
+    }
+
+    /// 加载清单；记录为运行中但本次运行中没有执行的任务标记为中断
+    pub fn load(&self, job_id: &str) -> Result<ExportManifest, String> {
+        let path = self.manifest_path(job_id)?;
+        let text = std::fs::read_to_string(&path)
+            .map_err(|e| Message::new("export.read_job_failed").with("path", path.display()).with("error", e).text())?;
+        let mut manifest: ExportManifest = serde_json::from_str(&text)
+            .map_err(|e| Message::new("export.invalid_manifest").with("error", e).text())?;
+        if manifest.version > MANIFEST_VERSION {
+            return Err(Message::new("export.manifest_too_new").with("version", manifest.version).text());
+        }
+        if manifest.status == ExportJobStatus::Running && !self.is_running(job_id) {
+            manifest.status = ExportJobStatus::Interrupted;
+        }
+        Ok(manifest)
+    }
+
+    /// 列出所有任务（按更新时间倒序）
+    pub fn list(&self) -> Result<Vec<ExportManifest>, String> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(Message::new("export.read_job_directory_failed").with("error", e).text()),
+        };
+        let mut jobs: Vec<ExportManifest> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|path| {
+                let job_id = path.file_stem()?.to_string_lossy().into_owned();
+                self.load(&job_id)
+                    .map_err(|e| log::warn!("⚠️ 跳过无效的导出任务 {}: {}", path.display(), e))
+                    .ok()
+            })
+            .collect();
+        jobs.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+        Ok(jobs)
+    }
+
+    /// 删除任务清单和未完成的临时文件（已完成的导出文件保留）
+    pub fn discard(&self, job_id: &str) -> Result<(), String> {
+        if self.is_running(job_id) {
+            return Err(Message::new("export.delete_running_job").text());
+        }
+        let manifest = self.load(job_id)?;
+        std::fs::remove_file(manifest.partial_path()).ok();
+        std::fs::remove_file(self.manifest_path(job_id)?).map_err(|e| Message::new("export.delete_job_failed").with("error", e).text())
+    }
+
+    fn is_running(&self, job_id: &str) -> bool {
+        self.running.lock().map(|running| running.contains(job_id)).unwrap_or(false)
+    }
+
+    /// 标记任务开始执行
+    ///
+    /// # Returns
+    /// - `Err(String)`: 任务已在运行时的错误信息
+    pub fn begin(&self, job_id: &str) -> Result<(), String> {
+        let mut running = self.running.lock().map_err(|_| Message::new("export.lock_poisoned").text())?;
+        if !running.insert(job_id.to_string()) {
+            return Err(Message::new("export.job_running").text());
+        }
+        Ok(())
+    }
+
+    /// 标记任务结束执行
+    pub fn finish(&self, job_id: &str) {
+        if let Ok(mut running) = self.running.lock() {
+            running.remove(job_id);
+        }
+    }
+}
+
+/// 执行（或续传）导出任务
+///
+/// 先校验已写入的块并截断其后的内容，然后逐块编码、追加写入并同步到磁盘，
+/// 每块写完后保存清单并回调进度。失败时清单状态为 `failed`，之后可以续传。
+///
+/// # 参数
+/// - `manifest`: 任务清单
+/// - `entries`: 筛选后的条目（必须与创建任务时一致）
+/// - `renderers`: 渲染器注册表
+/// - `store`: 任务存储
+/// - `on_progress`: 每块写完及任务结束时的进度回调
+///
+/// # Returns
+/// - `Ok(ExportManifest)`: 已完成的清单
+/// - `Err(String)`: 条目数不一致或写入失败时的错误信息
+pub fn run_export_job<F>(
+    mut manifest: ExportManifest,
+    entries: &[LogLine],
+    renderers: &RendererRegistry,
+    store: &ExportJobStore,
+    mut on_progress: F,
+) -> Result<ExportManifest, String>
+where
+    F: FnMut(&ExportProgress),
+{
+    manifest.status = ExportJobStatus::Running;
+    manifest.error = None;
+    let result = write_chunks(&mut manifest, entries, renderers, store, &mut on_progress);
+    if let Err(e) = &result {
+        log::error!("❌ 导出任务 {} 失败: {}", manifest.job_id, e);
+        manifest.status = ExportJobStatus::Failed;
+        manifest.error = Some(e.clone());
+        store.save(&mut manifest).ok();
+    }
+    on_progress(&manifest.progress());
+    result.map(|_| manifest)
+}
+
+fn write_chunks<F>(
+    manifest: &mut ExportManifest,
+    entries: &[LogLine],
+    renderers: &RendererRegistry,
+    store: &ExportJobStore,
+    on_progress: &mut F,
+) -> Result<(), String>
+where
+    F: FnMut(&ExportProgress),
+{
+    if entries.len() != manifest.total_entries {
+        return Err(Message::new("export.entry_count_mismatch")
+            .with("actual", entries.len())
+            .with("expected", manifest.total_entries)
+            .text());
+    }
+    let partial = manifest.partial_path();
+    if let Some(parent) = partial.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| Message::new("write.create_directory_failed").with("error", e).text())?;
+    }
+
+    let valid = verify_chunks(manifest);
+    manifest.chunks.truncate(valid);
+    let mut file = OpenOptions::new().create(true).truncate(false).write(true).open(&partial)
+        .map_err(|e| Message::new("export.open_output_failed").with("error", e).text())?;
+    let mut offset = manifest.written_bytes();
+    file.set_len(offset).map_err(|e| Message::new("export.truncate_output_failed").with("error", e).text())?;
+    file.seek(SeekFrom::Start(offset)).map_err(|e| Message::new("export.write_output_failed").with("error", e).text())?;
+    store.save(manifest)?;
+    if valid > 0 {
+        log::info!("🔁 续传导出任务 {}: 已校验 {} 块 ({} 条)", manifest.job_id, valid, manifest.written_entries());
+    }
+
+    let chunk_entries = manifest.chunk_entries.max(1);
+    while manifest.written_entries() < entries.len() {
+        let index = manifest.chunks.len();
+        let first = manifest.written_entries();
+        let chunk = &entries[first..(first + chunk_entries).min(entries.len())];
+        let output = export_entries(chunk, &chunk_options(&manifest.options, index), renderers)?;
+
+        file.write_all(&output.bytes).map_err(|e| Message::new("export.write_output_failed").with("error", e).text())?;
+        file.sync_data().map_err(|e| Message::new("export.sync_output_failed").with("error", e).text())?;
+        manifest.chunks.push(ExportChunk {
+            first_entry: first,
+            entries: chunk.len(),
+            offset,
+            bytes: output.bytes.len() as u64,
+            crc32: crc32_hex(&output.bytes),
+        });
+        offset += output.bytes.len() as u64;
+        store.save(manifest)?;
+        on_progress(&manifest.progress());
+    }
+    drop(file);
+
+    manifest.bytes = Some(offset);
+    manifest.crc32 = Some(file_crc32(&partial)?);
+    std::fs::rename(&partial, &manifest.target).map_err(|e| Message::new("write.replace_failed").with("error", e).text())?;
+    manifest.status = ExportJobStatus::Completed;
+    store.save(manifest)?;
+    let json = serde_json::to_string_pretty(manifest).map_err(|e| Message::new("export.serialize_job_failed").with("error", e).text())?;
+    atomic_write(manifest.sidecar_path(), json.as_bytes())?;
+    log::info!("✅ 导出任务 {} 完成: {} 条, {} 字节", manifest.job_id, entries.len(), offset);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resume_rewrites_from_corrupted_chunk() {
+        let entries: Vec<LogLine> = (1..=10)
+            .map(|i| LogLine {
+                level: Some("INFO".to_string()),
+                ..LogLine::new(i, format!("line {}", i))
+            })
+            .collect();
+        let dir = std::env::temp_dir().join(format!("lw-export-job-{}", uuid::Uuid::new_v4()));
+        let store = ExportJobStore::new(dir.join("jobs"));
+        let options = ExportOptions { format: "csv".to_string(), ..ExportOptions::default() };
+        let renderers = RendererRegistry::new();
+        let full = export_entries(&entries, &options, &renderers).unwrap().bytes;
+
+        let mut manifest = ExportManifest::new("/nonexistent.log", None, options, &dir.join("out.csv"), entries.len()).unwrap();
+        manifest.chunk_entries = 3;
+        let job_id = manifest.job_id.clone();
+        // 条目数与清单不一致时拒绝写入
+        let done = run_export_job(manifest.clone(), &entries[..6], &renderers, &store, |_| {});
+        assert!(done.is_err());
+        manifest.total_entries = 6;
+        let partial = run_export_job(manifest, &entries[..6], &renderers, &store, |_| {});
+        assert!(partial.is_ok());
+
+        // 模拟写完前两块后中断，且第二块被损坏
+        let mut manifest = store.load(&job_id).unwrap();
+        std::fs::rename(&manifest.target, manifest.partial_path()).unwrap();
+        let mut bytes = std::fs::read(manifest.partial_path()).unwrap();
+        *bytes.last_mut().unwrap() = b'x';
+        std::fs::write(manifest.partial_path(), bytes).unwrap();
+        assert_eq!(verify_chunks(&manifest), 1);
+
+        manifest.total_entries = entries.len();
+        let mut events = 0;
+        let done = run_export_job(manifest, &entries, &renderers, &store, |_| events += 1).unwrap();
+        assert_eq!(done.status, ExportJobStatus::Completed);
+        assert_eq!(done.chunks.len(), 4);
+        assert_eq!(events, 4);
+        assert_eq!(std::fs::read(&done.target).unwrap(), full);
+        assert_eq!(done.crc32.as_deref(), Some(crc32_hex(&full).as_str()));
+        assert!(Path::new(&done.target).with_extension("csv.manifest.json").exists());
+        assert!(ExportManifest::new("a", None, ExportOptions { format: "json".to_string(), ..ExportOptions::default() }, Path::new("/x"), 0).is_err());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
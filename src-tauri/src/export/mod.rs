@@ -0,0 +1,267 @@
+/// 日志导出
+///
+/// 将解析后的日志条目导出为文本（复用输出渲染器）、CSV或JSON，
+/// 并按选项转换换行符和编码，便于在Windows/Excel中直接打开。
+/// CSV和JSON可以按多行记录导出，一条异常及其堆栈占一行/一个对象。
+/// 按纯文本风格导出时去掉格式化内容中的emoji图标。
+/// 也可以按级别或元数据键拆分，每组写入一个文件（见 `split`）。
+/// 大量条目可以作为后台任务分块写入，中断后校验已写入的块并续传（见 `job`）。
+
+pub mod encoding;  // 文本编码与换行符转换
+pub mod snippet;  // 选中日志行的分享片段
+pub mod report;  // 调查报告生成
+pub mod split;  // 按键拆分导出
+pub mod job;  // 可续传的大文件导出任务
+
+use crate::i18n::Message;
+use crate::models::{LogLine, LogRecord};
+use crate::plugins::formatter::{FormattingProfile, RendererRegistry};
+use crate::records::group_records;
+use encoding::{encode_text, normalize_newlines, NewlineStyle, TextEncoding};
+use serde::{Deserialize, Serialize};
+
+/// 导出选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportOptions {
+    /// 导出格式："csv"、"json"，或渲染器名称（plain/ansi/html/markdown）
+    #[serde(default = "default_format")]
+    pub format: String,
+
+    /// 文本编码
+    #[serde(default)]
+    pub encoding: TextEncoding,
+
+    /// 换行符风格
+    #[serde(default)]
+    pub newline: NewlineStyle,
+
+    /// CSV分隔符（默认逗号，Excel欧洲区域设置常用分号，也可使用制表符）
+    #[serde(default = "default_csv_delimiter")]
+    pub csv_delimiter: char,
+
+    /// CSV是否输出表头
+    #[serde(default = "default_true")]
+    pub include_header: bool,
+
+    /// 按多行记录导出（仅CSV/JSON；文本格式始终逐行输出）
+    #[serde(default)]
+    pub records: bool,
+
+    /// 格式化风格（未设置时使用解析配置）
+    #[serde(default)]
+    pub formatting: Option<FormattingProfile>,
+}
+
+fn default_format() -> String {
+    "plain".to_string()
+}
+
+fn default_csv_delimiter() -> char {
+    ','
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            format: default_format(),
+            encoding: TextEncoding::default(),
+            newline: NewlineStyle::default(),
+            csv_delimiter: default_csv_delimiter(),
+            include_header: true,
+            records: false,
+            formatting: None,
+        }
+    }
+}
+
+/// 导出结果
+#[derive(Debug, Clone)]
+pub struct ExportOutput {
+    /// 编码后的文件内容
+    pub bytes: Vec<u8>,
+    /// 是否有字符无法用目标编码表示
+    pub lossy: bool,
+}
+
+/// 导出日志条目
+///
+/// # 参数
+/// - `entries`: 要导出的日志条目
+/// - `options`: 导出选项
+/// - `renderers`: 渲染器注册表，文本格式通过渲染器生成
+///
+/// # Returns
+/// - `Ok(ExportOutput)`: 编码后的导出内容
+/// - `Err(String)`: 格式不存在或分隔符无效时的错误信息
+pub fn export_entries(entries: &[LogLine], options: &ExportOptions, renderers: &RendererRegistry) -> Result<ExportOutput, String> {
+    let plain_entries: Vec<LogLine>;
+    let entries = if options.formatting.unwrap_or_default().is_plain() {
+        plain_entries = entries.iter()
+            .map(|entry| LogLine {
+                formatted_content: entry.formatted_content.as_deref().map(FormattingProfile::strip_decorations),
+                ..entry.clone()
+            })
+            .collect();
+        &plain_entries
+    } else {
+        entries
+    };
+
+    let text = match options.format.to_lowercase().as_str() {
+        "csv" if options.records => records_to_csv(&group_records(entries), options)?,
+        "csv" => to_csv(entries, options)?,
+        "json" if options.records => serde_json::to_string_pretty(&group_records(entries))
+            .map_err(|e| Message::new("export.json_failed").with("error", e).text())?,
+        "json" => serde_json::to_string_pretty(entries)
+            .map_err(|e| Message::new("export.json_failed").with("error", e).text())?,
+        name => {
+            let renderer = renderers.get(name)?;
+            let lines: Vec<String> = entries.iter().map(|line| renderer.render_line(line)).collect();
+            renderer.render_document(&lines)
+        }
+    };
+
+    let mut text = normalize_newlines(&text, options.newline);
+    if !text.is_empty() {
+        text.push_str(match options.newline {
+            NewlineStyle::Lf => "\n",
+            NewlineStyle::Crlf => "\r\n",
+        });
+    }
+
+    let (bytes, lossy) = encode_text(&text, options.encoding);
+    if lossy {
+        log::warn!("⚠️ 部分字符无法用 {:?} 编码表示，已替换为字符引用", options.encoding);
+    }
+    Ok(ExportOutput { bytes, lossy })
+}
+
+/// 生成CSV：行号、时间戳、级别、内容
+fn to_csv(entries: &[LogLine], options: &ExportOptions) -> Result<String, String> {
+    let rows = entries.iter().map(|entry| vec![
+        entry.line_number.to_string(),
+        entry.timestamp.clone().unwrap_or_default(),
+        entry.level.clone().unwrap_or_default(),
+        entry.formatted_content.clone().unwrap_or_else(|| entry.content.clone()),
+    ]);
+    csv_document(&["line_number", "timestamp", "level", "content"], rows, options)
+}
+
+/// 按记录生成CSV：起止行号、时间戳、级别、内容（多行内容在同一字段中）
+fn records_to_csv(records: &[LogRecord], options: &ExportOptions) -> Result<String, String> {
+    let rows = records.iter().map(|record| vec![
+        record.start_line.to_string(),
+        record.end_line.to_string(),
+        record.timestamp.clone().unwrap_or_default(),
+        record.level.clone().unwrap_or_default(),
+        record.formatted_content.clone(),
+    ]);
+    csv_document(&["start_line", "end_line", "timestamp", "level", "content"], rows, options)
+}
+
+/// 拼接CSV表头和数据行
+fn csv_document<I>(header: &[&str], rows: I, options: &ExportOptions) -> Result<String, String>
+where
+    I: Iterator<Item = Vec<String>>,
+{
+    let delimiter = options.csv_delimiter;
+    if matches!(delimiter, '"' | '\r' | '\n') {
+        return Err(Message::new("export.invalid_delimiter").with("delimiter", format!("{:?}", delimiter)).text());
+    }
+
+    let mut lines = Vec::new();
+    if options.include_header {
+        lines.push(header.join(&delimiter.to_string()));
+    }
+    for row in rows {
+        let fields: Vec<String> = row.iter().map(|f| csv_field(f, delimiter)).collect();
+        lines.push(fields.join(&delimiter.to_string()));
+    }
+    Ok(lines.join("\n"))
+}
+
+/// 按RFC 4180转义CSV字段
+fn csv_field(value: &str, delimiter: char) -> String {
+    if value.contains(delimiter) || value.contains(['"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(line_number: usize, content: &str) -> LogLine {
+        LogLine {
+            level: Some("ERROR".to_string()),
+            timestamp: Some("2024-01-01 00:00:00".to_string()),
+            ..LogLine::new(line_number, content)
+        }
+    }
+
+    #[test]
+    fn test_csv_export_with_delimiter_and_crlf() {
+        let options = ExportOptions {
+            format: "csv".to_string(),
+            newline: NewlineStyle::Crlf,
+            csv_delimiter: ';',
+            ..Default::default()
+        };
+        let entries = vec![entry(1, "失败; 重试"), entry(2, "say \"hi\"")];
+        let output = export_entries(&entries, &options, &RendererRegistry::new()).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output.bytes).unwrap(),
+            "line_number;timestamp;level;content\r\n\
+             1;2024-01-01 00:00:00;ERROR;\"失败; 重试\"\r\n\
+             2;2024-01-01 00:00:00;ERROR;\"say \"\"hi\"\"\"\r\n"
+        );
+    }
+
+    #[test]
+    fn test_csv_export_by_records() {
+        let options = ExportOptions {
+            format: "csv".to_string(),
+            records: true,
+            ..Default::default()
+        };
+        let mut trace = entry(2, "\tat com.example.App.main(App.java:10)");
+        trace.timestamp = None;
+        trace.level = None;
+        let entries = vec![entry(1, "boom"), trace, entry(3, "next")];
+        let output = export_entries(&entries, &options, &RendererRegistry::new()).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output.bytes).unwrap(),
+            "start_line,end_line,timestamp,level,content\n\
+             1,2,2024-01-01 00:00:00,ERROR,\"boom\n\tat com.example.App.main(App.java:10)\"\n\
+             3,3,2024-01-01 00:00:00,ERROR,next\n"
+        );
+    }
+
+    #[test]
+    fn test_plain_export_in_gbk() {
+        let options = ExportOptions {
+            encoding: TextEncoding::Gbk,
+            ..Default::default()
+        };
+        let output = export_entries(&[entry(1, "日志")], &options, &RendererRegistry::new()).unwrap();
+        assert_eq!(output.bytes, vec![0xC8, 0xD5, 0xD6, 0xBE, b'\n']);
+        assert!(!output.lossy);
+
+        let mut decorated = entry(2, "select 1");
+        decorated.formatted_content = Some("📝 SQL: select 1".to_string());
+        let plain = ExportOptions { formatting: Some(FormattingProfile::Plain), ..Default::default() };
+        let output = export_entries(&[decorated], &plain, &RendererRegistry::new()).unwrap();
+        assert_eq!(output.bytes, b"SQL: select 1\n");
+
+        let unknown = ExportOptions { format: "pdf".to_string(), ..Default::default() };
+        assert!(export_entries(&[], &unknown, &RendererRegistry::new()).is_err());
+    }
+}
@@ -0,0 +1,417 @@
+/// 调查报告
+///
+/// 把一次排查的分析结果（概要、错误聚类、时间线、异常区段、书签和备注）填入模板，
+/// 生成Markdown或HTML格式的事故复盘草稿。
+///
+/// 模板中的 `{{占位符}}` 替换为按报告格式渲染（已转义）的内容块：
+/// `title`、`source`、`generated_at`、`summary`、`error_clusters`、`timeline`、`anomalies`、`bookmarks`。
+/// 未提供模板时使用内置模板；模板中有未知的占位符时报错，避免拼写错误被静默忽略。
+
+use crate::config::PinnedEntry;
+use crate::i18n::{current_locale, Locale, Message};
+use crate::models::LogLine;
+use crate::outline::{build_outline, is_error, OutlineSection};
+use crate::plugins::formatter::{escape_html, escape_markdown, UnifiedFormatter};
+use crate::utils::string_utils::truncate_chars;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// 模板占位符
+static PLACEHOLDER: Lazy<Regex> = Lazy::new(|| Regex::new(r"\{\{\s*([A-Za-z_]+)\s*\}\}").unwrap());
+
+/// 错误聚类时替换为占位的可变部分（UUID、十六进制、数字）
+static VARIABLE_PARTS: Lazy<Vec<(Regex, &'static str)>> = Lazy::new(|| {
+    [
+        (r"\b[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}\b", "<uuid>"),
+        (r"\b0x[0-9a-fA-F]+\b", "<hex>"),
+        (r"\d+", "<n>"),
+    ]
+    .into_iter()
+    .map(|(pattern, replacement)| (Regex::new(pattern).unwrap(), replacement))
+    .collect()
+});
+
+/// 报告选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportOptions {
+    /// 报告格式："markdown" 或 "html"
+    #[serde(default = "default_format")]
+    pub format: String,
+
+    /// 报告标题（默认"调查报告: <文件名>"）
+    #[serde(default)]
+    pub title: Option<String>,
+
+    /// 自定义模板（默认使用内置模板）
+    #[serde(default)]
+    pub template: Option<String>,
+
+    /// 错误聚类最多列出的条数
+    #[serde(default = "default_max_clusters")]
+    pub max_clusters: usize,
+}
+
+fn default_format() -> String {
+    "markdown".to_string()
+}
+
+fn default_max_clusters() -> usize {
+    10
+}
+
+impl Default for ReportOptions {
+    fn default() -> Self {
+        Self {
+            format: default_format(),
+            title: None,
+            template: None,
+            max_clusters: default_max_clusters(),
+        }
+    }
+}
+
+/// 报告格式
+#[derive(Clone, Copy, PartialEq)]
+enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+impl ReportFormat {
+    fn escape(self, text: &str) -> String {
+        match self {
+            Self::Markdown => escape_markdown(text),
+            Self::Html => escape_html(text),
+        }
+    }
+
+    /// 渲染表格（表头和单元格均为未转义的文本）
+    fn table(self, headers: &[String], rows: &[Vec<String>]) -> String {
+        match self {
+            Self::Markdown => {
+                let mut out = vec![
+                    format!("| {} |", headers.join(" | ")),
+                    format!("|{}", "---|".repeat(headers.len())),
+                ];
+                out.extend(rows.iter().map(|row| {
+                    let cells: Vec<String> = row.iter().map(|cell| self.escape(cell)).collect();
+                    format!("| {} |", cells.join(" | "))
+                }));
+                out.join("\n")
+            }
+            Self::Html => {
+                let head: String = headers.iter().map(|h| format!("<th>{}</th>", escape_html(h))).collect();
+                let body: String = rows.iter()
+                    .map(|row| format!("<tr>{}</tr>", row.iter().map(|cell| format!("<td>{}</td>", escape_html(cell))).collect::<String>()))
+                    .collect();
+                format!("<table><tr>{}</tr>{}</table>", head, body)
+            }
+        }
+    }
+
+    /// 渲染列表（每项为已转义的文本）
+    fn list(self, items: &[String]) -> String {
+        match self {
+            Self::Markdown => items.iter().map(|item| format!("- {}", item)).collect::<Vec<_>>().join("\n"),
+            Self::Html => format!("<ul>{}</ul>", items.iter().map(|item| format!("<li>{}</li>", item)).collect::<String>()),
+        }
+    }
+
+    fn code(self, text: &str) -> String {
+        match self {
+            Self::Markdown => format!("`{}`", text.replace('`', "'")),
+            Self::Html => format!("<code>{}</code>", escape_html(text)),
+        }
+    }
+
+    fn none(self) -> String {
+        match self {
+            Self::Markdown => format!("_{}_", label("report.none")),
+            Self::Html => format!("<p class=\"none\">{}</p>", escape_html(&label("report.none"))),
+        }
+    }
+}
+
+/// 报告文本使用的语言（`code` 模式下使用中文，报告是给人看的文档）
+fn label_locale() -> Locale {
+    match current_locale() {
+        Locale::Code => Locale::ZhCn,
+        locale => locale,
+    }
+}
+
+fn label(code: &str) -> String {
+    Message::new(code).localize(label_locale())
+}
+
+/// 一类错误（消息中的数字、ID等可变部分归一后相同）
+struct ErrorCluster {
+    signature: String,
+    count: usize,
+    first_line: usize,
+    last_line: usize,
+}
+
+/// 错误消息的归一化签名（取第一行，可变部分替换为占位）
+fn error_signature(entry: &LogLine) -> String {
+    let first_line = entry.message_text().lines().next().unwrap_or("").trim();
+    let mut signature = first_line.to_string();
+    for (pattern, replacement) in VARIABLE_PARTS.iter() {
+        signature = pattern.replace_all(&signature, *replacement).into_owned();
+    }
+    truncate_chars(&signature, 160)
+}
+
+/// 按签名聚类错误条目（按次数降序，相同时按首次出现的行号）
+fn error_clusters(entries: &[LogLine]) -> Vec<ErrorCluster> {
+    let mut clusters: Vec<ErrorCluster> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for entry in entries.iter().filter(|entry| is_error(entry)) {
+        let signature = error_signature(entry);
+        match index.get(&signature) {
+            Some(&i) => {
+                clusters[i].count += 1;
+                clusters[i].last_line = entry.line_number;
+            }
+            None => {
+                index.insert(signature.clone(), clusters.len());
+                clusters.push(ErrorCluster { signature, count: 1, first_line: entry.line_number, last_line: entry.line_number });
+            }
+        }
+    }
+    clusters.sort_by(|a, b| b.count.cmp(&a.count).then(a.first_line.cmp(&b.first_line)));
+    clusters
+}
+
+fn line_span(first: usize, last: usize) -> String {
+    if first == last { first.to_string() } else { format!("{}-{}", first, last) }
+}
+
+fn render_summary(entries: &[LogLine], source: &str, format: ReportFormat) -> String {
+    let mut levels: BTreeMap<String, usize> = BTreeMap::new();
+    for entry in entries {
+        if let Some(level) = UnifiedFormatter::normalize_level(entry.level.clone()) {
+            *levels.entry(level).or_default() += 1;
+        }
+    }
+    let levels = if levels.is_empty() {
+        "-".to_string()
+    } else {
+        levels.iter().map(|(level, count)| format!("{}×{}", level, count)).collect::<Vec<_>>().join(", ")
+    };
+
+    let timestamps: Vec<&str> = entries.iter().filter_map(|entry| entry.timestamp.as_deref()).collect();
+    let time_range = match (timestamps.first(), timestamps.last()) {
+        (Some(first), Some(last)) if first != last => format!("{} → {}", first, last),
+        (Some(first), _) => first.to_string(),
+        _ => "-".to_string(),
+    };
+
+    format.table(
+        &[label("snippet.source"), label("report.entries"), label("snippet.levels"), label("snippet.time_range")],
+        &[vec![source.to_string(), entries.len().to_string(), levels, time_range]],
+    )
+}
+
+fn render_clusters(entries: &[LogLine], max_clusters: usize, format: ReportFormat) -> String {
+    let clusters = error_clusters(entries);
+    if clusters.is_empty() {
+        return format.none();
+    }
+    let rows: Vec<Vec<String>> = clusters.iter()
+        .take(max_clusters)
+        .map(|cluster| vec![cluster.count.to_string(), line_span(cluster.first_line, cluster.last_line), cluster.signature.clone()])
+        .collect();
+    format.table(&[label("report.count"), label("snippet.lines"), label("report.message")], &rows)
+}
+
+fn render_sections(sections: &[&OutlineSection], format: ReportFormat) -> String {
+    if sections.is_empty() {
+        return format.none();
+    }
+    let items: Vec<String> = sections.iter()
+        .map(|section| {
+            let mut item = format!("{} {}", label("snippet.lines"), line_span(section.line_number, section.end_line));
+            if let Some(ts) = &section.timestamp {
+                item.push(' ');
+                item.push_str(&format.code(ts));
+            }
+            let title = if section.error_count > 0 {
+                Message::new("report.error_burst").with("count", section.error_count).localize(label_locale())
+            } else {
+                section.title.clone()
+            };
+            format!("{} {} — {}", item, format.escape(&title), format.escape(&section.preview))
+        })
+        .collect();
+    format.list(&items)
+}
+
+fn render_bookmarks(bookmarks: &[PinnedEntry], format: ReportFormat) -> String {
+    if bookmarks.is_empty() {
+        return format.none();
+    }
+    let items: Vec<String> = bookmarks.iter()
+        .map(|pin| {
+            let mut item = format!("{} {}", label("snippet.lines"), pin.line_number);
+            if let Some(level) = &pin.level {
+                item.push_str(&format!(" [{}]", format.escape(level)));
+            }
+            item.push(' ');
+            item.push_str(&format.escape(&pin.preview));
+            if let Some(note) = pin.note.as_deref().filter(|note| !note.trim().is_empty()) {
+                item.push_str(&format!(" — {}: {}", format.escape(&label("report.note")), format.escape(note)));
+            }
+            item
+        })
+        .collect();
+    format.list(&items)
+}
+
+/// 内置模板
+fn default_template(format: ReportFormat) -> String {
+    let headings = ["report.summary", "report.error_clusters", "report.timeline", "report.anomalies", "report.bookmarks"];
+    let blocks = ["summary", "error_clusters", "timeline", "anomalies", "bookmarks"];
+    match format {
+        ReportFormat::Markdown => {
+            let mut out = vec![
+                "# {{title}}".to_string(),
+                String::new(),
+                format!("{}: {{{{generated_at}}}}", escape_markdown(&label("report.generated_at"))),
+            ];
+            for (heading, block) in headings.iter().zip(blocks) {
+                out.push(String::new());
+                out.push(format!("## {}", escape_markdown(&label(heading))));
+                out.push(String::new());
+                out.push(format!("{{{{{}}}}}", block));
+            }
+            out.push(String::new());
+            out.join("\n")
+        }
+        ReportFormat::Html => {
+            let sections: String = headings.iter().zip(blocks)
+                .map(|(heading, block)| format!("<h2>{}</h2>\n{{{{{}}}}}\n", escape_html(&label(heading)), block))
+                .collect();
+            format!(
+                r#"<!DOCTYPE html>
+<html><head><meta charset="utf-8"><title>{{{{title}}}}</title>
+<style>
+body{{font-family:-apple-system,"Segoe UI",sans-serif;margin:24px;color:#24292f;max-width:1100px}}
+table{{border-collapse:collapse;font-size:13px}}
+th,td{{border:1px solid #d0d7de;padding:4px 8px;text-align:left;vertical-align:top}}
+code{{font:12px ui-monospace,Menlo,Consolas,monospace;color:#0550ae}}
+p.meta,p.none{{color:#6e7781}}
+</style></head>
+<body>
+<h1>{{{{title}}}}</h1>
+<p class="meta">{}: {{{{generated_at}}}}</p>
+{}</body></html>
+"#,
+                escape_html(&label("report.generated_at")),
+                sections
+            )
+        }
+    }
+}
+
+/// 生成调查报告
+///
+/// # 参数
+/// - `entries`: 文件的全部日志条目（按行号排序）
+/// - `bookmarks`: 固定的条目（书签）和备注
+/// - `source`: 来源名称（通常为文件名）
+/// - `options`: 报告格式、标题、模板和错误聚类条数
+///
+/// # Returns
+/// - `Ok(String)`: Markdown或HTML报告
+/// - `Err(String)`: 格式不支持或模板中有未知占位符时的错误信息
+pub fn generate_report(entries: &[LogLine], bookmarks: &[PinnedEntry], source: &str, options: &ReportOptions) -> Result<String, String> {
+    let format = match options.format.to_lowercase().as_str() {
+        "markdown" | "md" => ReportFormat::Markdown,
+        "html" => ReportFormat::Html,
+        other => return Err(Message::new("report.unsupported_format").with("format", other).text()),
+    };
+    let template = options.template.clone().unwrap_or_else(|| default_template(format));
+
+    let unknown: Vec<String> = PLACEHOLDER.captures_iter(&template)
+        .map(|caps| caps[1].to_string())
+        .filter(|name| !matches!(name.as_str(),
+            "title" | "source" | "generated_at" | "summary" | "error_clusters" | "timeline" | "anomalies" | "bookmarks"))
+        .collect();
+    if !unknown.is_empty() {
+        return Err(Message::new("report.unknown_placeholders").with("placeholders", unknown.join(", ")).text());
+    }
+
+    let outline = build_outline(entries);
+    let (anomalies, timeline): (Vec<&OutlineSection>, Vec<&OutlineSection>) =
+        outline.sections.iter().partition(|section| section.error_count > 0);
+    let title = options.title.clone()
+        .unwrap_or_else(|| Message::new("report.title").with("source", source).localize(label_locale()));
+
+    let mut blocks: HashMap<&str, String> = HashMap::new();
+    blocks.insert("title", format.escape(&title));
+    blocks.insert("source", format.escape(source));
+    blocks.insert("generated_at", chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string());
+    blocks.insert("summary", render_summary(entries, source, format));
+    blocks.insert("error_clusters", render_clusters(entries, options.max_clusters, format));
+    blocks.insert("timeline", render_sections(&timeline, format));
+    blocks.insert("anomalies", render_sections(&anomalies, format));
+    blocks.insert("bookmarks", render_bookmarks(bookmarks, format));
+
+    Ok(PLACEHOLDER.replace_all(&template, |caps: &regex::Captures| blocks[&caps[1]].clone()).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(line_number: usize, level: &str, content: &str) -> LogLine {
+        LogLine {
+            level: Some(level.to_string()),
+            timestamp: Some(format!("2024-01-15 10:30:{:02}", line_number)),
+            ..LogLine::new(line_number, content)
+        }
+    }
+
+    fn entries() -> Vec<LogLine> {
+        vec![
+            entry(1, "INFO", "Starting OrderApplication v1.2.0 using Java 17"),
+            entry(2, "ERROR", "Timeout after 3000 ms calling inventory"),
+            entry(3, "ERROR", "Timeout after 5000 ms calling inventory"),
+            entry(4, "ERROR", "Order 42 rejected | <invalid>"),
+            entry(5, "INFO", "Started OrderApplication in 4.2 seconds"),
+        ]
+    }
+
+    #[test]
+    fn test_markdown_report_sections() {
+        let pin = PinnedEntry::from_entry("app.log", &entries()[1], Some("库存服务超时".to_string()));
+        let report = generate_report(&entries(), &[pin], "app.log", &ReportOptions::default()).unwrap();
+
+        assert!(report.starts_with("# 调查报告: app.log"));
+        // 数字不同的同类错误聚为一类
+        assert!(report.contains("| 2 | 2-3 | Timeout after \\<n\\> ms calling inventory |"));
+        assert!(report.contains("Order \\<n\\> rejected \\| \\<invalid\\>"));
+        assert!(report.contains("行 1-4 `2024-01-15 10:30:01` 应用启动"));
+        assert!(report.contains("3 条错误"));
+        assert!(report.contains("备注: 库存服务超时"));
+        assert!(!report.contains("{{"));
+    }
+
+    #[test]
+    fn test_custom_template_and_unknown_placeholder() {
+        let options = ReportOptions {
+            format: "html".to_string(),
+            title: Some("INC-7 <orders>".to_string()),
+            template: Some("<h1>{{ title }}</h1>{{bookmarks}}".to_string()),
+            ..Default::default()
+        };
+        let report = generate_report(&entries(), &[], "app.log", &options).unwrap();
+        assert_eq!(report, "<h1>INC-7 &lt;orders&gt;</h1><p class=\"none\">无</p>");
+
+        let options = ReportOptions { template: Some("{{summary}} {{owner}}".to_string()), ..Default::default() };
+        assert!(generate_report(&entries(), &[], "app.log", &options).unwrap_err().contains("owner"));
+    }
+}
@@ -0,0 +1,377 @@
+/// 日志片段分享
+///
+/// 把选中的日志行连同前后上下文渲染为独立的HTML或Markdown片段，
+/// 可以直接粘贴到issue或聊天工具中。片段包含摘要表（来源、行号、级别、时间范围）、
+/// 选中行的元数据字段表，以及带行号的日志内容：选中行加标记，不连续的部分以省略行分隔。
+/// HTML片段内联样式，按级别着色并高亮时间戳和级别，不依赖外部资源。
+
+use crate::config::theme::{default_level_styles, LevelStyle};
+use crate::i18n::{current_locale, Locale, Message};
+use crate::models::LogLine;
+use crate::plugins::formatter::{escape_html, escape_markdown, LogRenderer, PlainRenderer, UnifiedFormatter};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// 解析流程内部使用、不适合分享的元数据字段
+const INTERNAL_METADATA_KEYS: [&str; 4] = ["processed_by", "line_number", "end_line", "chunk_continued"];
+
+/// 元数据字段表中每个字段最多列出的不同取值
+const MAX_FIELD_VALUES: usize = 5;
+
+/// 片段选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnippetOptions {
+    /// 片段格式："html" 或 "markdown"
+    #[serde(default = "default_format")]
+    pub format: String,
+
+    /// 选中行前后各保留的上下文行数
+    #[serde(default = "default_context_lines")]
+    pub context_lines: usize,
+
+    /// HTML片段中各级别的颜色（取自主题配置，与界面显示一致）
+    #[serde(skip, default = "default_level_styles")]
+    pub level_styles: BTreeMap<String, LevelStyle>,
+}
+
+fn default_format() -> String {
+    "markdown".to_string()
+}
+
+fn default_context_lines() -> usize {
+    3
+}
+
+impl Default for SnippetOptions {
+    fn default() -> Self {
+        Self {
+            format: default_format(),
+            context_lines: default_context_lines(),
+            level_styles: default_level_styles(),
+        }
+    }
+}
+
+/// 片段中的一行（None表示省略的不连续部分）
+type SnippetRow<'a> = Option<(&'a LogLine, bool)>;
+
+/// 片段摘要
+struct SnippetSummary {
+    lines: String,
+    levels: String,
+    time_range: String,
+    fields: BTreeMap<String, BTreeSet<String>>,
+}
+
+/// 片段文本使用的语言（`code` 模式下使用中文，片段是给人看的文档）
+fn label_locale() -> Locale {
+    match current_locale() {
+        Locale::Code => Locale::ZhCn,
+        locale => locale,
+    }
+}
+
+fn label(code: &str) -> String {
+    Message::new(code).localize(label_locale())
+}
+
+/// 把行号列表压缩为区间文本（如 `12-15, 20`）
+fn compact_ranges(line_numbers: &[usize]) -> String {
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for &n in line_numbers {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == n => *end = n,
+            _ => ranges.push((n, n)),
+        }
+    }
+    ranges.iter()
+        .map(|&(start, end)| if start == end { start.to_string() } else { format!("{}-{}", start, end) })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn summarize(selected: &[&LogLine]) -> SnippetSummary {
+    let line_numbers: Vec<usize> = selected.iter().map(|line| line.line_number).collect();
+
+    let mut levels: BTreeMap<String, usize> = BTreeMap::new();
+    for line in selected {
+        if let Some(level) = UnifiedFormatter::normalize_level(line.level.clone()) {
+            *levels.entry(level).or_default() += 1;
+        }
+    }
+
+    let timestamps: Vec<&str> = selected.iter().filter_map(|line| line.timestamp.as_deref()).collect();
+    let time_range = match (timestamps.first(), timestamps.last()) {
+        (Some(first), Some(last)) if first != last => format!("{} → {}", first, last),
+        (Some(first), _) => first.to_string(),
+        _ => "-".to_string(),
+    };
+
+    let mut fields: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for line in selected {
+        for (key, value) in &line.metadata {
+            if INTERNAL_METADATA_KEYS.contains(&key.as_str()) || value.is_empty() {
+                continue;
+            }
+            let values = fields.entry(key.clone()).or_default();
+            if values.len() < MAX_FIELD_VALUES {
+                values.insert(value.clone());
+            }
+        }
+    }
+
+    SnippetSummary {
+        lines: compact_ranges(&line_numbers),
+        levels: if levels.is_empty() {
+            "-".to_string()
+        } else {
+            levels.iter().map(|(level, count)| format!("{}×{}", level, count)).collect::<Vec<_>>().join(", ")
+        },
+        time_range,
+        fields,
+    }
+}
+
+/// 选出要显示的行：选中行及其上下文，不连续处插入省略行
+fn select_rows<'a>(entries: &'a [LogLine], selected: &BTreeSet<usize>, context_lines: usize) -> Vec<SnippetRow<'a>> {
+    let mut visible = BTreeSet::new();
+    for (index, entry) in entries.iter().enumerate() {
+        if selected.contains(&entry.line_number) {
+            let end = (index + context_lines).min(entries.len() - 1);
+            visible.extend(index.saturating_sub(context_lines)..=end);
+        }
+    }
+
+    let mut rows = Vec::new();
+    let mut previous: Option<usize> = None;
+    for index in visible {
+        if previous.is_some_and(|p| p + 1 != index) {
+            rows.push(None);
+        }
+        let entry = &entries[index];
+        rows.push(Some((entry, selected.contains(&entry.line_number))));
+        previous = Some(index);
+    }
+    rows
+}
+
+/// 渲染选中日志行的分享片段
+///
+/// # 参数
+/// - `entries`: 文件的全部日志条目（按行号排序，用于取上下文）
+/// - `line_numbers`: 选中的行号
+/// - `source`: 来源名称（通常为文件名）
+/// - `options`: 片段选项
+///
+/// # Returns
+/// - `Ok(String)`: HTML或Markdown片段
+/// - `Err(String)`: 未选中任何存在的行或格式不支持时的错误信息
+pub fn render_snippet(entries: &[LogLine], line_numbers: &[usize], source: &str, options: &SnippetOptions) -> Result<String, String> {
+    let selected: BTreeSet<usize> = line_numbers.iter().copied().collect();
+    let selected_entries: Vec<&LogLine> = entries.iter().filter(|e| selected.contains(&e.line_number)).collect();
+    if selected_entries.is_empty() {
+        return Err(Message::new("snippet.lines_not_found").text());
+    }
+
+    let summary = summarize(&selected_entries);
+    let rows = select_rows(entries, &selected, options.context_lines);
+    match options.format.to_lowercase().as_str() {
+        "html" => Ok(to_html(&rows, &summary, source, &options.level_styles)),
+        "markdown" | "md" => Ok(to_markdown(&rows, &summary, source)),
+        other => Err(Message::new("snippet.unsupported_format").with("format", other).text()),
+    }
+}
+
+fn to_markdown(rows: &[SnippetRow], summary: &SnippetSummary, source: &str) -> String {
+    let width = rows.iter().flatten().map(|(line, _)| line.line_number.to_string().len()).max().unwrap_or(1);
+    let mut code = Vec::new();
+    for row in rows {
+        let Some((line, selected)) = row else {
+            code.push(format!("  {:>width$} ┆", "⋮", width = width));
+            continue;
+        };
+        let marker = if *selected { '▶' } else { ' ' };
+        for (i, text) in PlainRenderer.render_line(line).lines().enumerate() {
+            match i {
+                0 => code.push(format!("{} {:>width$} │ {}", marker, line.line_number, text, width = width)),
+                _ => code.push(format!("  {:>width$} │ {}", "", text, width = width)),
+            }
+        }
+    }
+    let code = code.join("\n");
+
+    // 围栏长度必须超过内容中最长的连续反引号
+    let longest_ticks = code.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest_ticks.max(2) + 1);
+
+    let mut out = vec![
+        format!("### {}", escape_markdown(&Message::new("snippet.title").with("source", source).localize(label_locale()))),
+        String::new(),
+        format!("| {} | {} | {} | {} |", label("snippet.source"), label("snippet.lines"), label("snippet.levels"), label("snippet.time_range")),
+        "|---|---|---|---|".to_string(),
+        format!("| {} | {} | {} | {} |", escape_markdown(source), summary.lines, summary.levels, escape_markdown(&summary.time_range)),
+    ];
+    if !summary.fields.is_empty() {
+        out.push(String::new());
+        out.push(format!("| {} | {} |", label("snippet.field"), label("snippet.values")));
+        out.push("|---|---|".to_string());
+        for (key, values) in &summary.fields {
+            let values: Vec<String> = values.iter().map(|v| escape_markdown(v)).collect();
+            out.push(format!("| {} | {} |", escape_markdown(key), values.join(", ")));
+        }
+    }
+    out.push(String::new());
+    out.push(format!("{}log\n{}\n{}", fence, code, fence));
+    out.join("\n")
+}
+
+/// 转义一行并高亮其中的时间戳和级别
+fn highlight_html(text: &str, line: &LogLine) -> String {
+    let mut marks: Vec<(usize, usize, &str)> = Vec::new();
+    if let Some(ts) = line.timestamp.as_deref().filter(|ts| !ts.is_empty()) {
+        if let Some(start) = text.find(ts) {
+            marks.push((start, start + ts.len(), "ts"));
+        }
+    }
+    if let Some(level) = line.level.as_deref().filter(|l| !l.is_empty()) {
+        let from = marks.first().map_or(0, |&(_, end, _)| end);
+        if let Some(offset) = text[from..].find(level) {
+            marks.push((from + offset, from + offset + level.len(), "lvl"));
+        }
+    }
+    marks.sort_by_key(|&(start, _, _)| start);
+
+    let mut out = String::new();
+    let mut pos = 0;
+    for (start, end, class) in marks {
+        out.push_str(&escape_html(&text[pos..start]));
+        out.push_str(&format!("<span class=\"{}\">{}</span>", class, escape_html(&text[start..end])));
+        pos = end;
+    }
+    out.push_str(&escape_html(&text[pos..]));
+    out
+}
+
+/// 各级别颜色的样式规则
+fn level_css(styles: &BTreeMap<String, LevelStyle>) -> String {
+    styles.iter()
+        .map(|(level, style)| format!(".log-level-{} .lvl{{color:{}}}\n", level.to_lowercase(), style.color))
+        .collect()
+}
+
+fn to_html(rows: &[SnippetRow], summary: &SnippetSummary, source: &str, level_styles: &BTreeMap<String, LevelStyle>) -> String {
+    let mut body = Vec::new();
+    for row in rows {
+        let Some((line, selected)) = row else {
+            body.push("<tr class=\"gap\"><td class=\"no\">⋮</td><td></td></tr>".to_string());
+            continue;
+        };
+        let level_class = UnifiedFormatter::normalize_level(line.level.clone())
+            .map(|level| level.to_lowercase())
+            .unwrap_or_else(|| "none".to_string());
+        let row_class = if *selected { "sel" } else { "ctx" };
+        let text = PlainRenderer.render_line(line);
+        body.push(format!(
+            "<tr class=\"{} log-level-{}\"><td class=\"no\">{}</td><td class=\"text\">{}</td></tr>",
+            row_class, level_class, line.line_number, highlight_html(&text, line)
+        ));
+    }
+
+    let mut fields = String::new();
+    if !summary.fields.is_empty() {
+        fields.push_str(&format!(
+            "<table class=\"meta\"><tr><th>{}</th><th>{}</th></tr>",
+            escape_html(&label("snippet.field")), escape_html(&label("snippet.values"))
+        ));
+        for (key, values) in &summary.fields {
+            let values: Vec<String> = values.iter().map(|v| escape_html(v)).collect();
+            fields.push_str(&format!("<tr><td>{}</td><td>{}</td></tr>", escape_html(key), values.join(", ")));
+        }
+        fields.push_str("</table>");
+    }
+
+    let title = Message::new("snippet.title").with("source", source).localize(label_locale());
+    format!(
+        r#"<!DOCTYPE html>
+<html><head><meta charset="utf-8"><title>{title}</title>
+<style>
+body{{font-family:-apple-system,"Segoe UI",sans-serif;margin:16px;color:#24292f}}
+table.meta{{border-collapse:collapse;margin-bottom:12px;font-size:13px}}
+table.meta th,table.meta td{{border:1px solid #d0d7de;padding:4px 8px;text-align:left}}
+table.log{{border-collapse:collapse;width:100%;font:12px/1.5 ui-monospace,Menlo,Consolas,monospace}}
+table.log td{{padding:0 8px;vertical-align:top}}
+td.no{{color:#8c959f;text-align:right;user-select:none;border-right:1px solid #d0d7de}}
+td.text{{white-space:pre-wrap;word-break:break-all}}
+tr.ctx td.text{{color:#6e7781}}
+tr.sel{{background:#fff8c5}}
+tr.gap td{{color:#8c959f;background:#f6f8fa}}
+.ts{{color:#0550ae}}
+.lvl{{font-weight:bold}}
+{level_css}</style></head><body>
+<h3>{title}</h3>
+<table class="meta"><tr><th>{source_label}</th><th>{lines_label}</th><th>{levels_label}</th><th>{time_label}</th></tr><tr><td>{source}</td><td>{lines}</td><td>{levels}</td><td>{time_range}</td></tr></table>
+{fields}
+<table class="log">
+{body}
+</table>
+</body></html>"#,
+        title = escape_html(&title),
+        level_css = level_css(level_styles),
+        source_label = escape_html(&label("snippet.source")),
+        lines_label = escape_html(&label("snippet.lines")),
+        levels_label = escape_html(&label("snippet.levels")),
+        time_label = escape_html(&label("snippet.time_range")),
+        source = escape_html(source),
+        lines = summary.lines,
+        levels = escape_html(&summary.levels),
+        time_range = escape_html(&summary.time_range),
+        fields = fields,
+        body = body.join("\n"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn entries() -> Vec<LogLine> {
+        (1..=10).map(|n| LogLine {
+            level: Some(if n == 5 { "ERROR" } else { "INFO" }.to_string()),
+            timestamp: Some(format!("2024-01-15 10:30:{:02}", n)),
+            metadata: HashMap::from([("thread".to_string(), "main".to_string()), ("processed_by".to_string(), "x".to_string())]),
+            ..LogLine::new(n, format!("2024-01-15 10:30:{:02} {} message {}", n, if n == 5 { "ERROR" } else { "INFO" }, n))
+        }).collect()
+    }
+
+    #[test]
+    fn test_markdown_snippet_with_context_and_gap() {
+        let options = SnippetOptions { context_lines: 1, ..Default::default() };
+        let snippet = render_snippet(&entries(), &[5, 9], "app.log", &options).unwrap();
+
+        assert!(snippet.contains("| app.log | 5, 9 | ERROR×1, INFO×1 | 2024-01-15 10:30:05 → 2024-01-15 10:30:09 |"));
+        assert!(snippet.contains("| thread | main |"));
+        assert!(!snippet.contains("processed\\_by"));
+        assert!(snippet.contains("```log\n   4 │ 2024-01-15 10:30:04 INFO message 4\n▶  5 │"));
+        assert!(snippet.contains("   ⋮ ┆\n   8 │"));
+        assert!(snippet.ends_with("▶  9 │ 2024-01-15 10:30:09 INFO message 9\n  10 │ 2024-01-15 10:30:10 INFO message 10\n```"));
+        assert!(render_snippet(&entries(), &[42], "app.log", &options).is_err());
+    }
+
+    #[test]
+    fn test_html_snippet_highlights_and_escapes() {
+        let mut entries = entries();
+        entries[4].content = "2024-01-15 10:30:05 ERROR <script>boom</script>".to_string();
+        let mut options = SnippetOptions { format: "html".to_string(), context_lines: 0, ..Default::default() };
+        options.level_styles.get_mut("ERROR").unwrap().color = "#ff0000".to_string();
+        let snippet = render_snippet(&entries, &[5], "a&b.log", &options).unwrap();
+
+        assert!(snippet.starts_with("<!DOCTYPE html>"));
+        assert!(snippet.contains("<td>a&amp;b.log</td>"));
+        assert!(snippet.contains(
+            "<tr class=\"sel log-level-error\"><td class=\"no\">5</td><td class=\"text\"><span class=\"ts\">2024-01-15 10:30:05</span> <span class=\"lvl\">ERROR</span> &lt;script&gt;boom&lt;/script&gt;</td></tr>"
+        ));
+        assert!(!snippet.contains("class=\"ctx"));
+        assert!(snippet.contains(".log-level-error .lvl{color:#ff0000}"));
+    }
+}
@@ -0,0 +1,187 @@
+/// 按键拆分导出
+///
+/// 按级别、日志器或任意元数据键（如 `pod`）把条目分组，每组写入一个文件，
+/// 文件名为 `<基础文件名>.<键值>.<扩展名>`，便于把大日志的不同部分分别交给负责人。
+/// 各组依次编码和写入，同一时间只保留一组的导出内容。
+
+use super::{export_entries, ExportOptions};
+use crate::i18n::Message;
+use crate::models::LogLine;
+use crate::plugins::formatter::RendererRegistry;
+use crate::write_guard::atomic_write;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// 默认的最大分组数（超过时报错，避免按高基数的键误拆出成千上万个文件）
+pub const DEFAULT_MAX_PARTITIONS: usize = 200;
+
+/// 没有该键的条目归入的分组
+pub const MISSING_KEY_VALUE: &str = "unknown";
+
+/// 文件名中键值部分的最大字符数
+const MAX_KEY_CHARS: usize = 80;
+
+/// 拆分出的一个文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SplitPart {
+    /// 键值
+    pub key: String,
+    /// 文件路径
+    pub path: String,
+    /// 条目数
+    pub entries: usize,
+    /// 写入的字节数
+    pub bytes: usize,
+    /// 是否有字符无法用目标编码表示
+    pub lossy: bool,
+}
+
+/// 条目的分组键值：`level` 取日志级别，其他键取同名元数据
+pub fn partition_value<'a>(entry: &'a LogLine, key: &str) -> Option<&'a str> {
+    match key {
+        "level" => entry.level.as_deref(),
+        _ => entry.metadata.get(key).map(String::as_str),
+    }
+    .map(str::trim)
+    .filter(|value| !value.is_empty())
+}
+
+/// 按键值分组（键值按字典序排列，组内保持条目顺序）
+///
+/// # Returns
+/// - `Vec<(String, Vec<usize>)>`: 键值和组内条目的下标
+pub fn partition_entries(entries: &[LogLine], key: &str) -> Vec<(String, Vec<usize>)> {
+    let mut partitions: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    for (index, entry) in entries.iter().enumerate() {
+        let value = partition_value(entry, key).unwrap_or(MISSING_KEY_VALUE);
+        partitions.entry(value.to_string()).or_default().push(index);
+    }
+    partitions.into_iter().collect()
+}
+
+/// 把键值转换为安全的文件名部分（路径分隔符等字符替换为 `_`）
+fn sanitize_key(value: &str) -> String {
+    let sanitized: String = value.chars()
+        .map(|c| if c.is_alphanumeric() || matches!(c, '-' | '_' | '.') { c } else { '_' })
+        .take(MAX_KEY_CHARS)
+        .collect();
+    let sanitized = sanitized.trim_matches('.').to_string();
+    if sanitized.is_empty() { MISSING_KEY_VALUE.to_string() } else { sanitized }
+}
+
+/// 拆分文件的路径：`dir/app.log` + `web-1` -> `dir/app.web-1.log`
+///
+/// # 参数
+/// - `base`: 基础文件路径
+/// - `value`: 键值
+/// - `used`: 已使用的文件名部分，转换后重名时追加序号
+pub fn partition_path(base: &Path, value: &str, used: &mut HashSet<String>) -> PathBuf {
+    let sanitized = sanitize_key(value);
+    let mut part = sanitized.clone();
+    let mut n = 2;
+    while !used.insert(part.to_lowercase()) {
+        part = format!("{}-{}", sanitized, n);
+        n += 1;
+    }
+
+    let stem = base.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+    let name = match base.extension() {
+        Some(ext) => format!("{}.{}.{}", stem, part, ext.to_string_lossy()),
+        None => format!("{}.{}", stem, part),
+    };
+    base.with_file_name(name)
+}
+
+/// 按键拆分并逐组写入文件
+///
+/// # 参数
+/// - `entries`: 要导出的条目（已筛选）
+/// - `key`: 分组键（`level` 或元数据键，如 `pod`、`logger`）
+/// - `base`: 基础文件路径，各组文件写在同一目录下
+/// - `options`: 导出选项（格式、编码、换行等）
+/// - `renderers`: 渲染器注册表
+/// - `max_partitions`: 最大分组数
+///
+/// # Returns
+/// - `Ok(Vec<SplitPart>)`: 写入的文件（按键值排序）
+/// - `Err(String)`: 分组过多、格式无效或写入失败时的错误信息（已写入的文件保留）
+pub fn export_split(
+    entries: &[LogLine],
+    key: &str,
+    base: &Path,
+    options: &ExportOptions,
+    renderers: &RendererRegistry,
+    max_partitions: usize,
+) -> Result<Vec<SplitPart>, String> {
+    let key = key.trim();
+    if key.is_empty() {
+        return Err(Message::new("split.missing_key").text());
+    }
+    let partitions = partition_entries(entries, key);
+    if partitions.len() > max_partitions {
+        return Err(Message::new("split.too_many_partitions")
+            .with("key", key)
+            .with("count", partitions.len())
+            .with("max", max_partitions)
+            .text());
+    }
+
+    let mut used = HashSet::new();
+    let mut parts = Vec::with_capacity(partitions.len());
+    for (value, indices) in partitions {
+        let group: Vec<LogLine> = indices.iter().map(|&i| entries[i].clone()).collect();
+        let output = export_entries(&group, options, renderers)?;
+        let path = partition_path(base, &value, &mut used);
+        atomic_write(&path, &output.bytes)?;
+        log::debug!("📄 拆分导出: {} -> {} ({} 条)", value, path.display(), group.len());
+        parts.push(SplitPart {
+            key: value,
+            path: path.to_string_lossy().into_owned(),
+            entries: group.len(),
+            bytes: output.bytes.len(),
+            lossy: output.lossy,
+        });
+    }
+    Ok(parts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_split_by_metadata_key() {
+        let entries: Vec<LogLine> = [("web-1", "a"), ("web/2", "b"), ("web-1", "c"), ("", "d")]
+            .iter()
+            .enumerate()
+            .map(|(i, (pod, content))| LogLine {
+                level: Some("INFO".to_string()),
+                metadata: if pod.is_empty() { HashMap::new() } else { HashMap::from([("pod".to_string(), pod.to_string())]) },
+                ..LogLine::new(i + 1, *content)
+            })
+            .collect();
+
+        let partitions = partition_entries(&entries, "pod");
+        assert_eq!(partitions, vec![
+            ("unknown".to_string(), vec![3]),
+            ("web-1".to_string(), vec![0, 2]),
+            ("web/2".to_string(), vec![1]),
+        ]);
+
+        let dir = std::env::temp_dir().join(format!("lw-split-{}", uuid::Uuid::new_v4()));
+        let parts = export_split(&entries, "pod", &dir.join("app.log"), &ExportOptions::default(), &RendererRegistry::new(), 10).unwrap();
+        let names: Vec<String> = parts.iter()
+            .map(|part| Path::new(&part.path).file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names, vec!["app.unknown.log", "app.web-1.log", "app.web_2.log"]);
+        assert_eq!(std::fs::read_to_string(&parts[1].path).unwrap().lines().count(), 2);
+        assert!(export_split(&entries, "pod", &dir.join("app.log"), &ExportOptions::default(), &RendererRegistry::new(), 2).is_err());
+        std::fs::remove_dir_all(&dir).ok();
+
+        let mut used = HashSet::new();
+        assert_eq!(partition_path(Path::new("/x/app.log"), "a/b", &mut used), Path::new("/x/app.a_b.log"));
+        assert_eq!(partition_path(Path::new("/x/app.log"), "a_b", &mut used), Path::new("/x/app.a_b-2.log"));
+    }
+}
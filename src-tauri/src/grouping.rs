@@ -0,0 +1,138 @@
+/// 条目分组统计
+///
+/// 按日志级别或任意元数据字段（日志器、线程、Pod等）对解析结果分组，
+/// 返回每组的条目数、级别分布和少量代表条目，界面的"分组"下拉框据此展示，
+/// 不需要把全部条目传给前端再在JS中分组。
+
+use crate::models::LogLine;
+use crate::search::EntryMatcher;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// 按日志级别分组时使用的分组字段名
+pub const GROUP_BY_LEVEL: &str = "level";
+
+/// 每组默认返回的代表条目数
+pub const DEFAULT_SAMPLES: usize = 3;
+
+/// 一个分组
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntryGroup {
+    /// 分组值（条目没有该字段时为None）
+    pub key: Option<String>,
+    /// 条目数
+    pub count: usize,
+    /// 第一条条目的行号
+    pub first_line: usize,
+    /// 最后一条条目的行号
+    pub last_line: usize,
+    /// 级别分布（级别 -> 条目数，没有级别的条目不计入）
+    pub levels: BTreeMap<String, usize>,
+    /// 代表条目（组内最先出现的若干条）
+    pub samples: Vec<LogLine>,
+}
+
+/// 分组结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupSummary {
+    /// 分组字段（`level` 或元数据键，如 `logger`、`thread`、`pod`）
+    pub by: String,
+    /// 筛选后参与分组的条目数
+    pub matched_entries: usize,
+    /// 文件中的条目总数
+    pub total_entries: usize,
+    /// 分组（按条目数降序，相同时按分组值排序）
+    pub groups: Vec<EntryGroup>,
+}
+
+/// 读取条目的分组值
+fn group_key<'a>(entry: &'a LogLine, by: &str) -> Option<&'a str> {
+    if by == GROUP_BY_LEVEL {
+        entry.level.as_deref()
+    } else {
+        entry.metadata.get(by).map(String::as_str)
+    }
+}
+
+/// 对条目分组
+///
+/// # 参数
+/// - `entries`: 解析后的条目
+/// - `by`: 分组字段：`level` 按日志级别，其他值按同名元数据（如 `logger`、`thread`、`pod`）
+/// - `matcher`: 筛选条件（None表示不筛选）
+/// - `samples`: 每组返回的代表条目数
+pub fn group_entries(entries: &[LogLine], by: &str, matcher: Option<&EntryMatcher>, samples: usize) -> GroupSummary {
+    let mut groups: HashMap<Option<&str>, EntryGroup> = HashMap::new();
+    let mut matched_entries = 0;
+
+    for entry in entries.iter().filter(|entry| matcher.is_none_or(|m| m.matches(entry))) {
+        matched_entries += 1;
+        let key = group_key(entry, by);
+        let group = groups.entry(key).or_insert_with(|| EntryGroup {
+            key: key.map(str::to_string),
+            count: 0,
+            first_line: entry.line_number,
+            last_line: entry.line_number,
+            levels: BTreeMap::new(),
+            samples: Vec::new(),
+        });
+        group.count += 1;
+        group.last_line = entry.line_number;
+        if let Some(level) = &entry.level {
+            *group.levels.entry(level.clone()).or_insert(0) += 1;
+        }
+        if group.samples.len() < samples {
+            group.samples.push(entry.clone());
+        }
+    }
+
+    let mut groups: Vec<EntryGroup> = groups.into_values().collect();
+    groups.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.key.cmp(&b.key)));
+
+    GroupSummary {
+        by: by.to_string(),
+        matched_entries,
+        total_entries: entries.len(),
+        groups,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::EntryFilter;
+
+    fn entry(line_number: usize, level: &str, logger: Option<&str>) -> LogLine {
+        LogLine {
+            level: Some(level.to_string()),
+            metadata: logger.map(|l| HashMap::from([("logger".to_string(), l.to_string())])).unwrap_or_default(),
+            ..LogLine::new(line_number, format!("line {}", line_number))
+        }
+    }
+
+    #[test]
+    fn test_group_by_metadata_and_level_with_filter() {
+        let entries = vec![
+            entry(1, "INFO", Some("c.e.Api")),
+            entry(2, "ERROR", Some("c.e.Db")),
+            entry(3, "ERROR", Some("c.e.Api")),
+            entry(4, "WARN", None),
+            entry(5, "INFO", Some("c.e.Api")),
+        ];
+
+        let summary = group_entries(&entries, "logger", None, 2);
+        assert_eq!(summary.groups.len(), 3);
+        let api = &summary.groups[0];
+        assert_eq!((api.key.as_deref(), api.count, api.first_line, api.last_line), (Some("c.e.Api"), 3, 1, 5));
+        assert_eq!(api.levels.get("INFO"), Some(&2));
+        assert_eq!(api.samples.iter().map(|s| s.line_number).collect::<Vec<_>>(), vec![1, 3]);
+        // 没有该字段的条目单独成组，相同条目数时None排在前面
+        assert_eq!(summary.groups[1].key, None);
+
+        let matcher = EntryFilter { levels: vec!["ERROR".to_string()], ..Default::default() }.compile().unwrap();
+        let summary = group_entries(&entries, GROUP_BY_LEVEL, Some(&matcher), DEFAULT_SAMPLES);
+        assert_eq!((summary.matched_entries, summary.total_entries), (2, 5));
+        assert_eq!(summary.groups.len(), 1);
+        assert_eq!(summary.groups[0].key.as_deref(), Some("ERROR"));
+    }
+}
@@ -0,0 +1,579 @@
+/// LogWhisper 后端消息国际化
+///
+/// 后端返回给前端的提示和错误信息统一通过消息代码 + 参数表示，
+/// 再按当前语言从消息目录中渲染为文本。
+///
+/// # 语言
+/// - `zh-CN`: 简体中文（默认）
+/// - `en-US`: 英文
+/// - `code`: 不做本地化，返回消息的JSON表示（`{"code": ..., "params": {...}}`），
+///   由前端自行翻译
+///
+/// # 使用方式
+/// ```rust
+/// let message = Message::new("file.not_found").with("path", &file_path);
+/// return Err(message.text());
+/// ```
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+
+/// 支持的语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Locale {
+    /// 简体中文
+    #[serde(rename = "zh-CN")]
+    ZhCn,
+    /// 英文
+    #[serde(rename = "en-US")]
+    EnUs,
+    /// 只返回消息代码和参数
+    #[serde(rename = "code")]
+    Code,
+}
+
+impl Locale {
+    /// 所有支持的语言标识
+    pub const ALL: [&'static str; 3] = ["zh-CN", "en-US", "code"];
+
+    /// 解析语言标识（不区分大小写，`zh`/`en` 视为对应的默认地区）
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.to_ascii_lowercase().replace('_', "-").as_str() {
+            "zh-cn" | "zh" => Ok(Locale::ZhCn),
+            "en-us" | "en" => Ok(Locale::EnUs),
+            "code" => Ok(Locale::Code),
+            _ => Err(Message::new("locale.unsupported")
+                .with("locale", value)
+                .with("supported", Locale::ALL.join(", "))
+                .text()),
+        }
+    }
+
+    /// 语言标识字符串
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Locale::ZhCn => "zh-CN",
+            Locale::EnUs => "en-US",
+            Locale::Code => "code",
+        }
+    }
+}
+
+/// 当前语言，启动时从窗口配置加载
+static CURRENT_LOCALE: Lazy<RwLock<Locale>> = Lazy::new(|| RwLock::new(Locale::ZhCn));
+
+/// 设置当前语言
+pub fn set_locale(locale: Locale) {
+    *CURRENT_LOCALE.write().unwrap() = locale;
+}
+
+#[cfg(test)]
+thread_local! {
+    /// 测试中按线程覆盖当前语言，避免切换语言影响并行运行的其他测试
+    static TEST_LOCALE: std::cell::Cell<Option<Locale>> = const { std::cell::Cell::new(None) };
+}
+
+/// 获取当前语言
+pub fn current_locale() -> Locale {
+    #[cfg(test)]
+    if let Some(locale) = TEST_LOCALE.with(|locale| locale.get()) {
+        return locale;
+    }
+    *CURRENT_LOCALE.read().unwrap()
+}
+
+/// 在当前线程切换到指定语言执行 `f`，结束后恢复
+#[cfg(test)]
+pub fn with_locale<T>(locale: Locale, f: impl FnOnce() -> T) -> T {
+    let previous = TEST_LOCALE.with(|current| current.replace(Some(locale)));
+    let result = f();
+    TEST_LOCALE.with(|current| current.set(previous));
+    result
+}
+
+/// 消息目录：(代码, 简体中文, 英文)，参数以 `{name}` 占位
+static CATALOG: &[(&str, &str, &str)] = &[
+    // 文件与请求
+    ("file.not_found", "文件不存在: {path}", "File not found: {path}"),
+    ("file.not_a_file", "路径不是文件: {path}", "Path is not a file: {path}"),
+    ("file.read_failed", "读取文件失败: {path} ({error})", "Failed to read file: {path} ({error})"),
+    ("request.missing_source", "请求中既没有文件路径也没有内容", "The request contains neither a file path nor content"),
+    ("content.empty", "日志内容为空", "The log content is empty"),
+    ("plugin.unknown", "未知的插件: {plugin} (可用: {available})", "Unknown plugin: {plugin} (available: {available})"),
+    ("parse.plugin_failed", "增强插件管理器处理失败: {error}", "Plugin processing failed: {error}"),
+    ("line.out_of_range", "行号超出范围: {line} (文件共 {total} 行)", "Line number out of range: {line} (file has {total} lines)"),
+    ("line.read_failed", "读取第{line}行失败", "Failed to read line {line}"),
+    ("line.no_entry", "文件 {path} 第{line}行没有日志条目", "No log entry at line {line} of {path}"),
+    ("parse.result_unavailable", "文件 {path} 的解析结果不可用", "No parse result is available for {path}"),
+    ("parse.no_history", "文件 {path} 还没有解析记录", "{path} has not been parsed yet"),
+    ("format.unknown", "未知的解析格式: {format}", "Unknown parse format: {format}"),
+    ("selection.empty", "没有选中任何日志行", "No log lines are selected"),
+    ("clipboard.read_failed", "读取剪贴板失败: {error}", "Failed to read the clipboard: {error}"),
+    ("clipboard.empty", "剪贴板中没有文本", "The clipboard contains no text"),
+    ("dialog.failed", "打开文件对话框失败: {error}", "Failed to open the file dialog: {error}"),
+    ("shortcut.register_failed", "注册全局快捷键 {shortcut} 失败: {error}", "Failed to register global shortcut {shortcut}: {error}"),
+    ("io.open_failed", "打开文件失败: {error}", "Failed to open the file: {error}"),
+    ("io.read_failed", "读取文件失败: {error}", "Failed to read the file: {error}"),
+    ("io.seek_failed", "定位文件偏移失败: {error}", "Failed to seek in the file: {error}"),
+    ("io.metadata_failed", "获取文件元数据失败: {error}", "Failed to read file metadata: {error}"),
+    ("line.invalid_range", "无效的行区间: {first}-{last} (文件共 {total} 行)", "Invalid line range: {first}-{last} (file has {total} lines)"),
+    ("line.start_out_of_range", "起始行超出文件范围", "The first line is outside the file"),
+    ("line.end_out_of_range", "结束行超出文件范围", "The last line is outside the file"),
+    ("anchor.invalid", "无效的条目锚点: {anchor}", "Invalid entry anchor: {anchor}"),
+    ("anchor.wrong_file", "锚点不属于文件 {path}", "The anchor does not belong to file {path}"),
+    ("sample.open_failed", "打开样本文件失败: {error}", "Failed to open the sample file: {error}"),
+    ("sample.read_failed", "读取样本文件失败: {error}", "Failed to read the sample file: {error}"),
+    ("task.line_count_failed", "行数统计任务失败: {error}", "Line counting failed: {error}"),
+    ("chunk.not_planned", "自适应分块需按顺序请求：第{chunk}块尚未规划", "Adaptive chunks must be requested in order: chunk {chunk} has not been planned yet"),
+    ("chunk.lock_failed", "分块规划锁失败", "Failed to lock the chunk planner"),
+    ("chunk.must_start_at_zero", "自适应分块必须从第0块开始请求", "Adaptive chunking must start at chunk 0"),
+    ("search.empty_query", "搜索关键字不能为空", "The search keyword cannot be empty"),
+    ("search.invalid_regex", "无效的正则表达式: {error}", "Invalid regular expression: {error}"),
+    ("table.no_columns", "表格至少需要一列", "The table needs at least one column"),
+    ("tail.not_found", "跟踪不存在: {id}", "Tail not found: {id}"),
+    ("tail.registry_lock_failed", "跟踪注册表锁失败", "Failed to lock the tail registry"),
+    ("tail.state_lock_failed", "跟踪状态锁失败", "Failed to lock the tail state"),
+    ("parse.processing_failed", "插件处理失败: {error}", "Plugin processing failed: {error}"),
+    // 会话
+    ("session.missing_source", "需要提供会话名称或会话文件路径", "A session name or session file path is required"),
+    ("session.nothing_to_compare", "会话 {session} 中没有可比较的日志", "Session {session} has no logs to compare"),
+    ("session.entries_not_found", "会话 {session} 中没有文件 {path} 的解析结果", "Session {session} has no parse result for {path}"),
+    ("session.empty_name", "会话名称不能为空", "The session name cannot be empty"),
+    ("session.create_directory_failed", "创建会话目录失败: {error}", "Failed to create the session directory: {error}"),
+    ("session.serialize_failed", "序列化会话失败: {error}", "Failed to serialize the session: {error}"),
+    ("session.read_directory_failed", "读取会话目录失败: {error}", "Failed to read the session directory: {error}"),
+    ("session.read_failed", "读取会话文件失败: {path} ({error})", "Failed to read the session file: {path} ({error})"),
+    ("session.invalid_format", "会话文件格式无效: {error}", "Invalid session file: {error}"),
+    ("session.version_too_new", "会话文件版本 {version} 高于当前支持的版本 {supported}，请升级LogWhisper", "Session file version {version} is newer than the supported version {supported}; please upgrade LogWhisper"),
+    // 后台任务
+    ("task.download_failed", "下载任务失败: {error}", "Download task failed: {error}"),
+    ("task.archive_failed", "读取压缩包任务失败: {error}", "Archive read task failed: {error}"),
+    ("task.search_failed", "搜索任务失败: {error}", "Search task failed: {error}"),
+    ("task.save_entries_failed", "保存解析结果任务失败: {error}", "Saving parse results failed: {error}"),
+    ("task.load_entries_failed", "读取解析结果任务失败: {error}", "Loading parse results failed: {error}"),
+    ("task.query_entries_failed", "查询解析结果任务失败: {error}", "Querying parse results failed: {error}"),
+    ("task.retention_failed", "保留策略任务失败: {error}", "Retention task failed: {error}"),
+    ("task.ssh_failed", "SSH任务失败: {error}", "SSH task failed: {error}"),
+    ("task.docker_failed", "Docker任务失败: {error}", "Docker task failed: {error}"),
+    ("task.kubectl_failed", "kubectl任务失败: {error}", "kubectl task failed: {error}"),
+    ("task.outliers_failed", "离群点检测任务失败: {error}", "Outlier detection failed: {error}"),
+    ("task.split_export_failed", "拆分导出任务失败: {error}", "Split export failed: {error}"),
+    ("task.issue_failed", "创建issue任务失败: {error}", "Creating the issue failed: {error}"),
+    ("task.summary_failed", "AI摘要任务失败: {error}", "AI summary failed: {error}"),
+    ("task.explain_failed", "错误解释任务失败: {error}", "Error explanation failed: {error}"),
+    ("task.parse_cancelled", "解析任务已取消: {path}", "Parsing was cancelled: {path}"),
+    ("task.analysis_cancelled", "分析任务已取消: {path}", "Analysis was cancelled: {path}"),
+    ("job.stage.indexing", "建立行索引", "Indexing lines"),
+    ("job.stage.parsing", "解析", "Parsing"),
+    ("job.cancelled", "任务已取消", "The task was cancelled"),
+    ("job.panicked", "任务崩溃: {message}", "The task crashed: {message}"),
+    ("job.unknown_panic", "未知错误", "unknown error"),
+    ("job.not_found", "任务不存在: {id}", "Task not found: {id}"),
+    ("job.finished", "任务已结束: {id}", "The task has already finished: {id}"),
+    // 远程来源与集成
+    ("remote.unknown_host", "未配置远程主机: {name}", "Remote host is not configured: {name}"),
+    ("issue.unknown_tracker", "未配置的issue跟踪系统: {tracker}", "Issue tracker is not configured: {tracker}"),
+    ("summary.disabled", "AI摘要未启用，请先在设置中配置模型服务", "AI summaries are disabled; configure a model service in the settings first"),
+    ("summary.missing_api_key", "环境变量 {name} 中没有API密钥", "No API key found in environment variable {name}"),
+    ("docker.connect_failed", "无法连接Docker（{host}）: {error}，请确认Docker正在运行", "Cannot connect to Docker ({host}): {error}; make sure Docker is running"),
+    ("docker.unsupported_host", "不支持的DOCKER_HOST: {host}", "Unsupported DOCKER_HOST: {host}"),
+    ("docker.request_failed", "发送Docker请求失败: {error}", "Failed to send the Docker request: {error}"),
+    ("docker.response_failed", "读取Docker响应失败: {error}", "Failed to read the Docker response: {error}"),
+    ("docker.error_status", "Docker返回错误 {status}: {message}", "Docker returned error {status}: {message}"),
+    ("docker.invalid_container_list", "解析容器列表失败: {error}", "Failed to parse the container list: {error}"),
+    ("docker.invalid_container_id", "无效的容器ID: {id}", "Invalid container ID: {id}"),
+    ("docker.invalid_container_info", "解析容器信息失败: {error}", "Failed to parse the container details: {error}"),
+    ("logs.invalid_since", "无效的起始时间: {since}", "Invalid start time: {since}"),
+    ("docker.invalid_response", "Docker响应格式无效", "Invalid Docker response"),
+    ("docker.invalid_chunk", "Docker响应分块格式无效", "Invalid chunked encoding in the Docker response"),
+    ("kubectl.spawn_failed", "无法启动kubectl（请确认已安装并在PATH中）: {error}", "Cannot start kubectl (make sure it is installed and on PATH): {error}"),
+    ("kubectl.failed", "kubectl执行失败: {error}", "kubectl failed: {error}"),
+    ("kubectl.invalid_namespace_list", "解析命名空间列表失败: {error}", "Failed to parse the namespace list: {error}"),
+    ("kubectl.invalid_pod_list", "解析Pod列表失败: {error}", "Failed to parse the pod list: {error}"),
+    ("kubectl.invalid_pod_info", "解析Pod信息失败: {error}", "Failed to parse the pod details: {error}"),
+    ("kubectl.label.namespace", "命名空间", "namespace"),
+    ("kubectl.label.pod", "Pod", "pod"),
+    ("kubectl.label.container", "容器", "container"),
+    ("kubectl.invalid_name", "无效的{label}名称: {name}", "Invalid {label} name: {name}"),
+    ("ssh.spawn_failed", "无法启动ssh（请确认已安装OpenSSH客户端）: {error}", "Cannot start ssh (make sure the OpenSSH client is installed): {error}"),
+    ("ssh.command_failed", "远程命令失败（{host}）: {error}", "Remote command failed ({host}): {error}"),
+    ("ssh.file_too_large", "远程文件 {path} 超过 {limit} 字节的读取上限", "Remote file {path} exceeds the read limit of {limit} bytes"),
+    ("ssh.no_output", "无法读取ssh输出", "Cannot read the ssh output"),
+    ("otlp.bind_failed", "无法监听端口 {port}: {error}", "Cannot listen on port {port}: {error}"),
+    ("otlp.nonblocking_failed", "设置监听模式失败: {error}", "Failed to configure the listener: {error}"),
+    ("otlp.local_addr_failed", "获取监听地址失败: {error}", "Failed to get the listening address: {error}"),
+    ("otlp.gzip_failed", "gzip解压失败: {error}", "Failed to decompress gzip data: {error}"),
+    ("otlp.invalid_json", "无效的OTLP JSON: {error}", "Invalid OTLP JSON: {error}"),
+    ("eventlog.invalid_channel", "无效的事件日志通道: {channel}", "Invalid event log channel: {channel}"),
+    ("eventlog.subscribe_failed", "订阅事件日志失败: {error}", "Failed to subscribe to the event log: {error}"),
+    ("eventlog.no_channels", "至少需要订阅一个事件日志通道", "Subscribe to at least one event log channel"),
+    ("eventlog.unsupported_platform", "Windows事件日志仅在Windows上可用", "The Windows event log is only available on Windows"),
+    ("issue.missing_token", "环境变量 {env} 中没有 {tracker} 的访问令牌", "No access token for {tracker} in environment variable {env}"),
+    ("issue.unrecognized_response", "{tracker} 返回了无法识别的响应", "{tracker} returned an unrecognized response"),
+    ("issue.read_response_failed", "读取 {tracker} 的响应失败: {error}", "Failed to read the response from {tracker}: {error}"),
+    ("issue.create_failed", "{tracker} 创建issue失败: HTTP {status} {detail}", "{tracker} failed to create the issue: HTTP {status} {detail}"),
+    ("issue.connect_failed", "连接 {tracker} 失败: {error}", "Failed to connect to {tracker}: {error}"),
+    ("issue.invalid_json", "{tracker} 返回了无效的JSON: {error}", "{tracker} returned invalid JSON: {error}"),
+    ("summary.model_error", "模型返回错误: {error}", "The model returned an error: {error}"),
+    ("summary.unrecognized_response", "模型返回了无法识别的响应", "The model returned an unrecognized response"),
+    ("summary.read_response_failed", "读取模型响应失败: {error}", "Failed to read the model response: {error}"),
+    ("summary.http_error", "模型服务返回错误: HTTP {status} {detail}", "The model service returned an error: HTTP {status} {detail}"),
+    ("summary.connect_failed", "连接模型服务 {url} 失败: {error}", "Failed to connect to the model service {url}: {error}"),
+    ("summary.invalid_json", "模型服务返回了无效的JSON: {error}", "The model service returned invalid JSON: {error}"),
+    ("summary.no_entries", "没有可摘要的日志条目", "There are no log entries to summarize"),
+    // 度量与导出
+    ("metrics.not_found", "文件中没有名为 {name} 的度量", "The file has no metric named {name}"),
+    ("metrics.invalid_threshold", "无效的离群点阈值: {threshold}", "Invalid outlier threshold: {threshold}"),
+    ("export.job_completed", "导出任务已完成", "The export job has already completed"),
+    ("export.job_running", "导出任务已在运行", "The export job is already running"),
+    ("export.source_changed", "日志文件已变化，无法续传导出任务: {path}", "The log file has changed; the export job cannot be resumed: {path}"),
+    ("export.job_unsupported_format", "导出任务不支持 {format} 格式或按记录导出，请使用CSV或纯文本格式", "Export jobs do not support the {format} format or record export; use CSV or plain text"),
+    ("export.read_output_failed", "读取导出文件失败: {error}", "Failed to read the export file: {error}"),
+    ("export.invalid_job_id", "无效的导出任务ID: {id}", "Invalid export job ID: {id}"),
+    ("export.serialize_job_failed", "序列化导出任务失败: {error}", "Failed to serialize the export job: {error}"),
+    ("export.read_job_failed", "读取导出任务失败: {path} ({error})", "Failed to read the export job: {path} ({error})"),
+    ("export.invalid_manifest", "导出任务清单格式无效: {error}", "Invalid export job manifest: {error}"),
+    ("export.manifest_too_new", "导出任务清单版本 {version} 过新", "Export job manifest version {version} is too new"),
+    ("export.read_job_directory_failed", "读取导出任务目录失败: {error}", "Failed to read the export job directory: {error}"),
+    ("export.delete_job_failed", "删除导出任务失败: {error}", "Failed to delete the export job: {error}"),
+    ("export.entry_count_mismatch", "筛选结果为 {actual} 条，与导出任务记录的 {expected} 条不一致", "The filter matched {actual} entries, but the export job recorded {expected}"),
+    ("export.open_output_failed", "打开导出文件失败: {error}", "Failed to open the export file: {error}"),
+    ("export.truncate_output_failed", "截断导出文件失败: {error}", "Failed to truncate the export file: {error}"),
+    ("export.write_output_failed", "写入导出文件失败: {error}", "Failed to write the export file: {error}"),
+    ("export.sync_output_failed", "同步导出文件失败: {error}", "Failed to flush the export file to disk: {error}"),
+    ("export.delete_running_job", "导出任务正在运行，无法删除", "The export job is running and cannot be deleted"),
+    ("export.lock_poisoned", "导出任务状态锁已损坏", "The export job state lock is poisoned"),
+    ("export.json_failed", "JSON序列化失败: {error}", "JSON serialization failed: {error}"),
+    ("export.invalid_delimiter", "无效的CSV分隔符: {delimiter}", "Invalid CSV delimiter: {delimiter}"),
+    ("split.too_many_partitions", "按 {key} 会拆分出 {count} 个文件，超过上限 {max}", "Splitting by {key} would create {count} files, more than the limit of {max}"),
+    ("split.missing_key", "拆分导出需要指定分组键", "Split export requires a grouping key"),
+    // 文件写入
+    ("write.outside_trusted_directories", "目标路径不在导出目录或已选择的目录中: {path}", "The target path is outside the export directory and the directories you selected: {path}"),
+    ("write.directory_not_found", "目录不存在: {path} ({error})", "Directory not found: {path} ({error})"),
+    ("write.not_a_directory", "路径不是目录: {path}", "Path is not a directory: {path}"),
+    ("write.path_not_absolute", "写入路径必须是绝对路径: {path}", "The output path must be absolute: {path}"),
+    ("write.missing_file_name", "写入路径缺少文件名: {path}", "The output path has no file name: {path}"),
+    ("write.invalid_path", "无效的写入路径: {path}", "Invalid output path: {path}"),
+    ("write.resolve_failed", "无法解析写入路径: {path} ({error})", "Failed to resolve the output path: {path} ({error})"),
+    ("write.target_is_directory", "写入路径是目录: {path}", "The output path is a directory: {path}"),
+    ("write.create_directory_failed", "创建目录失败: {error}", "Failed to create the directory: {error}"),
+    ("write.create_temp_failed", "创建临时文件失败: {error}", "Failed to create a temporary file: {error}"),
+    ("write.write_failed", "写入文件失败: {error}", "Failed to write the file: {error}"),
+    ("write.sync_failed", "同步文件失败: {error}", "Failed to flush the file to disk: {error}"),
+    ("write.replace_failed", "替换目标文件失败: {error}", "Failed to replace the target file: {error}"),
+    // 配置
+    ("locale.unsupported", "不支持的语言: {locale} (可用: {supported})", "Unsupported language: {locale} (available: {supported})"),
+    ("plugin_settings.no_schema", "插件 {plugin} 没有可配置项", "Plugin {plugin} has no configurable options"),
+    ("profile.unknown", "未知的解析配置: {name}", "Unknown parsing profile: {name}"),
+    ("profile.unknown_format", "解析配置 {name} 使用了未知的解析格式: {format}", "Parsing profile {name} uses an unknown parse format: {format}"),
+    ("profile.serialize_failed", "序列化解析配置失败: {error}", "Failed to serialize the parsing profile: {error}"),
+    ("profile.bundle_read_failed", "读取共享配置文件失败: {path} - {error}", "Failed to read the shared profile file: {path} - {error}"),
+    ("explain_rule.missing_id", "规则ID和标题不能为空", "The rule ID and title cannot be empty"),
+    ("explain_rule.no_patterns", "规则 {id} 至少需要一个匹配规则", "Rule {id} needs at least one pattern"),
+    ("explain_rule.invalid_pattern", "规则 {id} 的匹配规则无效: {error}", "Rule {id} has an invalid pattern: {error}"),
+    ("ingest.no_directories", "启用目录采集时至少需要配置一个目录", "Configure at least one directory to enable directory ingestion"),
+    ("ingest.empty_session", "目录采集的会话名称不能为空", "The directory ingestion session name cannot be empty"),
+    ("ingest.invalid_retention", "无效的容量上限: {value}", "Invalid size limit: {value}"),
+    ("issue_config.invalid_github_project", "GitHub项目应为 owner/repo: {project}", "GitHub projects must be owner/repo: {project}"),
+    ("config.invalid_endpoint", "无效的服务地址: {endpoint}", "Invalid service URL: {endpoint}"),
+    ("issue_config.empty_name", "issue跟踪系统名称不能为空", "The issue tracker name cannot be empty"),
+    ("issue_config.missing_token_env", "需要指定存放访问令牌的环境变量名", "Specify the environment variable that holds the access token"),
+    ("issue_config.empty_jira_project", "Jira项目键不能为空", "The Jira project key cannot be empty"),
+    ("issue_config.missing_jira_endpoint", "Jira需要配置服务地址", "Jira requires a service URL"),
+    ("memory.budget_too_small", "内存预算不能小于 {min} MB", "The memory budget cannot be less than {min} MB"),
+    ("palette.invalid_shortcut", "快捷键格式无效: {shortcut}", "Invalid shortcut: {shortcut}"),
+    ("palette.incomplete_shortcut", "快捷键需要包含修饰键和一个普通键: {shortcut}", "A shortcut needs a modifier and a regular key: {shortcut}"),
+    ("profile.empty_name", "解析配置名称不能为空", "The parsing profile name cannot be empty"),
+    ("profile.empty_field_name", "解析配置 {name} 中的派生字段名称不能为空", "Parsing profile {name} has a derived field without a name"),
+    ("profile.invalid_field_pattern", "派生字段 {field} 的正则无效: {error}", "Derived field {field} has an invalid regex: {error}"),
+    ("profile.highlight_missing_color", "高亮规则 {pattern} 缺少颜色", "Highlight rule {pattern} has no color"),
+    ("profile.invalid_highlight_pattern", "高亮规则的正则无效: {error}", "Highlight rule has an invalid regex: {error}"),
+    ("profile.bundle_invalid_json", "共享配置文件不是有效的JSON: {error}", "The shared profile file is not valid JSON: {error}"),
+    ("profile.bundle_wrong_format", "不是LogWhisper共享配置文件（缺少 \"format\": \"{format}\"）", "Not a LogWhisper shared profile file (missing \"format\": \"{format}\")"),
+    ("profile.bundle_invalid", "共享配置文件结构无效: {error}", "The shared profile file has an invalid structure: {error}"),
+    ("profile.bundle_version_unsupported", "不支持的共享配置文件版本 {version}（当前支持 {supported}），请升级LogWhisper", "Unsupported shared profile file version {version} (supported: {supported}); please upgrade LogWhisper"),
+    ("profile.bundle_duplicate", "共享配置文件中有重名的解析配置: {name}", "The shared profile file has duplicate parsing profiles: {name}"),
+    ("profile.plugin_settings_not_object", "插件 {plugin} 的设置必须是对象", "The settings of plugin {plugin} must be an object"),
+    ("remote_config.label.host", "主机名", "host name"),
+    ("remote_config.label.user", "用户名", "user name"),
+    ("remote_config.invalid_field", "无效的{label}: {value}", "Invalid {label}: {value}"),
+    ("remote_config.empty_name", "远程主机名称不能为空", "The remote host name cannot be empty"),
+    ("summary_config.batch_too_small", "每批字符数过小: {value}", "Characters per batch is too small: {value}"),
+    ("summary_config.empty_model", "模型名称不能为空", "The model name cannot be empty"),
+    ("summary_config.invalid_limits", "最大条目数和超时时间必须大于0", "The maximum entry count and timeout must be greater than 0"),
+    ("theme.invalid_level", "无效的级别名: {level}（只能包含字母、数字、下划线和连字符）", "Invalid level name: {level} (only letters, digits, underscores and hyphens are allowed)"),
+    ("theme.invalid_color", "无效的颜色: {color}（应为 #rgb 或 #rrggbb）", "Invalid color: {color} (expected #rgb or #rrggbb)"),
+    ("theme.level_missing_color", "新级别 {level} 需要指定颜色", "The new level {level} needs a color"),
+    ("config.theme_unavailable", "获取主题配置失败", "Failed to get the theme settings"),
+    ("config.current_theme_unavailable", "获取当前主题配置失败", "Failed to get the current theme settings"),
+    ("config.theme_updated", "主题配置更新成功", "Theme settings updated"),
+    ("config.theme_update_failed", "更新主题配置失败", "Failed to update the theme settings"),
+    ("config.parse_unavailable", "获取解析配置失败", "Failed to get the parse settings"),
+    ("config.plugin_unavailable", "获取插件配置失败", "Failed to get the plugin settings"),
+    ("config.window_unavailable", "获取窗口配置失败", "Failed to get the window settings"),
+    ("config.all_unavailable", "获取所有配置失败", "Failed to get the settings"),
+    ("knowledge_base.invalid_line", "知识库第 {line} 行: 无法识别的缩进或格式", "Knowledge base line {line}: unrecognized indentation or format"),
+    ("knowledge_base.rule_not_started", "知识库第 {line} 行: 规则需要以 `- ` 开头", "Knowledge base line {line}: rules must start with `- `"),
+    ("knowledge_base.orphan_item", "知识库第 {line} 行: 列表项不属于任何列表字段", "Knowledge base line {line}: the list item does not belong to a list field"),
+    ("knowledge_base.expected_key_value", "知识库第 {line} 行: 需要 `键: 值`", "Knowledge base line {line}: expected `key: value`"),
+    ("knowledge_base.unknown_field", "知识库第 {line} 行: 未知字段 {key}", "Knowledge base line {line}: unknown field {key}"),
+    ("knowledge_base.compile_failed", "编译错误解释规则失败: {error}", "Failed to compile the error explanation rules: {error}"),
+    // 压缩包与下载
+    ("archive.unsupported_format", "不支持的压缩包格式: {path}", "Unsupported archive format: {path}"),
+    ("archive.open_failed", "打开压缩包失败: {error}", "Failed to open the archive: {error}"),
+    ("archive.zip_read_failed", "读取zip失败: {error}", "Failed to read the zip archive: {error}"),
+    ("archive.tar_read_failed", "读取tar失败: {error}", "Failed to read the tar archive: {error}"),
+    ("archive.extract_failed", "解压 {member} 失败: {error}", "Failed to extract {member}: {error}"),
+    ("archive.member_too_large", "压缩包成员 {member} 超过 {limit} 字节的读取上限", "Archive member {member} exceeds the read limit of {limit} bytes"),
+    ("archive.member_not_found", "压缩包中没有文件: {member}", "The archive has no file named {member}"),
+    ("download.invalid_url", "不是有效的HTTP链接: {url}", "Not a valid HTTP URL: {url}"),
+    ("download.create_directory_failed", "创建下载目录失败: {error}", "Failed to create the download directory: {error}"),
+    ("download.http_error", "下载失败: HTTP {status} {reason}", "Download failed: HTTP {status} {reason}"),
+    ("download.failed", "下载失败: {error}", "Download failed: {error}"),
+    ("download.create_file_failed", "创建下载文件失败: {error}", "Failed to create the download file: {error}"),
+    ("download.interrupted", "下载中断: {error}", "The download was interrupted: {error}"),
+    ("download.write_failed", "写入下载文件失败: {error}", "Failed to write the download file: {error}"),
+    ("download.incomplete", "下载不完整: {downloaded}/{total} 字节，可重试续传", "Incomplete download: {downloaded}/{total} bytes; retry to resume"),
+    ("download.save_failed", "保存下载文件失败: {error}", "Failed to save the downloaded file: {error}"),
+    // 解析结果数据库
+    ("store.create_directory_failed", "创建数据库目录失败: {error}", "Failed to create the database directory: {error}"),
+    ("store.open_failed", "打开解析结果数据库失败: {path} ({error})", "Failed to open the parse result database: {path} ({error})"),
+    ("store.version_too_new", "解析结果数据库版本 {version} 高于当前支持的版本 {supported}，请升级LogWhisper", "Parse result database version {version} is newer than the supported version {supported}; please upgrade LogWhisper"),
+    ("store.lock_failed", "解析结果数据库锁失败", "Failed to lock the parse result database"),
+    ("store.serialize_entry_failed", "序列化条目失败: {error}", "Failed to serialize the entry: {error}"),
+    ("store.not_saved", "文件 {path} 的解析结果未保存", "No saved parse result for {path}"),
+    ("store.sql_failed", "解析结果数据库操作失败: {error}", "Parse result database operation failed: {error}"),
+    // 日志系统
+    ("logging.unsupported_format", "不支持的日志格式: {format}（可用: compact, json）", "Unsupported log format: {format} (available: compact, json)"),
+    ("logging.invalid_directives", "无效的日志级别设置 '{directives}': {error}", "Invalid log level setting '{directives}': {error}"),
+    ("logging.invalid_level", "无效的日志级别: {level}（可用: {available}）", "Invalid log level: {level} (available: {available})"),
+    ("logging.create_directory_failed", "创建日志目录失败: {error}", "Failed to create the log directory: {error}"),
+    ("logging.create_file_failed", "创建日志文件失败: {error}", "Failed to create the log file: {error}"),
+    ("logging.set_global_failed", "设置全局日志器失败: {error}", "Failed to install the global logger: {error}"),
+    ("logging.bridge_failed", "转发log日志失败: {error}", "Failed to forward log records: {error}"),
+    ("logging.reload_failed", "更新日志级别失败: {error}", "Failed to update the log level: {error}"),
+    ("logging.empty_level", "日志级别不能为空", "The log level cannot be empty"),
+    ("logging.already_initialized", "日志系统已经初始化", "Logging is already initialized"),
+    ("logging.not_initialized", "日志系统未初始化", "Logging is not initialized"),
+    ("logging.lock_failed", "日志状态锁失败", "Failed to lock the logging state"),
+    // 插件
+    ("chain.file_too_large", "插件链 '{chain}' 声明最多处理 {max} MB，当前文件 {size} MB，解析可能很慢或不完整", "Plugin chain '{chain}' supports files up to {max} MB, but this file is {size} MB; parsing may be slow or incomplete"),
+    ("chain.disabled", "插件链 '{chain}' 已禁用", "Plugin chain '{chain}' is disabled"),
+    ("chain.conditions_not_met", "插件链 '{chain}' 执行条件不匹配", "Plugin chain '{chain}' conditions do not match"),
+    ("chain.filter_failed", "过滤器 '{filter}' 失败: {error}", "Filter '{filter}' failed: {error}"),
+    ("chain.line_failed", "第{line}行解析失败: {error}", "Failed to parse line {line}: {error}"),
+    ("chain.not_found", "插件链 '{chain}' 不存在", "Plugin chain '{chain}' does not exist"),
+    ("chain.none_matched", "没有找到合适的处理链", "No suitable plugin chain was found"),
+    ("compact.invalid_rule", "无效的缩略规则 {pattern}: {error}", "Invalid abbreviation rule {pattern}: {error}"),
+    ("conformance.read_fixtures_failed", "读取样本目录失败: {path} ({error})", "Failed to read the fixture directory: {path} ({error})"),
+    ("plugin.not_found", "插件 '{plugin}' 不存在，可用插件: {available}", "Plugin '{plugin}' does not exist; available plugins: {available}"),
+    ("conformance.read_fixture_failed", "读取样本失败: {path} ({error})", "Failed to read the fixture: {path} ({error})"),
+    ("conformance.invalid_expected", "期望文件格式无效: {path} ({error})", "Invalid expected output file: {path} ({error})"),
+    ("conformance.read_expected_failed", "读取期望文件失败: {path} ({error})", "Failed to read the expected output file: {path} ({error})"),
+    ("conformance.create_directory_failed", "创建期望文件目录失败: {error}", "Failed to create the expected output directory: {error}"),
+    ("conformance.serialize_failed", "序列化期望输出失败: {error}", "Failed to serialize the expected output: {error}"),
+    ("chain.not_found_with_available", "插件链 '{chain}' 不存在，可用链: {available}", "Plugin chain '{chain}' does not exist; available chains: {available}"),
+    ("chain.manager_init_failed", "插件链管理器初始化失败", "Failed to initialize the plugin chain manager"),
+    ("chain.manager_lock_failed", "无法获取插件链管理器锁", "Failed to lock the plugin chain manager"),
+    ("chain.system_disabled", "插件链系统已禁用", "The plugin chain system is disabled"),
+    ("parse.invalid_json", "JSON解析失败: {error}", "Failed to parse JSON: {error}"),
+    ("springboot.regex_failed", "SpringBoot正则表达式编译失败: {error}", "Failed to compile the SpringBoot regex: {error}"),
+    ("renderer.not_found", "渲染器 '{renderer}' 不存在，可用渲染器: {available}", "Renderer '{renderer}' does not exist; available renderers: {available}"),
+    ("layout.no_specifiers", "布局模式中没有可识别的转换说明符: {pattern}", "The layout pattern has no recognized conversion specifiers: {pattern}"),
+    ("layout.invalid_regex", "布局模式生成的正则表达式无效: {error}", "The layout pattern produced an invalid regex: {error}"),
+    ("layout.unbalanced_parentheses", "说明符 %{name} 的括号不匹配", "Specifier %{name} has unbalanced parentheses"),
+    ("layout.unclosed_option", "说明符 %{name} 的选项缺少匹配的 }", "The options of specifier %{name} are missing a closing }"),
+    ("layout.empty", "布局模式不能为空", "The layout pattern cannot be empty"),
+    ("layout.unclosed_property", "布局模式中的 ${ 缺少匹配的 }", "A ${ in the layout pattern is missing its closing }"),
+    ("script.failed", "脚本钩子在 {count} 行执行失败，第 {line} 行: {error}", "The script hook failed on {count} lines; line {line}: {error}"),
+    ("plugin_settings.unknown_option", "未知的配置项: {key}", "Unknown option: {key}"),
+    ("plugin_settings.wrong_type", "配置项 {key} 的类型应为 {expected}", "Option {key} must be of type {expected}"),
+    ("plugin_settings.invalid_regex", "配置项 {key} 的正则表达式无效: {error}", "Option {key} has an invalid regex: {error}"),
+    ("plugin_settings.invalid_layout", "配置项 {key} 的布局模式无效: {error}", "Option {key} has an invalid layout pattern: {error}"),
+    ("plugin_settings.invalid_script", "配置项 {key} 的脚本无效: {error}", "Option {key} has an invalid script: {error}"),
+    ("plugin_settings.not_object", "插件设置必须是JSON对象", "Plugin settings must be a JSON object"),
+    ("validation.invalid_regex", "正则表达式无效: {error}", "Invalid regular expression: {error}"),
+    // 分享片段
+    ("snippet.title", "日志片段: {source}", "Log snippet: {source}"),
+    ("snippet.source", "来源", "Source"),
+    ("snippet.lines", "行", "Lines"),
+    ("snippet.levels", "级别", "Levels"),
+    ("snippet.time_range", "时间范围", "Time range"),
+    ("snippet.field", "字段", "Field"),
+    ("snippet.values", "值", "Values"),
+    ("snippet.unsupported_format", "不支持的片段格式: {format}（可用: html, markdown）", "Unsupported snippet format: {format} (available: html, markdown)"),
+    ("snippet.lines_not_found", "选中的行在文件中不存在", "The selected lines do not exist in the file"),
+    // 调查报告
+    ("report.title", "调查报告: {source}", "Investigation report: {source}"),
+    ("report.generated_at", "生成时间", "Generated at"),
+    ("report.summary", "概要", "Summary"),
+    ("report.error_clusters", "错误聚类", "Error clusters"),
+    ("report.timeline", "时间线", "Timeline"),
+    ("report.anomalies", "异常区段", "Anomalies"),
+    ("report.bookmarks", "书签与备注", "Bookmarks and notes"),
+    ("report.entries", "条目数", "Entries"),
+    ("report.count", "次数", "Count"),
+    ("report.message", "错误", "Error"),
+    ("report.error_burst", "{count} 条错误", "{count} errors"),
+    ("report.note", "备注", "Note"),
+    ("report.none", "无", "None"),
+    ("report.unsupported_format", "不支持的报告格式: {format}（可用: markdown, html）", "Unsupported report format: {format} (available: markdown, html)"),
+    ("report.unknown_placeholders", "报告模板中有未知的占位符: {placeholders}", "The report template has unknown placeholders: {placeholders}"),
+    // issue集成
+    ("issue.analysis", "分析概要", "Analysis summary"),
+    // 过滤器描述
+    ("filter.docker_json", "Docker JSON日志解析过滤器，解析容器JSON格式日志并提取内容", "Docker JSON filter: decodes container JSON log lines and extracts the message"),
+    ("filter.springboot", "SpringBoot应用日志解析过滤器，提取时间戳、级别、线程等结构化信息", "Spring Boot filter: extracts timestamp, level, thread and other fields"),
+    ("filter.mybatis", "MyBatis SQL日志过滤器，识别和格式化SQL语句及相关参数", "MyBatis filter: recognizes and formats SQL statements and parameters"),
+    ("filter.json_structure", "JSON结构化过滤器，将日志行统一格式化为JSON结构", "JSON structure filter: normalizes log lines into a JSON structure"),
+    ("filter.java_log", "Java应用日志过滤器，处理GC日志、JVM日志等Java应用日志", "Java log filter: handles GC, JVM and other Java application logs"),
+    ("filter.content_enhancer", "内容增强过滤器，提供高亮、链接识别等增强功能", "Content enhancer: marks URLs, e-mail addresses and errors"),
+    ("filter.carriage_return", "回车覆盖行折叠过滤器，将进度条等被\\r覆盖的片段折叠为最终显示内容", "Carriage-return filter: collapses progress bars overwritten with \\r into their final text"),
+    ("filter.ansi", "ANSI转义序列过滤器，移除终端颜色控制码并可将颜色记录到元数据", "ANSI filter: strips terminal escape codes and can record colors as metadata"),
+    ("filter.test_runner", "测试运行器输出过滤器，识别cargo test、go test和pytest的测试名称与结果", "Test runner filter: recognizes test names and results from cargo test, go test and pytest"),
+    ("filter.ci_log", "CI平台日志过滤器，解析GitHub Actions和GitLab CI的时间戳、分组和错误标记", "CI log filter: parses GitHub Actions and GitLab CI timestamps, groups and error markers"),
+    ("filter.compose", "Docker Compose日志过滤器，拆分多服务合并输出的服务前缀并记录服务名", "Docker Compose filter: splits the service prefix from merged multi-service output"),
+    ("filter.heroku", "Heroku路由日志过滤器，解析logfmt字段、按状态码映射级别并提取dyno和耗时度量", "Heroku router filter: parses logfmt fields, maps status codes to levels and extracts dyno and timing metrics"),
+    ("filter.lambda", "AWS Lambda日志过滤器，按START..REPORT分组调用并提取耗时和内存", "AWS Lambda filter: groups invocations from START to REPORT and extracts duration and memory"),
+    ("filter.nginx_error", "Nginx错误日志过滤器，提取级别、进程号、连接号和上游字段，并对上游错误分类", "Nginx error log filter: extracts level, pid, connection and upstream fields and classifies upstream errors"),
+    ("filter.haproxy", "HAProxy HTTP日志过滤器，提取前后端、计时器和状态码，并解释终止状态", "HAProxy filter: extracts frontend, backend, timers and status and explains termination states"),
+    ("filter.envoy", "Envoy/Istio访问日志过滤器，提取上游集群、耗时和请求ID，并解释响应标志", "Envoy/Istio access log filter: extracts upstream cluster, duration and request ID and explains response flags"),
+    ("filter.kafka", "Kafka服务端日志过滤器，提取组件、分区引用，并标记ISR变化和再均衡等事件", "Kafka broker log filter: extracts components and partitions and marks ISR changes and rebalances"),
+    ("filter.elasticsearch", "Elasticsearch/OpenSearch日志过滤器，提取节点、组件和索引，并标记GC与慢日志", "Elasticsearch/OpenSearch filter: extracts node, component and index and marks GC and slow logs"),
+    ("filter.freb", "IIS失败请求跟踪过滤器，把FREB XML展开为带事件名称和耗时的时间线", "IIS failed request tracing filter: expands FREB XML into a timeline of named, timed events"),
+    ("filter.cef", "CEF/LEEF安全日志过滤器，解析头部的厂商、产品和严重程度以及扩展键值对", "CEF/LEEF filter: parses vendor, product and severity headers and extension key-value pairs"),
+    ("filter.pattern_layout", "布局模式过滤器，按Logback/Log4j的布局模式自动生成解析规则", "Pattern layout filter: builds parsing rules from a Logback/Log4j layout pattern"),
+    ("filter.metrics", "度量提取过滤器，识别 key=value 形式的数值度量和耗时，并汇总为序列", "Metrics filter: recognizes numeric key=value metrics and durations and collects them into series"),
+    ("filter.wrapped_lines", "终端折行合并过滤器，将按控制台宽度折断的消息片段拼接回前一个条目", "Wrapped line filter: joins message fragments wrapped at the console width back onto the previous entry"),
+    ("filter.script", "脚本钩子过滤器，对每个条目执行用户的Rhai脚本以派生字段、改写消息或丢弃噪音行", "Script hook filter: runs the user's Rhai script on each entry to derive fields, rewrite messages or drop noisy lines"),
+];
+
+/// 查找消息模板
+fn lookup(code: &str, locale: Locale) -> Option<&'static str> {
+    CATALOG.iter().find(|(c, _, _)| *c == code).map(|(_, zh, en)| match locale {
+        Locale::EnUs => *en,
+        _ => *zh,
+    })
+}
+
+/// 获取指定语言的完整消息目录（`code` 语言返回中文模板）
+///
+/// 前端可以用它把 `code` 模式下收到的消息代码翻译为文本。
+pub fn catalog(locale: Locale) -> BTreeMap<&'static str, &'static str> {
+    CATALOG.iter().map(|(code, _, _)| (*code, lookup(code, locale).unwrap_or(code))).collect()
+}
+
+/// 按当前语言翻译消息代码，目录中不存在时返回 `fallback`
+pub fn tr_or(code: &str, fallback: &str) -> String {
+    match current_locale() {
+        Locale::Code => code.to_string(),
+        locale => lookup(code, locale).unwrap_or(fallback).to_string(),
+    }
+}
+
+/// 带参数的可本地化消息
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Message {
+    /// 消息代码（如 "file.not_found"）
+    pub code: String,
+
+    /// 模板参数
+    pub params: BTreeMap<String, String>,
+}
+
+impl Message {
+    /// 创建不带参数的消息
+    pub fn new(code: &str) -> Self {
+        Self {
+            code: code.to_string(),
+            params: BTreeMap::new(),
+        }
+    }
+
+    /// 添加模板参数
+    pub fn with(mut self, name: &str, value: impl ToString) -> Self {
+        self.params.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    /// 按指定语言渲染消息文本
+    pub fn localize(&self, locale: Locale) -> String {
+        if locale == Locale::Code {
+            return serde_json::to_string(self).unwrap_or_else(|_| self.code.clone());
+        }
+
+        let Some(template) = lookup(&self.code, locale) else {
+            return self.code.clone();
+        };
+
+        self.params.iter().fold(template.to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{}}}", name), value)
+        })
+    }
+
+    /// 按当前语言渲染消息文本
+    pub fn text(&self) -> String {
+        self.localize(current_locale())
+    }
+}
+
+impl std::fmt::Display for Message {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.text())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_localize_with_params() {
+        let message = Message::new("line.out_of_range").with("line", 12).with("total", 10);
+        assert_eq!(message.localize(Locale::ZhCn), "行号超出范围: 12 (文件共 10 行)");
+        assert_eq!(message.localize(Locale::EnUs), "Line number out of range: 12 (file has 10 lines)");
+
+        let code: Message = serde_json::from_str(&message.localize(Locale::Code)).unwrap();
+        assert_eq!(code, message);
+    }
+
+    #[test]
+    fn test_catalog_is_complete() {
+        for (code, zh, en) in CATALOG {
+            assert!(!zh.is_empty() && !en.is_empty(), "{}", code);
+        }
+        assert_eq!(catalog(Locale::EnUs).len(), CATALOG.len());
+        assert_eq!(Message::new("unknown.code").localize(Locale::EnUs), "unknown.code");
+    }
+
+    #[test]
+    fn test_module_errors_follow_locale() {
+        let dir = std::env::temp_dir().join(format!("logwhisper_i18n_{}", std::process::id()));
+        let errors = || {
+            (
+                crate::export::job::ExportJobStore::new(dir.clone()).load("../x").unwrap_err(),
+                crate::reader::archive::list_entries("app.log").unwrap_err(),
+                crate::jobs::JobQueue::new(1).cancel("missing").unwrap_err(),
+                crate::session::SessionStore::new(dir.clone()).session_path(" ").unwrap_err(),
+            )
+        };
+
+        let (export, archive, job, session) = with_locale(Locale::EnUs, errors);
+        assert_eq!(export, "Invalid export job ID: ../x");
+        assert_eq!(archive, "Unsupported archive format: app.log");
+        assert_eq!(job, "Task not found: missing");
+        assert_eq!(session, "The session name cannot be empty");
+
+        let (_, _, job, _) = errors();
+        assert_eq!(job, "任务不存在: missing");
+    }
+
+    #[test]
+    fn test_parse_locale() {
+        assert_eq!(Locale::parse("en").unwrap(), Locale::EnUs);
+        assert_eq!(Locale::parse("zh_CN").unwrap(), Locale::ZhCn);
+        assert!(Locale::parse("fr").is_err());
+    }
+}
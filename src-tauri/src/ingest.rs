@@ -0,0 +1,197 @@
+/// 目录采集（本地日志服务模式）
+///
+/// 后台任务按 `IngestSettings.poll_interval_secs` 递归扫描配置的目录，
+/// 大小或前缀指纹变化的文件增量解析后写入会话数据库（`EntryStore`），
+/// 每轮扫描后按保留天数和容量上限删除最旧的文件，形成可跨重启查询的滚动窗口。
+///
+/// 修改设置时代数加一，旧的采集任务在下一轮检查时退出，由新任务按新设置继续。
+
+use crate::config::IngestSettings;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use walkdir::WalkDir;
+
+/// 目录采集状态
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IngestStatus {
+    /// 采集任务是否在运行
+    pub running: bool,
+    /// 最近一次扫描完成时间（RFC 3339）
+    pub last_scan_at: Option<String>,
+    /// 最近一次扫描匹配的文件数
+    pub scanned_files: usize,
+    /// 最近一次扫描写入（新增或更新）的文件数
+    pub ingested_files: usize,
+    /// 启动以来按保留策略删除的文件数
+    pub pruned_files: usize,
+    /// 数据库中保存的文件数
+    pub stored_files: usize,
+    /// 数据库中保存的文件原始大小合计（字节）
+    pub stored_bytes: u64,
+    /// 最近一次扫描的错误（无错误时为None）
+    pub last_error: Option<String>,
+}
+
+/// 单轮扫描的结果
+#[derive(Debug, Clone, Default)]
+pub struct ScanReport {
+    /// 匹配的文件数
+    pub scanned_files: usize,
+    /// 写入（新增或更新）的文件数
+    pub ingested_files: usize,
+    /// 按保留策略删除的文件数
+    pub pruned_files: usize,
+    /// 扫描后数据库中保存的文件数
+    pub stored_files: usize,
+    /// 扫描后数据库中保存的文件原始大小合计（字节）
+    pub stored_bytes: u64,
+    /// 单个文件或数据库操作的错误
+    pub errors: Vec<String>,
+}
+
+/// 目录采集服务：记录采集任务的代数和状态
+pub struct IngestService {
+    generation: AtomicU64,
+    status: Mutex<IngestStatus>,
+}
+
+impl IngestService {
+    pub fn new() -> Self {
+        Self {
+            generation: AtomicU64::new(0),
+            status: Mutex::new(IngestStatus::default()),
+        }
+    }
+
+    /// 停止当前采集任务（代数加一），返回新任务应使用的代数
+    pub fn restart(&self) -> u64 {
+        let generation = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
+        self.update(|status| status.running = false);
+        generation
+    }
+
+    /// 采集任务的代数是否仍是最新的
+    pub fn is_current(&self, generation: u64) -> bool {
+        self.generation.load(Ordering::SeqCst) == generation
+    }
+
+    /// 标记采集任务开始或停止
+    pub fn set_running(&self, running: bool) {
+        self.update(|status| status.running = running);
+    }
+
+    /// 记录一轮扫描的结果
+    pub fn record_scan(&self, report: &ScanReport) {
+        self.update(|status| {
+            status.last_scan_at = Some(chrono::Utc::now().to_rfc3339());
+            status.scanned_files = report.scanned_files;
+            status.ingested_files = report.ingested_files;
+            status.pruned_files += report.pruned_files;
+            status.stored_files = report.stored_files;
+            status.stored_bytes = report.stored_bytes;
+            status.last_error = (!report.errors.is_empty()).then(|| report.errors.join("; "));
+        });
+    }
+
+    /// 当前状态
+    pub fn status(&self) -> IngestStatus {
+        self.status.lock().map(|status| status.clone()).unwrap_or_default()
+    }
+
+    fn update(&self, f: impl FnOnce(&mut IngestStatus)) {
+        if let Ok(mut status) = self.status.lock() {
+            f(&mut status);
+        }
+    }
+}
+
+impl Default for IngestService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 轮转后压缩的文件扩展名，不能按文本行解析，采集时跳过
+const COMPRESSED_EXTENSIONS: &[&str] = &["gz", "zip", "tar", "tgz", "bz2", "xz", "zst"];
+
+/// 递归查找配置目录中文件名匹配规则的文件（按路径排序，去重）
+///
+/// 不存在或无法读取的目录、压缩文件跳过。
+pub fn find_files(settings: &IngestSettings) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = settings.folders.iter()
+        .filter(|folder| !folder.trim().is_empty())
+        .flat_map(|folder| WalkDir::new(folder.trim()).follow_links(true).into_iter().filter_map(Result::ok))
+        .filter(|entry| entry.file_type().is_file())
+        .filter(|entry| {
+            let extension = entry.path().extension().map(|ext| ext.to_string_lossy().to_ascii_lowercase());
+            !extension.is_some_and(|ext| COMPRESSED_EXTENSIONS.contains(&ext.as_str()))
+        })
+        .filter(|entry| {
+            let name = entry.file_name().to_string_lossy();
+            settings.patterns.is_empty() || settings.patterns.iter().any(|pattern| wildcard_match(pattern, &name))
+        })
+        .map(|entry| entry.into_path())
+        .collect();
+    files.sort();
+    files.dedup();
+    files
+}
+
+/// 通配符匹配（`*` 匹配任意个字符，`?` 匹配单个字符）
+pub fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            // 让上一个 `*` 多匹配一个字符
+            p = star + 1;
+            t = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_files_by_pattern() {
+        assert!(wildcard_match("*.log", "app.log"));
+        assert!(wildcard_match("*.log.*", "app.log.2024-01-15.gz"));
+        assert!(wildcard_match("app-?.log", "app-1.log"));
+        assert!(!wildcard_match("*.log", "app.log.1"));
+        assert!(!wildcard_match("app-?.log", "app-10.log"));
+
+        let dir = std::env::temp_dir().join(format!("log_whisper_ingest_{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("nested")).unwrap();
+        for name in ["app.log", "nested/worker.log.1", "nested/worker.log.2.gz", "notes.txt"] {
+            std::fs::write(dir.join(name), "line\n").unwrap();
+        }
+
+        let settings = IngestSettings {
+            folders: vec![dir.to_string_lossy().into_owned(), "/nonexistent/log_whisper".to_string()],
+            ..Default::default()
+        };
+        let found: Vec<_> = find_files(&settings).into_iter()
+            .map(|path| path.strip_prefix(&dir).unwrap().to_string_lossy().replace('\\', "/"))
+            .collect();
+        assert_eq!(found, vec!["app.log", "nested/worker.log.1"]);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,217 @@
+/// issue创建集成（GitHub Issues / Jira）
+///
+/// 把选中的日志条目（脱敏后的分享片段）和文件的分析概要组成issue正文，
+/// 通过GitHub REST API（`POST /repos/{owner}/{repo}/issues`）或
+/// Jira REST API v2（`POST /rest/api/2/issue`）创建issue，免去手工复制日志到工单。
+///
+/// GitHub使用 `Bearer` 令牌认证；Jira配置了账号邮箱时使用基本认证（Jira Cloud的API令牌），
+/// 否则使用 `Bearer` 认证（Jira Data Center的个人访问令牌）。
+
+use crate::config::issues::{IssueTracker, TrackerKind};
+use crate::i18n::{current_locale, Locale, Message};
+use crate::utils::string_utils::truncate_chars;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::time::Duration;
+
+/// GitHub issue正文的最大字符数
+const GITHUB_MAX_BODY_CHARS: usize = 65_000;
+
+/// Jira描述字段的最大字符数
+const JIRA_MAX_BODY_CHARS: usize = 32_000;
+
+/// issue草稿（标题和脱敏后的正文）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssueDraft {
+    /// 标题
+    pub title: String,
+    /// Markdown正文
+    pub body: String,
+}
+
+/// 已创建的issue
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatedIssue {
+    /// issue跟踪系统名称
+    pub tracker: String,
+    /// issue编号（GitHub为 `#123`，Jira为 `OPS-123`）
+    pub key: String,
+    /// issue页面链接
+    pub url: String,
+}
+
+/// 正文标签使用的语言（`code` 模式下使用中文，issue是给人看的文档）
+fn label_locale() -> Locale {
+    match current_locale() {
+        Locale::Code => Locale::ZhCn,
+        locale => locale,
+    }
+}
+
+/// 组合issue正文：选中条目的片段在前，分析概要在后
+pub fn compose_body(snippet: &str, summary: &str) -> String {
+    format!("{}\n\n### {}\n\n{}\n", snippet.trim_end(), Message::new("issue.analysis").localize(label_locale()), summary.trim())
+}
+
+/// 从环境变量读取访问令牌
+pub fn read_token(tracker: &IssueTracker) -> Result<String, String> {
+    std::env::var(&tracker.token_env)
+        .ok()
+        .filter(|token| !token.trim().is_empty())
+        .ok_or_else(|| Message::new("issue.missing_token").with("env", &tracker.token_env).with("tracker", &tracker.name).text())
+}
+
+/// 创建issue的请求地址和JSON负载
+pub fn issue_request(tracker: &IssueTracker, draft: &IssueDraft) -> (String, Value) {
+    let base = tracker.base_url();
+    match tracker.kind {
+        TrackerKind::Github => {
+            let labels: Vec<&str> = tracker.issue_type.as_deref().unwrap_or("")
+                .split(',')
+                .map(str::trim)
+                .filter(|label| !label.is_empty())
+                .collect();
+            let mut payload = json!({
+                "title": draft.title,
+                "body": truncate_chars(&draft.body, GITHUB_MAX_BODY_CHARS),
+            });
+            if !labels.is_empty() {
+                payload["labels"] = json!(labels);
+            }
+            (format!("{}/repos/{}/issues", base, tracker.project.trim()), payload)
+        }
+        TrackerKind::Jira => {
+            let payload = json!({
+                "fields": {
+                    "project": { "key": tracker.project.trim() },
+                    "summary": draft.title,
+                    "description": truncate_chars(&draft.body, JIRA_MAX_BODY_CHARS),
+                    "issuetype": { "name": tracker.issue_type.as_deref().unwrap_or("Bug") },
+                }
+            });
+            (format!("{}/rest/api/2/issue", base), payload)
+        }
+    }
+}
+
+/// 解析创建issue的响应
+fn created_issue(tracker: &IssueTracker, response: &Value) -> Result<CreatedIssue, String> {
+    let (key, url) = match tracker.kind {
+        TrackerKind::Github => (
+            response["number"].as_u64().map(|number| format!("#{}", number)),
+            response["html_url"].as_str().map(str::to_string),
+        ),
+        TrackerKind::Jira => {
+            let key = response["key"].as_str().map(str::to_string);
+            let url = key.as_ref().map(|key| format!("{}/browse/{}", tracker.base_url(), key));
+            (key, url)
+        }
+    };
+    match (key, url) {
+        (Some(key), Some(url)) => Ok(CreatedIssue { tracker: tracker.name.clone(), key, url }),
+        _ => Err(Message::new("issue.unrecognized_response").with("tracker", &tracker.name).text()),
+    }
+}
+
+/// Base64编码（用于基本认证头）
+fn base64_encode(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = (chunk[0] as u32) << 16 | (*chunk.get(1).unwrap_or(&0) as u32) << 8 | *chunk.get(2).unwrap_or(&0) as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// 创建issue（阻塞调用）
+///
+/// # 参数
+/// - `tracker`: issue跟踪系统配置
+/// - `token`: 访问令牌
+/// - `draft`: 标题和正文
+///
+/// # Returns
+/// - `Ok(CreatedIssue)`: issue编号和链接
+/// - `Err(String)`: 网络错误或服务返回错误状态时的错误信息
+pub fn create_issue(tracker: &IssueTracker, token: &str, draft: &IssueDraft) -> Result<CreatedIssue, String> {
+    let (url, payload) = issue_request(tracker, draft);
+    let authorization = match (tracker.kind, tracker.user.as_deref()) {
+        (TrackerKind::Jira, Some(user)) => format!("Basic {}", base64_encode(format!("{}:{}", user, token).as_bytes())),
+        _ => format!("Bearer {}", token),
+    };
+    let agent = ureq::AgentBuilder::new()
+        .timeout_connect(Duration::from_secs(15))
+        .timeout_read(Duration::from_secs(60))
+        .user_agent(concat!("LogWhisper/", env!("CARGO_PKG_VERSION")))
+        .build();
+
+    let response = agent.post(&url)
+        .set("Authorization", &authorization)
+        .set("Accept", "application/json")
+        .set("Content-Type", "application/json")
+        .send_string(&payload.to_string());
+    let body = match response {
+        Ok(response) => response.into_string()
+            .map_err(|e| Message::new("issue.read_response_failed").with("tracker", &tracker.name).with("error", e).text())?,
+        Err(ureq::Error::Status(code, response)) => {
+            let detail = response.into_string().unwrap_or_default();
+            return Err(Message::new("issue.create_failed")
+                .with("tracker", &tracker.name)
+                .with("status", code)
+                .with("detail", truncate_chars(detail.trim(), 300))
+                .text());
+        }
+        Err(e) => return Err(Message::new("issue.connect_failed").with("tracker", &tracker.name).with("error", e).text()),
+    };
+    let response: Value = serde_json::from_str(&body)
+        .map_err(|e| Message::new("issue.invalid_json").with("tracker", &tracker.name).with("error", e).text())?;
+    created_issue(tracker, &response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracker(kind: TrackerKind) -> IssueTracker {
+        IssueTracker {
+            name: "ops".to_string(),
+            kind,
+            endpoint: (kind == TrackerKind::Jira).then(|| "https://acme.atlassian.net/".to_string()),
+            project: if kind == TrackerKind::Jira { "OPS" } else { "acme/orders" }.to_string(),
+            user: None,
+            token_env: "OPS_TOKEN".to_string(),
+            issue_type: Some("bug, logs".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_issue_requests_and_responses() {
+        let draft = IssueDraft { title: "Timeout calling inventory".to_string(), body: "body".to_string() };
+
+        let github = tracker(TrackerKind::Github);
+        let (url, payload) = issue_request(&github, &draft);
+        assert_eq!(url, "https://api.github.com/repos/acme/orders/issues");
+        assert_eq!(payload["labels"], json!(["bug", "logs"]));
+        let created = created_issue(&github, &json!({"number": 7, "html_url": "https://github.com/acme/orders/issues/7"})).unwrap();
+        assert_eq!(created.key, "#7");
+
+        let jira = tracker(TrackerKind::Jira);
+        let (url, payload) = issue_request(&jira, &draft);
+        assert_eq!(url, "https://acme.atlassian.net/rest/api/2/issue");
+        assert_eq!(payload["fields"]["project"]["key"], "OPS");
+        assert_eq!(payload["fields"]["issuetype"]["name"], "bug, logs");
+        let created = created_issue(&jira, &json!({"id": "10001", "key": "OPS-12"})).unwrap();
+        assert_eq!(created.url, "https://acme.atlassian.net/browse/OPS-12");
+        assert!(created_issue(&jira, &json!({})).is_err());
+
+        assert_eq!(base64_encode(b"me@acme.io:t0k"), "bWVAYWNtZS5pbzp0MGs=");
+        assert_eq!(base64_encode(b"ab"), "YWI=");
+    }
+}
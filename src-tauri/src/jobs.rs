@@ -0,0 +1,446 @@
+/// 后台任务队列
+///
+/// 解析、建索引和分析任务在固定数量的工作线程上执行，按优先级调度：交互请求（界面正在等待的解析）
+/// 总是先于后台任务（如预先建立整个文件的索引）出队，且后台任务最多占用除一个以外的全部工作线程，
+/// 保证后台建索引时交互请求仍有空闲线程可用。
+/// 每个任务可以报告进度；取消排队中的任务会直接移出队列，运行中的任务在下一个检查点停止。
+/// 任务panic时记为失败，工作线程继续执行后续任务。
+
+use crate::i18n::Message;
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::collections::{BinaryHeap, VecDeque};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use tokio::sync::oneshot;
+
+/// 保留的已结束任务数（`list_jobs` 返回运行中、排队中的任务和最近结束的任务）
+pub const MAX_FINISHED_JOBS: usize = 100;
+
+/// 任务类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobKind {
+    /// 解析文件
+    Parse,
+    /// 建立索引（行索引和完整解析缓存）
+    Index,
+    /// 分析解析结果
+    Analysis,
+}
+
+/// 任务优先级
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobPriority {
+    /// 后台任务
+    Background,
+    /// 界面正在等待结果的交互请求
+    Interactive,
+}
+
+/// 任务状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    fn is_finished(self) -> bool {
+        matches!(self, JobStatus::Completed | JobStatus::Failed | JobStatus::Cancelled)
+    }
+}
+
+/// 任务信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobInfo {
+    /// 任务ID
+    pub id: String,
+    /// 任务类型
+    pub kind: JobKind,
+    /// 优先级
+    pub priority: JobPriority,
+    /// 描述（如文件路径）
+    pub label: String,
+    /// 状态
+    pub status: JobStatus,
+    /// 进度（0-1）
+    pub progress: f64,
+    /// 当前阶段
+    pub stage: Option<String>,
+    /// 失败时的错误信息
+    pub error: Option<String>,
+    /// 提交、开始和结束时间（RFC 3339）
+    pub submitted_at: String,
+    pub started_at: Option<String>,
+    pub finished_at: Option<String>,
+}
+
+/// 任务执行时的上下文，用于报告进度和检查取消
+pub struct JobContext {
+    id: String,
+    cancelled: Arc<AtomicBool>,
+    shared: Arc<Shared>,
+}
+
+impl JobContext {
+    /// 任务ID
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// 是否已请求取消
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// 取消检查点：已请求取消时返回错误，任务应直接返回该错误
+    pub fn check_cancelled(&self) -> Result<(), String> {
+        if self.is_cancelled() {
+            return Err(Message::new("job.cancelled").text());
+        }
+        Ok(())
+    }
+
+    /// 报告进度
+    ///
+    /// # 参数
+    /// - `progress`: 进度（0-1）
+    /// - `stage`: 当前阶段描述
+    pub fn set_progress(&self, progress: f64, stage: &str) {
+        self.shared.update(&self.id, |job| {
+            job.info.progress = progress.clamp(0.0, 1.0);
+            job.info.stage = Some(stage.to_string());
+        });
+    }
+}
+
+type Task = Box<dyn FnOnce(&JobContext) -> Result<(), String> + Send>;
+
+/// 排队中的任务（优先级高者先出队，同优先级先提交者先出队）
+struct Pending {
+    priority: JobPriority,
+    seq: u64,
+    id: String,
+    task: Task,
+}
+
+impl PartialEq for Pending {
+    fn eq(&self, other: &Self) -> bool {
+        (self.priority, self.seq) == (other.priority, other.seq)
+    }
+}
+
+impl Eq for Pending {}
+
+impl PartialOrd for Pending {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Pending {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.priority.cmp(&other.priority).then(other.seq.cmp(&self.seq))
+    }
+}
+
+struct JobRecord {
+    info: JobInfo,
+    cancelled: Arc<AtomicBool>,
+}
+
+struct QueueState {
+    pending: BinaryHeap<Pending>,
+    jobs: VecDeque<JobRecord>,
+    running_background: usize,
+    seq: u64,
+}
+
+struct Shared {
+    state: Mutex<QueueState>,
+    available: Condvar,
+    max_background: usize,
+}
+
+impl Shared {
+    fn update(&self, id: &str, f: impl FnOnce(&mut JobRecord)) {
+        if let Ok(mut state) = self.state.lock() {
+            if let Some(job) = state.jobs.iter_mut().find(|job| job.info.id == id) {
+                f(job);
+            }
+        }
+    }
+
+    /// 取出下一个可以执行的任务；后台任务的线程已满时只取交互任务
+    fn next(&self) -> (Pending, Arc<AtomicBool>) {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            let runnable = state.pending.peek().is_some_and(|top| {
+                top.priority == JobPriority::Interactive || state.running_background < self.max_background
+            });
+            if runnable {
+                let pending = state.pending.pop().unwrap();
+                if pending.priority == JobPriority::Background {
+                    state.running_background += 1;
+                }
+                let now = chrono::Utc::now().to_rfc3339();
+                let record = state.jobs.iter_mut().find(|job| job.info.id == pending.id).unwrap();
+                record.info.status = JobStatus::Running;
+                record.info.started_at = Some(now);
+                let cancelled = record.cancelled.clone();
+                return (pending, cancelled);
+            }
+            state = self.available.wait(state).unwrap();
+        }
+    }
+
+    fn finish(&self, id: &str, priority: JobPriority, result: Result<(), String>) {
+        let mut state = self.state.lock().unwrap();
+        if priority == JobPriority::Background {
+            state.running_background -= 1;
+        }
+        if let Some(job) = state.jobs.iter_mut().find(|job| job.info.id == id) {
+            job.info.finished_at = Some(chrono::Utc::now().to_rfc3339());
+            job.info.status = match &result {
+                Ok(()) => JobStatus::Completed,
+                Err(_) if job.cancelled.load(Ordering::Relaxed) => JobStatus::Cancelled,
+                Err(_) => JobStatus::Failed,
+            };
+            match result {
+                Ok(()) => job.info.progress = 1.0,
+                Err(e) => job.info.error = Some(e),
+            }
+        }
+        prune_finished(&mut state.jobs);
+        self.available.notify_all();
+    }
+}
+
+/// panic携带的消息
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload.downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| Message::new("job.unknown_panic").text())
+}
+
+/// 只保留最近 `MAX_FINISHED_JOBS` 个已结束的任务
+fn prune_finished(jobs: &mut VecDeque<JobRecord>) {
+    let mut finished = jobs.iter().filter(|job| job.info.status.is_finished()).count();
+    while finished > MAX_FINISHED_JOBS {
+        let Some(index) = jobs.iter().position(|job| job.info.status.is_finished()) else {
+            break;
+        };
+        jobs.remove(index);
+        finished -= 1;
+    }
+}
+
+/// 按优先级调度的任务队列
+pub struct JobQueue {
+    shared: Arc<Shared>,
+}
+
+impl JobQueue {
+    /// 创建任务队列并启动工作线程
+    ///
+    /// # 参数
+    /// - `workers`: 工作线程数（至少2个，其中一个只执行交互任务）
+    pub fn new(workers: usize) -> Self {
+        let workers = workers.max(2);
+        let shared = Arc::new(Shared {
+            state: Mutex::new(QueueState {
+                pending: BinaryHeap::new(),
+                jobs: VecDeque::new(),
+                running_background: 0,
+                seq: 0,
+            }),
+            available: Condvar::new(),
+            max_background: workers - 1,
+        });
+        for i in 0..workers {
+            let shared = shared.clone();
+            std::thread::Builder::new()
+                .name(format!("job-worker-{}", i))
+                .spawn(move || loop {
+                    let (pending, cancelled) = shared.next();
+                    let context = JobContext { id: pending.id.clone(), cancelled, shared: shared.clone() };
+                    // 任务panic时结果通道随任务一起丢弃，提交者收到通道关闭的错误
+                    let result = catch_unwind(AssertUnwindSafe(|| (pending.task)(&context)))
+                        .unwrap_or_else(|payload| {
+                            let message = panic_message(payload.as_ref());
+                            log::error!("❌ 任务 {} 崩溃: {}", pending.id, message);
+                            Err(Message::new("job.panicked").with("message", message).text())
+                        });
+                    shared.finish(&pending.id, pending.priority, result);
+                })
+                .expect("启动任务工作线程失败");
+        }
+        Self { shared }
+    }
+
+    /// 按CPU核数选择工作线程数（2-8个）
+    pub fn with_default_workers() -> Self {
+        let cpus = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(2);
+        Self::new(cpus.clamp(2, 8))
+    }
+
+    /// 提交任务
+    ///
+    /// # 参数
+    /// - `kind`: 任务类型
+    /// - `priority`: 优先级
+    /// - `label`: 描述
+    /// - `task`: 任务函数，在工作线程上执行
+    ///
+    /// # Returns
+    /// - `(String, Receiver)`: 任务ID和接收结果的通道（任务在开始前被取消时通道关闭）
+    pub fn submit<F, R>(
+        &self,
+        kind: JobKind,
+        priority: JobPriority,
+        label: &str,
+        task: F,
+    ) -> (String, oneshot::Receiver<Result<R, String>>)
+    where
+        F: FnOnce(&JobContext) -> Result<R, String> + Send + 'static,
+        R: Send + 'static,
+    {
+        let id = uuid::Uuid::new_v4().simple().to_string();
+        let (sender, receiver) = oneshot::channel();
+        let task: Task = Box::new(move |context| {
+            let result = task(context);
+            let status = result.as_ref().map(|_| ()).map_err(Clone::clone);
+            sender.send(result).ok();
+            status
+        });
+
+        let mut state = self.shared.state.lock().unwrap();
+        state.seq += 1;
+        let seq = state.seq;
+        state.jobs.push_back(JobRecord {
+            info: JobInfo {
+                id: id.clone(),
+                kind,
+                priority,
+                label: label.to_string(),
+                status: JobStatus::Queued,
+                progress: 0.0,
+                stage: None,
+                error: None,
+                submitted_at: chrono::Utc::now().to_rfc3339(),
+                started_at: None,
+                finished_at: None,
+            },
+            cancelled: Arc::new(AtomicBool::new(false)),
+        });
+        state.pending.push(Pending { priority, seq, id: id.clone(), task });
+        self.shared.available.notify_all();
+        (id, receiver)
+    }
+
+    /// 列出任务（按提交顺序）
+    pub fn list(&self) -> Vec<JobInfo> {
+        self.shared.state.lock().unwrap().jobs.iter().map(|job| job.info.clone()).collect()
+    }
+
+    /// 取消任务：排队中的任务直接移出队列，运行中的任务在下一个取消检查点停止
+    ///
+    /// # Returns
+    /// - `Ok(JobInfo)`: 取消后的任务信息（运行中的任务状态仍为 `running`，结束后变为 `cancelled`）
+    /// - `Err(String)`: 任务不存在或已结束时的错误信息
+    pub fn cancel(&self, id: &str) -> Result<JobInfo, String> {
+        let mut state = self.shared.state.lock().unwrap();
+        let job = state.jobs.iter_mut().find(|job| job.info.id == id)
+            .ok_or_else(|| Message::new("job.not_found").with("id", id).text())?;
+        if job.info.status.is_finished() {
+            return Err(Message::new("job.finished").with("id", id).text());
+        }
+        job.cancelled.store(true, Ordering::Relaxed);
+        if job.info.status == JobStatus::Queued {
+            job.info.status = JobStatus::Cancelled;
+            job.info.finished_at = Some(chrono::Utc::now().to_rfc3339());
+        }
+        let info = job.info.clone();
+        if info.status == JobStatus::Cancelled {
+            state.pending.retain(|pending| pending.id != id);
+        }
+        Ok(info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    fn status(queue: &JobQueue, id: &str) -> JobStatus {
+        queue.list().into_iter().find(|job| job.id == id).unwrap().status
+    }
+
+    #[test]
+    fn test_interactive_jobs_are_not_starved() {
+        let queue = JobQueue::new(2);
+        let (release, blocked) = mpsc::channel::<()>();
+        let (started, wait_started) = mpsc::channel::<()>();
+        let (indexing, indexing_result) = queue.submit(JobKind::Index, JobPriority::Background, "big.log", move |context| {
+            started.send(()).unwrap();
+            blocked.recv().unwrap();
+            context.check_cancelled()?;
+            Ok(())
+        });
+        wait_started.recv().unwrap();
+
+        // 唯一的后台线程被占用：第二个后台任务排队，交互任务立即执行
+        let (queued, queued_result) = queue.submit(JobKind::Index, JobPriority::Background, "other.log", |_| Ok(()));
+        let (_, interactive) = queue.submit(JobKind::Parse, JobPriority::Interactive, "app.log", |context| {
+            context.set_progress(0.5, "解析");
+            Ok(42)
+        });
+        assert_eq!(interactive.blocking_recv().unwrap(), Ok(42));
+        assert_eq!(status(&queue, &queued), JobStatus::Queued);
+
+        assert_eq!(queue.cancel(&queued).unwrap().status, JobStatus::Cancelled);
+        assert!(queued_result.blocking_recv().is_err());
+        assert_eq!(queue.cancel(&indexing).unwrap().status, JobStatus::Running);
+        release.send(()).unwrap();
+        assert!(indexing_result.blocking_recv().unwrap().is_err());
+        while status(&queue, &indexing) == JobStatus::Running {
+            std::thread::yield_now();
+        }
+        assert_eq!(status(&queue, &indexing), JobStatus::Cancelled);
+        assert!(queue.cancel(&indexing).is_err());
+    }
+
+    #[test]
+    fn test_panicking_job_fails_and_worker_keeps_running() {
+        // 两个工作线程中只有一个执行后台任务，panic后计数泄漏会让后面的后台任务永远排队
+        let queue = JobQueue::new(2);
+        let (panicked, panicked_result) = queue.submit(JobKind::Analysis, JobPriority::Background, "bad.log", |_| -> Result<(), String> {
+            panic!("索引越界");
+        });
+        assert!(panicked_result.blocking_recv().is_err());
+
+        let (done, wait_done) = mpsc::channel();
+        let (next, _) = queue.submit(JobKind::Index, JobPriority::Background, "next.log", move |_| {
+            done.send(()).unwrap();
+            Ok(())
+        });
+        wait_done.recv_timeout(std::time::Duration::from_secs(5)).expect("后台任务应继续执行");
+
+        let failed = queue.list().into_iter().find(|job| job.id == panicked).unwrap();
+        assert_eq!(failed.status, JobStatus::Failed);
+        assert_eq!(failed.error.as_deref(), Some("任务崩溃: 索引越界"));
+        while status(&queue, &next) == JobStatus::Running {
+            std::thread::yield_now();
+        }
+        assert_eq!(status(&queue, &next), JobStatus::Completed);
+    }
+}
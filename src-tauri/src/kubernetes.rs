@@ -0,0 +1,308 @@
+/// Kubernetes Pod日志
+///
+/// 通过系统自带的 `kubectl` 访问集群：列出kubeconfig中的上下文、命名空间和Pod，
+/// 获取Pod日志（包括 `--previous` 上一次运行的日志和多容器Pod）并交给解析流程。
+/// 集群地址和认证完全交给kubectl（`KUBECONFIG` 或 `~/.kube/config`），应用不保存凭据。
+///
+/// 多容器Pod的每个容器分别获取日志，按容器依次拼接，
+/// 解析后每个条目的元数据中记录 `namespace`、`pod` 和 `container`，便于按容器过滤。
+
+use crate::i18n::Message;
+use crate::models::LogEntry;
+use serde::{Deserialize, Serialize};
+use std::process::{Command, Stdio};
+
+/// 请求超时（传给kubectl的 `--request-timeout`）
+const REQUEST_TIMEOUT: &str = "30s";
+
+/// Pod摘要
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PodSummary {
+    /// Pod名称
+    pub name: String,
+    /// 命名空间
+    pub namespace: String,
+    /// 阶段（Running/Pending/Failed等）
+    pub phase: String,
+    /// 容器名称（按Pod定义中的顺序）
+    pub containers: Vec<String>,
+    /// 所有容器的重启次数之和
+    pub restarts: u64,
+    /// 所在节点
+    pub node: Option<String>,
+}
+
+/// Pod日志获取选项
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PodLogOptions {
+    /// 容器名称（可选，不指定则获取所有容器）
+    #[serde(default)]
+    pub container: Option<String>,
+    /// 获取上一次运行（崩溃重启前）的日志
+    #[serde(default)]
+    pub previous: bool,
+    /// 起始时间（可选）：相对时长（如 `10m`、`2h`）或RFC 3339时间
+    #[serde(default)]
+    pub since: Option<String>,
+    /// 每个容器只获取最后N行（可选）
+    #[serde(default)]
+    pub tail: Option<usize>,
+}
+
+/// 单个容器的日志
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContainerLog {
+    /// 容器名称
+    pub container: String,
+    /// 日志内容
+    pub content: String,
+}
+
+/// kubectl客户端
+pub struct KubectlClient {
+    /// kubeconfig上下文（None表示当前上下文）
+    context: Option<String>,
+}
+
+impl KubectlClient {
+    /// 创建客户端
+    ///
+    /// # 参数
+    /// - `context`: kubeconfig上下文（可选，默认使用当前上下文）
+    pub fn new(context: Option<String>) -> Self {
+        Self { context: context.filter(|c| !c.trim().is_empty()) }
+    }
+
+    /// 构建kubectl命令行参数（不含程序名）
+    fn kubectl_args(&self, args: &[String]) -> Vec<String> {
+        let mut all = vec![format!("--request-timeout={}", REQUEST_TIMEOUT)];
+        if let Some(context) = &self.context {
+            all.push(format!("--context={}", context));
+        }
+        all.extend_from_slice(args);
+        all
+    }
+
+    /// 执行kubectl并返回标准输出
+    fn run(&self, args: &[String]) -> Result<String, String> {
+        let args = self.kubectl_args(args);
+        log::debug!("☸️ kubectl {}", args.join(" "));
+        let output = Command::new("kubectl")
+            .args(&args)
+            .stdin(Stdio::null())
+            .output()
+            .map_err(|e| Message::new("kubectl.spawn_failed").with("error", e).text())?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Message::new("kubectl.failed").with("error", stderr.trim()).text());
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    /// 列出kubeconfig中的上下文
+    pub fn list_contexts(&self) -> Result<Vec<String>, String> {
+        let output = self.run(&["config".to_string(), "get-contexts".to_string(), "-o".to_string(), "name".to_string()])?;
+        Ok(output.lines().map(str::trim).filter(|l| !l.is_empty()).map(str::to_string).collect())
+    }
+
+    /// 列出命名空间
+    pub fn list_namespaces(&self) -> Result<Vec<String>, String> {
+        let output = self.run(&["get".to_string(), "namespaces".to_string(), "-o".to_string(), "json".to_string()])?;
+        let list: serde_json::Value = serde_json::from_str(&output)
+            .map_err(|e| Message::new("kubectl.invalid_namespace_list").with("error", e).text())?;
+        let mut names: Vec<String> = list["items"].as_array().into_iter().flatten()
+            .filter_map(|item| item.pointer("/metadata/name").and_then(|n| n.as_str()).map(str::to_string))
+            .collect();
+        names.sort();
+        Ok(names)
+    }
+
+    /// 列出命名空间中的Pod
+    pub fn list_pods(&self, namespace: &str) -> Result<Vec<PodSummary>, String> {
+        validate_name("kubectl.label.namespace", namespace)?;
+        let output = self.run(&["get".to_string(), "pods".to_string(), "-n".to_string(), namespace.to_string(), "-o".to_string(), "json".to_string()])?;
+        let list: serde_json::Value = serde_json::from_str(&output)
+            .map_err(|e| Message::new("kubectl.invalid_pod_list").with("error", e).text())?;
+        Ok(list["items"].as_array().into_iter().flatten().map(pod_summary).collect())
+    }
+
+    /// 获取单个Pod的信息
+    pub fn get_pod(&self, namespace: &str, pod: &str) -> Result<PodSummary, String> {
+        validate_name("kubectl.label.namespace", namespace)?;
+        validate_name("kubectl.label.pod", pod)?;
+        let output = self.run(&["get".to_string(), "pod".to_string(), pod.to_string(), "-n".to_string(), namespace.to_string(), "-o".to_string(), "json".to_string()])?;
+        let value: serde_json::Value = serde_json::from_str(&output)
+            .map_err(|e| Message::new("kubectl.invalid_pod_info").with("error", e).text())?;
+        Ok(pod_summary(&value))
+    }
+
+    /// 获取Pod日志
+    ///
+    /// 指定容器时只获取该容器；否则查询Pod定义并逐个获取所有容器的日志。
+    /// 多容器时部分容器失败（如 `previous` 模式下某容器从未重启）只记录警告，全部失败才返回错误。
+    ///
+    /// # Returns
+    /// - `Ok(Vec<ContainerLog>)`: 按容器顺序排列的日志
+    /// - `Err(String)`: Pod不存在或kubectl不可用时的错误信息
+    pub fn pod_logs(&self, namespace: &str, pod: &str, options: &PodLogOptions) -> Result<Vec<ContainerLog>, String> {
+        let containers = match &options.container {
+            Some(container) => vec![container.clone()],
+            None => self.get_pod(namespace, pod)?.containers,
+        };
+
+        let mut logs = Vec::new();
+        let mut first_error = None;
+        for container in containers {
+            match self.run(&logs_args(namespace, pod, &container, options)?) {
+                Ok(content) => logs.push(ContainerLog { container, content }),
+                Err(e) => {
+                    log::warn!("⚠️ 获取容器 {}/{} 日志失败: {}", pod, container, e);
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        match first_error {
+            Some(e) if logs.is_empty() => Err(e),
+            _ => Ok(logs),
+        }
+    }
+}
+
+/// 校验资源名称（命名空间、Pod、容器均为DNS子域名，不能以 `-` 开头被当作选项）
+///
+/// `label` 是资源类型的消息代码，用于本地化错误信息。
+fn validate_name(label: &str, name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && !name.starts_with('-')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(Message::new("kubectl.invalid_name").with("label", Message::new(label).text()).with("name", format!("{:?}", name)).text())
+    }
+}
+
+/// 构建 `kubectl logs` 参数
+fn logs_args(namespace: &str, pod: &str, container: &str, options: &PodLogOptions) -> Result<Vec<String>, String> {
+    validate_name("kubectl.label.namespace", namespace)?;
+    validate_name("kubectl.label.pod", pod)?;
+    validate_name("kubectl.label.container", container)?;
+
+    let mut args = vec![
+        "logs".to_string(), pod.to_string(),
+        "-n".to_string(), namespace.to_string(),
+        "-c".to_string(), container.to_string(),
+    ];
+    if options.previous {
+        args.push("--previous".to_string());
+    }
+    if let Some(since) = options.since.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+        if chrono::DateTime::parse_from_rfc3339(since).is_ok() {
+            args.push(format!("--since-time={}", since));
+        } else if since.len() > 1 && since.ends_with(['s', 'm', 'h']) && since[..since.len() - 1].chars().all(|c| c.is_ascii_digit()) {
+            args.push(format!("--since={}", since));
+        } else {
+            return Err(Message::new("logs.invalid_since").with("since", since).text());
+        }
+    }
+    if let Some(tail) = options.tail {
+        args.push(format!("--tail={}", tail));
+    }
+    Ok(args)
+}
+
+/// 从 `kubectl get pod -o json` 的对象中提取摘要
+fn pod_summary(value: &serde_json::Value) -> PodSummary {
+    let text = |pointer: &str| value.pointer(pointer).and_then(|v| v.as_str()).unwrap_or_default().to_string();
+    PodSummary {
+        name: text("/metadata/name"),
+        namespace: text("/metadata/namespace"),
+        phase: text("/status/phase"),
+        containers: value.pointer("/spec/containers").and_then(|c| c.as_array()).into_iter().flatten()
+            .filter_map(|c| c["name"].as_str().map(str::to_string))
+            .collect(),
+        restarts: value.pointer("/status/containerStatuses").and_then(|s| s.as_array()).into_iter().flatten()
+            .filter_map(|s| s["restartCount"].as_u64())
+            .sum(),
+        node: value.pointer("/spec/nodeName").and_then(|n| n.as_str()).map(str::to_string),
+    }
+}
+
+/// 按容器顺序拼接日志，返回拼接后的内容和每个容器的起始行号（从1开始）
+///
+/// 行号与 `parse_log` 内容模式一致：空行不计入行号。
+pub fn combine_container_logs(logs: &[ContainerLog]) -> (String, Vec<(usize, String)>) {
+    let mut content = String::new();
+    let mut starts = Vec::with_capacity(logs.len());
+    let mut next_line = 1;
+    for log in logs {
+        let text = log.content.trim_end_matches('\n');
+        let line_count = text.lines().filter(|line| !line.trim().is_empty()).count();
+        if line_count == 0 {
+            continue;
+        }
+        starts.push((next_line, log.container.clone()));
+        next_line += line_count;
+        content.push_str(text);
+        content.push('\n');
+    }
+    (content, starts)
+}
+
+/// 在解析后的条目元数据中记录命名空间、Pod和容器
+pub fn tag_entries(entries: &mut [LogEntry], namespace: &str, pod: &str, starts: &[(usize, String)]) {
+    for entry in entries {
+        let container = starts.iter().rev()
+            .find(|(start, _)| *start <= entry.line_number)
+            .map(|(_, container)| container.clone());
+        entry.metadata.insert("namespace".to_string(), namespace.to_string());
+        entry.metadata.insert("pod".to_string(), pod.to_string());
+        if let Some(container) = container {
+            entry.metadata.insert("container".to_string(), container);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pod_summary_and_logs_args() {
+        let pod = serde_json::json!({
+            "metadata": {"name": "api-7d9f", "namespace": "prod"},
+            "spec": {"nodeName": "node-1", "containers": [{"name": "app"}, {"name": "sidecar"}]},
+            "status": {"phase": "Running", "containerStatuses": [{"restartCount": 3}, {"restartCount": 1}]}
+        });
+        let summary = pod_summary(&pod);
+        assert_eq!(summary.containers, ["app", "sidecar"]);
+        assert_eq!((summary.restarts, summary.node.as_deref()), (4, Some("node-1")));
+
+        let options = PodLogOptions { previous: true, since: Some("10m".to_string()), tail: Some(100), ..Default::default() };
+        let args = logs_args("prod", "api-7d9f", "app", &options).unwrap();
+        assert_eq!(&args[..6], ["logs", "api-7d9f", "-n", "prod", "-c", "app"]);
+        assert_eq!(&args[6..], ["--previous", "--since=10m", "--tail=100"]);
+
+        assert!(logs_args("prod", "--all", "app", &options).is_err());
+        let bad_since = PodLogOptions { since: Some("yesterday".to_string()), ..Default::default() };
+        assert!(logs_args("prod", "api", "app", &bad_since).is_err());
+    }
+
+    #[test]
+    fn test_combine_and_tag_entries_by_container() {
+        let logs = vec![
+            ContainerLog { container: "app".to_string(), content: "a1\n\na2\n".to_string() },
+            ContainerLog { container: "empty".to_string(), content: String::new() },
+            ContainerLog { container: "sidecar".to_string(), content: "s1\n".to_string() },
+        ];
+        let (content, starts) = combine_container_logs(&logs);
+        assert_eq!(content, "a1\n\na2\ns1\n");
+        assert_eq!(starts, [(1, "app".to_string()), (3, "sidecar".to_string())]);
+
+        let mut entries: Vec<LogEntry> = (1..=3).map(|line_number| LogEntry::new(line_number, "x")).collect();
+        tag_entries(&mut entries, "prod", "api", &starts);
+        assert_eq!(entries[1].metadata["container"], "app");
+        assert_eq!(entries[2].metadata["container"], "sidecar");
+        assert_eq!(entries[2].metadata["pod"], "api");
+    }
+}
@@ -0,0 +1,342 @@
+/// 应用自身的日志
+///
+/// 基于 `tracing` 输出应用自身的运行日志（代码中的 `log::info!` 等宏经由 `tracing-log` 转发）：
+/// - 控制台：紧凑文本或JSON格式（环境变量 `LOG_WHISPER_LOG_FORMAT=json`）
+/// - 文件：应用数据目录下 `logs/log-whisper.<日期>.log`，按天轮转，保留最近 `MAX_LOG_FILES` 个
+/// - 级别：启动时取 `RUST_LOG`（默认 `info`），运行时可通过 `set_log_level` 按模块调整，
+///   例如 `info,log_whisper::plugins=debug`，无需重启或设置环境变量；
+///   `set_backend_log_level` 只调整全局级别或单个模块的级别，其余设置保持不变
+///
+/// 与界面语言一样，日志配置是进程级的全局状态。
+
+use crate::i18n::Message;
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_log::AsLog;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::{fmt, reload, EnvFilter, Layer, Registry};
+
+/// 默认日志级别
+pub const DEFAULT_FILTER: &str = "info";
+
+/// 日志文件目录（相对于应用数据目录）
+pub const LOG_DIR: &str = "logs";
+
+/// 日志文件名前缀
+const LOG_FILE_PREFIX: &str = "log-whisper";
+
+/// 保留的日志文件数（按天轮转，即最近7天）
+pub const MAX_LOG_FILES: usize = 7;
+
+/// 选择输出格式的环境变量
+pub const FORMAT_ENV: &str = "LOG_WHISPER_LOG_FORMAT";
+
+/// 本应用的模块路径前缀
+pub const CRATE_TARGET: &str = "log_whisper";
+
+/// 可用的日志级别
+pub const LEVELS: [&str; 6] = ["trace", "debug", "info", "warn", "error", "off"];
+
+/// 日志输出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogFormat {
+    /// 紧凑的单行文本
+    #[default]
+    Compact,
+    /// 每行一个JSON对象，便于用LogWhisper自己或其他工具分析
+    Json,
+}
+
+impl LogFormat {
+    /// 解析格式名称（不区分大小写）
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_lowercase().as_str() {
+            "compact" | "text" => Ok(LogFormat::Compact),
+            "json" => Ok(LogFormat::Json),
+            other => Err(Message::new("logging.unsupported_format").with("format", other).text()),
+        }
+    }
+}
+
+/// 当前日志配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoggingStatus {
+    /// 当前生效的级别过滤（`EnvFilter` 语法）
+    pub filter: String,
+    /// 全局级别（过滤中没有全局级别时为 `error`，与 `EnvFilter` 的默认行为一致）
+    pub level: String,
+    /// 按模块设置的级别（模块路径 -> 级别）
+    pub targets: BTreeMap<String, String>,
+    /// 输出格式
+    pub format: LogFormat,
+    /// 日志文件目录（未启用文件输出时为None）
+    pub log_dir: Option<String>,
+}
+
+type FilterHandle = reload::Handle<EnvFilter, Registry>;
+
+/// 初始化后的日志状态
+struct Logging {
+    filter_handle: FilterHandle,
+    status: Mutex<LoggingStatus>,
+    /// 文件写入线程的守卫，进程退出前保持存活以刷新缓冲
+    _file_guard: Option<WorkerGuard>,
+}
+
+static LOGGING: OnceCell<Logging> = OnceCell::new();
+
+/// 解析并校验级别过滤
+///
+/// # 参数
+/// - `directives`: `EnvFilter` 语法，如 `info`、`warn,log_whisper::plugins=debug`
+pub fn parse_filter(directives: &str) -> Result<EnvFilter, String> {
+    let directives = directives.trim();
+    if directives.is_empty() {
+        return Err(Message::new("logging.empty_level").text());
+    }
+    EnvFilter::try_new(directives)
+        .map_err(|e| Message::new("logging.invalid_directives").with("directives", directives).with("error", e).text())
+}
+
+/// 把级别过滤拆分为全局级别和按模块的级别
+///
+/// 只识别 `级别` 和 `模块路径=级别` 两种指令，带span/字段条件的指令原样保留在 `filter` 中，不出现在结果里。
+fn split_directives(directives: &str) -> (Option<String>, BTreeMap<String, String>) {
+    let mut level = None;
+    let mut targets = BTreeMap::new();
+    for directive in directives.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+        match directive.split_once('=') {
+            Some((target, target_level)) if !target.contains('[') => {
+                targets.insert(target.trim().to_string(), target_level.trim().to_lowercase());
+            }
+            Some(_) => {}
+            None if LEVELS.contains(&directive.to_lowercase().as_str()) => level = Some(directive.to_lowercase()),
+            None => {}
+        }
+    }
+    (level, targets)
+}
+
+/// 补全模块路径：不以本应用模块前缀开头的短名称视为本应用内的模块（如 `plugins` → `log_whisper::plugins`）
+///
+/// 以 `::` 开头的名称视为其他crate，去掉前缀后使用（如 `::tauri` → `tauri`）。
+pub fn qualify_target(target: &str) -> String {
+    let target = target.trim();
+    if let Some(external) = target.strip_prefix("::") {
+        return external.to_string();
+    }
+    if target == CRATE_TARGET || target.starts_with(&format!("{}::", CRATE_TARGET)) {
+        target.to_string()
+    } else {
+        format!("{}::{}", CRATE_TARGET, target)
+    }
+}
+
+/// 在现有级别过滤上修改全局级别或单个模块的级别，其余指令保持不变
+///
+/// # 参数
+/// - `current`: 当前级别过滤
+/// - `level`: 新级别（trace/debug/info/warn/error/off）
+/// - `target`: 模块路径（None表示修改全局级别）
+///
+/// # Returns
+/// - `Ok(String)`: 修改后的级别过滤
+/// - `Err(String)`: 级别无效时的错误信息
+pub fn merge_directive(current: &str, level: &str, target: Option<&str>) -> Result<String, String> {
+    let level = level.trim().to_lowercase();
+    if !LEVELS.contains(&level.as_str()) {
+        return Err(Message::new("logging.invalid_level").with("level", level).with("available", LEVELS.join(", ")).text());
+    }
+    let target = target.map(str::trim).filter(|t| !t.is_empty()).map(qualify_target);
+
+    let mut directives: Vec<String> = Vec::new();
+    for directive in current.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+        let replaced = match (&target, directive.split_once('=')) {
+            (Some(target), Some((existing, _))) => existing.trim() == target,
+            (None, None) => LEVELS.contains(&directive.to_lowercase().as_str()),
+            _ => false,
+        };
+        if !replaced {
+            directives.push(directive.to_string());
+        }
+    }
+    match target {
+        Some(target) => directives.push(format!("{}={}", target, level)),
+        None => directives.insert(0, level),
+    }
+    Ok(directives.join(","))
+}
+
+/// 让 `log` 宏的全局级别与过滤条件一致，避免被过滤的日志仍然格式化参数
+fn sync_log_max_level(filter: &EnvFilter) {
+    let max = filter.max_level_hint().unwrap_or(tracing::level_filters::LevelFilter::TRACE);
+    log::set_max_level(max.as_log());
+}
+
+/// 创建按天轮转的日志文件写入器
+fn file_writer(log_dir: &Path) -> Result<RollingFileAppender, String> {
+    std::fs::create_dir_all(log_dir).map_err(|e| Message::new("logging.create_directory_failed").with("error", e).text())?;
+    RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix("log")
+        .max_log_files(MAX_LOG_FILES)
+        .build(log_dir)
+        .map_err(|e| Message::new("logging.create_file_failed").with("error", e).text())
+}
+
+/// 初始化日志系统（进程内只能调用一次）
+///
+/// 文件输出创建失败时只输出到控制台，不影响启动。
+///
+/// # 参数
+/// - `log_dir`: 日志文件目录（None表示不写文件）
+///
+/// # Returns
+/// - `Ok(())`: 初始化成功
+/// - `Err(String)`: 已经初始化过或全局日志器已被占用时的错误信息
+pub fn init(log_dir: Option<PathBuf>) -> Result<(), String> {
+    let format = match std::env::var(FORMAT_ENV) {
+        Ok(value) => LogFormat::parse(&value).unwrap_or_else(|e| {
+            eprintln!("⚠️ {}", e);
+            LogFormat::default()
+        }),
+        Err(_) => LogFormat::default(),
+    };
+    let directives = std::env::var("RUST_LOG").ok()
+        .filter(|value| parse_filter(value).is_ok())
+        .unwrap_or_else(|| DEFAULT_FILTER.to_string());
+    let filter = parse_filter(&directives)?;
+    sync_log_max_level(&filter);
+    let (filter_layer, filter_handle) = reload::Layer::new(filter);
+
+    let console = match format {
+        LogFormat::Compact => fmt::layer().compact().boxed(),
+        LogFormat::Json => fmt::layer().json().boxed(),
+    };
+
+    let (file, file_guard, log_dir) = match log_dir.as_deref().map(file_writer) {
+        Some(Ok(appender)) => {
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            let layer = match format {
+                LogFormat::Compact => fmt::layer().compact().with_ansi(false).with_writer(writer).boxed(),
+                LogFormat::Json => fmt::layer().json().with_writer(writer).boxed(),
+            };
+            (Some(layer), Some(guard), log_dir)
+        }
+        Some(Err(e)) => {
+            eprintln!("⚠️ {}，只输出到控制台", e);
+            (None, None, None)
+        }
+        None => (None, None, None),
+    };
+
+    let subscriber = Registry::default().with(filter_layer).with(console).with(file);
+    tracing::subscriber::set_global_default(subscriber).map_err(|e| Message::new("logging.set_global_failed").with("error", e).text())?;
+    tracing_log::LogTracer::init().map_err(|e| Message::new("logging.bridge_failed").with("error", e).text())?;
+
+    let status = LoggingStatus {
+        filter: directives,
+        level: String::new(),
+        targets: BTreeMap::new(),
+        format,
+        log_dir: log_dir.map(|dir| dir.to_string_lossy().into_owned()),
+    };
+    LOGGING
+        .set(Logging { filter_handle, status: Mutex::new(status.with_directives()), _file_guard: file_guard })
+        .map_err(|_| Message::new("logging.already_initialized").text())
+}
+
+impl LoggingStatus {
+    /// 根据 `filter` 填写全局级别和按模块的级别
+    fn with_directives(mut self) -> Self {
+        let (level, targets) = split_directives(&self.filter);
+        self.level = level.unwrap_or_else(|| "error".to_string());
+        self.targets = targets;
+        self
+    }
+}
+
+fn logging() -> Result<&'static Logging, String> {
+    LOGGING.get().ok_or_else(|| Message::new("logging.not_initialized").text())
+}
+
+/// 获取当前日志配置
+pub fn status() -> Result<LoggingStatus, String> {
+    let status = logging()?.status.lock().map_err(|_| Message::new("logging.lock_failed").text())?;
+    Ok(status.clone())
+}
+
+/// 修改全局级别或单个模块的级别，其余设置保持不变
+///
+/// # 参数
+/// - `level`: 新级别（trace/debug/info/warn/error/off）
+/// - `target`: 模块路径（None表示全局级别；短名称按本应用的模块补全，如 `plugins`）
+///
+/// # Returns
+/// - `Ok(LoggingStatus)`: 调整后的日志配置
+/// - `Err(String)`: 级别无效或日志系统未初始化时的错误信息
+pub fn set_level(level: &str, target: Option<&str>) -> Result<LoggingStatus, String> {
+    let current = status()?.filter;
+    set_filter(&merge_directive(&current, level, target)?)
+}
+
+/// 运行时调整日志级别
+///
+/// # 参数
+/// - `directives`: `EnvFilter` 语法，如 `info,log_whisper::plugins=debug`
+///
+/// # Returns
+/// - `Ok(LoggingStatus)`: 调整后的日志配置
+/// - `Err(String)`: 设置无效或日志系统未初始化时的错误信息
+pub fn set_filter(directives: &str) -> Result<LoggingStatus, String> {
+    let filter = parse_filter(directives)?;
+    let logging = logging()?;
+
+    sync_log_max_level(&filter);
+    logging.filter_handle.reload(filter).map_err(|e| Message::new("logging.reload_failed").with("error", e).text())?;
+
+    let mut status = logging.status.lock().map_err(|_| Message::new("logging.lock_failed").text())?;
+    *status = LoggingStatus { filter: directives.trim().to_string(), ..status.clone() }.with_directives();
+    Ok(status.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_format_and_filter() {
+        assert_eq!(LogFormat::parse("JSON").unwrap(), LogFormat::Json);
+        assert_eq!(LogFormat::parse("compact").unwrap(), LogFormat::Compact);
+        assert!(LogFormat::parse("xml").is_err());
+
+        let filter = parse_filter("warn,log_whisper::plugins=debug").unwrap();
+        assert_eq!(filter.max_level_hint(), Some(tracing::level_filters::LevelFilter::DEBUG));
+        assert!(parse_filter("  ").is_err());
+        assert!(parse_filter("log_whisper=loud").is_err());
+    }
+
+    #[test]
+    fn test_merge_directive_changes_only_requested_target() {
+        let filter = merge_directive("info", "debug", Some("plugins")).unwrap();
+        assert_eq!(filter, "info,log_whisper::plugins=debug");
+        let filter = merge_directive(&filter, "TRACE", Some("log_whisper::plugins")).unwrap();
+        assert_eq!(filter, "info,log_whisper::plugins=trace");
+        let filter = merge_directive(&filter, "warn", Some("::ureq")).unwrap();
+        let filter = merge_directive(&filter, "error", None).unwrap();
+        assert_eq!(filter, "error,log_whisper::plugins=trace,ureq=warn");
+        assert!(parse_filter(&filter).is_ok());
+
+        let (level, targets) = split_directives(&filter);
+        assert_eq!(level.as_deref(), Some("error"));
+        assert_eq!(targets.get("log_whisper::plugins").map(String::as_str), Some("trace"));
+        assert!(merge_directive(&filter, "verbose", None).is_err());
+    }
+}
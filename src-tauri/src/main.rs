@@ -19,23 +19,137 @@ use tokio::sync::Mutex;
 use std::path::PathBuf;
 
 // 模块导入
+mod anchor;
+mod chunk_stats;
 mod config;
+mod docker;
+mod entry_store;
+mod eventlog;
+mod explain;
+mod export;
+mod grouping;
+mod i18n;
+mod ingest;
+mod issues;
+mod jobs;
+mod summarizer;
+mod kubernetes;
+mod logging;
+mod models;
+mod otlp;
+mod outline;
+mod palette;
 mod plugins;
+mod reader;
+mod records;
+mod remote;
+mod runtime_stats;
+mod sampling;
+mod search;
+mod session;
+mod startup;
+mod table;
+mod templates;
+mod utils;
+mod window_state;
+mod write_guard;
 
 // 具体导入
-use config::{ConfigService, ThemeMode};
+use chunk_stats::{AccumulatedStats, ChunkReport, ChunkStatsRegistry};
+use config::{ConfigService, ExplainRule, IngestSettings, IssueTracker, LevelStyle, MemoryBudgetSettings, PaletteSettings, ParsingProfile, PinnedEntry, RemoteHost, SummarizerSettings, ThemeMode, WindowConfig};
+use config::theme::{EffectiveTheme, ThemeState, ThemeTracker};
+use palette::{PaletteHit, PaletteItem};
+use window_state::{MonitorArea, WindowGeometry, WindowStateSaver};
+use explain::ErrorExplanation;
+use export::ExportOptions;
+use export::job::{ExportJobStore, ExportManifest};
+use export::report::ReportOptions;
+use export::snippet::SnippetOptions;
+use grouping::GroupSummary;
+use i18n::{Locale, Message};
+use ingest::{IngestService, IngestStatus, ScanReport};
+use issues::{CreatedIssue, IssueDraft};
+use jobs::{JobContext, JobInfo, JobKind, JobPriority, JobQueue};
+use summarizer::EntrySummary;
+use plugins::chain::ChainTrace;
+use plugins::compact::CompactSettings;
 use plugins::core::EnhancedPluginManager;
-use plugins::LogEntry as PluginLogEntry;
+use plugins::PluginInfo;
+use plugins::capabilities::CapabilityDecision;
+use plugins::formatter::{FormattingProfile, RendererRegistry};
+use plugins::settings::PluginSettingsSchema;
+use plugins::metrics::{MetricOutliers, MetricSeries, OutlierMethod, SlowOperationGroup};
+use plugins::test_runner::TestRunSummary;
+use plugins::lambda::LambdaSummary;
+use plugins::kafka::KafkaHealthSummary;
+use models::{LogEntry, ParseRequest};
+use reader::archive::ArchiveEntry;
+use reader::chunking::ChunkPlanner;
+use reader::http::{DownloadResult, HttpDownloader};
+use reader::{CacheStats, IncrementalParseCache, LineIndex, LineIndexCache, MemoryBudget, ParseDelta, TailOptions, TailRegistry, TailStats};
+use records::RecordPage;
+use docker::{ContainerSummary, DockerClient};
+use entry_store::{EntryPage, EntryStore, PersistedFile};
+use kubernetes::{KubectlClient, PodLogOptions, PodSummary};
+use otlp::{OtlpReceiver, OtlpReceiverInfo};
+use outline::LogOutline;
+use remote::{RemoteFileEntry, SshConnectionManager};
+use runtime_stats::{RuntimeSnapshot, RuntimeStats};
+use sampling::{EntrySample, SampleStrategy};
+use search::{EntryFilter, SearchOptions, SearchResult, Searcher};
+use session::{Session, SessionStore, SessionSummary};
+use startup::{StartupBreakdown, StartupFailure};
+use table::TablePage;
+use templates::{RunComparison, TemplateSummary};
+use utils::string_utils::{ellipsize, truncate_chars};
+use write_guard::{WriteCheck, WriteConfirmation, WriteGuard};
 
 /// 应用程序全局状态
 ///
 /// 包含应用程序运行时所需的所有核心服务组件。
-/// 使用Arc确保在多线程环境中的安全共享。
+/// 使用Arc确保在多线程环境中的安全共享，克隆只复制引用，可移入后台任务。
+#[derive(Clone)]
 pub struct AppState {
     /// 配置服务实例，管理用户设置和应用配置
     pub config_service: Arc<Mutex<ConfigService>>,
     /// 增强插件管理器，负责日志解析插件的管理和调用
     pub plugin_manager: Arc<EnhancedPluginManager>,
+    /// 行偏移索引缓存，支持按行号随机读取大文件
+    pub line_index_cache: Arc<LineIndexCache>,
+    /// 输出渲染器注册表（plain/ansi/html/markdown）
+    pub renderers: Arc<RendererRegistry>,
+    /// 增量解析缓存，文件增长时只解析新增部分
+    pub parse_cache: Arc<IncrementalParseCache>,
+    /// 文件写入守卫，限制 write_file 可写入的目录
+    pub write_guard: Arc<WriteGuard>,
+    /// 正在运行的文件跟踪
+    pub tails: Arc<TailRegistry>,
+    /// SSH连接管理器，访问远程服务器上的日志
+    pub ssh: Arc<SshConnectionManager>,
+    /// HTTP(S)日志下载器
+    pub downloads: Arc<HttpDownloader>,
+    /// 工作区会话文件存储
+    pub sessions: Arc<SessionStore>,
+    /// 运行时统计（解析吞吐量、缓存命中），由 `health_check` 返回
+    pub runtime: Arc<RuntimeStats>,
+    /// 自适应分块规划器，按字节预算和解析速度决定块大小
+    pub chunk_planner: Arc<ChunkPlanner>,
+    /// 分块解析的按文件累计统计
+    pub chunk_stats: Arc<ChunkStatsRegistry>,
+    /// 目录采集（本地日志服务模式）的任务状态
+    pub ingest: Arc<IngestService>,
+    /// 当前插件链使用的解析配置插件设置（None表示全局插件设置）
+    pub profile_settings: Arc<std::sync::Mutex<Option<std::collections::HashMap<String, serde_json::Value>>>>,
+    /// 可续传的导出任务清单
+    pub export_jobs: Arc<ExportJobStore>,
+    /// 解析、索引和分析任务的优先级队列
+    pub jobs: Arc<JobQueue>,
+    /// 解析缓存和行索引缓存的内存预算
+    pub memory_budget: Arc<MemoryBudget>,
+    /// 主题模式和系统明暗主题，Auto模式据此跟随系统
+    pub theme: Arc<ThemeTracker>,
+    /// 窗口位置和大小的防抖保存
+    pub window_state: Arc<WindowStateSaver>,
 }
 
 impl AppState {
@@ -69,16 +183,59 @@ impl AppState {
 
         let config_service = Arc::new(Mutex::new(ConfigService::new(&db_path)?));
 
+        // 应用配置中的界面语言，后端消息按此语言本地化
+        let locale = config_service.lock().await.get_window_config()?.locale;
+        match Locale::parse(&locale) {
+            Ok(locale) => i18n::set_locale(locale),
+            Err(e) => warn!("⚠️ {}", e),
+        }
+
         // 初始化插件系统
         // 插件管理器负责加载和管理所有日志解析插件
         info!("🔧 初始化插件管理器...");
         let plugin_manager = Arc::new(EnhancedPluginManager::new());
         plugin_manager.initialize().await?;
 
+        // 按持久化的插件设置重建插件链
+        let plugin_settings = config_service.lock().await.get_plugin_config()?.plugin_settings;
+        plugin_manager.apply_plugin_settings(&plugin_settings)?;
+        plugin_manager.set_detection_sample(config_service.lock().await.get_parse_config()?.detection_sample())?;
+
+        let line_index_cache = Arc::new(LineIndexCache::new());
+        let memory_budget = Arc::new(MemoryBudget::new(config_service.lock().await.get_memory_budget()?.budget_bytes()));
+        let theme = Arc::new(ThemeTracker::new(config_service.lock().await.get_theme_config()?.mode));
+        let renderers = Arc::new(RendererRegistry::new());
+        let parse_cache = Arc::new(IncrementalParseCache::new());
+
+        // 默认可信写入目录：应用导出目录和系统下载目录
+        let export_dir = app_data_dir.join("exports");
+        std::fs::create_dir_all(&export_dir)?;
+        let write_guard = Arc::new(WriteGuard::new(
+            std::iter::once(export_dir).chain(dirs::download_dir())
+        ));
+
         info!("✅ 应用状态初始化完成");
         Ok(Self {
             config_service,
             plugin_manager,
+            line_index_cache,
+            renderers,
+            parse_cache,
+            write_guard,
+            tails: Arc::new(TailRegistry::new()),
+            ssh: Arc::new(SshConnectionManager::new(Some(app_data_dir.join("ssh")))),
+            downloads: Arc::new(HttpDownloader::new(app_data_dir.join("downloads"))),
+            sessions: Arc::new(SessionStore::new(app_data_dir.join("sessions"))),
+            runtime: Arc::new(RuntimeStats::new()),
+            chunk_planner: Arc::new(ChunkPlanner::default()),
+            chunk_stats: Arc::new(ChunkStatsRegistry::new()),
+            ingest: Arc::new(IngestService::new()),
+            profile_settings: Arc::new(std::sync::Mutex::new(None)),
+            export_jobs: Arc::new(ExportJobStore::new(app_data_dir.join("export-jobs"))),
+            jobs: Arc::new(JobQueue::with_default_workers()),
+            memory_budget,
+            theme,
+            window_state: Arc::new(WindowStateSaver::default()),
         })
     }
 }
@@ -153,12 +310,11 @@ async fn get_app_data_dir() -> Result<PathBuf, Box<dyn std::error::Error>> {
 /// 用于统一创建解析失败时的错误响应格式。
 ///
 /// # 参数
-/// - `error_message`: 错误消息
-/// - `file_path`: 相关的文件路径（可选）
+/// - `message`: 可本地化的错误消息（代码 + 参数）
 ///
 /// # Returns
 /// - `ParseResponse`: 格式化的错误响应
-fn create_error_response(error_message: &str, file_path: &str) -> ParseResponse {
+fn create_error_response(message: Message) -> ParseResponse {
     ParseResponse {
         success: false,
         entries: vec![],
@@ -166,11 +322,15 @@ fn create_error_response(error_message: &str, file_path: &str) -> ParseResponse
             total_lines: 0,
             success_lines: 0,
             error_lines: 0,
+            error_line_numbers: Vec::new(),
             parse_time_ms: 0,
         },
         chunk_info: None,
-        error: Some(format!("{}: {}", error_message, file_path)),
+        error: Some(message.text()),
+        error_detail: Some(message),
         detected_format: None,
+        capabilities: None,
+        pinned: Vec::new(),
     }
 }
 
@@ -181,6 +341,7 @@ fn create_error_response(error_message: &str, file_path: &str) -> ParseResponse
 /// # Returns
 /// - `ParseResponse`: 格式化的空内容响应
 fn create_empty_response() -> ParseResponse {
+    let message = Message::new("content.empty");
     ParseResponse {
         success: false,
         entries: vec![],
@@ -188,114 +349,168 @@ fn create_empty_response() -> ParseResponse {
             total_lines: 0,
             success_lines: 0,
             error_lines: 0,
+            error_line_numbers: Vec::new(),
             parse_time_ms: 0,
         },
         chunk_info: None,
-        error: Some("日志内容为空".to_string()),
+        error: Some(message.text()),
+        error_detail: Some(message),
         detected_format: None,
+        capabilities: None,
+        pinned: Vec::new(),
     }
 }
 
 /// 应用程序健康检查端点
 ///
-/// 提供应用程序的基本状态信息，用于监控系统健康状况。
-/// 不需要访问应用状态，是一个简单的状态检查。
+/// 提供应用程序的状态信息和运行时统计，用于监控系统健康状况和排查现场的性能问题：
+/// 运行时长、解析的文件数和行数、最近1/5/15分钟的解析吞吐量、增量解析缓存命中率、
+/// 缓存的估算内存占用以及正在运行的跟踪数。
+///
+/// # 参数
+/// - `state`: 应用状态，包含缓存、跟踪和运行时统计
 ///
 /// # Returns
-/// - `Ok(HealthResponse)`: 包含状态、版本和时间戳的健康信息
+/// - `Ok(HealthResponse)`: 包含状态、版本、时间戳和运行时统计的健康信息
 /// - `Err(String)`: 健康检查失败时的错误信息
 #[tauri::command]
-async fn health_check() -> Result<HealthResponse, String> {
+async fn health_check(state: tauri::State<'_, AppState>) -> Result<HealthResponse, String> {
     debug!("执行健康检查");
 
+    let runtime = state.runtime.snapshot(
+        state.parse_cache.usage(),
+        state.line_index_cache.usage(),
+        state.tails.active_count(),
+    );
+
     Ok(HealthResponse {
         status: "ok".to_string(),
         version: "1.0.0".to_string(),
         timestamp: chrono::Utc::now().to_rfc3339(),
+        runtime,
     })
 }
 
+/// 运行时调整后端日志级别
+///
+/// 无需重启或设置环境变量即可临时打开某些模块的调试日志，例如
+/// `info,log_whisper::plugins=debug` 只让解析插件输出调试日志。调整只在本次运行中有效。
+///
+/// # 参数
+/// - `filter`: 级别过滤（`EnvFilter` 语法：全局级别和 `模块路径=级别`，以逗号分隔）
+///
+/// # Returns
+/// - `Ok(LoggingStatus)`: 调整后的日志配置（级别、格式、日志文件目录）
+/// - `Err(String)`: 级别设置无效时的错误信息
+#[tauri::command]
+async fn set_log_level(filter: String) -> Result<logging::LoggingStatus, String> {
+    let status = logging::set_filter(&filter)?;
+    info!("🔧 后端日志级别已调整为: {}", status.filter);
+    Ok(status)
+}
+
+/// 调整后端日志的全局级别或单个模块的级别
+///
+/// 供技术支持让用户临时打开某些模块的调试日志，例如只把解析插件调为debug：
+/// `set_backend_log_level("debug", "plugins")`。其他模块的级别保持不变，调整只在本次运行中有效。
+///
+/// # 参数
+/// - `level`: 日志级别（trace/debug/info/warn/error/off）
+/// - `target_filter`: 模块路径（可选，不指定则调整全局级别）；短名称按本应用的模块补全，
+///   如 `plugins` → `log_whisper::plugins`，其他crate以 `::` 开头，如 `::ureq`
+///
+/// # Returns
+/// - `Ok(LoggingStatus)`: 调整后的日志配置
+/// - `Err(String)`: 级别无效时的错误信息
+#[tauri::command]
+async fn set_backend_log_level(level: String, target_filter: Option<String>) -> Result<logging::LoggingStatus, String> {
+    let status = logging::set_level(&level, target_filter.as_deref())?;
+    info!("🔧 后端日志级别已调整为: {}", status.filter);
+    Ok(status)
+}
+
+/// 获取后端日志配置（全局级别、按模块的级别、输出格式和日志文件目录）
+///
+/// # Returns
+/// - `Ok(LoggingStatus)`: 当前日志配置
+/// - `Err(String)`: 日志系统未初始化时的错误信息
+#[tauri::command]
+async fn get_backend_log_level() -> Result<logging::LoggingStatus, String> {
+    logging::status()
+}
+
 /// 获取文件信息用于分块处理
 ///
 /// 分析日志文件的基本信息，包括总行数、文件大小等，
 /// 用于前端确定分块处理策略。
 ///
+/// 行数通过分块流式扫描换行符统计，不会把文件内容加载进内存。
+/// 扫描多GB文件时每隔约64MB向窗口发送一次 `file-info-progress` 事件。
+///
+/// `file_path` 为HTTP(S)链接时先下载到缓存（发送 `download-progress` 事件），
+/// 返回的 `file_path` 为本地缓存路径，后续命令应使用该路径。
+///
 /// # 参数
-/// - `file_path`: 日志文件的路径
+/// - `file_path`: 日志文件的路径或HTTP(S)链接
+/// - `max_scan_bytes`: 最多扫描的字节数（可选），超出部分按平均行长估算行数
+/// - `window`: 调用命令的窗口，用于发送进度事件
+/// - `state`: 应用状态，包含下载器
 ///
 /// # Returns
 /// - `Ok(FileInfoResponse)`: 包含文件基本信息的响应
 /// - `Err(String)`: 获取文件信息失败时的错误信息
 #[tauri::command]
-async fn get_file_info(file_path: String) -> Result<FileInfoResponse, String> {
+async fn get_file_info(
+    file_path: String,
+    max_scan_bytes: Option<u64>,
+    window: tauri::Window,
+    state: tauri::State<'_, AppState>,
+) -> Result<FileInfoResponse, String> {
     info!("🔍 [BACKEND_DEBUG] get_file_info 命令调用开始");
     info!("📊 [BACKEND_DEBUG] 获取文件信息: {}", file_path);
 
+    let file_path = resolve_local_path(file_path, &window, &state).await?;
+
     // 文件存在性检查
     let path_obj = std::path::Path::new(&file_path);
     if !path_obj.exists() {
         error!("❌ [BACKEND_DEBUG] 文件不存在: {}", file_path);
-        return Err(format!("文件不存在: {}", file_path));
+        return Err(Message::new("file.not_found").with("path", &file_path).text());
     }
 
     if !path_obj.is_file() {
         error!("❌ [BACKEND_DEBUG] 路径不是文件: {}", file_path);
-        return Err(format!("路径不是文件: {}", file_path));
+        return Err(Message::new("file.not_a_file").with("path", &file_path).text());
     }
 
-    // 获取文件元数据
-    let metadata = match std::fs::metadata(&file_path) {
-        Ok(meta) => {
-            info!("✅ [BACKEND_DEBUG] 文件元数据获取成功");
-            meta
-        },
-        Err(e) => {
-            error!("❌ [BACKEND_DEBUG] 获取文件元数据失败: {} - 错误: {}", file_path, e);
-            return Err(format!("获取文件元数据失败: {}", e));
-        }
-    };
+    // 在阻塞线程中流式统计行数，避免大文件扫描占用异步运行时
+    let scan_path = file_path.clone();
+    let count = tokio::task::spawn_blocking(move || {
+        reader::line_counter::count_lines(&scan_path, max_scan_bytes, |progress| {
+            debug!("📏 [BACKEND_DEBUG] 行数统计进度: {}/{} bytes, {} 行",
+                   progress.bytes_scanned, progress.total_bytes, progress.lines_counted);
+            let payload = FileInfoProgress {
+                file_path: scan_path.clone(),
+                bytes_scanned: progress.bytes_scanned,
+                total_bytes: progress.total_bytes,
+                lines_counted: progress.lines_counted,
+            };
+            if let Err(e) = window.emit("file-info-progress", payload) {
+                warn!("⚠️ 发送进度事件失败: {}", e);
+            }
+        })
+    })
+    .await
+    .map_err(|e| Message::new("task.line_count_failed").with("error", e).text())??;
 
-    let file_size = metadata.len();
+    let file_size = count.total_bytes;
+    let total_lines = count.lines;
     info!("📏 [BACKEND_DEBUG] 文件大小: {} bytes ({} MB)", file_size, file_size / 1024 / 1024);
-
-    // 对于大文件，采样读取前1000行来估算总行数
-    let total_lines = if file_size > 10_000_000 { // 10MB以上的文件
-        info!("📏 [BACKEND_DEBUG] 大文件检测，采样估算行数");
-        match std::fs::read_to_string(&file_path) {
-            Ok(content) => {
-                let sample_lines: Vec<&str> = content.lines().take(1000).collect();
-                let sample_count = sample_lines.len();
-                if sample_count > 0 {
-                    // 基于采样估算总行数
-                    let avg_line_len = content.len() / sample_count;
-                    let estimated_lines = file_size as usize / avg_line_len;
-                    info!("📊 [BACKEND_DEBUG] 估算总行数: {} (基于{}行采样)", estimated_lines, sample_count);
-                    estimated_lines
-                } else {
-                    warn!("⚠️ [BACKEND_DEBUG] 文件内容为空，无法估算行数");
-                    0
-                }
-            }
-            Err(e) => {
-                warn!("⚠️ [BACKEND_DEBUG] 无法读取文件内容进行行数估算: {}", e);
-                0
-            }
-        }
+    if count.estimated {
+        info!("📊 [BACKEND_DEBUG] 估算总行数: {} (基于前{}字节采样)", total_lines, count.bytes_scanned);
     } else {
-        // 小文件直接计算准确行数
-        info!("📏 [BACKEND_DEBUG] 小文件直接计算行数");
-        match std::fs::read_to_string(&file_path) {
-            Ok(content) => {
-                let lines = content.lines().count();
-                info!("📊 [BACKEND_DEBUG] 准确行数统计: {} 行", lines);
-                lines
-            }
-            Err(e) => {
-                warn!("⚠️ [BACKEND_DEBUG] 无法读取文件内容: {}", e);
-                0
-            }
-        }
-    };
+        info!("📊 [BACKEND_DEBUG] 准确行数统计: {} 行", total_lines);
+    }
 
     // 推荐分块大小
     let recommended_chunk_size = if total_lines > 100_000 {
@@ -316,52 +531,113 @@ async fn get_file_info(file_path: String) -> Result<FileInfoResponse, String> {
         total_lines,
         recommended_chunk_size,
         is_large_file,
+        lines_estimated: count.estimated,
     };
 
     info!("✅ [BACKEND_DEBUG] get_file_info 命令完成: {:?}", response);
     Ok(response)
 }
 
+/// 下载HTTP(S)日志到本地缓存
+///
+/// 流式下载，下载过程中约每1MB向窗口发送一次 `download-progress` 事件。
+/// 已完成的下载直接复用缓存；上次中断的下载在服务器支持Range时续传。
+///
+/// # 参数
+/// - `url`: HTTP(S)链接
+/// - `refresh`: 是否忽略缓存重新下载（可选，默认false）
+/// - `window`: 接收进度事件的窗口
+/// - `state`: 应用状态，包含下载器
+///
+/// # Returns
+/// - `Ok(DownloadResult)`: 本地缓存路径和下载信息
+/// - `Err(String)`: 网络错误或服务器返回错误状态时的错误信息
+#[tauri::command]
+async fn download_url(
+    url: String,
+    refresh: Option<bool>,
+    window: tauri::Window,
+    state: tauri::State<'_, AppState>,
+) -> Result<DownloadResult, String> {
+    info!("🌐 下载日志: {}", url);
+    let downloads = state.downloads.clone();
+    tokio::task::spawn_blocking(move || {
+        downloads.download(&url, refresh.unwrap_or(false), |progress| {
+            if let Err(e) = window.emit("download-progress", progress.clone()) {
+                warn!("⚠️ 发送下载进度事件失败: {}", e);
+            }
+        })
+    })
+    .await
+    .map_err(|e| Message::new("task.download_failed").with("error", e).text())?
+}
+
+/// 将HTTP(S)链接解析为本地缓存路径，其他路径原样返回
+async fn resolve_local_path(file_path: String, window: &tauri::Window, state: &tauri::State<'_, AppState>) -> Result<String, String> {
+    if !reader::http::is_http_url(&file_path) {
+        return Ok(file_path);
+    }
+    let result = download_url(file_path, None, window.clone(), state.clone()).await?;
+    Ok(result.local_path)
+}
+
+/// 列出压缩包中的文件
+///
+/// 支持 zip、tar、tar.gz/tgz 和单文件 gz，不会解压到磁盘。
+/// 返回的 `virtual_path`（`<压缩包>!/<成员>`）可直接作为 `parse_log` 的 `file_path`。
+///
+/// # 参数
+/// - `archive_path`: 压缩包路径
+///
+/// # Returns
+/// - `Ok(Vec<ArchiveEntry>)`: 压缩包中的文件列表
+/// - `Err(String)`: 格式不支持或压缩包损坏时的错误信息
+#[tauri::command]
+async fn list_archive_entries(archive_path: String) -> Result<Vec<ArchiveEntry>, String> {
+    let path = archive_path.clone();
+    let entries = tokio::task::spawn_blocking(move || reader::archive::list_entries(&path))
+        .await
+        .map_err(|e| Message::new("task.archive_failed").with("error", e).text())??;
+    info!("🗜️ 压缩包 {} 中有 {} 个文件", archive_path, entries.len());
+    Ok(entries)
+}
+
 /// 获取可用的日志解析插件列表
 ///
-/// 返回当前系统中所有可用的日志解析插件信息，
-/// 包括插件名称、描述和版本信息。这些信息用于前端显示插件选择界面。
+/// 从插件管理器的注册表读取当前所有单插件解析器和插件链（包括按插件设置重建的自定义布局链），
+/// 包括插件名称、描述、版本、支持的扩展名和自动检测能力。这些信息用于前端显示插件选择界面。
 ///
 /// # Returns
-/// - `Ok(PluginsResponse)`: 包含所有可用插件信息的响应
+/// - `Ok(PluginsResponse)`: 包含所有可用插件信息的响应（按名称排序）
 /// - `Err(String)`: 获取插件列表失败时的错误信息
+#[tauri::command]
+async fn get_plugins(state: tauri::State<'_, AppState>) -> Result<PluginsResponse, String> {
+    Ok(PluginsResponse { plugins: state.plugin_manager.get_available_plugins() })
+}
+
+/// 获取可以在解析请求中指定的格式
 ///
-/// # 插件列表
-/// - auto: 自动检测日志格式
-/// - mybatis: MyBatis SQL日志解析器
-/// - docker_json: Docker JSON格式日志解析器
-/// - raw: 原始文本日志解析器
-#[tauri::command]
-async fn get_plugins() -> Result<PluginsResponse, String> {
-    let plugins = vec![
-        Plugin {
-            name: "auto".to_string(),
-            description: "自动检测".to_string(),
-            version: "1.0.0".to_string(),
-        },
-        Plugin {
-            name: "mybatis".to_string(),
-            description: "MyBatis SQL 解析器".to_string(),
-            version: "1.0.0".to_string(),
-        },
-        Plugin {
-            name: "docker_json".to_string(),
-            description: "Docker JSON 日志".to_string(),
-            version: "1.0.0".to_string(),
-        },
-        Plugin {
-            name: "raw".to_string(),
-            description: "原始文本".to_string(),
-            version: "1.0.0".to_string(),
-        },
-    ];
+/// 与 `get_plugins` 来自同一注册表，只返回已启用的插件，即解析请求 `plugin` 字段可以使用的名称。
+///
+/// # Returns
+/// - `Ok(Vec<PluginInfo>)`: 已启用的插件链和解析器（按名称排序）
+#[tauri::command]
+async fn get_supported_formats(state: tauri::State<'_, AppState>) -> Result<Vec<PluginInfo>, String> {
+    Ok(state.plugin_manager.get_available_plugins()
+        .into_iter()
+        .filter(|plugin| plugin.enabled)
+        .collect())
+}
 
-    Ok(PluginsResponse { plugins })
+/// 获取可用的输出渲染器列表
+///
+/// 返回可在解析请求 `renderer` 字段中使用的渲染器名称。
+///
+/// # Returns
+/// - `Ok(Vec<String>)`: 渲染器名称列表（如plain、ansi、html、markdown）
+#[tauri::command]
+async fn get_renderers(state: tauri::State<'_, AppState>) -> Result<Vec<String>, String> {
+    Ok(state.renderers.names())
 }
 
 /// 核心日志解析功能
@@ -370,48 +646,124 @@ async fn get_plugins() -> Result<PluginsResponse, String> {
 /// 支持两种输入模式：文件路径模式和内容传输模式，并支持大文件的分块处理。
 ///
 /// # 功能特性
-/// - 智能格式检测和插件选择
+/// - 智能格式检测和插件选择（请求的 `plugin` 字段指定插件链或解析器时跳过自动检测）
 /// - 大文件分块处理，避免内存溢出
+/// - 条目行号与文件一致：空行默认跳过，`preserve_blank_lines` 时作为空条目返回（raw解析器默认保留）
+/// - 文件路径模式下条目元数据包含源文件中的字节区间（`start_offset` / `end_offset`）
 /// - 性能监控和详细日志记录
 /// - 错误处理和优雅降级
 /// - 支持多种日志格式（SpringBoot、Docker JSON、MyBatis等）
 ///
 /// # 参数
-/// - `request`: 解析请求，包含文件路径或内容、插件选择等信息
+/// - `request`: 解析请求，包含文件路径（也可以是HTTP(S)链接）或内容、插件选择等信息
+/// - `window`: 调用命令的窗口，下载HTTP(S)链接时用于发送进度事件
 /// - `state`: 应用状态，包含插件管理器和配置服务
 ///
 /// # Returns
 /// - `Ok(ParseResponse)`: 解析结果，包含解析的日志条目和统计信息
-/// - `Err(String)`: 解析失败时的错误信息
+/// - `Err(String)`: 解析失败时的错误信息（指定的插件不存在时列出所有可用插件）
 ///
 /// # 性能考虑
 /// - 小文件（<1000行）：直接使用插件系统处理
 /// - 大文件（≥1000行）：自动分块处理，降低内存使用
+/// - 自适应分块（`adaptive_chunking`）：按1MB字节预算和上一块的解析速度选择块大小，
+///   选中的行数和起始行在 `chunk_info` 中返回
 /// - 智能缓存：避免重复的文件读取和解析操作
 #[tauri::command]
-async fn parse_log(request: ParseRequest, state: tauri::State<'_, AppState>) -> Result<ParseResponse, String> {
+async fn parse_log(mut request: ParseRequest, window: tauri::Window, state: tauri::State<'_, AppState>) -> Result<ParseResponse, String> {
     let start_time = std::time::Instant::now();
 
     info!("🚀 [BACKEND_DEBUG] parse_log 命令调用开始");
-    info!("📥 [BACKEND_DEBUG] 收到日志解析请求: {:?}", request);
+    info!("📥 [BACKEND_DEBUG] 收到日志解析请求: file_path={:?}, plugin={:?}, chunk_size={:?}, chunk_index={:?}, content={} bytes",
+          request.file_path, request.plugin, request.chunk_size, request.chunk_index, request.content.len());
     debug!("⏱️ [BACKEND_DEBUG] 开始性能计时");
 
+    // HTTP(S)链接先下载到本地缓存，之后按本地文件处理
+    if let Some(file_path) = request.file_path.take() {
+        request.file_path = Some(resolve_local_path(file_path, &window, &state).await?);
+    }
+
+    // 提前校验渲染器名称，避免解析完成后才发现请求无效
+    if let Some(renderer) = &request.renderer {
+        state.renderers.get(renderer)?;
+    }
+    let requested_plugin = state.plugin_manager.resolve_plugin(request.plugin.as_deref())?;
+
+    // 超长行截断阈值（字符数），避免单行巨型内容拖垮前端渲染
+    let parse_config = state.config_service.lock().await.get_parse_config().ok();
+    let max_line_length = parse_config.as_ref().map(|config| config.max_line_length).unwrap_or(0);
+    // 请求未要求合并终端折行时按解析配置决定
+    request.merge_wrapped_lines |= parse_config.as_ref().is_some_and(|config| config.merge_wrapped_lines);
+    // 请求未指定严格模式时按解析配置决定，显式关闭的请求不受配置影响
+    if request.strict_content.is_none() {
+        request.strict_content = parse_config.as_ref().map(|config| config.strict_content);
+    }
+    // 请求未指定格式化风格时按解析配置决定
+    if request.formatting.is_none() {
+        request.formatting = parse_config.as_ref().map(|config| config.formatting);
+    }
+    // 请求未携带紧凑显示设置时按解析配置决定
+    if request.compact.is_none() {
+        request.compact = parse_config.map(|config| config.compact);
+    }
+
+    // 文件匹配的解析配置：插件设置在解析前生效，派生字段在解析后写入
+    let profile = file_profile(request.file_path.as_deref(), &state).await;
+    activate_profile_settings(profile.as_ref(), &state).await;
+
+    // 请求指定了插件或用户为该文件指定了固定格式时跳过自动检测，请求指定的插件优先
+    let format = match requested_plugin {
+        Some(plugin) => {
+            info!("📌 使用请求指定的插件: {}", plugin);
+            Some(plugin)
+        }
+        None => {
+            let format = format_override(request.file_path.as_deref(), &state).await;
+            if let Some(format) = &format {
+                info!("📌 使用文件指定的格式: {}", format);
+            }
+            format
+        }
+    };
+    let plugin = format.clone().unwrap_or_else(|| "auto".to_string());
+
+    // 文件路径模式下加载该文件的固定条目，用于标记解析结果
+    let pinned = match &request.file_path {
+        Some(file_path) => state.config_service.lock().await
+            .get_pinned_entries(file_path, None)
+            .unwrap_or_else(|e| {
+                warn!("⚠️ 加载固定条目失败: {}", e);
+                Vec::new()
+            }),
+        None => Vec::new(),
+    };
+
     // 第一步：确定内容来源
     // 支持两种模式：文件路径模式（从磁盘读取）和内容传输模式（直接传入内容）
-    let content = if let Some(file_path) = &request.file_path {
+    let mut content = if let Some((archive_path, member)) = request.file_path.as_deref().and_then(reader::archive::split_member_path) {
+        // 压缩包成员模式：不解压到磁盘，直接流式读取压缩包中的单个文件
+        info!("🗜️ [BACKEND_DEBUG] 读取压缩包成员: {} -> {}", archive_path, member);
+        match reader::archive::read_member(archive_path, member, None) {
+            Ok(content) => content,
+            Err(e) => {
+                error!("❌ [BACKEND_DEBUG] 读取压缩包成员失败: {}", e);
+                return Ok(create_error_response(Message::new("file.read_failed").with("path", archive_path).with("error", e)));
+            }
+        }
+    } else if let Some(file_path) = &request.file_path {
         // 文件路径模式：从指定的文件路径读取日志内容
         info!("📁 [BACKEND_DEBUG] 使用文件路径模式: {}", file_path);
 
         // 文件存在性检查：确保文件可访问
         if !std::path::Path::new(file_path).exists() {
             error!("❌ [BACKEND_DEBUG] 文件不存在: {}", file_path);
-            return Ok(create_error_response("文件不存在", file_path));
+            return Ok(create_error_response(Message::new("file.not_found").with("path", file_path)));
         }
 
         // 文件类型检查：确保是普通文件而非目录
         if !std::path::Path::new(file_path).is_file() {
             error!("❌ [BACKEND_DEBUG] 路径不是文件: {}", file_path);
-            return Ok(create_error_response("路径不是文件", file_path));
+            return Ok(create_error_response(Message::new("file.not_a_file").with("path", file_path)));
         }
 
         // 文件读取：安全地读取文件内容
@@ -422,35 +774,31 @@ async fn parse_log(request: ParseRequest, state: tauri::State<'_, AppState>) ->
             }
             Err(e) => {
                 error!("❌ [BACKEND_DEBUG] 读取文件失败: {} - 错误: {}", file_path, e);
-                return Ok(create_error_response(&format!("读取文件失败: {}", e), file_path));
+                return Ok(create_error_response(Message::new("file.read_failed").with("path", file_path).with("error", e)));
             }
         }
-    } else if let Some(content) = &request.content {
-        // 内容传输模式：直接使用传入的日志内容
-        info!("📝 [BACKEND_DEBUG] 使用内容传输模式，大小: {} bytes", content.len());
-        content.clone()
+    } else if !request.content.is_empty() {
+        // 内容传输模式：直接接管传入的日志内容，不复制
+        info!("📝 [BACKEND_DEBUG] 使用内容传输模式，大小: {} bytes", request.content.len());
+        std::mem::take(&mut request.content)
     } else {
         // 错误处理：既没有文件路径也没有内容
         error!("❌ [BACKEND_DEBUG] 请求中既没有文件路径也没有内容");
-        return Ok(ParseResponse {
-            success: false,
-            entries: vec![],
-            stats: ParseStats {
-                total_lines: 0,
-                success_lines: 0,
-                error_lines: 0,
-                parse_time_ms: 0,
-            },
-            chunk_info: None,
-            error: Some("请求中既没有文件路径也没有内容".to_string()),
-            detected_format: None,
-        });
+        return Ok(create_error_response(Message::new("request.missing_source")));
     };
 
+    // 记录最近打开的文件（分块解析只在第一块记录），供快速搜索面板使用
+    if let (Some(file_path), None | Some(0)) = (&request.file_path, request.chunk_index) {
+        if let Err(e) = state.config_service.lock().await.record_recent_file(file_path) {
+            warn!("⚠️ 记录最近打开的文件失败: {}", e);
+        }
+    }
+
     // 第二步：预处理日志内容
-    // 过滤空行并统计总行数
-    let lines: Vec<&str> = content.lines().filter(|line| !line.trim().is_empty()).collect();
-    let total_lines = lines.len();
+    // 只记录非空行在内容中的位置，不为每一行创建字符串
+    let line_ranges = non_empty_line_ranges(&content);
+    let total_lines = line_ranges.len();
+    let file_lines = content.lines().count();
 
     info!("📊 [BACKEND_DEBUG] 日志预处理完成：{} 行有效内容", total_lines);
 
@@ -461,112 +809,190 @@ async fn parse_log(request: ParseRequest, state: tauri::State<'_, AppState>) ->
 
     // 第三步：确定处理策略（分块 vs 全量处理）
     // 根据文件大小和用户请求确定使用分块处理还是全量处理
-    let chunk_size = request.chunk_size.unwrap_or(1000); // 默认1000行一块
+    let chunk_size = request.chunk_size.unwrap_or(1000).max(1); // 默认1000行一块
     let chunk_index = request.chunk_index.unwrap_or(0);
+    let adaptive = request.adaptive_chunking;
 
     // 分块处理判断逻辑：
     // - 只有文件足够大（>chunk_size）且用户明确请求分块时才启用分块处理
+    // - 自适应分块时内容超过一块的字节预算才分块
     // - 小文件总是使用全量处理以获得最佳解析效果
-    let should_chunk = total_lines > chunk_size && request.chunk_size.is_some();
+    let should_chunk = if adaptive {
+        chunk_index > 0 || content.len() > state.chunk_planner.budget_bytes()
+    } else {
+        total_lines > chunk_size && request.chunk_size.is_some()
+    };
 
     debug!("📏 [BACKEND_DEBUG] 分块处理判断: total_lines={}, chunk_size={}, chunk_size_requested={}, should_chunk={}",
          total_lines, chunk_size, request.chunk_size.is_some(), should_chunk);
 
     if should_chunk {
         // ==================== 分块处理模式 ====================
-        info!("🔧 [BACKEND_DEBUG] 启用分块处理模式：第{}块，每块{}行", chunk_index + 1, chunk_size);
+        // 计算当前块的索引范围：自适应分块按字节预算和上一块的解析速度决定，否则按固定行数
+        let planner_key = request.file_path.clone().unwrap_or_default();
+        let (start_index, end_index, budget_bytes, estimated_chunks) = if adaptive {
+            let lengths: Vec<usize> = line_ranges.iter().map(|(_, range)| range.len()).collect();
+            let plan = state.chunk_planner.plan(&planner_key, chunk_index, &lengths)?;
+            info!("🔧 [BACKEND_DEBUG] 启用自适应分块：第{}块，{}行（预算 {} bytes）",
+                  chunk_index + 1, plan.end_index - plan.start_index, plan.budget_bytes);
+            (plan.start_index, plan.end_index, Some(plan.budget_bytes), plan.estimated_total_chunks)
+        } else {
+            info!("🔧 [BACKEND_DEBUG] 启用分块处理模式：第{}块，每块{}行", chunk_index + 1, chunk_size);
+            let (start_index, end_index) = reader::chunking::fixed_chunk(chunk_index, chunk_size, total_lines);
+            (start_index, end_index, None, total_lines.div_ceil(chunk_size))
+        };
 
-        // 计算当前块的索引范围
-        let start_index = chunk_index * chunk_size;
-        let end_index = std::cmp::min(start_index + chunk_size, total_lines);
+        // 请求的块超出文件末尾（文件变短或前端多请求了一块）时返回空块，不再有后续块
+        if start_index >= total_lines {
+            warn!("⚠️ [BACKEND_DEBUG] 请求的第{}块超出文件末尾（共{}行），返回空块", chunk_index + 1, total_lines);
+            let parse_time = start_time.elapsed().as_millis() as u64;
+            return Ok(ParseResponse {
+                success: true,
+                entries: Vec::new(),
+                stats: ParseStats::from_entries(total_lines, &[], parse_time),
+                chunk_info: Some(ChunkInfo {
+                    total_chunks: estimated_chunks,
+                    current_chunk: chunk_index,
+                    has_more: false,
+                    chunk_size: 0,
+                    start_line: total_lines + 1,
+                    budget_bytes,
+                }),
+                error: None,
+                error_detail: None,
+                detected_format: None,
+                capabilities: None,
+                pinned,
+            });
+        }
+        let chunk_bytes: usize = line_ranges[start_index..end_index].iter().map(|(_, range)| range.len() + 1).sum();
 
         debug!("📏 [BACKEND_DEBUG] 分块范围: 第{}-{}行（共{}行）", start_index + 1, end_index, total_lines);
 
-        // 提取当前块的原始日志内容作为字符串
-        let chunk_content: String = lines.iter()
-            .enumerate()
-            .skip(start_index)
-            .take(chunk_size)
-            .map(|(_, line)| *line)
-            .collect::<Vec<&str>>()
-            .join("\n");
-
-        info!("📊 [BACKEND_DEBUG] 分块内容长度: {} 字符", chunk_content.len());
-
-        // 使用插件链的自动检测系统处理分块内容
-        let parse_request = crate::plugins::ParseRequest {
+        // 提取当前块的原始日志内容作为字符串，开头携带上一块末尾的重叠行，
+        // 使跨越块边界的多行记录能找到所属的记录头
+        let overlap = request.chunk_overlap.unwrap_or(DEFAULT_CHUNK_OVERLAP).min(start_index);
+        let first = line_ranges[start_index.saturating_sub(overlap)].clone();
+        let last_end = line_ranges[end_index - 1].1.end;
+        // 块第一行和内容第一行在文件中的行序号，内容中保留空行使行号与文件一致
+        let (chunk_start_line, content_start_line) = (line_ranges[start_index].0, first.0);
+        // 块覆盖的行序号范围：两块之间的空行归入后一块
+        let chunk_line_range = if start_index == 0 { 0 } else { line_ranges[start_index - 1].0 + 1 }
+            ..if end_index == total_lines { file_lines } else { line_ranges[end_index - 1].0 + 1 };
+
+        // 原地裁剪内容，不再复制
+        content.truncate(last_end);
+        content.replace_range(..first.1.start, "");
+        let chunk_content = content;
+
+        info!("📊 [BACKEND_DEBUG] 分块内容长度: {} 字符（含 {} 行重叠）", chunk_content.len(), overlap);
+
+        // 使用插件链的自动检测系统处理分块内容，条目行号由插件链换算为全局行号
+        let parse_request = ParseRequest {
             file_path: request.file_path.clone(),
             content: chunk_content,
-            plugin: Some("auto".to_string()),
-            chunk_size: None,
+            plugin: Some(plugin.clone()),
+            chunk_size: Some(end_index - start_index),
+            chunk_index: Some(chunk_index),
+            chunk_overlap: Some(chunk_start_line - content_start_line),
+            chunk_start: Some(chunk_start_line),
+            merge_wrapped_lines: request.merge_wrapped_lines,
+            preserve_blank_lines: request.preserve_blank_lines,
+            compact: request.compact.clone(),
+            strict_content: request.strict_content,
+            formatting: request.formatting,
+            ..Default::default()
         };
 
         debug!("🔍 [BACKEND_DEBUG] 调用插件链自动检测系统处理分块");
-        let parse_result = match state.plugin_manager.auto_detect_and_parse(&parse_request) {
+        let plugin_start = std::time::Instant::now();
+        let (mut parse_result, chunk_format, capabilities) = match parse_with_override(&state.plugin_manager, format.as_deref(), &parse_request) {
             Ok(result) => {
+                state.runtime.record_parse(end_index - start_index, plugin_start.elapsed(), chunk_index == 0);
+                if adaptive {
+                    state.chunk_planner.record(&planner_key, chunk_bytes, plugin_start.elapsed());
+                }
                 info!("✅ [BACKEND_DEBUG] 插件链自动检测成功: {} -> {} 条目",
                       result.lines.len(), result.lines.len());
                 info!("🔍 [BACKEND_DEBUG] 检测格式: {:?}", result.detected_format);
                 if let Some(first_line) = result.lines.first() {
                     info!("🔍 [BACKEND_DEBUG] 第一条记录formatted_content: {:?}", first_line.formatted_content);
                 }
-                result.lines
+                (result.lines, result.detected_format, result.capabilities)
             }
             Err(e) => {
                 error!("❌ [BACKEND_DEBUG] 插件链自动检测失败: {}", e);
                 warn!("🔄 [BACKEND_DEBUG] 回退到通用解析器");
 
-                // 回退到简单的行解析
-                lines.iter()
+                // 回退到简单的行解析（块内容开头的重叠行不产生条目）
+                let lines = parse_request.content.lines()
                     .enumerate()
-                    .skip(start_index)
-                    .take(chunk_size)
-                    .map(|(global_index, line)| {
-                        let log_line = crate::plugins::LogLine {
-                            line_number: global_index + 1,
+                    .skip(chunk_start_line - content_start_line)
+                    .filter(|(_, line)| !line.trim().is_empty())
+                    .map(|(i, line)| {
+                        let log_line = LogEntry {
+                            line_number: content_start_line + i + 1,
                             content: line.to_string(),
                             timestamp: extract_timestamp(line),
                             level: extract_log_level(line),
                             formatted_content: Some(line.trim().to_string()),
+                            message: None,
                             metadata: std::collections::HashMap::new(),
                             processed_by: vec!["fallback_parser".to_string()],
                         };
                         log_line
                     })
-                    .collect()
+                    .collect();
+                (lines, None, None)
             }
         };
 
-        // Convert LogLine to LogEntry
-        let entries: Vec<LogEntry> = parse_result.into_iter().map(|log_line| {
-            LogEntry {
-                line_number: log_line.line_number,
-                content: log_line.content,
-                timestamp: log_line.timestamp,
-                level: log_line.level,
-                formatted_content: log_line.formatted_content,
-                metadata: log_line.metadata,
-                processed_by: log_line.processed_by,
-            }
-        }).collect();
+        truncate_long_lines(&mut parse_result, max_line_length);
+        if let Some(profile) = &profile {
+            profile.apply_derived_fields(&mut parse_result);
+        }
+
+        // 按请求的渲染器重写格式化内容
+        if let Some(renderer) = &request.renderer {
+            state.renderers.apply(renderer, &mut parse_result)?;
+        }
+        config::pins::annotate_pinned(&mut parse_result, &pinned);
+
+        let mut entries = parse_result;
 
         // 计算分块信息
-        let total_chunks = (total_lines + chunk_size - 1) / chunk_size; // 向上取整
-        let has_more = chunk_index + 1 < total_chunks;
+        // 自适应分块的总块数为估算值
+        let total_chunks = estimated_chunks;
+        let has_more = end_index < total_lines;
 
         // 性能统计
         let parse_time = start_time.elapsed().as_millis() as u64;
-        let stats = ParseStats {
-            total_lines,
-            success_lines: entries.len(),
-            error_lines: 0,
-            parse_time_ms: parse_time,
-        };
+        let stats = ParseStats::from_entries(total_lines, &entries, parse_time);
+
+        if let Some(file_path) = &request.file_path {
+            state.chunk_stats.record(file_path, ChunkReport {
+                chunk_index,
+                total_chunks,
+                total_lines,
+                chunk_lines: end_index - start_index,
+                error_lines: stats.error_lines,
+                entries: &entries,
+            });
+        }
+
+        // 统计之后再插入空行条目，空行不计入成功解析的行数
+        if request.preserve_blank_lines.unwrap_or(chunk_format.as_deref() == Some("raw")) {
+            records::fill_blank_lines(&mut entries, &blank_line_numbers(&line_ranges, chunk_line_range));
+        }
+        mark_source_offsets(request.file_path.as_deref(), &mut entries, &state);
 
         let chunk_info = ChunkInfo {
             total_chunks,
             current_chunk: chunk_index,
             has_more,
+            chunk_size: end_index - start_index,
+            start_line: start_index + 1,
+            budget_bytes,
         };
 
         info!("📦 [BACKEND_DEBUG] 分块解析完成: 第{}/{}块，{}条目，耗时: {}ms",
@@ -578,7 +1004,10 @@ async fn parse_log(request: ParseRequest, state: tauri::State<'_, AppState>) ->
             stats,
             chunk_info: Some(chunk_info),
             error: None,
+            error_detail: None,
             detected_format: None, // 分块处理时不做格式检测以提高性能
+            capabilities,
+            pinned,
         };
 
         info!("✅ [BACKEND_DEBUG] 分块解析响应构建完成，条目数: {}", response.entries.len());
@@ -594,60 +1023,70 @@ async fn parse_log(request: ParseRequest, state: tauri::State<'_, AppState>) ->
       // 使用增强插件管理器的自动检测和解析功能
     info!("🔧 使用增强插件管理器进行自动检测和解析");
 
-    let parse_request = crate::plugins::ParseRequest {
-        content: content.clone(),
-        plugin: Some("auto".to_string()), // 使用自动检测
+    // 内容移入请求，行位置仍然有效
+    let parse_request = ParseRequest {
+        content,
+        plugin: Some(plugin),
         file_path: request.file_path.clone(), // 传递文件路径以帮助链选择
         chunk_size: request.chunk_size,
+        merge_wrapped_lines: request.merge_wrapped_lines,
+        preserve_blank_lines: request.preserve_blank_lines,
+        compact: request.compact.clone(),
+        strict_content: request.strict_content,
+        formatting: request.formatting,
+        ..Default::default()
     };
 
     let plugin_start = std::time::Instant::now();
-    let (entries, detected_format) = match state.plugin_manager.auto_detect_and_parse(&parse_request) {
-        Ok(result) => {
+    let (mut entries, detected_format, capabilities) = match parse_with_override(&state.plugin_manager, format.as_deref(), &parse_request) {
+        Ok(mut result) => {
             let plugin_time = plugin_start.elapsed();
+            state.runtime.record_parse(total_lines, plugin_time, true);
             info!("增强插件管理器处理成功，生成 {} 条目，耗时: {}ms，检测格式: {:?}",
                   result.lines.len(), plugin_time.as_millis(), result.detected_format);
 
-            // 性能优化：直接转换，避免中间步骤
-            let conversion_start = std::time::Instant::now();
-            let converted_entries: Vec<LogEntry> = result.lines.into_iter().map(|line| LogEntry {
-                line_number: line.line_number,
-                content: line.content,
-                timestamp: line.timestamp,
-                level: line.level,
-                formatted_content: line.formatted_content,
-                metadata: line.metadata,
-                processed_by: line.processed_by,
-            }).collect();
-            let conversion_time = conversion_start.elapsed();
-            info!("数据转换耗时: {}ms", conversion_time.as_millis());
-
-            let detected_format = result.detected_format.clone();
-            (converted_entries, detected_format)
+            truncate_long_lines(&mut result.lines, max_line_length);
+            if let Some(profile) = &profile {
+                profile.apply_derived_fields(&mut result.lines);
+            }
+
+            // 按请求的渲染器重写格式化内容
+            if let Some(renderer) = &request.renderer {
+                state.renderers.apply(renderer, &mut result.lines)?;
+            }
+            config::pins::annotate_pinned(&mut result.lines, &pinned);
+
+            (result.lines, result.detected_format, result.capabilities)
         }
         Err(e) => {
             error!("增强插件管理器处理失败: {}", e);
+            let message = Message::new("parse.plugin_failed").with("error", &e);
             // 快速回退处理，避免重复计算
             return Ok(ParseResponse {
                 success: true,
-                entries: lines.iter().enumerate().map(|(i, line)| LogEntry {
+                entries: line_ranges.iter().map(|(i, range)| LogEntry {
                     line_number: i + 1,
-                    content: line.to_string(),
+                    content: parse_request.content[range.clone()].to_string(),
                     timestamp: None,
                     level: None,
-                    formatted_content: Some(line.trim().to_string()),
+                    formatted_content: Some(parse_request.content[range.clone()].trim().to_string()),
+                    message: None,
                     metadata: std::collections::HashMap::new(),
                     processed_by: vec!["fallback_parser".to_string()],
                 }).collect(),
                 stats: ParseStats {
-                    total_lines: lines.len(),
-                    success_lines: lines.len(),
+                    total_lines,
+                    success_lines: total_lines,
                     error_lines: 0,
+                    error_line_numbers: Vec::new(),
                     parse_time_ms: start_time.elapsed().as_millis() as u64,
                 },
                 chunk_info: None,
-                error: Some(format!("增强插件管理器处理失败: {}", e)),
+                error: Some(message.text()),
+                error_detail: Some(message),
                 detected_format: Some("Unknown".to_string()),
+                capabilities: None,
+                pinned,
             });
         }
     };
@@ -656,12 +1095,7 @@ async fn parse_log(request: ParseRequest, state: tauri::State<'_, AppState>) ->
     // JSON序列化性能监控
     let json_start = std::time::Instant::now();
 
-    let stats = ParseStats {
-        total_lines: lines.len(),
-        success_lines: entries.len(),
-        error_lines: 0,
-        parse_time_ms: parse_time,
-    };
+    let stats = ParseStats::from_entries(total_lines, &entries, parse_time);
 
     // 预估JSON大小
     let estimated_json_size = entries.iter()
@@ -671,9 +1105,25 @@ async fn parse_log(request: ParseRequest, state: tauri::State<'_, AppState>) ->
     let json_time = json_start.elapsed();
     info!("JSON序列化预估耗时: {}ms，预估大小: {} bytes", json_time.as_millis(), estimated_json_size);
 
+    if let Some(file_path) = &request.file_path {
+        state.chunk_stats.record(file_path, ChunkReport {
+            chunk_index: 0,
+            total_chunks: 1,
+            total_lines,
+            chunk_lines: total_lines,
+            error_lines: stats.error_lines,
+            entries: &entries,
+        });
+    }
+
+    if request.preserve_blank_lines.unwrap_or(detected_format.as_deref() == Some("raw")) {
+        records::fill_blank_lines(&mut entries, &blank_line_numbers(&line_ranges, 0..file_lines));
+    }
+    mark_source_offsets(request.file_path.as_deref(), &mut entries, &state);
+
     let detected_format_display = detected_format.clone().unwrap_or_else(|| "Unknown".to_string());
     info!("全量解析完成: {} 行，处理为 {} 条目，耗时: {}ms，检测格式: {}",
-              lines.len(), entries.len(), parse_time, detected_format_display);
+              total_lines, entries.len(), parse_time, detected_format_display);
 
     let response_start = std::time::Instant::now();
     let response = ParseResponse {
@@ -682,7 +1132,10 @@ async fn parse_log(request: ParseRequest, state: tauri::State<'_, AppState>) ->
         stats,
         chunk_info: None,
         error: None,
+        error_detail: None,
         detected_format: detected_format,
+        capabilities,
+        pinned,
     };
     let response_time = response_start.elapsed();
     info!("响应构建耗时: {}ms", response_time.as_millis());
@@ -690,6 +1143,55 @@ async fn parse_log(request: ParseRequest, state: tauri::State<'_, AppState>) ->
     Ok(response)
 }
 
+/// 获取文件分块解析的累计统计
+///
+/// 分块加载时每个响应的 `stats` 只包含当前块，界面据此显示已加载部分的准确总数。
+/// 同一块重复请求不会重复计数，请求第0块时重新开始累计。
+///
+/// # 参数
+/// - `file_path`: 日志文件路径（与 `parse_log` 请求中的路径一致）
+/// - `state`: 应用状态，包含分块统计
+///
+/// # Returns
+/// - `Ok(AccumulatedStats)`: 已解析各块的条目数、错误行数和级别分布
+/// - `Err(String)`: 该文件还没有解析记录时的错误信息
+#[tauri::command]
+async fn get_accumulated_stats(file_path: String, state: tauri::State<'_, AppState>) -> Result<AccumulatedStats, String> {
+    state.chunk_stats.get(&file_path)
+        .ok_or_else(|| Message::new("parse.no_history").with("path", &file_path).text())
+}
+
+/// 解析剪贴板中的日志
+///
+/// 读取系统剪贴板中的文本，交给与 `parse_log` 相同的解析流程（内容模式，自动检测格式），
+/// 用户从聊天工具或终端复制的日志片段无需先保存为文件。
+///
+/// # 参数
+/// - `plugin`: 指定插件（可选，默认自动检测）
+/// - `window`: 调用命令的窗口
+/// - `state`: 应用状态
+///
+/// # Returns
+/// - `Ok(ParseResponse)`: 解析结果
+/// - `Err(String)`: 剪贴板不可读或没有文本时的错误信息
+#[tauri::command]
+async fn parse_clipboard(plugin: Option<String>, window: tauri::Window, state: tauri::State<'_, AppState>) -> Result<ParseResponse, String> {
+    use tauri::{ClipboardManager, Manager};
+
+    let content = window.app_handle().clipboard_manager().read_text()
+        .map_err(|e| Message::new("clipboard.read_failed").with("error", e).text())?
+        .filter(|text| !text.trim().is_empty())
+        .ok_or_else(|| Message::new("clipboard.empty").text())?;
+    info!("📋 解析剪贴板内容: {} 字节", content.len());
+
+    let request = ParseRequest {
+        content,
+        plugin: Some(plugin.unwrap_or_else(|| "auto".to_string())),
+        ..Default::default()
+    };
+    parse_log(request, window, state).await
+}
+
 /// 测试解析端点
 ///
 /// 用于测试日志解析功能的可用性和参数验证。
@@ -719,7 +1221,7 @@ async fn test_parse(request: ParseRequest) -> Result<serde_json::Value, String>
     // 检查请求类型，用于验证前端参数传递
     let request_type = if request.file_path.is_some() {
         "file_path"
-    } else if request.content.is_some() {
+    } else if !request.content.is_empty() {
         "content"
     } else {
         "unknown"
@@ -735,92 +1237,2270 @@ async fn test_parse(request: ParseRequest) -> Result<serde_json::Value, String>
     }))
 }
 
-// ============================================================================
-// 主题配置管理命令
-// ============================================================================
+/// 分块解析时默认携带的上一块末尾行数
+const DEFAULT_CHUNK_OVERLAP: usize = 50;
 
-/// 获取当前主题配置
-///
-/// 返回应用程序的当前主题设置，包括颜色方案、字体配置等。
-/// 主题配置影响用户界面的外观和显示效果。
+/// 解析预览默认读取的行数
+const PREVIEW_DEFAULT_LINES: usize = 200;
+
+/// 解析预览最多读取的行数
+const PREVIEW_MAX_LINES: usize = 10_000;
+
+/// 预览解析结果（只解析文件开头的N行）
 ///
-/// # 功能特性
-/// - 支持明暗主题切换（Light/Dark/Auto）
-/// - 可自定义主色调和强调色
-/// - 字体大小和字体族配置
-/// - 配置持久化存储
+/// 解析大文件之前先用开头的少量行试运行插件链，返回解析条目、选中的链
+/// 以及实际执行的过滤器，便于用户确认格式识别是否正确。
 ///
 /// # 参数
-/// - `state`: 应用状态，包含配置服务实例
+/// - `file_path`: 日志文件路径
+/// - `plugin`: 插件链或插件名称（可选，默认 `auto` 自动选择）
+/// - `n_lines`: 读取的行数（可选，默认200，最多10000）
+/// - `state`: 应用状态，包含插件管理器
 ///
 /// # Returns
-/// - `Ok(ThemeResponse)`: 包含当前主题配置的响应
-/// - `Err(String)`: 获取配置失败时的错误信息
-///
-/// # 主题模式说明
-/// - Light: 浅色主题，适合白天使用
-/// - Dark: 深色主题，适合夜间使用，保护视力
-/// - Auto: 自动跟随系统主题设置
+/// - `Ok(PreviewParseResponse)`: 预览的解析结果
+/// - `Err(String)`: 文件不可读、插件不存在或解析失败时的错误信息
 #[tauri::command]
-async fn get_theme_config(state: tauri::State<'_, AppState>) -> Result<ThemeResponse, String> {
-    debug!("🎨 获取主题配置");
+async fn preview_parse(
+    file_path: String,
+    plugin: Option<String>,
+    n_lines: Option<usize>,
+    state: tauri::State<'_, AppState>,
+) -> Result<PreviewParseResponse, String> {
+    use std::io::BufRead;
+
+    let n_lines = n_lines.unwrap_or(PREVIEW_DEFAULT_LINES).clamp(1, PREVIEW_MAX_LINES);
+    let plugin = plugin.unwrap_or_else(|| "auto".to_string());
+    info!("👀 预览解析: {} (插件: {}, 前 {} 行)", file_path, plugin, n_lines);
+
+    let file = std::fs::File::open(&file_path)
+        .map_err(|e| Message::new("file.read_failed").with("path", &file_path).with("error", e).text())?;
+    let mut lines = std::io::BufReader::new(file).lines();
+    let head = lines.by_ref()
+        .take(n_lines)
+        .collect::<Result<Vec<String>, _>>()
+        .map_err(|e| Message::new("file.read_failed").with("path", &file_path).with("error", e).text())?;
+    let has_more = lines.next().is_some();
+
+    let request = ParseRequest {
+        content: head.join("\n"),
+        plugin: Some(plugin.clone()),
+        file_path: Some(file_path.clone()),
+        ..Default::default()
+    };
+    let result = if plugin == "auto" {
+        state.plugin_manager.auto_detect_and_parse(&request)?
+    } else if state.plugin_manager.get_available_chains().contains(&plugin) {
+        state.plugin_manager.process_with_chain(&plugin, &request)?
+    } else {
+        state.plugin_manager.parse_with_plugin(&plugin, &request)?
+    };
 
-    match state.config_service.lock().await.get_theme_config() {
-        Ok(theme) => {
-            debug!("✅ 主题配置获取成功: mode={:?}", theme.mode);
+    info!("✅ 预览完成: 格式 {:?}, {} 条, 过滤器 {:?}", result.detected_format, result.lines.len(), result.applied_filters);
+    Ok(PreviewParseResponse {
+        file_path,
+        lines_read: head.len(),
+        has_more,
+        detected_format: result.detected_format,
+        applied_filters: result.applied_filters,
+        entries: result.lines,
+        parsing_errors: result.parsing_errors,
+    })
+}
 
-            // 将内部主题模式枚举转换为前端字符串格式
-            let response = ThemeResponse {
-                mode: match theme.mode {
-                    ThemeMode::Light => "light".to_string(),
-                    ThemeMode::Dark => "dark".to_string(),
-                    ThemeMode::Auto => "auto".to_string(),
-                },
-                primary_color: theme.primary_color,
-                accent_color: theme.accent_color,
-                font_size: theme.font_size,
-                font_family: theme.font_family,
-            };
+/// 分析测试运行结果
+///
+/// 识别 `cargo test`、`go test -v`、`pytest` 的输出，汇总通过/失败/跳过的测试，
+/// 并列出每个失败测试及其捕获的输出块，便于CI日志排查。
+///
+/// # 参数
+/// - `file_path`: 测试输出文件路径（可选）
+/// - `content`: 测试输出内容（可选，未提供文件路径时使用）
+///
+/// # Returns
+/// - `Ok(TestRunSummary)`: 测试运行汇总
+/// - `Err(String)`: 未提供输入或文件不可读时的错误信息
+#[tauri::command]
+async fn analyze_test_run(file_path: Option<String>, content: Option<String>) -> Result<TestRunSummary, String> {
+    let content = match (file_path, content) {
+        (Some(file_path), _) => std::fs::read_to_string(&file_path)
+            .map_err(|e| Message::new("file.read_failed").with("path", &file_path).with("error", e).text())?,
+        (None, Some(content)) => content,
+        (None, None) => return Err(Message::new("request.missing_source").text()),
+    };
 
-            Ok(response)
-        }
-        Err(e) => {
-            error!("❌ 获取主题配置失败: {}", e);
-            Err("获取主题配置失败".to_string())
-        }
-    }
+    let summary = plugins::test_runner::analyze_test_output(&content)?;
+    info!("🧪 测试运行分析完成: {} 通过, {} 失败, {} 跳过 ({:?})",
+          summary.passed, summary.failed, summary.skipped, summary.frameworks);
+    Ok(summary)
 }
 
-/// 更新主题配置
+/// 分析AWS Lambda调用
 ///
-/// 根据用户的请求更新应用程序的主题设置。
-/// 支持部分更新，只修改请求中明确指定的字段。
-///
-/// # 功能特性
-/// - 部分更新支持：只更新提供的字段
-/// - 配置验证：确保主题参数的有效性
-/// - 持久化存储：自动保存配置到本地存储
-/// - 实时生效：更新后立即反映到用户界面
+/// 识别Lambda日志和CloudWatch导出文件中的 `START` / `END` / `REPORT` 行，
+/// 按调用汇总耗时、计费耗时、内存使用、冷启动和超时。
 ///
 /// # 参数
-/// - `request`: 主题更新请求，包含要更新的主题字段
-/// - `state`: 应用状态，包含配置服务实例
+/// - `file_path`: 日志文件路径（可选）
+/// - `content`: 日志内容（可选，未提供文件路径时使用）
 ///
 /// # Returns
-/// - `Ok(String)`: 更新成功的确认消息
-/// - `Err(String)`: 更新失败时的错误信息
+/// - `Ok(LambdaSummary)`: 调用汇总
+/// - `Err(String)`: 未提供输入或文件不可读时的错误信息
+#[tauri::command]
+async fn analyze_lambda_invocations(file_path: Option<String>, content: Option<String>) -> Result<LambdaSummary, String> {
+    let content = match (file_path, content) {
+        (Some(file_path), _) => std::fs::read_to_string(&file_path)
+            .map_err(|e| Message::new("file.read_failed").with("path", &file_path).with("error", e).text())?,
+        (None, Some(content)) => content,
+        (None, None) => return Err(Message::new("request.missing_source").text()),
+    };
+
+    let summary = plugins::lambda::analyze_lambda_output(&content)?;
+    info!("λ Lambda调用分析完成: {} 次调用, {} 次冷启动, {} 次超时",
+          summary.total, summary.cold_starts, summary.timeouts);
+    Ok(summary)
+}
+
+/// 分析Kafka broker健康状况
 ///
-/// # 更新流程
-/// 1. 获取当前主题配置作为基础
-/// 2. 验证并更新请求中的字段
-/// 3. 保存新配置到持久化存储
-/// 4. 返回更新结果确认
+/// 识别Kafka服务端日志中的ISR收缩/扩张、消费组再均衡、控制器切换等事件，
+/// 按分区和消费组汇总，找出频繁抖动的分区和反复再均衡的消费组。
+///
+/// # 参数
+/// - `file_path`: 日志文件路径（可选）
+/// - `content`: 日志内容（可选，未提供文件路径时使用）
+///
+/// # Returns
+/// - `Ok(KafkaHealthSummary)`: 健康汇总
+/// - `Err(String)`: 未提供输入或文件不可读时的错误信息
 #[tauri::command]
-async fn update_theme_config(
-    request: ThemeUpdateRequest,
-    state: tauri::State<'_, AppState>,
-) -> Result<String, String> {
-    info!("🎨 收到主题配置更新请求: {:?}", request);
+async fn analyze_kafka_health(file_path: Option<String>, content: Option<String>) -> Result<KafkaHealthSummary, String> {
+    let content = match (file_path, content) {
+        (Some(file_path), _) => std::fs::read_to_string(&file_path)
+            .map_err(|e| Message::new("file.read_failed").with("path", &file_path).with("error", e).text())?,
+        (None, Some(content)) => content,
+        (None, None) => return Err(Message::new("request.missing_source").text()),
+    };
+
+    let summary = plugins::kafka::analyze_kafka_output(&content)?;
+    info!("📨 Kafka健康分析完成: {} 类事件, {} 个分区ISR变化, {} 个消费组再均衡",
+          summary.event_counts.len(), summary.isr_changes_by_partition.len(), summary.rebalances_by_group.len());
+    Ok(summary)
+}
+
+/// 分析SpringBoot启动耗时
+///
+/// 按banner、`Starting ...`、上下文初始化、JPA初始化、Web服务器启动和 `Started ... in X seconds`
+/// 等节点计算各阶段耗时，文件中有多次启动时每次单独统计。
+///
+/// # 参数
+/// - `file_path`: 日志文件路径
+/// - `state`: 应用状态，包含增量解析缓存
+///
+/// # Returns
+/// - `Ok(Vec<StartupBreakdown>)`: 每次启动的耗时分解；没有识别到启动节点时为空
+/// - `Err(String)`: 文件不可读或解析失败时的错误信息
+#[tauri::command]
+async fn get_startup_breakdown(file_path: String, state: tauri::State<'_, AppState>) -> Result<Vec<StartupBreakdown>, String> {
+    let entries = cached_entries(&file_path, &state).await?;
+    let runs = startup::analyze_startup(&entries);
+    info!("🚀 文件 {} 的启动分析: {} 次启动", file_path, runs.len());
+    Ok(runs)
+}
+
+/// 提取SpringBoot启动失败报告
+///
+/// 解析 `APPLICATION FAILED TO START` 区块的 `Description` / `Action` 部分，
+/// 识别失败分类（缺少bean、端口占用、循环依赖等），并附带失败前最近的条件评估报告。
+/// 区块以空行划分，因此读取原始文件内容而不是解析后的条目。
+///
+/// # 参数
+/// - `file_path`: 日志文件路径
+///
+/// # Returns
+/// - `Ok(Some(StartupFailure))`: 最后一次启动失败的报告
+/// - `Ok(None)`: 日志中没有启动失败报告
+/// - `Err(String)`: 文件不可读时的错误信息
+#[tauri::command]
+async fn get_startup_failure(file_path: String) -> Result<Option<StartupFailure>, String> {
+    let content = std::fs::read_to_string(&file_path)
+        .map_err(|e| Message::new("file.read_failed").with("path", &file_path).with("error", e).text())?;
+    let failure = startup::find_startup_failure(&content);
+    match &failure {
+        Some(failure) => info!("🧯 文件 {} 第 {} 行启动失败: {}", file_path, failure.line_number, failure.kind),
+        None => info!("🧯 文件 {} 中没有启动失败报告", file_path),
+    }
+    Ok(failure)
+}
+
+/// 搜索日志
+///
+/// 在后端执行文本/正则搜索，返回每个匹配条目中的命中位置（字节和字符偏移），
+/// 前端据此高亮，无需在JS中重新执行正则。
+///
+/// # 参数
+/// - `options`: 搜索选项（关键字、模式、大小写、全词匹配、结果上限）
+/// - `entries`: 要搜索的已解析条目（可选，优先搜索格式化内容）
+/// - `file_path`: 要搜索的文件（可选，未提供 `entries` 时逐行搜索原始内容）
+///
+/// # Returns
+/// - `Ok(SearchResult)`: 匹配条目及命中位置
+/// - `Err(String)`: 关键字无效、未提供搜索对象或文件不可读时的错误信息
+#[tauri::command]
+async fn search_logs(
+    options: SearchOptions,
+    entries: Option<Vec<LogEntry>>,
+    file_path: Option<String>,
+) -> Result<SearchResult, String> {
+    info!("🔍 搜索日志: {:?} (模式: {:?}, 区分大小写: {}, 全词: {})",
+          options.query, options.mode, options.case_sensitive, options.whole_word);
+
+    let searcher = Searcher::new(&options)?;
+    let result = match (entries, file_path) {
+        (Some(entries), _) => searcher.search_entries(&entries),
+        (None, Some(file_path)) => {
+            tokio::task::spawn_blocking(move || searcher.search_file(&file_path))
+                .await
+                .map_err(|e| Message::new("task.search_failed").with("error", e).text())??
+        }
+        (None, None) => return Err(Message::new("request.missing_source").text()),
+    };
+
+    info!("✅ 搜索完成: {} 条匹配, {} 处命中{}",
+          result.hits.len(), result.total_spans, if result.truncated { "（已截断）" } else { "" });
+    Ok(result)
+}
+
+/// 按字段对文件的解析结果分组
+///
+/// 返回分组值、条目数、级别分布和少量代表条目，界面的"分组"下拉框据此展示，
+/// 无需把全部条目传给前端。
+///
+/// # 参数
+/// - `file_path`: 日志文件路径
+/// - `by`: 分组字段：`level` 按日志级别，其他值按同名元数据（如 `logger`、`thread`、`pod`）
+/// - `filter`: 筛选条件（可选，先筛选再分组）
+/// - `samples`: 每组返回的代表条目数（可选，默认3）
+/// - `state`: 应用状态，包含增量解析缓存
+///
+/// # Returns
+/// - `Ok(GroupSummary)`: 按条目数降序排列的分组
+/// - `Err(String)`: 筛选条件无效、文件不可读或解析失败时的错误信息
+#[tauri::command]
+async fn group_entries(
+    file_path: String,
+    by: String,
+    filter: Option<EntryFilter>,
+    samples: Option<usize>,
+    state: tauri::State<'_, AppState>,
+) -> Result<GroupSummary, String> {
+    let matcher = filter.filter(|filter| !filter.is_empty()).map(|filter| filter.compile()).transpose()?;
+    let entries = cached_entries(&file_path, &state).await?;
+    let summary = grouping::group_entries(
+        &entries,
+        &by,
+        matcher.as_ref(),
+        samples.unwrap_or(grouping::DEFAULT_SAMPLES),
+    );
+    info!("🗂️ 文件 {} 按 {} 分组: {} 组（{}/{} 条）",
+          file_path, by, summary.groups.len(), summary.matched_entries, summary.total_entries);
+    Ok(summary)
+}
+
+/// 抽取筛选结果的代表性条目
+///
+/// 在千万行级的文件上预览筛选条件的效果：直接扫描解析缓存，只保留 `n` 条条目返回，
+/// 不构造全部匹配条目。
+///
+/// # 参数
+/// - `file_path`: 日志文件路径
+/// - `filter`: 筛选条件（可选）
+/// - `n`: 抽样条目数（可选，默认100，最多10000）
+/// - `strategy`: 抽样策略（可选）：`random`（默认）、`stratified`（按级别分层）、`time_spaced`（按时间均匀分布）
+/// - `seed`: 随机种子（可选，相同种子得到相同结果）
+/// - `state`: 应用状态，包含增量解析缓存
+///
+/// # Returns
+/// - `Ok(EntrySample)`: 匹配的条目总数和按行号排序的抽样条目
+/// - `Err(String)`: 筛选条件无效、文件不可读或解析失败时的错误信息
+#[tauri::command]
+async fn sample_entries(
+    file_path: String,
+    filter: Option<EntryFilter>,
+    n: Option<usize>,
+    strategy: Option<SampleStrategy>,
+    seed: Option<u64>,
+    state: tauri::State<'_, AppState>,
+) -> Result<EntrySample, String> {
+    let matcher = filter.filter(|filter| !filter.is_empty()).map(|filter| filter.compile()).transpose()?;
+    let delta = refresh_cached_parse(&file_path, &state).await?;
+    let strategy = strategy.unwrap_or_default();
+    let seed = seed.unwrap_or_else(|| uuid::Uuid::new_v4().as_u128() as u64);
+    let parse_cache = state.parse_cache.clone();
+
+    let label = file_path.clone();
+    let sample = run_analysis_job(&state, &file_path, move |_| {
+        parse_cache.with_entries(&delta.file_path, |entries| {
+            sampling::sample_entries(
+                || entries.iter(),
+                |entry| matcher.as_ref().is_none_or(|matcher| matcher.matches(entry)),
+                n.unwrap_or(100),
+                strategy,
+                seed,
+            )
+        })
+        .ok_or_else(|| Message::new("parse.result_unavailable").with("path", &label).text())
+    })
+    .await?;
+
+    info!("🎲 文件 {} 抽样: {} 条（匹配 {} 条, 策略: {:?}）", file_path, sample.entries.len(), sample.matched, strategy);
+    Ok(sample)
+}
+
+/// 获取缓存统计
+///
+/// 返回内存预算、解析缓存和行索引缓存的总占用、各文件的占用，以及超出预算时淘汰的文件缓存。
+#[tauri::command]
+async fn get_cache_stats(state: tauri::State<'_, AppState>) -> Result<CacheStats, String> {
+    Ok(state.memory_budget.stats(&state.parse_cache, &state.line_index_cache))
+}
+
+/// 获取缓存内存预算设置
+#[tauri::command]
+async fn get_memory_budget(state: tauri::State<'_, AppState>) -> Result<MemoryBudgetSettings, String> {
+    state.config_service.lock().await.get_memory_budget()
+}
+
+/// 保存缓存内存预算设置
+///
+/// 立即生效：当前占用超过新预算时马上按最近最少使用淘汰文件缓存。
+///
+/// # 参数
+/// - `settings`: 预算（MB，至少64）
+/// - `state`: 应用状态，包含配置服务和缓存
+///
+/// # Returns
+/// - `Ok(CacheStats)`: 应用新预算后的缓存统计
+/// - `Err(String)`: 预算无效或保存失败时的错误信息
+#[tauri::command]
+async fn set_memory_budget(settings: MemoryBudgetSettings, state: tauri::State<'_, AppState>) -> Result<CacheStats, String> {
+    settings.validate()?;
+    state.config_service.lock().await.set_memory_budget(&settings)?;
+    state.memory_budget.set_limit(settings.budget_bytes());
+    let evictions = state.memory_budget.enforce(&state.parse_cache, &state.line_index_cache, None);
+    info!("🧠 内存预算设置为 {} MB，淘汰 {} 个文件缓存", settings.budget_mb, evictions.len());
+    Ok(state.memory_budget.stats(&state.parse_cache, &state.line_index_cache))
+}
+
+/// 在后台为文件建立索引
+///
+/// 预先建立行索引并完整解析到缓存，之后打开、搜索和分析该文件时直接使用缓存。
+/// 作为后台任务排队，不会占满工作线程，界面的交互请求始终优先执行。
+///
+/// # 参数
+/// - `file_path`: 日志文件路径
+/// - `state`: 应用状态，包含任务队列
+///
+/// # Returns
+/// - `Ok(String)`: 任务ID，可通过 `list_jobs` 查看进度、`cancel_job` 取消
+/// - `Err(String)`: 提交失败时的错误信息
+#[tauri::command]
+async fn index_file(file_path: String, state: tauri::State<'_, AppState>) -> Result<String, String> {
+    let task = cached_parse_task(&file_path, &state).await;
+    let (id, _) = state.jobs.submit(JobKind::Index, JobPriority::Background, &file_path, task);
+    info!("🗂️ 提交后台索引任务 {}: {}", id, file_path);
+    Ok(id)
+}
+
+/// 列出任务队列中的任务（排队中、运行中和最近结束的任务，按提交顺序）
+#[tauri::command]
+async fn list_jobs(state: tauri::State<'_, AppState>) -> Result<Vec<JobInfo>, String> {
+    Ok(state.jobs.list())
+}
+
+/// 取消任务
+///
+/// 排队中的任务直接移出队列；运行中的任务在下一个检查点停止（如建立行索引之后）。
+///
+/// # 参数
+/// - `job_id`: 任务ID
+/// - `state`: 应用状态，包含任务队列
+///
+/// # Returns
+/// - `Ok(JobInfo)`: 取消后的任务信息
+/// - `Err(String)`: 任务不存在或已结束时的错误信息
+#[tauri::command]
+async fn cancel_job(job_id: String, state: tauri::State<'_, AppState>) -> Result<JobInfo, String> {
+    info!("⏹️ 取消任务: {}", job_id);
+    state.jobs.cancel(&job_id)
+}
+
+/// 挖掘文件的消息模板
+///
+/// 按Drain的思路把消息归纳为模板（可变部分为 `<*>`），返回每个模板的条目数、级别分布、
+/// 参数示例和示例行号，界面的"模式"视图据此展示，比简单去重更能归并同类消息。
+///
+/// # 参数
+/// - `file_path`: 日志文件路径
+/// - `filter`: 筛选条件（可选，先筛选再挖掘）
+/// - `similarity`: 合并到已有模板的相似度阈值（可选，0-1，默认0.5，越大模板越细）
+/// - `limit`: 最多返回的模板数（可选，默认全部）
+/// - `state`: 应用状态，包含增量解析缓存
+///
+/// # Returns
+/// - `Ok(TemplateSummary)`: 按条目数降序排列的模板
+/// - `Err(String)`: 筛选条件无效、文件不可读或解析失败时的错误信息
+#[tauri::command]
+async fn mine_templates(
+    file_path: String,
+    filter: Option<EntryFilter>,
+    similarity: Option<f64>,
+    limit: Option<usize>,
+    state: tauri::State<'_, AppState>,
+) -> Result<TemplateSummary, String> {
+    let matcher = filter.filter(|filter| !filter.is_empty()).map(|filter| filter.compile()).transpose()?;
+    let mut entries = cached_entries(&file_path, &state).await?;
+    if let Some(matcher) = &matcher {
+        entries.retain(|entry| matcher.matches(entry));
+    }
+
+    let similarity = similarity.unwrap_or(templates::DEFAULT_SIMILARITY);
+    let mut summary = run_analysis_job(&state, &file_path, move |_| Ok(templates::mine_templates(&entries, similarity))).await?;
+    info!("🧩 文件 {} 挖掘出 {} 个模板（{} 条）", file_path, summary.templates.len(), summary.total_entries);
+    if let Some(limit) = limit {
+        summary.templates.truncate(limit);
+    }
+    Ok(summary)
+}
+
+/// 读取会话中全部文件的条目
+///
+/// 文件的解析结果已保存到会话数据库时直接读取，否则解析文件；已不存在的文件跳过。
+async fn session_run_entries(session: &str, state: &AppState) -> Result<Vec<LogEntry>, String> {
+    let loaded = state.sessions.load(session)?;
+    // 没有保存过解析结果的会话不创建数据库
+    let store = if state.sessions.entries_db_path(session)?.exists() {
+        Some(open_entry_store(session, state)?)
+    } else {
+        None
+    };
+
+    let mut entries = Vec::new();
+    for file in &loaded.files {
+        let persisted = match &store {
+            Some(store) if store.file(&file.path)?.is_some() => Some(store.load_entries(&file.path)?),
+            _ => None,
+        };
+        match persisted {
+            Some(file_entries) => entries.extend(file_entries),
+            None => match cached_entries(&file.path, state).await {
+                Ok(file_entries) => entries.extend(file_entries),
+                Err(e) => warn!("⚠️ 会话 {} 中的文件无法读取，跳过: {} ({})", session, file.path, e),
+            },
+        }
+    }
+    if entries.is_empty() {
+        return Err(Message::new("session.nothing_to_compare").with("session", session).text());
+    }
+    Ok(entries)
+}
+
+/// 对比两次运行的消息模板
+///
+/// 把两个会话（如标记为"正常"和"异常"的两次运行）中全部文件的消息归纳为同一套模板，
+/// 找出只在运行B中出现、在运行B中消失以及频率剧烈变化的模板，用于回归问题排查。
+///
+/// # 参数
+/// - `session_a`: 运行A的会话名称（通常为正常的基线）
+/// - `session_b`: 运行B的会话名称（通常为异常的运行）
+/// - `similarity`: 模板相似度阈值（可选，0-1，默认0.5）
+/// - `change_factor`: 频率变化倍数阈值（可选，默认3）
+/// - `state`: 应用状态，包含会话存储和增量解析缓存
+///
+/// # Returns
+/// - `Ok(RunComparison)`: 新出现、消失和频率剧烈变化的模板
+/// - `Err(String)`: 会话不存在或没有可读取的日志时的错误信息
+#[tauri::command]
+async fn compare_runs(
+    session_a: String,
+    session_b: String,
+    similarity: Option<f64>,
+    change_factor: Option<f64>,
+    state: tauri::State<'_, AppState>,
+) -> Result<RunComparison, String> {
+    let run_a = session_run_entries(&session_a, &state).await?;
+    let run_b = session_run_entries(&session_b, &state).await?;
+    let similarity = similarity.unwrap_or(templates::DEFAULT_SIMILARITY);
+    let change_factor = change_factor.unwrap_or(templates::DEFAULT_CHANGE_FACTOR);
+
+    let label = format!("{} → {}", session_a, session_b);
+    let comparison = run_analysis_job(&state, &label, move |_| Ok(templates::compare_runs(&run_a, &run_b, similarity, change_factor))).await?;
+    info!("⚖️ 对比运行 {} → {}: 新增 {} 个模板, 消失 {} 个, 频率变化 {} 个",
+          session_a, session_b, comparison.new_templates.len(),
+          comparison.disappeared_templates.len(), comparison.changed_templates.len());
+    Ok(comparison)
+}
+
+/// 生成表格视图的行
+///
+/// 按指定的列（`timestamp`、`level`、`message` 等内置字段或元数据键）返回按列顺序排列的单元格，
+/// 并附带每列的类型提示，表格视图无需在JS中整理元数据。
+///
+/// # 参数
+/// - `file_path`: 日志文件路径
+/// - `columns`: 列名，按显示顺序排列
+/// - `filter`: 筛选条件（可选，先筛选再分页）
+/// - `offset`: 起始行序号（可选，默认0）
+/// - `limit`: 每页最多行数（可选，默认500）
+/// - `state`: 应用状态，包含增量解析缓存
+///
+/// # Returns
+/// - `Ok(TablePage)`: 列定义、当前页的行和满足条件的行总数
+/// - `Err(String)`: 没有指定列、筛选条件无效、文件不可读或解析失败时的错误信息
+#[tauri::command]
+async fn get_table_rows(
+    file_path: String,
+    columns: Vec<String>,
+    filter: Option<EntryFilter>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    state: tauri::State<'_, AppState>,
+) -> Result<TablePage, String> {
+    let matcher = filter.filter(|filter| !filter.is_empty()).map(|filter| filter.compile()).transpose()?;
+    let entries = cached_entries(&file_path, &state).await?;
+    let page = table::table_rows(
+        &entries,
+        &columns,
+        matcher.as_ref(),
+        offset.unwrap_or(0),
+        limit.unwrap_or(table::DEFAULT_PAGE_SIZE),
+    )?;
+    debug!("📋 文件 {} 表格视图: {} 列，返回 {}/{} 行", file_path, page.columns.len(), page.rows.len(), page.total_rows);
+    Ok(page)
+}
+
+/// 生成日志大纲
+///
+/// 识别应用启动、上下文初始化、服务器启动、启动完成和关闭等生命周期节点，
+/// 以及错误集中出现的区段，返回带行号锚点的目录，界面据此在运行的各个阶段之间跳转。
+///
+/// # 参数
+/// - `file_path`: 日志文件路径
+/// - `state`: 应用状态，包含增量解析缓存
+///
+/// # Returns
+/// - `Ok(LogOutline)`: 按起始行号排列的区段
+/// - `Err(String)`: 文件不可读或解析失败时的错误信息
+#[tauri::command]
+async fn get_log_outline(file_path: String, state: tauri::State<'_, AppState>) -> Result<LogOutline, String> {
+    let entries = cached_entries(&file_path, &state).await?;
+    let outline = outline::build_outline(&entries);
+    info!("🧭 文件 {} 的大纲: {} 个区段", file_path, outline.sections.len());
+    Ok(outline)
+}
+
+/// 获取条目的锚点
+///
+/// 锚点由文件、行号和内容哈希组成，保存到书签或分享后可通过 `resolve_anchor` 找回条目。
+///
+/// # 参数
+/// - `file_path`: 日志文件路径
+/// - `line_number`: 条目行号
+/// - `state`: 应用状态，包含增量解析缓存
+///
+/// # Returns
+/// - `Ok(String)`: 条目锚点
+/// - `Err(String)`: 文件不可读或该行没有条目时的错误信息
+#[tauri::command]
+async fn get_entry_anchor(file_path: String, line_number: usize, state: tauri::State<'_, AppState>) -> Result<String, String> {
+    let entries = cached_entries(&file_path, &state).await?;
+    let entry = entries.iter()
+        .find(|entry| entry.line_number == line_number)
+        .ok_or_else(|| Message::new("line.no_entry").with("path", &file_path).with("line", line_number).text())?;
+    Ok(anchor::EntryAnchor::new(&file_path, entry).to_string())
+}
+
+/// 按锚点找回条目
+///
+/// 行号偏移后按内容哈希找到离原行号最近的相同条目。
+///
+/// # 参数
+/// - `file_path`: 日志文件路径
+/// - `anchor`: `get_entry_anchor` 返回的锚点
+/// - `state`: 应用状态，包含增量解析缓存
+///
+/// # Returns
+/// - `Ok(Some(ResolvedAnchor))`: 条目当前的行号、行号偏移和预览
+/// - `Ok(None)`: 文件中已没有该条目
+/// - `Err(String)`: 锚点无效、不属于该文件或文件不可读时的错误信息
+#[tauri::command]
+async fn resolve_anchor(
+    file_path: String,
+    anchor: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Option<anchor::ResolvedAnchor>, String> {
+    let parsed = anchor::EntryAnchor::parse(&anchor)?;
+    let entries = cached_entries(&file_path, &state).await?;
+    let resolved = anchor::resolve_anchor(&parsed, &file_path, &entries)?;
+    match &resolved {
+        Some(found) => info!("⚓ 锚点定位到 {} 第{}行（偏移 {}）", file_path, found.line_number, found.line_offset),
+        None => warn!("⚠️ 锚点在 {} 中找不到对应条目", file_path),
+    }
+    Ok(resolved)
+}
+
+/// 固定日志条目
+///
+/// 将条目加入文件的固定列表并持久化，同一行重复固定时更新备注。
+///
+/// # 参数
+/// - `file_path`: 日志文件路径
+/// - `entry`: 要固定的日志条目（只保存行号、锚点和预览）
+/// - `note`: 备注（可选）
+/// - `session_id`: 会话标识（可选，不指定时按文件共享）
+/// - `state`: 应用状态，包含配置服务
+///
+/// # Returns
+/// - `Ok(Vec<PinnedEntry>)`: 更新后的固定列表（按行号排序）
+/// - `Err(String)`: 保存失败时的错误信息
+#[tauri::command]
+async fn pin_entry(
+    file_path: String,
+    entry: LogEntry,
+    note: Option<String>,
+    session_id: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<PinnedEntry>, String> {
+    info!("📌 固定条目: {} 第{}行", file_path, entry.line_number);
+
+    let mut config_service = state.config_service.lock().await;
+    let mut pinned = config_service.get_pinned_entries(&file_path, session_id.as_deref())?;
+    config::pins::insert_pin(&mut pinned, PinnedEntry::from_entry(&file_path, &entry, note));
+    config_service.set_pinned_entries(&file_path, session_id.as_deref(), &pinned)?;
+    Ok(pinned)
+}
+
+/// 取消固定日志条目
+///
+/// # 参数
+/// - `file_path`: 日志文件路径
+/// - `line_number`: 要取消固定的行号
+/// - `session_id`: 会话标识（可选）
+/// - `state`: 应用状态，包含配置服务
+///
+/// # Returns
+/// - `Ok(Vec<PinnedEntry>)`: 更新后的固定列表
+/// - `Err(String)`: 保存失败时的错误信息
+#[tauri::command]
+async fn unpin_entry(
+    file_path: String,
+    line_number: usize,
+    session_id: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<PinnedEntry>, String> {
+    info!("📍 取消固定: {} 第{}行", file_path, line_number);
+
+    let mut config_service = state.config_service.lock().await;
+    let mut pinned = config_service.get_pinned_entries(&file_path, session_id.as_deref())?;
+    pinned.retain(|pin| pin.line_number != line_number);
+    config_service.set_pinned_entries(&file_path, session_id.as_deref(), &pinned)?;
+    Ok(pinned)
+}
+
+/// 获取文件的固定条目列表
+///
+/// 文件已解析时按锚点把固定条目移动到条目当前的行号，行号变化后重新保存。
+///
+/// # 参数
+/// - `file_path`: 日志文件路径
+/// - `session_id`: 会话标识（可选）
+/// - `state`: 应用状态，包含配置服务和增量解析缓存
+///
+/// # Returns
+/// - `Ok(Vec<PinnedEntry>)`: 固定列表（按行号排序）
+/// - `Err(String)`: 读取失败时的错误信息
+#[tauri::command]
+async fn list_pinned(
+    file_path: String,
+    session_id: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<PinnedEntry>, String> {
+    let mut pinned = state.config_service.lock().await.get_pinned_entries(&file_path, session_id.as_deref())?;
+    if pinned.iter().all(|pin| pin.anchor.is_none()) {
+        return Ok(pinned);
+    }
+
+    match cached_entries(&file_path, &state).await {
+        Ok(entries) => {
+            if config::pins::relocate_pins(&mut pinned, &file_path, &entries) {
+                info!("📌 固定条目已按锚点重新定位: {}", file_path);
+                state.config_service.lock().await.set_pinned_entries(&file_path, session_id.as_deref(), &pinned)?;
+            }
+        }
+        Err(e) => warn!("⚠️ 无法按锚点定位固定条目: {}", e),
+    }
+    Ok(pinned)
+}
+
+/// 保存工作区会话
+///
+/// 会话文件写入应用数据目录的 `sessions` 子目录，同名会话会被覆盖。
+/// 未携带固定条目的文件，从当前固定列表（先按会话名称，再按文件）补全，
+/// 这样会话文件本身就包含完整的排查现场，可以直接分享。
+///
+/// # 参数
+/// - `session`: 会话内容（打开的文件、过滤条件、格式选择、滚动位置等）
+/// - `state`: 应用状态，包含配置服务和会话存储
+///
+/// # Returns
+/// - `Ok(SessionSummary)`: 保存后的会话摘要（包含会话文件路径）
+/// - `Err(String)`: 名称无效或写入失败时的错误信息
+#[tauri::command]
+async fn save_session(mut session: Session, state: tauri::State<'_, AppState>) -> Result<SessionSummary, String> {
+    {
+        let config_service = state.config_service.lock().await;
+        for file in session.files.iter_mut().filter(|f| f.bookmarks.is_empty()) {
+            file.bookmarks = config_service.get_pinned_entries(&file.path, Some(&session.name))?;
+            if file.bookmarks.is_empty() {
+                file.bookmarks = config_service.get_pinned_entries(&file.path, None)?;
+            }
+        }
+    }
+
+    let path = state.sessions.save(&mut session)?;
+    info!("💾 会话已保存: {} -> {}", session.name, path.display());
+    Ok(SessionSummary {
+        name: session.name,
+        saved_at: session.saved_at,
+        file_count: session.files.len(),
+        path: path.to_string_lossy().into_owned(),
+    })
+}
+
+/// 加载工作区会话
+///
+/// 按名称从会话目录加载，或按路径加载同事分享的会话文件。
+/// 会话中的固定条目写回以会话名称为 `session_id` 的固定列表，
+/// 之后 `pin_entry` / `list_pinned` 传入同一 `session_id` 即可继续使用。
+/// 已不存在的本地文件在结果中标记为 `missing`。
+///
+/// # 参数
+/// - `name`: 会话名称（与 `path` 二选一）
+/// - `path`: 会话文件路径（与 `name` 二选一）
+/// - `state`: 应用状态，包含配置服务和会话存储
+///
+/// # Returns
+/// - `Ok(Session)`: 会话内容
+/// - `Err(String)`: 会话不存在或格式无效时的错误信息
+#[tauri::command]
+async fn load_session(name: Option<String>, path: Option<String>, state: tauri::State<'_, AppState>) -> Result<Session, String> {
+    let session = match (name, path) {
+        (_, Some(path)) => session::load_file(std::path::Path::new(&path))?,
+        (Some(name), None) => state.sessions.load(&name)?,
+        (None, None) => return Err(Message::new("session.missing_source").text()),
+    };
+
+    let mut config_service = state.config_service.lock().await;
+    for file in session.files.iter().filter(|f| !f.bookmarks.is_empty()) {
+        config_service.set_pinned_entries(&file.path, Some(&session.name), &file.bookmarks)?;
+    }
+    let missing = session.files.iter().filter(|f| f.missing).count();
+    info!("📂 会话已加载: {} ({} 个文件, {} 个不存在)", session.name, session.files.len(), missing);
+    Ok(session)
+}
+
+/// 列出已保存的工作区会话（按保存时间倒序）
+#[tauri::command]
+async fn list_sessions(state: tauri::State<'_, AppState>) -> Result<Vec<SessionSummary>, String> {
+    state.sessions.list()
+}
+
+/// 打开会话的解析结果数据库
+fn open_entry_store(session: &str, state: &AppState) -> Result<EntryStore, String> {
+    EntryStore::open(state.sessions.entries_db_path(session)?)
+}
+
+/// 把文件的解析结果保存到会话数据库
+///
+/// 多GB文件解析耗时很长，保存后重启应用可以通过 `restore_session_entries` 恢复，
+/// 或直接用 `query_session_entries` 查询，不需要重新解析。同一文件再次保存会替换之前的结果。
+///
+/// # 参数
+/// - `session`: 会话名称
+/// - `file_path`: 日志文件路径（使用增量解析缓存中的最新结果）
+/// - `state`: 应用状态，包含解析缓存和会话存储
+///
+/// # Returns
+/// - `Ok(PersistedFile)`: 保存的文件信息
+/// - `Err(String)`: 解析或写入数据库失败时的错误信息
+#[tauri::command]
+async fn persist_session_entries(session: String, file_path: String, state: tauri::State<'_, AppState>) -> Result<PersistedFile, String> {
+    let delta = refresh_cached_parse(&file_path, &state).await?;
+    let entries = state.parse_cache.entries(&delta.file_path)
+        .ok_or_else(|| Message::new("parse.result_unavailable").with("path", &file_path).text())?;
+    let index = line_index(&file_path, &state)?;
+    let store = open_entry_store(&session, &state)?;
+
+    let persisted = tokio::task::spawn_blocking(move || {
+        store.save_file(&file_path, index.file_size, index.fingerprint, delta.detected_format.as_deref(), &entries)
+    })
+    .await
+    .map_err(|e| Message::new("task.save_entries_failed").with("error", e).text())??;
+    info!("💾 解析结果已保存到会话 {}: {} ({} 条)", session, persisted.path, persisted.entry_count);
+    Ok(persisted)
+}
+
+/// 从会话数据库恢复文件的解析结果
+///
+/// 文件自保存后未变化（大小和前缀指纹一致）时，把保存的条目放入增量解析缓存，
+/// 之后的 `refresh_parse` 和分析命令直接使用，不再重新解析；文件只是追加了内容时也只解析新增部分。
+///
+/// # 参数
+/// - `session`: 会话名称
+/// - `file_path`: 日志文件路径
+/// - `state`: 应用状态，包含解析缓存和会话存储
+///
+/// # Returns
+/// - `Ok(true)`: 已恢复到解析缓存
+/// - `Ok(false)`: 文件已变化，未恢复（仍可用 `query_session_entries` 查询保存的结果）
+/// - `Err(String)`: 文件未保存或读取失败时的错误信息
+#[tauri::command]
+async fn restore_session_entries(session: String, file_path: String, state: tauri::State<'_, AppState>) -> Result<bool, String> {
+    let store = open_entry_store(&session, &state)?;
+    let persisted = store.file(&file_path)?
+        .ok_or_else(|| Message::new("session.entries_not_found").with("session", &session).with("path", &file_path).text())?;
+    let index = line_index(&file_path, &state)?;
+    if index.file_size != persisted.file_size || index.fingerprint != persisted.fingerprint {
+        warn!("⚠️ 文件自保存后已变化，不恢复解析结果: {}", file_path);
+        return Ok(false);
+    }
+
+    let load_path = file_path.clone();
+    let entries = tokio::task::spawn_blocking(move || store.load_entries(&load_path))
+        .await
+        .map_err(|e| Message::new("task.load_entries_failed").with("error", e).text())??;
+    let restored = state.parse_cache.restore(&index, persisted.file_size, persisted.fingerprint, entries, persisted.detected_format);
+    if restored {
+        info!("📂 已从会话 {} 恢复解析结果: {} ({} 条)", session, file_path, persisted.entry_count);
+    }
+    Ok(restored)
+}
+
+/// 查询会话数据库中保存的解析结果
+///
+/// 级别、元数据和内容搜索转换为SQL查询，不需要把全部条目加载到内存。
+///
+/// # 参数
+/// - `session`: 会话名称
+/// - `file_path`: 日志文件路径
+/// - `filter`: 筛选条件（None表示不筛选）
+/// - `offset`: 起始序号（默认0）
+/// - `limit`: 每页最多条目数（默认500）
+/// - `state`: 应用状态，包含会话存储
+///
+/// # Returns
+/// - `Ok(EntryPage)`: 当前页的条目和满足条件的总数
+/// - `Err(String)`: 文件未保存、筛选条件无效或查询失败时的错误信息
+#[tauri::command]
+async fn query_session_entries(
+    session: String,
+    file_path: String,
+    filter: Option<EntryFilter>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    state: tauri::State<'_, AppState>,
+) -> Result<EntryPage, String> {
+    let store = open_entry_store(&session, &state)?;
+    let filter = filter.unwrap_or_default();
+    tokio::task::spawn_blocking(move || {
+        store.query(&file_path, &filter, offset.unwrap_or(0), limit.unwrap_or(entry_store::DEFAULT_PAGE_SIZE))
+    })
+    .await
+    .map_err(|e| Message::new("task.query_entries_failed").with("error", e).text())?
+}
+
+/// 列出会话数据库中保存了解析结果的文件
+#[tauri::command]
+async fn list_session_entry_files(session: String, state: tauri::State<'_, AppState>) -> Result<Vec<PersistedFile>, String> {
+    open_entry_store(&session, &state)?.files()
+}
+
+/// 删除会话数据库中文件的解析结果
+///
+/// # Returns
+/// - `Ok(true)`: 已删除
+/// - `Ok(false)`: 文件没有保存的解析结果
+#[tauri::command]
+async fn remove_session_entries(session: String, file_path: String, state: tauri::State<'_, AppState>) -> Result<bool, String> {
+    open_entry_store(&session, &state)?.remove_file(&file_path)
+}
+
+/// 快速搜索面板的搜索
+///
+/// 在最近打开的文件、所有固定条目和已保存的工作区会话中按前缀和模糊匹配搜索，
+/// 查询为空时按最近使用顺序返回（最近文件、书签、会话）。
+///
+/// # 参数
+/// - `query`: 查询文本
+/// - `limit`: 最多返回的结果数（默认20，上限200）
+/// - `state`: 应用状态，包含配置服务和会话存储
+///
+/// # Returns
+/// - `Ok(Vec<PaletteHit>)`: 按得分排序的结果，`matched` 为标题中匹配的字符下标
+/// - `Err(String)`: 读取候选项失败时的错误信息
+#[tauri::command]
+async fn search_palette(query: String, limit: Option<usize>, state: tauri::State<'_, AppState>) -> Result<Vec<PaletteHit>, String> {
+    let mut items: Vec<PaletteItem> = Vec::new();
+    {
+        let config_service = state.config_service.lock().await;
+        items.extend(config_service.get_recent_files()?.iter().map(PaletteItem::recent_file));
+
+        let mut bookmarks: Vec<(&str, Option<&str>, &PinnedEntry)> = Vec::new();
+        let all_pinned = config_service.all_pinned_entries()?;
+        for file in &all_pinned {
+            bookmarks.extend(file.pinned.iter().map(|pin| (file.file_path.as_str(), file.session_id.as_deref(), pin)));
+        }
+        bookmarks.sort_by(|a, b| b.2.pinned_at.cmp(&a.2.pinned_at));
+        items.extend(bookmarks.into_iter().map(|(file_path, session, pin)| PaletteItem::bookmark(file_path, session, pin)));
+    }
+    items.extend(state.sessions.list()?.iter().map(PaletteItem::session));
+
+    let hits = palette::search(&query, items, limit.unwrap_or(palette::DEFAULT_PALETTE_LIMIT));
+    debug!("🔎 快速搜索 '{}': {} 个结果", query, hits.len());
+    Ok(hits)
+}
+
+/// 获取快速搜索面板设置（全局快捷键）
+#[tauri::command]
+async fn get_palette_settings(state: tauri::State<'_, AppState>) -> Result<PaletteSettings, String> {
+    state.config_service.lock().await.get_palette_settings()
+}
+
+/// 更新快速搜索面板设置
+///
+/// 先注销旧的全局快捷键再注册新的；新快捷键注册失败（格式无效或已被其他程序占用）时
+/// 恢复旧快捷键并返回错误，设置不保存。
+///
+/// # 参数
+/// - `settings`: 新设置
+/// - `app`: 应用句柄，用于注册全局快捷键
+/// - `state`: 应用状态，包含配置服务实例
+#[tauri::command]
+async fn set_palette_settings(settings: PaletteSettings, app: tauri::AppHandle, state: tauri::State<'_, AppState>) -> Result<PaletteSettings, String> {
+    settings.validate()?;
+    let mut config_service = state.config_service.lock().await;
+    let previous = config_service.get_palette_settings()?;
+
+    if previous.enabled {
+        unregister_palette_shortcut(&app, &previous.shortcut);
+    }
+    if settings.enabled {
+        if let Err(e) = register_palette_shortcut(&app, &settings.shortcut) {
+            if previous.enabled {
+                if let Err(e) = register_palette_shortcut(&app, &previous.shortcut) {
+                    warn!("⚠️ 恢复原全局快捷键失败: {}", e);
+                }
+            }
+            return Err(e);
+        }
+    }
+
+    config_service.set_palette_settings(&settings)?;
+    info!("⌨️ 快速搜索快捷键: {} ({})", settings.shortcut, if settings.enabled { "启用" } else { "停用" });
+    Ok(settings)
+}
+
+/// 注册呼出快速搜索面板的全局快捷键
+///
+/// 按下快捷键时恢复并聚焦主窗口，然后向其发送 `open-search-palette` 事件。
+fn register_palette_shortcut(app: &tauri::AppHandle, shortcut: &str) -> Result<(), String> {
+    use tauri::{GlobalShortcutManager, Manager};
+
+    let handle = app.clone();
+    app.global_shortcut_manager()
+        .register(shortcut, move || {
+            let Some(window) = handle.get_window("main") else {
+                return;
+            };
+            let result = window.unminimize()
+                .and_then(|_| window.show())
+                .and_then(|_| window.set_focus())
+                .and_then(|_| window.emit("open-search-palette", ()));
+            if let Err(e) = result {
+                warn!("⚠️ 打开快速搜索面板失败: {}", e);
+            }
+        })
+        .map_err(|e| Message::new("shortcut.register_failed").with("shortcut", shortcut).with("error", e).text())
+}
+
+/// 注销快速搜索面板的全局快捷键
+fn unregister_palette_shortcut(app: &tauri::AppHandle, shortcut: &str) {
+    use tauri::GlobalShortcutManager;
+
+    if let Err(e) = app.global_shortcut_manager().unregister(shortcut) {
+        warn!("⚠️ 注销全局快捷键 {} 失败: {}", shortcut, e);
+    }
+}
+
+/// 按设置重启目录采集任务
+///
+/// 正在运行的任务在下一轮检查时退出；设置未启用时只停止不启动。
+fn restart_ingest(settings: IngestSettings, state: &AppState) {
+    let generation = state.ingest.restart();
+    if !settings.enabled {
+        info!("⏹️ 目录采集已停止");
+        return;
+    }
+
+    let state = state.clone();
+    info!("📥 目录采集已启动: {:?} -> 会话 {}", settings.folders, settings.session);
+    tokio::spawn(async move {
+        let poll_interval = std::time::Duration::from_secs(settings.poll_interval_secs.max(1));
+        state.ingest.set_running(true);
+        while state.ingest.is_current(generation) {
+            let report = ingest_scan(&settings, &state).await;
+            if report.ingested_files > 0 || report.pruned_files > 0 {
+                info!("📥 目录采集: 写入 {} 个文件, 按保留策略删除 {} 个文件", report.ingested_files, report.pruned_files);
+            }
+            for e in &report.errors {
+                warn!("⚠️ 目录采集失败: {}", e);
+            }
+            if state.ingest.is_current(generation) {
+                state.ingest.record_scan(&report);
+            }
+            tokio::time::sleep(poll_interval).await;
+        }
+    });
+}
+
+/// 扫描一轮采集目录：写入变化的文件，再按保留策略删除最旧的文件
+async fn ingest_scan(settings: &IngestSettings, state: &AppState) -> ScanReport {
+    let mut report = ScanReport::default();
+    let store = match open_entry_store(&settings.session, state) {
+        Ok(store) => Arc::new(store),
+        Err(e) => {
+            report.errors.push(e);
+            return report;
+        }
+    };
+
+    let scan_settings = settings.clone();
+    let files = tokio::task::spawn_blocking(move || ingest::find_files(&scan_settings))
+        .await
+        .unwrap_or_default();
+    report.scanned_files = files.len();
+    for path in files {
+        let file_path = path.to_string_lossy().into_owned();
+        match ingest_file(&file_path, &store, state).await {
+            Ok(true) => report.ingested_files += 1,
+            Ok(false) => {}
+            Err(e) => report.errors.push(format!("{}: {}", file_path, e)),
+        }
+    }
+
+    let cutoff = settings.retention_cutoff(chrono::Utc::now());
+    let max_bytes = settings.max_bytes();
+    let prune_store = store.clone();
+    match tokio::task::spawn_blocking(move || prune_store.prune(cutoff, max_bytes)).await {
+        Ok(Ok(pruned)) => {
+            // 已删除的文件不再需要增量解析缓存
+            for path in &pruned {
+                state.parse_cache.invalidate(path);
+            }
+            report.pruned_files = pruned.len();
+        }
+        Ok(Err(e)) => report.errors.push(e),
+        Err(e) => report.errors.push(Message::new("task.retention_failed").with("error", e).text()),
+    }
+
+    match store.files() {
+        Ok(files) => {
+            report.stored_files = files.len();
+            report.stored_bytes = files.iter().map(|file| file.file_size).sum();
+        }
+        Err(e) => report.errors.push(e),
+    }
+    report
+}
+
+/// 文件自上次写入后有变化（大小或前缀指纹不同）时增量解析并写入数据库
+///
+/// # Returns
+/// - `Ok(true)`: 已写入
+/// - `Ok(false)`: 文件未变化
+async fn ingest_file(file_path: &str, store: &Arc<EntryStore>, state: &AppState) -> Result<bool, String> {
+    let index = line_index(file_path, state)?;
+    if let Some(saved) = store.file(file_path)? {
+        if saved.file_size == index.file_size && saved.fingerprint == index.fingerprint {
+            return Ok(false);
+        }
+    }
+
+    let delta = refresh_cached_parse(file_path, state).await?;
+    let entries = state.parse_cache.entries(&delta.file_path)
+        .ok_or_else(|| Message::new("parse.result_unavailable").with("path", file_path).text())?;
+    let store = store.clone();
+    let file_path = file_path.to_string();
+    tokio::task::spawn_blocking(move || {
+        store.save_file(&file_path, index.file_size, index.fingerprint, delta.detected_format.as_deref(), &entries)
+    })
+    .await
+    .map_err(|e| Message::new("task.save_entries_failed").with("error", e).text())??;
+    Ok(true)
+}
+
+/// 获取目录采集设置
+#[tauri::command]
+async fn get_ingest_settings(state: tauri::State<'_, AppState>) -> Result<IngestSettings, String> {
+    state.config_service.lock().await.get_ingest_settings()
+}
+
+/// 保存目录采集设置并按新设置重启采集任务
+///
+/// 启用后后台持续扫描配置的目录，把日志写入 `settings.session` 对应的会话数据库，
+/// 可用 `query_session_entries` 查询；应用重启后自动恢复采集。
+///
+/// # 参数
+/// - `settings`: 采集目录、文件名规则、扫描间隔和保留策略（天数 / GB）
+/// - `state`: 应用状态，包含配置服务和会话存储
+///
+/// # Returns
+/// - `Ok(IngestSettings)`: 保存的设置
+/// - `Err(String)`: 设置无效或保存失败时的错误信息
+#[tauri::command]
+async fn set_ingest_settings(settings: IngestSettings, state: tauri::State<'_, AppState>) -> Result<IngestSettings, String> {
+    settings.validate()?;
+    state.sessions.entries_db_path(&settings.session)?;
+    state.config_service.lock().await.set_ingest_settings(&settings)?;
+    restart_ingest(settings.clone(), &state);
+    Ok(settings)
+}
+
+/// 获取目录采集状态（最近一次扫描结果和数据库中保存的文件）
+#[tauri::command]
+async fn get_ingest_status(state: tauri::State<'_, AppState>) -> Result<IngestStatus, String> {
+    Ok(state.ingest.status())
+}
+
+/// 获取目标行的上下文
+///
+/// 借助行偏移索引直接定位到目标行附近，只读取窗口内的若干行，
+/// 无需加载整个文件即可返回原始内容和解析后的条目。
+///
+/// # 参数
+/// - `file_path`: 日志文件路径
+/// - `line_number`: 目标行号（从1开始）
+/// - `before`: 目标行之前的行数（可选，默认10）
+/// - `after`: 目标行之后的行数（可选，默认10）
+/// - `state`: 应用状态，包含行索引缓存和插件管理器
+///
+/// # Returns
+/// - `Ok(ContextResponse)`: 窗口内的原始行和解析条目
+/// - `Err(String)`: 文件不可读或行号越界时的错误信息
+#[tauri::command]
+async fn get_context(
+    file_path: String,
+    line_number: usize,
+    before: Option<usize>,
+    after: Option<usize>,
+    state: tauri::State<'_, AppState>,
+) -> Result<ContextResponse, String> {
+    let before = before.unwrap_or(10);
+    let after = after.unwrap_or(10);
+    debug!("🔎 获取上下文: {} 第{}行 (前{}行, 后{}行)", file_path, line_number, before, after);
+
+    let index = line_index(&file_path, &state)?;
+    let total_lines = index.line_count();
+    if line_number == 0 || line_number > total_lines {
+        return Err(Message::new("line.out_of_range").with("line", line_number).with("total", total_lines).text());
+    }
+
+    let start_line = line_number.saturating_sub(before).max(1);
+    let end_line = (line_number + after).min(total_lines);
+    let raw_lines = index.read_lines(start_line, end_line)?;
+
+    let lines: Vec<ContextLine> = raw_lines.iter().enumerate().map(|(i, raw)| ContextLine {
+        line_number: start_line + i,
+        raw: raw.clone(),
+        is_target: start_line + i == line_number,
+    }).collect();
+
+    // 插件链会跳过空行并从1开始重新编号，这里记录非空行对应的原始行号以便回映射
+    let non_empty_line_numbers: Vec<usize> = lines.iter()
+        .filter(|line| !line.raw.trim().is_empty())
+        .map(|line| line.line_number)
+        .collect();
+
+    let parse_request = ParseRequest {
+        content: raw_lines.join("\n"),
+        plugin: Some("auto".to_string()),
+        file_path: Some(file_path.clone()),
+        ..Default::default()
+    };
+
+    let (entries, detected_format) = match state.plugin_manager.auto_detect_and_parse(&parse_request) {
+        Ok(mut result) => {
+            for entry in &mut result.lines {
+                entry.line_number = non_empty_line_numbers.get(entry.line_number.saturating_sub(1))
+                    .copied()
+                    .unwrap_or(start_line + entry.line_number.saturating_sub(1));
+            }
+            (result.lines, result.detected_format)
+        }
+        Err(e) => {
+            warn!("⚠️ 上下文解析失败，仅返回原始行: {}", e);
+            (Vec::new(), None)
+        }
+    };
+
+    Ok(ContextResponse {
+        file_path,
+        target_line: line_number,
+        start_line,
+        end_line,
+        total_lines,
+        lines,
+        entries,
+        detected_format,
+    })
+}
+
+/// 获取完整的原始行内容
+///
+/// 解析结果中超过 `max_line_length` 的行会被截断（元数据 `truncated=true`），
+/// 前端需要查看完整内容时通过此命令按需读取，不会重新解析整个文件。
+///
+/// # 参数
+/// - `file_path`: 日志文件路径
+/// - `line_number`: 行号（从1开始）
+/// - `state`: 应用状态，包含行索引缓存
+///
+/// # Returns
+/// - `Ok(String)`: 该行的完整原始内容
+/// - `Err(String)`: 文件不可读或行号越界时的错误信息
+#[tauri::command]
+async fn get_full_line(
+    file_path: String,
+    line_number: usize,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, String> {
+    debug!("📜 读取完整行: {} 第{}行", file_path, line_number);
+
+    let index = line_index(&file_path, &state)?;
+    if line_number == 0 || line_number > index.line_count() {
+        return Err(Message::new("line.out_of_range").with("line", line_number).with("total", index.line_count()).text());
+    }
+
+    index.read_lines(line_number, line_number)?
+        .pop()
+        .ok_or_else(|| Message::new("line.read_failed").with("line", line_number).text())
+}
+
+/// 用于选择插件链的文件开头样本行数
+const EXPLAIN_SAMPLE_LINES: usize = 200;
+
+/// 逐步解释某一行的解析过程
+///
+/// 对指定行重新执行插件链，记录每个过滤器的 `should_process` 判断、
+/// 改变了哪些字段、添加了哪些元数据，用于排查某一行为什么被解析错。
+/// 未指定插件链时使用文件指定的格式，都没有时按文件开头的样本选择，与解析整个文件时一致。
+///
+/// # 参数
+/// - `file_path`: 日志文件路径
+/// - `line_number`: 行号（从1开始）
+/// - `plugin`: 指定的插件链名称（None或"auto"表示自动选择）
+/// - `state`: 应用状态，包含行索引缓存和插件管理器
+///
+/// # Returns
+/// - `Ok(ChainTrace)`: 逐步追踪结果
+/// - `Err(String)`: 文件不可读、行号越界或插件链不可用时的错误信息
+#[tauri::command]
+async fn explain_line(
+    file_path: String,
+    line_number: usize,
+    plugin: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<ChainTrace, String> {
+    info!("🔬 解释解析过程: {} 第{}行", file_path, line_number);
+
+    let index = line_index(&file_path, &state)?;
+    if line_number == 0 || line_number > index.line_count() {
+        return Err(Message::new("line.out_of_range").with("line", line_number).with("total", index.line_count()).text());
+    }
+
+    let content = index.read_lines(line_number, line_number)?
+        .pop()
+        .ok_or_else(|| Message::new("line.read_failed").with("line", line_number).text())?;
+    let sample = index.read_lines(1, index.line_count().min(EXPLAIN_SAMPLE_LINES))?.join("\n");
+
+    let format = match plugin.as_deref().filter(|name| *name != "auto") {
+        Some(name) => Some(name.to_string()),
+        None => format_override(Some(&file_path), &state).await,
+    };
+    let request = ParseRequest {
+        content,
+        plugin: plugin.clone(),
+        file_path: Some(file_path.clone()),
+        ..Default::default()
+    };
+    let mut trace = state.plugin_manager.explain(format.as_deref(), &sample, &request)?;
+    if let Some(line) = trace.result.as_mut() {
+        line.line_number = line_number;
+    }
+    Ok(trace)
+}
+
+/// 刷新文件的解析结果（增量）
+///
+/// 文件只是在末尾追加了内容时，只解析新增区域并修补缓存的解析结果和行索引，
+/// 返回给前端的只是增量部分；文件被截断或改写时回退为完整解析。
+/// 首次调用时完整解析整个文件。
+///
+/// # 参数
+/// - `file_path`: 日志文件路径
+/// - `state`: 应用状态，包含行索引缓存、增量解析缓存和插件管理器
+///
+/// # Returns
+/// - `Ok(ParseDelta)`: 刷新方式、需要替换的起始行号和新解析的条目
+/// - `Err(String)`: 文件不可读或解析失败时的错误信息
+#[tauri::command]
+async fn refresh_parse(file_path: String, state: tauri::State<'_, AppState>) -> Result<ParseDelta, String> {
+    debug!("🔄 刷新解析结果: {}", file_path);
+
+    let max_line_length = state.config_service.lock().await
+        .get_parse_config()
+        .map(|config| config.max_line_length)
+        .unwrap_or(0);
+
+    let mut delta = refresh_cached_parse(&file_path, &state).await?;
+
+    truncate_long_lines(&mut delta.entries, max_line_length);
+    info!("✅ 刷新完成: {:?}, 新增 {} 条, 共 {} 条", delta.mode, delta.entries.len(), delta.total_entries);
+    Ok(delta)
+}
+
+/// 单页默认返回的记录数
+const RECORDS_DEFAULT_LIMIT: usize = 500;
+
+/// 按多行记录分页获取文件的解析结果
+///
+/// 异常堆栈、格式化输出的JSON、多行SQL等续行与其首行合并为一条记录，
+/// 分页以记录为单位，同一条记录不会被拆分到两页。
+///
+/// # 参数
+/// - `file_path`: 日志文件路径
+/// - `offset`: 起始记录序号（可选，默认0）
+/// - `limit`: 每页最多记录数（可选，默认500）
+/// - `state`: 应用状态，包含增量解析缓存
+///
+/// # Returns
+/// - `Ok(RecordPage)`: 当前页的记录和记录总数
+/// - `Err(String)`: 文件不可读或解析失败时的错误信息
+#[tauri::command]
+async fn get_records(
+    file_path: String,
+    offset: Option<usize>,
+    limit: Option<usize>,
+    state: tauri::State<'_, AppState>,
+) -> Result<RecordPage, String> {
+    let entries = cached_entries(&file_path, &state).await?;
+    let page = records::paginate_records(&entries, offset.unwrap_or(0), limit.unwrap_or(RECORDS_DEFAULT_LIMIT));
+    debug!("📚 文件 {} 共 {} 条记录，返回 {} 条", file_path, page.total_records, page.records.len());
+    Ok(page)
+}
+
+/// 开始跟踪文件
+///
+/// 后台任务按 `poll_interval_ms` 检查文件，新增内容增量解析后合并为批次，
+/// 以 `tail-batch` 事件发送给窗口（负载为 `TailBatch`）。前端处理完每一批后
+/// 应调用 `ack_tail_batch`；未确认的批次过多时进入摘要模式，只在追上后发送丢弃摘要。
+/// 开始跟踪前文件中已有的内容不会发送。
+///
+/// 设置 `options.filter` 后只发送匹配的条目（按级别、关键字/正则、元数据筛选），
+/// 被过滤掉的条目数随批次的 `suppressed` 字段发送；长时间没有匹配条目时，
+/// 每隔 `suppressed_report_interval_ms` 单独发送一个只含计数的空批次。
+///
+/// # 参数
+/// - `file_path`: 日志文件路径
+/// - `options`: 批次大小、批次间隔、允许的未确认批次数、检查间隔和筛选条件（可选）
+/// - `window`: 接收事件的窗口
+/// - `state`: 应用状态
+///
+/// # Returns
+/// - `Ok(String)`: 跟踪ID
+/// - `Err(String)`: 文件不可读、解析失败或筛选条件无效时的错误信息
+#[tauri::command]
+async fn start_tail(
+    file_path: String,
+    options: Option<TailOptions>,
+    window: tauri::Window,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, String> {
+    // 先解析已有内容，之后只发送新增条目
+    refresh_cached_parse(&file_path, &state).await?;
+
+    let max_line_length = state.config_service.lock().await
+        .get_parse_config()
+        .map(|config| config.max_line_length)
+        .unwrap_or(0);
+
+    let handle = state.tails.start(&file_path, options.unwrap_or_default())?;
+    let tail_id = handle.tail_id.clone();
+    let state = state.inner().clone();
+    info!("👀 开始跟踪文件: {} ({})", file_path, tail_id);
+
+    tokio::spawn(async move {
+        let poll_interval = std::time::Duration::from_millis(handle.options.poll_interval_ms.max(10));
+        let batch_interval = std::time::Duration::from_millis(handle.options.max_batch_interval_ms.max(10));
+        let mut last_poll = std::time::Instant::now();
+
+        while !handle.is_stopped() {
+            tokio::time::sleep(poll_interval.min(batch_interval)).await;
+            let now = std::time::Instant::now();
+
+            let mut new_entries = Vec::new();
+            if now.duration_since(last_poll) >= poll_interval {
+                last_poll = now;
+                match refresh_cached_parse(&handle.file_path, &state).await {
+                    Ok(delta) => new_entries = delta.entries,
+                    Err(e) => warn!("⚠️ 跟踪 {} 刷新失败: {}", handle.tail_id, e),
+                }
+                truncate_long_lines(&mut new_entries, max_line_length);
+            }
+
+            let batches: Vec<_> = match handle.batcher.lock() {
+                Ok(mut batcher) => {
+                    batcher.push(new_entries, now);
+                    std::iter::from_fn(|| batcher.next_batch(now)).collect()
+                }
+                Err(_) => break,
+            };
+            for batch in batches {
+                if let Err(e) = window.emit("tail-batch", batch) {
+                    warn!("⚠️ 发送跟踪批次失败: {}", e);
+                }
+            }
+        }
+        info!("🛑 跟踪已停止: {}", handle.tail_id);
+    });
+
+    Ok(tail_id)
+}
+
+/// 确认已处理的跟踪批次
+///
+/// # 参数
+/// - `tail_id`: 跟踪ID
+/// - `seq`: 已处理的最后一个批次序号
+/// - `state`: 应用状态
+///
+/// # Returns
+/// - `Ok(())`: 确认成功
+/// - `Err(String)`: 跟踪不存在时的错误信息
+#[tauri::command]
+async fn ack_tail_batch(tail_id: String, seq: u64, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    state.tails.ack(&tail_id, seq)
+}
+
+/// 更换跟踪的筛选条件，之后的新增条目按新条件筛选
+///
+/// # 参数
+/// - `tail_id`: 跟踪ID
+/// - `filter`: 筛选条件（None表示不筛选）
+/// - `state`: 应用状态
+///
+/// # Returns
+/// - `Ok(())`: 更换成功
+/// - `Err(String)`: 跟踪不存在或筛选条件无效时的错误信息
+#[tauri::command]
+async fn set_tail_filter(tail_id: String, filter: Option<EntryFilter>, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    state.tails.set_filter(&tail_id, filter)?;
+    info!("🔎 跟踪 {} 的筛选条件已更新", tail_id);
+    Ok(())
+}
+
+/// 获取跟踪统计（已发送/丢弃的条目数、未确认批次数等）
+///
+/// # 参数
+/// - `tail_id`: 跟踪ID
+/// - `state`: 应用状态
+///
+/// # Returns
+/// - `Ok(TailStats)`: 跟踪统计
+/// - `Err(String)`: 跟踪不存在时的错误信息
+#[tauri::command]
+async fn get_tail_stats(tail_id: String, state: tauri::State<'_, AppState>) -> Result<TailStats, String> {
+    state.tails.stats(&tail_id)
+}
+
+/// 停止跟踪文件
+///
+/// # 参数
+/// - `tail_id`: 跟踪ID
+/// - `state`: 应用状态
+///
+/// # Returns
+/// - `Ok(TailStats)`: 跟踪的最终统计
+/// - `Err(String)`: 跟踪不存在时的错误信息
+#[tauri::command]
+async fn stop_tail(tail_id: String, state: tauri::State<'_, AppState>) -> Result<TailStats, String> {
+    let stats = state.tails.stop(&tail_id)?;
+    info!("🛑 停止跟踪 {}: 发送 {} 条，丢弃 {} 条，过滤 {} 条", tail_id, stats.entries_sent, stats.entries_dropped, stats.entries_suppressed);
+    Ok(stats)
+}
+
+/// 获取已配置的远程主机列表
+///
+/// # Returns
+/// - `Ok(Vec<RemoteHost>)`: 按名称排序的远程主机
+/// - `Err(String)`: 读取配置失败时的错误信息
+#[tauri::command]
+async fn list_remote_hosts(state: tauri::State<'_, AppState>) -> Result<Vec<RemoteHost>, String> {
+    state.config_service.lock().await.get_remote_hosts()
+}
+
+/// 添加或更新远程主机（按名称）
+///
+/// # 参数
+/// - `host`: 主机配置（主机名、端口、用户名和私钥文件路径）
+/// - `state`: 应用状态，包含配置服务
+///
+/// # Returns
+/// - `Ok(Vec<RemoteHost>)`: 更新后的远程主机列表
+/// - `Err(String)`: 配置无效或保存失败时的错误信息
+#[tauri::command]
+async fn save_remote_host(host: RemoteHost, state: tauri::State<'_, AppState>) -> Result<Vec<RemoteHost>, String> {
+    host.validate()?;
+    info!("🔐 保存远程主机: {} ({})", host.name, host.host);
+
+    let mut config_service = state.config_service.lock().await;
+    let mut hosts = config_service.get_remote_hosts()?;
+    config::remote::upsert_host(&mut hosts, host);
+    config_service.set_remote_hosts(&hosts)?;
+    Ok(hosts)
+}
+
+/// 删除远程主机
+///
+/// # 参数
+/// - `name`: 主机名称
+/// - `state`: 应用状态，包含配置服务
+///
+/// # Returns
+/// - `Ok(Vec<RemoteHost>)`: 更新后的远程主机列表
+/// - `Err(String)`: 保存失败时的错误信息
+#[tauri::command]
+async fn remove_remote_host(name: String, state: tauri::State<'_, AppState>) -> Result<Vec<RemoteHost>, String> {
+    let mut config_service = state.config_service.lock().await;
+    let mut hosts = config_service.get_remote_hosts()?;
+    hosts.retain(|host| host.name != name);
+    config_service.set_remote_hosts(&hosts)?;
+    Ok(hosts)
+}
+
+/// 按名称查找已配置的远程主机
+async fn find_remote_host(name: &str, state: &AppState) -> Result<RemoteHost, String> {
+    state.config_service.lock().await
+        .get_remote_hosts()?
+        .into_iter()
+        .find(|host| host.name == name)
+        .ok_or_else(|| Message::new("remote.unknown_host").with("name", name).text())
+}
+
+/// 列出远程目录
+///
+/// # 参数
+/// - `host_name`: 远程主机名称
+/// - `path`: 远程目录路径
+/// - `state`: 应用状态，包含配置服务和SSH连接管理器
+///
+/// # Returns
+/// - `Ok(Vec<RemoteFileEntry>)`: 目录中的条目（目录在前）
+/// - `Err(String)`: 连接失败或目录不可读时的错误信息
+#[tauri::command]
+async fn list_remote_dir(host_name: String, path: String, state: tauri::State<'_, AppState>) -> Result<Vec<RemoteFileEntry>, String> {
+    let host = find_remote_host(&host_name, &state).await?;
+    let ssh = state.ssh.clone();
+    tokio::task::spawn_blocking(move || ssh.list_dir(&host, &path))
+        .await
+        .map_err(|e| Message::new("task.ssh_failed").with("error", e).text())?
+}
+
+/// 读取并解析远程日志文件
+///
+/// 文件内容通过SSH读取后交给与 `parse_log` 相同的解析流程（内容模式）。
+///
+/// # 参数
+/// - `host_name`: 远程主机名称
+/// - `path`: 远程文件路径
+/// - `plugin`: 指定插件（可选，默认自动检测）
+/// - `window`: 调用命令的窗口
+/// - `state`: 应用状态
+///
+/// # Returns
+/// - `Ok(ParseResponse)`: 解析结果
+/// - `Err(String)`: 连接失败或文件不可读时的错误信息
+#[tauri::command]
+async fn open_remote_log(
+    host_name: String,
+    path: String,
+    plugin: Option<String>,
+    window: tauri::Window,
+    state: tauri::State<'_, AppState>,
+) -> Result<ParseResponse, String> {
+    let host = find_remote_host(&host_name, &state).await?;
+    info!("🌐 读取远程日志: {}", remote::remote_label(&host, &path));
+
+    let ssh = state.ssh.clone();
+    let content = tokio::task::spawn_blocking(move || ssh.read_file(&host, &path, None))
+        .await
+        .map_err(|e| Message::new("task.ssh_failed").with("error", e).text())??;
+
+    let request = ParseRequest {
+        content,
+        plugin: Some(plugin.unwrap_or_else(|| "auto".to_string())),
+        ..Default::default()
+    };
+    parse_log(request, window, state).await
+}
+
+/// 跟踪远程日志文件
+///
+/// 在远程主机上执行 `tail -F`，新行解析后与本地跟踪一样以 `tail-batch` 事件分批发送，
+/// 同样需要通过 `ack_tail_batch` 确认，通过 `stop_tail` 停止。行号从开始跟踪时计为1。
+///
+/// # 参数
+/// - `host_name`: 远程主机名称
+/// - `path`: 远程文件路径
+/// - `options`: 批次与背压选项（可选）
+/// - `window`: 接收事件的窗口
+/// - `state`: 应用状态
+///
+/// # Returns
+/// - `Ok(String)`: 跟踪ID
+/// - `Err(String)`: 无法启动ssh时的错误信息
+#[tauri::command]
+async fn start_remote_tail(
+    host_name: String,
+    path: String,
+    options: Option<TailOptions>,
+    window: tauri::Window,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, String> {
+    let host = find_remote_host(&host_name, &state).await?;
+    let label = remote::remote_label(&host, &path);
+    let (mut child, receiver) = state.ssh.spawn_tail(&host, &path)?;
+
+    let handle = state.tails.start(&label, options.unwrap_or_default())?;
+    let tail_id = handle.tail_id.clone();
+    let plugin_manager = state.plugin_manager.clone();
+    info!("👀 开始跟踪远程文件: {} ({})", label, tail_id);
+
+    tokio::spawn(async move {
+        let interval = std::time::Duration::from_millis(handle.options.max_batch_interval_ms.max(10));
+        let mut lines_seen = 0;
+
+        while !handle.is_stopped() {
+            tokio::time::sleep(interval).await;
+            let now = std::time::Instant::now();
+
+            let lines: Vec<String> = receiver.try_iter().collect();
+            let mut new_entries = Vec::new();
+            if !lines.is_empty() {
+                let request = ParseRequest {
+                    content: lines.join("\n"),
+                    plugin: Some("auto".to_string()),
+                    ..Default::default()
+                };
+                match plugin_manager.auto_detect_and_parse(&request) {
+                    Ok(result) => new_entries = result.lines,
+                    Err(e) => warn!("⚠️ 远程跟踪 {} 解析失败: {}", handle.tail_id, e),
+                }
+                for entry in &mut new_entries {
+                    entry.line_number += lines_seen;
+                }
+                lines_seen += lines.len();
+            }
+
+            let batches: Vec<_> = match handle.batcher.lock() {
+                Ok(mut batcher) => {
+                    batcher.push(new_entries, now);
+                    std::iter::from_fn(|| batcher.next_batch(now)).collect()
+                }
+                Err(_) => break,
+            };
+            for batch in batches {
+                if let Err(e) = window.emit("tail-batch", batch) {
+                    warn!("⚠️ 发送跟踪批次失败: {}", e);
+                }
+            }
+        }
+
+        if let Err(e) = child.kill() {
+            warn!("⚠️ 结束远程跟踪进程失败: {}", e);
+        }
+        let _ = child.wait();
+        info!("🛑 远程跟踪已停止: {}", handle.tail_id);
+    });
+
+    Ok(tail_id)
+}
+
+/// 订阅Windows事件日志
+///
+/// 通过 `EvtSubscribe` 订阅事件日志通道的新事件，转换后与文件跟踪一样以 `tail-batch` 事件分批发送，
+/// 同样需要通过 `ack_tail_batch` 确认，通过 `stop_tail` 停止。仅Windows可用。
+///
+/// # 参数
+/// - `channels`: 订阅的通道（可选，默认Application和System）
+/// - `options`: 批次与背压选项（可选）
+/// - `window`: 接收事件的窗口
+/// - `state`: 应用状态
+///
+/// # Returns
+/// - `Ok(String)`: 跟踪ID
+/// - `Err(String)`: 非Windows平台、通道不存在或无权限时的错误信息
+#[tauri::command]
+async fn start_event_log_tail(
+    channels: Option<Vec<String>>,
+    options: Option<TailOptions>,
+    window: tauri::Window,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, String> {
+    let channels = channels
+        .filter(|c| !c.is_empty())
+        .unwrap_or_else(|| eventlog::DEFAULT_CHANNELS.iter().map(|c| c.to_string()).collect());
+    let label = format!("eventlog://{}", channels.join(","));
+    let (subscription, receiver) = eventlog::EventLogSubscription::start(&channels)?;
+
+    let handle = state.tails.start(&label, options.unwrap_or_default())?;
+    let tail_id = handle.tail_id.clone();
+    info!("👀 开始订阅事件日志: {} ({})", label, tail_id);
+
+    tokio::spawn(async move {
+        let interval = std::time::Duration::from_millis(handle.options.max_batch_interval_ms.max(10));
+        let mut events_seen = 0;
+
+        while !handle.is_stopped() {
+            tokio::time::sleep(interval).await;
+            let now = std::time::Instant::now();
+
+            let new_entries: Vec<LogEntry> = receiver.try_iter()
+                .filter_map(|event| {
+                    events_seen += 1;
+                    eventlog::event_to_line(&event, events_seen)
+                })
+                .collect();
+
+            let batches: Vec<_> = match handle.batcher.lock() {
+                Ok(mut batcher) => {
+                    batcher.push(new_entries, now);
+                    std::iter::from_fn(|| batcher.next_batch(now)).collect()
+                }
+                Err(_) => break,
+            };
+            for batch in batches {
+                if let Err(e) = window.emit("tail-batch", batch) {
+                    warn!("⚠️ 发送跟踪批次失败: {}", e);
+                }
+            }
+        }
+
+        drop(subscription);
+        info!("🛑 事件日志订阅已停止: {}", handle.tail_id);
+    });
+
+    Ok(tail_id)
+}
+
+/// 启动OTLP日志接收器
+///
+/// 在本机监听OTLP/HTTP日志导出（JSON编码），接收到的日志记录转换后与文件跟踪一样
+/// 以 `tail-batch` 事件分批发送，同样需要通过 `ack_tail_batch` 确认，通过 `stop_tail` 停止。
+///
+/// # 参数
+/// - `port`: 监听端口（可选，默认4318）
+/// - `options`: 批次与背压选项（可选）
+/// - `window`: 接收事件的窗口
+/// - `state`: 应用状态
+///
+/// # Returns
+/// - `Ok(OtlpReceiverInfo)`: 跟踪ID和日志导出端点URL
+/// - `Err(String)`: 端口被占用等错误信息
+#[tauri::command]
+async fn start_otlp_receiver(
+    port: Option<u16>,
+    options: Option<TailOptions>,
+    window: tauri::Window,
+    state: tauri::State<'_, AppState>,
+) -> Result<OtlpReceiverInfo, String> {
+    let (receiver, records) = OtlpReceiver::start(port.unwrap_or(otlp::DEFAULT_OTLP_PORT))?;
+    let endpoint = receiver.endpoint();
+
+    let handle = state.tails.start(&endpoint, options.unwrap_or_default())?;
+    let tail_id = handle.tail_id.clone();
+    info!("👀 开始接收OTLP日志: {} ({})", endpoint, tail_id);
+
+    tokio::spawn(async move {
+        let interval = std::time::Duration::from_millis(handle.options.max_batch_interval_ms.max(10));
+        let mut records_seen = 0;
+
+        while !handle.is_stopped() {
+            tokio::time::sleep(interval).await;
+            let now = std::time::Instant::now();
+
+            let mut new_entries: Vec<LogEntry> = records.try_iter().flatten().collect();
+            for entry in &mut new_entries {
+                records_seen += 1;
+                entry.line_number = records_seen;
+            }
+
+            let batches: Vec<_> = match handle.batcher.lock() {
+                Ok(mut batcher) => {
+                    batcher.push(new_entries, now);
+                    std::iter::from_fn(|| batcher.next_batch(now)).collect()
+                }
+                Err(_) => break,
+            };
+            for batch in batches {
+                if let Err(e) = window.emit("tail-batch", batch) {
+                    warn!("⚠️ 发送跟踪批次失败: {}", e);
+                }
+            }
+        }
+
+        drop(receiver);
+        info!("🛑 OTLP日志接收已停止: {}", handle.tail_id);
+    });
+
+    Ok(OtlpReceiverInfo { tail_id, endpoint })
+}
+
+/// 列出Docker容器
+///
+/// # 参数
+/// - `all`: 是否包括已停止的容器（可选，默认只列出运行中的容器）
+///
+/// # Returns
+/// - `Ok(Vec<ContainerSummary>)`: 容器列表
+/// - `Err(String)`: Docker不可用时的错误信息
+#[tauri::command]
+async fn list_docker_containers(all: Option<bool>) -> Result<Vec<ContainerSummary>, String> {
+    tokio::task::spawn_blocking(move || DockerClient::from_env().list_containers(all.unwrap_or(false)))
+        .await
+        .map_err(|e| Message::new("task.docker_failed").with("error", e).text())?
+}
+
+/// 获取并解析容器日志
+///
+/// 通过Docker Engine API获取容器的stdout/stderr，转换为Docker JSON日志格式后
+/// 交给与 `parse_log` 相同的解析流程（内容模式），无需找到磁盘上的 `json.log`。
+///
+/// # 参数
+/// - `container_id`: 容器ID或名称
+/// - `since`: 起始时间（可选）：Unix时间戳、RFC 3339时间或相对时长（如 `10m`、`2h`）
+/// - `tail`: 只获取最后N行（可选）
+/// - `window`: 调用命令的窗口
+/// - `state`: 应用状态
+///
+/// # Returns
+/// - `Ok(ParseResponse)`: 解析结果
+/// - `Err(String)`: 容器不存在或Docker不可用时的错误信息
+#[tauri::command]
+async fn fetch_container_logs(
+    container_id: String,
+    since: Option<String>,
+    tail: Option<usize>,
+    window: tauri::Window,
+    state: tauri::State<'_, AppState>,
+) -> Result<ParseResponse, String> {
+    info!("🐳 获取容器日志: {}", container_id);
+    let content = tokio::task::spawn_blocking(move || {
+        DockerClient::from_env().container_logs(&container_id, since.as_deref(), tail)
+    })
+        .await
+        .map_err(|e| Message::new("task.docker_failed").with("error", e).text())??;
+
+    let request = ParseRequest {
+        content,
+        plugin: Some("auto".to_string()),
+        ..Default::default()
+    };
+    parse_log(request, window, state).await
+}
+
+/// 列出kubeconfig中的上下文
+///
+/// # Returns
+/// - `Ok(Vec<String>)`: 上下文名称
+/// - `Err(String)`: kubectl不可用时的错误信息
+#[tauri::command]
+async fn list_kube_contexts() -> Result<Vec<String>, String> {
+    tokio::task::spawn_blocking(|| KubectlClient::new(None).list_contexts())
+        .await
+        .map_err(|e| Message::new("task.kubectl_failed").with("error", e).text())?
+}
+
+/// 列出集群中的命名空间
+///
+/// # 参数
+/// - `context`: kubeconfig上下文（可选，默认使用当前上下文）
+///
+/// # Returns
+/// - `Ok(Vec<String>)`: 按名称排序的命名空间
+/// - `Err(String)`: 集群不可达或无权限时的错误信息
+#[tauri::command]
+async fn list_kube_namespaces(context: Option<String>) -> Result<Vec<String>, String> {
+    tokio::task::spawn_blocking(move || KubectlClient::new(context).list_namespaces())
+        .await
+        .map_err(|e| Message::new("task.kubectl_failed").with("error", e).text())?
+}
+
+/// 列出命名空间中的Pod
+///
+/// # 参数
+/// - `context`: kubeconfig上下文（可选，默认使用当前上下文）
+/// - `namespace`: 命名空间
+///
+/// # Returns
+/// - `Ok(Vec<PodSummary>)`: Pod列表（包含容器名称和重启次数）
+/// - `Err(String)`: 集群不可达或无权限时的错误信息
+#[tauri::command]
+async fn list_kube_pods(context: Option<String>, namespace: String) -> Result<Vec<PodSummary>, String> {
+    tokio::task::spawn_blocking(move || KubectlClient::new(context).list_pods(&namespace))
+        .await
+        .map_err(|e| Message::new("task.kubectl_failed").with("error", e).text())?
+}
+
+/// 获取并解析Pod日志
+///
+/// 通过 `kubectl logs` 获取日志后交给与 `parse_log` 相同的解析流程（内容模式）。
+/// 未指定容器时获取所有容器的日志并按容器依次拼接；每个条目的元数据中记录
+/// `namespace`、`pod` 和 `container`，前端据此按容器过滤。
+///
+/// # 参数
+/// - `context`: kubeconfig上下文（可选，默认使用当前上下文）
+/// - `namespace`: 命名空间
+/// - `pod`: Pod名称
+/// - `options`: 容器、`previous`、起始时间和行数选项（可选）
+/// - `window`: 调用命令的窗口
+/// - `state`: 应用状态
+///
+/// # Returns
+/// - `Ok(ParseResponse)`: 解析结果
+/// - `Err(String)`: Pod不存在或kubectl不可用时的错误信息
+#[tauri::command]
+async fn fetch_pod_logs(
+    context: Option<String>,
+    namespace: String,
+    pod: String,
+    options: Option<PodLogOptions>,
+    window: tauri::Window,
+    state: tauri::State<'_, AppState>,
+) -> Result<ParseResponse, String> {
+    info!("☸️ 获取Pod日志: {}/{}", namespace, pod);
+    let options = options.unwrap_or_default();
+    let (ns, pod_name) = (namespace.clone(), pod.clone());
+    let logs = tokio::task::spawn_blocking(move || KubectlClient::new(context).pod_logs(&ns, &pod_name, &options))
+        .await
+        .map_err(|e| Message::new("task.kubectl_failed").with("error", e).text())??;
+
+    let (content, starts) = kubernetes::combine_container_logs(&logs);
+    let request = ParseRequest {
+        content,
+        plugin: Some("auto".to_string()),
+        ..Default::default()
+    };
+    let mut response = parse_log(request, window, state).await?;
+    kubernetes::tag_entries(&mut response.entries, &namespace, &pod, &starts);
+    Ok(response)
+}
+
+/// 获取文件中提取出的所有度量序列（不含数据点）
+///
+/// 度量由 `metrics` 过滤器在解析时提取（如 `took=123ms`、`latency: 45ms`），
+/// 界面据此列出可绘制的序列，再通过 `get_metric_series` 获取数据点。
+///
+/// # 参数
+/// - `file_path`: 日志文件路径
+/// - `state`: 应用状态，包含增量解析缓存
+///
+/// # Returns
+/// - `Ok(Vec<MetricSeries>)`: 按名称排序的序列摘要（`points` 为空）
+/// - `Err(String)`: 文件不可读或解析失败时的错误信息
+#[tauri::command]
+async fn list_metric_series(file_path: String, state: tauri::State<'_, AppState>) -> Result<Vec<MetricSeries>, String> {
+    let entries = cached_entries(&file_path, &state).await?;
+    let mut series = plugins::metrics::collect_metric_series(&entries, None);
+    for s in &mut series {
+        s.points.clear();
+    }
+    info!("📈 文件 {} 中有 {} 个度量序列", file_path, series.len());
+    Ok(series)
+}
+
+/// 获取文件中指定名称的度量序列
+///
+/// # 参数
+/// - `file_path`: 日志文件路径
+/// - `name`: 度量名称（如 `took`）
+/// - `state`: 应用状态，包含增量解析缓存
+///
+/// # Returns
+/// - `Ok(MetricSeries)`: 带行号和时间戳的数据点，以及最小/最大/平均值
+/// - `Err(String)`: 文件不可读或没有该名称的度量时的错误信息
+#[tauri::command]
+async fn get_metric_series(file_path: String, name: String, state: tauri::State<'_, AppState>) -> Result<MetricSeries, String> {
+    let entries = cached_entries(&file_path, &state).await?;
+    plugins::metrics::collect_metric_series(&entries, Some(&name))
+        .pop()
+        .ok_or_else(|| Message::new("metrics.not_found").with("name", &name).text())
+}
+
+/// 列出耗时超过阈值的慢操作
+///
+/// 耗时由 `metrics` 过滤器从 `took=123ms`、`elapsed: 2.5s`、`completed in 45 ms` 等内容中提取，
+/// 统一换算为毫秒；结果按操作名称（元数据 `operation`）或日志器分组。
+///
+/// # 参数
+/// - `file_path`: 日志文件路径
+/// - `threshold_ms`: 耗时阈值（毫秒）
+/// - `state`: 应用状态，包含增量解析缓存
+///
+/// # Returns
+/// - `Ok(Vec<SlowOperationGroup>)`: 按最大耗时降序排列的分组
+/// - `Err(String)`: 文件不可读或解析失败时的错误信息
+#[tauri::command]
+async fn list_slow_operations(
+    file_path: String,
+    threshold_ms: f64,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<SlowOperationGroup>, String> {
+    let entries = cached_entries(&file_path, &state).await?;
+    let groups = plugins::metrics::find_slow_operations(&entries, threshold_ms);
+    info!("🐢 文件 {} 中有 {} 组超过 {}ms 的慢操作", file_path, groups.len(), threshold_ms);
+    Ok(groups)
+}
+
+/// 检测度量序列中的离群点
+///
+/// 对 `metrics` 过滤器提取的每个序列计算z-score或修正z分数（MAD），
+/// 返回分数绝对值超过阈值的数据点及其前后的条目，让延迟尖峰等异常自动浮现。
+///
+/// # 参数
+/// - `file_path`: 日志文件路径
+/// - `name`: 只检测指定名称的度量（None表示全部序列）
+/// - `method`: 检测方法 `zscore` 或 `mad`（默认 `mad`）
+/// - `threshold`: 分数阈值（默认z-score为3.0，MAD为3.5）
+/// - `context_lines`: 每个离群点前后附带的条目数（默认2）
+/// - `state`: 应用状态，包含增量解析缓存
+///
+/// # Returns
+/// - `Ok(Vec<MetricOutliers>)`: 有离群点的序列，离群点按分数绝对值降序排列
+/// - `Err(String)`: 文件不可读或阈值无效时的错误信息
+#[tauri::command]
+async fn detect_metric_outliers(
+    file_path: String,
+    name: Option<String>,
+    method: Option<OutlierMethod>,
+    threshold: Option<f64>,
+    context_lines: Option<usize>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<MetricOutliers>, String> {
+    let method = method.unwrap_or_default();
+    let threshold = threshold.unwrap_or_else(|| method.default_threshold());
+    if !threshold.is_finite() || threshold <= 0.0 {
+        return Err(Message::new("metrics.invalid_threshold").with("threshold", threshold).text());
+    }
+    let entries = cached_entries(&file_path, &state).await?;
+    let reports = tokio::task::spawn_blocking(move || {
+        plugins::metrics::detect_metric_outliers(&entries, name.as_deref(), method, threshold, context_lines.unwrap_or(2))
+    })
+    .await
+    .map_err(|e| Message::new("task.outliers_failed").with("error", e).text())?;
+    let total: usize = reports.iter().map(|r| r.outliers.len()).sum();
+    info!("📈 文件 {} 中 {} 个度量序列共有 {} 个离群点", file_path, reports.len(), total);
+    Ok(reports)
+}
+
+/// 使用样本文件验证解析器（开发者工具）
+///
+/// 用指定插件逐行处理样本文件，返回哪些行被识别、哪些行未被识别，
+/// 以及每行提取出的字段。提供 `pattern` 时按自定义正则报告捕获组，
+/// 便于调试自定义正则解析器。
+///
+/// # 参数
+/// - `plugin`: 要验证的插件名称
+/// - `sample_path`: 样本文件路径
+/// - `pattern`: 自定义正则表达式（可选）
+/// - `max_lines`: 最多读取的样本行数（可选，默认1000）
+/// - `state`: 应用状态，包含插件管理器
+///
+/// # Returns
+/// - `Ok(ParserValidationReport)`: 逐行验证报告
+/// - `Err(String)`: 插件不存在、正则无效或样本不可读时的错误信息
+#[tauri::command]
+async fn validate_parser(
+    plugin: String,
+    sample_path: String,
+    pattern: Option<String>,
+    max_lines: Option<usize>,
+    state: tauri::State<'_, AppState>,
+) -> Result<plugins::validation::ParserValidationReport, String> {
+    use std::io::BufRead;
+
+    let max_lines = max_lines.unwrap_or(1000);
+    info!("🧪 验证解析器: {} (样本: {}, 最多 {} 行)", plugin, sample_path, max_lines);
+
+    let file = std::fs::File::open(&sample_path)
+        .map_err(|e| Message::new("sample.open_failed").with("error", e).text())?;
+    let sample = std::io::BufReader::new(file)
+        .lines()
+        .take(max_lines)
+        .collect::<Result<Vec<String>, _>>()
+        .map_err(|e| Message::new("sample.read_failed").with("error", e).text())?
+        .join("\n");
+
+    let report = state.plugin_manager.validate_parser(&plugin, &sample, Some(&sample_path), pattern.as_deref())?;
+    info!("✅ 解析器验证完成: {}/{} 行匹配", report.matched_lines, report.total_lines);
+    Ok(report)
+}
+
+/// 运行解析器一致性测试（开发者工具）
+///
+/// 用每个已注册的解析器解析样本目录中的 `*.log` 样本，与 `golden/` 子目录中的期望输出比较，
+/// 插件作者可以用自己的样本目录检查解析器改动是否引入回归。
+///
+/// # 参数
+/// - `fixtures_dir`: 样本目录（可选，默认为源码中的 `tests/fixtures`）
+/// - `options`: 要运行的解析器和是否按当前输出更新期望文件（可选）
+/// - `state`: 应用状态，包含插件管理器
+///
+/// # Returns
+/// - `Ok(ConformanceReport)`: 每个样本 × 解析器的比较结果
+/// - `Err(String)`: 样本目录不可读、插件不存在或期望文件无效时的错误信息
+#[tauri::command]
+async fn run_parser_conformance(
+    fixtures_dir: Option<String>,
+    options: Option<plugins::conformance::ConformanceOptions>,
+    state: tauri::State<'_, AppState>,
+) -> Result<plugins::conformance::ConformanceReport, String> {
+    let fixtures_dir = fixtures_dir
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|| std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures"));
+    info!("🧪 运行解析器一致性测试: {}", fixtures_dir.display());
+
+    let report = state.plugin_manager.run_conformance(&fixtures_dir, &options.unwrap_or_default())?;
+    info!("✅ 一致性测试完成: 通过 {}，失败 {}，缺失 {}，更新 {}", report.passed, report.failed, report.missing, report.updated);
+    Ok(report)
+}
+
+/// 从Logback/Log4j布局模式生成解析规则（预览）
+///
+/// 返回生成的正则表达式和提取的字段，界面据此展示预览；
+/// 生成的正则可直接传给 `validate_parser` 检查样本。
+/// 确认后通过 `set_plugin_settings("pattern_layout", {"pattern": ...})` 保存即可生效。
+///
+/// # 参数
+/// - `pattern`: 布局模式，如 `%d{ISO8601} [%thread] %-5level %logger{36} - %msg%n`
+///
+/// # Returns
+/// - `Ok(PatternLayoutInfo)`: 生成的正则表达式和字段列表
+/// - `Err(String)`: 布局模式无效时的错误信息
+#[tauri::command]
+async fn compile_pattern_layout(pattern: String) -> Result<plugins::pattern_layout::PatternLayoutInfo, String> {
+    info!("🧩 编译布局模式: {}", pattern);
+    let layout = plugins::pattern_layout::PatternLayout::compile(&pattern)?;
+    Ok(layout.info())
+}
+
+// ============================================================================
+// 主题配置管理命令
+// ============================================================================
+
+/// 获取当前主题配置
+///
+/// 返回应用程序的当前主题设置，包括颜色方案、字体配置等。
+/// 主题配置影响用户界面的外观和显示效果。
+///
+/// # 功能特性
+/// - 支持明暗主题切换（Light/Dark/Auto）
+/// - 可自定义主色调和强调色
+/// - 字体大小和字体族配置
+/// - 配置持久化存储
+///
+/// # 参数
+/// - `state`: 应用状态，包含配置服务实例
+///
+/// # Returns
+/// - `Ok(ThemeResponse)`: 包含当前主题配置的响应
+/// - `Err(String)`: 获取配置失败时的错误信息
+///
+/// # 主题模式说明
+/// - Light: 浅色主题，适合白天使用
+/// - Dark: 深色主题，适合夜间使用，保护视力
+/// - Auto: 自动跟随系统主题设置
+#[tauri::command]
+async fn get_theme_config(state: tauri::State<'_, AppState>) -> Result<ThemeResponse, String> {
+    debug!("🎨 获取主题配置");
+
+    match state.config_service.lock().await.get_theme_config() {
+        Ok(theme) => {
+            debug!("✅ 主题配置获取成功: mode={:?}", theme.mode);
+
+            // 将内部主题模式枚举转换为前端字符串格式
+            let response = ThemeResponse {
+                mode: theme.mode.as_str().to_string(),
+                effective: state.theme.current().effective,
+                primary_color: theme.primary_color,
+                accent_color: theme.accent_color,
+                font_size: theme.font_size,
+                font_family: theme.font_family,
+                level_styles: theme.level_styles,
+            };
+
+            Ok(response)
+        }
+        Err(e) => {
+            error!("❌ 获取主题配置失败: {}", e);
+            Err(Message::new("config.theme_unavailable").text())
+        }
+    }
+}
+
+/// 更新主题配置
+///
+/// 根据用户的请求更新应用程序的主题设置。
+/// 支持部分更新，只修改请求中明确指定的字段。
+///
+/// # 功能特性
+/// - 部分更新支持：只更新提供的字段
+/// - 配置验证：确保主题参数的有效性
+/// - 持久化存储：自动保存配置到本地存储
+/// - 实时生效：更新后立即反映到用户界面
+///
+/// # 参数
+/// - `request`: 主题更新请求，包含要更新的主题字段
+/// - `window`: 调用命令的窗口，实际生效的主题变化时用于广播 `theme_changed` 事件
+/// - `state`: 应用状态，包含配置服务实例
+///
+/// # Returns
+/// - `Ok(String)`: 更新成功的确认消息
+/// - `Err(String)`: 更新失败时的错误信息
+///
+/// # 更新流程
+/// 1. 获取当前主题配置作为基础
+/// 2. 验证并更新请求中的字段
+/// 3. 保存新配置到持久化存储
+/// 4. 返回更新结果确认
+#[tauri::command]
+async fn update_theme_config(
+    request: ThemeUpdateRequest,
+    window: tauri::Window,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, String> {
+    info!("🎨 收到主题配置更新请求: {:?}", request);
 
     // 第一步：获取当前配置作为更新基础
     // 这样可以实现部分更新，只修改请求中包含的字段
@@ -831,13 +3511,13 @@ async fn update_theme_config(
         }
         Err(e) => {
             error!("❌ 获取当前主题配置失败: {}", e);
-            return Err("获取当前主题配置失败".to_string());
+            return Err(Message::new("config.current_theme_unavailable").text());
         }
     };
 
     // 第二步：验证并更新主题模式
     // 支持的模式：light, dark, auto（默认）
-    let old_mode = theme.mode.clone();
+    let old_mode = theme.mode;
     theme.mode = match request.mode.as_str() {
         "light" => {
             debug!("🌞 切换到浅色主题");
@@ -874,22 +3554,115 @@ async fn update_theme_config(
         theme.font_size = font_size;
     }
 
-    if let Some(font_family) = request.font_family {
-        debug!("🔤 更新字体族: {} -> {}", theme.font_family, font_family);
-        theme.font_family = font_family;
+    if let Some(font_family) = request.font_family {
+        debug!("🔤 更新字体族: {} -> {}", theme.font_family, font_family);
+        theme.font_family = font_family;
+    }
+
+    // 第五步：保存配置到持久化存储
+    match state.config_service.lock().await.set_theme_config(&theme) {
+        Ok(_) => {
+            info!("✅ 主题配置更新成功: 模式 {:?} -> {:?}", old_mode, theme.mode);
+            if let Some(change) = state.theme.set_mode(theme.mode) {
+                broadcast_theme_change(&window, change);
+            }
+            Ok(Message::new("config.theme_updated").text())
+        }
+        Err(e) => {
+            error!("❌ 主题配置保存失败: {}", e);
+            Err(Message::new("config.theme_update_failed").text())
+        }
+    }
+}
+
+/// 获取当前的主题状态
+///
+/// 查询窗口当前的系统明暗主题并记录，返回配置的模式、系统主题和实际生效的主题。
+/// 之后系统切换明暗时后端通过 `theme_changed` 事件推送新状态，Auto模式无需轮询。
+///
+/// # 参数
+/// - `window`: 调用命令的窗口，用于读取系统主题
+/// - `state`: 应用状态，包含主题跟踪器
+///
+/// # Returns
+/// - `Ok(ThemeState)`: 当前主题状态
+#[tauri::command]
+async fn get_theme_state(window: tauri::Window, state: tauri::State<'_, AppState>) -> Result<ThemeState, String> {
+    match window.theme() {
+        Ok(theme) => apply_system_theme(&window, &state, theme),
+        Err(e) => warn!("⚠️ 读取系统主题失败: {}", e),
+    }
+    Ok(state.theme.current())
+}
+
+/// 记录窗口报告的系统主题，实际生效的主题变化时广播 `theme_changed` 事件
+fn apply_system_theme(window: &tauri::Window, state: &AppState, theme: tauri::Theme) {
+    let system = match theme {
+        tauri::Theme::Dark => EffectiveTheme::Dark,
+        _ => EffectiveTheme::Light,
+    };
+    if let Some(change) = state.theme.set_system(system) {
+        info!("🌗 系统主题切换为 {:?}，当前生效主题: {:?}", system, change.effective);
+        broadcast_theme_change(window, change);
+    }
+}
+
+/// 向所有窗口发送 `theme_changed` 事件
+fn broadcast_theme_change(window: &tauri::Window, change: ThemeState) {
+    use tauri::Manager;
+
+    if let Err(e) = window.emit_all("theme_changed", change) {
+        warn!("⚠️ 发送主题变更事件失败: {}", e);
     }
+}
 
-    // 第五步：保存配置到持久化存储
-    match state.config_service.lock().await.set_theme_config(&theme) {
-        Ok(_) => {
-            info!("✅ 主题配置更新成功: 模式 {:?} -> {:?}", old_mode, theme.mode);
-            Ok("主题配置更新成功".to_string())
-        }
-        Err(e) => {
-            error!("❌ 主题配置保存失败: {}", e);
-            Err("更新主题配置失败".to_string())
-        }
+/// 获取各级别的颜色和默认可见性
+///
+/// 所有窗口和HTML导出使用同一份级别样式，界面据此着色并默认隐藏不可见的级别（默认隐藏TRACE）。
+///
+/// # Returns
+/// - `Ok(LevelStylesResponse)`: 级别样式和默认隐藏的级别
+#[tauri::command]
+async fn get_level_styles(state: tauri::State<'_, AppState>) -> Result<LevelStylesResponse, String> {
+    let theme = state.config_service.lock().await.get_theme_config()?;
+    Ok(LevelStylesResponse { hidden_levels: theme.hidden_levels(), styles: theme.level_styles })
+}
+
+/// 设置级别的颜色和默认可见性
+///
+/// 保存后向所有窗口发送 `level-styles-changed` 事件（载荷与 `get_level_styles` 相同），各窗口据此同步显示。
+///
+/// # 参数
+/// - `level`: 级别名（不区分大小写，可以是新级别）
+/// - `color`: 新颜色（#rgb 或 #rrggbb，不指定时保持不变；新级别必须指定）
+/// - `visible`: 默认是否显示（不指定时保持不变）
+/// - `window`: 调用命令的窗口，用于广播事件
+/// - `state`: 应用状态，包含配置服务
+///
+/// # Returns
+/// - `Ok(LevelStylesResponse)`: 更新后的级别样式
+/// - `Err(String)`: 级别名或颜色无效、保存失败时的错误信息
+#[tauri::command]
+async fn set_level_style(
+    level: String,
+    color: Option<String>,
+    visible: Option<bool>,
+    window: tauri::Window,
+    state: tauri::State<'_, AppState>,
+) -> Result<LevelStylesResponse, String> {
+    use tauri::Manager;
+
+    let mut config_service = state.config_service.lock().await;
+    let mut theme = config_service.get_theme_config()?;
+    let style = theme.set_level_style(&level, color, visible)?;
+    config_service.set_theme_config(&theme)?;
+    info!("🎨 级别样式已更新: {} -> {} ({})", level, style.color, if style.visible { "显示" } else { "默认隐藏" });
+
+    let response = LevelStylesResponse { hidden_levels: theme.hidden_levels(), styles: theme.level_styles };
+    if let Err(e) = window.emit_all("level-styles-changed", response.clone()) {
+        warn!("⚠️ 发送级别样式变更事件失败: {}", e);
     }
+    Ok(response)
 }
 
 // ============================================================================
@@ -907,6 +3680,12 @@ async fn update_theme_config(
 /// - max_file_size: 支持的最大文件大小限制
 /// - chunk_size: 大文件分块处理的块大小
 /// - timeout_seconds: 解析超时时间限制
+/// - max_line_length: 单行最大显示长度（字符），超出部分截断
+/// - detect_sample_kb / detect_sample_lines: 自动检测的采样范围（0表示不限制）
+/// - merge_wrapped_lines: 是否合并终端按宽度折断的续行
+/// - compact: 紧凑格式开关和线程名缩略规则
+/// - strict_content: 严格模式（不改写原始内容）
+/// - formatting: 格式化风格（plain/decorated）
 ///
 /// # 参数
 /// - `state`: 应用状态，包含配置服务实例
@@ -929,15 +3708,421 @@ async fn get_parse_config(state: tauri::State<'_, AppState>) -> Result<serde_jso
                 "max_file_size": parse.max_file_size,
                 "chunk_size": parse.chunk_size,
                 "timeout_seconds": parse.timeout_seconds,
+                "max_line_length": parse.max_line_length,
+                "detect_sample_kb": parse.detect_sample_kb,
+                "detect_sample_lines": parse.detect_sample_lines,
+                "merge_wrapped_lines": parse.merge_wrapped_lines,
+                "compact": parse.compact,
+                "strict_content": parse.strict_content,
+                "formatting": parse.formatting,
             });
 
             Ok(data)
         }
         Err(e) => {
             error!("❌ 获取解析配置失败: {}", e);
-            Err("获取解析配置失败".to_string())
+            Err(Message::new("config.parse_unavailable").text())
+        }
+    }
+}
+
+/// 设置自动检测的采样范围
+///
+/// 选择解析格式时只检查内容开头的样本，两个限制同时生效，0表示不限制。
+/// 保存到解析配置中并立即生效。
+///
+/// # 参数
+/// - `sample_kb`: 最多采样的KB数
+/// - `sample_lines`: 最多采样的行数
+/// - `state`: 应用状态，包含配置服务和插件管理器
+///
+/// # Returns
+/// - `Ok(())`: 设置成功
+/// - `Err(String)`: 保存失败时的错误信息
+#[tauri::command]
+async fn set_detection_sampling(
+    sample_kb: usize,
+    sample_lines: usize,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    info!("🎯 设置自动检测采样范围: {} KB / {} 行", sample_kb, sample_lines);
+
+    let mut config_service = state.config_service.lock().await;
+    let mut parse_config = config_service.get_parse_config()?;
+    parse_config.detect_sample_kb = sample_kb;
+    parse_config.detect_sample_lines = sample_lines;
+    config_service.set_parse_config(&parse_config)?;
+
+    state.plugin_manager.set_detection_sample(parse_config.detection_sample())
+}
+
+/// 设置是否合并终端折行
+///
+/// 从终端复制的日志会按控制台宽度被折断，启用后解析时将没有时间戳的折行片段
+/// 拼接回前一个条目。保存到解析配置中，之后的解析请求生效。
+///
+/// # 参数
+/// - `enabled`: 是否启用
+/// - `state`: 应用状态，包含配置服务
+///
+/// # Returns
+/// - `Ok(())`: 设置成功
+/// - `Err(String)`: 保存失败时的错误信息
+#[tauri::command]
+async fn set_merge_wrapped_lines(enabled: bool, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    info!("🧵 设置终端折行合并: {}", enabled);
+
+    let mut config_service = state.config_service.lock().await;
+    let mut parse_config = config_service.get_parse_config()?;
+    parse_config.merge_wrapped_lines = enabled;
+    config_service.set_parse_config(&parse_config)
+}
+
+/// 设置格式化风格
+///
+/// 纯文本风格（plain）不在格式化内容中输出emoji图标和类型标签，复制到工单或导出时更干净；
+/// 装饰风格（decorated）为默认值。保存到解析配置中，之后的解析请求和导出生效。
+///
+/// # 参数
+/// - `profile`: 格式化风格
+/// - `state`: 应用状态，包含配置服务
+///
+/// # Returns
+/// - `Ok(())`: 设置成功
+/// - `Err(String)`: 保存失败时的错误信息
+#[tauri::command]
+async fn set_formatting_profile(profile: FormattingProfile, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    info!("🎨 设置格式化风格: {:?}", profile);
+
+    let mut config_service = state.config_service.lock().await;
+    let mut parse_config = config_service.get_parse_config()?;
+    parse_config.formatting = profile;
+    config_service.set_parse_config(&parse_config)
+}
+
+/// 设置严格模式
+///
+/// 启用后过滤器不再用提取出的消息改写条目的原始内容，消息写入 `message` 字段，
+/// 搜索和导出可以得到与文件完全一致的原始行。保存到解析配置中，之后的解析请求生效。
+///
+/// # 参数
+/// - `enabled`: 是否启用
+/// - `state`: 应用状态，包含配置服务
+///
+/// # Returns
+/// - `Ok(())`: 设置成功
+/// - `Err(String)`: 保存失败时的错误信息
+#[tauri::command]
+async fn set_strict_content(enabled: bool, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    info!("🔒 设置严格模式: {}", enabled);
+
+    let mut config_service = state.config_service.lock().await;
+    let mut parse_config = config_service.get_parse_config()?;
+    parse_config.strict_content = enabled;
+    config_service.set_parse_config(&parse_config)
+}
+
+/// 设置紧凑显示规则
+///
+/// 紧凑格式把冗长的线程名按"正则 → 模板"规则缩略（如 `http-nio-8080-exec-1` → `H80801`），
+/// SpringBoot解析器和过滤器使用同一套规则。保存到解析配置中，之后的解析请求生效。
+///
+/// # 参数
+/// - `settings`: 是否启用紧凑格式和线程名缩略规则（按顺序匹配）
+/// - `state`: 应用状态，包含配置服务
+///
+/// # Returns
+/// - `Ok(())`: 设置成功
+/// - `Err(String)`: 规则的正则表达式无效或保存失败时的错误信息
+#[tauri::command]
+async fn set_compact_settings(settings: CompactSettings, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    info!("✂️ 设置紧凑显示: 启用={}, {} 条线程名缩略规则", settings.enabled, settings.thread_rules.len());
+    settings.validate()?;
+
+    let mut config_service = state.config_service.lock().await;
+    let mut parse_config = config_service.get_parse_config()?;
+    parse_config.compact = settings;
+    config_service.set_parse_config(&parse_config)
+}
+
+/// 获取文件指定的解析格式
+///
+/// # 参数
+/// - `file_path`: 日志文件路径
+/// - `state`: 应用状态，包含配置服务实例
+///
+/// # Returns
+/// - `Ok(Option<String>)`: 指定的格式名称，未指定时为None（自动检测）
+/// - `Err(String)`: 读取配置失败时的错误信息
+#[tauri::command]
+async fn get_format_override(
+    file_path: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Option<String>, String> {
+    state.config_service.lock().await.get_format_override(&file_path)
+}
+
+/// 为文件指定固定的解析格式（如"这个文件总是logfmt"）
+///
+/// 指定后解析该文件时跳过自动检测，直接使用该格式（插件链或插件名称）；
+/// `format` 为None或"auto"时清除指定，恢复自动检测。已缓存的解析结果会被丢弃。
+///
+/// # 参数
+/// - `file_path`: 日志文件路径
+/// - `format`: 格式名称
+/// - `state`: 应用状态，包含配置服务、插件管理器和解析缓存
+///
+/// # Returns
+/// - `Ok(())`: 设置成功
+/// - `Err(String)`: 格式不存在或保存失败时的错误信息
+#[tauri::command]
+async fn set_format_override(
+    file_path: String,
+    format: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    let format = format.filter(|format| format != "auto");
+    if let Some(format) = &format {
+        if !state.plugin_manager.has_format(format) {
+            return Err(Message::new("format.unknown").with("format", format).text());
+        }
+    }
+
+    info!("📌 设置文件解析格式: {} -> {:?}", file_path, format);
+    state.config_service.lock().await.set_format_override(&file_path, format.as_deref())?;
+    state.parse_cache.invalidate(&file_path);
+    Ok(())
+}
+
+/// 列出所有解析配置
+#[tauri::command]
+async fn list_profiles(state: tauri::State<'_, AppState>) -> Result<Vec<ParsingProfile>, String> {
+    state.config_service.lock().await.get_profiles()
+}
+
+/// 添加或更新解析配置（按名称）
+///
+/// 解析配置把格式、插件设置、派生字段和高亮规则打包在一起，
+/// 打开文件名匹配 `file_patterns` 的文件时自动应用（如 `backend-*.log` 总是使用Backend配置）。
+/// 保存后丢弃所有已缓存的解析结果，使修改立即生效。
+///
+/// # 参数
+/// - `profile`: 解析配置
+/// - `state`: 应用状态，包含配置服务、插件管理器和解析缓存
+///
+/// # Returns
+/// - `Ok(Vec<ParsingProfile>)`: 更新后的解析配置列表
+/// - `Err(String)`: 格式不存在、插件设置或正则无效、保存失败时的错误信息
+#[tauri::command]
+async fn save_profile(profile: ParsingProfile, state: tauri::State<'_, AppState>) -> Result<Vec<ParsingProfile>, String> {
+    profile.validate()?;
+    if let Some(format) = profile.format.as_deref().filter(|format| !state.plugin_manager.has_format(format)) {
+        return Err(Message::new("format.unknown").with("format", format).text());
+    }
+    for (plugin, settings) in &profile.plugin_settings {
+        state.plugin_manager.validate_plugin_settings(plugin, settings)?;
+    }
+    info!("🧩 保存解析配置: {} (匹配 {:?})", profile.name, profile.file_patterns);
+
+    let mut config_service = state.config_service.lock().await;
+    let mut profiles = config_service.get_profiles()?;
+    config::profiles::upsert_profile(&mut profiles, profile);
+    config_service.set_profiles(&profiles)?;
+    state.parse_cache.clear();
+    Ok(profiles)
+}
+
+/// 删除解析配置，同时清除为文件手动指定的该配置
+#[tauri::command]
+async fn remove_profile(name: String, state: tauri::State<'_, AppState>) -> Result<Vec<ParsingProfile>, String> {
+    let mut config_service = state.config_service.lock().await;
+    let mut profiles = config_service.get_profiles()?;
+    profiles.retain(|profile| profile.name != name);
+    config_service.set_profiles(&profiles)?;
+
+    let mut assignments = config_service.get_profile_assignments()?;
+    assignments.retain(|_, profile| *profile != name);
+    config_service.set_profile_assignments(&assignments)?;
+    state.parse_cache.clear();
+    Ok(profiles)
+}
+
+/// 为文件手动指定解析配置
+///
+/// 手动指定优先于文件名通配符匹配；`name` 为None时清除指定，恢复按文件名匹配。
+/// 已缓存的解析结果会被丢弃。
+///
+/// # 参数
+/// - `file_path`: 日志文件路径
+/// - `name`: 解析配置名称
+/// - `state`: 应用状态，包含配置服务和解析缓存
+///
+/// # Returns
+/// - `Ok(Option<ParsingProfile>)`: 文件现在使用的解析配置
+/// - `Err(String)`: 配置不存在或保存失败时的错误信息
+#[tauri::command]
+async fn apply_profile(
+    file_path: String,
+    name: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Option<ParsingProfile>, String> {
+    let mut config_service = state.config_service.lock().await;
+    let mut assignments = config_service.get_profile_assignments()?;
+    match name {
+        Some(name) => {
+            if !config_service.get_profiles()?.iter().any(|profile| profile.name == name) {
+                return Err(Message::new("profile.unknown").with("name", &name).text());
+            }
+            info!("🧩 为文件指定解析配置: {} -> {}", file_path, name);
+            assignments.insert(file_path.clone(), name);
+        }
+        None => {
+            assignments.remove(&file_path);
+        }
+    }
+    config_service.set_profile_assignments(&assignments)?;
+    state.parse_cache.invalidate(&file_path);
+    config_service.get_file_profile(&file_path)
+}
+
+/// 获取文件使用的解析配置（手动指定优先，否则按文件名匹配）
+///
+/// 界面据此显示当前配置并应用其中的高亮规则。
+#[tauri::command]
+async fn get_file_profile(file_path: String, state: tauri::State<'_, AppState>) -> Result<Option<ParsingProfile>, String> {
+    state.config_service.lock().await.get_file_profile(&file_path)
+}
+
+/// 导出解析配置为JSON文本（可由 `save_profile` 导入）
+#[tauri::command]
+async fn export_profile(name: String, state: tauri::State<'_, AppState>) -> Result<String, String> {
+    let profile = state.config_service.lock().await.get_profiles()?
+        .into_iter()
+        .find(|profile| profile.name == name)
+        .ok_or_else(|| Message::new("profile.unknown").with("name", &name).text())?;
+    serde_json::to_string_pretty(&profile).map_err(|e| Message::new("profile.serialize_failed").with("error", e).text())
+}
+
+/// 导出共享配置文件（`.lwprofile`）
+///
+/// 把解析配置（含派生字段和高亮规则）和自定义解析器的插件设置写入一个带版本号的JSON文件，
+/// 同事用 `import_profile_bundle` 导入。写入与 `export_logs` 一样受可信目录限制并使用原子写入。
+///
+/// # 参数
+/// - `path`: 导出文件路径（绝对路径）
+/// - `names`: 导出的解析配置名称（可选，默认全部）
+/// - `include_plugin_settings`: 是否包含全局插件设置（可选，默认包含）
+/// - `confirm_token`: 写入可信目录以外的路径时的确认令牌（可选）
+/// - `state`: 应用状态，包含配置服务和写入守卫
+///
+/// # Returns
+/// - `Ok(ExportResponse)`: 导出结果（`entries` 为导出的解析配置数）；`written` 为 false 时需要用户确认
+/// - `Err(String)`: 配置不存在或写入失败时的错误信息
+#[tauri::command]
+async fn export_profile_bundle(
+    path: String,
+    names: Option<Vec<String>>,
+    include_plugin_settings: Option<bool>,
+    confirm_token: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<ExportResponse, String> {
+    let (mut profiles, plugin_settings) = {
+        let config_service = state.config_service.lock().await;
+        (config_service.get_profiles()?, config_service.get_plugin_config()?.plugin_settings)
+    };
+    if let Some(names) = &names {
+        if let Some(missing) = names.iter().find(|name| !profiles.iter().any(|profile| profile.name == **name)) {
+            return Err(Message::new("profile.unknown").with("name", missing).text());
+        }
+        profiles.retain(|profile| names.contains(&profile.name));
+    }
+    let plugin_settings = if include_plugin_settings.unwrap_or(true) { plugin_settings } else { Default::default() };
+    let count = profiles.len();
+
+    let target = match state.write_guard.check(&path, confirm_token.as_deref())? {
+        WriteCheck::Allowed(target) => target,
+        WriteCheck::ConfirmationRequired(confirmation) => {
+            warn!("⚠️ 导出路径需要用户确认: {}", confirmation.path);
+            return Ok(ExportResponse {
+                written: false,
+                path: confirmation.path.clone(),
+                bytes: 0,
+                entries: count,
+                lossy: false,
+                confirmation: Some(confirmation),
+            });
+        }
+    };
+
+    let bundle = config::profiles::ProfileBundle::new(profiles, plugin_settings);
+    let text = serde_json::to_string_pretty(&bundle).map_err(|e| Message::new("profile.serialize_failed").with("error", e).text())?;
+    write_guard::atomic_write(&target, text.as_bytes())?;
+
+    info!("📤 导出共享配置: {} ({} 个解析配置，{} 个插件设置)", target.display(), count, bundle.plugin_settings.len());
+    Ok(ExportResponse {
+        written: true,
+        path: target.to_string_lossy().into_owned(),
+        bytes: text.len(),
+        entries: count,
+        lossy: false,
+        confirmation: None,
+    })
+}
+
+/// 导入共享配置文件（`.lwprofile`）
+///
+/// 校验文件格式、版本、正则、格式名称和插件设置后合并到本地配置；
+/// 同名但内容不同的解析配置和插件设置按 `strategy` 处理。`dry_run` 为true时只返回合并结果，不保存。
+///
+/// # 参数
+/// - `path`: 共享配置文件路径
+/// - `strategy`: 冲突处理方式 `skip`（默认）、`overwrite` 或 `rename`
+/// - `dry_run`: 是否只预览（可选，默认false）
+/// - `state`: 应用状态，包含配置服务、插件管理器和解析缓存
+///
+/// # Returns
+/// - `Ok(ImportReport)`: 新增、覆盖、改名和跳过的配置，以及冲突和导入的插件设置
+/// - `Err(String)`: 文件不可读或校验失败时的错误信息（此时不导入任何内容）
+#[tauri::command]
+async fn import_profile_bundle(
+    path: String,
+    strategy: Option<config::profiles::ConflictStrategy>,
+    dry_run: Option<bool>,
+    state: tauri::State<'_, AppState>,
+) -> Result<config::profiles::ImportReport, String> {
+    let text = std::fs::read_to_string(&path)
+        .map_err(|e| Message::new("profile.bundle_read_failed").with("path", &path).with("error", e).text())?;
+    let bundle = config::profiles::ProfileBundle::parse(&text)?;
+    for profile in &bundle.profiles {
+        if let Some(format) = profile.format.as_deref().filter(|format| !state.plugin_manager.has_format(format)) {
+            return Err(Message::new("profile.unknown_format").with("name", &profile.name).with("format", format).text());
+        }
+    }
+    for (plugin, settings) in bundle.plugin_settings.iter().chain(bundle.profiles.iter().flat_map(|profile| &profile.plugin_settings)) {
+        state.plugin_manager.validate_plugin_settings(plugin, settings)?;
+    }
+
+    let mut config_service = state.config_service.lock().await;
+    let mut profiles = config_service.get_profiles()?;
+    let mut plugin_config = config_service.get_plugin_config()?;
+    let mut report = config::profiles::merge_bundle(&mut profiles, &mut plugin_config.plugin_settings, bundle, strategy.unwrap_or_default());
+    report.dry_run = dry_run.unwrap_or(false);
+    if report.dry_run {
+        return Ok(report);
+    }
+
+    config_service.set_profiles(&profiles)?;
+    if !report.imported_plugins.is_empty() {
+        config_service.set_plugin_config(&plugin_config)?;
+        state.plugin_manager.apply_plugin_settings(&plugin_config.plugin_settings)?;
+        if let Ok(mut active) = state.profile_settings.lock() {
+            *active = None;
         }
     }
+    state.parse_cache.clear();
+    info!("📥 导入共享配置 {}: 新增 {}，覆盖 {}，改名 {}，跳过 {}，插件设置 {}",
+          path, report.added.len(), report.replaced.len(), report.renamed.len(), report.skipped.len(), report.imported_plugins.len());
+    Ok(report)
 }
 
 /// 获取插件配置
@@ -977,11 +4162,70 @@ async fn get_plugin_config(state: tauri::State<'_, AppState>) -> Result<serde_js
         }
         Err(e) => {
             error!("❌ 获取插件配置失败: {}", e);
-            Err("获取插件配置失败".to_string())
+            Err(Message::new("config.plugin_unavailable").text())
         }
     }
 }
 
+/// 获取插件配置项Schema
+///
+/// 返回每个声明了可配置项的插件（过滤器）的JSON Schema及当前设置值，
+/// 前端据此生成插件设置界面。
+///
+/// # 参数
+/// - `state`: 应用状态，包含配置服务和插件管理器
+///
+/// # Returns
+/// - `Ok(Vec<PluginSettingsSchema>)`: 插件配置项列表
+/// - `Err(String)`: 获取配置失败时的错误信息
+#[tauri::command]
+async fn get_plugin_settings_schema(state: tauri::State<'_, AppState>) -> Result<Vec<PluginSettingsSchema>, String> {
+    debug!("🔌 获取插件配置项Schema");
+
+    let plugin_settings = state.config_service.lock().await.get_plugin_config()?.plugin_settings;
+    Ok(state.plugin_manager.plugin_settings_schemas(&plugin_settings))
+}
+
+/// 更新单个插件的设置
+///
+/// 按插件声明的Schema校验设置后持久化到插件配置中，并重建插件链使其立即生效。
+///
+/// # 参数
+/// - `plugin`: 插件名称（如 "docker_json"、"ansi"）
+/// - `settings`: 新的设置对象，未包含的配置项使用默认值
+/// - `state`: 应用状态，包含配置服务和插件管理器
+///
+/// # Returns
+/// - `Ok(serde_json::Value)`: 更新结果
+/// - `Err(String)`: 设置不合法或保存失败时的错误信息
+#[tauri::command]
+async fn set_plugin_settings(
+    plugin: String,
+    settings: serde_json::Value,
+    state: tauri::State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    info!("🔌 更新插件设置: {} -> {}", plugin, settings);
+
+    state.plugin_manager.validate_plugin_settings(&plugin, &settings)?;
+
+    let mut config_service = state.config_service.lock().await;
+    let mut plugin_config = config_service.get_plugin_config()?;
+    plugin_config.plugin_settings.insert(plugin.clone(), settings);
+    config_service.set_plugin_config(&plugin_config)?;
+
+    state.plugin_manager.apply_plugin_settings(&plugin_config.plugin_settings)?;
+    if let Ok(mut active) = state.profile_settings.lock() {
+        *active = None;
+    }
+
+    info!("✅ 插件设置已保存并生效: {}", plugin);
+    Ok(serde_json::json!({
+        "success": true,
+        "message": "插件设置已更新",
+        "plugin": plugin
+    }))
+}
+
 /// 获取窗口配置
 ///
 /// 返回与应用程序窗口相关的配置参数，包括窗口尺寸、位置和行为设置。
@@ -993,37 +4237,178 @@ async fn get_plugin_config(state: tauri::State<'_, AppState>) -> Result<serde_js
 /// - maximized: 是否默认最大化显示
 /// - always_on_top: 是否保持窗口置顶
 /// - remember_position: 是否记住窗口位置
+/// - x, y: 上次记录的窗口位置（从未记录过为null）
+///
+/// # 参数
+/// - `state`: 应用状态，包含配置服务实例
+///
+/// # Returns
+/// - `Ok(serde_json::Value)`: 包含窗口配置的JSON对象
+/// - `Err(String)`: 获取配置失败时的错误信息
+#[tauri::command]
+async fn get_window_config(state: tauri::State<'_, AppState>) -> Result<serde_json::Value, String> {
+    debug!("🪟 获取窗口配置");
+
+    match state.config_service.lock().await.get_window_config() {
+        Ok(window) => {
+            debug!("✅ 窗口配置获取成功");
+
+            // 将内部窗口配置转换为前端JSON格式
+            let data = serde_json::json!({
+                "width": window.width,
+                "height": window.height,
+                "maximized": window.maximized,
+                "always_on_top": window.always_on_top,
+                "remember_position": window.remember_position,
+                "x": window.x,
+                "y": window.y,
+                "locale": window.locale,
+            });
+
+            Ok(data)
+        }
+        Err(e) => {
+            error!("❌ 获取窗口配置失败: {}", e);
+            Err(Message::new("config.window_unavailable").text())
+        }
+    }
+}
+
+/// 按保存的窗口配置恢复窗口位置、大小和最大化状态
+///
+/// 从未保存过窗口状态时保持 tauri.conf.json 中的默认窗口；
+/// 保存的位置不在当前任何显示器上时居中显示。
+fn restore_window_state(window: &tauri::Window, config: &WindowConfig) -> tauri::Result<()> {
+    let monitors: Vec<MonitorArea> = window.available_monitors()?
+        .iter()
+        .map(|monitor| {
+            let position = monitor.position().to_logical::<i32>(monitor.scale_factor());
+            let size = monitor.size().to_logical::<u32>(monitor.scale_factor());
+            MonitorArea { x: position.x, y: position.y, width: size.width, height: size.height }
+        })
+        .collect();
+    let Some(restored) = window_state::restore(config, &monitors) else {
+        return Ok(());
+    };
+
+    if window.is_maximized()? {
+        window.unmaximize()?;
+    }
+    window.set_size(tauri::LogicalSize::new(restored.width, restored.height))?;
+    match restored.position {
+        Some((x, y)) => window.set_position(tauri::LogicalPosition::new(x, y))?,
+        None => window.center()?,
+    }
+    if restored.maximized {
+        window.maximize()?;
+    }
+    info!("🪟 已恢复窗口状态: {}x{} 位置 {:?} 最大化 {}", restored.width, restored.height, restored.position, restored.maximized);
+    Ok(())
+}
+
+/// 窗口移动或缩放后记录当前几何信息，停止变化 `SAVE_DEBOUNCE` 后写入窗口配置
+fn record_window_state(window: &tauri::Window, state: &AppState) {
+    let geometry = match read_window_geometry(window) {
+        Ok(geometry) => geometry,
+        Err(e) => {
+            debug!("⚠️ 读取窗口几何信息失败: {}", e);
+            return;
+        }
+    };
+    let generation = state.window_state.record(geometry);
+    let saver = state.window_state.clone();
+    let config_service = state.config_service.clone();
+
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(window_state::SAVE_DEBOUNCE).await;
+        let Some(geometry) = saver.take_if_latest(generation) else {
+            return;
+        };
+
+        let mut config_service = config_service.lock().await;
+        let result = config_service.get_window_config().and_then(|mut config| {
+            if window_state::apply(&mut config, &geometry) {
+                config_service.set_window_config(&config)?;
+            }
+            Ok(())
+        });
+        if let Err(e) = result {
+            warn!("⚠️ 保存窗口状态失败: {}", e);
+        }
+    });
+}
+
+/// 读取窗口当前的位置、大小和最大化状态（逻辑像素）
+fn read_window_geometry(window: &tauri::Window) -> tauri::Result<WindowGeometry> {
+    let scale_factor = window.scale_factor()?;
+    let position = window.outer_position()?.to_logical::<i32>(scale_factor);
+    let size = window.inner_size()?.to_logical::<u32>(scale_factor);
+    Ok(WindowGeometry {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+        maximized: window.is_maximized()?,
+    })
+}
+
+/// 获取当前界面语言
+///
+/// # Returns
+/// - `Ok(String)`: 语言标识（"zh-CN"、"en-US" 或 "code"）
+#[tauri::command]
+async fn get_locale() -> Result<String, String> {
+    Ok(i18n::current_locale().as_str().to_string())
+}
+
+/// 设置界面语言
+///
+/// 更新后端消息使用的语言并持久化到窗口配置。
+/// 设置为 "code" 时，后端消息以 `{"code": ..., "params": {...}}` 的JSON形式返回，
+/// 由前端根据 `get_message_catalog` 自行本地化。
 ///
 /// # 参数
+/// - `locale`: 语言标识（"zh-CN"、"en-US" 或 "code"）
 /// - `state`: 应用状态，包含配置服务实例
 ///
 /// # Returns
-/// - `Ok(serde_json::Value)`: 包含窗口配置的JSON对象
-/// - `Err(String)`: 获取配置失败时的错误信息
+/// - `Ok(String)`: 生效后的语言标识
+/// - `Err(String)`: 语言不受支持或保存失败时的错误信息
 #[tauri::command]
-async fn get_window_config(state: tauri::State<'_, AppState>) -> Result<serde_json::Value, String> {
-    debug!("🪟 获取窗口配置");
+async fn set_locale(locale: String, state: tauri::State<'_, AppState>) -> Result<String, String> {
+    let locale = Locale::parse(&locale)?;
+    info!("🌐 设置界面语言: {}", locale.as_str());
 
-    match state.config_service.lock().await.get_window_config() {
-        Ok(window) => {
-            debug!("✅ 窗口配置获取成功");
+    let mut config_service = state.config_service.lock().await;
+    let mut window_config = config_service.get_window_config()?;
+    window_config.locale = locale.as_str().to_string();
+    config_service.set_window_config(&window_config)?;
 
-            // 将内部窗口配置转换为前端JSON格式
-            let data = serde_json::json!({
-                "width": window.width,
-                "height": window.height,
-                "maximized": window.maximized,
-                "always_on_top": window.always_on_top,
-                "remember_position": window.remember_position,
-            });
+    i18n::set_locale(locale);
+    Ok(locale.as_str().to_string())
+}
 
-            Ok(data)
-        }
-        Err(e) => {
-            error!("❌ 获取窗口配置失败: {}", e);
-            Err("获取窗口配置失败".to_string())
-        }
-    }
+/// 获取后端消息目录
+///
+/// 返回消息代码到文本模板的映射（参数以 `{name}` 占位），
+/// 供前端在 "code" 模式下自行渲染后端消息。
+///
+/// # 参数
+/// - `locale`: 目录语言（可选，默认当前语言）
+///
+/// # Returns
+/// - `Ok(BTreeMap<String, String>)`: 消息代码到模板的映射
+/// - `Err(String)`: 语言不受支持时的错误信息
+#[tauri::command]
+async fn get_message_catalog(locale: Option<String>) -> Result<std::collections::BTreeMap<String, String>, String> {
+    let locale = match locale {
+        Some(locale) => Locale::parse(&locale)?,
+        None => i18n::current_locale(),
+    };
+
+    Ok(i18n::catalog(locale).into_iter()
+        .map(|(code, template)| (code.to_string(), template.to_string()))
+        .collect())
 }
 
 /// 获取所有配置
@@ -1064,7 +4449,7 @@ async fn get_all_configs(state: tauri::State<'_, AppState>) -> Result<serde_json
         }
         Err(e) => {
             error!("❌ 获取所有配置失败: {}", e);
-            Err("获取所有配置失败".to_string())
+            Err(Message::new("config.all_unavailable").text())
         }
     }
 }
@@ -1111,30 +4496,25 @@ async fn read_text_file(path: String) -> Result<String, String> {
     // 检查路径是否存在
     if !path_obj.exists() {
         error!("❌ 文件不存在: {}", path);
-        return Err(format!("文件不存在: {}", path));
+        return Err(Message::new("file.not_found").with("path", &path).text());
     }
 
     // 检查是否为文件（而非目录）
     if !path_obj.is_file() {
         error!("❌ 路径不是文件: {}", path);
-        return Err(format!("路径不是文件: {}", path));
+        return Err(Message::new("file.not_a_file").with("path", &path).text());
     }
 
     // 尝试读取文件内容
     match std::fs::read_to_string(&path) {
         Ok(content) => {
             info!("✅ 文件读取成功: {} (大小: {} bytes)", path, content.len());
-            debug!("📝 文件内容预览: {}",
-                  if content.len() > 100 {
-                      format!("{}...", &content[..100])
-                  } else {
-                      content.clone()
-                  });
+            debug!("📝 文件内容预览: {}", ellipsize(&content, 100));
             Ok(content)
         }
         Err(e) => {
             error!("❌ 读取文件失败: {} - 错误: {}", path, e);
-            Err(format!("读取文件失败: {}", e))
+            Err(Message::new("file.read_failed").with("path", &path).with("error", e).text())
         }
     }
 }
@@ -1144,116 +4524,824 @@ async fn read_text_file(path: String) -> Result<String, String> {
 /// 安全地将内容写入到指定路径的文件中。
 /// 此命令为前端提供了写入本地文件的能力，用于保存配置、导出数据等操作。
 ///
-/// # 功能特性
-/// - 原子性写入：确保文件完整性
-/// - 自动创建目录结构
-/// - 完整的错误处理和日志记录
-/// - 覆盖写入模式
+/// # 功能特性
+/// - 原子性写入：先写入临时文件再重命名，崩溃时不会截断目标文件
+/// - 自动创建目录结构
+/// - 完整的错误处理和日志记录
+/// - 覆盖写入模式
+///
+/// # 参数
+/// - `path`: 要写入的文件路径（绝对路径）
+/// - `contents`: 要写入的文件内容
+/// - `confirm_token`: 写入可信目录以外的路径时，上一次请求返回的确认令牌（可选）
+/// - `state`: 应用状态，包含写入守卫
+///
+/// # Returns
+/// - `Ok(WriteFileResponse)`: 写入结果；`written` 为 false 时需要向用户确认，
+///   确认后携带 `confirmation.token` 再次调用
+/// - `Err(String)`: 写入失败时的详细错误信息
+///
+/// # 错误处理
+/// - 磁盘空间不足
+/// - 权限不足
+/// - 目录不存在（自动创建）
+/// - 文件被占用
+///
+/// # 安全考虑
+/// - 默认只允许写入导出目录、下载目录和用户通过 `choose_write_directory` / `choose_save_path` 对话框选中的目录
+/// - 其他路径需要一次性确认令牌，令牌与路径绑定，5分钟内有效
+#[tauri::command]
+async fn write_file(
+    path: String,
+    contents: String,
+    confirm_token: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<WriteFileResponse, String> {
+    info!("💾 请求写入文件: {} (大小: {} bytes)", path, contents.len());
+
+    // 路径安全验证
+    let target = match state.write_guard.check(&path, confirm_token.as_deref())? {
+        WriteCheck::Allowed(target) => target,
+        WriteCheck::ConfirmationRequired(confirmation) => {
+            warn!("⚠️ 写入路径需要用户确认: {}", confirmation.path);
+            return Ok(WriteFileResponse {
+                written: false,
+                path: confirmation.path.clone(),
+                bytes: 0,
+                confirmation: Some(confirmation),
+            });
+        }
+    };
+
+    // 原子写入文件内容
+    match write_guard::atomic_write(&target, contents.as_bytes()) {
+        Ok(_) => {
+            info!("✅ 文件写入成功: {} (大小: {} bytes)", target.display(), contents.len());
+            Ok(WriteFileResponse {
+                written: true,
+                path: target.to_string_lossy().into_owned(),
+                bytes: contents.len(),
+                confirmation: None,
+            })
+        }
+        Err(e) => {
+            error!("❌ 写入文件失败: {} - 错误: {}", target.display(), e);
+            Err(e)
+        }
+    }
+}
+
+/// 导出日志条目
+///
+/// 将前端筛选后的日志条目导出到文件，支持文本/CSV/JSON格式、
+/// UTF-8/UTF-8 BOM/GBK编码、LF/CRLF换行以及CSV分隔符选择。
+/// 写入与 `write_file` 一样受可信目录限制并使用原子写入。
+///
+/// # 参数
+/// - `path`: 导出文件路径（绝对路径）
+/// - `entries`: 要导出的日志条目
+/// - `options`: 导出选项（省略的字段使用默认值：plain格式、UTF-8、LF、逗号分隔，格式化风格取解析配置）
+/// - `confirm_token`: 写入可信目录以外的路径时的确认令牌（可选）
+/// - `state`: 应用状态，包含渲染器注册表和写入守卫
+///
+/// # Returns
+/// - `Ok(ExportResponse)`: 导出结果；`written` 为 false 时需要用户确认
+/// - `Err(String)`: 格式无效或写入失败时的错误信息
+#[tauri::command]
+async fn export_logs(
+    path: String,
+    entries: Vec<LogEntry>,
+    options: Option<ExportOptions>,
+    confirm_token: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<ExportResponse, String> {
+    let mut options = options.unwrap_or_default();
+    // 未指定格式化风格时按解析配置决定
+    if options.formatting.is_none() {
+        options.formatting = state.config_service.lock().await.get_parse_config().ok().map(|config| config.formatting);
+    }
+    info!("📤 导出日志: {} ({} 条, 格式: {}, 编码: {:?}, 换行: {:?})",
+          path, entries.len(), options.format, options.encoding, options.newline);
+
+    let target = match state.write_guard.check(&path, confirm_token.as_deref())? {
+        WriteCheck::Allowed(target) => target,
+        WriteCheck::ConfirmationRequired(confirmation) => {
+            warn!("⚠️ 导出路径需要用户确认: {}", confirmation.path);
+            return Ok(ExportResponse {
+                written: false,
+                path: confirmation.path.clone(),
+                bytes: 0,
+                entries: entries.len(),
+                lossy: false,
+                confirmation: Some(confirmation),
+            });
+        }
+    };
+
+    let output = export::export_entries(&entries, &options, &state.renderers)?;
+    write_guard::atomic_write(&target, &output.bytes)?;
+
+    info!("✅ 导出完成: {} ({} bytes)", target.display(), output.bytes.len());
+    Ok(ExportResponse {
+        written: true,
+        path: target.to_string_lossy().into_owned(),
+        bytes: output.bytes.len(),
+        entries: entries.len(),
+        lossy: output.lossy,
+        confirmation: None,
+    })
+}
+
+/// 拆分导出响应
+#[derive(Debug, Serialize, Deserialize)]
+struct SplitExportResponse {
+    /// 是否已写入
+    written: bool,
+
+    /// 写入的文件（按键值排序）
+    files: Vec<export::split::SplitPart>,
+
+    /// 需要用户确认时的确认请求（携带其中的令牌再次调用 export_split）
+    confirmation: Option<WriteConfirmation>,
+}
+
+/// 按键拆分导出文件的条目
+///
+/// 按 `key`（`level` 或元数据键，如 `pod`、`logger`）把筛选后的条目分组，
+/// 每组写入 `path` 同目录下的 `<文件名>.<键值>.<扩展名>`，没有该键的条目写入 `unknown` 组。
+/// 可信目录检查针对 `path`，确认后同目录下的拆分文件都允许写入；最多拆分为200个文件。
+///
+/// # 参数
+/// - `file_path`: 日志文件路径
+/// - `path`: 基础导出文件路径（绝对路径，如 `/exports/app.log`）
+/// - `key`: 分组键
+/// - `filter`: 筛选条件（可选）
+/// - `options`: 导出选项（可选，同 `export_logs`）
+/// - `confirm_token`: 写入可信目录以外的路径时的确认令牌（可选）
+/// - `state`: 应用状态，包含增量解析缓存、渲染器注册表和写入守卫
+///
+/// # Returns
+/// - `Ok(SplitExportResponse)`: 写入的文件；`written` 为 false 时需要用户确认
+/// - `Err(String)`: 筛选条件无效、分组过多或写入失败时的错误信息
+#[tauri::command]
+async fn export_split(
+    file_path: String,
+    path: String,
+    key: String,
+    filter: Option<EntryFilter>,
+    options: Option<ExportOptions>,
+    confirm_token: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<SplitExportResponse, String> {
+    let mut options = options.unwrap_or_default();
+    if options.formatting.is_none() {
+        options.formatting = state.config_service.lock().await.get_parse_config().ok().map(|config| config.formatting);
+    }
+    let matcher = filter.filter(|filter| !filter.is_empty()).map(|filter| filter.compile()).transpose()?;
+
+    let target = match state.write_guard.check(&path, confirm_token.as_deref())? {
+        WriteCheck::Allowed(target) => target,
+        WriteCheck::ConfirmationRequired(confirmation) => {
+            warn!("⚠️ 导出路径需要用户确认: {}", confirmation.path);
+            return Ok(SplitExportResponse { written: false, files: Vec::new(), confirmation: Some(confirmation) });
+        }
+    };
+
+    let mut entries = cached_entries(&file_path, &state).await?;
+    if let Some(matcher) = &matcher {
+        entries.retain(|entry| matcher.matches(entry));
+    }
+    info!("📤 拆分导出: {} 按 {} ({} 条, 格式: {})", file_path, key, entries.len(), options.format);
+
+    let renderers = state.renderers.clone();
+    let files = tokio::task::spawn_blocking(move || {
+        export::split::export_split(&entries, &key, &target, &options, &renderers, export::split::DEFAULT_MAX_PARTITIONS)
+    })
+    .await
+    .map_err(|e| Message::new("task.split_export_failed").with("error", e).text())??;
+
+    info!("✅ 拆分导出完成: {} 个文件", files.len());
+    Ok(SplitExportResponse { written: true, files, confirmation: None })
+}
+
+/// 导出任务响应
+#[derive(Debug, Serialize, Deserialize)]
+struct ExportJobResponse {
+    /// 是否已启动
+    started: bool,
+
+    /// 任务清单（进度通过 `export-progress` 事件推送）
+    job: Option<ExportManifest>,
+
+    /// 需要用户确认时的确认请求（携带其中的令牌再次调用 start_export_job）
+    confirmation: Option<WriteConfirmation>,
+}
+
+/// 在后台执行导出任务，每写完一块向窗口发送 `export-progress` 事件
+async fn spawn_export_job(manifest: ExportManifest, entries: Vec<models::LogLine>, window: tauri::Window, state: &tauri::State<'_, AppState>) -> Result<(), String> {
+    let store = state.export_jobs.clone();
+    let renderers = state.renderers.clone();
+    store.begin(&manifest.job_id)?;
+    tokio::spawn(async move {
+        let job_id = manifest.job_id.clone();
+        let task_store = store.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            export::job::run_export_job(manifest, &entries, &renderers, &task_store, |progress| {
+                if let Err(e) = window.emit("export-progress", progress.clone()) {
+                    warn!("⚠️ 发送导出进度事件失败: {}", e);
+                }
+            })
+        })
+        .await;
+        if let Err(e) = result {
+            error!("❌ 导出任务 {} 异常退出: {}", job_id, e);
+        }
+        store.finish(&job_id);
+    });
+    Ok(())
+}
+
+/// 启动后台导出任务
+///
+/// 用于导出数百万条筛选后的条目：立即返回任务清单，在后台按每块5万条编码并追加写入，
+/// 每块写完后记录CRC32校验和并发送 `export-progress` 事件。应用重启后可以用
+/// `resume_export_job` 续传。支持CSV和纯文本/ANSI/Markdown格式（JSON、HTML和按记录导出无法分块拼接）。
+///
+/// # 参数
+/// - `file_path`: 日志文件路径
+/// - `path`: 导出文件路径（绝对路径）
+/// - `filter`: 筛选条件（可选）
+/// - `options`: 导出选项（可选，同 `export_logs`）
+/// - `confirm_token`: 写入可信目录以外的路径时的确认令牌（可选）
+/// - `window`: 接收进度事件的窗口
+/// - `state`: 应用状态，包含增量解析缓存、写入守卫和导出任务存储
+///
+/// # Returns
+/// - `Ok(ExportJobResponse)`: 已启动的任务；`started` 为 false 时需要用户确认
+/// - `Err(String)`: 筛选条件无效、格式不支持或文件不可读时的错误信息
+#[tauri::command]
+async fn start_export_job(
+    file_path: String,
+    path: String,
+    filter: Option<EntryFilter>,
+    options: Option<ExportOptions>,
+    confirm_token: Option<String>,
+    window: tauri::Window,
+    state: tauri::State<'_, AppState>,
+) -> Result<ExportJobResponse, String> {
+    let mut options = options.unwrap_or_default();
+    if options.formatting.is_none() {
+        options.formatting = state.config_service.lock().await.get_parse_config().ok().map(|config| config.formatting);
+    }
+    let filter = filter.filter(|filter| !filter.is_empty());
+    let matcher = filter.clone().map(|filter| filter.compile()).transpose()?;
+
+    let target = match state.write_guard.check(&path, confirm_token.as_deref())? {
+        WriteCheck::Allowed(target) => target,
+        WriteCheck::ConfirmationRequired(confirmation) => {
+            warn!("⚠️ 导出路径需要用户确认: {}", confirmation.path);
+            return Ok(ExportJobResponse { started: false, job: None, confirmation: Some(confirmation) });
+        }
+    };
+
+    let mut entries = cached_entries(&file_path, &state).await?;
+    if let Some(matcher) = &matcher {
+        entries.retain(|entry| matcher.matches(entry));
+    }
+    let mut manifest = ExportManifest::new(&file_path, filter, options, &target, entries.len())?;
+    state.export_jobs.save(&mut manifest)?;
+    info!("📤 启动导出任务 {}: {} -> {} ({} 条)", manifest.job_id, file_path, target.display(), entries.len());
+
+    spawn_export_job(manifest.clone(), entries, window, &state).await?;
+    Ok(ExportJobResponse { started: true, job: Some(manifest), confirmation: None })
+}
+
+/// 续传中断或失败的导出任务
+///
+/// 重新解析日志文件并按任务记录的筛选条件筛选，校验已写入块的校验和后从第一个缺失或损坏的块继续。
+/// 日志文件在任务创建后发生变化时拒绝续传。
+///
+/// # 参数
+/// - `job_id`: 任务ID
+/// - `window`: 接收进度事件的窗口
+/// - `state`: 应用状态，包含增量解析缓存和导出任务存储
+///
+/// # Returns
+/// - `Ok(ExportManifest)`: 任务清单
+/// - `Err(String)`: 任务不存在、已完成、正在运行或日志文件已变化时的错误信息
+#[tauri::command]
+async fn resume_export_job(
+    job_id: String,
+    window: tauri::Window,
+    state: tauri::State<'_, AppState>,
+) -> Result<ExportManifest, String> {
+    let manifest = state.export_jobs.load(&job_id)?;
+    match manifest.status {
+        export::job::ExportJobStatus::Completed => return Err(Message::new("export.job_completed").text()),
+        export::job::ExportJobStatus::Running => return Err(Message::new("export.job_running").text()),
+        _ => {}
+    }
+    manifest.check_source()?;
+    let matcher = manifest.filter.clone().map(|filter| filter.compile()).transpose()?;
+
+    let mut entries = cached_entries(&manifest.source, &state).await?;
+    if let Some(matcher) = &matcher {
+        entries.retain(|entry| matcher.matches(entry));
+    }
+    info!("🔁 续传导出任务 {}: 已写入 {}/{} 条", job_id, manifest.written_entries(), manifest.total_entries);
+
+    spawn_export_job(manifest.clone(), entries, window, &state).await?;
+    Ok(manifest)
+}
+
+/// 列出导出任务（按更新时间倒序，上次运行中未完成的任务状态为 `interrupted`）
+#[tauri::command]
+async fn list_export_jobs(state: tauri::State<'_, AppState>) -> Result<Vec<ExportManifest>, String> {
+    state.export_jobs.list()
+}
+
+/// 删除导出任务的清单和未完成的临时文件（已完成的导出文件保留）
+#[tauri::command]
+async fn discard_export_job(job_id: String, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    info!("🗑️ 删除导出任务: {}", job_id);
+    state.export_jobs.discard(&job_id)
+}
+
+/// 导出选中日志行的分享片段
+///
+/// 把选中的行连同上下文渲染为独立的HTML或Markdown片段（带摘要表、元数据字段表和行号），
+/// 直接返回片段文本，由前端复制到剪贴板或保存，便于粘贴到issue或聊天工具中。
+///
+/// # 参数
+/// - `file_path`: 日志文件路径
+/// - `line_range`: 选中的行号范围 `[起始, 结束]`（含两端，可选）
+/// - `line_numbers`: 选中的条目行号（可选，与 `line_range` 合并）
+/// - `options`: 片段格式和上下文行数（可选，默认Markdown、前后各3行）
+/// - `state`: 应用状态，包含增量解析缓存
+///
+/// # Returns
+/// - `Ok(String)`: 片段文本
+/// - `Err(String)`: 未选中任何行、文件不可读或格式不支持时的错误信息
+#[tauri::command]
+async fn export_selection(
+    file_path: String,
+    line_range: Option<(usize, usize)>,
+    line_numbers: Option<Vec<usize>>,
+    options: Option<SnippetOptions>,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, String> {
+    let mut selected = line_numbers.unwrap_or_default();
+    if let Some((start, end)) = line_range {
+        selected.extend(start.min(end)..=start.max(end));
+    }
+    if selected.is_empty() {
+        return Err(Message::new("selection.empty").text());
+    }
+
+    let mut options = options.unwrap_or_default();
+    options.level_styles = state.config_service.lock().await.get_theme_config()?.level_styles;
+    // 只构造选中行及其上下文范围内的条目
+    let delta = refresh_cached_parse(&file_path, &state).await?;
+    let first_line = selected.iter().min().copied().unwrap_or(1);
+    let last_line = selected.iter().max().copied().unwrap_or(0);
+    let entries = state.parse_cache.entries_in_lines(&delta.file_path, first_line, last_line, options.context_lines)
+        .ok_or_else(|| Message::new("parse.result_unavailable").with("path", &file_path).text())?;
+    let source = std::path::Path::new(&file_path)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| file_path.clone());
+    info!("📋 导出分享片段: {} ({} 行, 格式: {})", source, selected.len(), options.format);
+    export::snippet::render_snippet(&entries, &selected, &source, &options)
+}
+
+/// 生成调查报告
+///
+/// 把错误聚类、时间线（生命周期节点）、异常区段（错误集中区段）以及固定条目和备注
+/// 填入模板，渲染为Markdown或HTML的事故复盘草稿，直接返回报告文本，由前端保存或复制。
+///
+/// # 参数
+/// - `file_path`: 日志文件路径
+/// - `session_id`: 书签所属的会话（可选，不指定时使用文件自身的固定列表）
+/// - `options`: 报告格式、标题、自定义模板和错误聚类条数（可选，默认Markdown内置模板）
+/// - `state`: 应用状态，包含配置服务和增量解析缓存
+///
+/// # Returns
+/// - `Ok(String)`: 报告文本
+/// - `Err(String)`: 文件不可读、格式不支持或模板中有未知占位符时的错误信息
+#[tauri::command]
+async fn generate_report(
+    file_path: String,
+    session_id: Option<String>,
+    options: Option<ReportOptions>,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, String> {
+    let options = options.unwrap_or_default();
+    let entries = cached_entries(&file_path, &state).await?;
+    let bookmarks = state.config_service.lock().await.get_pinned_entries(&file_path, session_id.as_deref())?;
+    let source = std::path::Path::new(&file_path)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| file_path.clone());
+    info!("📝 生成调查报告: {} ({} 条, {} 个书签, 格式: {})", source, entries.len(), bookmarks.len(), options.format);
+    export::report::generate_report(&entries, &bookmarks, &source, &options)
+}
+
+/// 列出已配置的issue跟踪系统
+#[tauri::command]
+async fn list_issue_trackers(state: tauri::State<'_, AppState>) -> Result<Vec<IssueTracker>, String> {
+    state.config_service.lock().await.get_issue_trackers()
+}
+
+/// 添加或更新issue跟踪系统（按名称）
+///
+/// # 参数
+/// - `tracker`: 类型（github/jira）、服务地址、项目、存放令牌的环境变量名等
+/// - `state`: 应用状态，包含配置服务
+///
+/// # Returns
+/// - `Ok(Vec<IssueTracker>)`: 更新后的issue跟踪系统列表
+/// - `Err(String)`: 配置无效或保存失败时的错误信息
+#[tauri::command]
+async fn save_issue_tracker(tracker: IssueTracker, state: tauri::State<'_, AppState>) -> Result<Vec<IssueTracker>, String> {
+    tracker.validate()?;
+    info!("🎫 保存issue跟踪系统: {} ({})", tracker.name, tracker.base_url());
+
+    let mut config_service = state.config_service.lock().await;
+    let mut trackers = config_service.get_issue_trackers()?;
+    config::issues::upsert_tracker(&mut trackers, tracker);
+    config_service.set_issue_trackers(&trackers)?;
+    Ok(trackers)
+}
+
+/// 删除issue跟踪系统
+#[tauri::command]
+async fn remove_issue_tracker(name: String, state: tauri::State<'_, AppState>) -> Result<Vec<IssueTracker>, String> {
+    let mut config_service = state.config_service.lock().await;
+    let mut trackers = config_service.get_issue_trackers()?;
+    trackers.retain(|tracker| tracker.name != name);
+    config_service.set_issue_trackers(&trackers)?;
+    Ok(trackers)
+}
+
+/// 合并选中的行号范围和行号列表
+fn selected_lines(line_range: Option<(usize, usize)>, line_numbers: Option<Vec<usize>>) -> Result<Vec<usize>, String> {
+    let mut selected = line_numbers.unwrap_or_default();
+    if let Some((start, end)) = line_range {
+        selected.extend(start.min(end)..=start.max(end));
+    }
+    if selected.is_empty() {
+        return Err(Message::new("selection.empty").text());
+    }
+    Ok(selected)
+}
+
+/// 由选中的日志行生成脱敏后的issue草稿
+///
+/// 正文为选中行及上下文的Markdown片段，后接文件的分析概要（级别分布、时间范围和错误聚类），
+/// 全部内容经过脱敏（口令、令牌、邮箱、IP等）。
+async fn issue_draft(
+    file_path: &str,
+    line_range: Option<(usize, usize)>,
+    line_numbers: Option<Vec<usize>>,
+    title: Option<String>,
+    state: &AppState,
+) -> Result<IssueDraft, String> {
+    use utils::redact::{redact_entry, redact_text};
+
+    let selected = selected_lines(line_range, line_numbers)?;
+    let entries = cached_entries(file_path, state).await?;
+    let options = SnippetOptions::default();
+    let first_line = selected.iter().min().copied().unwrap_or(1);
+    let last_line = selected.iter().max().copied().unwrap_or(0);
+    let nearby: Vec<LogEntry> = entries.iter()
+        .filter(|entry| entry.line_number + options.context_lines >= first_line && entry.line_number <= last_line + options.context_lines)
+        .map(redact_entry)
+        .collect();
+    let source = std::path::Path::new(file_path)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| file_path.to_string());
+    let snippet = export::snippet::render_snippet(&nearby, &selected, &source, &options)?;
+
+    let summary_options = ReportOptions {
+        template: Some("{{summary}}\n\n{{error_clusters}}".to_string()),
+        ..Default::default()
+    };
+    let summary = redact_text(&export::report::generate_report(&entries, &[], &source, &summary_options)?);
+
+    let title = title.filter(|title| !title.trim().is_empty()).unwrap_or_else(|| {
+        let first = nearby.iter().find(|entry| entry.line_number == first_line);
+        let text = first.map_or("", |entry| entry.message_text()).lines().next().unwrap_or("").trim();
+        truncate_chars(&format!("[{}] {}", source, text), 120)
+    });
+    Ok(IssueDraft { title, body: issues::compose_body(&snippet, &summary) })
+}
+
+/// 预览由选中日志行生成的issue（不发送）
+///
+/// # 参数
+/// - `file_path`: 日志文件路径
+/// - `line_range`: 选中的行号范围 `[起始, 结束]`（含两端，可选）
+/// - `line_numbers`: 选中的条目行号（可选，与 `line_range` 合并）
+/// - `title`: issue标题（可选，默认取第一条选中条目的消息）
+/// - `state`: 应用状态，包含增量解析缓存
+///
+/// # Returns
+/// - `Ok(IssueDraft)`: 脱敏后的标题和正文
+/// - `Err(String)`: 未选中任何行或文件不可读时的错误信息
+#[tauri::command]
+async fn preview_issue_from_selection(
+    file_path: String,
+    line_range: Option<(usize, usize)>,
+    line_numbers: Option<Vec<usize>>,
+    title: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<IssueDraft, String> {
+    issue_draft(&file_path, line_range, line_numbers, title, &state).await
+}
+
+/// 由选中的日志行在GitHub或Jira中创建issue
+///
+/// 正文与 `preview_issue_from_selection` 相同（脱敏后的片段和分析概要），
+/// 访问令牌从跟踪系统配置的环境变量读取。
+///
+/// # 参数
+/// - `tracker`: issue跟踪系统名称
+/// - `file_path`: 日志文件路径
+/// - `line_range`: 选中的行号范围 `[起始, 结束]`（含两端，可选）
+/// - `line_numbers`: 选中的条目行号（可选，与 `line_range` 合并）
+/// - `title`: issue标题（可选，默认取第一条选中条目的消息）
+/// - `state`: 应用状态，包含配置服务和增量解析缓存
+///
+/// # Returns
+/// - `Ok(CreatedIssue)`: issue编号和链接
+/// - `Err(String)`: 跟踪系统未配置、令牌缺失、未选中任何行或创建失败时的错误信息
+#[tauri::command]
+async fn create_issue_from_selection(
+    tracker: String,
+    file_path: String,
+    line_range: Option<(usize, usize)>,
+    line_numbers: Option<Vec<usize>>,
+    title: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<CreatedIssue, String> {
+    let tracker = state.config_service.lock().await.get_issue_trackers()?
+        .into_iter()
+        .find(|t| t.name == tracker)
+        .ok_or_else(|| Message::new("issue.unknown_tracker").with("tracker", &tracker).text())?;
+    let token = issues::read_token(&tracker)?;
+    let draft = issue_draft(&file_path, line_range, line_numbers, title, &state).await?;
+
+    info!("🎫 在 {} 中创建issue: {}", tracker.name, draft.title);
+    let created = tokio::task::spawn_blocking(move || issues::create_issue(&tracker, &token, &draft))
+        .await
+        .map_err(|e| Message::new("task.issue_failed").with("error", e).text())??;
+    info!("✅ 已创建issue {}: {}", created.key, created.url);
+    Ok(created)
+}
+
+/// 获取AI摘要设置
+#[tauri::command]
+async fn get_summarizer_settings(state: tauri::State<'_, AppState>) -> Result<SummarizerSettings, String> {
+    state.config_service.lock().await.get_summarizer_settings()
+}
+
+/// 保存AI摘要设置
+///
+/// # 参数
+/// - `settings`: 服务类型（`ollama` / `openai`）、服务地址、模型、API密钥环境变量名和分批参数
+/// - `state`: 应用状态，包含配置服务
+///
+/// # Returns
+/// - `Ok(SummarizerSettings)`: 保存的设置
+/// - `Err(String)`: 设置无效或保存失败时的错误信息
+#[tauri::command]
+async fn set_summarizer_settings(settings: SummarizerSettings, state: tauri::State<'_, AppState>) -> Result<SummarizerSettings, String> {
+    settings.validate()?;
+    info!("🤖 保存AI摘要设置: {:?} {} ({})", settings.provider, settings.model, settings.base_url());
+    state.config_service.lock().await.set_summarizer_settings(&settings)?;
+    Ok(settings)
+}
+
+/// 用大模型摘要选中的日志条目
+///
+/// 条目经过脱敏（口令、令牌、邮箱、IP等）后按 `batch_chars` 分批发送到配置的
+/// 本地Ollama或OpenAI兼容服务；多批时先逐批摘要再合并。需要先在设置中启用。
+///
+/// # 参数
+/// - `file_path`: 日志文件路径
+/// - `line_range`: 选中的行号范围 `[起始, 结束]`（含两端，可选）
+/// - `line_numbers`: 选中的条目行号（可选，与 `line_range` 合并）
+/// - `question`: 希望模型重点回答的问题（可选）
+/// - `state`: 应用状态，包含配置服务和增量解析缓存
+///
+/// # Returns
+/// - `Ok(EntrySummary)`: 摘要文本、模型和分批信息
+/// - `Err(String)`: 未启用、未选中任何行或请求失败时的错误信息
+#[tauri::command]
+async fn summarize_entries(
+    file_path: String,
+    line_range: Option<(usize, usize)>,
+    line_numbers: Option<Vec<usize>>,
+    question: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<EntrySummary, String> {
+    let settings = state.config_service.lock().await.get_summarizer_settings()?;
+    if !settings.enabled {
+        return Err(Message::new("summary.disabled").text());
+    }
+    let api_key = match settings.api_key_env.as_deref().filter(|name| !name.trim().is_empty()) {
+        Some(name) => Some(
+            std::env::var(name)
+                .ok()
+                .filter(|key| !key.trim().is_empty())
+                .ok_or_else(|| Message::new("summary.missing_api_key").with("name", name).text())?,
+        ),
+        None => None,
+    };
+
+    let selected: std::collections::HashSet<usize> = selected_lines(line_range, line_numbers)?.into_iter().collect();
+    let entries: Vec<LogEntry> = cached_entries(&file_path, &state).await?
+        .into_iter()
+        .filter(|entry| selected.contains(&entry.line_number))
+        .collect();
+
+    info!("🤖 使用 {} 摘要 {} 的 {} 条日志", settings.model, file_path, entries.len());
+    let summary = tokio::task::spawn_blocking(move || {
+        summarizer::summarize(&settings, api_key.as_deref(), &entries, question.as_deref())
+    })
+    .await
+    .map_err(|e| Message::new("task.summary_failed").with("error", e).text())??;
+    info!("✅ 摘要完成: {} 条日志，{} 批", summary.entries, summary.batches);
+    Ok(summary)
+}
+
+/// 列出用户添加的错误解释规则（不含内置规则）
+#[tauri::command]
+async fn list_explain_rules(state: tauri::State<'_, AppState>) -> Result<Vec<ExplainRule>, String> {
+    state.config_service.lock().await.get_explain_rules()
+}
+
+/// 添加或更新用户错误解释规则（按ID）
+///
+/// 与内置规则ID相同时覆盖内置规则。
+///
+/// # 参数
+/// - `rule`: 规则ID、标题、匹配规则（正则，不区分大小写）、解释和处理建议
+/// - `state`: 应用状态，包含配置服务
+///
+/// # Returns
+/// - `Ok(Vec<ExplainRule>)`: 更新后的用户规则列表
+/// - `Err(String)`: 规则无效或保存失败时的错误信息
+#[tauri::command]
+async fn save_explain_rule(rule: ExplainRule, state: tauri::State<'_, AppState>) -> Result<Vec<ExplainRule>, String> {
+    rule.validate()?;
+    info!("💡 保存错误解释规则: {} ({})", rule.id, rule.title);
+
+    let mut config_service = state.config_service.lock().await;
+    let mut rules = config_service.get_explain_rules()?;
+    config::explain::upsert_rule(&mut rules, rule);
+    config_service.set_explain_rules(&rules)?;
+    Ok(rules)
+}
+
+/// 删除用户错误解释规则
+#[tauri::command]
+async fn remove_explain_rule(id: String, state: tauri::State<'_, AppState>) -> Result<Vec<ExplainRule>, String> {
+    let mut config_service = state.config_service.lock().await;
+    let mut rules = config_service.get_explain_rules()?;
+    rules.retain(|rule| rule.id != id);
+    config_service.set_explain_rules(&rules)?;
+    Ok(rules)
+}
+
+/// 用错误知识库解释文件中的错误
+///
+/// 用内置知识库和用户规则匹配条目内容（如 `OutOfMemoryError`、`Connection refused`、TLS握手失败），
+/// 按规则汇总命中的条目，并附上原因解释和处理建议。完全离线运行。
 ///
 /// # 参数
-/// - `path`: 要写入的文件路径
-/// - `contents`: 要写入的文件内容
+/// - `file_path`: 日志文件路径
+/// - `state`: 应用状态，包含配置服务和增量解析缓存
 ///
 /// # Returns
-/// - `Ok(())`: 写入成功的确认
-/// - `Err(String)`: 写入失败时的详细错误信息
+/// - `Ok(Vec<ErrorExplanation>)`: 按命中条目数降序排列的解释，每条附带示例条目
+/// - `Err(String)`: 文件不可读或用户规则无效时的错误信息
+#[tauri::command]
+async fn explain_errors(file_path: String, state: tauri::State<'_, AppState>) -> Result<Vec<ErrorExplanation>, String> {
+    let user_rules = state.config_service.lock().await.get_explain_rules()?;
+    let entries = cached_entries(&file_path, &state).await?;
+    let explanations = tokio::task::spawn_blocking(move || explain::explain_errors(&entries, &user_rules))
+        .await
+        .map_err(|e| Message::new("task.explain_failed").with("error", e).text())??;
+    info!("💡 文件 {} 中有 {} 类已知错误", file_path, explanations.len());
+    Ok(explanations)
+}
+
+/// 弹出选择目录对话框，并把用户选中的目录加入可信写入目录
 ///
-/// # 错误处理
-/// - 磁盘空间不足
-/// - 权限不足
-/// - 目录不存在（自动创建）
-/// - 文件被占用
+/// 对话框由后端弹出，只有用户在对话框中实际选中的目录才会被信任，
+/// 前端不能直接指定要信任的路径。之后写入该目录（及其子目录）不再需要确认，仅在本次运行期间有效。
 ///
-/// # 安全考虑
-/// - 路径验证：确保写入路径安全
-/// - 权限检查：验证写入权限
-/// - 备份策略：重要文件建议先备份
+/// # 参数
+/// - `window`: 对话框的父窗口
+/// - `title`: 对话框标题（可选）
+/// - `default_path`: 对话框的初始目录（可选）
+/// - `state`: 应用状态，包含写入守卫
+///
+/// # Returns
+/// - `Ok(Some(String))`: 规范化后的目录路径
+/// - `Ok(None)`: 用户取消了选择
 #[tauri::command]
-async fn write_file(path: String, contents: String) -> Result<(), String> {
-    info!("💾 请求写入文件: {} (大小: {} bytes)", path, contents.len());
+async fn choose_write_directory(
+    window: tauri::Window,
+    title: Option<String>,
+    default_path: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Option<String>, String> {
+    let picked = tokio::task::spawn_blocking(move || {
+        let mut dialog = tauri::api::dialog::blocking::FileDialogBuilder::new().set_parent(&window);
+        if let Some(title) = &title {
+            dialog = dialog.set_title(title);
+        }
+        if let Some(path) = &default_path {
+            dialog = dialog.set_directory(path);
+        }
+        dialog.pick_folder()
+    })
+    .await
+    .map_err(|e| Message::new("dialog.failed").with("error", e).text())?;
 
-    // 路径安全验证
-    let path_obj = std::path::Path::new(&path);
+    let Some(dir) = picked else {
+        return Ok(None);
+    };
+    let dir = state.write_guard.allow_directory(&dir)?;
+    Ok(Some(dir.to_string_lossy().into_owned()))
+}
 
-    // 确保父目录存在，如果不存在则创建
-    if let Some(parent) = path_obj.parent() {
-        if !parent.exists() {
-            debug!("📁 创建目录结构: {}", parent.display());
-            match std::fs::create_dir_all(parent) {
-                Ok(_) => {
-                    info!("✅ 目录创建成功: {}", parent.display());
-                }
-                Err(e) => {
-                    error!("❌ 创建目录失败: {} - 错误: {}", parent.display(), e);
-                    return Err(format!("创建目录失败: {}", e));
-                }
-            }
+/// 弹出保存文件对话框，并把所选文件所在的目录加入可信写入目录
+///
+/// 与 `choose_write_directory` 相同，只信任用户在后端弹出的对话框中选中的位置，
+/// 返回的路径可以直接交给 `write_file` 等命令写入而不需要确认。
+///
+/// # 参数
+/// - `window`: 对话框的父窗口
+/// - `default_name`: 默认文件名（可选）
+/// - `default_path`: 对话框的初始目录（可选）
+/// - `extensions`: 允许的扩展名（可选，如 `["log", "txt"]`）
+/// - `state`: 应用状态，包含写入守卫
+///
+/// # Returns
+/// - `Ok(Some(String))`: 用户选择的文件路径
+/// - `Ok(None)`: 用户取消了保存
+#[tauri::command]
+async fn choose_save_path(
+    window: tauri::Window,
+    default_name: Option<String>,
+    default_path: Option<String>,
+    extensions: Option<Vec<String>>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Option<String>, String> {
+    let picked = tokio::task::spawn_blocking(move || {
+        let mut dialog = tauri::api::dialog::blocking::FileDialogBuilder::new().set_parent(&window);
+        if let Some(name) = &default_name {
+            dialog = dialog.set_file_name(name);
         }
-    }
-
-    // 尝试写入文件内容
-    let content_len = contents.len(); // 先保存长度，避免所有权转移
-    match std::fs::write(&path, contents) {
-        Ok(_) => {
-            info!("✅ 文件写入成功: {} (大小: {} bytes)", path, content_len);
-            debug!("💾 文件详情: 大小={} bytes, 路径={}",
-                  std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0),
-                  path);
-            Ok(())
+        if let Some(path) = &default_path {
+            dialog = dialog.set_directory(path);
         }
-        Err(e) => {
-            error!("❌ 写入文件失败: {} - 错误: {}", path, e);
-            Err(format!("写入文件失败: {}", e))
+        if let Some(extensions) = extensions.filter(|extensions| !extensions.is_empty()) {
+            let extensions: Vec<&str> = extensions.iter().map(String::as_str).collect();
+            dialog = dialog.add_filter(extensions.join(", "), &extensions);
         }
+        dialog.save_file()
+    })
+    .await
+    .map_err(|e| Message::new("dialog.failed").with("error", e).text())?;
+
+    let Some(path) = picked else {
+        return Ok(None);
+    };
+    if let Some(parent) = path.parent() {
+        state.write_guard.allow_directory(parent)?;
     }
+    Ok(Some(path.to_string_lossy().into_owned()))
 }
 
-
+/// 获取可信写入目录列表
+#[tauri::command]
+async fn get_write_directories(state: tauri::State<'_, AppState>) -> Result<Vec<String>, String> {
+    Ok(state.write_guard.allowed_directories()
+        .iter()
+        .map(|dir| dir.to_string_lossy().into_owned())
+        .collect())
+}
 
 
 // ============================================================================
 // 数据结构定义
 // ============================================================================
 
-/// 日志解析请求结构
-///
-/// 定义了前端向后端发起日志解析请求的完整参数结构。
-/// 支持文件路径和内容直接传输两种模式。
-///
-/// # 字段说明
-/// - file_path: 日志文件的路径（可选）
-/// - content: 直接传入的日志内容（可选）
-/// - plugin: 指定使用的解析插件（可选，不指定则自动检测）
-/// - chunk_size: 分块处理时的块大小（可选，默认1000行）
-/// - chunk_index: 当前请求的块索引（可选，用于分块处理）
-///
-/// # 使用模式
-/// 1. 文件模式：提供file_path，后端读取文件内容
-/// 2. 内容模式：提供content，后端直接处理传入内容
-/// 3. 分块模式：设置chunk_size和chunk_index，用于大文件处理
-#[derive(Debug, Serialize, Deserialize)]
-struct ParseRequest {
-    /// 日志文件路径（绝对路径或相对路径）
-    #[serde(default)]
-    file_path: Option<String>,
-
-    /// 直接传入的日志内容（UTF-8编码）
-    #[serde(default)]
-    content: Option<String>,
-
-    /// 指定使用的解析插件名称（如"springboot", "docker_json"等）
-    #[serde(default)]
-    plugin: Option<String>,
-
-    /// 分块处理时的块大小（行数，默认1000）
-    #[serde(default)]
-    chunk_size: Option<usize>,
-
-    /// 当前请求的块索引（从0开始，用于分块处理）
-    #[serde(default)]
-    chunk_index: Option<usize>,
-}
-
 /// 日志解析响应结构
 ///
 /// 包含日志解析的完整结果，包括解析的日志条目、统计信息和错误状态。
@@ -1264,8 +5352,10 @@ struct ParseRequest {
 /// - entries: 解析后的日志条目列表
 /// - stats: 解析统计信息（行数、耗时等）
 /// - chunk_info: 分块处理信息（仅在分块模式时有值）
-/// - error: 错误信息（仅在出错时有值）
+/// - error: 错误信息（按当前语言本地化，仅在出错时有值）
+/// - error_detail: 错误的消息代码和参数（仅在出错时有值，供前端自行本地化）
 /// - detected_format: 自动检测到的日志格式
+/// - capabilities: 插件能力协商结果（插件链模式）
 ///
 /// # 响应类型
 /// 1. 成功响应：success=true，包含entries和stats
@@ -1288,8 +5378,20 @@ struct ParseResponse {
     /// 错误信息（解析失败时提供详细错误描述）
     error: Option<String>,
 
+    /// 错误的消息代码和参数（解析失败时提供）
+    #[serde(default)]
+    error_detail: Option<Message>,
+
     /// 自动检测到的日志格式（如"SpringBoot", "DockerJson"等）
     detected_format: Option<String>,
+
+    /// 插件能力协商结果（选中的插件链、因文件过大跳过的链和超出上限的警告）
+    #[serde(default)]
+    capabilities: Option<CapabilityDecision>,
+
+    /// 该文件的固定条目（不受分块影响，始终返回完整列表）
+    #[serde(default)]
+    pinned: Vec<PinnedEntry>,
 }
 
 /// 分块信息结构
@@ -1301,6 +5403,9 @@ struct ParseResponse {
 /// - total_chunks: 总分块数量
 /// - current_chunk: 当前块的索引（从0开始）
 /// - has_more: 是否还有后续块需要处理
+/// - chunk_size: 本块的行数（自适应分块时由后端选择）
+/// - start_line: 本块第一行的序号（非空行计数，从1开始）
+/// - budget_bytes: 自适应分块时本块的字节预算
 ///
 /// # 使用场景
 /// - 大文件分块加载的进度显示
@@ -1308,7 +5413,7 @@ struct ParseResponse {
 /// - 分块完成状态的判断
 #[derive(Debug, Serialize, Deserialize)]
 struct ChunkInfo {
-    /// 总分块数量（向上取整）
+    /// 总分块数量（向上取整；自适应分块时为按剩余内容估算的值）
     total_chunks: usize,
 
     /// 当前处理的块索引（从0开始）
@@ -1316,53 +5421,17 @@ struct ChunkInfo {
 
     /// 是否还有后续块需要处理
     has_more: bool,
-}
-
-/// 日志条目结构
-///
-/// 表示解析后的单个日志条目，包含原始内容和解析后的结构化信息。
-/// 这是日志解析的核心数据结构，支持多种日志格式的统一表示。
-///
-/// # 字段说明
-/// - line_number: 在原文件中的行号（从1开始）
-/// - content: 原始日志内容
-/// - timestamp: 解析出的时间戳（可选）
-/// - level: 日志级别（如INFO, ERROR, WARN等）
-/// - formatted_content: 格式化后的显示内容
-/// - metadata: 附加元数据（键值对形式）
-/// - processed_by: 处理此条目的插件列表
-///
-/// # 解析增强
-/// - 时间戳提取和标准化
-/// - 日志级别识别和分类
-/// - 内容格式化和高亮
-/// - 元数据提取（如线程ID、类名等）
-/// - 处理链追踪
-#[derive(Debug, Serialize, Deserialize)]
-struct LogEntry {
-    /// 在原日志文件中的行号（从1开始）
-    line_number: usize,
-
-    /// 原始日志内容（保持不变）
-    content: String,
 
-    /// 解析出的时间戳（ISO 8601格式或原始格式）
-    timestamp: Option<String>,
+    /// 本块的行数
+    chunk_size: usize,
 
-    /// 日志级别（INFO, ERROR, WARN, DEBUG, TRACE等）
-    level: Option<String>,
+    /// 本块第一行的序号（非空行计数，从1开始）
+    start_line: usize,
 
-    /// 格式化后的显示内容（可能包含高亮、结构化信息）
-    formatted_content: Option<String>,
-
-    /// 附加元数据（如线程ID、类名、方法名等）
-    metadata: std::collections::HashMap<String, String>,
-
-    /// 处理此条目的插件名称列表（用于追踪处理链）
-    processed_by: Vec<String>,
+    /// 自适应分块时本块的字节预算（固定行数分块时为None）
+    budget_bytes: Option<usize>,
 }
 
-
 /// 解析统计信息结构
 ///
 /// 包含日志解析过程的性能和结果统计数据。
@@ -1372,6 +5441,7 @@ struct LogEntry {
 /// - total_lines: 原始日志文件的总行数
 /// - success_lines: 成功解析的行数
 /// - error_lines: 解析失败的行数
+/// - error_line_numbers: 解析失败的行号
 /// - parse_time_ms: 解析耗时（毫秒）
 ///
 /// # 性能指标
@@ -1386,38 +5456,37 @@ struct ParseStats {
     /// 成功解析并处理的行数
     success_lines: usize,
 
-    /// 解析失败或出错的行数
+    /// 解析失败或出错的行数（插件在条目上标记了 `parse_error`）
     error_lines: usize,
 
+    /// 解析失败的行号（最多 `MAX_ERROR_LINE_NUMBERS` 个），界面据此跳转到解析器无法处理的行
+    #[serde(default)]
+    error_line_numbers: Vec<usize>,
+
     /// 解析过程的总耗时（毫秒）
     parse_time_ms: u64,
 }
 
-/// 插件信息结构
-///
-/// 描述单个日志解析插件的基本信息。
-/// 用于前端展示可用插件列表和插件选择界面。
-///
-/// # 字段说明
-/// - name: 插件的唯一标识符（用于API调用）
-/// - description: 插件功能描述（面向用户的说明）
-/// - version: 插件版本号（用于兼容性检查）
-///
-/// # 插件类型
-/// - auto: 自动格式检测插件
-/// - mybatis: MyBatis SQL日志解析插件
-/// - docker_json: Docker容器日志解析插件
-/// - raw: 原始文本日志解析插件
-#[derive(Debug, Serialize, Deserialize)]
-struct Plugin {
-    /// 插件的唯一名称标识符
-    name: String,
-
-    /// 插件功能的用户友好描述
-    description: String,
+/// 统计中最多返回的错误行号数（`error_lines` 仍为完整计数）
+const MAX_ERROR_LINE_NUMBERS: usize = 1000;
 
-    /// 插件版本号（语义化版本）
-    version: String,
+impl ParseStats {
+    /// 按解析结果计算统计，标记了解析失败的条目计为错误行
+    ///
+    /// # 参数
+    /// - `total_lines`: 非空行总数
+    /// - `entries`: 解析后的条目
+    /// - `parse_time_ms`: 解析耗时（毫秒）
+    fn from_entries(total_lines: usize, entries: &[LogEntry], parse_time_ms: u64) -> Self {
+        let errors = plugins::chain::line_parse_errors(entries);
+        Self {
+            total_lines,
+            success_lines: entries.len() - errors.len(),
+            error_lines: errors.len(),
+            error_line_numbers: errors.iter().take(MAX_ERROR_LINE_NUMBERS).map(|(line_number, _)| *line_number).collect(),
+            parse_time_ms,
+        }
+    }
 }
 
 /// 插件列表响应结构
@@ -1427,7 +5496,7 @@ struct Plugin {
 #[derive(Debug, Serialize, Deserialize)]
 struct PluginsResponse {
     /// 可用插件列表
-    plugins: Vec<Plugin>,
+    plugins: Vec<PluginInfo>,
 }
 
 /// 健康检查响应结构
@@ -1439,6 +5508,7 @@ struct PluginsResponse {
 /// - status: 应用状态（"ok"表示正常）
 /// - version: 应用程序版本号
 /// - timestamp: 响应生成时间（ISO 8601格式）
+/// - runtime: 运行时统计（吞吐量、缓存命中率、缓存占用、跟踪数）
 #[derive(Debug, Serialize, Deserialize)]
 struct HealthResponse {
     /// 应用运行状态
@@ -1449,6 +5519,9 @@ struct HealthResponse {
 
     /// 响应生成的时间戳（UTC）
     timestamp: String,
+
+    /// 运行时统计
+    runtime: RuntimeSnapshot,
 }
 
 /// 主题配置响应结构
@@ -1458,15 +5531,20 @@ struct HealthResponse {
 ///
 /// # 字段说明
 /// - mode: 主题模式（"light", "dark", "auto"）
+/// - effective: 实际生效的明暗主题（"light", "dark"）
 /// - primary_color: 主色调（十六进制颜色值）
 /// - accent_color: 强调色（十六进制颜色值）
 /// - font_size: 基础字体大小（像素）
 /// - font_family: 字体族名称
+/// - level_styles: 各级别的颜色和默认可见性
 #[derive(Debug, Serialize, Deserialize)]
 struct ThemeResponse {
     /// 主题模式（light/dark/auto）
     mode: String,
 
+    /// 实际生效的明暗主题（Auto模式下跟随系统）
+    effective: EffectiveTheme,
+
     /// 主色调（如"#3b82f6"）
     primary_color: String,
 
@@ -1478,6 +5556,20 @@ struct ThemeResponse {
 
     /// 字体族名称（如"Inter", "Roboto"等）
     font_family: String,
+
+    /// 各级别的颜色和默认可见性（键为大写级别名）
+    #[serde(default)]
+    level_styles: std::collections::BTreeMap<String, LevelStyle>,
+}
+
+/// 级别样式响应结构
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct LevelStylesResponse {
+    /// 各级别的颜色和默认可见性（键为大写级别名）
+    styles: std::collections::BTreeMap<String, LevelStyle>,
+
+    /// 默认隐藏的级别
+    hidden_levels: Vec<String>,
 }
 
 /// 主题配置更新请求结构
@@ -1521,9 +5613,10 @@ struct ThemeUpdateRequest {
 /// # 字段说明
 /// - file_path: 文件路径
 /// - file_size: 文件大小（字节）
-/// - total_lines: 总行数（估算值）
+/// - total_lines: 总行数（指定扫描上限时为估算值）
 /// - recommended_chunk_size: 推荐的分块大小
 /// - is_large_file: 是否为大文件
+/// - lines_estimated: 行数是否为估算值
 #[derive(Debug, Serialize, Deserialize)]
 struct FileInfoResponse {
     /// 文件路径
@@ -1532,7 +5625,7 @@ struct FileInfoResponse {
     /// 文件大小（字节）
     file_size: u64,
 
-    /// 总行数（大文件为估算值）
+    /// 总行数（指定扫描上限时为估算值）
     total_lines: usize,
 
     /// 推荐的分块大小（行数）
@@ -1540,6 +5633,160 @@ struct FileInfoResponse {
 
     /// 是否为大文件（>50MB）
     is_large_file: bool,
+
+    /// 行数是否为按采样推算的估算值
+    lines_estimated: bool,
+}
+
+/// 文件写入响应结构
+///
+/// # 字段说明
+/// - written: 是否已写入
+/// - path: 规范化后的目标路径
+/// - bytes: 写入的字节数
+/// - confirmation: 需要用户确认时的确认请求
+#[derive(Debug, Serialize, Deserialize)]
+struct WriteFileResponse {
+    /// 是否已写入
+    written: bool,
+
+    /// 规范化后的目标路径
+    path: String,
+
+    /// 写入的字节数
+    bytes: usize,
+
+    /// 需要用户确认时的确认请求（携带其中的令牌再次调用 write_file）
+    confirmation: Option<WriteConfirmation>,
+}
+
+/// 日志导出响应结构
+///
+/// # 字段说明
+/// - written: 是否已写入
+/// - path: 规范化后的目标路径
+/// - bytes: 写入的字节数
+/// - entries: 导出的条目数
+/// - lossy: 是否有字符无法用目标编码表示（GBK下替换为字符引用）
+/// - confirmation: 需要用户确认时的确认请求
+#[derive(Debug, Serialize, Deserialize)]
+struct ExportResponse {
+    /// 是否已写入
+    written: bool,
+
+    /// 规范化后的目标路径
+    path: String,
+
+    /// 写入的字节数
+    bytes: usize,
+
+    /// 导出的条目数（导出共享配置时为解析配置数）
+    entries: usize,
+
+    /// 是否有字符无法用目标编码表示
+    lossy: bool,
+
+    /// 需要用户确认时的确认请求（携带其中的令牌再次调用 export_logs）
+    confirmation: Option<WriteConfirmation>,
+}
+
+/// 解析预览响应结构
+///
+/// # 字段说明
+/// - lines_read: 实际读取的行数（包括空行）
+/// - has_more: 文件在预览范围之后是否还有内容
+/// - detected_format: 选中的插件链或解析器
+/// - applied_filters: 实际执行的过滤器（插件链模式）
+/// - entries: 预览范围内的解析条目
+/// - parsing_errors: 解析过程中的错误
+#[derive(Debug, Serialize, Deserialize)]
+struct PreviewParseResponse {
+    /// 文件路径
+    file_path: String,
+
+    /// 实际读取的行数
+    lines_read: usize,
+
+    /// 预览范围之后是否还有内容
+    has_more: bool,
+
+    /// 选中的插件链或解析器
+    detected_format: Option<String>,
+
+    /// 实际执行的过滤器，按执行顺序
+    applied_filters: Vec<String>,
+
+    /// 解析条目
+    entries: Vec<LogEntry>,
+
+    /// 解析过程中的错误
+    parsing_errors: Vec<String>,
+}
+
+/// 文件行数统计进度事件（`file-info-progress`）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct FileInfoProgress {
+    /// 文件路径
+    file_path: String,
+
+    /// 已扫描的字节数
+    bytes_scanned: u64,
+
+    /// 文件总字节数
+    total_bytes: u64,
+
+    /// 目前已统计到的行数
+    lines_counted: usize,
+}
+
+/// 上下文窗口中的单个原始行
+#[derive(Debug, Serialize, Deserialize)]
+struct ContextLine {
+    /// 在原文件中的行号（从1开始，包括空行）
+    line_number: usize,
+
+    /// 原始行内容（不含换行符）
+    raw: String,
+
+    /// 是否为请求的目标行
+    is_target: bool,
+}
+
+/// 上下文查询响应结构
+///
+/// 包含目标行周围窗口的原始行以及插件链解析后的条目。
+///
+/// # 字段说明
+/// - target_line: 请求的目标行号
+/// - start_line / end_line: 实际返回的行区间（闭区间，已按文件边界截断）
+/// - total_lines: 文件总行数（来自行索引）
+/// - lines: 窗口内的原始行
+/// - entries: 窗口内容的解析结果，行号已映射回原文件
+#[derive(Debug, Serialize, Deserialize)]
+struct ContextResponse {
+    /// 文件路径
+    file_path: String,
+
+    /// 目标行号
+    target_line: usize,
+
+    /// 窗口起始行号（包含）
+    start_line: usize,
+
+    /// 窗口结束行号（包含）
+    end_line: usize,
+
+    /// 文件总行数
+    total_lines: usize,
+
+    /// 窗口内的原始行
+    lines: Vec<ContextLine>,
+
+    /// 窗口内容的解析条目
+    entries: Vec<LogEntry>,
+
+    /// 窗口内容检测到的日志格式
+    detected_format: Option<String>,
 }
 
 // ============================================================================
@@ -1564,176 +5811,325 @@ static TIMESTAMP_PATTERNS: Lazy<Vec<Regex>> = Lazy::new(|| {
 
 /// 使用插件系统处理日志条目
 ///
-/// 将前端日志条目格式转换为插件系统格式，通过插件处理后再转换回前端格式。
-/// 这个函数是前端LogEntry和插件系统PluginLogEntry之间的桥梁。
+/// 前端和插件系统共用 `models::LogEntry`，条目直接交给插件管理器批量处理，
+/// 无需在两种格式之间转换。
 ///
 /// # 处理流程
-/// 1. 格式转换：LogEntry -> PluginLogEntry
-/// 2. 插件处理：调用插件管理器进行日志解析
-/// 3. 结果转换：PluginLogEntry -> LogEntry
-/// 4. 性能监控：记录处理时间和结果统计
+/// 1. 插件处理：调用插件管理器进行日志解析
+/// 2. 性能监控：记录处理时间和结果统计
 ///
 /// # 参数
-/// - `entries`: 前端格式的日志条目数组
+/// - `entries`: 待处理的日志条目数组
 /// - `plugin_manager`: 增强插件管理器实例
 ///
 /// # Returns
-/// - `Ok(Vec<LogEntry>)`: 处理后的前端格式日志条目
+/// - `Ok(Vec<LogEntry>)`: 处理后的日志条目
 /// - `Err(String)`: 插件处理失败时的错误信息
 ///
 /// # 性能特性
 /// - 批量处理：一次性处理多个条目以提高效率
-/// - 内存优化：避免不必要的内存分配和拷贝
 /// - 错误隔离：插件失败不影响整个应用稳定性
 async fn process_logs_with_plugin_system(entries: &[LogEntry], plugin_manager: &Arc<EnhancedPluginManager>) -> Result<Vec<LogEntry>, String> {
     let start_time = std::time::Instant::now();
     info!("🔧 开始插件系统处理，输入条目数: {}", entries.len());
 
-    // 第一步：格式转换 LogEntry -> PluginLogEntry
-    // 这是前端数据格式和插件系统数据格式之间的适配层
-    debug!("📋 转换数据格式到插件系统格式");
-    let plugin_entries: Vec<PluginLogEntry> = entries.iter().map(|entry| {
-        PluginLogEntry {
-            line_number: entry.line_number,
-            content: entry.content.clone(),
-            timestamp: entry.timestamp.clone(),
-            level: entry.level.clone(),
-            formatted_content: entry.formatted_content.clone(),
-            metadata: std::collections::HashMap::new(), // 插件系统会重新构建元数据
-            processed_by: Vec::new(), // 插件系统会重新记录处理链
-        }
-    }).collect();
-
-    debug!("✅ 数据格式转换完成，条目数: {}", plugin_entries.len());
-
-    // 第二步：插件系统处理
-    // 调用增强插件管理器进行实际的日志解析和处理
-    debug!("🔄 调用插件管理器处理日志条目");
-    let process_start = std::time::Instant::now();
-    let result = plugin_manager.process_log_entries(plugin_entries).await
+    let result = plugin_manager.process_log_entries(entries.to_vec()).await
         .map_err(|e| {
             error!("❌ 插件系统处理失败: {}", e);
-            format!("插件处理失败: {}", e)
+            Message::new("parse.processing_failed").with("error", e).text()
         })?;
-    let process_time = process_start.elapsed();
-
-    info!("✅ 插件系统处理完成，输入: {} -> 输出: {} 条目，处理耗时: {}ms",
-          entries.len(), result.len(), process_time.as_millis());
-
-    // 第三步：结果转换 PluginLogEntry -> LogEntry
-    // 将插件系统处理结果转换回前端可用的格式
-    debug!("🔄 转换插件处理结果到前端格式");
-    let conversion_start = std::time::Instant::now();
-    let converted_entries: Vec<LogEntry> = result.into_iter().map(|entry| {
-        LogEntry {
-            line_number: entry.line_number,
-            content: entry.content,
-            timestamp: entry.timestamp,
-            level: entry.level,
-            formatted_content: entry.formatted_content,
-            metadata: entry.metadata,
-            processed_by: entry.processed_by,
-        }
-    }).collect();
-    let conversion_time = conversion_start.elapsed();
 
-    let total_time = start_time.elapsed();
-    info!("✅ 完整插件系统处理完成，最终条目数: {}，总耗时: {}ms (处理: {}ms, 转换: {}ms)",
-          converted_entries.len(), total_time.as_millis(), process_time.as_millis(), conversion_time.as_millis());
+    info!("✅ 插件系统处理完成，输入: {} -> 输出: {} 条目，耗时: {}ms",
+          entries.len(), result.len(), start_time.elapsed().as_millis());
 
-    Ok(converted_entries)
+    Ok(result)
 }
 
 /// 智能检测日志格式
 ///
-/// 通过分析日志行内容特征来自动识别日志格式类型。
+/// 通过对样本行做结构匹配为每种格式打分，得分最高的格式胜出。
 /// 支持识别常见的日志格式，用于选择合适的解析插件。
 ///
 /// # 支持的格式
-/// - SpringBoot: Java应用日志，包含INFO/ERROR/WARN/DEBUG级别
-/// - DockerJson: Docker容器日志，JSON格式包含log/stream字段
-/// - MyBatis: SQL框架日志，包含Preparing/Parameters/==>关键字
+/// - SpringBoot: Java应用日志，以时间戳和日志级别开头
+/// - DockerJson: Docker容器日志，JSON对象包含log/stream字段
+/// - MyBatis: SQL框架日志，以 `==>  Preparing:` 等标记为主
 /// - Unknown: 无法识别的格式，使用通用解析器
 ///
 /// # 检测策略
-/// 1. 按优先级依次检测各格式特征
-/// 2. 基于特征出现的频率和模式判断
-/// 3. 使用50%阈值作为主要格式的判断标准
-/// 4. MyBatis格式使用存在性判断而非频率
+/// 1. 取开头的非空样本行（见 `plugins::detection::SAMPLE_LINES`）
+/// 2. 每种格式按整行结构匹配计算置信度，堆栈续行不计入分母
+/// 3. 置信度最高且不低于 `plugins::detection::MIN_CONFIDENCE` 的格式胜出
 ///
 /// # 参数
 /// - `lines`: 日志行数组切片
 ///
 /// # Returns
 /// - `String`: 检测到的格式名称
-///
-/// # 性能优化
-/// - 早期退出：一旦确定格式立即返回
-/// - 采样检测：大文件可考虑只检测前N行
-/// - 缓存结果：相同内容的重复检测
 #[allow(dead_code)]
 fn detect_log_format(lines: &[&str]) -> String {
+    use plugins::detection;
+
     debug!("🔍 开始智能日志格式检测，总行数: {}", lines.len());
 
-    if lines.is_empty() {
-        warn!("⚠️ 日志行为空，返回Unknown格式");
-        return "Unknown".to_string();
+    let sample: Vec<&str> = lines.iter()
+        .copied()
+        .filter(|line| !line.trim().is_empty())
+        .take(detection::SAMPLE_LINES)
+        .collect();
+    for score in detection::score_formats(&sample) {
+        debug!("📊 {} 置信度: {:.2}", score.format, score.confidence);
+    }
+
+    match detection::best_format(&sample) {
+        Some(score) => {
+            info!("✅ 检测到{}格式，置信度: {:.1}%", score.format, score.confidence * 100.0);
+            match score.format.as_str() {
+                "springboot" => "SpringBoot",
+                "docker_json" => "DockerJson",
+                "mybatis" => "MyBatis",
+                _ => "Unknown",
+            }.to_string()
+        }
+        None => {
+            info!("❓ 未能识别已知日志格式，使用通用解析器");
+            "Unknown".to_string()
+        }
+    }
+}
+
+/// 用户为文件指定的格式，没有指定时使用文件解析配置中的格式（都没有或读取失败时返回None）
+async fn format_override(file_path: Option<&str>, state: &AppState) -> Option<String> {
+    let path = file_path?;
+    let format = state.config_service.lock().await
+        .get_format_override(path)
+        .unwrap_or_else(|e| {
+            warn!("⚠️ 读取文件格式设置失败: {}", e);
+            None
+        });
+    match format {
+        Some(format) => Some(format),
+        None => file_profile(file_path, state).await.and_then(|profile| profile.format),
     }
+}
 
-    // 检测SpringBoot格式
-    // 特征：包含标准日志级别关键字
-    debug!("🔍 检测SpringBoot格式特征");
-    let springboot_count = lines.iter()
-        .filter(|line| {
-            line.contains("INFO") || line.contains("ERROR") || line.contains("WARN") || line.contains("DEBUG")
+/// 文件使用的解析配置（没有匹配的配置或读取失败时返回None）
+async fn file_profile(file_path: Option<&str>, state: &AppState) -> Option<ParsingProfile> {
+    let file_path = file_path?;
+    state.config_service.lock().await
+        .get_file_profile(file_path)
+        .unwrap_or_else(|e| {
+            warn!("⚠️ 读取解析配置失败: {}", e);
+            None
         })
-        .count();
+}
+
+/// 让插件链使用解析配置中的插件设置
+///
+/// 插件设置在构建插件链时读取，所以配置中的插件设置与全局设置合并后重建插件链；
+/// 没有插件设置的配置（或没有配置）恢复全局设置。与当前生效的设置相同时不重建。
+async fn activate_profile_settings(profile: Option<&ParsingProfile>, state: &AppState) {
+    let profile = profile.filter(|profile| !profile.plugin_settings.is_empty());
+    if profile.is_none() && state.profile_settings.lock().is_ok_and(|active| active.is_none()) {
+        return;
+    }
+    let mut settings = match state.config_service.lock().await.get_plugin_config() {
+        Ok(config) => config.plugin_settings,
+        Err(e) => {
+            warn!("⚠️ 读取插件配置失败，无法切换插件设置: {}", e);
+            return;
+        }
+    };
+    let wanted = profile.map(|profile| {
+        settings.extend(profile.plugin_settings.clone());
+        settings.clone()
+    });
+
+    let Ok(mut active) = state.profile_settings.lock() else {
+        return;
+    };
+    if *active == wanted {
+        return;
+    }
+    match state.plugin_manager.apply_plugin_settings(&settings) {
+        Ok(()) => {
+            info!("🧩 插件链使用{}插件设置", if wanted.is_some() { "解析配置的" } else { "全局" });
+            *active = wanted;
+        }
+        Err(e) => warn!("⚠️ 应用解析配置的插件设置失败: {}", e),
+    }
+}
+
+/// 按用户指定的格式解析，没有指定时自动检测
+fn parse_with_override(
+    plugin_manager: &EnhancedPluginManager,
+    format: Option<&str>,
+    request: &ParseRequest,
+) -> Result<plugins::ParseResult, String> {
+    match format {
+        Some(format) => plugin_manager.parse_with_format(format, request),
+        None => plugin_manager.auto_detect_and_parse(request),
+    }
+}
 
-    let springboot_ratio = springboot_count as f64 / lines.len() as f64;
-    debug!("📊 SpringBoot特征匹配度: {}/{} ({:.1}%)", springboot_count, lines.len(), springboot_ratio * 100.0);
+/// 刷新文件的增量解析缓存
+///
+/// `refresh_parse` 和基于缓存条目的分析命令共用，文件未变化时不会重新解析。
+/// 解析作为交互任务在任务队列中执行，不会被后台建索引的任务阻塞。
+async fn refresh_cached_parse(file_path: &str, state: &AppState) -> Result<ParseDelta, String> {
+    let task = cached_parse_task(file_path, state).await;
+    let (_, result) = state.jobs.submit(JobKind::Parse, JobPriority::Interactive, file_path, task);
+    result.await.map_err(|_| Message::new("task.parse_cancelled").with("path", file_path).text())?
+}
 
-    if springboot_ratio > 0.5 { // 超过50%的行包含日志级别
-        info!("✅ 检测到SpringBoot格式，特征匹配度: {:.1}%", springboot_ratio * 100.0);
-        return "SpringBoot".to_string();
+/// 构造刷新文件解析缓存的任务
+///
+/// 读取文件的格式设置和解析配置（并切换插件设置）后返回，建立行索引和解析在任务中执行，
+/// 两步之间检查取消。
+async fn cached_parse_task(file_path: &str, state: &AppState) -> impl FnOnce(&JobContext) -> Result<ParseDelta, String> + Send + 'static {
+    let profile = file_profile(Some(file_path), state).await;
+    activate_profile_settings(profile.as_ref(), state).await;
+    let format = format_override(Some(file_path), state).await;
+    let merge_wrapped_lines = state.config_service.lock().await
+        .get_parse_config()
+        .is_ok_and(|config| config.merge_wrapped_lines);
+    let file_path = file_path.to_string();
+    let line_index_cache = state.line_index_cache.clone();
+    let parse_cache = state.parse_cache.clone();
+    let plugin_manager = state.plugin_manager.clone();
+    let runtime = state.runtime.clone();
+    let memory_budget = state.memory_budget.clone();
+
+    move |context| {
+        context.set_progress(0.0, &Message::new("job.stage.indexing").text());
+        let index = memory_budget.line_index(&parse_cache, &line_index_cache, &file_path)?;
+        context.check_cancelled()?;
+        context.set_progress(0.5, &Message::new("job.stage.parsing").text());
+        let delta = parse_cache.refresh(&index, |content| {
+            let parse_request = ParseRequest {
+                content: content.to_string(),
+                plugin: Some("auto".to_string()),
+                file_path: Some(file_path.clone()),
+                merge_wrapped_lines,
+                ..Default::default()
+            };
+            let parse_start = std::time::Instant::now();
+            let mut result = parse_with_override(&plugin_manager, format.as_deref(), &parse_request)?;
+            runtime.record_parse(content.lines().count(), parse_start.elapsed(), false);
+            if let Some(profile) = &profile {
+                profile.apply_derived_fields(&mut result.lines);
+            }
+            Ok(result)
+        })?;
+        runtime.record_cache(delta.mode);
+        // 解析结果加入缓存后再次检查预算
+        memory_budget.touch(&index.file_path);
+        memory_budget.enforce(&parse_cache, &line_index_cache, Some(&index.file_path));
+        Ok(delta)
     }
+}
+
+/// 在任务队列中执行交互请求的分析任务
+async fn run_analysis_job<F, R>(state: &AppState, label: &str, task: F) -> Result<R, String>
+where
+    F: FnOnce(&JobContext) -> Result<R, String> + Send + 'static,
+    R: Send + 'static,
+{
+    let (_, result) = state.jobs.submit(JobKind::Analysis, JobPriority::Interactive, label, task);
+    result.await.map_err(|_| Message::new("task.analysis_cancelled").with("path", label).text())?
+}
+
+/// 获取文件最新的全部解析条目（未截断）
+async fn cached_entries(file_path: &str, state: &AppState) -> Result<Vec<LogEntry>, String> {
+    let delta = refresh_cached_parse(file_path, state).await?;
+    state.parse_cache.entries(&delta.file_path)
+        .ok_or_else(|| Message::new("parse.result_unavailable").with("path", file_path).text())
+}
 
-    // 检测Docker JSON格式
-    // 特征：JSON格式，包含log和stream字段
-    debug!("🔍 检测Docker JSON格式特征");
-    let docker_json_count = lines.iter()
-        .filter(|line| {
-            let trimmed = line.trim();
-            trimmed.starts_with('{') && trimmed.contains("\"log\":") && trimmed.contains("\"stream\":")
+/// 内容中非空行的位置
+///
+/// 与 `str::lines()` 的分行规则一致，跳过空白行。
+///
+/// # Returns
+/// - 每个非空行的 `(原内容中的行序号, 字节范围)`，行序号从0开始
+fn non_empty_line_ranges(content: &str) -> Vec<(usize, std::ops::Range<usize>)> {
+    let base = content.as_ptr() as usize;
+    content.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            let start = line.as_ptr() as usize - base;
+            (i, start..start + line.len())
         })
-        .count();
+        .collect()
+}
 
-    let docker_ratio = docker_json_count as f64 / lines.len() as f64;
-    debug!("📊 Docker JSON特征匹配度: {}/{} ({:.1}%)", docker_json_count, lines.len(), docker_ratio * 100.0);
+/// 获取文件的行索引并计入缓存内存预算（见 `MemoryBudget::line_index`）
+fn line_index(file_path: &str, state: &AppState) -> Result<Arc<LineIndex>, String> {
+    state.memory_budget.line_index(&state.parse_cache, &state.line_index_cache, file_path)
+}
 
-    if docker_ratio > 0.5 { // 超过50%的行符合JSON格式
-        info!("✅ 检测到DockerJson格式，特征匹配度: {:.1}%", docker_ratio * 100.0);
-        return "DockerJson".to_string();
+/// 按源文件的行索引在条目元数据中写入字节偏移（`start_offset` / `end_offset`）
+///
+/// 压缩包成员和内容传输模式没有可定位的源文件，不写入。
+fn mark_source_offsets(file_path: Option<&str>, entries: &mut [LogEntry], state: &AppState) {
+    let Some(file_path) = file_path.filter(|path| reader::archive::split_member_path(path).is_none()) else {
+        return;
+    };
+    match line_index(file_path, state) {
+        Ok(index) => index.mark_offsets(entries),
+        Err(e) => warn!("⚠️ 构建行索引失败，条目不包含字节偏移: {}", e),
     }
+}
 
-    // 检测MyBatis格式
-    // 特征：包含MyBatis特有的SQL日志关键字
-    debug!("🔍 检测MyBatis格式特征");
-    let mybatis_count = lines.iter()
-        .filter(|line| {
-            line.contains("Preparing:") || line.contains("Parameters:") || line.contains("==>")
-        })
-        .count();
+/// `lines` 范围内空白行的行号（从1开始）
+///
+/// # 参数
+/// - `line_ranges`: `non_empty_line_ranges` 的结果
+/// - `lines`: 行序号范围（从0开始，左闭右开）
+fn blank_line_numbers(line_ranges: &[(usize, std::ops::Range<usize>)], lines: std::ops::Range<usize>) -> Vec<usize> {
+    let first = line_ranges.partition_point(|(i, _)| *i < lines.start);
+    let mut non_empty = line_ranges[first..].iter().map(|(i, _)| *i).peekable();
+    lines.filter(|&i| non_empty.next_if_eq(&i).is_none()).map(|i| i + 1).collect()
+}
+
+/// 截断超长日志行
+///
+/// 对 `content` 和 `formatted_content` 超过 `max_chars` 个字符的行进行截断，
+/// 并在元数据中记录 `truncated=true` 和 `original_length`（原始字符数），
+/// 完整内容可通过 `get_full_line` 按需获取。
+///
+/// # 参数
+/// - `lines`: 解析后的日志行
+/// - `max_chars`: 最大字符数，0表示不限制
+fn truncate_long_lines(lines: &mut [LogEntry], max_chars: usize) {
+    if max_chars == 0 {
+        return;
+    }
 
-    debug!("📊 MyBatis特征匹配: {}/{} 行", mybatis_count, lines.len());
+    let mut truncated_count = 0;
+    for line in lines.iter_mut() {
+        // 字节长度不超过阈值时字符数也一定不超过，可以跳过逐字符计数
+        if line.content.len() > max_chars {
+            let original_length = line.content.chars().count();
+            if original_length > max_chars {
+                line.content = truncate_chars(&line.content, max_chars);
+                line.metadata.insert("truncated".to_string(), "true".to_string());
+                line.metadata.insert("original_length".to_string(), original_length.to_string());
+                truncated_count += 1;
+            }
+        }
 
-    if mybatis_count > 0 { // MyBatis格式使用存在性判断
-        info!("✅ 检测到MyBatis格式，找到 {} 个特征行", mybatis_count);
-        return "MyBatis".to_string();
+        if let Some(formatted) = &line.formatted_content {
+            if formatted.len() > max_chars && formatted.chars().count() > max_chars {
+                line.formatted_content = Some(truncate_chars(formatted, max_chars));
+            }
+        }
     }
 
-    // 无法识别任何已知格式
-    info!("❓ 未能识别已知日志格式，使用通用解析器");
-    "Unknown".to_string()
+    if truncated_count > 0 {
+        info!("✂️ 截断了 {} 条超长日志行（上限 {} 字符）", truncated_count, max_chars);
+    }
 }
 
 /// 从日志行中提取时间戳 (优化版本)
@@ -1761,8 +6157,7 @@ fn detect_log_format(lines: &[&str]) -> String {
 fn extract_timestamp(line: &str) -> Option<String> {
     // 仅在调试模式下输出详细日志，减少大文件处理时的性能开销
     if log::log_enabled!(log::Level::Debug) {
-        debug!("🕐 尝试从日志行提取时间戳: {}",
-              if line.len() > 50 { format!("{}...", &line[..50]) } else { line.to_string() });
+        debug!("🕐 尝试从日志行提取时间戳: {}", ellipsize(line, 50));
     }
 
     // 使用预编译的正则表达式，避免重复编译
@@ -1815,8 +6210,7 @@ fn extract_timestamp(line: &str) -> Option<String> {
 fn extract_log_level(line: &str) -> Option<String> {
     // 仅在调试模式下输出详细日志，减少大文件处理时的性能开销
     if log::log_enabled!(log::Level::Debug) {
-        debug!("🔍 尝试从日志行提取级别: {}",
-              if line.len() > 30 { format!("{}...", &line[..30]) } else { line.to_string() });
+        debug!("🔍 尝试从日志行提取级别: {}", ellipsize(line, 30));
     }
 
     let line_lower = line.to_lowercase();
@@ -1909,17 +6303,38 @@ fn contains_exact_word(text: &str, word: &str) -> bool {
 ///
 /// # 注册的命令
 /// - 健康检查: health_check
-/// - 插件管理: get_plugins
-/// - 日志解析: parse_log, test_parse
-/// - 配置管理: get_theme_config, update_theme_config, get_parse_config, get_plugin_config, get_window_config, get_all_configs
-/// - 文件操作: read_text_file, write_file, save_dialog
+/// - 应用日志: set_log_level, set_backend_log_level, get_backend_log_level
+/// - 插件管理: get_plugins, get_supported_formats, get_renderers
+/// - 日志解析: parse_log, get_accumulated_stats, parse_clipboard, test_parse, preview_parse, refresh_parse, get_records, get_context, get_full_line, explain_line, validate_parser, run_parser_conformance, compile_pattern_layout
+/// - 任务队列: index_file, list_jobs, cancel_job
+/// - 缓存管理: get_cache_stats, get_memory_budget, set_memory_budget
+/// - 日志搜索: search_logs, group_entries, sample_entries, mine_templates, compare_runs, get_table_rows, get_log_outline
+/// - 文件跟踪: start_tail, ack_tail_batch, set_tail_filter, get_tail_stats, stop_tail, start_event_log_tail, start_otlp_receiver
+/// - 远程日志: list_remote_hosts, save_remote_host, remove_remote_host, list_remote_dir, open_remote_log, start_remote_tail
+/// - 容器日志: list_docker_containers, fetch_container_logs, list_kube_contexts, list_kube_namespaces, list_kube_pods, fetch_pod_logs
+/// - 测试分析: analyze_test_run
+/// - Lambda分析: analyze_lambda_invocations
+/// - Kafka分析: analyze_kafka_health
+/// - 启动分析: get_startup_breakdown, get_startup_failure
+/// - 度量分析: list_metric_series, get_metric_series, list_slow_operations, detect_metric_outliers
+/// - 固定条目: pin_entry, unpin_entry, list_pinned, get_entry_anchor, resolve_anchor
+/// - 快速搜索: search_palette, get_palette_settings, set_palette_settings
+/// - 工作区会话: save_session, load_session, list_sessions, persist_session_entries, restore_session_entries, query_session_entries, list_session_entry_files, remove_session_entries
+/// - 目录采集: get_ingest_settings, set_ingest_settings, get_ingest_status
+/// - 配置管理: get_theme_config, update_theme_config, get_theme_state, get_level_styles, set_level_style, get_parse_config, set_detection_sampling, set_merge_wrapped_lines, set_strict_content, set_compact_settings, set_formatting_profile, get_format_override, set_format_override, list_profiles, save_profile, remove_profile, apply_profile, get_file_profile, export_profile, export_profile_bundle, import_profile_bundle, get_plugin_config, get_plugin_settings_schema, set_plugin_settings, get_window_config, get_all_configs
+/// - 界面语言: get_locale, set_locale, get_message_catalog
+/// - 文件操作: download_url, list_archive_entries, read_text_file, write_file, export_logs, export_split, start_export_job, resume_export_job, list_export_jobs, discard_export_job, export_selection, generate_report, choose_write_directory, choose_save_path, get_write_directories
+/// - issue集成: list_issue_trackers, save_issue_tracker, remove_issue_tracker, preview_issue_from_selection, create_issue_from_selection
+/// - AI摘要: get_summarizer_settings, set_summarizer_settings, summarize_entries
+/// - 错误解释: list_explain_rules, save_explain_rule, remove_explain_rule, explain_errors
 #[tokio::main]
 async fn main() {
     // 第一步：初始化日志系统
-    // 配置日志级别和输出格式，用于应用的调试和监控
-    env_logger::Builder::from_default_env()
-        .filter_level(log::LevelFilter::Info) // 默认INFO级别，可通过环境变量调整
-        .init();
+    // 输出到控制台和应用数据目录下按天轮转的日志文件，级别可通过RUST_LOG或set_log_level调整
+    let log_dir = get_app_data_dir().await.ok().map(|dir| dir.join(logging::LOG_DIR));
+    if let Err(e) = logging::init(log_dir) {
+        eprintln!("⚠️ 初始化日志系统失败: {}", e);
+    }
 
     info!("🚀 LogWhisper Tauri 应用启动中...");
     debug!("🔧 日志系统初始化完成");
@@ -1940,32 +6355,242 @@ async fn main() {
         }
     };
 
+    // 恢复上次启用的目录采集
+    match app_state.config_service.lock().await.get_ingest_settings() {
+        Ok(settings) if settings.enabled => restart_ingest(settings, &app_state),
+        Ok(_) => {}
+        Err(e) => warn!("⚠️ 读取目录采集设置失败: {}", e),
+    }
+
+    // 读取上次保存的窗口状态，窗口创建后恢复
+    let window_config = match app_state.config_service.lock().await.get_window_config() {
+        Ok(config) => Some(config),
+        Err(e) => {
+            warn!("⚠️ 读取窗口配置失败: {}", e);
+            None
+        }
+    };
+
+    // 读取快速搜索面板设置，应用启动后注册全局快捷键
+    let palette_settings = app_state.config_service.lock().await.get_palette_settings().unwrap_or_else(|e| {
+        warn!("⚠️ 读取快速搜索设置失败，使用默认设置: {}", e);
+        PaletteSettings::default()
+    });
+
     // 第三步：配置和启动Tauri应用
     info!("🏗️ 配置Tauri应用框架...");
 
     tauri::Builder::default()
         .manage(app_state) // 注册全局应用状态
+        .setup(move |app| {
+            use tauri::Manager;
+
+            if let (Some(window), Some(config)) = (app.get_window("main"), window_config) {
+                if let Err(e) = restore_window_state(&window, &config) {
+                    warn!("⚠️ 恢复窗口状态失败: {}", e);
+                }
+            }
+            if palette_settings.enabled {
+                if let Err(e) = register_palette_shortcut(&app.handle(), &palette_settings.shortcut) {
+                    warn!("⚠️ {}", e);
+                }
+            }
+            Ok(())
+        })
+        .on_window_event(|event| {
+            use tauri::Manager;
+
+            let window = event.window();
+            match event.event() {
+                // Auto模式跟随系统明暗切换
+                tauri::WindowEvent::ThemeChanged(theme) => {
+                    apply_system_theme(window, &window.state::<AppState>(), *theme);
+                }
+                tauri::WindowEvent::Moved(_) | tauri::WindowEvent::Resized(_) => {
+                    record_window_state(window, &window.state::<AppState>());
+                }
+                _ => {}
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             // 系统管理命令
             health_check,
 
+            // 应用日志命令
+            set_log_level,
+            set_backend_log_level,
+            get_backend_log_level,
+
             // 插件和解析命令
             get_plugins,
+            get_supported_formats,
+            get_renderers,
             get_file_info,
             parse_log,
+            get_accumulated_stats,
+            parse_clipboard,
             test_parse,
+            preview_parse,
+            refresh_parse,
+            get_records,
+            get_context,
+            get_full_line,
+            explain_line,
+            validate_parser,
+            run_parser_conformance,
+            compile_pattern_layout,
+
+            // 日志搜索命令
+            search_logs,
+            get_cache_stats,
+            get_memory_budget,
+            set_memory_budget,
+            index_file,
+            list_jobs,
+            cancel_job,
+            group_entries,
+            sample_entries,
+            mine_templates,
+            compare_runs,
+            get_table_rows,
+            get_log_outline,
+
+            // 测试分析命令
+            analyze_test_run,
+
+            // Lambda分析命令
+            analyze_lambda_invocations,
+
+            // Kafka分析命令
+            analyze_kafka_health,
+
+            // 启动分析命令
+            get_startup_breakdown,
+            get_startup_failure,
+
+            // 文件跟踪命令
+            start_tail,
+            ack_tail_batch,
+            set_tail_filter,
+            get_tail_stats,
+            stop_tail,
+            start_event_log_tail,
+            start_otlp_receiver,
+
+            // 远程日志命令
+            list_remote_hosts,
+            save_remote_host,
+            remove_remote_host,
+            list_remote_dir,
+            open_remote_log,
+            start_remote_tail,
+
+            // 容器日志命令
+            list_docker_containers,
+            fetch_container_logs,
+            list_kube_contexts,
+            list_kube_namespaces,
+            list_kube_pods,
+            fetch_pod_logs,
+
+            // 度量分析命令
+            list_metric_series,
+            get_metric_series,
+            list_slow_operations,
+            detect_metric_outliers,
+
+            // 固定条目命令
+            pin_entry,
+            unpin_entry,
+            list_pinned,
+            get_entry_anchor,
+            resolve_anchor,
+
+            // 工作区会话命令
+            save_session,
+            load_session,
+            list_sessions,
+            persist_session_entries,
+            restore_session_entries,
+            query_session_entries,
+            list_session_entry_files,
+            remove_session_entries,
+
+            // 快速搜索命令
+            search_palette,
+            get_palette_settings,
+            set_palette_settings,
+
+            // 目录采集命令
+            get_ingest_settings,
+            set_ingest_settings,
+            get_ingest_status,
 
             // 配置管理命令
             get_theme_config,
             update_theme_config,
+            get_theme_state,
+            get_level_styles,
+            set_level_style,
             get_parse_config,
+            set_detection_sampling,
+            set_merge_wrapped_lines,
+            set_strict_content,
+            set_compact_settings,
+            set_formatting_profile,
+            get_format_override,
+            set_format_override,
+            list_profiles,
+            save_profile,
+            remove_profile,
+            apply_profile,
+            get_file_profile,
+            export_profile,
+            export_profile_bundle,
+            import_profile_bundle,
             get_plugin_config,
+            get_plugin_settings_schema,
+            set_plugin_settings,
             get_window_config,
             get_all_configs,
 
+            // 界面语言命令
+            get_locale,
+            set_locale,
+            get_message_catalog,
+
             // 文件系统操作命令
+            download_url,
+            list_archive_entries,
             read_text_file,
-            write_file
+            write_file,
+            export_logs,
+            export_split,
+            start_export_job,
+            resume_export_job,
+            list_export_jobs,
+            discard_export_job,
+            export_selection,
+            generate_report,
+            choose_write_directory,
+            choose_save_path,
+            get_write_directories,
+
+            // issue集成命令
+            list_issue_trackers,
+            save_issue_tracker,
+            remove_issue_tracker,
+            preview_issue_from_selection,
+            create_issue_from_selection,
+            // AI摘要命令
+            get_summarizer_settings,
+            set_summarizer_settings,
+            summarize_entries,
+            // 错误解释命令
+            list_explain_rules,
+            save_explain_rule,
+            remove_explain_rule,
+            explain_errors
         ])
         .run(tauri::generate_context!())
         .expect("🔥 Tauri应用运行失败，请检查配置");
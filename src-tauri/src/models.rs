@@ -0,0 +1,312 @@
+/// LogWhisper 共享数据模型
+///
+/// 前端命令（`main.rs`）和插件系统共用的日志条目、解析请求和解析结果定义。
+/// 所有结构都可直接序列化给前端，因此各层之间无需再做格式转换。
+///
+/// # 数据结构
+/// - **LogLine / LogEntry**: 解析后的单行日志条目
+/// - **LogRecord**: 由一行或多行日志组成的逻辑记录（如带堆栈的异常）
+/// - **ParseResult**: 解析器返回的解析结果
+/// - **ParseRequest**: 解析请求（前端命令参数和插件请求）
+
+use crate::plugins::capabilities::CapabilityDecision;
+use crate::plugins::compact::CompactSettings;
+use crate::plugins::formatter::FormattingProfile;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// 日志条目类型别名 (向后兼容)
+///
+/// 为了保持API兼容性而保留的类型别名。
+/// 新代码应该直接使用 `LogLine` 类型。
+pub type LogEntry = LogLine;
+
+/// 日志行数据结构
+///
+/// 表示解析后的单行日志条目，包含原始内容和解析后的结构化信息。
+/// 这是插件系统的核心数据结构，用于在解析器之间传递数据。
+///
+/// # 字段说明
+/// - `line_number`: 在原文件中的行号（从1开始）
+/// - `content`: 原始日志内容
+/// - `level`: 解析出的日志级别（如INFO, ERROR等）
+/// - `timestamp`: 解析出的时间戳
+/// - `formatted_content`: 格式化后的显示内容
+/// - `message`: 过滤器从原始内容中提取出的消息正文
+/// - `metadata`: 附加元数据（如线程ID、类名等）
+/// - `processed_by`: 处理此条目的插件列表
+///
+/// # 设计特点
+/// - 支持多种日志格式的统一表示
+/// - 可选字段适应不同的解析需求
+/// - 元数据支持扩展信息存储
+/// - 处理链追踪用于调试和优化
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LogLine {
+    /// 在原文件中的行号（从1开始）
+    pub line_number: usize,
+
+    /// 原始日志内容（保持不变）
+    pub content: String,
+
+    /// 解析出的日志级别（如INFO, ERROR, WARN等）
+    pub level: Option<String>,
+
+    /// 解析出的时间戳（ISO格式或原始格式）
+    pub timestamp: Option<String>,
+
+    /// 格式化后的显示内容（可能包含高亮、结构化信息）
+    pub formatted_content: Option<String>,
+
+    /// 提取出的消息正文（去掉时间戳、级别、容器包装等前缀；未提取时为None）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+
+    /// 附加元数据（如线程ID、类名、方法名等）
+    pub metadata: HashMap<String, String>,
+
+    /// 处理此条目的插件名称列表（用于追踪处理链）
+    pub processed_by: Vec<String>,
+}
+
+impl LogLine {
+    /// 创建只有行号和原始内容的条目，其余字段为空
+    ///
+    /// # 参数
+    /// - `line_number`: 行号（从1开始）
+    /// - `content`: 原始日志内容
+    pub fn new(line_number: usize, content: impl Into<String>) -> Self {
+        Self { line_number, content: content.into(), ..Default::default() }
+    }
+
+    /// 消息正文：提取出的消息，没有时为原始内容
+    pub fn message_text(&self) -> &str {
+        self.message.as_deref().unwrap_or(&self.content)
+    }
+
+    /// 写入提取出的消息正文
+    ///
+    /// 严格模式下原始内容保持不变，只写入 `message`；否则直接替换 `content`（兼容旧行为），
+    /// `message` 保持为空，避免每个条目保存两份相同的文本。
+    ///
+    /// # 参数
+    /// - `message`: 提取出的消息正文
+    /// - `strict`: 是否为严格模式（见 `ParseRequest::is_strict`）
+    pub fn set_message(&mut self, message: String, strict: bool) {
+        if strict {
+            self.message = Some(message);
+        } else {
+            self.content = message;
+        }
+    }
+}
+
+/// 日志记录数据结构
+///
+/// 一条逻辑日志记录，可以跨越多个物理行（异常堆栈、格式化输出的JSON、
+/// 多行SQL等）。记录由首行（记录头）和其后的续行组成，分组规则见 `records` 模块。
+///
+/// # 字段说明
+/// - `start_line`: 记录首行的行号
+/// - `end_line`: 记录最后一行的行号
+/// - `level`: 记录级别（取自首行）
+/// - `timestamp`: 记录时间戳（取自首行）
+/// - `content`: 所有物理行的原始内容，以换行符连接
+/// - `formatted_content`: 所有物理行的格式化内容，以换行符连接
+/// - `metadata`: 首行的元数据
+/// - `lines`: 组成记录的物理行（第一个为记录头）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogRecord {
+    /// 记录首行的行号（从1开始）
+    pub start_line: usize,
+
+    /// 记录最后一行的行号（单行记录与 `start_line` 相同）
+    pub end_line: usize,
+
+    /// 记录级别（取自首行）
+    pub level: Option<String>,
+
+    /// 记录时间戳（取自首行）
+    pub timestamp: Option<String>,
+
+    /// 所有物理行的原始内容（以换行符连接）
+    pub content: String,
+
+    /// 所有物理行的格式化内容（以换行符连接，缺少格式化内容的行使用原始内容）
+    pub formatted_content: String,
+
+    /// 首行的元数据
+    pub metadata: HashMap<String, String>,
+
+    /// 组成记录的物理行（第一个为记录头）
+    pub lines: Vec<LogLine>,
+}
+
+impl LogRecord {
+    /// 由组成记录的物理行构建记录
+    ///
+    /// # Panics
+    /// `lines` 为空时panic，调用方保证至少有一行
+    pub fn from_lines(lines: Vec<LogLine>) -> Self {
+        let head = &lines[0];
+        let content = lines.iter().map(|line| line.content.as_str()).collect::<Vec<_>>().join("\n");
+        let formatted_content = lines.iter()
+            .map(|line| line.formatted_content.as_deref().unwrap_or(&line.content))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        Self {
+            start_line: head.line_number,
+            end_line: lines[lines.len() - 1].line_number,
+            level: head.level.clone(),
+            timestamp: head.timestamp.clone(),
+            content,
+            formatted_content,
+            metadata: head.metadata.clone(),
+            lines,
+        }
+    }
+}
+
+/// 解析结果数据结构
+///
+/// 包含日志解析的完整结果，包括解析的日志条目、统计信息和错误状态。
+/// 这是所有解析器必须返回的统一结果格式。
+///
+/// # 字段说明
+/// - `lines`: 解析后的日志行列表
+/// - `total_lines`: 原始内容的总行数
+/// - `detected_format`: 检测到的日志格式
+/// - `parsing_errors`: 解析过程中遇到的错误列表
+/// - `applied_filters`: 实际执行的过滤器（插件链模式，按执行顺序）
+///
+/// # 错误处理策略
+/// - 非致命错误继续处理其他行
+/// - 收集所有错误信息供用户参考
+/// - 即使有错误也返回部分结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParseResult {
+    /// 解析后的日志行列表
+    pub lines: Vec<LogLine>,
+
+    /// 原始内容的总行数（包括空行）
+    pub total_lines: usize,
+
+    /// 自动检测到的日志格式（如"SpringBoot", "DockerJson"等）
+    pub detected_format: Option<String>,
+
+    /// 解析过程中遇到的错误列表
+    pub parsing_errors: Vec<String>,
+
+    /// 实际执行的过滤器名称（插件链模式，按执行顺序；单插件模式为空）
+    #[serde(default)]
+    pub applied_filters: Vec<String>,
+
+    /// 插件能力协商结果（插件链模式：选中的链、因文件过大跳过的链和超出上限的警告）
+    #[serde(default)]
+    pub capabilities: Option<CapabilityDecision>,
+}
+
+/// 解析请求数据结构
+///
+/// 前端 `parse_log` 命令的请求参数，同时也是传给插件解析器的请求。
+/// 所有字段在反序列化时都可以省略，支持文件路径和内容直接传输两种模式。
+///
+/// # 字段说明
+/// - `content`: 要解析的日志内容（内容模式，文件模式下为空）
+/// - `plugin`: 指定使用的插件名称（可选）
+/// - `file_path`: 源文件路径（文件模式，同时用于格式检测）
+/// - `chunk_size`: 分块处理时的块大小
+/// - `chunk_index`: 当前请求的块索引
+/// - `chunk_overlap`: 内容开头携带的上一块末尾行数
+/// - `renderer`: 输出渲染器名称
+///
+/// # 使用模式
+/// - 文件模式：提供file_path，后端读取文件内容
+/// - 内容模式：提供content，后端直接处理传入内容
+/// - 指定插件模式：设置plugin字段使用特定解析器
+/// - 自动检测模式：不设置plugin，让系统自动选择
+/// - 分块处理模式：设置chunk_size和chunk_index用于大文件处理
+/// - 自适应分块模式：设置adaptive_chunking和chunk_index，块大小由后端按字节预算选择
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ParseRequest {
+    /// 要解析的日志内容（UTF-8编码）
+    #[serde(default)]
+    pub content: String,
+
+    /// 指定使用的插件名称（可选，不指定则自动检测）
+    #[serde(default)]
+    pub plugin: Option<String>,
+
+    /// 源文件路径（用于文件读取、扩展名和格式检测）
+    #[serde(default)]
+    pub file_path: Option<String>,
+
+    /// 分块处理时的块大小（行数，用于大文件优化）
+    #[serde(default)]
+    pub chunk_size: Option<usize>,
+
+    /// 当前请求的块索引（从0开始，用于分块处理）
+    #[serde(default)]
+    pub chunk_index: Option<usize>,
+
+    /// 分块处理时携带的上一块末尾行数（用于跨块的多行记录，只作为上下文，不产生条目）
+    #[serde(default)]
+    pub chunk_overlap: Option<usize>,
+
+    /// 分块起始位置（内容中块第一行之前的行数，不含重叠行），未设置时为 `chunk_index * chunk_size`
+    #[serde(default)]
+    pub chunk_start: Option<usize>,
+
+    /// 按测得的行长度和解析速度自动选择块大小（此时忽略 `chunk_size`，块需从第0块开始按顺序请求）
+    #[serde(default)]
+    pub adaptive_chunking: bool,
+
+    /// 输出渲染器名称（plain/ansi/html/markdown，可选，不指定则保持插件链的格式化结果）
+    #[serde(default)]
+    pub renderer: Option<String>,
+
+    /// 是否将终端按宽度折断的续行合并到前一个条目（未设置时使用解析配置）
+    #[serde(default)]
+    pub merge_wrapped_lines: bool,
+
+    /// 是否将空行作为空条目返回（未设置时只有raw解析器保留空行；无论是否保留，条目行号都与文件一致）
+    #[serde(default)]
+    pub preserve_blank_lines: Option<bool>,
+
+    /// 紧凑格式开关和线程名缩略规则（未设置时使用解析配置）
+    #[serde(default)]
+    pub compact: Option<CompactSettings>,
+
+    /// 严格模式：过滤器不改写原始内容，提取出的消息只写入 `message`（未设置时使用解析配置）
+    #[serde(default)]
+    pub strict_content: Option<bool>,
+
+    /// 格式化风格（plain/decorated，未设置时使用解析配置）
+    #[serde(default)]
+    pub formatting: Option<FormattingProfile>,
+}
+
+impl ParseRequest {
+    /// 是否为严格模式（请求和解析配置都未设置时为否，保持替换 `content` 的旧行为）
+    pub fn is_strict(&self) -> bool {
+        self.strict_content.unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frontend_request_fields_are_optional() {
+        let request: ParseRequest = serde_json::from_str(r#"{"file_path": "/tmp/app.log", "plugin": "auto", "chunk_size": 1000}"#).unwrap();
+        assert!(request.content.is_empty());
+        assert_eq!(request.chunk_size, Some(1000));
+        assert_eq!(request.chunk_index, None);
+
+        let request: ParseRequest = serde_json::from_str(r#"{"content": "line"}"#).unwrap();
+        assert_eq!(request.content, "line");
+        assert!(request.file_path.is_none());
+    }
+}
@@ -0,0 +1,361 @@
+/// OpenTelemetry OTLP日志接收器
+///
+/// 在本机启动一个小型OTLP/HTTP接收端（默认 `127.0.0.1:4318`，路径 `/v1/logs`），
+/// 开发时把应用的OTLP日志导出器指向LogWhisper即可实时查看日志。
+/// 接收到的 `LogRecord` 转换为 `LogLine`：严重级别映射为日志级别，body作为消息，
+/// 日志属性、trace/span ID和资源属性（以 `resource.` 为前缀，如 `resource.service.name`）记录在元数据中。
+///
+/// 只支持JSON编码（`Content-Type: application/json`，可gzip压缩）；
+/// protobuf编码的请求返回415，导出器需配置为 `http/json` 协议。
+
+use crate::i18n::Message;
+use crate::models::LogLine;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 默认监听端口（OTLP/HTTP标准端口）
+pub const DEFAULT_OTLP_PORT: u16 = 4318;
+
+/// OTLP日志导出路径
+const LOGS_PATH: &str = "/v1/logs";
+
+/// 单个请求体的大小上限
+const MAX_BODY_BYTES: usize = 16 * 1024 * 1024;
+
+/// 接收器检查停止标志的间隔
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 已启动的接收器信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OtlpReceiverInfo {
+    /// 跟踪ID（用于确认批次和停止接收）
+    pub tail_id: String,
+    /// 日志导出端点URL（填入应用的 `OTEL_EXPORTER_OTLP_LOGS_ENDPOINT`）
+    pub endpoint: String,
+}
+
+/// 运行中的OTLP接收器，Drop时停止监听
+pub struct OtlpReceiver {
+    /// 实际监听的地址
+    pub addr: SocketAddr,
+    stop: Arc<AtomicBool>,
+}
+
+impl OtlpReceiver {
+    /// 在本机指定端口启动接收器
+    ///
+    /// # 参数
+    /// - `port`: 监听端口（0表示由系统分配）
+    ///
+    /// # Returns
+    /// - `Ok((OtlpReceiver, Receiver<Vec<LogLine>>))`: 接收器和每个导出请求转换出的条目
+    /// - `Err(String)`: 端口被占用等错误信息
+    pub fn start(port: u16) -> Result<(Self, Receiver<Vec<LogLine>>), String> {
+        let listener = TcpListener::bind(("127.0.0.1", port))
+            .map_err(|e| Message::new("otlp.bind_failed").with("port", port).with("error", e).text())?;
+        listener.set_nonblocking(true).map_err(|e| Message::new("otlp.nonblocking_failed").with("error", e).text())?;
+        let addr = listener.local_addr().map_err(|e| Message::new("otlp.local_addr_failed").with("error", e).text())?;
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = mpsc::channel();
+        let thread_stop = stop.clone();
+        std::thread::spawn(move || {
+            while !thread_stop.load(Ordering::Relaxed) {
+                match listener.accept() {
+                    Ok((stream, _)) => {
+                        if let Err(e) = handle_connection(stream, &sender) {
+                            log::warn!("⚠️ OTLP请求处理失败: {}", e);
+                        }
+                    }
+                    Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => std::thread::sleep(ACCEPT_POLL_INTERVAL),
+                    Err(e) => log::warn!("⚠️ OTLP接收连接失败: {}", e),
+                }
+            }
+            log::info!("🛑 OTLP接收器已停止: {}", addr);
+        });
+
+        log::info!("📡 OTLP接收器已启动: http://{}{}", addr, LOGS_PATH);
+        Ok((Self { addr, stop }, receiver))
+    }
+
+    /// 日志导出端点URL
+    pub fn endpoint(&self) -> String {
+        format!("http://{}{}", self.addr, LOGS_PATH)
+    }
+}
+
+impl Drop for OtlpReceiver {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// 处理一个HTTP连接（每个连接只处理一个请求）
+fn handle_connection(stream: TcpStream, sender: &Sender<Vec<LogLine>>) -> Result<(), String> {
+    stream.set_nonblocking(false).ok();
+    stream.set_read_timeout(Some(Duration::from_secs(10))).ok();
+    let mut reader = BufReader::new(stream.try_clone().map_err(|e| e.to_string())?);
+    let mut writer = stream;
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).map_err(|e| e.to_string())?;
+    let mut parts = request_line.split_whitespace();
+    let (method, path) = (parts.next().unwrap_or_default(), parts.next().unwrap_or_default());
+
+    let mut headers = HashMap::new();
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).map_err(|e| e.to_string())? == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+        }
+    }
+
+    let (status, body) = if path.split('?').next() != Some(LOGS_PATH) {
+        (404, r#"{"message":"not found"}"#.to_string())
+    } else if method != "POST" {
+        (405, r#"{"message":"method not allowed"}"#.to_string())
+    } else if !headers.get("content-type").is_some_and(|t| t.starts_with("application/json")) {
+        (415, r#"{"message":"only OTLP/HTTP JSON encoding is supported"}"#.to_string())
+    } else {
+        let length: usize = headers.get("content-length").and_then(|l| l.parse().ok()).unwrap_or(0);
+        if length > MAX_BODY_BYTES {
+            (413, r#"{"message":"request too large"}"#.to_string())
+        } else {
+            let mut raw = vec![0; length];
+            reader.read_exact(&mut raw).map_err(|e| e.to_string())?;
+            match decode_body(&raw, headers.get("content-encoding").map(String::as_str)) {
+                Ok(request) => {
+                    let lines = logs_to_lines(&request);
+                    if !lines.is_empty() && sender.send(lines).is_err() {
+                        return Ok(());
+                    }
+                    (200, "{}".to_string())
+                }
+                Err(e) => (400, serde_json::json!({ "message": e }).to_string()),
+            }
+        }
+    };
+
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Payload Too Large",
+        _ => "Unsupported Media Type",
+    };
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status, reason, body.len(), body
+    );
+    writer.write_all(response.as_bytes()).map_err(|e| e.to_string())
+}
+
+/// 解码请求体（支持gzip）并解析JSON
+fn decode_body(raw: &[u8], encoding: Option<&str>) -> Result<Value, String> {
+    let mut bytes = Vec::new();
+    let decoded = match encoding {
+        Some("gzip") => {
+            flate2::read::GzDecoder::new(raw)
+                .take(MAX_BODY_BYTES as u64)
+                .read_to_end(&mut bytes)
+                .map_err(|e| Message::new("otlp.gzip_failed").with("error", e).text())?;
+            bytes.as_slice()
+        }
+        _ => raw,
+    };
+    serde_json::from_slice(decoded).map_err(|e| Message::new("otlp.invalid_json").with("error", e).text())
+}
+
+/// 把OTLP的 `AnyValue` 转换为显示文本
+fn any_value_to_string(value: &Value) -> String {
+    if let Some(s) = value.get("stringValue").and_then(Value::as_str) {
+        return s.to_string();
+    }
+    if let Some(v) = value.get("intValue").or_else(|| value.get("doubleValue")).or_else(|| value.get("boolValue")) {
+        return v.as_str().map(str::to_string).unwrap_or_else(|| v.to_string());
+    }
+    if let Some(b) = value.get("bytesValue").and_then(Value::as_str) {
+        return b.to_string();
+    }
+    if let Some(values) = value.pointer("/arrayValue/values").and_then(Value::as_array) {
+        return format!("[{}]", values.iter().map(any_value_to_string).collect::<Vec<_>>().join(", "));
+    }
+    if let Some(values) = value.pointer("/kvlistValue/values") {
+        let pairs = attributes(values).into_iter().map(|(k, v)| format!("{}={}", k, v));
+        return format!("{{{}}}", pairs.collect::<Vec<_>>().join(", "));
+    }
+    String::new()
+}
+
+/// 把 `KeyValue` 列表转换为键值对
+fn attributes(list: &Value) -> Vec<(String, String)> {
+    list.as_array().into_iter().flatten()
+        .filter_map(|kv| {
+            let key = kv.get("key")?.as_str()?.to_string();
+            Some((key, kv.get("value").map(any_value_to_string).unwrap_or_default()))
+        })
+        .collect()
+}
+
+/// 把严重级别映射为日志级别（severityNumber优先，未设置时使用severityText）
+fn severity_level(number: u64, text: Option<&str>) -> Option<String> {
+    let level = match number {
+        1..=8 => "DEBUG",
+        9..=12 => "INFO",
+        13..=16 => "WARN",
+        17..=24 => "ERROR",
+        _ => {
+            return text.map(str::trim).filter(|t| !t.is_empty()).map(|t| match t.to_uppercase().as_str() {
+                "WARNING" => "WARN".to_string(),
+                "FATAL" | "CRITICAL" => "ERROR".to_string(),
+                "TRACE" => "DEBUG".to_string(),
+                other => other.to_string(),
+            });
+        }
+    };
+    Some(level.to_string())
+}
+
+/// 把Unix纳秒时间戳（JSON编码中为字符串）转换为RFC 3339时间
+fn nanos_to_timestamp(value: Option<&Value>) -> Option<String> {
+    let nanos: i64 = match value? {
+        Value::String(s) => s.parse().ok()?,
+        other => other.as_i64()?,
+    };
+    if nanos <= 0 {
+        return None;
+    }
+    let time = chrono::DateTime::from_timestamp(nanos / 1_000_000_000, (nanos % 1_000_000_000) as u32)?;
+    Some(time.to_rfc3339_opts(chrono::SecondsFormat::Millis, true))
+}
+
+/// 把 `ExportLogsServiceRequest` 转换为日志条目（行号为0，由调用方按接收顺序编号）
+pub fn logs_to_lines(request: &Value) -> Vec<LogLine> {
+    let mut lines = Vec::new();
+    for resource_logs in request["resourceLogs"].as_array().into_iter().flatten() {
+        let resource: Vec<(String, String)> = attributes(&resource_logs["resource"]["attributes"])
+            .into_iter()
+            .map(|(k, v)| (format!("resource.{}", k), v))
+            .collect();
+
+        for scope_logs in resource_logs["scopeLogs"].as_array().into_iter().flatten() {
+            let scope = scope_logs["scope"]["name"].as_str().filter(|s| !s.is_empty());
+
+            for record in scope_logs["logRecords"].as_array().into_iter().flatten() {
+                let timestamp = nanos_to_timestamp(record.get("timeUnixNano"))
+                    .or_else(|| nanos_to_timestamp(record.get("observedTimeUnixNano")));
+                let level = severity_level(
+                    record["severityNumber"].as_u64().unwrap_or(0),
+                    record["severityText"].as_str(),
+                );
+                let body = record.get("body").map(any_value_to_string).unwrap_or_default();
+
+                let mut metadata: HashMap<String, String> = resource.iter().cloned().collect();
+                metadata.extend(attributes(&record["attributes"]));
+                if let Some(scope) = scope {
+                    metadata.insert("scope".to_string(), scope.to_string());
+                }
+                for (key, field) in [("trace_id", "traceId"), ("span_id", "spanId")] {
+                    if let Some(id) = record[field].as_str().filter(|id| !id.is_empty()) {
+                        metadata.insert(key.to_string(), id.to_string());
+                    }
+                }
+
+                let content = [timestamp.as_deref(), level.as_deref(), Some(body.as_str())]
+                    .into_iter()
+                    .flatten()
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                lines.push(LogLine {
+                    line_number: 0,
+                    content,
+                    level,
+                    timestamp,
+                    formatted_content: None,
+                    message: None,
+                    metadata,
+                    processed_by: vec!["otlp".to_string()],
+                });
+            }
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_request() -> Value {
+        serde_json::json!({
+            "resourceLogs": [{
+                "resource": {"attributes": [{"key": "service.name", "value": {"stringValue": "checkout"}}]},
+                "scopeLogs": [{
+                    "scope": {"name": "com.example.Checkout"},
+                    "logRecords": [{
+                        "timeUnixNano": "1705314645123000000",
+                        "severityNumber": 17,
+                        "severityText": "Error",
+                        "body": {"stringValue": "payment failed"},
+                        "attributes": [{"key": "order.id", "value": {"intValue": "42"}}],
+                        "traceId": "5b8efff798038103d269b633813fc60c",
+                        "spanId": "eee19b7ec3c1b174"
+                    }, {
+                        "observedTimeUnixNano": "1705314646000000000",
+                        "severityText": "warning",
+                        "body": {"kvlistValue": {"values": [{"key": "retry", "value": {"boolValue": true}}]}}
+                    }]
+                }]
+            }]
+        })
+    }
+
+    #[test]
+    fn test_logs_to_lines_maps_record_fields() {
+        let lines = logs_to_lines(&sample_request());
+        assert_eq!(lines.len(), 2);
+
+        let error = &lines[0];
+        assert_eq!(error.content, "2024-01-15T10:30:45.123Z ERROR payment failed");
+        assert_eq!(error.metadata["resource.service.name"], "checkout");
+        assert_eq!(error.metadata["order.id"], "42");
+        assert_eq!(error.metadata["trace_id"], "5b8efff798038103d269b633813fc60c");
+        assert_eq!(error.metadata["scope"], "com.example.Checkout");
+
+        assert_eq!(lines[1].level.as_deref(), Some("WARN"));
+        assert_eq!(lines[1].timestamp.as_deref(), Some("2024-01-15T10:30:46.000Z"));
+        assert!(lines[1].content.ends_with("{retry=true}"));
+    }
+
+    #[test]
+    fn test_receiver_accepts_json_and_rejects_protobuf() {
+        let (receiver, lines) = OtlpReceiver::start(0).unwrap();
+        let post = |content_type: &str, body: &str| {
+            let mut stream = TcpStream::connect(receiver.addr).unwrap();
+            write!(
+                stream,
+                "POST /v1/logs HTTP/1.1\r\nHost: localhost\r\nContent-Type: {}\r\nContent-Length: {}\r\n\r\n{}",
+                content_type, body.len(), body
+            ).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+
+        assert!(post("application/json", &sample_request().to_string()).starts_with("HTTP/1.1 200"));
+        assert_eq!(lines.recv_timeout(Duration::from_secs(5)).unwrap().len(), 2);
+        assert!(post("application/x-protobuf", "").starts_with("HTTP/1.1 415"));
+        assert!(post("application/json", "{not json").starts_with("HTTP/1.1 400"));
+    }
+}
@@ -0,0 +1,188 @@
+/// 日志大纲
+///
+/// 识别一次运行中的生命周期节点（应用启动、上下文刷新、服务器启动、启动完成、关闭）
+/// 和错误集中出现的区段，按行号排列成目录，界面据此在运行的各个阶段之间跳转。
+///
+/// 每个节点的区段从节点所在行延续到下一个节点之前；错误集中区段单独标出起止行。
+
+use crate::models::LogLine;
+use crate::utils::string_utils::truncate_chars;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// 相邻两条错误间隔不超过该行数时视为同一区段
+pub const BURST_MAX_GAP: usize = 20;
+
+/// 区段内至少包含的错误条目数
+pub const BURST_MIN_ERRORS: usize = 3;
+
+/// 生命周期节点：(类型, 标题, 匹配规则)，同一条目按顺序取第一个匹配
+static LIFECYCLE_MARKERS: Lazy<Vec<(&'static str, &'static str, Regex)>> = Lazy::new(|| {
+    [
+        ("application_start", "应用启动", r":: Spring Boot ::|\bStarting \S+ (?:v\S+ )?(?:using Java|on |with PID)"),
+        ("context_refreshed", "上下文初始化完成", r"Root WebApplicationContext: initialization completed|ContextRefreshedEvent|Initialized JPA EntityManagerFactory"),
+        ("server_started", "服务器启动", r"(?i)\b(?:Tomcat|Jetty|Netty|Undertow) started on port|\bserver started\b|\blistening on (?:port|https?://|\d)"),
+        ("application_started", "启动完成", r"\bStarted \S+ in [\d.]+ seconds"),
+        ("shutdown", "关闭", r"(?i)\bshutting down\b|\bgraceful shutdown\b|\bstopping service\b|\bSIGTERM\b|\bClosing (?:JPA EntityManagerFactory|Spring root WebApplicationContext)"),
+    ]
+    .into_iter()
+    .map(|(kind, title, pattern)| (kind, title, Regex::new(pattern).unwrap()))
+    .collect()
+});
+
+/// 大纲中的一个区段
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutlineSection {
+    /// 区段类型：生命周期节点类型或 `error_burst`
+    pub kind: String,
+    /// 目录中显示的标题
+    pub title: String,
+    /// 起始行号（跳转锚点）
+    pub line_number: usize,
+    /// 结束行号（到下一个生命周期节点之前或最后一条错误）
+    pub end_line: usize,
+    /// 起始条目的时间戳
+    pub timestamp: Option<String>,
+    /// 起始条目的内容摘要
+    pub preview: String,
+    /// 错误集中区段的错误条目数（生命周期节点为0）
+    pub error_count: usize,
+}
+
+/// 日志大纲
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogOutline {
+    /// 按起始行号排列的区段
+    pub sections: Vec<OutlineSection>,
+    /// 条目总数
+    pub total_entries: usize,
+}
+
+fn display_text(entry: &LogLine) -> &str {
+    entry.formatted_content.as_deref().unwrap_or(&entry.content)
+}
+
+/// 是否为错误条目（ERROR / FATAL / SEVERE）
+pub fn is_error(entry: &LogLine) -> bool {
+    entry.level.as_deref().is_some_and(|level| matches!(level.to_uppercase().as_str(), "ERROR" | "FATAL" | "SEVERE"))
+}
+
+fn section(entry: &LogLine, kind: &str, title: String) -> OutlineSection {
+    OutlineSection {
+        kind: kind.to_string(),
+        title,
+        line_number: entry.line_number,
+        end_line: entry.line_number,
+        timestamp: entry.timestamp.clone(),
+        preview: truncate_chars(display_text(entry).trim(), 120),
+        error_count: 0,
+    }
+}
+
+/// 生成日志大纲
+///
+/// # 参数
+/// - `entries`: 解析后的条目（按行号排列）
+///
+/// # Returns
+/// - `LogOutline`: 生命周期节点和错误集中区段，按起始行号排列
+pub fn build_outline(entries: &[LogLine]) -> LogOutline {
+    let mut milestones: Vec<OutlineSection> = Vec::new();
+    let mut bursts: Vec<OutlineSection> = Vec::new();
+    let mut current_burst: Option<(OutlineSection, usize)> = None;
+    let last_line = entries.last().map_or(0, |entry| entry.line_number);
+
+    for entry in entries {
+        let text = display_text(entry);
+        if let Some((kind, title, _)) = LIFECYCLE_MARKERS.iter().find(|(_, _, pattern)| pattern.is_match(text)) {
+            // 连续的同类节点（如多行banner）只保留第一个
+            if milestones.last().is_none_or(|last| last.kind != *kind) {
+                milestones.push(section(entry, kind, title.to_string()));
+            }
+        }
+
+        if !is_error(entry) {
+            continue;
+        }
+        match &mut current_burst {
+            Some((burst, last_error)) if entry.line_number - *last_error <= BURST_MAX_GAP => {
+                burst.end_line = entry.line_number;
+                burst.error_count += 1;
+                *last_error = entry.line_number;
+            }
+            _ => {
+                if let Some((burst, _)) = current_burst.take() {
+                    bursts.push(burst);
+                }
+                let mut burst = section(entry, "error_burst", String::new());
+                burst.error_count = 1;
+                current_burst = Some((burst, entry.line_number));
+            }
+        }
+    }
+    if let Some((burst, _)) = current_burst {
+        bursts.push(burst);
+    }
+
+    // 生命周期区段延续到下一个节点之前
+    let next_starts: Vec<usize> = milestones.iter().skip(1).map(|s| s.line_number).collect();
+    for (index, milestone) in milestones.iter_mut().enumerate() {
+        milestone.end_line = next_starts.get(index).map_or(last_line, |next| next.saturating_sub(1).max(milestone.line_number));
+    }
+
+    let mut sections = milestones;
+    sections.extend(
+        bursts.into_iter()
+            .filter(|burst| burst.error_count >= BURST_MIN_ERRORS)
+            .map(|mut burst| {
+                burst.title = format!("错误集中（{} 条）", burst.error_count);
+                burst
+            }),
+    );
+    sections.sort_by_key(|section| (section.line_number, section.kind == "error_burst"));
+
+    LogOutline {
+        sections,
+        total_entries: entries.len(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(line_number: usize, level: &str, content: &str) -> LogLine {
+        LogLine { level: Some(level.to_string()), ..LogLine::new(line_number, content) }
+    }
+
+    #[test]
+    fn test_outline_lifecycle_and_error_bursts() {
+        let entries = vec![
+            entry(1, "INFO", " :: Spring Boot ::                (v3.2.0)"),
+            entry(2, "INFO", "Starting OrderApplication using Java 17.0.9 with PID 4242"),
+            entry(5, "INFO", "Root WebApplicationContext: initialization completed in 812 ms"),
+            entry(8, "INFO", "Tomcat started on port 8080 (http) with context path ''"),
+            entry(9, "INFO", "Started OrderApplication in 3.41 seconds (process running for 3.9)"),
+            entry(20, "ERROR", "Request failed"),
+            entry(22, "ERROR", "Request failed"),
+            entry(30, "ERROR", "Request failed"),
+            entry(100, "ERROR", "Isolated failure"),
+            entry(120, "INFO", "Graceful shutdown complete"),
+        ];
+
+        let outline = build_outline(&entries);
+        let summary: Vec<_> = outline.sections.iter()
+            .map(|s| (s.kind.as_str(), s.line_number, s.end_line, s.error_count))
+            .collect();
+        assert_eq!(summary, vec![
+            ("application_start", 1, 4, 0),
+            ("context_refreshed", 5, 7, 0),
+            ("server_started", 8, 8, 0),
+            ("application_started", 9, 119, 0),
+            ("error_burst", 20, 30, 3),
+            ("shutdown", 120, 120, 0),
+        ]);
+        assert_eq!(outline.sections[4].title, "错误集中（3 条）");
+    }
+}
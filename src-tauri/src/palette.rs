@@ -0,0 +1,205 @@
+/// 快速搜索面板
+///
+/// 全局快捷键呼出的搜索面板在最近打开的文件、固定条目（书签）和已保存的工作区会话
+/// （会话保存了各文件的过滤条件）中搜索。候选项在内存中逐个打分：标题以查询开头得分最高，
+/// 其次是在单词边界处出现、在任意位置出现，最后是按顺序包含查询的全部字符（模糊匹配，字符越紧凑得分越高）；
+/// 只有副标题匹配时得分减半。得分相同时保持候选顺序（最近使用的在前），查询为空时按该顺序返回。
+
+use crate::config::{PinnedEntry, RecentFile};
+use crate::session::SessionSummary;
+use serde::{Deserialize, Serialize};
+
+/// 默认返回的结果数
+pub const DEFAULT_PALETTE_LIMIT: usize = 20;
+
+/// 单次最多返回的结果数
+pub const MAX_PALETTE_LIMIT: usize = 200;
+
+/// 候选项类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PaletteItemKind {
+    /// 最近打开的文件
+    RecentFile,
+    /// 固定条目
+    Bookmark,
+    /// 已保存的工作区会话
+    Session,
+}
+
+/// 搜索面板的候选项
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PaletteItem {
+    /// 类型
+    pub kind: PaletteItemKind,
+    /// 标题（文件名、书签备注或预览、会话名称）
+    pub title: String,
+    /// 副标题（完整路径、书签位置、会话摘要）
+    pub subtitle: String,
+    /// 文件路径（最近文件和书签）
+    pub path: Option<String>,
+    /// 书签所在行号
+    pub line_number: Option<usize>,
+    /// 会话名称（会话和会话中的书签）
+    pub session: Option<String>,
+}
+
+impl PaletteItem {
+    /// 最近打开的文件：标题为文件名，副标题为完整路径
+    pub fn recent_file(file: &RecentFile) -> Self {
+        let name = std::path::Path::new(&file.path)
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| file.path.clone());
+        Self {
+            kind: PaletteItemKind::RecentFile,
+            title: name,
+            subtitle: file.path.clone(),
+            path: Some(file.path.clone()),
+            line_number: None,
+            session: None,
+        }
+    }
+
+    /// 固定条目：有备注时以备注为标题，否则以内容预览为标题
+    pub fn bookmark(file_path: &str, session: Option<&str>, pin: &PinnedEntry) -> Self {
+        Self {
+            kind: PaletteItemKind::Bookmark,
+            title: pin.note.clone().filter(|note| !note.trim().is_empty()).unwrap_or_else(|| pin.preview.clone()),
+            subtitle: format!("{}:{}", file_path, pin.line_number),
+            path: Some(file_path.to_string()),
+            line_number: Some(pin.line_number),
+            session: session.map(str::to_string),
+        }
+    }
+
+    /// 已保存的会话
+    pub fn session(summary: &SessionSummary) -> Self {
+        Self {
+            kind: PaletteItemKind::Session,
+            title: summary.name.clone(),
+            subtitle: format!("{} 个文件 · {}", summary.file_count, summary.saved_at),
+            path: None,
+            line_number: None,
+            session: Some(summary.name.clone()),
+        }
+    }
+}
+
+/// 搜索结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PaletteHit {
+    /// 匹配的候选项
+    #[serde(flatten)]
+    pub item: PaletteItem,
+    /// 得分（越高越靠前）
+    pub score: u32,
+    /// 标题中匹配的字符下标（按字符计，供前端高亮）
+    pub matched: Vec<usize>,
+}
+
+/// 按字符转为小写，保持下标与原文一一对应
+fn lower_chars(text: &str) -> Vec<char> {
+    text.chars().map(|c| c.to_lowercase().next().unwrap_or(c)).collect()
+}
+
+/// 计算查询在文本中的得分和匹配的字符下标
+///
+/// # 参数
+/// - `query`: 小写的查询字符（非空）
+/// - `text`: 候选文本
+fn match_text(query: &[char], text: &str) -> Option<(u32, Vec<usize>)> {
+    let text = lower_chars(text);
+    if query.len() > text.len() {
+        return None;
+    }
+    let span = |start: usize| (start..start + query.len()).collect::<Vec<usize>>();
+
+    let positions: Vec<usize> = (0..=text.len() - query.len())
+        .filter(|&start| text[start..start + query.len()] == *query)
+        .collect();
+    if positions.first() == Some(&0) {
+        return Some((300, span(0)));
+    }
+    if let Some(&start) = positions.iter().find(|&&start| !text[start - 1].is_alphanumeric()) {
+        return Some((200, span(start)));
+    }
+    if let Some(&start) = positions.first() {
+        return Some((100, span(start)));
+    }
+
+    // 模糊匹配：按顺序贪心匹配每个查询字符
+    let mut matched = Vec::with_capacity(query.len());
+    let mut chars = text.iter().enumerate();
+    for &q in query {
+        let (index, _) = chars.by_ref().find(|(_, &c)| c == q)?;
+        matched.push(index);
+    }
+    let gaps = matched[matched.len() - 1] - matched[0] + 1 - query.len();
+    Some((50u32.saturating_sub(gaps as u32).max(1), matched))
+}
+
+/// 在候选项中搜索
+///
+/// # 参数
+/// - `query`: 查询（忽略大小写和首尾空白）
+/// - `items`: 候选项（按最近使用排序）
+/// - `limit`: 最多返回的结果数（上限 `MAX_PALETTE_LIMIT`）
+pub fn search(query: &str, items: Vec<PaletteItem>, limit: usize) -> Vec<PaletteHit> {
+    let limit = limit.min(MAX_PALETTE_LIMIT);
+    let query = lower_chars(query.trim());
+    if query.is_empty() {
+        return items.into_iter()
+            .take(limit)
+            .map(|item| PaletteHit { item, score: 0, matched: Vec::new() })
+            .collect();
+    }
+
+    let mut hits: Vec<PaletteHit> = items.into_iter()
+        .filter_map(|item| {
+            let (score, matched) = match_text(&query, &item.title)
+                .or_else(|| match_text(&query, &item.subtitle).map(|(score, _)| (score / 2, Vec::new())))?;
+            Some(PaletteHit { item, score, matched })
+        })
+        .collect();
+    hits.sort_by_key(|hit| std::cmp::Reverse(hit.score));
+    hits.truncate(limit);
+    hits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recent(path: &str) -> PaletteItem {
+        PaletteItem::recent_file(&RecentFile { path: path.to_string(), opened_at: String::new() })
+    }
+
+    #[test]
+    fn test_palette_search_ranking() {
+        let items = vec![
+            recent("/var/log/nginx/error.log"),
+            recent("/srv/app/payment-service.log"),
+            recent("/srv/app/service.log"),
+            recent("/home/me/PaymentGateway.txt"),
+        ];
+        let titles = |hits: &[PaletteHit]| hits.iter().map(|hit| hit.item.title.clone()).collect::<Vec<_>>();
+
+        // 前缀 > 单词边界 > 子串；得分相同时保持最近使用顺序
+        let hits = search("serv", items.clone(), 10);
+        assert_eq!(titles(&hits), vec!["service.log", "payment-service.log"]);
+        assert_eq!(hits[1].matched, vec![8, 9, 10, 11]);
+
+        // 模糊匹配，忽略大小写
+        let hits = search("PGW", items.clone(), 10);
+        assert_eq!(titles(&hits), vec!["PaymentGateway.txt"]);
+        assert_eq!(hits[0].matched, vec![0, 7, 11]);
+
+        // 只有副标题（路径）匹配时得分减半
+        let hits = search("nginx", items.clone(), 10);
+        assert_eq!((titles(&hits), hits[0].score), (vec!["error.log".to_string()], 100));
+
+        assert_eq!(search("  ", items.clone(), 2).len(), 2);
+        assert!(search("zzz", items, 10).is_empty());
+    }
+}
@@ -52,6 +52,7 @@
 /// - **容错设计**：在不确定时提供保守的结果
 
 use crate::plugins::{LogParser, ParseRequest, ParseResult, LogLine};
+use crate::utils::string_utils;
 use std::collections::HashMap;
 
 /// 自动日志解析器实现
@@ -209,6 +210,7 @@ impl LogParser for AutoParser {
                     level,
                     timestamp,
                     formatted_content: Some(line.trim().to_string()),
+                    message: None,
                     metadata,
                     processed_by: vec!["auto_parser".to_string()],
                 }
@@ -220,6 +222,8 @@ impl LogParser for AutoParser {
             total_lines: content.lines().count(),
             detected_format: Some("auto".to_string()),
             parsing_errors: Vec::new(),
+            applied_filters: Vec::new(),
+            capabilities: None,
         })
     }
 }
@@ -318,8 +322,8 @@ fn extract_level_and_timestamp(line: &str) -> (Option<String>, Option<String>) {
     };
 
     // 简单的时间戳提取
-    let timestamp = if line.len() > 20 {
-        Some(line[..20].to_string())
+    let timestamp = if line.chars().nth(20).is_some() {
+        Some(string_utils::prefix_chars(line, 20).to_string())
     } else {
         None
     };
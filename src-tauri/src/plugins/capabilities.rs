@@ -0,0 +1,221 @@
+/// 插件能力协商
+///
+/// 过滤器通过 `PluginFilter::capabilities` 声明能处理的最大文件大小和性能等级，
+/// 插件链的能力取链中最严格的大小上限和最慢的性能等级。
+/// 自动选择插件链时跳过声明无法处理当前文件大小的链，多个链匹配度接近时优先较快的链；
+/// 用户指定的链即使超出大小上限也照常执行，只在结果中给出警告。
+/// 协商结果随 `ParseResult::capabilities` 返回，前端据此提示用户为什么选中了某个链。
+
+use crate::i18n::Message;
+use crate::plugins::ParseRequest;
+use serde::{Deserialize, Serialize};
+
+/// 匹配度相差不超过该值的链视为同样匹配，此时优先性能等级较快的链
+pub const SCORE_TIE_MARGIN: f32 = 0.05;
+
+/// 性能等级（从快到慢排序）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PerformanceRating {
+    /// 逐行独立匹配
+    #[default]
+    Fast,
+    /// 需要跨行合并（折行、堆栈）
+    Moderate,
+    /// 需要缓冲整段内容后再处理
+    Slow,
+}
+
+/// 插件声明的处理能力
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginCapabilities {
+    /// 能处理的最大文件大小（字节，None表示不限）
+    pub max_file_size: Option<u64>,
+    /// 性能等级
+    pub performance: PerformanceRating,
+}
+
+impl PluginCapabilities {
+    /// 创建能力声明
+    ///
+    /// # 参数
+    /// - `max_file_size`: 能处理的最大文件大小（字节，None表示不限）
+    /// - `performance`: 性能等级
+    pub fn new(max_file_size: Option<u64>, performance: PerformanceRating) -> Self {
+        Self { max_file_size, performance }
+    }
+
+    /// 合并两个能力声明：取较小的大小上限和较慢的性能等级
+    pub fn combine(self, other: Self) -> Self {
+        let max_file_size = match (self.max_file_size, other.max_file_size) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        Self { max_file_size, performance: self.performance.max(other.performance) }
+    }
+
+    /// 是否能处理指定大小的文件
+    pub fn accepts(&self, file_size: u64) -> bool {
+        self.max_file_size.is_none_or(|max| file_size <= max)
+    }
+
+    /// 文件超出大小上限时的说明
+    ///
+    /// # 参数
+    /// - `name`: 插件或插件链名称
+    /// - `file_size`: 文件大小（字节）
+    pub fn size_warning(&self, name: &str, file_size: u64) -> Option<String> {
+        let max = self.max_file_size.filter(|&max| file_size > max)?;
+        Some(Message::new("chain.file_too_large")
+            .with("chain", name)
+            .with("max", max / (1024 * 1024))
+            .with("size", file_size.div_ceil(1024 * 1024))
+            .text())
+    }
+}
+
+/// 能力协商结果
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CapabilityDecision {
+    /// 参与协商的文件大小（字节）
+    pub file_size: u64,
+    /// 选中的插件链
+    pub selected: Option<String>,
+    /// 选中链的能力
+    pub capabilities: PluginCapabilities,
+    /// 因文件超出大小上限而跳过的链
+    pub skipped: Vec<String>,
+    /// 匹配度接近但较慢、因此没有选中的链
+    pub slower_alternatives: Vec<String>,
+    /// 警告（选中的链超出其声明的大小上限时）
+    pub warning: Option<String>,
+}
+
+impl CapabilityDecision {
+    /// 创建协商结果
+    ///
+    /// # 参数
+    /// - `file_size`: 文件大小（字节）
+    pub fn new(file_size: u64) -> Self {
+        Self { file_size, ..Default::default() }
+    }
+
+    /// 记录选中的链；超出其大小上限时记录警告
+    pub fn select(&mut self, name: &str, capabilities: PluginCapabilities) {
+        self.selected = Some(name.to_string());
+        self.capabilities = capabilities;
+        self.warning = capabilities.size_warning(name, self.file_size);
+        if let Some(warning) = &self.warning {
+            log::warn!("⚠️ {}", warning);
+        }
+    }
+
+    /// 记录因文件过大跳过的链
+    pub fn skip(&mut self, name: &str) {
+        log::warn!("⏭️ 文件大小 {} 字节超出插件链 '{}' 声明的上限，跳过", self.file_size, name);
+        self.skipped.push(name.to_string());
+    }
+}
+
+/// 参与评分的候选链
+#[derive(Debug, Clone)]
+pub struct Candidate<'a> {
+    /// 链名称
+    pub name: &'a str,
+    /// 匹配度
+    pub score: f32,
+    /// 链的能力
+    pub capabilities: PluginCapabilities,
+}
+
+/// 在候选链中协商选择
+///
+/// 跳过不能处理该文件大小的候选；在剩余候选中，与最高匹配度相差不超过 `SCORE_TIE_MARGIN` 的
+/// 视为同样匹配，从中选性能等级最快的（等级相同时取匹配度较高者，再按候选的顺序）。
+///
+/// # 参数
+/// - `candidates`: 候选链
+/// - `decision`: 协商结果，记录跳过的链和较慢的备选
+///
+/// # Returns
+/// - `Option<usize>`: 选中候选的下标；没有可用候选时返回None
+pub fn choose(candidates: &[Candidate], decision: &mut CapabilityDecision) -> Option<usize> {
+    let mut accepted = Vec::new();
+    for (index, candidate) in candidates.iter().enumerate() {
+        if candidate.capabilities.accepts(decision.file_size) {
+            accepted.push(index);
+        } else {
+            decision.skip(candidate.name);
+        }
+    }
+    decision.skipped.sort();
+
+    let best = accepted.iter().map(|&i| candidates[i].score).fold(f32::NEG_INFINITY, f32::max);
+    let tied: Vec<usize> = accepted.into_iter()
+        .filter(|&i| candidates[i].score >= best - SCORE_TIE_MARGIN)
+        .collect();
+    let chosen = tied.iter().copied().min_by(|&a, &b| {
+        let (first, second) = (&candidates[a], &candidates[b]);
+        first.capabilities.performance.cmp(&second.capabilities.performance)
+            .then(second.score.total_cmp(&first.score))
+            .then(a.cmp(&b))
+    })?;
+
+    let performance = candidates[chosen].capabilities.performance;
+    decision.slower_alternatives = tied.into_iter()
+        .filter(|&i| candidates[i].capabilities.performance > performance)
+        .map(|i| candidates[i].name.to_string())
+        .collect();
+    decision.slower_alternatives.sort();
+    Some(chosen)
+}
+
+/// 请求对应的文件大小：文件模式取磁盘上的文件大小（分块解析时内容只是其中一块），否则取内容长度
+///
+/// # 参数
+/// - `content`: 要处理的内容
+/// - `request`: 解析请求
+pub fn request_file_size(content: &str, request: &ParseRequest) -> u64 {
+    request.file_path.as_deref()
+        .and_then(|path| std::fs::metadata(path).ok())
+        .filter(|metadata| metadata.is_file())
+        .map(|metadata| metadata.len())
+        .unwrap_or(content.len() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MB: u64 = 1024 * 1024;
+
+    #[test]
+    fn test_choose_skips_oversized_and_prefers_faster() {
+        let slow_limited = PluginCapabilities::new(Some(100 * MB), PerformanceRating::Slow);
+        let candidates = vec![
+            Candidate { name: "test_runner", score: 0.95, capabilities: slow_limited },
+            Candidate { name: "springboot", score: 0.92, capabilities: PluginCapabilities::new(None, PerformanceRating::Moderate) },
+            Candidate { name: "generic", score: 0.90, capabilities: PluginCapabilities::default() },
+            Candidate { name: "database", score: 0.5, capabilities: PluginCapabilities::default() },
+        ];
+
+        // 小文件：匹配度接近的三个链中选最快的generic
+        let mut decision = CapabilityDecision::new(MB);
+        assert_eq!(choose(&candidates, &mut decision), Some(2));
+        assert_eq!(decision.slower_alternatives, vec!["springboot", "test_runner"]);
+        assert!(decision.skipped.is_empty());
+
+        // 大文件：test_runner超出上限被跳过
+        let mut decision = CapabilityDecision::new(200 * MB);
+        assert_eq!(choose(&candidates[..2], &mut decision), Some(1));
+        assert_eq!(decision.skipped, vec!["test_runner"]);
+        assert!(decision.slower_alternatives.is_empty());
+        assert_eq!(choose(&candidates[..1], &mut CapabilityDecision::new(200 * MB)), None);
+
+        let combined = slow_limited.combine(PluginCapabilities::new(Some(50 * MB), PerformanceRating::Fast));
+        assert_eq!(combined, PluginCapabilities::new(Some(50 * MB), PerformanceRating::Slow));
+        let mut forced = CapabilityDecision::new(200 * MB);
+        forced.select("test_runner", slow_limited);
+        assert!(forced.warning.unwrap().contains("100 MB"));
+    }
+}
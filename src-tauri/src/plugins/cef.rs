@@ -0,0 +1,342 @@
+/// CEF / LEEF 安全日志解析
+///
+/// SIEM和安全设备导出的事件常用两种格式，前面可以带syslog前缀：
+///
+/// ```text
+/// Jan 15 10:30:25 fw01 CEF:0|Palo Alto Networks|PAN-OS|10.1|THREAT|Port scan detected|8|src=10.0.0.5 dst=10.0.0.9 spt=51234 act=blocked msg=Scan from 10.0.0.5
+/// LEEF:1.0|Microsoft|MSExchange|2016|LOGON_FAILED|src=10.0.0.7\tusrName=alice\tsev=6
+/// LEEF:2.0|Lancope|StealthWatch|1.0|41|^|src=10.0.0.8^dst=10.0.0.9^sev=9
+/// ```
+///
+/// CEF头部以 `|` 分隔（值中的 `|` 写作 `\|`），扩展部分是空格分隔的 `键=值`，值本身可以包含空格，
+/// 到下一个 `键=` 为止。LEEF 1.0的扩展以制表符分隔，LEEF 2.0在头部第6个字段指定分隔符（单字符或 `x5E` 形式）。
+///
+/// # 级别
+/// CEF的严重程度（头部第7个字段）和LEEF的 `sev` 字段按0-10映射：0-3为INFO，4-6为WARN，7-10为ERROR；
+/// CEF也可以直接写 `Low` / `Medium` / `High` / `Very-High`。
+///
+/// # 元数据
+/// - `security_format`: `cef` 或 `leef`
+/// - `vendor`、`product`、`product_version`、`signature_id`: 头部字段（LEEF的事件ID记录在 `signature_id`）
+/// - `event_name`: CEF头部的事件名称
+/// - `severity` / `severity_label`: 原始严重程度和对应的 Low / Medium / High / Very-High
+/// - 扩展中的键值对按原键名写入（如 `src`、`dst`、`act`、`suser`）
+
+use crate::plugins::chain::{PluginChainContext, PluginFilter};
+use crate::plugins::{LogLine, ParseRequest};
+use chrono::DateTime;
+use log::info;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::HashMap;
+
+/// 头部起始：`CEF:0|` 或 `LEEF:1.0|`，前面可以是syslog前缀
+static HEADER_START: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?:^|\s)(CEF|LEEF):(\d+(?:\.\d+)?)\|").unwrap()
+});
+
+/// CEF扩展中的键：前面是行首或空白，`=` 未被转义
+static CEF_EXTENSION_KEY: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?:^|\s)([\w.\[\]-]+)=").unwrap()
+});
+
+/// syslog前缀中的时间：`Jan 15 10:30:25` 或 ISO 8601
+static SYSLOG_TIME: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(?:<\d+>)?(?:\d+ )?([A-Z][a-z]{2} +\d{1,2} \d{2}:\d{2}:\d{2}|\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}:\d{2}(?:\.\d+)?(?:Z|[+-]\d{2}:?\d{2})?)").unwrap()
+});
+
+/// 记录事件时间的扩展字段，按顺序取第一个
+const TIME_FIELDS: &[&str] = &["rt", "devTime", "start", "end"];
+
+/// 把0-10的严重程度映射为 (日志级别, 严重程度名称)
+///
+/// # 参数
+/// - `severity`: 数字（CEF头部、LEEF的 `sev`）或CEF的名称形式
+///
+/// # Returns
+/// - `Option<(&str, &str)>`: 无法识别（如 `Unknown`）时返回None
+pub fn severity_level(severity: &str) -> Option<(&'static str, &'static str)> {
+    let score = match severity.trim().to_ascii_lowercase().as_str() {
+        "low" => 0,
+        "medium" => 4,
+        "high" => 7,
+        "very-high" | "veryhigh" => 9,
+        other => other.parse::<u8>().ok().filter(|score| *score <= 10)?,
+    };
+    Some(match score {
+        0..=3 => ("INFO", "Low"),
+        4..=6 => ("WARN", "Medium"),
+        7..=8 => ("ERROR", "High"),
+        _ => ("ERROR", "Very-High"),
+    })
+}
+
+/// 按未转义的 `|` 拆分头部，最多拆出 `count` 个字段，返回字段和剩余的扩展部分
+fn split_header(text: &str, count: usize) -> Option<(Vec<String>, &str)> {
+    let mut fields = Vec::with_capacity(count);
+    let mut current = String::new();
+    let mut chars = text.char_indices();
+    while let Some((_, c)) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some((_, escaped @ ('|' | '\\'))) => current.push(escaped),
+                Some((_, other)) => {
+                    current.push('\\');
+                    current.push(other);
+                }
+                None => current.push('\\'),
+            },
+            '|' => {
+                fields.push(std::mem::take(&mut current));
+                if fields.len() == count {
+                    let rest = chars.next().map_or("", |(i, _)| &text[i..]);
+                    return Some((fields, rest));
+                }
+            }
+            _ => current.push(c),
+        }
+    }
+    None
+}
+
+/// 还原CEF扩展值中的转义
+fn unescape_value(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('r') => result.push('\r'),
+            Some(other) => result.push(other),
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+/// 解析CEF扩展：值延续到下一个 `键=` 之前
+fn parse_cef_extension(text: &str) -> Vec<(String, String)> {
+    let keys: Vec<_> = CEF_EXTENSION_KEY.captures_iter(text)
+        .map(|c| {
+            let key = c.get(1).unwrap();
+            (key.as_str().to_string(), c.get(0).unwrap().start(), key.end() + 1)
+        })
+        .collect();
+    keys.iter().enumerate()
+        .map(|(index, (key, _, value_start))| {
+            let value_end = keys.get(index + 1).map_or(text.len(), |(_, start, _)| *start);
+            (key.clone(), unescape_value(text[*value_start..value_end].trim()))
+        })
+        .collect()
+}
+
+/// LEEF 2.0头部中的分隔符：单个字符或 `x5E` / `0x5E` 形式的十六进制
+fn leef_delimiter(field: &str) -> char {
+    let hex = field.strip_prefix("0x").or_else(|| field.strip_prefix('x'));
+    match hex {
+        Some(hex) if !hex.is_empty() => u32::from_str_radix(hex, 16).ok().and_then(char::from_u32).unwrap_or('\t'),
+        _ => field.chars().next().unwrap_or('\t'),
+    }
+}
+
+/// 解析LEEF扩展：按分隔符拆分后取第一个 `=` 两侧
+fn parse_leef_extension(text: &str, delimiter: char) -> Vec<(String, String)> {
+    text.split(delimiter)
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .filter(|(key, _)| !key.is_empty())
+        .collect()
+}
+
+/// 事件时间：毫秒时间戳转换为可读时间，其他写法原样保留
+fn event_time(value: &str) -> String {
+    value.parse::<i64>().ok()
+        .filter(|millis| *millis > 100_000_000_000)
+        .and_then(DateTime::from_timestamp_millis)
+        .map(|time| time.format("%Y-%m-%d %H:%M:%S%.3f").to_string())
+        .unwrap_or_else(|| value.to_string())
+}
+
+/// CEF/LEEF安全日志过滤器
+pub struct CefLeefFilter;
+
+impl CefLeefFilter {
+    /// 行是否带有CEF/LEEF头部
+    pub fn matches_line(line: &str) -> bool {
+        HEADER_START.is_match(line)
+    }
+
+    /// 内容是否像CEF/LEEF安全日志
+    pub fn looks_like_security_log(content: &str) -> bool {
+        content.lines().take(200).any(Self::matches_line)
+    }
+
+    /// 处理单行，返回是否识别为CEF/LEEF事件
+    fn tag_line(line: &mut LogLine, strict: bool) -> bool {
+        let Some(caps) = HEADER_START.captures(line.message_text()) else {
+            return false;
+        };
+        let format = caps[1].to_ascii_lowercase();
+        let version = caps[2].to_string();
+        let prefix = line.message_text()[..caps.get(0).unwrap().start()].trim().to_string();
+        let body = &line.message_text()[caps.get(0).unwrap().end()..];
+
+        // CEF: 厂商|产品|版本|签名ID|名称|严重程度|扩展
+        // LEEF 1.0: 厂商|产品|版本|事件ID|扩展；LEEF 2.0在事件ID后多一个分隔符字段
+        let header_count = match (format.as_str(), version.starts_with('2')) {
+            ("cef", _) => 6,
+            (_, true) => 5,
+            _ => 4,
+        };
+        let Some((header, extension)) = split_header(body, header_count) else {
+            return false;
+        };
+        let extension = match format.as_str() {
+            "cef" => parse_cef_extension(extension),
+            _ => parse_leef_extension(extension, header.get(4).map_or('\t', |d| leef_delimiter(d))),
+        };
+
+        let mut metadata: HashMap<String, String> = extension.iter().cloned().collect();
+        metadata.insert("security_format".to_string(), format.clone());
+        for (key, value) in ["vendor", "product", "product_version", "signature_id"].iter().zip(&header) {
+            metadata.insert(key.to_string(), value.clone());
+        }
+        let severity = if format == "cef" {
+            metadata.insert("event_name".to_string(), header[4].clone());
+            Some(header[5].clone())
+        } else {
+            metadata.get("sev").cloned()
+        };
+        if let Some(severity) = severity {
+            if let Some((level, label)) = severity_level(&severity) {
+                line.level = Some(level.to_string());
+                metadata.insert("severity_label".to_string(), label.to_string());
+            }
+            metadata.insert("severity".to_string(), severity);
+        }
+
+        line.timestamp = TIME_FIELDS.iter()
+            .find_map(|field| metadata.get(*field))
+            .map(|value| event_time(value))
+            .or_else(|| SYSLOG_TIME.captures(&prefix).map(|c| c[1].to_string()));
+
+        let title = metadata.get("event_name").unwrap_or(&header[3]).clone();
+        let mut content = format!("[{} {}] {}", header[0], header[1], title);
+        if let Some(message) = metadata.get("msg").filter(|m| !m.is_empty()) {
+            content.push_str(": ");
+            content.push_str(message);
+        }
+        line.metadata.extend(metadata);
+        line.set_message(content, strict);
+        true
+    }
+}
+
+impl PluginFilter for CefLeefFilter {
+    fn name(&self) -> &str {
+        "cef"
+    }
+
+    fn description(&self) -> &str {
+        "CEF/LEEF安全日志过滤器，解析头部的厂商、产品和严重程度以及扩展键值对"
+    }
+
+    fn priority(&self) -> i32 {
+        20 // 格式解析，在ANSI清理之后执行
+    }
+
+    fn should_process(&self, context: &PluginChainContext) -> bool {
+        if context.current_lines.is_empty() {
+            return Self::looks_like_security_log(&context.original_content);
+        }
+        context.current_lines.iter().take(200).any(|line| HEADER_START.is_match(line.message_text()))
+    }
+
+    fn process(&self, context: &mut PluginChainContext, request: &ParseRequest) -> Result<(), String> {
+        info!("🛡️ CEF/LEEF安全日志过滤器开始处理");
+        let strict = request.is_strict();
+
+        let mut lines = if context.current_lines.is_empty() {
+            // 第一次处理，从原始内容创建行列表，过滤空行
+            context.original_content.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()).map(|(i, line)| {
+                LogLine {
+                    line_number: i + 1,
+                    content: line.to_string(),
+                    level: None,
+                    timestamp: None,
+                    formatted_content: None,
+                    message: None,
+                    metadata: HashMap::new(),
+                    processed_by: vec![],
+                }
+            }).collect()
+        } else {
+            std::mem::take(&mut context.current_lines)
+        };
+
+        let mut tagged = 0;
+        for line in &mut lines {
+            if Self::tag_line(line, strict) {
+                line.processed_by.push("cef_filter".to_string());
+                line.formatted_content = Some(line.message_text().to_string());
+                tagged += 1;
+            }
+        }
+
+        context.current_lines = lines;
+        info!("🛡️ CEF/LEEF安全日志过滤器处理完成，识别了 {} 个事件", tagged);
+        Ok(())
+    }
+
+    fn can_handle(&self, content: &str, _file_path: Option<&str>) -> bool {
+        Self::looks_like_security_log(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::chain::PluginChainManager;
+    use crate::plugins::presets::register_preset_chains;
+
+    #[test]
+    fn test_parses_cef_and_leef_events() {
+        let content = "Jan 15 10:30:25 fw01 CEF:0|Palo Alto Networks|PAN\\|OS|10.1|THREAT|Port scan detected|8|src=10.0.0.5 dst=10.0.0.9 act=blocked msg=Scan from 10.0.0.5 a\\=b\n\
+LEEF:1.0|Microsoft|MSExchange|2016|LOGON_FAILED|src=10.0.0.7\tusrName=alice\tsev=5\tdevTime=1705314625000\n\
+LEEF:2.0|Lancope|StealthWatch|1.0|41|x5E|src=10.0.0.8^dst=10.0.0.9^sev=2";
+
+        let mut manager = PluginChainManager::new();
+        register_preset_chains(&mut manager);
+        let result = manager.process(content, &ParseRequest::default()).unwrap();
+        assert_eq!(result.detected_format.as_deref(), Some("cef"));
+
+        let lines = &result.lines;
+        let meta = |index: usize, key: &str| lines[index].metadata.get(key).map(String::as_str);
+        assert_eq!(lines[0].content, "[Palo Alto Networks PAN|OS] Port scan detected: Scan from 10.0.0.5 a=b");
+        assert_eq!(lines[0].level.as_deref(), Some("ERROR"));
+        assert_eq!(lines[0].timestamp.as_deref(), Some("Jan 15 10:30:25"));
+        assert_eq!((meta(0, "signature_id"), meta(0, "severity_label")), (Some("THREAT"), Some("High")));
+        assert_eq!((meta(0, "src"), meta(0, "act")), (Some("10.0.0.5"), Some("blocked")));
+
+        assert_eq!(meta(1, "security_format"), Some("leef"));
+        assert_eq!(lines[1].content, "[Microsoft MSExchange] LOGON_FAILED");
+        assert_eq!(lines[1].level.as_deref(), Some("WARN"));
+        assert_eq!(lines[1].timestamp.as_deref(), Some("2024-01-15 10:30:25.000"));
+        assert_eq!(meta(1, "usrName"), Some("alice"));
+
+        assert_eq!(lines[2].level.as_deref(), Some("INFO"));
+        assert_eq!((meta(2, "signature_id"), meta(2, "dst")), (Some("41"), Some("10.0.0.9")));
+    }
+
+    #[test]
+    fn test_severity_level() {
+        assert_eq!(severity_level("10"), Some(("ERROR", "Very-High")));
+        assert_eq!(severity_level("Medium"), Some(("WARN", "Medium")));
+        assert_eq!(severity_level("Unknown"), None);
+        assert_eq!(severity_level("11"), None);
+    }
+}
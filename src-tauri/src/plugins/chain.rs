@@ -23,27 +23,121 @@
 /// - **内存优化**：流式处理，避免大量内存占用
 /// - **缓存机制**：缓存常用处理结果（未来功能）
 
-use crate::plugins::{ParseRequest, ParseResult, LogLine};
-use std::collections::HashMap;
+use crate::i18n::Message;
+use crate::plugins::{ParseRequest, ParseResult, LogLine, PluginInfo, PluginKind, PLUGIN_VERSION};
+use crate::plugins::capabilities::{choose, request_file_size, CapabilityDecision, Candidate, PluginCapabilities};
+use crate::plugins::detection;
+use serde::Serialize;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 use log::{debug, info, warn, error};
 
+/// 多行记录的结束行号元数据键
+///
+/// 把后续行合并进来的过滤器应在条目上记录最后一行的行号，
+/// 分块处理时据此判断从重叠区开始的记录是否延续到了当前块。
+pub const END_LINE_METADATA_KEY: &str = "end_line";
+
+/// 解析失败的元数据键
+///
+/// 过滤器无法解析某一行时保留原始内容，并在条目上记录失败原因，
+/// 插件链完成后这些行汇总到 `ParseResult.parsing_errors`，响应统计中计为错误行。
+pub const PARSE_ERROR_METADATA_KEY: &str = "parse_error";
+
+/// 标记了解析失败的条目（行号和失败原因）
+pub fn line_parse_errors(lines: &[LogLine]) -> Vec<(usize, &str)> {
+    lines.iter()
+        .filter_map(|line| line.metadata.get(PARSE_ERROR_METADATA_KEY).map(|error| (line.line_number, error.as_str())))
+        .collect()
+}
+
+/// 分块位置信息
+///
+/// 分块解析时，内容开头会携带上一块末尾的 `overlap` 行作为上下文，
+/// 使跨越块边界的多行记录（如堆栈的续行）能找到所属的记录头。
+/// 链执行完成后，完全位于重叠区的条目会被丢弃，行号换算为整个文件中的行号。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ChunkPosition {
+    /// 块索引（从0开始）
+    pub index: usize,
+    /// 块大小（行数）
+    pub size: usize,
+    /// 块之前的行数（不含重叠行）
+    pub start: usize,
+    /// 内容开头携带的上一块末尾行数
+    pub overlap: usize,
+}
+
+impl ChunkPosition {
+    /// 从解析请求中读取分块位置，未设置 `chunk_index` 时返回None
+    pub fn from_request(request: &ParseRequest) -> Option<Self> {
+        let index = request.chunk_index?;
+        let size = request.chunk_size.unwrap_or(0);
+        // 自适应分块的块大小不固定，起始位置由请求给出
+        let start = request.chunk_start.unwrap_or(index * size);
+        // 重叠行不能超过当前块之前的总行数
+        let overlap = request.chunk_overlap.unwrap_or(0).min(start);
+        Some(Self { index, size, start, overlap })
+    }
+
+    /// 内容第一行之前的行数（内容中的行号加上该值即为文件中的行号）
+    pub fn line_offset(&self) -> usize {
+        self.start - self.overlap
+    }
+
+    /// 内容中的行号（从1开始）是否位于重叠区
+    pub fn is_overlap_line(&self, line_number: usize) -> bool {
+        line_number <= self.overlap
+    }
+
+    /// 链执行完成后整理条目
+    ///
+    /// - 丢弃完全位于重叠区的条目（它们属于上一块）
+    /// - 从重叠区开始、延续到当前块的多行记录保留，并标记 `chunk_continued=true`，
+    ///   前端应使用它替换上一块中同一行号的条目
+    /// - 行号（以及 `end_line`）换算为文件中的行号
+    pub fn finish(&self, lines: &mut Vec<LogLine>) {
+        lines.retain(|line| {
+            let end_line = line.metadata.get(END_LINE_METADATA_KEY)
+                .and_then(|end| end.parse::<usize>().ok())
+                .unwrap_or(line.line_number);
+            !self.is_overlap_line(end_line)
+        });
+
+        let offset = self.line_offset();
+        for line in lines.iter_mut() {
+            if self.is_overlap_line(line.line_number) {
+                line.metadata.insert("chunk_continued".to_string(), "true".to_string());
+            }
+            line.line_number += offset;
+            if let Some(end) = line.metadata.get_mut(END_LINE_METADATA_KEY) {
+                if let Ok(value) = end.parse::<usize>() {
+                    *end = (value + offset).to_string();
+                }
+            }
+        }
+    }
+}
+
 /// 插件链上下文
 ///
 /// 包含插件链处理过程中的所有状态信息，包括原始数据、中间结果和最终输出。
 /// 这个上下文会在插件链中传递，每个插件都可以读取和修改其中的数据。
 ///
 /// # 字段说明
-/// - `original_content`: 原始日志内容（只读，用于调试）
+/// - `original_content`: 原始日志内容（只读，执行插件链时借用请求中的内容，不复制）
 /// - `current_lines`: 当前处理的日志行列表（可修改）
 /// - `processing_chain`: 已执行的插件名称列表
 /// - `chain_metadata`: 插件链级别的元数据
 /// - `should_continue`: 是否继续执行后续插件
 /// - `errors`: 处理过程中收集的错误信息
+/// - `chunk`: 分块解析时的块位置（行号在链执行期间相对于内容，完成后才换算）
+/// - `merge_wrapped_lines`: 是否合并终端折行
 #[derive(Debug, Clone)]
-pub struct PluginChainContext {
+pub struct PluginChainContext<'a> {
     /// 原始日志内容（用于调试和回溯）
-    pub original_content: String,
+    pub original_content: Cow<'a, str>,
 
     /// 当前处理的日志行列表（会被插件修改）
     pub current_lines: Vec<LogLine>,
@@ -59,24 +153,32 @@ pub struct PluginChainContext {
 
     /// 处理过程中收集的错误信息
     pub errors: Vec<String>,
+
+    /// 分块解析时的块位置（非分块解析时为None）
+    pub chunk: Option<ChunkPosition>,
+
+    /// 是否合并终端折行（来自请求的 `merge_wrapped_lines`）
+    pub merge_wrapped_lines: bool,
 }
 
-impl PluginChainContext {
+impl<'a> PluginChainContext<'a> {
     /// 创建新的插件链上下文
     ///
     /// # 参数
-    /// - `content`: 原始日志内容
+    /// - `content`: 原始日志内容（借用的 `&str` 或拥有的 `String`）
     ///
     /// # Returns
     /// - `Self`: 新创建的上下文实例
-    pub fn new(content: String) -> Self {
+    pub fn new(content: impl Into<Cow<'a, str>>) -> Self {
         Self {
-            original_content: content.clone(),
+            original_content: content.into(),
             current_lines: Vec::new(),
             processing_chain: Vec::new(),
             chain_metadata: HashMap::new(),
             should_continue: true,
             errors: Vec::new(),
+            chunk: None,
+            merge_wrapped_lines: false,
         }
     }
 
@@ -148,7 +250,6 @@ pub trait PluginFilter {
     ///
     /// # Returns
     /// - `&str`: 过滤器的用户友好描述
-    #[allow(dead_code)]
     fn description(&self) -> &str;
 
     /// 返回过滤器的优先级
@@ -202,6 +303,26 @@ pub trait PluginFilter {
     /// # Returns
     /// - `bool`: true表示可以处理，false表示不适合
     fn can_handle(&self, content: &str, file_path: Option<&str>) -> bool;
+
+    /// 返回过滤器可配置项的JSON Schema
+    ///
+    /// 声明了配置项的过滤器会在构建插件链时收到用户设置（见 `plugins::settings`）。
+    ///
+    /// # Returns
+    /// - `Option<serde_json::Value>`: 配置项Schema，没有可配置项时返回None
+    fn settings_schema(&self) -> Option<serde_json::Value> {
+        None
+    }
+
+    /// 返回过滤器能处理的最大文件大小和性能等级
+    ///
+    /// 自动选择插件链时据此跳过不能处理当前文件的链，并在匹配度接近时优先较快的链。
+    ///
+    /// # Returns
+    /// - `PluginCapabilities`: 默认不限大小、逐行处理
+    fn capabilities(&self) -> PluginCapabilities {
+        PluginCapabilities::default()
+    }
 }
 
 /// 插件链定义
@@ -359,6 +480,13 @@ impl PluginChain {
         self.filters.sort_by_key(|f| f.priority());
     }
 
+    /// 链的处理能力：取各过滤器中最小的文件大小上限和最慢的性能等级
+    pub fn capabilities(&self) -> PluginCapabilities {
+        self.filters.iter()
+            .map(|filter| filter.capabilities())
+            .fold(PluginCapabilities::default(), PluginCapabilities::combine)
+    }
+
     /// 执行插件链处理
     ///
     /// 按顺序执行链中的所有过滤器，直到所有过滤器完成或链被停止。
@@ -381,19 +509,37 @@ impl PluginChain {
 
         if !self.enabled {
             warn!("⚠️ 插件链 '{}' 已禁用，跳过处理", self.name);
-            return Err(format!("插件链 '{}' 已禁用", self.name));
+            return Err(Message::new("chain.disabled").with("chain", &self.name).text());
         }
 
         // 检查执行条件
         if let Some(conditions) = &self.conditions {
             if !conditions.matches(content, request.file_path.as_deref()) {
                 debug!("🔍 插件链 '{}' 执行条件不匹配，跳过处理", self.name);
-                return Err(format!("插件链 '{}' 执行条件不匹配", self.name));
+                return Err(Message::new("chain.conditions_not_met").with("chain", &self.name).text());
             }
         }
 
+        self.run_filters(content, request, start_time)
+    }
+
+    /// 执行插件链处理，不检查执行条件
+    ///
+    /// # Returns
+    /// - `Result<ParseResult, String>`: 处理结果；链已禁用时返回错误
+    pub fn run(&self, content: &str, request: &ParseRequest) -> Result<ParseResult, String> {
+        info!("🔗 开始执行插件链（跳过条件检查）: {}", self.name);
+        if !self.enabled {
+            return Err(Message::new("chain.disabled").with("chain", &self.name).text());
+        }
+        self.run_filters(content, request, std::time::Instant::now())
+    }
+
+    fn run_filters(&self, content: &str, request: &ParseRequest, start_time: std::time::Instant) -> Result<ParseResult, String> {
         // 创建处理上下文
-        let mut context = PluginChainContext::new(content.to_string());
+        let mut context = PluginChainContext::new(content);
+        context.chunk = ChunkPosition::from_request(request);
+        context.merge_wrapped_lines = request.merge_wrapped_lines;
 
         // 执行过滤器链
         for filter in &self.filters {
@@ -421,7 +567,7 @@ impl PluginChain {
                 Err(e) => {
                     let filter_time = filter_start.elapsed();
                     error!("❌ 过滤器 '{}' 执行失败: {}, 耗时: {}ms", filter.name(), e, filter_time.as_millis());
-                    context.add_error(format!("过滤器 '{}' 失败: {}", filter.name(), e));
+                    context.add_error(Message::new("chain.filter_failed").with("filter", filter.name()).with("error", e).text());
 
                     // 根据错误策略决定是否继续
                     // 目前选择继续执行，可以配置为遇到错误就停止
@@ -435,14 +581,257 @@ impl PluginChain {
         info!("📊 处理统计: {} 个过滤器，{} 条日志，{} 个错误",
               context.processing_chain.len(), context.current_lines.len(), context.errors.len());
 
+        // 标记多行记录的范围，分块解析据此保留跨块的记录
+        crate::records::mark_record_spans(&mut context.current_lines);
+
+        // 分块解析：丢弃重叠区的条目并换算行号
+        if let Some(chunk) = context.chunk {
+            chunk.finish(&mut context.current_lines);
+        }
+
+        // 汇总逐行的解析失败（行号已换算为文件中的行号）
+        for (line_number, error) in line_parse_errors(&context.current_lines) {
+            context.errors.push(Message::new("chain.line_failed").with("line", line_number).with("error", error).text());
+        }
+
         // 构建最终结果
         Ok(ParseResult {
             lines: context.current_lines,
             total_lines: content.lines().count(),
             detected_format: Some(self.name.clone()),
             parsing_errors: context.errors,
+            applied_filters: context.processing_chain,
+            capabilities: None,
         })
     }
+
+    /// 逐步追踪插件链对内容的处理
+    ///
+    /// 与 `process()` 按相同顺序执行过滤器，但在每个过滤器前后对比第一条结果，
+    /// 记录 `should_process()` 的判断、字段变化和新增的元数据，用于排查某一行为什么被解析错。
+    /// 追踪时不检查链的执行条件，也不做分块和多行记录的后处理。
+    ///
+    /// # 参数
+    /// - `content`: 要追踪的日志内容（通常是单行）
+    /// - `request`: 解析请求参数
+    ///
+    /// # Returns
+    /// - `Result<ChainTrace, String>`: 逐步追踪结果；链已禁用时返回错误
+    pub fn explain(&self, content: &str, request: &ParseRequest) -> Result<ChainTrace, String> {
+        if !self.enabled {
+            return Err(Message::new("chain.disabled").with("chain", &self.name).text());
+        }
+
+        let mut context = PluginChainContext::new(content);
+        context.merge_wrapped_lines = request.merge_wrapped_lines;
+        let mut steps = Vec::with_capacity(self.filters.len());
+
+        for filter in &self.filters {
+            let lines = context.current_lines.len();
+            if !context.should_continue {
+                steps.push(FilterStep::new(filter.as_ref(), StepStatus::NotReached, lines));
+                continue;
+            }
+            if !filter.should_process(&context) {
+                steps.push(FilterStep::new(filter.as_ref(), StepStatus::Skipped, lines));
+                continue;
+            }
+
+            let before = context.current_lines.first().cloned();
+            let mut step = FilterStep::new(filter.as_ref(), StepStatus::Applied, lines);
+            match filter.process(&mut context, request) {
+                Ok(()) => context.processing_chain.push(filter.name().to_string()),
+                Err(e) => {
+                    context.add_error(Message::new("chain.filter_failed").with("filter", filter.name()).with("error", &e).text());
+                    step.status = StepStatus::Failed;
+                    step.error = Some(e);
+                }
+            }
+            step.lines_after = context.current_lines.len();
+            step.stopped_chain = !context.should_continue;
+            step.record_diff(before.as_ref(), context.current_lines.first());
+            steps.push(step);
+        }
+
+        debug!("🔬 插件链 '{}' 追踪完成: {} 个步骤", self.name, steps.len());
+        Ok(ChainTrace {
+            chain: self.name.clone(),
+            content: content.to_string(),
+            steps,
+            result: context.current_lines.into_iter().next(),
+            errors: context.errors,
+        })
+    }
+}
+
+/// 过滤器在单步追踪中的执行情况
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    /// 已执行且成功
+    Applied,
+    /// 已执行但返回错误
+    Failed,
+    /// `should_process()` 返回false，跳过
+    Skipped,
+    /// 链在此之前被停止，未执行
+    NotReached,
+}
+
+/// 字段在某个过滤器执行前后的值
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldChange {
+    /// 字段名（content、level、timestamp、formatted_content 或元数据键）
+    pub field: String,
+    /// 执行前的值
+    pub before: Option<String>,
+    /// 执行后的值
+    pub after: Option<String>,
+}
+
+/// 单个过滤器的追踪记录
+#[derive(Debug, Clone, Serialize)]
+pub struct FilterStep {
+    /// 过滤器名称
+    pub filter: String,
+    /// 过滤器描述
+    pub description: String,
+    /// 执行情况
+    pub status: StepStatus,
+    /// 执行失败时的错误信息
+    pub error: Option<String>,
+    /// 日志行字段的变化
+    pub changes: Vec<FieldChange>,
+    /// 新增的元数据
+    pub metadata_added: BTreeMap<String, String>,
+    /// 值被修改的元数据
+    pub metadata_changed: Vec<FieldChange>,
+    /// 被移除的元数据键
+    pub metadata_removed: Vec<String>,
+    /// 执行前的条目数
+    pub lines_before: usize,
+    /// 执行后的条目数（过滤器可能合并或拆分条目）
+    pub lines_after: usize,
+    /// 执行后链是否被停止
+    pub stopped_chain: bool,
+}
+
+/// 插件链对一行日志的逐步处理追踪
+#[derive(Debug, Clone, Serialize)]
+pub struct ChainTrace {
+    /// 使用的插件链名称
+    pub chain: String,
+    /// 被处理的原始内容
+    pub content: String,
+    /// 每个过滤器的处理记录（按执行顺序）
+    pub steps: Vec<FilterStep>,
+    /// 链执行完成后的第一条结果
+    pub result: Option<LogLine>,
+    /// 链执行过程中的错误
+    pub errors: Vec<String>,
+}
+
+/// 读取日志行的指定字段
+fn field_value(line: &LogLine, field: &str) -> Option<String> {
+    match field {
+        "content" => Some(line.content.clone()),
+        "message" => line.message.clone(),
+        "level" => line.level.clone(),
+        "timestamp" => line.timestamp.clone(),
+        "formatted_content" => line.formatted_content.clone(),
+        _ => None,
+    }
+}
+
+impl FilterStep {
+    fn new(filter: &(dyn PluginFilter + Send + Sync), status: StepStatus, lines: usize) -> Self {
+        Self {
+            filter: filter.name().to_string(),
+            description: crate::i18n::tr_or(&format!("filter.{}", filter.name()), filter.description()),
+            status,
+            error: None,
+            changes: Vec::new(),
+            metadata_added: BTreeMap::new(),
+            metadata_changed: Vec::new(),
+            metadata_removed: Vec::new(),
+            lines_before: lines,
+            lines_after: lines,
+            stopped_chain: false,
+        }
+    }
+
+    /// 对比过滤器执行前后的第一条结果，记录字段和元数据的变化
+    fn record_diff(&mut self, before: Option<&LogLine>, after: Option<&LogLine>) {
+        for field in ["content", "message", "level", "timestamp", "formatted_content"] {
+            let old = before.and_then(|line| field_value(line, field));
+            let new = after.and_then(|line| field_value(line, field));
+            if old != new {
+                self.changes.push(FieldChange { field: field.to_string(), before: old, after: new });
+            }
+        }
+
+        let empty = HashMap::new();
+        let old_meta = before.map_or(&empty, |line| &line.metadata);
+        let new_meta = after.map_or(&empty, |line| &line.metadata);
+        for (key, value) in new_meta {
+            match old_meta.get(key) {
+                None => {
+                    self.metadata_added.insert(key.clone(), value.clone());
+                }
+                Some(old) if old != value => self.metadata_changed.push(FieldChange {
+                    field: key.clone(),
+                    before: Some(old.clone()),
+                    after: Some(value.clone()),
+                }),
+                Some(_) => {}
+            }
+        }
+        self.metadata_changed.sort_by(|a, b| a.field.cmp(&b.field));
+        self.metadata_removed = old_meta.keys().filter(|key| !new_meta.contains_key(*key)).cloned().collect();
+        self.metadata_removed.sort();
+    }
+}
+
+/// 自动检测的采样范围
+///
+/// 选择插件链时只检查内容开头的样本，避免大文件的每个块都被完整扫描。
+/// 两个限制同时生效，取较小的范围；0表示不限制。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DetectionSample {
+    /// 最多采样的字节数
+    pub max_bytes: usize,
+    /// 最多采样的行数
+    pub max_lines: usize,
+}
+
+impl DetectionSample {
+    /// 截取内容开头的样本（按行截断，不会切断UTF-8字符）
+    pub fn apply<'a>(&self, content: &'a str) -> &'a str {
+        let mut end = content.len();
+        if self.max_lines > 0 {
+            if let Some((index, _)) = content.match_indices('\n').nth(self.max_lines - 1) {
+                end = index;
+            }
+        }
+        if self.max_bytes > 0 && end > self.max_bytes {
+            let mut boundary = self.max_bytes;
+            while !content.is_char_boundary(boundary) {
+                boundary -= 1;
+            }
+            // 尽量在最后一个完整行处截断，单行超长时在字符边界截断
+            end = match content[..boundary].rfind('\n') {
+                Some(index) if index > 0 => index,
+                _ => boundary,
+            };
+        }
+        &content[..end]
+    }
+}
+
+impl Default for DetectionSample {
+    fn default() -> Self {
+        Self { max_bytes: 64 * 1024, max_lines: 500 }
+    }
 }
 
 /// 插件链管理器
@@ -465,6 +854,9 @@ pub struct PluginChainManager {
 
     /// 是否启用智能链选择
     smart_selection: bool,
+
+    /// 自动检测的采样范围
+    detection_sample: DetectionSample,
 }
 
 impl PluginChainManager {
@@ -477,6 +869,7 @@ impl PluginChainManager {
             chains: HashMap::new(),
             default_chain: None,
             smart_selection: true,
+            detection_sample: DetectionSample::default(),
         }
     }
 
@@ -489,6 +882,16 @@ impl PluginChainManager {
         self.chains.insert(name, chain);
     }
 
+    /// 向所有已注册的链添加过滤器（如对所有格式生效的脚本钩子）
+    ///
+    /// # 参数
+    /// - `filter`: 要添加的过滤器
+    pub fn add_filter_to_all_chains(&mut self, filter: Arc<dyn PluginFilter + Send + Sync>) {
+        for chain in self.chains.values_mut() {
+            chain.add_filter(filter.clone());
+        }
+    }
+
     /// 设置默认链
     ///
     /// # 参数
@@ -499,114 +902,102 @@ impl PluginChainManager {
 
     /// 智能选择最佳处理链
     ///
-    /// 基于日志内容特征和文件路径信息选择最适合的处理链。
+    /// 按 `detection::score_formats` 对内容样本的格式评分选择最适合的处理链，并按插件能力协商：
+    /// 跳过声明无法处理 `file_size` 的链，匹配度接近的链中优先性能等级较快的链。
+    /// 内容匹配用户布局模式时优先选择布局模式链；没有格式达到 `detection::MIN_CONFIDENCE` 时使用默认链。
     ///
     /// # 参数
     /// - `content`: 日志内容
     /// - `file_path`: 文件路径（可选）
+    /// - `file_size`: 文件大小（字节，0表示不限制）
     ///
     /// # Returns
-    /// - `Option<&PluginChain>`: 选择的链引用，如果没有匹配的则返回None
-    pub fn select_best_chain(&self, content: &str, file_path: Option<&str>) -> Option<&PluginChain> {
+    /// - `(Option<&PluginChain>, CapabilityDecision)`: 选择的链引用（没有匹配的则为None）和协商结果
+    pub fn select_best_chain(&self, content: &str, file_path: Option<&str>, file_size: u64) -> (Option<&PluginChain>, CapabilityDecision) {
+        let mut decision = CapabilityDecision::new(file_size);
+        let chain = self.negotiate(content, file_path, &mut decision);
+        if let Some(chain) = chain {
+            decision.select(&chain.name, chain.capabilities());
+        }
+        (chain, decision)
+    }
+
+    fn negotiate(&self, content: &str, file_path: Option<&str>, decision: &mut CapabilityDecision) -> Option<&PluginChain> {
         if !self.smart_selection {
             // 如果禁用智能选择，返回默认链
             return self.default_chain.as_ref().and_then(|name| self.chains.get(name));
         }
 
-        // 优先检测Docker JSON格式（最高优先级）
-        let content_lower = content.to_lowercase();
-        if content_lower.contains("{") &&
-           content_lower.contains("\"log\"") &&
-           content_lower.contains("\"stream\"") {
-            info!("🐳 检测到Docker JSON格式，优先选择Docker链");
-            return self.chains.get("docker");
+        // 用户配置了布局模式且内容与之匹配时，优先于内置格式
+        if let Some(chain) = self.chains.get("pattern_layout").filter(|chain| chain.enabled) {
+            if chain.filters.iter().any(|f| f.name() == "pattern_layout" && f.can_handle(content, file_path)) {
+                if chain.capabilities().accepts(decision.file_size) {
+                    info!("🧩 内容匹配用户布局模式，优先选择布局模式链");
+                    return Some(chain);
+                }
+                decision.skip(&chain.name);
+            }
         }
 
-        // 计算每个链的匹配度
-        let mut best_chain = None;
-        let mut best_score = 0.0;
-
+        // 每个链取其对应格式的最高置信度
+        let scores = detection::score_formats(&detection::sample_lines(content));
+        let mut candidates = Vec::new();
         for (name, chain) in &self.chains {
-            if !chain.enabled {
+            if !chain.enabled || decision.skipped.contains(name) {
                 continue;
             }
 
-            let score = self.calculate_chain_score(chain, content, file_path);
+            let score = Self::chain_score(chain, &scores, file_path);
             debug!("🔍 链 '{}' 匹配度: {:.2}", name, score);
-
-            if score > best_score {
-                best_score = score;
-                best_chain = Some(chain);
-            }
+            candidates.push(Candidate { name, score, capabilities: chain.capabilities() });
         }
+        // 得分相同时按格式检测器的顺序（`detection::DETECTORS`）优先，不依赖HashMap的遍历顺序
+        candidates.sort_by_key(|candidate| {
+            scores.iter().position(|score| score.chain == *candidate.name).unwrap_or(usize::MAX)
+        });
+        let chosen = choose(&candidates, decision);
+        let best_score = chosen.map_or(0.0, |index| candidates[index].score);
 
         // 如果没有链匹配或匹配度太低，使用默认链
-        if best_score < 0.3 {
+        if best_score < detection::MIN_CONFIDENCE {
             if let Some(default_name) = &self.default_chain {
                 info!("⚠️ 没有找到高匹配度的链，使用默认链: {}", default_name);
                 return self.chains.get(default_name);
             }
         }
 
-        best_chain
+        chosen.and_then(|index| self.chains.get(candidates[index].name))
     }
 
     /// 计算链与内容的匹配度
     ///
     /// # 参数
     /// - `chain`: 插件链引用
-    /// - `content`: 日志内容
+    /// - `scores`: 样本的格式评分（见 `detection::score_formats`）
     /// - `file_path`: 文件路径（可选）
     ///
     /// # Returns
-    /// - `f32`: 匹配度分数（0.0 - 1.0）
-    fn calculate_chain_score(&self, chain: &PluginChain, content: &str, file_path: Option<&str>) -> f32 {
-        let mut score = 0.0;
-        let total_filters = chain.filters.len();
-
-        if total_filters == 0 {
+    /// - `f32`: 链对应格式的最高置信度，文件名匹配链的文件模式时加0.1（0.0 - 1.0）；
+    ///   没有对应格式的链（如通用链）为0
+    fn chain_score(chain: &PluginChain, scores: &[detection::FormatScore], file_path: Option<&str>) -> f32 {
+        let confidence = scores.iter()
+            .filter(|score| score.chain == chain.name)
+            .map(|score| score.confidence)
+            .fold(0.0, f32::max);
+        if confidence == 0.0 {
             return 0.0;
         }
 
-        // 计算能处理此内容的过滤器比例
-        let mut can_handle_count = 0;
-        for filter in &chain.filters {
-            if filter.can_handle(content, file_path) {
-                can_handle_count += 1;
-            }
-        }
-
-        score += (can_handle_count as f32) / (total_filters as f32);
-
-        // 如果有条件，检查条件匹配度
-        if let Some(conditions) = &chain.conditions {
-            if conditions.matches(content, file_path) {
-                score += 0.4; // 增加条件匹配加分，确保特定链优先于通用链
-                debug!("✅ 链 '{}' 条件匹配加分，当前分数: {:.2}", chain.name, score);
-            } else {
-                debug!("❌ 链 '{}' 条件不匹配", chain.name);
-                // 如果条件不匹配，大幅降低分数
-                score *= 0.3;
-                debug!("🔻 链 '{}' 条件不匹配，分数降至: {:.2}", chain.name, score);
-            }
-        } else {
-            // 通用链没有条件，给予轻微惩罚，让特定链有优先权
-            score *= 0.9;
-            debug!("🔧 通用链 '{}' 分数调整: {:.2}", chain.name, score);
-        }
-
         // 文件路径匹配加分
-        if let Some(path) = file_path {
-            for filter in &chain.filters {
-                if filter.can_handle(content, Some(path)) {
-                    score += 0.1;
-                    break;
-                }
-            }
+        let path_matches = file_path.zip(chain.conditions.as_ref()).is_some_and(|(path, conditions)| {
+            let path = path.to_lowercase();
+            conditions.file_patterns.iter().any(|pattern| path.contains(&pattern.to_lowercase()))
+        });
+        if path_matches {
+            (confidence + 0.1).min(1.0)
+        } else {
+            confidence
         }
-
-        // 确保分数在0-1范围内
-        score.min(1.0)
     }
 
     /// 处理日志内容
@@ -620,12 +1011,104 @@ impl PluginChainManager {
     /// # Returns
     /// - `Result<ParseResult, String>`: 处理结果或错误信息
     pub fn process(&self, content: &str, request: &ParseRequest) -> Result<ParseResult, String> {
-        // 选择最佳处理链
-        let chain = self.select_best_chain(content, request.file_path.as_deref())
-            .ok_or_else(|| "没有找到合适的处理链".to_string())?;
-
+        let file_size = request_file_size(content, request);
+        let (chain, decision) = self.select_for(content, request.file_path.as_deref(), file_size)?;
         info!("🎯 选择处理链: {}", chain.name);
-        chain.process(content, request)
+        // 链已按内容的格式评分选出，不再检查链的关键词条件
+        let mut result = chain.run(content, request)?;
+        result.capabilities = Some(decision);
+        Ok(result)
+    }
+
+    /// 选择最佳处理链
+    ///
+    /// 只对内容开头的样本做特征匹配，带ANSI颜色码的样本先清理后再匹配。
+    fn select_for(&self, content: &str, file_path: Option<&str>, file_size: u64) -> Result<(&PluginChain, CapabilityDecision), String> {
+        let content = self.detection_sample.apply(content);
+        let selection_content = if content.contains('\x1b') {
+            std::borrow::Cow::Owned(crate::plugins::filters::AnsiFilter::strip_ansi(content).0)
+        } else {
+            std::borrow::Cow::Borrowed(content)
+        };
+        match self.select_best_chain(&selection_content, file_path, file_size) {
+            (Some(chain), decision) => Ok((chain, decision)),
+            (None, _) => Err(Message::new("chain.none_matched").text()),
+        }
+    }
+
+    /// 逐步追踪插件链对内容的处理
+    ///
+    /// # 参数
+    /// - `chain_name`: 指定的链名称；None时按 `sample` 智能选择（与解析整个文件时的选择一致）
+    /// - `sample`: 用于选择链的内容样本（通常是文件开头的若干行）
+    /// - `request`: 解析请求参数，`request.content` 为要追踪的内容
+    ///
+    /// # Returns
+    /// - `Result<ChainTrace, String>`: 逐步追踪结果；链不存在或已禁用时返回错误
+    pub fn explain(&self, chain_name: Option<&str>, sample: &str, request: &ParseRequest) -> Result<ChainTrace, String> {
+        let chain = match chain_name {
+            Some(name) => self.chains.get(name).ok_or_else(|| Message::new("chain.not_found").with("chain", name).text())?,
+            None => self.select_for(sample, request.file_path.as_deref(), 0)?.0,
+        };
+        info!("🔬 使用插件链 '{}' 追踪处理过程", chain.name);
+        chain.explain(&request.content, request)
+    }
+
+    /// 使用指定名称的链处理内容，跳过智能选择
+    ///
+    /// # 参数
+    /// - `chain_name`: 链名称
+    /// - `content`: 要处理的日志内容
+    /// - `request`: 解析请求参数
+    ///
+    /// # Returns
+    /// - `Result<ParseResult, String>`: 处理结果；链不存在、已禁用或执行条件不匹配时返回错误
+    pub fn process_with(&self, chain_name: &str, content: &str, request: &ParseRequest) -> Result<ParseResult, String> {
+        let chain = self.chains.get(chain_name)
+            .ok_or_else(|| Message::new("chain.not_found").with("chain", chain_name).text())?;
+        info!("🎯 使用指定处理链: {}", chain.name);
+        let decision = Self::forced_decision(chain, content, request);
+        let mut result = chain.process(content, request)?;
+        result.capabilities = Some(decision);
+        Ok(result)
+    }
+
+    /// 使用指定名称的链处理内容，不检查链的执行条件
+    ///
+    /// 用于用户为文件指定了固定格式的场景：用户的选择优先于链的文件名和内容条件。
+    ///
+    /// # Returns
+    /// - `Result<ParseResult, String>`: 处理结果；链不存在或已禁用时返回错误
+    pub fn process_forced(&self, chain_name: &str, content: &str, request: &ParseRequest) -> Result<ParseResult, String> {
+        let chain = self.chains.get(chain_name)
+            .ok_or_else(|| Message::new("chain.not_found").with("chain", chain_name).text())?;
+        info!("📌 使用文件指定的处理链: {}", chain.name);
+        let decision = Self::forced_decision(chain, content, request);
+        let mut result = chain.run(content, request)?;
+        result.capabilities = Some(decision);
+        Ok(result)
+    }
+
+    /// 指定链时的协商结果：不跳过，超出声明的大小上限时只给出警告
+    fn forced_decision(chain: &PluginChain, content: &str, request: &ParseRequest) -> CapabilityDecision {
+        let mut decision = CapabilityDecision::new(request_file_size(content, request));
+        decision.select(&chain.name, chain.capabilities());
+        decision
+    }
+
+    /// 设置自动检测的采样范围
+    pub fn set_detection_sample(&mut self, sample: DetectionSample) {
+        self.detection_sample = sample;
+    }
+
+    /// 当前自动检测的采样范围
+    pub fn detection_sample(&self) -> DetectionSample {
+        self.detection_sample
+    }
+
+    /// 是否注册了指定名称的链
+    pub fn has_chain(&self, chain_name: &str) -> bool {
+        self.chains.contains_key(chain_name)
     }
 
     /// 获取所有已注册的链信息
@@ -636,6 +1119,50 @@ impl PluginChainManager {
         self.chains.keys().cloned().collect()
     }
 
+    /// 获取所有已注册链的插件信息
+    ///
+    /// 链条件中的文件路径模式作为支持的扩展名返回；启用的链参与智能选择，因此可自动检测。
+    ///
+    /// # Returns
+    /// - `Vec<PluginInfo>`: 所有链的插件信息
+    pub fn chain_infos(&self) -> Vec<PluginInfo> {
+        self.chains.values().map(|chain| PluginInfo {
+            name: chain.name.clone(),
+            description: chain.description.clone(),
+            supported_extensions: chain.conditions.as_ref()
+                .map(|conditions| conditions.file_patterns.clone())
+                .unwrap_or_default(),
+            auto_detectable: chain.enabled && self.smart_selection,
+            version: PLUGIN_VERSION.to_string(),
+            kind: PluginKind::Chain,
+            enabled: chain.enabled,
+        }).collect()
+    }
+
+    /// 收集所有链中过滤器声明的配置项Schema
+    ///
+    /// 同一过滤器出现在多个链中时只返回一次。
+    ///
+    /// # Returns
+    /// - `Vec<(String, String, serde_json::Value)>`: (过滤器名称, 描述, Schema)，按名称排序
+    pub fn settings_schemas(&self) -> Vec<(String, String, serde_json::Value)> {
+        let mut schemas: HashMap<String, (String, serde_json::Value)> = HashMap::new();
+        for chain in self.chains.values() {
+            for filter in &chain.filters {
+                if let Some(schema) = filter.settings_schema() {
+                    schemas.entry(filter.name().to_string())
+                        .or_insert_with(|| (filter.description().to_string(), schema));
+                }
+            }
+        }
+
+        let mut schemas: Vec<_> = schemas.into_iter()
+            .map(|(name, (description, schema))| (name, description, schema))
+            .collect();
+        schemas.sort_by(|a, b| a.0.cmp(&b.0));
+        schemas
+    }
+
     /// 启用或禁用智能链选择
     ///
     /// # 参数
@@ -0,0 +1,314 @@
+/// CI平台日志解析
+///
+/// 处理GitHub Actions和GitLab CI导出的原始作业日志：
+/// - GitHub Actions：每行以 `2024-01-15T10:30:25.1234567Z ` 时间戳开头，
+///   使用 `##[group]` / `##[endgroup]` 标记可折叠分组，`##[error]` / `##[warning]` 等标记级别；
+///   工作流命令形式（`::group::`、`::error file=...::msg`）同样识别
+/// - GitLab CI：使用 `section_start:<unix时间>:<名称>\r\x1b[0K<标题>` 和
+///   `section_end:<unix时间>:<名称>\r\x1b[0K` 标记分组，新版日志每行带有 `<时间戳> 00O ` 前缀
+///
+/// # 元数据
+/// - `ci_provider`: github / gitlab
+/// - `group_id`: 所属分组的唯一标识（前端据此折叠）
+/// - `group_name`: 所属分组的标题
+/// - `group_marker`: start / end（分组的起止行）
+/// - `group_duration_s`: 分组耗时（GitLab结束行）
+
+use crate::plugins::chain::{PluginChainContext, PluginFilter};
+use crate::plugins::filters::AnsiFilter;
+use crate::plugins::{LogLine, ParseRequest};
+use log::info;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::HashMap;
+
+/// GitHub Actions 行首时间戳：`2024-01-15T10:30:25.1234567Z `
+static GITHUB_TIMESTAMP: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2})\.\d{7}Z ?").unwrap()
+});
+
+/// GitHub Actions 日志标记：`##[group]标题`、`##[error]消息`
+static GITHUB_MARKER: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^##\[(group|endgroup|error|warning|notice|debug|command|section)\](.*)$").unwrap()
+});
+
+/// GitHub Actions 工作流命令：`::error file=app.js,line=1::消息`
+static GITHUB_COMMAND: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^::(group|endgroup|error|warning|notice|debug)(?: [^:]*)?::(.*)$").unwrap()
+});
+
+/// GitLab 新版日志行前缀：`2024-01-15T10:30:25.123456Z 00O `（时间戳 + 流标识）
+static GITLAB_PREFIX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2})(?:\.\d+)?Z \d{2}[OE]\+? ?").unwrap()
+});
+
+/// GitLab 分组标记：`section_start:1700000000:build_script[collapsed=true]`
+static GITLAB_SECTION: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"section_(start|end):(\d+):([A-Za-z0-9_.-]+)(?:\[[^\]]*\])?\r?(?:\x1b\[0K)?(.*)$").unwrap()
+});
+
+/// 分组栈中的一项
+struct OpenGroup {
+    id: String,
+    name: String,
+    started_at: Option<u64>,
+}
+
+/// CI平台日志过滤器
+///
+/// # 链中位置
+/// 优先级11：在Docker JSON解包之后、回车折叠（12）和ANSI清理（15）之前执行，
+/// 因为GitLab的分组标记本身由 `\r` 和 `ESC[0K` 组成，必须先于这两个过滤器识别。
+pub struct CiLogFilter;
+
+impl CiLogFilter {
+    /// 行是否带有CI平台的时间戳或分组、注解标记
+    pub fn matches_line(line: &str) -> bool {
+        GITHUB_TIMESTAMP.is_match(line) ||
+        line.contains("##[group]") || line.contains("##[error]") || line.contains("##[warning]") ||
+        GITLAB_SECTION.is_match(line)
+    }
+
+    /// 内容是否像CI平台的原始作业日志
+    pub fn looks_like_ci_log(content: &str) -> bool {
+        content.lines().take(200).any(Self::matches_line)
+    }
+
+    /// 处理单行，返回是否识别为CI日志行
+    fn tag_line(line: &mut LogLine, groups: &mut Vec<OpenGroup>, next_group: &mut usize, strict: bool) -> bool {
+        let mut provider = None;
+        let mut text = line.message_text().to_string();
+
+        // 去掉行首时间戳前缀
+        if let Some(caps) = GITLAB_PREFIX.captures(&text) {
+            line.timestamp = Some(caps[1].to_string());
+            provider = Some("gitlab");
+            text = text[caps.get(0).unwrap().end()..].to_string();
+        } else if let Some(caps) = GITHUB_TIMESTAMP.captures(&text) {
+            line.timestamp = Some(caps[1].to_string());
+            provider = Some("github");
+            text = text[caps.get(0).unwrap().end()..].to_string();
+        }
+
+        if let Some(caps) = GITLAB_SECTION.captures(&text) {
+            provider = Some("gitlab");
+            let timestamp = caps[2].parse::<u64>().ok();
+            let name = caps[3].to_string();
+            if &caps[1] == "start" {
+                let header = AnsiFilter::strip_ansi(caps[4].trim()).0;
+                let title = if header.is_empty() { name.clone() } else { header };
+                groups.push(OpenGroup { id: name, name: title.clone(), started_at: timestamp });
+                Self::mark_group(line, groups.last(), "start");
+                text = title;
+            } else {
+                let position = groups.iter().rposition(|g| g.id == name);
+                let group = position.map(|index| groups.remove(index));
+                Self::mark_group(line, group.as_ref(), "end");
+                if let (Some(start), Some(end)) = (group.as_ref().and_then(|g| g.started_at), timestamp) {
+                    line.metadata.insert("group_duration_s".to_string(), end.saturating_sub(start).to_string());
+                }
+                text = format!("section_end:{}", name);
+            }
+        } else if let Some((kind, message)) = GITHUB_MARKER.captures(&text)
+            .or_else(|| GITHUB_COMMAND.captures(&text))
+            .map(|caps| (caps[1].to_string(), caps[2].to_string()))
+        {
+            provider = provider.or(Some("github"));
+            match kind.as_str() {
+                "group" => {
+                    *next_group += 1;
+                    let title = message.trim().to_string();
+                    groups.push(OpenGroup { id: format!("group-{}", next_group), name: title.clone(), started_at: None });
+                    Self::mark_group(line, groups.last(), "start");
+                    text = title;
+                }
+                "endgroup" => {
+                    let group = groups.pop();
+                    Self::mark_group(line, group.as_ref(), "end");
+                    text = "##[endgroup]".to_string();
+                }
+                level => {
+                    let level = match level {
+                        "error" => Some("ERROR"),
+                        "warning" => Some("WARN"),
+                        "notice" => Some("INFO"),
+                        "debug" => Some("DEBUG"),
+                        _ => None,
+                    };
+                    if let Some(level) = level {
+                        line.level = Some(level.to_string());
+                    }
+                    line.metadata.insert("ci_marker".to_string(), kind.clone());
+                    Self::mark_group(line, groups.last(), "");
+                    text = message;
+                }
+            }
+        } else {
+            Self::mark_group(line, groups.last(), "");
+        }
+
+        let Some(provider) = provider.or_else(|| (!groups.is_empty()).then_some("")) else {
+            return false;
+        };
+        if !provider.is_empty() {
+            line.metadata.insert("ci_provider".to_string(), provider.to_string());
+        }
+        if !text.trim().is_empty() {
+            line.set_message(text, strict);
+        }
+        true
+    }
+
+    /// 记录行所属的分组；`marker` 非空时同时记录分组起止标记
+    fn mark_group(line: &mut LogLine, group: Option<&OpenGroup>, marker: &str) {
+        if let Some(group) = group {
+            line.metadata.insert("group_id".to_string(), group.id.clone());
+            line.metadata.insert("group_name".to_string(), group.name.clone());
+        }
+        if !marker.is_empty() {
+            line.metadata.insert("group_marker".to_string(), marker.to_string());
+        }
+    }
+}
+
+impl PluginFilter for CiLogFilter {
+    fn name(&self) -> &str {
+        "ci_log"
+    }
+
+    fn description(&self) -> &str {
+        "CI平台日志过滤器，解析GitHub Actions和GitLab CI的时间戳、分组和错误标记"
+    }
+
+    fn priority(&self) -> i32 {
+        11 // 在回车折叠和ANSI清理之前执行
+    }
+
+    fn should_process(&self, context: &PluginChainContext) -> bool {
+        if context.current_lines.is_empty() {
+            return Self::looks_like_ci_log(&context.original_content);
+        }
+        context.current_lines.iter().take(200).any(|line| Self::looks_like_ci_log(line.message_text()))
+    }
+
+    fn process(&self, context: &mut PluginChainContext, request: &ParseRequest) -> Result<(), String> {
+        info!("🏗️ CI日志过滤器开始处理");
+        let strict = request.is_strict();
+
+        let mut lines = if context.current_lines.is_empty() {
+            // 第一次处理，从原始内容创建行列表，过滤空行
+            context.original_content.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()).map(|(i, line)| {
+                LogLine {
+                    line_number: i + 1,
+                    content: line.to_string(),
+                    level: None,
+                    timestamp: None,
+                    formatted_content: None,
+                    message: None,
+                    metadata: HashMap::new(),
+                    processed_by: vec![],
+                }
+            }).collect()
+        } else {
+            std::mem::take(&mut context.current_lines)
+        };
+
+        let mut groups = Vec::new();
+        let mut next_group = 0;
+        let mut tagged = 0;
+        for line in &mut lines {
+            if Self::tag_line(line, &mut groups, &mut next_group, strict) {
+                line.processed_by.push("ci_log_filter".to_string());
+                line.formatted_content = Some(line.message_text().to_string());
+                tagged += 1;
+            }
+        }
+
+        context.current_lines = lines;
+        context.set_chain_metadata("ci_groups".to_string(), next_group.to_string());
+
+        info!("🏗️ CI日志过滤器处理完成，标记了 {} 行", tagged);
+        Ok(())
+    }
+
+    fn can_handle(&self, content: &str, _file_path: Option<&str>) -> bool {
+        Self::looks_like_ci_log(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::chain::PluginChain;
+    use std::sync::Arc;
+
+    fn parse(content: &str) -> Vec<LogLine> {
+        let mut chain = PluginChain::new("test".to_string(), "test".to_string());
+        chain.add_filter(Arc::new(CiLogFilter));
+        chain.add_filter(Arc::new(AnsiFilter::new()));
+        chain.process(content, &ParseRequest::default()).unwrap().lines
+    }
+
+    fn meta<'a>(line: &'a LogLine, key: &str) -> Option<&'a str> {
+        line.metadata.get(key).map(String::as_str)
+    }
+
+    #[test]
+    fn test_github_actions_groups_and_levels() {
+        let content = "\
+2024-01-15T10:30:25.1234567Z ##[group]Run npm test
+2024-01-15T10:30:25.2234567Z npm test
+2024-01-15T10:30:26.1234567Z ##[endgroup]
+2024-01-15T10:30:27.1234567Z ##[error]Process completed with exit code 1.
+2024-01-15T10:30:27.2234567Z ::warning file=app.js,line=1::Unused variable";
+        let lines = parse(content);
+
+        assert_eq!(lines[0].content, "Run npm test");
+        assert_eq!(lines[0].timestamp.as_deref(), Some("2024-01-15T10:30:25"));
+        assert_eq!(meta(&lines[0], "group_marker"), Some("start"));
+        assert_eq!(meta(&lines[1], "group_id"), Some("group-1"));
+        assert_eq!(meta(&lines[1], "group_name"), Some("Run npm test"));
+        assert_eq!(meta(&lines[2], "group_marker"), Some("end"));
+        assert_eq!(meta(&lines[3], "group_id"), None);
+        assert_eq!(lines[3].level.as_deref(), Some("ERROR"));
+        assert_eq!(lines[3].content, "Process completed with exit code 1.");
+        assert_eq!(lines[4].level.as_deref(), Some("WARN"));
+        assert_eq!(meta(&lines[4], "ci_provider"), Some("github"));
+    }
+
+    #[test]
+    fn test_gitlab_sections() {
+        let content = "\
+section_start:1700000000:build_script[collapsed=true]\r\x1b[0K\x1b[36;1mExecuting build\x1b[0;m
+make all
+section_end:1700000042:build_script\r\x1b[0K
+Job succeeded";
+        let lines = parse(content);
+
+        assert_eq!(lines[0].content, "Executing build");
+        assert_eq!(meta(&lines[0], "group_id"), Some("build_script"));
+        assert_eq!(meta(&lines[1], "group_name"), Some("Executing build"));
+        assert_eq!(meta(&lines[2], "group_marker"), Some("end"));
+        assert_eq!(meta(&lines[2], "group_duration_s"), Some("42"));
+        assert_eq!(meta(&lines[3], "group_id"), None);
+        assert!(CiLogFilter.can_handle(content, None));
+        assert!(!CiLogFilter.can_handle("2024-01-15 10:30:25.123 [main] INFO App - started", None));
+    }
+
+    #[test]
+    fn test_preset_selects_ci_chain() {
+        use crate::plugins::chain::PluginChainManager;
+        use crate::plugins::presets::register_preset_chains;
+
+        let mut manager = PluginChainManager::new();
+        register_preset_chains(&mut manager);
+
+        let content = "\
+2024-01-15T10:30:25.1234567Z ##[group]Run mvn test
+2024-01-15T10:30:26.1234567Z 2024-01-15 10:30:26.123 [main] INFO org.springframework.boot.App - Started
+2024-01-15T10:30:27.1234567Z ##[endgroup]";
+        let result = manager.process(content, &ParseRequest::default()).unwrap();
+        assert_eq!(result.detected_format.as_deref(), Some("ci"));
+        assert_eq!(result.lines[1].metadata.get("group_id").map(String::as_str), Some("group-1"));
+    }
+}
@@ -0,0 +1,166 @@
+/// 紧凑显示规则
+///
+/// SpringBoot解析器和过滤器在紧凑格式中把冗长的线程名缩略为简短标识（如 `http-nio-8080-exec-1` → `H80801`）。
+/// 缩略规则是可配置的"正则 → 模板"列表，保存在解析配置中，用户可以调整、补充或整体关闭。
+///
+/// 启用时缩略结果同时写入元数据 `thread_short`，表格和分组视图可以直接使用。
+///
+/// 模板使用 `regex` 的替换语法（`$1`、`${name}`），按顺序取第一条匹配的规则；
+/// 没有规则匹配时，超过15个字符的线程名截断显示。模板展开为空字符串表示不显示该线程（如 `main`）。
+
+use crate::i18n::Message;
+use crate::utils::string_utils;
+use log::warn;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// 缩略后的线程名写入的元数据键（解析器和过滤器一致，模板展开为空时值为空字符串）
+pub const THREAD_SHORT_METADATA_KEY: &str = "thread_short";
+
+/// 没有规则匹配时线程名的最大显示长度
+pub const MAX_THREAD_DISPLAY_CHARS: usize = 15;
+
+/// 一条缩略规则
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactRule {
+    /// 匹配线程名的正则表达式
+    pub pattern: String,
+    /// 替换模板（如 `H$1$2`）
+    pub template: String,
+}
+
+impl CompactRule {
+    fn new(pattern: &str, template: &str) -> Self {
+        Self { pattern: pattern.to_string(), template: template.to_string() }
+    }
+}
+
+/// 紧凑显示设置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactSettings {
+    /// 是否启用紧凑格式（关闭后线程名和类名不再缩略）
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// 线程名缩略规则（按顺序匹配）
+    #[serde(default = "default_compact_rules")]
+    pub thread_rules: Vec<CompactRule>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// 默认的线程名缩略规则
+///
+/// - `main` → 不显示
+/// - `http-nio-8080-exec-1` / `nio-8080-exec-1` → `H80801`
+/// - `worker-thread-5` → `W5`，`redis-thread-2` → `R2`，`scheduling-1` → `S1`
+pub fn default_compact_rules() -> Vec<CompactRule> {
+    vec![
+        CompactRule::new(r"^main$", ""),
+        CompactRule::new(r"^(?:http-)?nio-(\d+)-exec-(\d+)$", "H${1}${2}"),
+        CompactRule::new(r"^worker-thread-(.+)$", "W$1"),
+        CompactRule::new(r"^redis-thread-(.+)$", "R$1"),
+        CompactRule::new(r"^scheduling-(.+)$", "S$1"),
+    ]
+}
+
+impl Default for CompactSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            thread_rules: default_compact_rules(),
+        }
+    }
+}
+
+impl CompactSettings {
+    /// 校验所有规则的正则表达式
+    ///
+    /// # Returns
+    /// - `Err(String)`: 第一条无效规则的错误信息
+    pub fn validate(&self) -> Result<(), String> {
+        for rule in &self.thread_rules {
+            Regex::new(&rule.pattern)
+                .map_err(|e| Message::new("compact.invalid_rule").with("pattern", format!("{:?}", rule.pattern)).with("error", e).text())?;
+        }
+        Ok(())
+    }
+}
+
+/// 编译后的缩略规则
+pub struct ThreadCompactor {
+    enabled: bool,
+    rules: Vec<(Regex, String)>,
+}
+
+/// 默认设置编译出的缩略规则，请求未携带设置时使用
+static DEFAULT_COMPACTOR: Lazy<ThreadCompactor> = Lazy::new(|| ThreadCompactor::new(&CompactSettings::default()));
+
+impl ThreadCompactor {
+    /// 编译缩略规则，无效的规则记录警告后跳过
+    pub fn new(settings: &CompactSettings) -> Self {
+        let rules = settings.thread_rules.iter()
+            .filter_map(|rule| match Regex::new(&rule.pattern) {
+                Ok(regex) => Some((regex, rule.template.clone())),
+                Err(e) => {
+                    warn!("⚠️ 忽略无效的缩略规则 {:?}: {}", rule.pattern, e);
+                    None
+                }
+            })
+            .collect();
+        Self { enabled: settings.enabled, rules }
+    }
+
+    /// 默认规则
+    pub fn default_rules() -> &'static ThreadCompactor {
+        &DEFAULT_COMPACTOR
+    }
+
+    /// 是否启用紧凑格式
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// 缩略线程名
+    ///
+    /// # Returns
+    /// - `String`: 第一条匹配规则的模板展开结果（可能为空，表示不显示）；
+    ///   没有规则匹配时返回截断到15个字符的线程名
+    pub fn compact(&self, thread: &str) -> String {
+        for (regex, template) in &self.rules {
+            if let Some(caps) = regex.captures(thread) {
+                let mut compacted = String::new();
+                caps.expand(template, &mut compacted);
+                return compacted;
+            }
+        }
+        string_utils::ellipsize(thread, MAX_THREAD_DISPLAY_CHARS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_and_custom_rules() {
+        let compactor = ThreadCompactor::default_rules();
+        assert_eq!(compactor.compact("main"), "");
+        assert_eq!(compactor.compact("http-nio-8080-exec-1"), "H80801");
+        assert_eq!(compactor.compact("nio-8080-exec-10"), "H808010");
+        assert_eq!(compactor.compact("worker-thread-5"), "W5");
+        assert_eq!(compactor.compact("scheduling-1"), "S1");
+        assert_eq!(compactor.compact("very-long-thread-name"), "very-long-th...");
+
+        let settings = CompactSettings {
+            enabled: true,
+            thread_rules: vec![CompactRule::new(r"^kafka-consumer-(?P<n>\d+)$", "K${n}"), CompactRule::new("(", "")],
+        };
+        assert!(settings.validate().is_err());
+        let compactor = ThreadCompactor::new(&settings);
+        assert_eq!(compactor.compact("kafka-consumer-3"), "K3");
+        assert_eq!(compactor.compact("main"), "main");
+    }
+}
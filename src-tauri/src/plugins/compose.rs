@@ -0,0 +1,236 @@
+/// Docker Compose 多服务日志拆分
+///
+/// `docker compose logs` / `docker compose up` 把所有服务的输出合并到一起，
+/// 每行以 `<服务名>-<序号>  | ` 前缀开头（旧版为 `<服务名>_<序号>`），前缀按服务着色并补齐宽度：
+///
+/// ```text
+/// \x1b[36mweb-1  | \x1b[0m2024-01-15 10:30:25.123  INFO 1 --- [main] com.example.App : Started App
+/// \x1b[33mdb-1   | \x1b[0m2024-01-15 10:30:25.456 UTC [1] LOG:  database system is ready
+/// ```
+///
+/// 此过滤器去掉前缀及其颜色（消息自身的颜色留给ANSI过滤器处理），
+/// 使用 `--timestamps` 导出时同时提取Docker添加的时间戳。
+///
+/// # 元数据
+/// - `service`: 服务名（去掉副本序号），按服务分组或筛选时使用此键
+/// - `container`: 前缀中的完整容器名（含副本序号）
+/// - `service_color`: 前缀的颜色（界面按服务着色时使用）
+
+use crate::plugins::chain::{PluginChainContext, PluginFilter};
+use crate::plugins::filters::AnsiFilter;
+use crate::plugins::{LogLine, ParseRequest};
+use log::info;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::{BTreeSet, HashMap};
+
+/// 前缀（去掉颜色后）：服务名 + 至少一个空格的补齐
+static SERVICE_PREFIX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^([A-Za-z0-9][A-Za-z0-9_.-]*)\s+$").unwrap()
+});
+
+/// 容器名末尾的副本序号：`web-1`、`web_1`
+static REPLICA_SUFFIX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"[-_]\d+$").unwrap()
+});
+
+/// 前缀之后紧跟的颜色重置序列
+static LEADING_RESET: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(?:\x1b\[0?m)+").unwrap()
+});
+
+/// `docker compose logs --timestamps` 添加的时间戳：`2024-01-15T10:30:25.123456789Z `
+static DOCKER_TIMESTAMP: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2})(?:\.\d+)?Z ").unwrap()
+});
+
+/// 前缀的最大长度（超过时 `|` 属于消息内容）
+const MAX_PREFIX_LEN: usize = 80;
+
+/// 不会作为服务名出现的前缀（`INFO  | 消息` 之类的日志格式）
+const LEVEL_NAMES: &[&str] = &["TRACE", "DEBUG", "INFO", "WARN", "WARNING", "ERROR", "FATAL"];
+
+/// 拆分出的前缀
+struct ServicePrefix<'a> {
+    container: String,
+    color: Option<String>,
+    rest: &'a str,
+}
+
+/// Docker Compose 多服务日志过滤器
+///
+/// # 链中位置
+/// 优先级11：与CI日志过滤器相同，在回车折叠（12）和ANSI清理（15）之前执行，
+/// 先去掉带颜色的前缀，ANSI过滤器记录的才是消息自身的颜色。
+pub struct ComposeLogFilter;
+
+impl ComposeLogFilter {
+    /// 拆分行首的服务前缀
+    fn split_prefix(line: &str) -> Option<ServicePrefix<'_>> {
+        let bar = line.find('|').filter(|&bar| bar > 0 && bar <= MAX_PREFIX_LEN)?;
+        let (head, color) = AnsiFilter::strip_ansi(&line[..bar]);
+        let container = SERVICE_PREFIX.captures(&head)?[1].to_string();
+        if LEVEL_NAMES.contains(&container.to_uppercase().as_str()) {
+            return None;
+        }
+
+        let rest = &line[bar + 1..];
+        let rest = rest.strip_prefix(' ').unwrap_or(rest);
+        let rest = &rest[LEADING_RESET.find(rest).map_or(0, |m| m.end())..];
+        Some(ServicePrefix { container, color, rest })
+    }
+
+    /// 去掉副本序号得到服务名
+    pub fn service_name(container: &str) -> &str {
+        match REPLICA_SUFFIX.find(container) {
+            Some(suffix) if suffix.start() > 0 => &container[..suffix.start()],
+            _ => container,
+        }
+    }
+
+    /// 行是否带有服务前缀
+    pub fn matches_line(line: &str) -> bool {
+        Self::split_prefix(line).is_some()
+    }
+
+    /// 内容是否像 `docker compose logs` 的合并输出
+    ///
+    /// 前200个非空行中至少两行、且超过一半带有服务前缀时认为是Compose日志
+    /// （`docker compose up` 开头的 `Attaching to ...` 等行没有前缀）。
+    pub fn looks_like_compose_log(content: &str) -> bool {
+        let mut total = 0;
+        let mut prefixed = 0;
+        for line in content.lines().filter(|line| !line.trim().is_empty()).take(200) {
+            total += 1;
+            if Self::matches_line(line) {
+                prefixed += 1;
+            }
+        }
+        prefixed >= 2 && prefixed * 2 > total
+    }
+}
+
+impl PluginFilter for ComposeLogFilter {
+    fn name(&self) -> &str {
+        "compose"
+    }
+
+    fn description(&self) -> &str {
+        "Docker Compose日志过滤器，拆分多服务合并输出的服务前缀并记录服务名"
+    }
+
+    fn priority(&self) -> i32 {
+        11 // 在回车折叠和ANSI清理之前执行
+    }
+
+    fn should_process(&self, context: &PluginChainContext) -> bool {
+        if context.current_lines.is_empty() {
+            return Self::looks_like_compose_log(&context.original_content);
+        }
+        context.current_lines.iter().take(200).any(|line| Self::split_prefix(line.message_text()).is_some())
+    }
+
+    fn process(&self, context: &mut PluginChainContext, request: &ParseRequest) -> Result<(), String> {
+        info!("🐙 Docker Compose日志过滤器开始处理");
+        let strict = request.is_strict();
+
+        let mut lines = if context.current_lines.is_empty() {
+            // 第一次处理，从原始内容创建行列表，过滤空行
+            context.original_content.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()).map(|(i, line)| {
+                LogLine {
+                    line_number: i + 1,
+                    content: line.to_string(),
+                    level: None,
+                    timestamp: None,
+                    formatted_content: None,
+                    message: None,
+                    metadata: HashMap::new(),
+                    processed_by: vec![],
+                }
+            }).collect()
+        } else {
+            std::mem::take(&mut context.current_lines)
+        };
+
+        let mut services = BTreeSet::new();
+        for line in &mut lines {
+            let source = line.message_text().to_string();
+            let Some(prefix) = Self::split_prefix(&source) else { continue };
+
+            let mut rest = prefix.rest;
+            if let Some(caps) = DOCKER_TIMESTAMP.captures(rest) {
+                line.timestamp = Some(caps[1].to_string());
+                rest = &rest[caps.get(0).unwrap().end()..];
+            }
+            let rest = rest.to_string();
+
+            let service = Self::service_name(&prefix.container).to_string();
+            services.insert(service.clone());
+            line.metadata.insert("service".to_string(), service);
+            line.metadata.insert("container".to_string(), prefix.container);
+            if let Some(color) = prefix.color {
+                line.metadata.insert("service_color".to_string(), color);
+            }
+            line.set_message(rest, strict);
+            line.formatted_content = Some(line.message_text().to_string());
+            line.processed_by.push("compose_filter".to_string());
+        }
+
+        context.current_lines = lines;
+        context.set_chain_metadata("compose_services".to_string(), services.into_iter().collect::<Vec<_>>().join(","));
+
+        info!("🐙 Docker Compose日志过滤器处理完成");
+        Ok(())
+    }
+
+    fn can_handle(&self, content: &str, _file_path: Option<&str>) -> bool {
+        Self::looks_like_compose_log(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::chain::PluginChainManager;
+    use crate::plugins::presets::register_preset_chains;
+
+    fn meta<'a>(line: &'a LogLine, key: &str) -> Option<&'a str> {
+        line.metadata.get(key).map(String::as_str)
+    }
+
+    #[test]
+    fn test_splits_service_prefix_and_colors() {
+        let content = "\
+Attaching to db-1, web-1
+\x1b[36mweb-1  | \x1b[0m2024-01-15 10:30:25.123  INFO 1 --- [main] com.example.App : Started App
+\x1b[33mdb_1   | \x1b[0m2024-01-15T10:30:25.456789012Z \x1b[31mready to accept connections\x1b[0m
+\x1b[36mweb-1  | \x1b[0m2024-01-15 10:30:26.001 ERROR 1 --- [main] com.example.App : Request failed";
+        assert!(ComposeLogFilter.can_handle(content, None));
+
+        let mut manager = PluginChainManager::new();
+        register_preset_chains(&mut manager);
+        let result = manager.process(content, &ParseRequest::default()).unwrap();
+        assert_eq!(result.detected_format.as_deref(), Some("compose"));
+
+        let lines = &result.lines;
+        assert_eq!(meta(&lines[0], "service"), None);
+        assert_eq!(meta(&lines[1], "service"), Some("web"));
+        assert_eq!(meta(&lines[1], "container"), Some("web-1"));
+        assert_eq!(meta(&lines[1], "logger"), Some("com.example.App"));
+        assert_eq!(lines[1].content, "Started App");
+        assert_eq!(meta(&lines[2], "service"), Some("db"));
+        assert_eq!(lines[2].timestamp.as_deref(), Some("2024-01-15T10:30:25"));
+        assert_eq!(lines[2].content, "ready to accept connections");
+        assert_eq!(meta(&lines[2], "ansi_color"), Some("red"));
+        assert_eq!(lines[3].level.as_deref(), Some("ERROR"));
+    }
+
+    #[test]
+    fn test_rejects_pipe_separated_formats() {
+        assert!(!ComposeLogFilter::looks_like_compose_log("INFO  | started\nERROR | failed"));
+        assert!(!ComposeLogFilter::looks_like_compose_log("| a | b |\n| 1 | 2 |"));
+        assert!(!ComposeLogFilter::looks_like_compose_log("2024-01-15 10:30:25 | INFO | started\n2024-01-15 10:30:26 | INFO | done"));
+        assert_eq!(ComposeLogFilter::service_name("api_gateway-2"), "api_gateway");
+        assert_eq!(ComposeLogFilter::service_name("redis"), "redis");
+    }
+}
@@ -0,0 +1,350 @@
+/// 解析器一致性测试（黄金文件）
+///
+/// 从fixture目录加载样本日志，用每个已注册的解析器解析，并与 `golden/` 子目录中
+/// 保存的期望输出（`<样本名>.<解析器>.json`）逐条目、逐字段比较，防止解析器改动引入回归。
+/// 插件作者也可以通过 `run_parser_conformance` 开发者命令对自己的样本目录运行同样的检查。
+///
+/// # 目录结构
+/// - `<fixtures>/*.log`: 样本日志
+/// - `<fixtures>/golden/<样本名>.<解析器>.json`: 期望输出（条目列表或解析错误）
+///
+/// 期望输出不存在时报告为缺失；以更新模式运行时按当前输出写入（或覆盖）期望文件。
+
+use crate::i18n::Message;
+use crate::plugins::manager::PluginManager;
+use crate::plugins::ParseRequest;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+/// 期望输出所在的子目录
+pub const GOLDEN_DIR: &str = "golden";
+
+/// 样本日志的扩展名
+pub const FIXTURE_EXTENSION: &str = "log";
+
+/// 每个用例最多报告的差异数
+const MAX_DIFFS: usize = 20;
+
+/// 一致性测试选项
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConformanceOptions {
+    /// 只运行这些解析器（None表示所有已注册的解析器）
+    #[serde(default)]
+    pub parsers: Option<Vec<String>>,
+
+    /// 按当前输出写入期望文件，而不是比较
+    #[serde(default)]
+    pub update: bool,
+}
+
+/// 用例结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaseStatus {
+    /// 输出与期望一致
+    Passed,
+    /// 输出与期望不一致
+    Failed,
+    /// 没有期望文件
+    Missing,
+    /// 已按当前输出写入期望文件
+    Updated,
+}
+
+/// 一处差异
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntryDiff {
+    /// 条目序号（从0开始，整体差异如条目数或解析错误时为None）
+    pub index: Option<usize>,
+    /// 字段（元数据字段为 `metadata.<键>`）
+    pub field: String,
+    /// 期望值
+    pub expected: Value,
+    /// 实际值
+    pub actual: Value,
+}
+
+/// 一个样本 × 解析器的用例
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConformanceCase {
+    /// 样本文件名
+    pub fixture: String,
+    /// 解析器名称
+    pub parser: String,
+    /// 结果
+    pub status: CaseStatus,
+    /// 期望的条目数（没有期望文件时为0）
+    pub expected_entries: usize,
+    /// 实际的条目数
+    pub actual_entries: usize,
+    /// 差异（最多 `MAX_DIFFS` 处）
+    pub diffs: Vec<EntryDiff>,
+}
+
+/// 一致性测试报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConformanceReport {
+    /// 样本目录
+    pub fixtures_dir: String,
+    /// 所有用例（按样本名、解析器名排序）
+    pub cases: Vec<ConformanceCase>,
+    /// 通过的用例数
+    pub passed: usize,
+    /// 失败的用例数
+    pub failed: usize,
+    /// 缺少期望文件的用例数
+    pub missing: usize,
+    /// 已更新期望文件的用例数
+    pub updated: usize,
+}
+
+impl ConformanceReport {
+    /// 所有用例都通过（或已更新）
+    pub fn is_success(&self) -> bool {
+        self.failed == 0 && self.missing == 0
+    }
+}
+
+/// 期望文件内容：解析出的条目，或解析失败时的错误信息
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct Golden {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    #[serde(default)]
+    entries: Vec<Value>,
+}
+
+/// 期望文件路径
+pub fn golden_path(fixtures_dir: &Path, fixture: &str, parser: &str) -> PathBuf {
+    let stem = Path::new(fixture).file_stem().map_or_else(|| fixture.into(), |s| s.to_string_lossy());
+    fixtures_dir.join(GOLDEN_DIR).join(format!("{}.{}.json", stem, parser))
+}
+
+/// 列出目录中的样本文件名（按名称排序）
+fn list_fixtures(fixtures_dir: &Path) -> Result<Vec<String>, String> {
+    let entries = std::fs::read_dir(fixtures_dir)
+        .map_err(|e| Message::new("conformance.read_fixtures_failed").with("path", fixtures_dir.display()).with("error", e).text())?;
+    let mut fixtures: Vec<String> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == FIXTURE_EXTENSION))
+        .filter_map(|path| path.file_name().map(|name| name.to_string_lossy().into_owned()))
+        .collect();
+    fixtures.sort();
+    Ok(fixtures)
+}
+
+/// 用解析器解析样本，得到与期望文件相同结构的输出
+///
+/// 条目转换为JSON值后元数据按键排序，期望文件的内容因此是稳定的。
+fn run_parser(manager: &PluginManager, parser: &str, fixture: &str, content: &str) -> Golden {
+    let request = ParseRequest {
+        content: content.to_string(),
+        plugin: Some(parser.to_string()),
+        // 只传文件名，期望输出不受样本目录位置影响
+        file_path: Some(fixture.to_string()),
+        ..Default::default()
+    };
+    match manager.parse_with_plugin(parser, &request) {
+        Ok(result) => Golden {
+            error: None,
+            entries: result.lines.iter().map(|line| serde_json::to_value(line).unwrap_or(Value::Null)).collect(),
+        },
+        Err(e) => Golden { error: Some(e), entries: Vec::new() },
+    }
+}
+
+/// 逐条目、逐字段比较期望输出和实际输出
+fn diff_outputs(expected: &Golden, actual: &Golden) -> Vec<EntryDiff> {
+    let mut diffs = Vec::new();
+    if expected.error != actual.error {
+        diffs.push(EntryDiff {
+            index: None,
+            field: "error".to_string(),
+            expected: expected.error.clone().map_or(Value::Null, Value::String),
+            actual: actual.error.clone().map_or(Value::Null, Value::String),
+        });
+    }
+    if expected.entries.len() != actual.entries.len() {
+        diffs.push(EntryDiff {
+            index: None,
+            field: "entries".to_string(),
+            expected: expected.entries.len().into(),
+            actual: actual.entries.len().into(),
+        });
+    }
+
+    let null = Value::Null;
+    for (index, (expected, actual)) in expected.entries.iter().zip(&actual.entries).enumerate() {
+        for (field, expected, actual) in field_pairs(expected, actual) {
+            if expected != actual {
+                diffs.push(EntryDiff {
+                    index: Some(index),
+                    field,
+                    expected: expected.unwrap_or(&null).clone(),
+                    actual: actual.unwrap_or(&null).clone(),
+                });
+            }
+        }
+    }
+    diffs.truncate(MAX_DIFFS);
+    diffs
+}
+
+/// 列出两个条目的所有字段（元数据展开为 `metadata.<键>`）
+fn field_pairs<'a>(expected: &'a Value, actual: &'a Value) -> Vec<(String, Option<&'a Value>, Option<&'a Value>)> {
+    let keys = |value: &'a Value| value.as_object().into_iter().flat_map(|map| map.keys().cloned());
+    let fields: BTreeSet<String> = keys(expected).chain(keys(actual)).collect();
+
+    let mut pairs = Vec::new();
+    for field in fields {
+        if field == "metadata" {
+            let expected = &expected["metadata"];
+            let actual = &actual["metadata"];
+            let keys: BTreeSet<String> = keys(expected).chain(keys(actual)).collect();
+            for key in keys {
+                pairs.push((format!("metadata.{}", key), expected.get(&key), actual.get(&key)));
+            }
+        } else {
+            pairs.push((field.clone(), expected.get(&field), actual.get(&field)));
+        }
+    }
+    pairs
+}
+
+/// 对样本目录运行解析器一致性测试
+///
+/// # 参数
+/// - `manager`: 插件管理器（提供已注册的解析器）
+/// - `fixtures_dir`: 样本目录
+/// - `options`: 要运行的解析器和是否更新期望文件
+///
+/// # Returns
+/// - `Ok(ConformanceReport)`: 每个样本 × 解析器的比较结果
+/// - `Err(String)`: 样本目录不可读、解析器不存在或期望文件无效/无法写入时的错误信息
+pub fn run_conformance(manager: &PluginManager, fixtures_dir: &Path, options: &ConformanceOptions) -> Result<ConformanceReport, String> {
+    let parsers = match &options.parsers {
+        Some(parsers) => {
+            if let Some(unknown) = parsers.iter().find(|p| manager.get_parser(p).is_none()) {
+                return Err(Message::new("plugin.not_found")
+                    .with("plugin", unknown)
+                    .with("available", format!("{:?}", manager.plugin_names()))
+                    .text());
+            }
+            parsers.clone()
+        }
+        None => manager.plugin_names(),
+    };
+
+    let mut cases = Vec::new();
+    for fixture in list_fixtures(fixtures_dir)? {
+        let content = std::fs::read_to_string(fixtures_dir.join(&fixture))
+            .map_err(|e| Message::new("conformance.read_fixture_failed").with("path", &fixture).with("error", e).text())?;
+
+        for parser in &parsers {
+            let actual = run_parser(manager, parser, &fixture, &content);
+            let path = golden_path(fixtures_dir, &fixture, parser);
+
+            let expected = match std::fs::read_to_string(&path) {
+                Ok(text) => Some(serde_json::from_str::<Golden>(&text)
+                    .map_err(|e| Message::new("conformance.invalid_expected").with("path", path.display()).with("error", e).text())?),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+                Err(e) => {
+                    return Err(Message::new("conformance.read_expected_failed").with("path", path.display()).with("error", e).text());
+                }
+            };
+
+            let (status, diffs) = if options.update {
+                std::fs::create_dir_all(fixtures_dir.join(GOLDEN_DIR))
+                    .map_err(|e| Message::new("conformance.create_directory_failed").with("error", e).text())?;
+                let json = serde_json::to_string_pretty(&actual)
+                    .map_err(|e| Message::new("conformance.serialize_failed").with("error", e).text())?;
+                crate::write_guard::atomic_write(&path, format!("{}\n", json).as_bytes())?;
+                (CaseStatus::Updated, Vec::new())
+            } else {
+                match &expected {
+                    None => (CaseStatus::Missing, Vec::new()),
+                    Some(expected) => {
+                        let diffs = diff_outputs(expected, &actual);
+                        let status = if diffs.is_empty() { CaseStatus::Passed } else { CaseStatus::Failed };
+                        (status, diffs)
+                    }
+                }
+            };
+
+            cases.push(ConformanceCase {
+                fixture: fixture.clone(),
+                parser: parser.clone(),
+                status,
+                expected_entries: expected.map_or(0, |golden| golden.entries.len()),
+                actual_entries: actual.entries.len(),
+                diffs,
+            });
+        }
+    }
+
+    let count = |status: CaseStatus| cases.iter().filter(|case| case.status == status).count();
+    Ok(ConformanceReport {
+        fixtures_dir: fixtures_dir.to_string_lossy().into_owned(),
+        passed: count(CaseStatus::Passed),
+        failed: count(CaseStatus::Failed),
+        missing: count(CaseStatus::Missing),
+        updated: count(CaseStatus::Updated),
+        cases,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 仓库中的样本目录；设置环境变量 `UPDATE_GOLDEN=1` 运行测试可重新生成期望文件
+    fn repository_fixtures() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures")
+    }
+
+    #[test]
+    fn test_repository_fixtures_match_golden_files() {
+        let options = ConformanceOptions {
+            update: std::env::var_os("UPDATE_GOLDEN").is_some(),
+            ..Default::default()
+        };
+        let report = run_conformance(&PluginManager::new(), &repository_fixtures(), &options).unwrap();
+
+        assert!(!report.cases.is_empty());
+        let problems: Vec<_> = report.cases.iter().filter(|case| matches!(case.status, CaseStatus::Failed | CaseStatus::Missing)).collect();
+        assert!(problems.is_empty(), "解析输出与期望文件不一致（确认改动后用 UPDATE_GOLDEN=1 重新生成）: {:#?}", problems);
+    }
+
+    #[test]
+    fn test_missing_update_and_regression_detection() {
+        let dir = std::env::temp_dir().join(format!("log_whisper_conformance_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("app.log"), "2024-01-15 10:30:25.123 ERROR [main] c.e.App - boom\n").unwrap();
+        let manager = PluginManager::new();
+        let options = ConformanceOptions { parsers: Some(vec!["raw".to_string()]), update: false };
+
+        let report = run_conformance(&manager, &dir, &options).unwrap();
+        assert_eq!((report.missing, report.is_success()), (1, false));
+
+        let update = ConformanceOptions { update: true, ..options.clone() };
+        assert_eq!(run_conformance(&manager, &dir, &update).unwrap().updated, 1);
+        assert!(run_conformance(&manager, &dir, &options).unwrap().is_success());
+
+        // 篡改期望文件，模拟解析器行为变化
+        let path = golden_path(&dir, "app.log", "raw");
+        let mut golden: Golden = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        golden.entries[0]["level"] = Value::String("WARN".to_string());
+        std::fs::write(&path, serde_json::to_string(&golden).unwrap()).unwrap();
+
+        let report = run_conformance(&manager, &dir, &options).unwrap();
+        let case = &report.cases[0];
+        assert_eq!(case.status, CaseStatus::Failed);
+        assert_eq!((case.diffs[0].index, case.diffs[0].field.as_str()), (Some(0), "level"));
+        assert_eq!(case.diffs[0].expected, "WARN");
+
+        assert!(run_conformance(&manager, &dir, &ConformanceOptions { parsers: Some(vec!["nope".to_string()]), update: false }).is_err());
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
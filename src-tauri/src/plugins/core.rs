@@ -22,9 +22,14 @@
 /// - 保持API兼容性的同时增强能力
 
 use crate::plugins::{manager::PluginManager, PluginInfo, ParseRequest, ParseResult, LogEntry};
-use crate::plugins::chain::{PluginChainManager};
-use crate::plugins::presets::register_preset_chains;
+use crate::plugins::chain::{ChainTrace, ChunkPosition, DetectionSample, PluginChainManager};
+use crate::plugins::presets::{register_preset_chains, register_preset_chains_with_settings};
+use crate::i18n::{self, Message};
+use crate::plugins::settings::{validate_settings, PluginSettings, PluginSettingsSchema};
+use crate::plugins::validation::{validate_parser, ParserValidationReport};
+use crate::plugins::conformance::{run_conformance, ConformanceOptions, ConformanceReport};
 use log::{info, debug, warn, error};
+use std::collections::{BTreeMap, HashMap};
 use std::sync::{Arc, Mutex};
 
 /// 增强插件管理器主结构
@@ -106,7 +111,7 @@ impl EnhancedPluginManager {
                 info!("✅ 已注册 {} 个预设链: {:?}", available_chains.len(), available_chains);
             } else {
                 error!("❌ 无法获取插件链管理器锁，初始化失败");
-                return Err("插件链管理器初始化失败".to_string());
+                return Err(Message::new("chain.manager_init_failed").text());
             }
         } else {
             warn!("⚠️ 插件链系统已禁用，将使用传统单插件模式");
@@ -120,20 +125,217 @@ impl EnhancedPluginManager {
         Ok(())
     }
 
+    /// 使用用户设置重建插件链
+    ///
+    /// 插件在构建链时读取设置，因此设置变更后需要重建所有预设链才能生效。
+    ///
+    /// # 参数
+    /// - `settings`: 插件名称到设置对象的映射（来自 `PluginConfig.plugin_settings`）
+    ///
+    /// # Returns
+    /// - `Ok(())`: 重建成功
+    /// - `Err(String)`: 无法获取插件链管理器锁时的错误信息
+    pub fn apply_plugin_settings(&self, settings: &HashMap<String, serde_json::Value>) -> Result<(), String> {
+        let mut chain_manager = self.chain_manager.lock()
+            .map_err(|_| Message::new("chain.manager_lock_failed").text())?;
+
+        let mut rebuilt = PluginChainManager::new();
+        register_preset_chains_with_settings(&mut rebuilt, &PluginSettings::new(settings.clone()));
+        rebuilt.set_detection_sample(chain_manager.detection_sample());
+        *chain_manager = rebuilt;
+
+        info!("⚙️ 已按插件设置重建插件链 ({} 个插件有自定义设置)", settings.len());
+        Ok(())
+    }
+
+    /// 设置自动检测的采样范围
+    ///
+    /// # 参数
+    /// - `sample`: 选择插件链时检查的内容范围
+    pub fn set_detection_sample(&self, sample: DetectionSample) -> Result<(), String> {
+        self.chain_manager.lock()
+            .map_err(|_| Message::new("chain.manager_lock_failed").text())?
+            .set_detection_sample(sample);
+        debug!("🎯 自动检测采样范围: {} 字节 / {} 行", sample.max_bytes, sample.max_lines);
+        Ok(())
+    }
+
+    /// 是否存在指定名称的格式（插件链或单插件解析器）
+    pub fn has_format(&self, format: &str) -> bool {
+        let has_chain = self.chain_enabled
+            && self.chain_manager.lock().map(|manager| manager.has_chain(format)).unwrap_or(false);
+        has_chain || self.inner.get_parser(format).is_some()
+    }
+
+    /// 所有可用的格式名称（插件链和单插件解析器，按字母排序并去重）
+    pub fn format_names(&self) -> Vec<String> {
+        let mut names = self.get_available_chains();
+        names.extend(self.inner.plugin_names());
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// 校验请求中指定的插件
+    ///
+    /// # 参数
+    /// - `plugin`: 请求中的插件名称（None或"auto"表示自动检测）
+    ///
+    /// # Returns
+    /// - `Ok(Some(String))`: 指定了已注册的插件链或解析器
+    /// - `Ok(None)`: 未指定插件，使用自动检测
+    /// - `Err(String)`: 插件不存在，错误信息中列出所有可用的插件名称
+    pub fn resolve_plugin(&self, plugin: Option<&str>) -> Result<Option<String>, String> {
+        match plugin.map(str::trim).filter(|name| !name.is_empty() && *name != "auto") {
+            None => Ok(None),
+            Some(name) if self.has_format(name) => Ok(Some(name.to_string())),
+            Some(name) => Err(Message::new("plugin.unknown")
+                .with("plugin", name)
+                .with("available", self.format_names().join(", "))
+                .text()),
+        }
+    }
+
+    /// 使用用户为文件指定的格式解析，跳过自动检测
+    ///
+    /// 优先按同名插件链处理（不检查链的执行条件），没有同名链时使用同名的单插件解析器。
+    ///
+    /// # 参数
+    /// - `format`: 格式名称（插件链或插件名称）
+    /// - `request`: 解析请求参数
+    ///
+    /// # Returns
+    /// - `Ok(ParseResult)`: 解析结果，`detected_format` 为指定的格式
+    /// - `Err(String)`: 格式不存在或解析失败
+    pub fn parse_with_format(&self, format: &str, request: &ParseRequest) -> Result<ParseResult, String> {
+        if self.chain_enabled {
+            let chain_manager = self.chain_manager.lock()
+                .map_err(|_| Message::new("chain.manager_lock_failed").text())?;
+            if chain_manager.has_chain(format) {
+                return chain_manager.process_forced(format, &request.content, request);
+            }
+        }
+
+        let mut result = self.inner.parse_with_plugin(format, request)?;
+        result.detected_format = Some(format.to_string());
+        crate::records::mark_record_spans(&mut result.lines);
+        if let Some(chunk) = ChunkPosition::from_request(request) {
+            chunk.finish(&mut result.lines);
+        }
+        Ok(result)
+    }
+
+    /// 获取所有插件的配置项Schema及当前设置
+    ///
+    /// # 参数
+    /// - `settings`: 当前持久化的插件设置
+    ///
+    /// # Returns
+    /// - `Vec<PluginSettingsSchema>`: 声明了配置项的插件列表（按名称排序）
+    pub fn plugin_settings_schemas(&self, settings: &HashMap<String, serde_json::Value>) -> Vec<PluginSettingsSchema> {
+        let schemas = match self.chain_manager.lock() {
+            Ok(chain_manager) => chain_manager.settings_schemas(),
+            Err(_) => {
+                warn!("⚠️ 无法获取插件链管理器锁，返回空的配置项列表");
+                return Vec::new();
+            }
+        };
+
+        schemas.into_iter().map(|(plugin, description, schema)| {
+            let values = settings.get(&plugin).cloned().unwrap_or_else(|| serde_json::json!({}));
+            let description = i18n::tr_or(&format!("filter.{}", plugin), &description);
+            PluginSettingsSchema { plugin, description, schema, values }
+        }).collect()
+    }
+
+    /// 按插件声明的Schema校验设置
+    ///
+    /// # 参数
+    /// - `plugin`: 插件名称
+    /// - `settings`: 待保存的设置对象
+    ///
+    /// # Returns
+    /// - `Ok(())`: 设置合法
+    /// - `Err(String)`: 插件没有可配置项或设置不合法时的错误信息
+    pub fn validate_plugin_settings(&self, plugin: &str, settings: &serde_json::Value) -> Result<(), String> {
+        let schema = self.plugin_settings_schemas(&HashMap::new())
+            .into_iter()
+            .find(|schema| schema.plugin == plugin)
+            .ok_or_else(|| Message::new("plugin_settings.no_schema").with("plugin", plugin).text())?;
+
+        validate_settings(&schema.schema, settings)
+    }
+
     /// 获取所有可用插件的详细信息
     ///
-    /// 委托给内部的PluginManager，返回系统中所有已注册插件的元数据。
-    /// 这是一个透明代理方法，保持与基础管理器的接口兼容性。
+    /// 合并内部PluginManager中注册的单插件解析器和插件链管理器中注册的链
+    /// （包括自定义布局链等按配置重建的链），与解析时一样，同名时插件链优先。
     ///
     /// # Returns
-    /// - `Vec<PluginInfo>`: 所有插件的详细信息列表
+    /// - `Vec<PluginInfo>`: 所有插件的详细信息列表（按名称排序）
     ///
     /// # 包含信息
-    /// - 插件名称和描述
+    /// - 插件名称、描述和版本
     /// - 支持的文件扩展名
-    /// - 自动检测能力
+    /// - 自动检测能力、类型和启用状态
     pub fn get_available_plugins(&self) -> Vec<PluginInfo> {
-        self.inner.get_available_plugins()
+        let mut plugins: BTreeMap<String, PluginInfo> = self.inner.get_available_plugins()
+            .into_iter()
+            .map(|plugin| (plugin.name.clone(), plugin))
+            .collect();
+        if self.chain_enabled {
+            if let Ok(chain_manager) = self.chain_manager.lock() {
+                for chain in chain_manager.chain_infos() {
+                    plugins.insert(chain.name.clone(), chain);
+                }
+            }
+        }
+        plugins.into_values().collect()
+    }
+
+    /// 使用指定解析器逐行验证样本内容
+    ///
+    /// 供开发者调试解析器或自定义正则使用，返回逐行的识别结果和捕获字段。
+    ///
+    /// # 参数
+    /// - `plugin_name`: 要验证的插件名称
+    /// - `content`: 样本内容
+    /// - `file_path`: 样本文件路径（可选）
+    /// - `pattern`: 可选的自定义正则表达式
+    ///
+    /// # Returns
+    /// - `Ok(ParserValidationReport)`: 逐行验证报告
+    /// - `Err(String)`: 插件不存在或正则无效
+    pub fn validate_parser(
+        &self,
+        plugin_name: &str,
+        content: &str,
+        file_path: Option<&str>,
+        pattern: Option<&str>,
+    ) -> Result<ParserValidationReport, String> {
+        let parser = self.inner.get_parser(plugin_name).ok_or_else(|| {
+            Message::new("plugin.not_found")
+                .with("plugin", plugin_name)
+                .with("available", format!("{:?}", self.inner.plugin_names()))
+                .text()
+        })?;
+
+        debug!("🧪 验证解析器: {}", plugin_name);
+        validate_parser(parser.as_ref(), content, file_path, pattern)
+    }
+
+    /// 对样本目录运行解析器一致性测试（与黄金文件比较）
+    ///
+    /// # 参数
+    /// - `fixtures_dir`: 样本目录
+    /// - `options`: 要运行的解析器和是否更新期望文件
+    ///
+    /// # Returns
+    /// - `Ok(ConformanceReport)`: 每个样本 × 解析器的比较结果
+    /// - `Err(String)`: 样本目录不可读、插件不存在或期望文件无效时的错误信息
+    pub fn run_conformance(&self, fixtures_dir: &std::path::Path, options: &ConformanceOptions) -> Result<ConformanceReport, String> {
+        debug!("🧪 运行解析器一致性测试: {}", fixtures_dir.display());
+        run_conformance(&self.inner, fixtures_dir, options)
     }
 
     /// 使用指定插件解析日志内容
@@ -211,7 +413,13 @@ impl EnhancedPluginManager {
 
         // 回退到传统的单插件模式
         debug!("🔄 使用传统单插件模式处理");
-        self.inner.auto_detect_and_parse(request)
+        let mut result = self.inner.auto_detect_and_parse(request)?;
+        crate::records::mark_record_spans(&mut result.lines);
+        // 单插件解析器不感知分块位置，在这里统一丢弃重叠区条目并换算行号
+        if let Some(chunk) = ChunkPosition::from_request(request) {
+            chunk.finish(&mut result.lines);
+        }
+        Ok(result)
     }
 
     /// 批量处理日志条目
@@ -271,27 +479,43 @@ impl EnhancedPluginManager {
     /// - `Err(String)`: 链不存在或处理失败
     pub fn process_with_chain(&self, chain_name: &str, request: &ParseRequest) -> Result<ParseResult, String> {
         if !self.chain_enabled {
-            return Err("插件链系统已禁用".to_string());
+            return Err(Message::new("chain.system_disabled").text());
         }
 
         info!("🔗 使用指定插件链处理: {}", chain_name);
 
-        // 获取可用链列表
-        let available_chains = if let Ok(chain_manager) = self.chain_manager.lock() {
-            chain_manager.get_available_chains()
-        } else {
-            return Err("无法获取插件链管理器锁".to_string());
-        };
+        let chain_manager = self.chain_manager.lock()
+            .map_err(|_| Message::new("chain.manager_lock_failed").text())?;
+        if !chain_manager.has_chain(chain_name) {
+            return Err(Message::new("chain.not_found_with_available")
+                .with("chain", chain_name)
+                .with("available", format!("{:?}", chain_manager.get_available_chains()))
+                .text());
+        }
+        chain_manager.process_with(chain_name, &request.content, request)
+    }
 
-        if !available_chains.contains(&chain_name.to_string()) {
-            return Err(format!("插件链 '{}' 不存在，可用链: {:?}", chain_name, available_chains));
+    /// 逐步追踪插件链对一行日志的处理
+    ///
+    /// 记录链中每个过滤器是否处理、改变了哪些字段、添加了哪些元数据，
+    /// 用于排查某一行为什么被解析错。
+    ///
+    /// # 参数
+    /// - `chain_name`: 指定的链名称；None时按 `sample` 智能选择
+    /// - `sample`: 用于选择链的内容样本
+    /// - `request`: 解析请求参数，`request.content` 为要追踪的行
+    ///
+    /// # Returns
+    /// - `Ok(ChainTrace)`: 逐步追踪结果
+    /// - `Err(String)`: 插件链系统已禁用、链不存在或已禁用
+    pub fn explain(&self, chain_name: Option<&str>, sample: &str, request: &ParseRequest) -> Result<ChainTrace, String> {
+        if !self.chain_enabled {
+            return Err(Message::new("chain.system_disabled").text());
         }
 
-        // 临时实现：设置请求中的链名称，然后在process中处理
-        // 这需要修改ParseRequest结构体来支持链名称参数
-        // 暂时使用自动检测作为回退
-        warn!("⚠️ 指定链处理功能尚未完全实现，使用自动检测");
-        self.auto_detect_and_parse(request)
+        let chain_manager = self.chain_manager.lock()
+            .map_err(|_| Message::new("chain.manager_lock_failed").text())?;
+        chain_manager.explain(chain_name, sample, request)
     }
 
     /// 获取所有可用的插件链信息
@@ -15,7 +15,7 @@ mod debug_tests {
 
         println!("🐳 Testing DockerJsonFilter directly");
 
-        let docker_filter = DockerJsonFilter;
+        let docker_filter = DockerJsonFilter::default();
         let docker_content = r#"{"log":"[0.000s][warning][gc] -XX:+PrintGCDetails is deprecated. Will use -Xlog:gc* instead.\n","stream":"stdout","time":"2025-09-16T08:17:32.172897326Z"}"#;
 
         println!("Input: {}", docker_content);
@@ -27,7 +27,7 @@ mod debug_tests {
             content: docker_content.to_string(),
             plugin: None,
             file_path: Some("container.log".to_string()),
-            chunk_size: None,
+            ..Default::default()
         };
 
         let should_process = docker_filter.should_process(&context);
@@ -67,7 +67,7 @@ mod debug_tests {
             content: gc_content.to_string(),
             plugin: None,
             file_path: Some("gc.log".to_string()),
-            chunk_size: None,
+            ..Default::default()
         };
 
         let should_process = java_filter.should_process(&context);
@@ -107,7 +107,7 @@ mod debug_tests {
         let available_chains = chain_manager.get_available_chains();
         println!("Available chains: {:?}", available_chains);
 
-        let selected_chain = chain_manager.select_best_chain(docker_content, Some("container.log"));
+        let selected_chain = chain_manager.select_best_chain(docker_content, Some("container.log"), 0).0;
         match selected_chain {
             Some(chain) => {
                 println!("Selected chain: {}", chain.name);
@@ -124,7 +124,7 @@ mod debug_tests {
                     content: docker_content.to_string(),
                     plugin: None,
                     file_path: Some("container.log".to_string()),
-                    chunk_size: None,
+                    ..Default::default()
                 };
 
                 println!("\n🔄 Running chain processing...");
@@ -178,7 +178,7 @@ mod debug_tests {
             content: docker_content.to_string(),
             plugin: None,
             file_path: Some("container.log".to_string()),
-            chunk_size: None,
+            ..Default::default()
         };
 
         println!("🔄 Running enhanced manager processing...");
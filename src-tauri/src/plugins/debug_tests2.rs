@@ -29,7 +29,7 @@ mod debug_tests2 {
 
         for path in test_paths {
             println!("\nTesting path: {:?}", path);
-            let selected_chain = chain_manager.select_best_chain(docker_content, path);
+            let selected_chain = chain_manager.select_best_chain(docker_content, path, 0).0;
             match selected_chain {
                 Some(chain) => {
                     println!("  Selected chain: {}", chain.name);
@@ -80,7 +80,7 @@ mod debug_tests2 {
         let docker_content = r#"{"log":"[0.000s][warning][gc] -XX:+PrintGCDetails is deprecated. Will use -Xlog:gc* instead.\n","stream":"stdout","time":"2025-09-16T08:17:32.172897326Z"}"#;
 
         // Get docker chain directly by selecting it specifically
-        let docker_chain = chain_manager.select_best_chain(docker_content, Some("docker-container.log"));
+        let docker_chain = chain_manager.select_best_chain(docker_content, Some("docker-container.log"), 0).0;
         if let Some(docker_chain) = docker_chain {
             // Only proceed if it's actually the docker chain
             if docker_chain.name != "docker" {
@@ -110,7 +110,7 @@ mod debug_tests2 {
                 content: docker_content.to_string(),
                 plugin: None,
                 file_path: Some("docker-container.log".to_string()), // Use path that should match
-                chunk_size: None,
+                ..Default::default()
             };
 
             println!("\n🔄 Running direct docker chain processing...");
@@ -164,7 +164,7 @@ mod debug_tests2 {
                 content: docker_content.to_string(),
                 plugin: None,
                 file_path: Some(path.to_string()),
-                chunk_size: None,
+                ..Default::default()
             };
 
             let recommended = enhanced_manager.recommend_chain(docker_content, Some(path));
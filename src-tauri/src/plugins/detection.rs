@@ -0,0 +1,253 @@
+/// 日志格式置信度评分
+///
+/// 对内容开头的样本行逐行做结构匹配（正则或JSON结构），每种格式给出0.0-1.0的置信度，
+/// 得分最高且超过 `MIN_CONFIDENCE` 的格式胜出。解析器的自动检测和插件链的智能选择共用 `DETECTORS` 中的评分。
+///
+/// 外层包装格式（CI作业日志、Lambda调用、测试运行器输出、FREB跟踪）内部可以是任意日志，
+/// 只要样本中出现标记行就给出 `MARKER_CONFIDENCE`，其余格式按匹配行所占比例计分。
+///
+/// 与简单的 `contains()` 关键词判断相比，混合日志（如夹杂SQL和堆栈的SpringBoot日志、
+/// 内层是SpringBoot日志的Docker JSON）也能得到正确的结果：
+/// - 堆栈续行（`at ...`、`Caused by:`、`... N more`）不计入分母
+/// - 每行只按整行结构计分，日志正文中偶然出现的关键词不会加分
+
+use crate::plugins::{
+    cef::CefLeefFilter, ci::CiLogFilter, compose::ComposeLogFilter, elasticsearch::ElasticsearchLogFilter,
+    envoy::EnvoyAccessLogFilter, haproxy::HaproxyLogFilter, heroku::HerokuRouterFilter, kafka::KafkaLogFilter,
+    lambda::LambdaLogFilter, nginx::NginxErrorFilter, test_runner::TestRunnerFilter,
+};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+
+/// 参与评分的最大样本行数（空行不计）
+pub const SAMPLE_LINES: usize = 200;
+
+/// 判定为某种格式所需的最低置信度
+pub const MIN_CONFIDENCE: f32 = 0.3;
+
+/// 外层包装格式出现标记行时的置信度
+pub const MARKER_CONFIDENCE: f32 = 1.0;
+
+/// SpringBoot / Logback 默认布局：时间戳开头，随后（可选的线程名）和日志级别
+static SPRINGBOOT_LINE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\d{4}-\d{2}-\d{2}[ T]\d{2}:\d{2}:\d{2}(?:[.,]\d{1,9})?(?:Z|[+-]\d{2}:?\d{2})?\s+(?:\[[^\]]*\]\s+)?(?:TRACE|DEBUG|INFO|WARN|WARNING|ERROR|FATAL)\b")
+        .expect("SpringBoot行正则无效")
+});
+
+/// MyBatis SQL日志标记（`==>  Preparing:`、`<==      Total:` 等）
+static MYBATIS_LINE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?:==>\s+(?:Preparing|Parameters):|<==\s+(?:Total|Updates|Columns|Row):)")
+        .expect("MyBatis行正则无效")
+});
+
+/// 带有分布式追踪ID的行（MDC字段 `traceId=` / `"trace_id":`，或Sleuth的 `[应用,traceId,spanId]`）
+static TRACED_LINE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?i)(?:\btrace_?id"?\s*[=:]|\[[\w.-]*,[0-9a-f]{16,32},[0-9a-f]{16}(?:,\w+)?\])"#)
+        .expect("追踪ID正则无效")
+});
+
+/// 多行记录的续行（堆栈帧、异常原因、省略的帧）
+static CONTINUATION_LINE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(?:\s+at\s|\s*Caused by:|\s*\.\.\.\s+\d+\s+more|\s+)").expect("续行正则无效")
+});
+
+/// 单个格式的评分
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FormatScore {
+    /// 格式名称（与解析器名称一致）
+    pub format: String,
+    /// 置信度（0.0 - 1.0）
+    pub confidence: f32,
+    /// 处理该格式的插件链
+    pub chain: String,
+}
+
+/// 单个格式的检测器
+pub struct FormatDetector {
+    /// 格式名称（有专用解析器的格式与解析器名称一致）
+    pub format: &'static str,
+    /// 处理该格式的插件链
+    pub chain: &'static str,
+    /// 按样本行计算置信度
+    pub confidence: fn(&[&str]) -> f32,
+}
+
+/// 所有内置格式的检测器
+///
+/// 得分相同时靠前的检测器胜出：结构最严格的Docker JSON最先，随后是外层包装格式
+/// （其中的应用日志也会匹配后面的格式），通用的SpringBoot和MyBatis最后。
+pub static DETECTORS: &[FormatDetector] = &[
+    FormatDetector { format: "docker_json", chain: "docker", confidence: docker_json_confidence },
+    FormatDetector { format: "ci", chain: "ci", confidence: |lines| markers(lines, CiLogFilter::matches_line) },
+    FormatDetector { format: "lambda", chain: "lambda", confidence: |lines| markers(lines, LambdaLogFilter::matches_line) },
+    FormatDetector { format: "freb", chain: "freb", confidence: freb_confidence },
+    FormatDetector { format: "test_runner", chain: "test_runner", confidence: |lines| markers(lines, TestRunnerFilter::matches_line) },
+    FormatDetector { format: "compose", chain: "compose", confidence: |lines| ratio(lines, ComposeLogFilter::matches_line) },
+    FormatDetector { format: "heroku", chain: "heroku", confidence: |lines| ratio(lines, HerokuRouterFilter::matches_line) },
+    FormatDetector { format: "nginx_error", chain: "nginx_error", confidence: |lines| ratio(lines, NginxErrorFilter::matches_line) },
+    FormatDetector { format: "haproxy", chain: "haproxy", confidence: |lines| ratio(lines, HaproxyLogFilter::matches_line) },
+    FormatDetector { format: "envoy", chain: "envoy", confidence: |lines| ratio(lines, EnvoyAccessLogFilter::matches_line) },
+    FormatDetector { format: "kafka", chain: "kafka", confidence: |lines| ratio(lines, KafkaLogFilter::matches_line) },
+    FormatDetector { format: "elasticsearch", chain: "elasticsearch", confidence: |lines| ratio(lines, ElasticsearchLogFilter::matches_line) },
+    FormatDetector { format: "cef", chain: "cef", confidence: |lines| ratio(lines, CefLeefFilter::matches_line) },
+    FormatDetector { format: "microservice", chain: "microservice", confidence: microservice_confidence },
+    FormatDetector { format: "springboot", chain: "springboot", confidence: springboot_confidence },
+    FormatDetector { format: "mybatis", chain: "database", confidence: mybatis_confidence },
+];
+
+/// 参与自动检测评分的格式
+pub fn detectable_formats() -> impl Iterator<Item = &'static str> {
+    DETECTORS.iter().map(|detector| detector.format)
+}
+
+/// 指定格式是否参与自动检测评分
+pub fn is_detectable(format: &str) -> bool {
+    detectable_formats().any(|name| name == format)
+}
+
+/// 取内容开头的非空样本行
+pub fn sample_lines(content: &str) -> Vec<&str> {
+    content.lines().filter(|line| !line.trim().is_empty()).take(SAMPLE_LINES).collect()
+}
+
+/// 计算样本中满足条件的行所占比例，续行不计入分母
+fn ratio(lines: &[&str], matches: impl Fn(&str) -> bool) -> f32 {
+    let mut total = 0;
+    let mut matched = 0;
+    for line in lines {
+        if matches(line) {
+            matched += 1;
+            total += 1;
+        } else if !CONTINUATION_LINE.is_match(line) {
+            total += 1;
+        }
+    }
+    if total == 0 {
+        return 0.0;
+    }
+    matched as f32 / total as f32
+}
+
+/// 样本中出现标记行时为 `MARKER_CONFIDENCE`，否则为0
+fn markers(lines: &[&str], matches: impl Fn(&str) -> bool) -> f32 {
+    if lines.iter().any(|line| matches(line)) {
+        MARKER_CONFIDENCE
+    } else {
+        0.0
+    }
+}
+
+/// SpringBoot格式置信度：以时间戳和日志级别开头的记录行所占比例
+pub fn springboot_confidence(lines: &[&str]) -> f32 {
+    ratio(lines, |line| SPRINGBOOT_LINE.is_match(line))
+}
+
+/// Docker JSON格式置信度：包含字符串 `log` 字段和 `stream` 字段的JSON对象行所占比例
+pub fn docker_json_confidence(lines: &[&str]) -> f32 {
+    ratio(lines, |line| {
+        let line = line.trim();
+        line.starts_with('{')
+            && serde_json::from_str::<serde_json::Value>(line).is_ok_and(|value| {
+                value.get("log").is_some_and(|log| log.is_string()) && value.get("stream").is_some()
+            })
+    })
+}
+
+/// MyBatis格式置信度：SQL日志标记行所占比例
+///
+/// 夹杂少量SQL的应用日志得分较低，由应用日志的格式胜出（SQL由插件链的MyBatis过滤器处理）。
+pub fn mybatis_confidence(lines: &[&str]) -> f32 {
+    ratio(lines, |line| MYBATIS_LINE.is_match(line))
+}
+
+/// 微服务日志置信度：带有追踪ID的SpringBoot记录行所占比例
+///
+/// 不超过SpringBoot的置信度，只有追踪ID足够普遍时才由排在前面的微服务链胜出。
+pub fn microservice_confidence(lines: &[&str]) -> f32 {
+    ratio(lines, |line| SPRINGBOOT_LINE.is_match(line) && TRACED_LINE.is_match(line))
+}
+
+/// FREB跟踪置信度：开头20行内有 `<failedRequest` 根元素且有 `<Event` 元素时为 `MARKER_CONFIDENCE`
+pub fn freb_confidence(lines: &[&str]) -> f32 {
+    let has_root = lines.iter().take(20).any(|line| line.contains("<failedRequest"));
+    if has_root && lines.iter().any(|line| line.contains("<Event")) {
+        MARKER_CONFIDENCE
+    } else {
+        0.0
+    }
+}
+
+/// 对样本行计算所有内置格式的置信度
+///
+/// # Returns
+/// - `Vec<FormatScore>`: 按置信度从高到低排序的评分
+pub fn score_formats(lines: &[&str]) -> Vec<FormatScore> {
+    let mut scores: Vec<FormatScore> = DETECTORS.iter()
+        .map(|detector| FormatScore {
+            format: detector.format.to_string(),
+            confidence: (detector.confidence)(lines),
+            chain: detector.chain.to_string(),
+        })
+        .collect();
+    // 稳定排序：得分相同时保持 `DETECTORS` 的顺序
+    scores.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+    scores
+}
+
+/// 选出置信度最高且不低于 `MIN_CONFIDENCE` 的格式
+pub fn best_format(lines: &[&str]) -> Option<FormatScore> {
+    score_formats(lines).into_iter()
+        .next()
+        .filter(|score| score.confidence >= MIN_CONFIDENCE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn best(content: &str) -> Option<String> {
+        best_format(&sample_lines(content)).map(|score| score.format)
+    }
+
+    #[test]
+    fn test_mixed_springboot_log_with_sql_and_stack_trace() {
+        let content = "\
+2024-01-15 10:30:25.123  INFO 4242 --- [main] c.e.Application : Started Application
+2024-01-15 10:30:26.001 DEBUG 4242 --- [http-1] c.e.UserMapper.select : ==>  Preparing: SELECT * FROM users WHERE id = ?
+2024-01-15 10:30:26.002 DEBUG 4242 --- [http-1] c.e.UserMapper.select : ==> Parameters: 42(Long)
+2024-01-15 10:30:26.010 ERROR 4242 --- [http-1] c.e.UserController : request failed
+java.lang.IllegalStateException: user locked
+\tat com.example.UserService.load(UserService.java:42)
+\tat com.example.UserController.get(UserController.java:17)
+\t... 48 more
+2024-01-15 10:30:27.000 [scheduler-1] WARN com.example.Job - retrying";
+
+        let scores = score_formats(&sample_lines(content));
+        assert_eq!(scores[0].format, "springboot");
+        // 异常首行不是续行，5条记录行 + 1行异常首行
+        assert!((scores[0].confidence - 5.0 / 6.0).abs() < 1e-6);
+        assert_eq!(best(content).as_deref(), Some("springboot"));
+    }
+
+    #[test]
+    fn test_docker_json_wins_over_inner_format_and_sql_dump_is_mybatis() {
+        let docker = r#"{"log":"2024-01-15 10:30:25.123  INFO 1 --- [main] c.e.App : started\n","stream":"stdout","time":"2024-01-15T10:30:25Z"}
+{"log":"==>  Preparing: SELECT 1\n","stream":"stdout","time":"2024-01-15T10:30:26Z"}
+{"level":"info","msg":"not docker"}"#;
+        let scores = score_formats(&sample_lines(docker));
+        assert_eq!(scores[0].format, "docker_json");
+        assert!((scores[0].confidence - 2.0 / 3.0).abs() < 1e-6);
+
+        let sql = "==>  Preparing: SELECT * FROM orders WHERE id = ?\n==> Parameters: 7(Integer)\n<==      Total: 1\nsome unrelated note";
+        assert_eq!(best(sql).as_deref(), Some("mybatis"));
+    }
+
+    #[test]
+    fn test_keywords_in_free_text_do_not_count() {
+        // 旧的检测只要包含INFO/ERROR就判定为SpringBoot
+        let content = "user reported an ERROR in the INFO panel\nlog: stream stopped\nWARN: disk almost full";
+        assert_eq!(best(content), None);
+        assert_eq!(best(""), None);
+    }
+}
@@ -40,8 +40,10 @@
 /// - 错误容忍：部分解析失败不影响整体处理
 /// - 并发安全：无状态设计支持多线程处理
 
-use crate::plugins::{LogParser, ParseRequest, ParseResult, LogLine};
+use crate::i18n::Message;
+use crate::plugins::{detection, LogParser, ParseRequest, ParseResult, LogLine};
 use crate::plugins::formatter::UnifiedFormatter;
+use crate::plugins::chain::PARSE_ERROR_METADATA_KEY;
 use std::collections::HashMap;
 use serde_json;
 
@@ -112,6 +114,11 @@ impl LogParser for DockerJsonParser {
         })
     }
 
+    /// 按样本行的结构匹配计算置信度（见 `detection::docker_json_confidence`）
+    fn confidence(&self, content: &str, _file_path: Option<&str>) -> f32 {
+        detection::docker_json_confidence(&detection::sample_lines(content))
+    }
+
     /// 执行Docker JSON日志解析
     ///
     /// 这是Docker JSON插件的核心解析功能，将JSON格式的Docker日志转换为结构化的LogLine列表。
@@ -140,7 +147,7 @@ impl LogParser for DockerJsonParser {
     ///
     /// # 参数
     /// - `content`: 要解析的完整JSON日志内容
-    /// - `_request`: 解析请求参数（当前未使用）
+    /// - `request`: 解析请求参数（使用其中的格式化风格）
     ///
     /// # Returns
     /// - `Ok(ParseResult)`: 解析成功的结构化日志结果，包含错误信息
@@ -161,7 +168,7 @@ impl LogParser for DockerJsonParser {
     /// // 元数据输出
     /// metadata = {"stream": "stdout"}
     /// ```
-    fn parse(&self, content: &str, _request: &ParseRequest) -> Result<ParseResult, String> {
+    fn parse(&self, content: &str, request: &ParseRequest) -> Result<ParseResult, String> {
         let mut lines = Vec::new();
         let mut parsing_errors = Vec::new();
 
@@ -196,14 +203,21 @@ impl LogParser for DockerJsonParser {
                         "docker_json"
                     );
 
-                    let formatted_content = UnifiedFormatter::format_display_string(&unified_format);
+                    let formatted_content = UnifiedFormatter::format_display_string(&unified_format, request.formatting.unwrap_or_default());
 
+                    // 严格模式下保留原始JSON行，提取出的log字段写入message
+                    let (raw, message) = if request.is_strict() {
+                        (line.to_string(), Some(log_content))
+                    } else {
+                        (log_content, None)
+                    };
                     lines.push(LogLine {
                         line_number: line_num,
-                        content: log_content,
+                        content: raw,
                         level,
                         timestamp,
                         formatted_content: Some(formatted_content),
+                        message,
                         metadata,
                         processed_by: vec!["docker_json_parser".to_string()],
                     });
@@ -211,7 +225,9 @@ impl LogParser for DockerJsonParser {
                 Err(e) => {
                     parsing_errors.push(format!("Line {}: Failed to parse JSON: {}", line_num, e));
 
-                    let metadata = HashMap::new();
+                    let metadata = HashMap::from([(PARSE_ERROR_METADATA_KEY.to_string(), Message::new("parse.invalid_json")
+                        .with("error", e)
+                        .text())]);
 
                     // 对解析失败的行也使用统一格式化器
                     let unified_format = UnifiedFormatter::format_log_line(
@@ -223,7 +239,7 @@ impl LogParser for DockerJsonParser {
                         "docker_json"
                     );
 
-                    let formatted_content = UnifiedFormatter::format_display_string(&unified_format);
+                    let formatted_content = UnifiedFormatter::format_display_string(&unified_format, request.formatting.unwrap_or_default());
 
                     lines.push(LogLine {
                         line_number: line_num,
@@ -231,6 +247,7 @@ impl LogParser for DockerJsonParser {
                         level: unified_format.level.clone(),
                         timestamp: unified_format.timestamp.clone(),
                         formatted_content: Some(formatted_content),
+                        message: None,
                         metadata,
                         processed_by: vec!["docker_json_parser".to_string()],
                     });
@@ -243,6 +260,8 @@ impl LogParser for DockerJsonParser {
             total_lines: content.lines().count(),
             detected_format: Some("docker_json".to_string()),
             parsing_errors,
+            applied_filters: Vec::new(),
+            capabilities: None,
         })
     }
 }
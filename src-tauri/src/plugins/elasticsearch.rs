@@ -0,0 +1,280 @@
+/// Elasticsearch / OpenSearch 服务端日志解析
+///
+/// 支持两种格式：
+/// - 旧版方括号格式：`[2024-01-15T10:30:25,123][INFO ][o.e.c.m.MetadataCreateIndexService] [node-1] [orders] creating index, ...`
+/// - 新版JSON格式：7.x的 `{"type": "server", "component": ..., "node.name": ...}`
+///   和8.x的ECS格式 `{"@timestamp": ..., "log.logger": ..., "elasticsearch.node.name": ...}`
+///
+/// # 元数据
+/// - `node_name`、`component`（日志器）、`cluster_name`（JSON格式）
+/// - `index` / `shard`: 消息开头引用的索引和分片（`[orders][0]`、`[orders/uuid]`）
+/// - `es_marker`: `gc`（JvmGcMonitorService的GC日志）或 `slowlog`（搜索/索引慢日志）
+/// - `gc_duration_ms`: GC耗时
+/// - 慢日志：`slowlog_type`（如 `search.query`、`indexing.index`），并写入 `duration_ms` 和 `operation`，
+///   慢操作分析（`list_slow_operations`）据此按 `慢日志类型 索引` 分组
+
+use crate::plugins::chain::{PluginChainContext, PluginFilter};
+use crate::plugins::metrics::{duration_to_ms, parse_measurement, DURATION_METADATA_KEY, OPERATION_METADATA_KEY};
+use crate::plugins::{LogLine, ParseRequest};
+use log::info;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::HashMap;
+
+/// 方括号格式：`[时间][级别 ][组件] [节点] 消息`
+static BRACKETED: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\[(\d{4}-\d{2}-\d{2})T(\d{2}:\d{2}:\d{2})[,.](\d{3})\]\[(\w+)\s*\]\[([^\]]+?)\s*\] (?:\[([^\]]+)\] )?(.*)$").unwrap()
+});
+
+/// 消息开头的索引引用：`[orders]`、`[orders][0]`、`[orders/Xy3kq]`
+static INDEX_REFERENCE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\[([a-z0-9._][^\]\[/ ]*)(?:/[\w-]+)?\](?:\[(\d+)\])?").unwrap()
+});
+
+/// 慢日志耗时：`took_millis[1200]`
+static TOOK_MILLIS: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"took_millis\[(\d+)\]").unwrap()
+});
+
+/// GC耗时：`duration [1.2s]`、`spent [300ms]`
+static GC_DURATION: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?:duration|spent) \[(\d+(?:\.\d+)?)(ms|s|m|h)\]").unwrap()
+});
+
+/// Elasticsearch服务端日志过滤器
+pub struct ElasticsearchLogFilter;
+
+impl ElasticsearchLogFilter {
+    /// 行是否为JSON格式的Elasticsearch日志
+    fn is_json_log(line: &str) -> bool {
+        line.trim_start().starts_with('{')
+            && (line.contains("\"node.name\"") || line.contains("\"elasticsearch.node.name\""))
+            && (line.contains("\"component\"") || line.contains("\"log.logger\""))
+    }
+
+    /// 行是否为Elasticsearch服务端日志行（方括号或JSON格式）
+    pub fn matches_line(line: &str) -> bool {
+        BRACKETED.is_match(line) || Self::is_json_log(line)
+    }
+
+    /// 内容是否像Elasticsearch服务端日志
+    pub fn looks_like_elasticsearch_log(content: &str) -> bool {
+        content.lines().take(200).any(Self::matches_line)
+    }
+
+    /// 慢日志类型：由组件名（`index.search.slowlog.query`、`i.s.s.query`）和日志类型（`index_search_slowlog`）确定
+    fn slowlog_type(component: &str, log_type: &str) -> Option<String> {
+        let kind = if component.contains("search.slowlog") || log_type.contains("search_slowlog") {
+            "search"
+        } else if component.contains("indexing.slowlog") || log_type.contains("indexing_slowlog") {
+            "indexing"
+        } else {
+            return None;
+        };
+        let phase = component.rsplit('.').next().unwrap_or(component);
+        Some(format!("{}.{}", kind, phase))
+    }
+
+    /// 解析方括号格式，返回 (慢日志耗时, 日志类型)
+    fn tag_bracketed_line(line: &mut LogLine, strict: bool) -> Option<(Option<String>, String)> {
+        let content = line.message_text().to_string();
+        let caps = BRACKETED.captures(&content)?;
+        line.timestamp = Some(format!("{} {}.{}", &caps[1], &caps[2], &caps[3]));
+        line.level = Some(caps[4].to_uppercase());
+        line.metadata.insert("component".to_string(), caps[5].to_string());
+        if let Some(node) = caps.get(6) {
+            line.metadata.insert("node_name".to_string(), node.as_str().to_string());
+        }
+        line.set_message(caps[7].to_string(), strict);
+        let took = TOOK_MILLIS.captures(line.message_text()).map(|took| took[1].to_string());
+        Some((took, String::new()))
+    }
+
+    /// 解析JSON格式，返回 (慢日志耗时, 日志类型)
+    fn tag_json_line(line: &mut LogLine, strict: bool) -> Option<(Option<String>, String)> {
+        if !Self::is_json_log(line.message_text()) {
+            return None;
+        }
+        let Ok(serde_json::Value::Object(object)) = serde_json::from_str::<serde_json::Value>(line.message_text()) else {
+            return None;
+        };
+        let field = |keys: &[&str]| keys.iter().find_map(|key| match object.get(*key) {
+            Some(serde_json::Value::String(value)) => Some(value.clone()),
+            Some(serde_json::Value::Number(value)) => Some(value.to_string()),
+            _ => None,
+        });
+
+        if let Some(timestamp) = field(&["timestamp", "@timestamp"]) {
+            let timestamp = timestamp.replace(',', ".").replace('T', " ");
+            line.timestamp = Some(timestamp.trim_end_matches('Z').get(..23).unwrap_or(&timestamp).to_string());
+        }
+        line.level = field(&["level", "log.level"]).map(|level| level.trim().to_uppercase());
+        for (key, sources) in [
+            ("component", &["component", "log.logger"][..]),
+            ("node_name", &["node.name", "elasticsearch.node.name"][..]),
+            ("cluster_name", &["cluster.name", "elasticsearch.cluster.name"][..]),
+            ("index", &["elasticsearch.index.name"][..]),
+        ] {
+            if let Some(value) = field(sources) {
+                line.metadata.insert(key.to_string(), value);
+            }
+        }
+        line.set_message(field(&["message"]).unwrap_or_default(), strict);
+        let took = field(&["took_millis", "elasticsearch.slowlog.took_millis"]);
+        Some((took, field(&["type", "event.dataset"]).unwrap_or_default()))
+    }
+
+    /// 处理单行，返回是否识别为Elasticsearch日志行
+    fn tag_line(line: &mut LogLine, strict: bool) -> bool {
+        let Some((took, log_type)) = Self::tag_bracketed_line(line, strict).or_else(|| Self::tag_json_line(line, strict)) else {
+            return false;
+        };
+        let component = line.metadata.get("component").cloned().unwrap_or_default();
+        let message = line.message_text().to_string();
+
+        let is_gc = component.ends_with("JvmGcMonitorService") || message.starts_with("[gc]");
+        if is_gc {
+            line.metadata.insert("es_marker".to_string(), "gc".to_string());
+            if let Some(duration) = GC_DURATION.captures(&message) {
+                if let Some(ms) = parse_measurement(&duration[1]).and_then(|(number, _)| duration_to_ms(number, &duration[2])) {
+                    line.metadata.insert("gc_duration_ms".to_string(), ms.to_string());
+                }
+            }
+        } else if let Some(reference) = INDEX_REFERENCE.captures(&message) {
+            line.metadata.entry("index".to_string()).or_insert_with(|| reference[1].to_string());
+            if let Some(shard) = reference.get(2) {
+                line.metadata.insert("shard".to_string(), shard.as_str().to_string());
+            }
+        }
+
+        if let Some(slowlog) = Self::slowlog_type(&component, &log_type) {
+            line.metadata.insert("es_marker".to_string(), "slowlog".to_string());
+            if let Some(took) = took {
+                line.metadata.insert(DURATION_METADATA_KEY.to_string(), took);
+            }
+            let operation = match line.metadata.get("index") {
+                Some(index) => format!("{} {}", slowlog, index),
+                None => slowlog.clone(),
+            };
+            line.metadata.insert(OPERATION_METADATA_KEY.to_string(), operation);
+            line.metadata.insert("slowlog_type".to_string(), slowlog);
+        }
+        true
+    }
+}
+
+impl PluginFilter for ElasticsearchLogFilter {
+    fn name(&self) -> &str {
+        "elasticsearch"
+    }
+
+    fn description(&self) -> &str {
+        "Elasticsearch/OpenSearch日志过滤器，提取节点、组件和索引，并标记GC与慢日志"
+    }
+
+    fn priority(&self) -> i32 {
+        20 // 格式解析
+    }
+
+    fn should_process(&self, context: &PluginChainContext) -> bool {
+        if context.current_lines.is_empty() {
+            return Self::looks_like_elasticsearch_log(&context.original_content);
+        }
+        context.current_lines.iter().take(200).any(|line| Self::looks_like_elasticsearch_log(line.message_text()))
+    }
+
+    fn process(&self, context: &mut PluginChainContext, request: &ParseRequest) -> Result<(), String> {
+        info!("🔎 Elasticsearch日志过滤器开始处理");
+        let strict = request.is_strict();
+
+        let mut lines = if context.current_lines.is_empty() {
+            // 第一次处理，从原始内容创建行列表，过滤空行
+            context.original_content.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()).map(|(i, line)| {
+                LogLine {
+                    line_number: i + 1,
+                    content: line.to_string(),
+                    level: None,
+                    timestamp: None,
+                    formatted_content: None,
+                    message: None,
+                    metadata: HashMap::new(),
+                    processed_by: vec![],
+                }
+            }).collect()
+        } else {
+            std::mem::take(&mut context.current_lines)
+        };
+
+        let mut tagged = 0;
+        for line in &mut lines {
+            if Self::tag_line(line, strict) {
+                line.processed_by.push("elasticsearch_filter".to_string());
+                line.formatted_content = Some(line.message_text().to_string());
+                tagged += 1;
+            }
+        }
+
+        context.current_lines = lines;
+        info!("🔎 Elasticsearch日志过滤器处理完成，识别了 {} 行", tagged);
+        Ok(())
+    }
+
+    fn can_handle(&self, content: &str, _file_path: Option<&str>) -> bool {
+        Self::looks_like_elasticsearch_log(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::chain::PluginChainManager;
+    use crate::plugins::metrics::find_slow_operations;
+    use crate::plugins::presets::register_preset_chains;
+
+    fn parse(content: &str) -> Vec<LogLine> {
+        let mut manager = PluginChainManager::new();
+        register_preset_chains(&mut manager);
+        let result = manager.process(content, &ParseRequest::default()).unwrap();
+        assert_eq!(result.detected_format.as_deref(), Some("elasticsearch"));
+        result.lines
+    }
+
+    #[test]
+    fn test_bracketed_format_markers_and_slowlog() {
+        let content = "\
+[2024-01-15T10:30:25,123][INFO ][o.e.c.m.MetadataCreateIndexService] [node-1] [orders] creating index, cause [api], templates [], shards [1]/[1]
+[2024-01-15T10:30:26,000][WARN ][o.e.m.j.JvmGcMonitorService] [node-1] [gc][young][12345][678] duration [1.2s], collections [1]/[1.5s], total [1.2s]/[10m]
+[2024-01-15T10:30:27,000][WARN ][index.search.slowlog.query] [node-1] [orders][0] took[1.2s], took_millis[1200], total_hits[10 hits], search_type[QUERY_THEN_FETCH]";
+        let lines = parse(content);
+        let meta = |index: usize, key: &str| lines[index].metadata.get(key).map(String::as_str);
+
+        assert_eq!(lines[0].timestamp.as_deref(), Some("2024-01-15 10:30:25.123"));
+        assert_eq!(lines[0].level.as_deref(), Some("INFO"));
+        assert_eq!((meta(0, "node_name"), meta(0, "index")), (Some("node-1"), Some("orders")));
+        assert_eq!(meta(0, "component"), Some("o.e.c.m.MetadataCreateIndexService"));
+        assert_eq!((meta(1, "es_marker"), meta(1, "gc_duration_ms"), meta(1, "index")), (Some("gc"), Some("1200"), None));
+        assert_eq!((meta(2, "es_marker"), meta(2, "shard")), (Some("slowlog"), Some("0")));
+        assert_eq!(meta(2, "slowlog_type"), Some("search.query"));
+
+        let slow = find_slow_operations(&lines, 1000.0);
+        assert_eq!(slow.len(), 1);
+        assert_eq!(slow[0].name, "search.query orders");
+        assert_eq!(slow[0].max_ms, 1200.0);
+    }
+
+    #[test]
+    fn test_json_formats() {
+        let content = r#"{"type": "server", "timestamp": "2024-01-15T10:30:25,123Z", "level": "INFO", "component": "o.e.n.Node", "cluster.name": "es", "node.name": "node-1", "message": "started"}
+{"@timestamp":"2024-01-15T10:30:26.456Z","log.level":"WARN","message":"[logs-2024][2]","elasticsearch.slowlog.took_millis":"850","event.dataset":"elasticsearch.index_indexing_slowlog","log.logger":"index.indexing.slowlog.index","elasticsearch.node.name":"node-2"}"#;
+        let lines = parse(content);
+        let meta = |index: usize, key: &str| lines[index].metadata.get(key).map(String::as_str);
+
+        assert_eq!(lines[0].content, "started");
+        assert_eq!(lines[0].timestamp.as_deref(), Some("2024-01-15 10:30:25.123"));
+        assert_eq!((meta(0, "node_name"), meta(0, "cluster_name")), (Some("node-1"), Some("es")));
+        assert_eq!(lines[1].level.as_deref(), Some("WARN"));
+        assert_eq!((meta(1, "index"), meta(1, "shard")), (Some("logs-2024"), Some("2")));
+        assert_eq!((meta(1, "slowlog_type"), meta(1, "duration_ms")), (Some("indexing.index"), Some("850")));
+        assert_eq!(meta(1, "operation"), Some("indexing.index logs-2024"));
+    }
+}
@@ -0,0 +1,336 @@
+/// Envoy / Istio 访问日志解析
+///
+/// 支持两种形式：
+/// - 默认文本格式（Istio的默认格式在其基础上追加了响应详情和上游集群等字段）：
+///
+///   ```text
+///   [2024-01-15T10:30:25.123Z] "GET /api/users HTTP/1.1" 503 UF,URX 0 91 30 - "10.0.0.1" "curl/8.0" "5c7a2f0e-..." "users.default" "10.1.2.3:8080" outbound|8080||users.default.svc.cluster.local
+///   ```
+/// - JSON格式（`json_format` 或Istio的 `accessLogEncoding: JSON`），字段名与命令操作符同名（`response_flags`、`upstream_cluster` 等）
+///
+/// # 元数据
+/// - `method`、`path`、`protocol`、`status`、`authority`、`user_agent`、`x_forwarded_for`
+/// - `response_flags`: 响应标志（如 `UF,URX`），`response_flag_explanations`: 各标志的说明
+/// - `upstream_cluster`、`upstream_host`、`upstream_service_time`、`bytes_received`、`bytes_sent`
+/// - `duration_ms`: 请求耗时
+/// - `request_id`: `x-request-id`；JSON日志中带有追踪字段（`trace_id`、`traceparent`、`x_b3_traceid`）时写入 `trace_id`，
+///   与OTLP日志使用相同的键，按追踪ID关联请求
+
+use crate::plugins::chain::{PluginChainContext, PluginFilter};
+use crate::plugins::metrics::DURATION_METADATA_KEY;
+use crate::plugins::{LogLine, ParseRequest};
+use log::info;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::HashMap;
+
+/// 文本格式的行首：`[时间] "方法 路径 协议" 状态码 响应标志 `
+static TEXT_PREFIX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"^\[(\d{4}-\d{2}-\d{2})T(\d{2}:\d{2}:\d{2})(?:\.\d+)?Z\] "(\S+) (\S+) ([^"]+)" (\d{1,3}) (\S+) (.*)$"#).unwrap()
+});
+
+/// 响应标志说明
+const RESPONSE_FLAGS: &[(&str, &str)] = &[
+    ("UH", "上游集群中没有健康的主机"),
+    ("UF", "连接上游失败"),
+    ("UO", "上游溢出（熔断）"),
+    ("NR", "没有匹配的路由"),
+    ("URX", "达到上游重试次数或连接尝试次数上限"),
+    ("NC", "找不到上游集群"),
+    ("DT", "请求或连接超过最大持续时间"),
+    ("DC", "下游连接中断"),
+    ("LH", "本地服务健康检查失败"),
+    ("UT", "上游请求超时"),
+    ("LR", "连接被本地重置"),
+    ("UR", "上游远端重置"),
+    ("UC", "上游连接中断"),
+    ("DI", "故障注入了延迟"),
+    ("FI", "故障注入中止了请求"),
+    ("RL", "被本地限流"),
+    ("UAEX", "被外部授权服务拒绝"),
+    ("RLSE", "限流服务出错，请求被拒绝"),
+    ("IH", "请求头无效"),
+    ("SI", "流空闲超时"),
+    ("DPE", "下游协议错误"),
+    ("UPE", "上游协议错误"),
+    ("UMSDR", "上游流达到最大持续时间"),
+    ("OM", "被过载管理器终止"),
+    ("DF", "DNS解析失败"),
+    ("DO", "过载丢弃"),
+];
+
+/// 表示上游失败的响应标志（对应ERROR级别）
+const UPSTREAM_FAILURE_FLAGS: &[&str] = &["UH", "UF", "UO", "URX", "NC", "UT", "UR", "UC", "UPE", "UMSDR", "DF"];
+
+/// 解释响应标志
+///
+/// # 参数
+/// - `flags`: 逗号分隔的响应标志，`-` 表示没有标志
+///
+/// # Returns
+/// - `Vec<String>`: 各标志的说明（未知标志原样保留）
+pub fn explain_response_flags(flags: &str) -> Vec<String> {
+    flags.split(',')
+        .map(str::trim)
+        .filter(|flag| !flag.is_empty() && *flag != "-")
+        .map(|flag| RESPONSE_FLAGS.iter()
+            .find(|(name, _)| *name == flag)
+            .map_or_else(|| flag.to_string(), |(name, explanation)| format!("{}: {}", name, explanation)))
+        .collect()
+}
+
+/// 把文本按空白拆分为字段，双引号内的空白不拆分（引号本身去掉）
+fn split_fields(text: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in text.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    fields.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        fields.push(current);
+    }
+    fields
+}
+
+/// Envoy访问日志过滤器
+pub struct EnvoyAccessLogFilter;
+
+impl EnvoyAccessLogFilter {
+    /// 行是否为JSON格式的Envoy访问日志
+    fn is_json_access_log(line: &str) -> bool {
+        line.trim_start().starts_with('{') && line.contains("\"response_flags\"")
+            && (line.contains("\"upstream_cluster\"") || line.contains("\"upstream_host\""))
+    }
+
+    /// 行是否为Envoy访问日志（文本或JSON格式）
+    pub fn matches_line(line: &str) -> bool {
+        TEXT_PREFIX.is_match(line) || Self::is_json_access_log(line)
+    }
+
+    /// 内容是否像Envoy访问日志
+    pub fn looks_like_envoy_log(content: &str) -> bool {
+        content.lines().take(200).any(Self::matches_line)
+    }
+
+    /// 写入非空、非 `-` 的字段
+    fn insert(line: &mut LogLine, key: &str, value: &str) {
+        if !value.is_empty() && value != "-" {
+            line.metadata.insert(key.to_string(), value.to_string());
+        }
+    }
+
+    /// 解析文本格式
+    ///
+    /// 响应标志之后的字段数量随Istio版本变化，以连续三个数值字段（接收字节、发送字节、耗时）定位其后的固定字段。
+    fn tag_text_line(line: &mut LogLine) -> bool {
+        let content = line.message_text().to_string();
+        let Some(caps) = TEXT_PREFIX.captures(&content) else {
+            return false;
+        };
+
+        line.timestamp = Some(format!("{} {}", &caps[1], &caps[2]));
+        Self::insert(line, "method", &caps[3]);
+        Self::insert(line, "path", &caps[4]);
+        Self::insert(line, "protocol", &caps[5]);
+        Self::insert(line, "status", &caps[6]);
+        Self::insert(line, "response_flags", &caps[7]);
+
+        let fields = split_fields(&caps[8]);
+        let is_number = |field: &String| field.parse::<u64>().is_ok();
+        if let Some(start) = (0..fields.len().saturating_sub(2)).find(|&i| fields[i..i + 3].iter().all(is_number)) {
+            let names = [
+                "bytes_received", "bytes_sent", DURATION_METADATA_KEY, "upstream_service_time",
+                "x_forwarded_for", "user_agent", "request_id", "authority", "upstream_host", "upstream_cluster",
+            ];
+            for (name, value) in names.iter().zip(&fields[start..]) {
+                Self::insert(line, name, value);
+            }
+        }
+        true
+    }
+
+    /// 解析JSON格式
+    fn tag_json_line(line: &mut LogLine, strict: bool) -> bool {
+        if !Self::is_json_access_log(line.message_text()) {
+            return false;
+        }
+        let Ok(serde_json::Value::Object(object)) = serde_json::from_str::<serde_json::Value>(line.message_text()) else {
+            return false;
+        };
+        let field = |key: &str| match object.get(key) {
+            Some(serde_json::Value::String(value)) => Some(value.clone()),
+            Some(serde_json::Value::Number(value)) => Some(value.to_string()),
+            _ => None,
+        };
+
+        if let Some(start) = field("start_time") {
+            line.timestamp = Some(start.get(..19).unwrap_or(&start).replace('T', " "));
+        }
+        for (key, source) in [
+            ("method", "method"), ("path", "path"), ("protocol", "protocol"), ("status", "response_code"),
+            ("response_flags", "response_flags"), ("bytes_received", "bytes_received"), ("bytes_sent", "bytes_sent"),
+            (DURATION_METADATA_KEY, "duration"), ("upstream_service_time", "upstream_service_time"),
+            ("x_forwarded_for", "x_forwarded_for"), ("user_agent", "user_agent"), ("request_id", "request_id"),
+            ("authority", "authority"), ("upstream_host", "upstream_host"), ("upstream_cluster", "upstream_cluster"),
+        ] {
+            if let Some(value) = field(source) {
+                Self::insert(line, key, &value);
+            }
+        }
+
+        // W3C traceparent：`00-<trace_id>-<span_id>-<flags>`
+        let trace_id = field("trace_id")
+            .or_else(|| field("x_b3_traceid"))
+            .or_else(|| field("traceparent").and_then(|parent| parent.split('-').nth(1).map(str::to_string)));
+        if let Some(trace_id) = trace_id {
+            Self::insert(line, "trace_id", &trace_id);
+        }
+
+        let method = field("method").unwrap_or_default();
+        let path = field("path").unwrap_or_default();
+        let status = field("response_code").unwrap_or_default();
+        let flags = field("response_flags").unwrap_or_default();
+        line.set_message(format!("{} {} {} {}", method, path, status, flags).trim().to_string(), strict);
+        true
+    }
+
+    /// 写入响应标志说明并确定级别
+    fn finish_line(line: &mut LogLine) {
+        let flags = line.metadata.get("response_flags").cloned().unwrap_or_default();
+        let explanations = explain_response_flags(&flags);
+        if !explanations.is_empty() {
+            line.metadata.insert("response_flag_explanations".to_string(), explanations.join("；"));
+        }
+
+        let status = line.metadata.get("status").map(String::as_str).unwrap_or("");
+        let upstream_failure = flags.split(',').any(|flag| UPSTREAM_FAILURE_FLAGS.contains(&flag));
+        let level = if upstream_failure || status.starts_with('5') || status == "0" {
+            "ERROR"
+        } else if !explanations.is_empty() || status.starts_with('4') {
+            "WARN"
+        } else {
+            "INFO"
+        };
+        line.level = Some(level.to_string());
+    }
+}
+
+impl PluginFilter for EnvoyAccessLogFilter {
+    fn name(&self) -> &str {
+        "envoy"
+    }
+
+    fn description(&self) -> &str {
+        "Envoy/Istio访问日志过滤器，提取上游集群、耗时和请求ID，并解释响应标志"
+    }
+
+    fn priority(&self) -> i32 {
+        20 // 格式解析，在ANSI清理之后执行
+    }
+
+    fn should_process(&self, context: &PluginChainContext) -> bool {
+        if context.current_lines.is_empty() {
+            return Self::looks_like_envoy_log(&context.original_content);
+        }
+        context.current_lines.iter().take(200).any(|line| Self::looks_like_envoy_log(line.message_text()))
+    }
+
+    fn process(&self, context: &mut PluginChainContext, request: &ParseRequest) -> Result<(), String> {
+        info!("🛰️ Envoy访问日志过滤器开始处理");
+        let strict = request.is_strict();
+
+        let mut lines = if context.current_lines.is_empty() {
+            // 第一次处理，从原始内容创建行列表，过滤空行
+            context.original_content.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()).map(|(i, line)| {
+                LogLine {
+                    line_number: i + 1,
+                    content: line.to_string(),
+                    level: None,
+                    timestamp: None,
+                    formatted_content: None,
+                    message: None,
+                    metadata: HashMap::new(),
+                    processed_by: vec![],
+                }
+            }).collect()
+        } else {
+            std::mem::take(&mut context.current_lines)
+        };
+
+        let mut tagged = 0;
+        for line in &mut lines {
+            if Self::tag_text_line(line) || Self::tag_json_line(line, strict) {
+                Self::finish_line(line);
+                line.processed_by.push("envoy_filter".to_string());
+                line.formatted_content = Some(line.message_text().to_string());
+                tagged += 1;
+            }
+        }
+
+        context.current_lines = lines;
+        info!("🛰️ Envoy访问日志过滤器处理完成，识别了 {} 行", tagged);
+        Ok(())
+    }
+
+    fn can_handle(&self, content: &str, _file_path: Option<&str>) -> bool {
+        Self::looks_like_envoy_log(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::chain::PluginChainManager;
+    use crate::plugins::presets::register_preset_chains;
+
+    fn parse(content: &str) -> Vec<LogLine> {
+        let mut manager = PluginChainManager::new();
+        register_preset_chains(&mut manager);
+        let result = manager.process(content, &ParseRequest::default()).unwrap();
+        assert_eq!(result.detected_format.as_deref(), Some("envoy"));
+        result.lines
+    }
+
+    #[test]
+    fn test_text_access_log_with_response_flags() {
+        let content = r#"[2024-01-15T10:30:25.123Z] "GET /api/users HTTP/1.1" 200 - 0 1234 12 10 "10.0.0.1" "curl/8.0" "5c7a2f0e-1111-4000-8000-000000000001" "users.default" "10.1.2.3:8080"
+[2024-01-15T10:30:26.000Z] "POST /api/orders HTTP/2" 503 UF,URX via_upstream - "-" 0 91 30 - "10.0.0.2" "okhttp" "5c7a2f0e-1111-4000-8000-000000000002" "orders.default" "10.1.2.4:8080" outbound|8080||orders.default.svc.cluster.local"#;
+        let lines = parse(content);
+        let meta = |index: usize, key: &str| lines[index].metadata.get(key).map(String::as_str);
+
+        assert_eq!(lines[0].timestamp.as_deref(), Some("2024-01-15 10:30:25"));
+        assert_eq!(lines[0].level.as_deref(), Some("INFO"));
+        assert_eq!((meta(0, "duration_ms"), meta(0, "upstream_service_time")), (Some("12"), Some("10")));
+        assert_eq!(meta(0, "request_id"), Some("5c7a2f0e-1111-4000-8000-000000000001"));
+        assert_eq!(meta(0, "response_flags"), None);
+
+        assert_eq!(lines[1].level.as_deref(), Some("ERROR"));
+        assert_eq!(meta(1, "upstream_cluster"), Some("outbound|8080||orders.default.svc.cluster.local"));
+        assert_eq!(meta(1, "upstream_service_time"), None);
+        assert_eq!(meta(1, "response_flag_explanations"), Some("UF: 连接上游失败；URX: 达到上游重试次数或连接尝试次数上限"));
+    }
+
+    #[test]
+    fn test_json_access_log_with_trace_id() {
+        let content = r#"{"start_time":"2024-01-15T10:30:25.123Z","method":"GET","path":"/api/users","protocol":"HTTP/1.1","response_code":429,"response_flags":"RL","duration":3,"request_id":"abc","authority":"users","upstream_host":null,"upstream_cluster":"inbound|8080||","traceparent":"00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"}"#;
+        let lines = parse(content);
+        let meta = |key: &str| lines[0].metadata.get(key).map(String::as_str);
+
+        assert_eq!(lines[0].content, "GET /api/users 429 RL");
+        assert_eq!(lines[0].level.as_deref(), Some("WARN"));
+        assert_eq!(meta("status"), Some("429"));
+        assert_eq!(meta("duration_ms"), Some("3"));
+        assert_eq!(meta("trace_id"), Some("4bf92f3577b34da6a3ce929d0e0e4736"));
+        assert_eq!(meta("upstream_host"), None);
+        assert_eq!(meta("response_flag_explanations"), Some("RL: 被本地限流"));
+    }
+}
@@ -6,6 +6,8 @@
 ///
 /// # 支持的过滤器类型
 /// - **DockerJsonFilter**: 解析Docker JSON格式日志
+/// - **CarriageReturnFilter**: 折叠被回车覆盖的进度条行
+/// - **AnsiFilter**: 移除终端ANSI颜色控制码并记录颜色元数据
 /// - **SpringBootFilter**: 解析SpringBoot应用日志
 /// - **MyBatisFilter**: 识别和格式化MyBatis SQL日志
 /// - **JsonStructureFilter**: 结构化JSON输出格式化
@@ -19,10 +21,15 @@
 /// - **错误恢复**: 提供良好的错误处理机制
 /// - **性能优化**: 避免不必要的处理和内存分配
 
-use crate::plugins::chain::{PluginFilter, PluginChainContext};
+use crate::i18n::Message;
+use crate::plugins::chain::{PluginFilter, PluginChainContext, PARSE_ERROR_METADATA_KEY};
+use crate::plugins::compact::{ThreadCompactor, THREAD_SHORT_METADATA_KEY};
+use crate::plugins::formatter::{canonical_level, FormattingProfile};
+use crate::plugins::settings::PluginOptions;
+use crate::utils::string_utils;
 use crate::plugins::{ParseRequest, LogLine};
 use std::collections::HashMap;
-use serde_json;
+use serde_json::{self, json};
 use log::{debug, info, warn};
 
 /// Docker JSON过滤器
@@ -38,7 +45,41 @@ use log::{debug, info, warn};
 ///
 /// # 链中位置
 /// 通常作为链的第一个过滤器，负责将Docker JSON格式转换为纯文本格式。
-pub struct DockerJsonFilter;
+///
+/// # 配置项
+/// - `gc_prefix_pattern`: Java GC日志前缀正则，第1个捕获组为日志级别
+/// - `strip_gc_prefix`: 是否从格式化内容中去除GC日志前缀
+pub struct DockerJsonFilter {
+    /// Java GC日志前缀正则（如 `[0.000s][warning][gc] `）
+    gc_prefix_pattern: regex::Regex,
+    /// 是否从格式化内容中去除GC日志前缀
+    pub strip_gc_prefix: bool,
+}
+
+impl DockerJsonFilter {
+    /// 默认的GC日志前缀正则
+    const DEFAULT_GC_PREFIX_PATTERN: &'static str = r"^\[[^\]]+\]\[([^\]]+)\]\s*";
+
+    /// 使用插件设置创建过滤器，正则无效时回退到默认值
+    pub fn from_settings(options: PluginOptions) -> Self {
+        let pattern = options.str("gc_prefix_pattern", Self::DEFAULT_GC_PREFIX_PATTERN);
+        let gc_prefix_pattern = regex::Regex::new(pattern).unwrap_or_else(|e| {
+            warn!("⚠️ gc_prefix_pattern 无效，使用默认值: {}", e);
+            regex::Regex::new(Self::DEFAULT_GC_PREFIX_PATTERN).unwrap()
+        });
+
+        Self {
+            gc_prefix_pattern,
+            strip_gc_prefix: options.bool("strip_gc_prefix", true),
+        }
+    }
+}
+
+impl Default for DockerJsonFilter {
+    fn default() -> Self {
+        Self::from_settings(PluginOptions::default())
+    }
+}
 
 impl PluginFilter for DockerJsonFilter {
     fn name(&self) -> &str {
@@ -61,12 +102,12 @@ impl PluginFilter for DockerJsonFilter {
 
         // 检查是否还有JSON格式的行需要处理
         context.current_lines.iter().any(|line| {
-            line.content.trim_start().starts_with('{') &&
-            (line.content.contains("\"log\"") || line.content.contains("\"stream\""))
+            line.message_text().trim_start().starts_with('{') &&
+            (line.message_text().contains("\"log\"") || line.message_text().contains("\"stream\""))
         })
     }
 
-    fn process(&self, context: &mut PluginChainContext, _request: &ParseRequest) -> Result<(), String> {
+    fn process(&self, context: &mut PluginChainContext, request: &ParseRequest) -> Result<(), String> {
         info!("🐳 Docker JSON过滤器开始处理");
 
         let lines_to_process = if context.current_lines.is_empty() {
@@ -78,6 +119,7 @@ impl PluginFilter for DockerJsonFilter {
                     level: None,
                     timestamp: None,
                     formatted_content: None,
+                    message: None,
                     metadata: HashMap::new(),
                     processed_by: vec![],
                 }
@@ -91,11 +133,11 @@ impl PluginFilter for DockerJsonFilter {
         let mut processed_count = 0;
 
         for mut line in lines_to_process {
-            let trimmed = line.content.trim_start();
+            let trimmed = line.message_text().trim_start();
 
             if trimmed.starts_with('{') && (trimmed.contains("\"log\"") || trimmed.contains("\"stream\"")) {
                 // 尝试解析JSON格式
-                match serde_json::from_str::<serde_json::Value>(&line.content) {
+                match serde_json::from_str::<serde_json::Value>(line.message_text()) {
                     Ok(json) => {
                         // 提取stream信息
                         if let Some(stream) = json.get("stream").and_then(|v| v.as_str()) {
@@ -110,29 +152,23 @@ impl PluginFilter for DockerJsonFilter {
                         // 提取log内容作为主要内容，并解析Java GC日志格式
                         if let Some(log_content) = json.get("log").and_then(|v| v.as_str()) {
                             let clean_content = log_content.trim_end_matches('\n');
-                            line.content = clean_content.to_string();
+                            line.set_message(clean_content.to_string(), request.is_strict());
 
                             // 解析Java GC日志格式中的日志级别
                             // 格式: [0.000s][warning][gc] -XX:+PrintGCDetails is deprecated...
                             // 或: [0.002s][info   ][gc,init] CardTable entry size: 512
-                            let gc_log_pattern = regex::Regex::new(r"^\[[^\]]+\]\[([^\]]+)\]").unwrap();
-                            if let Some(caps) = gc_log_pattern.captures(clean_content) {
+                            if let Some(caps) = self.gc_prefix_pattern.captures(clean_content) {
                                 if let Some(level_str) = caps.get(1) {
-                                    let normalized_level = match level_str.as_str().trim() {
-                                        "warning" => "WARN".to_string(),
-                                        "info" => "INFO".to_string(),
-                                        "error" => "ERROR".to_string(),
-                                        "debug" => "DEBUG".to_string(),
-                                        "trace" => "DEBUG".to_string(),
-                                        other => other.to_uppercase(),
-                                    };
-                                    line.level = Some(normalized_level);
+                                    line.level = Some(canonical_level(level_str.as_str().trim()).into_owned());
                                 }
                             }
 
                             // 设置格式化内容为清洁的消息内容（去除GC日志前缀）
-                            let clean_content_pattern = regex::Regex::new(r"^\[[^\]]+\]\[[^\]]+\]\s*").unwrap();
-                            let formatted = clean_content_pattern.replace(clean_content, "").to_string();
+                            let formatted = if self.strip_gc_prefix {
+                                self.gc_prefix_pattern.replace(clean_content, "").to_string()
+                            } else {
+                                clean_content.to_string()
+                            };
                             line.formatted_content = Some(formatted);
                         }
 
@@ -140,12 +176,14 @@ impl PluginFilter for DockerJsonFilter {
                         line.processed_by.push("docker_json_filter".to_string());
                         processed_count += 1;
 
-                        debug!("✅ Docker JSON解析成功: 行{} -> {}", line.line_number, line.content);
+                        debug!("✅ Docker JSON解析成功: 行{} -> {}", line.line_number, line.message_text());
                     }
                     Err(e) => {
                         warn!("⚠️ Docker JSON解析失败: 行{} - {}", line.line_number, e);
                         // 解析失败时保留原始内容，但添加错误信息
-                        line.metadata.insert("parse_error".to_string(), format!("JSON解析失败: {}", e));
+                        line.metadata.insert(PARSE_ERROR_METADATA_KEY.to_string(), Message::new("parse.invalid_json")
+                            .with("error", e)
+                            .text());
                     }
                 }
             }
@@ -168,6 +206,25 @@ impl PluginFilter for DockerJsonFilter {
             trimmed.starts_with('{') && (trimmed.contains("\"log\"") || trimmed.contains("\"stream\""))
         })
     }
+
+    fn settings_schema(&self) -> Option<serde_json::Value> {
+        Some(json!({
+            "type": "object",
+            "properties": {
+                "gc_prefix_pattern": {
+                    "type": "string",
+                    "format": "regex",
+                    "default": Self::DEFAULT_GC_PREFIX_PATTERN,
+                    "description": "Java GC日志前缀正则，第1个捕获组为日志级别"
+                },
+                "strip_gc_prefix": {
+                    "type": "boolean",
+                    "default": true,
+                    "description": "是否从格式化内容中去除GC日志前缀"
+                }
+            }
+        }))
+    }
 }
 
 /// SpringBoot过滤器
@@ -212,16 +269,16 @@ impl PluginFilter for SpringBootFilter {
 
         // 检查是否有SpringBoot格式的日志行
         context.current_lines.iter().any(|line| {
-            let content_lower = line.content.to_lowercase();
+            let content_lower = line.message_text().to_lowercase();
             // 检查新的日志格式特征: 2025-10-15T07:40:55.169Z  INFO 1 --- [thread] Class : Message
-            let has_new_format = line.content.starts_with(|c: char| c.is_ascii_digit()) &&
-                line.content.len() >= 20 &&
-                (line.content.contains("---") && line.content.contains('['));
+            let has_new_format = line.message_text().starts_with(|c: char| c.is_ascii_digit()) &&
+                line.message_text().len() >= 20 &&
+                (line.message_text().contains("---") && line.message_text().contains('['));
 
             // 检查传统格式特征
-            let has_traditional_format = line.content.starts_with(|c: char| c.is_ascii_digit()) &&
-                line.content.len() >= 10 &&
-                (line.content.contains('[') || line.content.contains(" INFO ") || line.content.contains(" ERROR "));
+            let has_traditional_format = line.message_text().starts_with(|c: char| c.is_ascii_digit()) &&
+                line.message_text().len() >= 10 &&
+                (line.message_text().contains('[') || line.message_text().contains(" INFO ") || line.message_text().contains(" ERROR "));
 
             // 检查Spring相关关键字
             let has_spring_keywords = content_lower.contains("spring") ||
@@ -238,11 +295,15 @@ impl PluginFilter for SpringBootFilter {
         })
     }
 
-    fn process(&self, context: &mut PluginChainContext, _request: &ParseRequest) -> Result<(), String> {
+    fn process(&self, context: &mut PluginChainContext, request: &ParseRequest) -> Result<(), String> {
         info!("🌱 SpringBoot过滤器开始处理");
 
+        // 线程名缩略规则与SpringBoot解析器一致，请求未携带设置时使用默认规则
+        let compactor = request.compact.as_ref().map(ThreadCompactor::new);
+        let compactor = compactor.as_ref().unwrap_or_else(|| ThreadCompactor::default_rules());
+
         let regex = regex::Regex::new(Self::LOG_PATTERN)
-            .map_err(|e| format!("SpringBoot正则表达式编译失败: {}", e))?;
+            .map_err(|e| Message::new("springboot.regex_failed").with("error", e).text())?;
 
         info!("🔍 SpringBoot正则表达式: {}", Self::LOG_PATTERN);
 
@@ -255,6 +316,7 @@ impl PluginFilter for SpringBootFilter {
                     level: None,
                     timestamp: None,
                     formatted_content: None,
+                    message: None,
                     metadata: HashMap::new(),
                     processed_by: vec![],
                 }
@@ -268,19 +330,21 @@ impl PluginFilter for SpringBootFilter {
         let mut processed_count = 0;
 
         for mut line in lines_to_process {
-            let trimmed = line.content.trim();
+            let trimmed = line.message_text().trim();
 
             // 跳过空白行 - 完全移除而不是标记为跳过
             if trimmed.is_empty() {
                 continue;
             }
 
-            let content_copy = line.content.clone();
-            info!("🔍 尝试匹配行 {}: '{}'", line.line_number, content_copy);
+            info!("🔍 尝试匹配行 {}: '{}'", line.line_number, line.message_text());
 
             // 异常堆栈跟踪功能已移除 - 所有行都作为普通日志处理
 
-            if let Some(captures) = regex.captures(&content_copy) {
+            // 在消息正文的副本上匹配，匹配期间可以写入条目的其他字段
+            let source = line.message_text().to_string();
+            if let Some(captures) = regex.captures(&source) {
+                let mut message_content = None;
                 info!("✅ 匹配成功! 捕获组数量: {}", captures.len());
                 for (i, cap) in captures.iter().enumerate() {
                     if let Some(group) = cap {
@@ -299,7 +363,7 @@ impl PluginFilter for SpringBootFilter {
                 // 提取并标准化日志级别 (捕获组2)
                 if let Some(level) = captures.get(2) {
                     let normalized_level = self.normalize_level(level.as_str());
-                    line.level = Some(normalized_level.clone());
+                    line.level = Some(normalized_level.to_string());
                     info!("  日志级别: {} -> {}", level.as_str(), normalized_level);
 
                     // 根据级别确定stream类型
@@ -310,6 +374,9 @@ impl PluginFilter for SpringBootFilter {
                 // 提取线程名 (捕获组3)
                 if let Some(thread) = captures.get(3) {
                     line.metadata.insert("thread".to_string(), thread.as_str().to_string());
+                    if compactor.enabled() {
+                        line.metadata.insert(THREAD_SHORT_METADATA_KEY.to_string(), compactor.compact(thread.as_str().trim()));
+                    }
                     info!("  线程名: {}", thread.as_str());
                 }
 
@@ -319,7 +386,7 @@ impl PluginFilter for SpringBootFilter {
                     let logger_str = logger.as_str().trim();
                     if logger_str.contains(' ') {
                         // 如果包含空格，说明这是消息内容而不是类名
-                        line.content = logger_str.to_string();
+                        message_content = Some(logger_str.to_string());
                         info!("  消息: {}", logger_str);
                     } else {
                         // 这是类名
@@ -328,31 +395,34 @@ impl PluginFilter for SpringBootFilter {
 
                         // 消息内容在捕获组5
                         if let Some(message) = captures.get(5) {
-                            line.content = message.as_str().to_string();
+                            message_content = Some(message.as_str().to_string());
                             info!("  消息: {}", message.as_str());
                         }
                     }
                 } else {
                     // 没有类名，消息内容在捕获组5
                     if let Some(message) = captures.get(5) {
-                        line.content = message.as_str().to_string();
+                        message_content = Some(message.as_str().to_string());
                         info!("  消息: {}", message.as_str());
                     }
                 }
+                if let Some(message) = message_content {
+                    line.set_message(message, request.is_strict());
+                }
 
                 line.processed_by.push("springboot_filter".to_string());
                 processed_count += 1;
 
                 // 设置格式化内容为纯净的消息内容，避免重复显示日志级别
-                line.formatted_content = Some(line.content.clone());
+                line.formatted_content = Some(line.message_text().to_string());
 
-                info!("✅ SpringBoot解析成功: 行{} -> {}", line.line_number, line.content);
+                info!("✅ SpringBoot解析成功: 行{} -> {}", line.line_number, line.message_text());
             } else {
                 info!("❌ 匹配失败，检查是否有其他特征...");
 
                 // 不匹配标准格式的行，可能是堆栈跟踪或其他内容
                 // 检查是否包含日志级别关键字，如果不包含，设为DEBUG
-                let content_lower = line.content.to_lowercase();
+                let content_lower = line.message_text().to_lowercase();
                 if content_lower.contains("error") || content_lower.contains("exception") {
                     line.level = Some("ERROR".to_string());
                     line.metadata.insert("stream".to_string(), "stderr".to_string());
@@ -426,15 +496,9 @@ impl SpringBootFilter {
         }
     }
 
-    /// 标准化日志级别
-    fn normalize_level(&self, level: &str) -> String {
-        match level.to_uppercase().as_str() {
-            "ERROR" | "ERR" | "FATAL" | "SEVERE" => "ERROR".to_string(),
-            "WARN" | "WARNING" | "ALERT" => "WARN".to_string(),
-            "INFO" | "INFORMATION" | "NOTE" => "INFO".to_string(),
-            "DEBUG" | "TRACE" | "VERBOSE" => "DEBUG".to_string(),
-            _ => level.to_uppercase(),
-        }
+    /// 标准化日志级别（已知级别不分配内存，见 `canonical_level`）
+    fn normalize_level(&self, level: &str) -> std::borrow::Cow<'static, str> {
+        canonical_level(level)
     }
 
     /// 根据日志级别确定输出流类型
@@ -474,7 +538,7 @@ impl PluginFilter for MyBatisFilter {
     fn should_process(&self, context: &PluginChainContext) -> bool {
         // 检查是否有MyBatis相关的日志内容
         context.current_lines.iter().any(|line| {
-            let content_lower = line.content.to_lowercase();
+            let content_lower = line.message_text().to_lowercase();
             content_lower.contains("preparing:") ||
             content_lower.contains("parameters:") ||
             content_lower.contains("==>")
@@ -489,7 +553,7 @@ impl PluginFilter for MyBatisFilter {
         let mut processed_count = 0;
 
         for line in context.current_lines.drain(..) {
-            let content_lower = line.content.to_lowercase();
+            let content_lower = line.message_text().to_lowercase();
 
             if content_lower.contains("preparing:") ||
                content_lower.contains("parameters:") ||
@@ -536,7 +600,7 @@ impl MyBatisFilter {
         let mut formatted_lines = Vec::with_capacity(sql_lines.len());
 
         for mut line in sql_lines {
-            let content_lower = line.content.to_lowercase();
+            let content_lower = line.message_text().to_lowercase();
 
             if content_lower.contains("preparing:") {
                 // SQL准备语句
@@ -544,8 +608,8 @@ impl MyBatisFilter {
                 line.level = Some("DEBUG".to_string());
 
                 // 提取SQL语句
-                if let Some(sql_start) = line.content.to_lowercase().find("preparing:") {
-                    let sql_statement = line.content[sql_start + 11..].trim();
+                if let Some(sql_start) = line.message_text().to_lowercase().find("preparing:") {
+                    let sql_statement = line.message_text()[sql_start + 11..].trim();
                     line.metadata.insert("sql_statement".to_string(), sql_statement.to_string());
                 }
             } else if content_lower.contains("parameters:") {
@@ -554,8 +618,8 @@ impl MyBatisFilter {
                 line.level = Some("DEBUG".to_string());
 
                 // 提取参数
-                if let Some(param_start) = line.content.to_lowercase().find("parameters:") {
-                    let parameters = line.content[param_start + 12..].trim();
+                if let Some(param_start) = line.message_text().to_lowercase().find("parameters:") {
+                    let parameters = line.message_text()[param_start + 12..].trim();
                     line.metadata.insert("sql_parameters".to_string(), parameters.to_string());
                 }
             } else if content_lower.contains("==>") {
@@ -595,14 +659,15 @@ impl PluginFilter for JsonStructureFilter {
         true // 总是需要结构化输出
     }
 
-    fn process(&self, context: &mut PluginChainContext, _request: &ParseRequest) -> Result<(), String> {
+    fn process(&self, context: &mut PluginChainContext, request: &ParseRequest) -> Result<(), String> {
         info!("📋 JSON结构化过滤器开始处理");
+        let profile = request.formatting.unwrap_or_default();
 
         for line in &mut context.current_lines {
             // 如果已经有formatted_content，不要覆盖（保持SpringBootFilter等过滤器设置的纯净内容）
             if line.formatted_content.is_none() {
                 // 构建格式化内容
-                let formatted = self.build_formatted_content(&line);
+                let formatted = self.build_formatted_content(&line, profile);
                 line.formatted_content = Some(formatted);
             }
 
@@ -624,7 +689,8 @@ impl PluginFilter for JsonStructureFilter {
 impl JsonStructureFilter {
     /// 构建格式化的内容字符串
     /// 格式: 级别 时间 前缀(10字符内，默认收起) 日志正文
-    fn build_formatted_content(&self, line: &LogLine) -> String {
+    /// 纯文本风格下正文标签不带emoji图标
+    fn build_formatted_content(&self, line: &LogLine, profile: FormattingProfile) -> String {
         let mut parts = Vec::new();
 
         // 1. 日志级别 (简化显示，不带括号)
@@ -645,7 +711,7 @@ impl JsonStructureFilter {
         }
 
         // 4. 日志正文 (支持JSON收起和SQL格式化)
-        let formatted_content = self.format_log_content(&line);
+        let formatted_content = self.format_log_content(&line, profile);
         parts.push(formatted_content);
 
         parts.join(" ")
@@ -670,11 +736,7 @@ impl JsonStructureFilter {
         }
 
         // 如果无法解析，返回原时间戳的后8个字符
-        if timestamp.len() > 8 {
-            timestamp[timestamp.len() - 8..].to_string()
-        } else {
-            timestamp.to_string()
-        }
+        string_utils::suffix_chars(timestamp, 8).to_string()
     }
 
     /// 构建前缀信息 (限制10字符，默认收起)
@@ -690,11 +752,7 @@ impl JsonStructureFilter {
 
         // 类名/Logger名 (取前8个字符)
         if let Some(logger) = line.metadata.get("logger") {
-            let short_logger = if logger.len() > 8 {
-                format!("{}...", &logger[..5])
-            } else {
-                logger.clone()
-            };
+            let short_logger = string_utils::ellipsize(logger, 8);
             prefix_parts.push(short_logger);
         }
 
@@ -717,17 +775,12 @@ impl JsonStructureFilter {
         }
 
         // 组合前缀，限制总长度
-        let combined = prefix_parts.join("|");
-        if combined.len() > 10 {
-            format!("{}...", &combined[..7])
-        } else {
-            combined
-        }
+        string_utils::ellipsize(&prefix_parts.join("|"), 10)
     }
 
     /// 格式化日志正文 (支持JSON收起和SQL格式化)
-    fn format_log_content(&self, line: &LogLine) -> String {
-        let content = &line.content;
+    fn format_log_content(&self, line: &LogLine, profile: FormattingProfile) -> String {
+        let content = line.message_text();
 
         // 如果是已格式化的异常，直接返回
         if let Some(log_type) = line.metadata.get("log_type") {
@@ -737,51 +790,51 @@ impl JsonStructureFilter {
                log_type == "exception_business" ||
                log_type == "exception_framework_header" ||
                log_type == "exception_framework" {
-                return content.clone();
+                return content.to_string();
             }
         }
 
         // SQL格式化
         if let Some(sql_type) = line.metadata.get("sql_type") {
-            return self.format_sql_content(content, sql_type);
+            return self.format_sql_content(content, sql_type, profile);
         }
 
         // JSON内容收起
         if self.is_json_content(content) {
-            return self.format_json_content(content);
+            return self.format_json_content(content, profile);
         }
 
         // 普通内容直接返回
-        content.clone()
+        content.to_string()
     }
 
     /// 格式化SQL内容
-    fn format_sql_content(&self, content: &str, sql_type: &str) -> String {
+    fn format_sql_content(&self, content: &str, sql_type: &str, profile: FormattingProfile) -> String {
         let content = content.trim();
 
         match sql_type {
             "preparing" => {
                 if let Some(start) = content.to_lowercase().find("preparing:") {
                     let sql = content[start + 11..].trim();
-                    format!("📝 SQL: {}", self.format_sql_statement(sql))
+                    profile.labeled("📝", "SQL", &self.format_sql_statement(sql))
                 } else {
-                    format!("📝 SQL: {}", content)
+                    profile.labeled("📝", "SQL", content)
                 }
             }
             "parameters" => {
                 if let Some(start) = content.to_lowercase().find("parameters:") {
                     let params = content[start + 12..].trim();
-                    format!("🔧 PARAMS: {}", self.format_sql_parameters(params))
+                    profile.labeled("🔧", "PARAMS", &self.format_sql_parameters(params))
                 } else {
-                    format!("🔧 PARAMS: {}", content)
+                    profile.labeled("🔧", "PARAMS", content)
                 }
             }
             "result" => {
                 if let Some(start) = content.to_lowercase().find("==>") {
                     let result = content[start + 3..].trim();
-                    format!("✅ RESULT: {}", result)
+                    profile.labeled("✅", "RESULT", result)
                 } else {
-                    format!("✅ RESULT: {}", content)
+                    profile.labeled("✅", "RESULT", content)
                 }
             }
             _ => content.to_string()
@@ -807,21 +860,13 @@ impl JsonStructureFilter {
             .replace("group by", "GROUP BY");
 
         // 如果SQL太长，进行收起处理
-        if formatted.len() > 100 {
-            format!("{}...", &formatted[..97])
-        } else {
-            formatted
-        }
+        string_utils::ellipsize(&formatted, 100)
     }
 
     /// 格式化SQL参数
     fn format_sql_parameters(&self, params: &str) -> String {
         // 简化参数显示
-        if params.len() > 50 {
-            format!("{}...", &params[..47])
-        } else {
-            params.to_string()
-        }
+        string_utils::ellipsize(params, 50)
     }
 
     /// 检查是否为JSON内容
@@ -832,28 +877,24 @@ impl JsonStructureFilter {
     }
 
     /// 格式化JSON内容
-    fn format_json_content(&self, content: &str) -> String {
+    fn format_json_content(&self, content: &str, profile: FormattingProfile) -> String {
         let trimmed = content.trim();
 
-        if trimmed.len() > 80 {
-            // JSON内容太长，进行收起
-            format!("📄 JSON: {}...", &trimmed[..77])
-        } else {
-            format!("📄 JSON: {}", trimmed)
-        }
+        // JSON内容太长时进行收起
+        profile.labeled("📄", "JSON", &string_utils::ellipsize(trimmed, 80))
     }
 
     /// 格式化异常内容
     #[allow(dead_code)]
-    fn format_exception_content(&self, content: &str) -> String {
+    fn format_exception_content(&self, content: &str, profile: FormattingProfile) -> String {
         let lines: Vec<&str> = content.lines().collect();
 
         if lines.len() > 3 {
             // 异常内容太长，只显示前3行
             let preview: Vec<String> = lines.iter().take(3).map(|&s| s.to_string()).collect();
-            format!("💥 EXCEPTION: {}... (+{} lines)", preview.join(" "), lines.len() - 3)
+            profile.labeled("💥", "EXCEPTION", &format!("{}... (+{} lines)", preview.join(" "), lines.len() - 3))
         } else {
-            format!("💥 EXCEPTION: {}", content)
+            profile.labeled("💥", "EXCEPTION", content)
         }
     }
 }
@@ -886,25 +927,25 @@ impl PluginFilter for JavaLogFilter {
     fn should_process(&self, context: &PluginChainContext) -> bool {
         // 检查是否有Java相关的日志内容
         context.current_lines.iter().any(|line| {
-            let content_lower = line.content.to_lowercase();
+            let content_lower = line.message_text().to_lowercase();
             content_lower.contains("[warning][gc]") ||
             content_lower.contains("[info][gc]") ||
             content_lower.contains("[debug][gc]") ||
             content_lower.contains("gc,") ||
             content_lower.contains("heap") ||
             content_lower.contains("g1") ||
-            (line.content.starts_with('[') && line.content.contains("]"))
+            (line.message_text().starts_with('[') && line.message_text().contains("]"))
         })
     }
 
-    fn process(&self, context: &mut PluginChainContext, _request: &ParseRequest) -> Result<(), String> {
+    fn process(&self, context: &mut PluginChainContext, request: &ParseRequest) -> Result<(), String> {
         info!("☕ Java日志过滤器开始处理");
 
         let mut processed_lines = Vec::with_capacity(context.current_lines.len());
         let mut processed_count = 0;
 
         for mut line in context.current_lines.drain(..) {
-            let content_lower = line.content.to_lowercase();
+            let content_lower = line.message_text().to_lowercase();
 
             // 识别Java日志格式，如: [0.000s][warning][gc] -XX:+PrintGCDetails is deprecated
             if content_lower.contains("[warning][gc]") {
@@ -922,12 +963,13 @@ impl PluginFilter for JavaLogFilter {
                 line.metadata.insert("log_type".to_string(), "gc_debug".to_string());
                 line.metadata.insert("stream".to_string(), "stdout".to_string());
                 processed_count += 1;
-            } else if line.content.starts_with('[') && line.content.contains("][") {
+            } else if line.message_text().starts_with('[') && line.message_text().contains("][") {
                 // 通用Java日志格式
-                if let Some(start_bracket) = line.content.find('[') {
-                    if let Some(end_bracket) = line.content[start_bracket + 1..].find(']') {
-                        let time_part = &line.content[start_bracket..end_bracket + 1];
-                        let remaining = &line.content[end_bracket + 1..];
+                let source = line.message_text().to_string();
+                if let Some(start_bracket) = source.find('[') {
+                    if let Some(end_bracket) = source[start_bracket + 1..].find(']') {
+                        let time_part = &source[start_bracket..end_bracket + 1];
+                        let remaining = &source[end_bracket + 1..];
 
                         if let Some(level_start) = remaining.find('[') {
                             if let Some(level_end) = remaining[level_start + 1..].find(']') {
@@ -942,21 +984,21 @@ impl PluginFilter for JavaLogFilter {
                                 // 提取并标准化级别
                                 let level_upper = level_part.to_uppercase();
                                 let normalized_level = if level_upper.contains("WARNING") {
-                                    "WARN".to_string()
+                                    "WARN"
                                 } else if level_upper.contains("ERROR") {
-                                    "ERROR".to_string()
+                                    "ERROR"
                                 } else if level_upper.contains("INFO") {
-                                    "INFO".to_string()
+                                    "INFO"
                                 } else if level_upper.contains("DEBUG") {
-                                    "DEBUG".to_string()
+                                    "DEBUG"
                                 } else if level_upper.contains("TRACE") {
-                                    "TRACE".to_string()
+                                    "TRACE"
                                 } else {
-                                    "INFO".to_string() // 默认级别
+                                    "INFO" // 默认级别
                                 };
 
-                                line.level = Some(normalized_level.clone());
-                                line.content = message.to_string();
+                                line.level = Some(normalized_level.to_string());
+                                line.set_message(message.to_string(), request.is_strict());
 
                                 // 根据级别确定stream类型
                                 let stream_type = if normalized_level == "ERROR" { "stderr" } else { "stdout" };
@@ -997,7 +1039,48 @@ impl PluginFilter for JavaLogFilter {
 /// 内容增强过滤器
 ///
 /// 对日志内容进行额外的增强处理，如高亮、链接识别、数据提取等。
-pub struct ContentEnhancerFilter;
+///
+/// # 配置项
+/// - `detect_urls`: 是否标记包含URL的行（`has_url`）
+/// - `detect_emails`: 是否标记包含邮箱的行（`has_email`）
+/// - `mark_errors`: 是否标记ERROR级别的行（`is_error`）
+pub struct ContentEnhancerFilter {
+    /// 是否标记包含URL的行
+    pub detect_urls: bool,
+    /// 是否标记包含邮箱的行
+    pub detect_emails: bool,
+    /// 是否标记ERROR级别的行
+    pub mark_errors: bool,
+}
+
+impl ContentEnhancerFilter {
+    /// 使用插件设置创建过滤器
+    pub fn from_settings(options: PluginOptions) -> Self {
+        Self {
+            detect_urls: options.bool("detect_urls", true),
+            detect_emails: options.bool("detect_emails", true),
+            mark_errors: options.bool("mark_errors", true),
+        }
+    }
+
+    fn has_url(&self, line: &LogLine) -> bool {
+        self.detect_urls && (line.message_text().contains("http://") || line.message_text().contains("https://"))
+    }
+
+    fn has_email(&self, line: &LogLine) -> bool {
+        self.detect_emails && line.message_text().contains("@")
+    }
+
+    fn is_error(&self, line: &LogLine) -> bool {
+        self.mark_errors && line.level.as_ref().map_or(false, |l| l == "ERROR")
+    }
+}
+
+impl Default for ContentEnhancerFilter {
+    fn default() -> Self {
+        Self::from_settings(PluginOptions::default())
+    }
+}
 
 impl PluginFilter for ContentEnhancerFilter {
     fn name(&self) -> &str {
@@ -1015,10 +1098,7 @@ impl PluginFilter for ContentEnhancerFilter {
     fn should_process(&self, context: &PluginChainContext) -> bool {
         // 检查是否有需要增强的内容
         context.current_lines.iter().any(|line| {
-            line.content.contains("http://") ||
-            line.content.contains("https://") ||
-            line.content.contains("@") ||
-            line.level.as_ref().map_or(false, |l| l == "ERROR")
+            self.has_url(line) || self.has_email(line) || self.is_error(line)
         })
     }
 
@@ -1031,19 +1111,19 @@ impl PluginFilter for ContentEnhancerFilter {
             let mut enhanced = false;
 
             // 检测URL
-            if line.content.contains("http://") || line.content.contains("https://") {
+            if self.has_url(line) {
                 line.metadata.insert("has_url".to_string(), "true".to_string());
                 enhanced = true;
             }
 
             // 检测邮箱地址
-            if line.content.contains("@") {
+            if self.has_email(line) {
                 line.metadata.insert("has_email".to_string(), "true".to_string());
                 enhanced = true;
             }
 
             // 检测错误级别，添加特殊标记
-            if line.level.as_ref().map_or(false, |l| l == "ERROR") {
+            if self.is_error(line) {
                 line.metadata.insert("is_error".to_string(), "true".to_string());
                 enhanced = true;
             }
@@ -1063,6 +1143,354 @@ impl PluginFilter for ContentEnhancerFilter {
     fn can_handle(&self, _content: &str, _file_path: Option<&str>) -> bool {
         true // 可以处理任何内容，但会选择性增强
     }
+
+    fn settings_schema(&self) -> Option<serde_json::Value> {
+        Some(json!({
+            "type": "object",
+            "properties": {
+                "detect_urls": { "type": "boolean", "default": true, "description": "标记包含URL的行" },
+                "detect_emails": { "type": "boolean", "default": true, "description": "标记包含邮箱地址的行" },
+                "mark_errors": { "type": "boolean", "default": true, "description": "标记ERROR级别的行" }
+            }
+        }))
+    }
+}
+
+/// 回车覆盖行折叠过滤器
+///
+/// pip、maven、docker pull 等工具会用 `\r` 反复覆盖同一行来绘制进度条，
+/// 写入文件后一行中可能包含成千上万次覆盖。此过滤器按终端的覆盖语义
+/// 将这些片段折叠为最终显示的那一行，并在元数据 `cr_collapsed` 中记录折叠次数。
+///
+/// # 链中位置
+/// 优先级12：在Docker JSON解包之后、ANSI清理和格式解析之前执行。
+pub struct CarriageReturnFilter;
+
+impl CarriageReturnFilter {
+    /// 按终端覆盖语义渲染包含 `\r` 的文本
+    ///
+    /// 每个 `\r` 将光标移回行首，后续字符逐个覆盖已有字符；
+    /// 覆盖文本较短时，前一次输出的剩余部分仍然可见。
+    ///
+    /// # Returns
+    /// - `(String, usize)`: 最终显示的文本，以及被覆盖的片段数量
+    pub fn collapse(text: &str) -> (String, usize) {
+        if !text.contains('\r') {
+            return (text.to_string(), 0);
+        }
+
+        let mut screen: Vec<char> = Vec::new();
+        let mut overwrites = 0;
+
+        for (i, segment) in text.split('\r').enumerate() {
+            if segment.is_empty() {
+                continue;
+            }
+            if i > 0 && !screen.is_empty() {
+                overwrites += 1;
+            }
+            for (column, c) in segment.chars().enumerate() {
+                if column < screen.len() {
+                    screen[column] = c;
+                } else {
+                    screen.push(c);
+                }
+            }
+        }
+
+        (screen.into_iter().collect(), overwrites)
+    }
+}
+
+impl PluginFilter for CarriageReturnFilter {
+    fn name(&self) -> &str {
+        "carriage_return"
+    }
+
+    fn description(&self) -> &str {
+        "回车覆盖行折叠过滤器，将进度条等被\\r覆盖的片段折叠为最终显示内容"
+    }
+
+    fn priority(&self) -> i32 {
+        12 // 在Docker JSON解包之后、ANSI清理之前执行
+    }
+
+    fn should_process(&self, context: &PluginChainContext) -> bool {
+        if context.current_lines.is_empty() {
+            return context.original_content.contains('\r');
+        }
+
+        context.current_lines.iter().any(|line| line.message_text().contains('\r'))
+    }
+
+    fn process(&self, context: &mut PluginChainContext, request: &ParseRequest) -> Result<(), String> {
+        info!("↩️ 回车覆盖折叠过滤器开始处理");
+
+        let lines_to_process = if context.current_lines.is_empty() {
+            // 第一次处理，从原始内容创建行列表，过滤空行
+            context.original_content.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()).map(|(i, line)| {
+                LogLine {
+                    line_number: i + 1,
+                    content: line.to_string(),
+                    level: None,
+                    timestamp: None,
+                    formatted_content: None,
+                    message: None,
+                    metadata: HashMap::new(),
+                    processed_by: vec![],
+                }
+            }).collect()
+        } else {
+            // 后续处理，使用现有的行列表
+            std::mem::take(&mut context.current_lines)
+        };
+
+        let mut processed_lines = Vec::with_capacity(lines_to_process.len());
+        let mut total_collapsed = 0;
+
+        for mut line in lines_to_process {
+            if line.message_text().contains('\r') {
+                let (rendered, collapsed) = Self::collapse(line.message_text());
+                line.set_message(rendered, request.is_strict());
+
+                if let Some(formatted) = &line.formatted_content {
+                    line.formatted_content = Some(Self::collapse(formatted).0);
+                }
+
+                if collapsed > 0 {
+                    line.metadata.insert("cr_collapsed".to_string(), collapsed.to_string());
+                    line.processed_by.push("carriage_return_filter".to_string());
+                    total_collapsed += collapsed;
+                }
+            }
+
+            if !line.message_text().trim().is_empty() {
+                processed_lines.push(line);
+            }
+        }
+
+        context.current_lines = processed_lines;
+        context.set_chain_metadata("cr_collapsed".to_string(), total_collapsed.to_string());
+
+        info!("↩️ 回车覆盖折叠完成，共折叠 {} 个片段", total_collapsed);
+        Ok(())
+    }
+
+    fn can_handle(&self, content: &str, _file_path: Option<&str>) -> bool {
+        content.contains('\r')
+    }
+}
+
+/// ANSI转义序列过滤器
+///
+/// 终端捕获的日志中常带有 `\x1b[31m` 之类的颜色控制码，会污染内容并干扰级别识别。
+/// 此过滤器在其他解析过滤器之前运行，移除所有ANSI转义序列，
+/// 并可选地将SGR前景色记录到元数据（如 `ansi_color=red`）。
+///
+/// # 链中位置
+/// 优先级15：位于Docker JSON解包（10）之后、SpringBoot等格式解析（20+）之前，
+/// 这样容器日志中 `log` 字段内的颜色码同样会被清理。
+pub struct AnsiFilter {
+    /// 是否将SGR颜色转换为元数据
+    pub capture_colors: bool,
+}
+
+impl AnsiFilter {
+    /// 创建默认的ANSI过滤器（记录颜色元数据）
+    pub fn new() -> Self {
+        Self { capture_colors: true }
+    }
+
+    /// 使用插件设置创建过滤器
+    pub fn from_settings(options: PluginOptions) -> Self {
+        Self { capture_colors: options.bool("capture_colors", true) }
+    }
+
+    /// 移除文本中的ANSI转义序列
+    ///
+    /// 支持CSI序列（`ESC [ ... 终止字节`）、OSC序列（`ESC ] ... BEL/ESC \`）
+    /// 以及其他双字节ESC序列。
+    ///
+    /// # Returns
+    /// - `(String, Option<String>)`: 清理后的文本，以及第一个SGR前景色（如有）
+    pub fn strip_ansi(text: &str) -> (String, Option<String>) {
+        if !text.contains('\x1b') {
+            return (text.to_string(), None);
+        }
+
+        let mut output = String::with_capacity(text.len());
+        let mut color = None;
+        let mut chars = text.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            if c != '\x1b' {
+                output.push(c);
+                continue;
+            }
+
+            match chars.peek() {
+                Some('[') => {
+                    chars.next();
+                    let mut params = String::new();
+                    while let Some(&next) = chars.peek() {
+                        chars.next();
+                        if ('\x40'..='\x7e').contains(&next) {
+                            if next == 'm' && color.is_none() {
+                                color = Self::sgr_foreground_color(&params);
+                            }
+                            break;
+                        }
+                        params.push(next);
+                    }
+                }
+                Some(']') => {
+                    chars.next();
+                    while let Some(next) = chars.next() {
+                        if next == '\x07' {
+                            break;
+                        }
+                        if next == '\x1b' && chars.peek() == Some(&'\\') {
+                            chars.next();
+                            break;
+                        }
+                    }
+                }
+                Some(_) => {
+                    chars.next();
+                }
+                None => {}
+            }
+        }
+
+        (output, color)
+    }
+
+    /// 从SGR参数中解析前景色名称
+    ///
+    /// 支持标准色（30-37）、高亮色（90-97）、256色（`38;5;n`）和真彩色（`38;2;r;g;b`）。
+    fn sgr_foreground_color(params: &str) -> Option<String> {
+        const NAMES: [&str; 8] = ["black", "red", "green", "yellow", "blue", "magenta", "cyan", "white"];
+
+        let codes: Vec<u32> = params.split(';').filter_map(|p| p.parse().ok()).collect();
+        let mut i = 0;
+        while i < codes.len() {
+            match codes[i] {
+                code @ 30..=37 => return Some(NAMES[(code - 30) as usize].to_string()),
+                code @ 90..=97 => return Some(format!("bright_{}", NAMES[(code - 90) as usize])),
+                38 if codes.get(i + 1) == Some(&5) => {
+                    return codes.get(i + 2).map(|n| format!("color256:{}", n));
+                }
+                38 if codes.get(i + 1) == Some(&2) && codes.len() >= i + 5 => {
+                    return Some(format!("#{:02x}{:02x}{:02x}", codes[i + 2], codes[i + 3], codes[i + 4]));
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+        None
+    }
+}
+
+impl Default for AnsiFilter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PluginFilter for AnsiFilter {
+    fn name(&self) -> &str {
+        "ansi"
+    }
+
+    fn description(&self) -> &str {
+        "ANSI转义序列过滤器，移除终端颜色控制码并可将颜色记录到元数据"
+    }
+
+    fn priority(&self) -> i32 {
+        15 // 在Docker JSON解包之后、其他格式解析之前执行
+    }
+
+    fn should_process(&self, context: &PluginChainContext) -> bool {
+        if context.current_lines.is_empty() {
+            return context.original_content.contains('\x1b');
+        }
+
+        context.current_lines.iter().any(|line| line.message_text().contains('\x1b'))
+    }
+
+    fn process(&self, context: &mut PluginChainContext, request: &ParseRequest) -> Result<(), String> {
+        info!("🎨 ANSI过滤器开始处理");
+
+        let lines_to_process = if context.current_lines.is_empty() {
+            // 第一次处理，从原始内容创建行列表，过滤空行
+            context.original_content.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()).map(|(i, line)| {
+                LogLine {
+                    line_number: i + 1,
+                    content: line.to_string(),
+                    level: None,
+                    timestamp: None,
+                    formatted_content: None,
+                    message: None,
+                    metadata: HashMap::new(),
+                    processed_by: vec![],
+                }
+            }).collect()
+        } else {
+            // 后续处理，使用现有的行列表
+            std::mem::take(&mut context.current_lines)
+        };
+
+        let mut processed_lines = Vec::with_capacity(lines_to_process.len());
+        let mut stripped_count = 0;
+
+        for mut line in lines_to_process {
+            if line.message_text().contains('\x1b') {
+                let (clean, color) = Self::strip_ansi(line.message_text());
+                line.set_message(clean, request.is_strict());
+
+                if let Some(formatted) = &line.formatted_content {
+                    line.formatted_content = Some(Self::strip_ansi(formatted).0);
+                }
+
+                if self.capture_colors {
+                    if let Some(color) = color {
+                        line.metadata.insert("ansi_color".to_string(), color);
+                    }
+                }
+
+                line.processed_by.push("ansi_filter".to_string());
+                stripped_count += 1;
+            }
+
+            // 只剩控制码的行在清理后为空，直接丢弃
+            if !line.message_text().trim().is_empty() {
+                processed_lines.push(line);
+            }
+        }
+
+        context.current_lines = processed_lines;
+        context.set_chain_metadata("ansi_stripped".to_string(), stripped_count.to_string());
+
+        info!("🎨 ANSI过滤器处理完成，清理了 {} 行", stripped_count);
+        Ok(())
+    }
+
+    fn can_handle(&self, content: &str, _file_path: Option<&str>) -> bool {
+        content.contains('\x1b')
+    }
+
+    fn settings_schema(&self) -> Option<serde_json::Value> {
+        Some(json!({
+            "type": "object",
+            "properties": {
+                "capture_colors": {
+                    "type": "boolean",
+                    "default": true,
+                    "description": "将SGR前景色记录到元数据 ansi_color"
+                }
+            }
+        }))
+    }
 }
 
 #[cfg(test)]
@@ -1100,4 +1528,111 @@ mod springboot_tests {
         }
     }
 
-  }
\ No newline at end of file
+  }
+
+#[cfg(test)]
+mod ansi_tests {
+    use super::AnsiFilter;
+    use crate::plugins::chain::{PluginChain, PluginFilter, PluginChainContext};
+    use crate::plugins::filters::SpringBootFilter;
+    use crate::plugins::ParseRequest;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_strip_ansi_removes_sgr_and_reports_color() {
+        let (clean, color) = AnsiFilter::strip_ansi("\x1b[1;31mERROR\x1b[0m something failed");
+        assert_eq!(clean, "ERROR something failed");
+        assert_eq!(color.as_deref(), Some("red"));
+
+        let (clean, color) = AnsiFilter::strip_ansi("\x1b[38;5;208mwarn\x1b[0m");
+        assert_eq!(clean, "warn");
+        assert_eq!(color.as_deref(), Some("color256:208"));
+
+        let (clean, color) = AnsiFilter::strip_ansi("\x1b]0;title\x07\x1b[2Kplain");
+        assert_eq!(clean, "plain");
+        assert_eq!(color, None);
+    }
+
+    #[test]
+    fn test_filter_cleans_lines_before_level_detection() {
+        let content = "2024-01-15 14:30:25.123 \x1b[31mERROR\x1b[0m [main] com.example.App - boom";
+        let mut context = PluginChainContext::new(content.to_string());
+        let filter = AnsiFilter::new();
+
+        assert!(filter.should_process(&context));
+        filter.process(&mut context, &ParseRequest::default()).unwrap();
+
+        let line = &context.current_lines[0];
+        assert!(!line.content.contains('\x1b'));
+        assert_eq!(line.metadata.get("ansi_color").map(String::as_str), Some("red"));
+
+        let mut chain = PluginChain::new("test".to_string(), "test".to_string());
+        chain.add_filter(Arc::new(SpringBootFilter));
+        chain.add_filter(Arc::new(AnsiFilter::new()));
+        let result = chain.process(content, &ParseRequest::default()).unwrap();
+        assert_eq!(result.lines[0].level.as_deref(), Some("ERROR"));
+    }
+
+    #[test]
+    fn test_strict_mode_keeps_raw_content() {
+        let content = "2024-01-15 14:30:25.123 \x1b[31mERROR\x1b[0m [main] com.example.App - boom";
+        let mut chain = PluginChain::new("test".to_string(), "test".to_string());
+        chain.add_filter(Arc::new(SpringBootFilter));
+        chain.add_filter(Arc::new(AnsiFilter::new()));
+
+        // 默认模式下提取出的消息同时改写原始内容
+        let rewritten = chain.process(content, &ParseRequest::default()).unwrap().lines.remove(0);
+        assert!(rewritten.content.ends_with("com.example.App - boom"));
+        assert_eq!(rewritten.message_text(), rewritten.content);
+
+        let request = ParseRequest { strict_content: Some(true), ..Default::default() };
+        let line = &chain.process(content, &request).unwrap().lines[0];
+        assert_eq!(line.content, content);
+        assert_eq!(line.message, Some(rewritten.content));
+        assert_eq!(line.level.as_deref(), Some("ERROR"));
+    }
+
+    #[test]
+    fn test_color_capture_can_be_disabled() {
+        let mut context = PluginChainContext::new("\x1b[32mok\x1b[0m".to_string());
+        AnsiFilter { capture_colors: false }.process(&mut context, &ParseRequest::default()).unwrap();
+
+        assert_eq!(context.current_lines[0].content, "ok");
+        assert!(context.current_lines[0].metadata.get("ansi_color").is_none());
+    }
+}
+
+#[cfg(test)]
+mod carriage_return_tests {
+    use super::CarriageReturnFilter;
+    use crate::plugins::chain::{PluginFilter, PluginChainContext};
+    use crate::plugins::ParseRequest;
+
+    #[test]
+    fn test_collapse_uses_terminal_overwrite_semantics() {
+        assert_eq!(CarriageReturnFilter::collapse("plain"), ("plain".to_string(), 0));
+        assert_eq!(
+            CarriageReturnFilter::collapse(" 10% [#   ]\r 50% [##  ]\r100% [####]"),
+            ("100% [####]".to_string(), 2)
+        );
+        // 较短的覆盖文本保留前一次输出的尾部
+        assert_eq!(CarriageReturnFilter::collapse("Downloading...\rDone"), ("Doneloading...".to_string(), 1));
+    }
+
+    #[test]
+    fn test_filter_records_collapsed_count() {
+        let content = "start\nProgress 1/3\rProgress 2/3\rProgress 3/3\nend";
+        let mut context = PluginChainContext::new(content.to_string());
+        let filter = CarriageReturnFilter;
+
+        assert!(filter.should_process(&context));
+        filter.process(&mut context, &ParseRequest::default()).unwrap();
+
+        assert_eq!(context.current_lines.len(), 3);
+        let progress = &context.current_lines[1];
+        assert_eq!(progress.content, "Progress 3/3");
+        assert_eq!(progress.line_number, 2);
+        assert_eq!(progress.metadata.get("cr_collapsed").map(String::as_str), Some("2"));
+        assert!(context.current_lines[0].metadata.get("cr_collapsed").is_none());
+    }
+}
@@ -1,5 +1,9 @@
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::sync::Arc;
 use serde::{Deserialize, Serialize};
+use crate::i18n::Message;
+use crate::plugins::LogLine;
 
 /// 统一的日志输出格式标准
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,6 +24,64 @@ pub struct UnifiedLogFormat {
     pub raw_content: String,
 }
 
+/// 格式化风格
+///
+/// 装饰风格在格式化内容中加入emoji图标和类型标签（如 `📝 SQL:`、`[SQL]`），
+/// 纯文本风格只输出文字，便于复制到工单和导出。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FormattingProfile {
+    /// 纯文本：不输出emoji图标和类型标签
+    Plain,
+    /// 装饰：带emoji图标和类型标签
+    #[default]
+    Decorated,
+}
+
+/// 格式化内容中使用的装饰图标
+const DECORATION_ICONS: [&str; 5] = ["📝", "🔧", "✅", "📄", "💥"];
+
+impl FormattingProfile {
+    /// 是否为纯文本风格
+    pub fn is_plain(self) -> bool {
+        self == FormattingProfile::Plain
+    }
+
+    /// 生成带标签的内容：装饰风格为 `📝 SQL: ...`，纯文本风格为 `SQL: ...`
+    pub fn labeled(self, icon: &str, label: &str, body: &str) -> String {
+        match self {
+            FormattingProfile::Plain => format!("{}: {}", label, body),
+            FormattingProfile::Decorated => format!("{} {}: {}", icon, label, body),
+        }
+    }
+
+    /// 去掉文本中的装饰图标，用于按纯文本风格导出已按装饰风格解析的结果
+    pub fn strip_decorations(text: &str) -> String {
+        DECORATION_ICONS.iter().fold(text.to_string(), |text, icon| {
+            text.replace(&format!("{} ", icon), "").replace(icon, "")
+        })
+    }
+}
+
+/// 标准日志级别及其别名（不区分大小写）
+const LEVEL_ALIASES: &[(&str, &[&str])] = &[
+    ("ERROR", &["ERROR", "ERR", "FATAL", "SEVERE"]),
+    ("WARN", &["WARN", "WARNING", "ALERT"]),
+    ("INFO", &["INFO", "INFORMATION", "NOTE"]),
+    ("DEBUG", &["DEBUG", "TRACE", "VERBOSE", "FINE", "FINER", "FINEST"]),
+];
+
+/// 标准化日志级别
+///
+/// 已知级别及其别名（不区分大小写）返回静态字符串，所有条目共享同一份，不分配内存；
+/// 只有未知级别才分配，转为大写返回。
+pub fn canonical_level(level: &str) -> Cow<'static, str> {
+    LEVEL_ALIASES.iter()
+        .find(|(_, aliases)| aliases.iter().any(|alias| alias.eq_ignore_ascii_case(level)))
+        .map(|(canonical, _)| Cow::Borrowed(*canonical))
+        .unwrap_or_else(|| Cow::Owned(level.to_uppercase()))
+}
+
 /// 统一日志格式化器
 pub struct UnifiedFormatter;
 
@@ -50,8 +112,8 @@ impl UnifiedFormatter {
         }
     }
 
-    /// 生成统一的格式化显示字符串
-    pub fn format_display_string(format: &UnifiedLogFormat) -> String {
+    /// 生成统一的格式化显示字符串（纯文本风格不输出元数据类型标签）
+    pub fn format_display_string(format: &UnifiedLogFormat, profile: FormattingProfile) -> String {
         let mut parts = Vec::new();
 
         // 时间戳（简化格式）
@@ -72,7 +134,9 @@ impl UnifiedFormatter {
         }
 
         // 特殊标记（基于元数据）
-        Self::add_metadata_tags(&mut parts, &format.metadata);
+        if !profile.is_plain() {
+            Self::add_metadata_tags(&mut parts, &format.metadata);
+        }
 
         // 主要消息
         parts.push(format.message.clone());
@@ -127,20 +191,8 @@ impl UnifiedFormatter {
     }
 
     /// 标准化日志级别
-    fn normalize_level(level: Option<String>) -> Option<String> {
-        match level {
-            Some(l) => {
-                let upper = l.to_uppercase();
-                match upper.as_str() {
-                    "ERROR" | "ERR" | "FATAL" | "SEVERE" => Some("ERROR".to_string()),
-                    "WARN" | "WARNING" | "ALERT" => Some("WARN".to_string()),
-                    "INFO" | "INFORMATION" | "NOTE" => Some("INFO".to_string()),
-                    "DEBUG" | "TRACE" | "VERBOSE" => Some("DEBUG".to_string()),
-                    _ => Some(upper),
-                }
-            }
-            None => None,
-        }
+    pub fn normalize_level(level: Option<String>) -> Option<String> {
+        level.map(|level| canonical_level(&level).into_owned())
     }
 
     /// 提取主要消息内容
@@ -297,7 +349,6 @@ impl UnifiedFormatter {
     }
 
     /// 创建颜色样式（用于终端输出）
-    #[allow(dead_code)]
     pub fn get_color_code(level: &Option<String>) -> &'static str {
         match level.as_deref() {
             Some("ERROR") => "\x1b[31m",    // 红色
@@ -309,12 +360,195 @@ impl UnifiedFormatter {
     }
 
     /// 重置颜色样式
-    #[allow(dead_code)]
     pub fn reset_color() -> &'static str {
         "\x1b[0m"
     }
 }
 
+// ============================================================================
+// 输出渲染器
+// ============================================================================
+
+/// 日志输出渲染器
+///
+/// 将解析后的日志行渲染为特定输出目标的文本。渲染器只负责"外观"，
+/// 不修改解析结果本身，因此同一份解析结果可以被导出、CLI和界面以不同方式复用。
+pub trait LogRenderer: Send + Sync {
+    /// 渲染器的唯一名称（用于请求中选择渲染器）
+    fn name(&self) -> &str;
+
+    /// 渲染单行日志
+    fn render_line(&self, line: &LogLine) -> String;
+
+    /// 将已渲染的行组合为完整文档（默认按换行拼接）
+    fn render_document(&self, rendered_lines: &[String]) -> String {
+        rendered_lines.join("\n")
+    }
+}
+
+/// 取得用于渲染的显示文本：优先使用格式化内容，否则使用原始内容
+fn display_text(line: &LogLine) -> &str {
+    line.formatted_content.as_deref().unwrap_or(&line.content)
+}
+
+/// 纯文本渲染器：原样输出格式化内容
+pub struct PlainRenderer;
+
+impl LogRenderer for PlainRenderer {
+    fn name(&self) -> &str {
+        "plain"
+    }
+
+    fn render_line(&self, line: &LogLine) -> String {
+        display_text(line).to_string()
+    }
+}
+
+/// ANSI彩色渲染器：按日志级别着色，用于终端/CLI输出
+pub struct AnsiRenderer;
+
+impl LogRenderer for AnsiRenderer {
+    fn name(&self) -> &str {
+        "ansi"
+    }
+
+    fn render_line(&self, line: &LogLine) -> String {
+        let level = UnifiedFormatter::normalize_level(line.level.clone());
+        format!(
+            "{}{}{}",
+            UnifiedFormatter::get_color_code(&level),
+            display_text(line),
+            UnifiedFormatter::reset_color()
+        )
+    }
+}
+
+/// HTML渲染器：每行输出带级别样式类的span，内容经过HTML转义
+pub struct HtmlRenderer;
+
+impl LogRenderer for HtmlRenderer {
+    fn name(&self) -> &str {
+        "html"
+    }
+
+    fn render_line(&self, line: &LogLine) -> String {
+        let level_class = UnifiedFormatter::normalize_level(line.level.clone())
+            .map(|level| level.to_lowercase())
+            .unwrap_or_else(|| "none".to_string());
+        format!(
+            "<span class=\"log-line log-level-{}\" data-line=\"{}\">{}</span>",
+            level_class,
+            line.line_number,
+            escape_html(display_text(line))
+        )
+    }
+
+    fn render_document(&self, rendered_lines: &[String]) -> String {
+        format!("<pre class=\"log-view\">\n{}\n</pre>", rendered_lines.join("\n"))
+    }
+}
+
+/// Markdown渲染器：每行输出为列表项，级别加粗，正文转义Markdown特殊字符
+pub struct MarkdownRenderer;
+
+impl LogRenderer for MarkdownRenderer {
+    fn name(&self) -> &str {
+        "markdown"
+    }
+
+    fn render_line(&self, line: &LogLine) -> String {
+        match UnifiedFormatter::normalize_level(line.level.clone()) {
+            Some(level) => format!("- **{}** {}", level, escape_markdown(display_text(line))),
+            None => format!("- {}", escape_markdown(display_text(line))),
+        }
+    }
+}
+
+/// HTML转义
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Markdown转义，避免日志内容中的符号被解释为格式标记
+pub fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '|') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// 渲染器注册表
+///
+/// 按名称管理所有可用的输出渲染器，构造时自动注册内置的
+/// plain / ansi / html / markdown 渲染器。
+pub struct RendererRegistry {
+    renderers: HashMap<String, Arc<dyn LogRenderer>>,
+}
+
+impl RendererRegistry {
+    pub fn new() -> Self {
+        let mut registry = Self { renderers: HashMap::new() };
+        registry.register(Arc::new(PlainRenderer));
+        registry.register(Arc::new(AnsiRenderer));
+        registry.register(Arc::new(HtmlRenderer));
+        registry.register(Arc::new(MarkdownRenderer));
+        registry
+    }
+
+    /// 注册渲染器，同名渲染器会被替换
+    pub fn register(&mut self, renderer: Arc<dyn LogRenderer>) {
+        self.renderers.insert(renderer.name().to_string(), renderer);
+    }
+
+    /// 按名称获取渲染器（不区分大小写）
+    ///
+    /// # Returns
+    /// - `Ok(Arc<dyn LogRenderer>)`: 找到的渲染器
+    /// - `Err(String)`: 渲染器不存在，错误信息包含可用渲染器列表
+    pub fn get(&self, name: &str) -> Result<Arc<dyn LogRenderer>, String> {
+        self.renderers.get(&name.to_lowercase()).cloned().ok_or_else(|| {
+            Message::new("renderer.not_found").with("renderer", name).with("available", format!("{:?}", self.names())).text()
+        })
+    }
+
+    /// 获取所有渲染器名称（按字母排序）
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.renderers.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// 使用指定渲染器重写日志行的格式化内容
+    pub fn apply(&self, name: &str, lines: &mut [LogLine]) -> Result<(), String> {
+        let renderer = self.get(name)?;
+        for line in lines.iter_mut() {
+            line.formatted_content = Some(renderer.render_line(line));
+        }
+        Ok(())
+    }
+}
+
+impl Default for RendererRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -341,6 +575,13 @@ mod tests {
             UnifiedFormatter::normalize_level(Some("warning".to_string())),
             Some("WARN".to_string())
         );
+
+        // 已知级别的所有条目共享同一份静态字符串，未知级别才分配
+        let (first, second) = (canonical_level("error"), canonical_level("SEVERE"));
+        assert!(matches!((&first, &second), (Cow::Borrowed(_), Cow::Borrowed(_))));
+        assert!(std::ptr::eq(first.as_ptr(), second.as_ptr()));
+        assert_eq!(canonical_level("Finest"), "DEBUG");
+        assert_eq!(canonical_level("notice"), Cow::<str>::Owned("NOTICE".to_string()));
     }
 
     #[test]
@@ -358,11 +599,15 @@ mod tests {
             raw_content: "Error: Database connection failed".to_string(),
         };
 
-        let result = UnifiedFormatter::format_display_string(&format);
+        let result = UnifiedFormatter::format_display_string(&format, FormattingProfile::default());
         assert!(result.contains("2024-09-30T08:00:07"));
         assert!(result.contains("[ERROR]"));
         assert!(result.contains("[STDERR]"));
         assert!(result.contains("Database connection failed"));
+
+        let plain = UnifiedFormatter::format_display_string(&format, FormattingProfile::Plain);
+        assert_eq!(plain, "2024-09-30T08:00:07 [ERROR] Database connection failed");
+        assert_eq!(FormattingProfile::strip_decorations("📝 SQL: select 1"), "SQL: select 1");
     }
 
     #[test]
@@ -417,4 +662,38 @@ mod tests {
         let result = UnifiedFormatter::remove_leading_timestamp(content);
         assert_eq!(result, "Simple log message without timestamp");
     }
-}
\ No newline at end of file
+
+    fn sample_line(level: Option<&str>, text: &str) -> LogLine {
+        LogLine {
+            level: level.map(|l| l.to_string()),
+            formatted_content: Some(text.to_string()),
+            ..LogLine::new(7, text)
+        }
+    }
+
+    #[test]
+    fn test_renderer_registry_lists_builtin_renderers() {
+        let registry = RendererRegistry::new();
+        assert_eq!(registry.names(), vec!["ansi", "html", "markdown", "plain"]);
+        assert!(registry.get("HTML").is_ok());
+        assert!(registry.get("xml").err().unwrap().contains("plain"));
+    }
+
+    #[test]
+    fn test_html_renderer_escapes_and_tags_level() {
+        let line = sample_line(Some("error"), "<b>boom</b> & co");
+        let rendered = HtmlRenderer.render_line(&line);
+        assert_eq!(
+            rendered,
+            "<span class=\"log-line log-level-error\" data-line=\"7\">&lt;b&gt;boom&lt;/b&gt; &amp; co</span>"
+        );
+    }
+
+    #[test]
+    fn test_ansi_and_markdown_renderers() {
+        let line = sample_line(Some("WARN"), "disk *almost* full");
+        assert_eq!(AnsiRenderer.render_line(&line), "\x1b[33mdisk *almost* full\x1b[0m");
+        assert_eq!(MarkdownRenderer.render_line(&line), "- **WARN** disk \\*almost\\* full");
+        assert_eq!(PlainRenderer.render_line(&line), "disk *almost* full");
+    }
+}
@@ -0,0 +1,316 @@
+/// IIS失败请求跟踪（FREB）解析
+///
+/// IIS的Failed Request Tracing为每个失败请求写一个XML文件（`fr000001.xml`）：
+///
+/// ```text
+/// <failedRequest url="http://localhost:80/api/orders" verb="GET" statusCode="500" timeTaken="1250" failureReason="STATUS_CODE" ...>
+///  <Event xmlns="http://schemas.microsoft.com/win/2004/08/events/event">
+///   <System>... <Level>4</Level> ... <TimeCreated SystemTime="2024-01-15T10:30:25.123Z"/> ...</System>
+///   <EventData><Data Name="ModuleName">ManagedPipelineHandler</Data>...</EventData>
+///   <RenderingInfo Culture="en-US"><Opcode>MODULE_SET_RESPONSE_ERROR_STATUS</Opcode></RenderingInfo>
+///  </Event>
+///  ...
+/// </failedRequest>
+/// ```
+///
+/// 过滤器把整个文件展开为时间线：第一条为请求概要，之后每个 `<Event>` 一条，
+/// 行号取 `<Event>` 在原文件中的行号，便于对照原始XML。
+///
+/// # 元数据
+/// - `freb_event`: 事件名称（`RenderingInfo` 中的 `Opcode`，如 `GENERAL_REQUEST_START`）
+/// - `module`、`notification`、`status`、`error_code`: 对应 `EventData` 中的
+///   `ModuleName`、`Notification`、`HttpStatus`、`ErrorCode`
+/// - `elapsed_ms`: 距请求开始的毫秒数
+/// - `duration_ms` / `operation`: 该步骤耗时（到下一个事件为止）和事件名称，慢操作分析可直接使用
+/// - 概要条目记录 `url`、`verb`、`status`、`failure_reason`、`app_pool`、`duration_ms`（`timeTaken`）
+
+use crate::eventlog::{element, event_level, unescape_xml, EVENT_DATA, TIME_CREATED};
+use crate::plugins::capabilities::{PerformanceRating, PluginCapabilities};
+use crate::plugins::chain::{PluginChainContext, PluginFilter};
+use crate::plugins::metrics::{DURATION_METADATA_KEY, OPERATION_METADATA_KEY};
+use crate::plugins::{LogLine, ParseRequest};
+use chrono::DateTime;
+use log::info;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::HashMap;
+
+/// 能处理的最大跟踪文件（64MB）
+const MAX_TRACE_FILE_SIZE: u64 = 64 * 1024 * 1024;
+
+/// 根元素的属性：`<failedRequest url="..." verb="GET" ...>`
+static ROOT_ATTRIBUTE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(\w+)="([^"]*)""#).unwrap()
+});
+
+/// `EventData` 字段与元数据键的对应关系
+const DATA_FIELDS: &[(&str, &str)] = &[
+    ("ModuleName", "module"),
+    ("Notification", "notification"),
+    ("HttpStatus", "status"),
+    ("ErrorCode", "error_code"),
+];
+
+/// FREB跟踪过滤器
+pub struct FrebTraceFilter;
+
+impl FrebTraceFilter {
+    /// 内容是否像FREB跟踪文件
+    pub fn looks_like_freb_trace(content: &str) -> bool {
+        content.lines().take(20).any(|line| line.contains("<failedRequest")) && content.contains("<Event")
+    }
+
+    /// 根元素 `<failedRequest ...>` 的属性
+    fn root_attributes(content: &str) -> HashMap<String, String> {
+        let Some(start) = content.find("<failedRequest") else {
+            return HashMap::new();
+        };
+        let end = content[start..].find('>').map_or(content.len(), |i| start + i);
+        ROOT_ATTRIBUTE.captures_iter(&content[start..end])
+            .map(|c| (c[1].to_string(), unescape_xml(&c[2])))
+            .collect()
+    }
+
+    /// 请求概要条目
+    fn summary_line(content: &str) -> LogLine {
+        let attributes = Self::root_attributes(content);
+        let attribute = |name: &str| attributes.get(name).cloned().unwrap_or_default();
+        let status = attribute("statusCode");
+        let level = match status.parse::<u16>() {
+            Ok(code) if code >= 500 => "ERROR",
+            Ok(code) if code >= 400 => "WARN",
+            _ => "INFO",
+        };
+
+        let mut metadata = HashMap::new();
+        for (key, name) in [
+            ("url", "url"), ("verb", "verb"), ("status", "statusCode"), ("failure_reason", "failureReason"),
+            ("app_pool", "appPoolId"), ("activity_id", "activityId"), (DURATION_METADATA_KEY, "timeTaken"),
+        ] {
+            if let Some(value) = attributes.get(name).filter(|v| !v.is_empty()) {
+                metadata.insert(key.to_string(), value.clone());
+            }
+        }
+        metadata.insert("freb_event".to_string(), "FAILED_REQUEST".to_string());
+
+        let content_text = format!(
+            "{} {} -> {} ({}) {}ms",
+            attribute("verb"), attribute("url"), status, attribute("failureReason"), attribute("timeTaken")
+        );
+        LogLine {
+            line_number: 1,
+            content: content_text.clone(),
+            level: Some(level.to_string()),
+            timestamp: None,
+            formatted_content: Some(content_text),
+            message: None,
+            metadata,
+            processed_by: vec!["freb_filter".to_string()],
+        }
+    }
+
+    /// 把单个 `<Event>` 转换为条目，同时返回事件时间的毫秒时间戳（用于计算耗时）
+    fn event_line(xml: &str, line_number: usize) -> (LogLine, Option<i64>) {
+        let system = element(xml, "System").unwrap_or_default();
+        let level = event_level(element(system, "Level").and_then(|l| l.trim().parse().ok()).unwrap_or(4));
+        let timestamp = TIME_CREATED.captures(system).map(|c| c[1].to_string());
+        let millis = timestamp.as_deref()
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            .map(|t| t.timestamp_millis());
+        let name = element(xml, "RenderingInfo")
+            .and_then(|info| element(info, "Opcode"))
+            .map(|opcode| opcode.trim().to_string())
+            .unwrap_or_else(|| "UNKNOWN_EVENT".to_string());
+
+        let mut metadata = HashMap::new();
+        let mut fields = Vec::new();
+        let data = element(xml, "EventData").unwrap_or_default();
+        for capture in EVENT_DATA.captures_iter(data) {
+            let Some(field) = capture.get(1).map(|f| f.as_str()) else {
+                continue;
+            };
+            let value = unescape_xml(capture[2].trim());
+            if let Some((_, key)) = DATA_FIELDS.iter().find(|(name, _)| *name == field) {
+                metadata.insert(key.to_string(), value.clone());
+            }
+            // 上下文ID对所有事件相同，不放入显示内容
+            if field != "ContextId" && !value.is_empty() {
+                fields.push(format!("{}={}", field, value));
+            }
+        }
+        metadata.insert("freb_event".to_string(), name.clone());
+        metadata.insert(OPERATION_METADATA_KEY.to_string(), name.clone());
+
+        let content = if fields.is_empty() { name } else { format!("{} {}", name, fields.join(" ")) };
+        let line = LogLine {
+            line_number,
+            content: content.clone(),
+            level: Some(level.to_string()),
+            timestamp,
+            formatted_content: Some(content),
+            message: None,
+            metadata,
+            processed_by: vec!["freb_filter".to_string()],
+        };
+        (line, millis)
+    }
+
+    /// 把跟踪文件展开为时间线条目
+    ///
+    /// # Returns
+    /// - `Vec<LogLine>`: 请求概要和按文件顺序排列的事件，事件的耗时按相邻事件的时间差计算
+    pub fn flatten(content: &str) -> Vec<LogLine> {
+        let mut lines = vec![Self::summary_line(content)];
+        let mut times = Vec::new();
+
+        let mut offset = 0;
+        while let Some(start) = content[offset..].find("<Event").map(|i| offset + i) {
+            let Some(end) = content[start..].find("</Event>").map(|i| start + i + "</Event>".len()) else {
+                break;
+            };
+            let line_number = content[..start].matches('\n').count() + 1;
+            let (line, millis) = Self::event_line(&content[start..end], line_number);
+            lines.push(line);
+            times.push(millis);
+            offset = end;
+        }
+
+        let start_time = times.iter().flatten().next().copied();
+        for (index, line) in lines.iter_mut().skip(1).enumerate() {
+            let Some(time) = times[index] else {
+                continue;
+            };
+            if let Some(start) = start_time {
+                line.metadata.insert("elapsed_ms".to_string(), (time - start).to_string());
+            }
+            if let Some(Some(next)) = times.get(index + 1) {
+                line.metadata.insert(DURATION_METADATA_KEY.to_string(), (next - time).max(0).to_string());
+            }
+        }
+        lines
+    }
+}
+
+impl PluginFilter for FrebTraceFilter {
+    fn name(&self) -> &str {
+        "freb"
+    }
+
+    fn description(&self) -> &str {
+        "IIS失败请求跟踪过滤器，把FREB XML展开为带事件名称和耗时的时间线"
+    }
+
+    fn priority(&self) -> i32 {
+        5 // 最先执行，整体替换XML内容
+    }
+
+    fn should_process(&self, context: &PluginChainContext) -> bool {
+        Self::looks_like_freb_trace(&context.original_content)
+    }
+
+    fn process(&self, context: &mut PluginChainContext, _request: &ParseRequest) -> Result<(), String> {
+        info!("🧾 FREB跟踪过滤器开始处理");
+
+        let lines = Self::flatten(&context.original_content);
+        let events = lines.len() - 1;
+        if let Some(status) = lines[0].metadata.get("status") {
+            context.set_chain_metadata("freb_status".to_string(), status.clone());
+        }
+        context.current_lines = lines;
+
+        info!("🧾 FREB跟踪过滤器处理完成，展开了 {} 个事件", events);
+        Ok(())
+    }
+
+    fn can_handle(&self, content: &str, _file_path: Option<&str>) -> bool {
+        Self::looks_like_freb_trace(content)
+    }
+
+    /// 整个XML文档展开后才能生成事件，单个跟踪文件通常只有几十KB
+    fn capabilities(&self) -> PluginCapabilities {
+        PluginCapabilities::new(Some(MAX_TRACE_FILE_SIZE), PerformanceRating::Slow)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::chain::PluginChainManager;
+    use crate::plugins::presets::register_preset_chains;
+
+    const TRACE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<?xml-stylesheet type='text/xsl' href='freb.xsl'?>
+<failedRequest url="http://localhost:80/api/orders?id=1&amp;x=2"
+               siteId="1"
+               appPoolId="DefaultAppPool"
+               verb="GET"
+               failureReason="STATUS_CODE"
+               statusCode="500"
+               timeTaken="1250">
+ <Event xmlns="http://schemas.microsoft.com/win/2004/08/events/event">
+  <System>
+   <Provider Name="WWW Server" Guid="{3A2A4E84-4C21-4981-AE10-3FDA0D9B0F83}"/>
+   <Level>0</Level>
+   <Opcode>1</Opcode>
+   <TimeCreated SystemTime="2024-01-15T10:30:25.1000000Z"/>
+  </System>
+  <EventData>
+   <Data Name="ContextId">{00000000-0000-0000-1a00-0080000000fb}</Data>
+   <Data Name="SiteId">1</Data>
+  </EventData>
+  <RenderingInfo Culture="en-US"><Opcode>GENERAL_REQUEST_START</Opcode></RenderingInfo>
+ </Event>
+ <Event xmlns="http://schemas.microsoft.com/win/2004/08/events/event">
+  <System>
+   <Level>5</Level>
+   <Opcode>1</Opcode>
+   <TimeCreated SystemTime="2024-01-15T10:30:25.1500000Z"/>
+  </System>
+  <EventData>
+   <Data Name="ModuleName">ManagedPipelineHandler</Data>
+   <Data Name="Notification">128</Data>
+  </EventData>
+  <RenderingInfo Culture="en-US"><Opcode>NOTIFY_MODULE_START</Opcode></RenderingInfo>
+ </Event>
+ <Event xmlns="http://schemas.microsoft.com/win/2004/08/events/event">
+  <System>
+   <Level>3</Level>
+   <Opcode>16</Opcode>
+   <TimeCreated SystemTime="2024-01-15T10:30:26.3500000Z"/>
+  </System>
+  <EventData>
+   <Data Name="ModuleName">ManagedPipelineHandler</Data>
+   <Data Name="HttpStatus">500</Data>
+   <Data Name="ErrorCode">2147942402</Data>
+  </EventData>
+  <RenderingInfo Culture="en-US"><Opcode>MODULE_SET_RESPONSE_ERROR_STATUS</Opcode></RenderingInfo>
+ </Event>
+</failedRequest>"#;
+
+    #[test]
+    fn test_flattens_events_into_timeline() {
+        let mut manager = PluginChainManager::new();
+        register_preset_chains(&mut manager);
+        let result = manager.process(TRACE, &ParseRequest::default()).unwrap();
+        assert_eq!(result.detected_format.as_deref(), Some("freb"));
+
+        let lines = &result.lines;
+        assert_eq!(lines.len(), 4);
+        let meta = |index: usize, key: &str| lines[index].metadata.get(key).map(String::as_str);
+        assert_eq!(lines[0].content, "GET http://localhost:80/api/orders?id=1&x=2 -> 500 (STATUS_CODE) 1250ms");
+        assert_eq!(lines[0].level.as_deref(), Some("ERROR"));
+        assert_eq!(meta(0, "app_pool"), Some("DefaultAppPool"));
+        assert_eq!(meta(0, "duration_ms"), Some("1250"));
+
+        assert_eq!(lines[1].line_number, 10);
+        assert_eq!(meta(1, "freb_event"), Some("GENERAL_REQUEST_START"));
+        assert_eq!(lines[1].content, "GENERAL_REQUEST_START SiteId=1");
+        assert_eq!((meta(1, "elapsed_ms"), meta(1, "duration_ms")), (Some("0"), Some("50")));
+        assert_eq!(lines[2].level.as_deref(), Some("DEBUG"));
+        assert_eq!(meta(2, "module"), Some("ManagedPipelineHandler"));
+        assert_eq!(meta(2, "duration_ms"), Some("1200"));
+        assert_eq!(lines[3].level.as_deref(), Some("WARN"));
+        assert_eq!((meta(3, "status"), meta(3, "error_code")), (Some("500"), Some("2147942402")));
+        assert_eq!((meta(3, "elapsed_ms"), meta(3, "duration_ms")), (Some("1250"), None));
+    }
+}
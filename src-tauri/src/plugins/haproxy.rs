@@ -0,0 +1,282 @@
+/// HAProxy HTTP 日志解析
+///
+/// 解析 `option httplog` 的默认格式（通常经syslog写入，行首带有 `haproxy[pid]: ` 前缀）：
+///
+/// ```text
+/// Feb  6 12:14:14 lb1 haproxy[14389]: 10.0.1.2:33317 [06/Feb/2024:12:14:14.655] http-in static/srv1 10/0/30/69/109 200 2750 - - ---- 1/1/1/1/0 0/0 "GET /index.html HTTP/1.1"
+/// ```
+///
+/// # 元数据
+/// - `client_ip` / `client_port`、`frontend`、`backend`、`server`
+/// - `timer_tq` / `timer_tw` / `timer_tc` / `timer_tr` / `timer_tt`: 五个计时器（毫秒，`-1` 表示该阶段未完成）；
+///   `timer_tt` 同时写入 `duration_ms`
+/// - `status`、`bytes`、`retries`、`request`
+/// - `termination_state`: 四个字符的终止状态（如 `sH--`）
+/// - `termination_cause` / `termination_phase`: 前两个字符的含义
+/// - `termination_explanation`: 终止状态的可读说明，如 "服务端超时；等待服务端响应头时"
+
+use crate::plugins::chain::{PluginChainContext, PluginFilter};
+use crate::plugins::metrics::DURATION_METADATA_KEY;
+use crate::plugins::{LogLine, ParseRequest};
+use chrono::NaiveDateTime;
+use log::info;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::HashMap;
+
+/// HTTP日志行（syslog前缀之后）
+static HTTP_LOG: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(concat!(
+        r"(?:^|haproxy\[\d+\]: )",
+        r"(\S+):(\d+) \[([^\]]+)\] (\S+) ([^/\s]+)/(\S+) ",          // 客户端、接收时间、前端、后端/服务器
+        r"(-?\d+)/(-?\d+)/(-?\d+)/(-?\d+)/\+?(-?\d+) ",              // Tq/Tw/Tc/Tr/Tt
+        r"(-?\d+) \+?(\d+) \S+ \S+ ([A-Za-z-]{4}) ",                 // 状态码、字节数、cookie、终止状态
+        r"\d+/\d+/\d+/\d+/\+?(\d+) \d+/\d+",                         // 连接数/重试次数、队列
+        r#"(?: \{[^}]*\})*(?: "([^"]*)")?"#,                         // 捕获的头部、请求行
+    )).unwrap()
+});
+
+/// 计时器元数据键（按日志中的顺序）
+const TIMER_KEYS: [&str; 5] = ["timer_tq", "timer_tw", "timer_tc", "timer_tr", "timer_tt"];
+
+/// 终止状态第一个字符：会话结束的原因
+fn termination_cause(flag: char) -> Option<&'static str> {
+    Some(match flag {
+        'C' => "客户端中止",
+        'S' => "服务端中止或拒绝连接",
+        'P' => "代理主动中止（内部安全检查）",
+        'L' => "由HAProxy本地处理",
+        'R' => "代理资源耗尽",
+        'I' => "代理内部错误",
+        'D' => "服务器宕机，会话被HAProxy终止",
+        'U' => "备用服务器恢复，会话被HAProxy终止",
+        'K' => "被管理员主动终止",
+        'c' => "客户端超时",
+        's' => "服务端超时",
+        _ => return None,
+    })
+}
+
+/// 终止状态第二个字符：会话结束时所处的阶段
+fn termination_phase(flag: char) -> Option<&'static str> {
+    Some(match flag {
+        'R' => "等待客户端发送完整请求时",
+        'Q' => "在队列中等待时",
+        'C' => "等待连接服务端时",
+        'H' => "等待服务端响应头时",
+        'D' => "传输数据时",
+        'L' => "向客户端发送最后的数据时",
+        'T' => "请求被拦截（tarpit）时",
+        _ => return None,
+    })
+}
+
+/// 终止状态第三个字符：请求携带的持久化cookie
+fn cookie_state(flag: char) -> Option<&'static str> {
+    Some(match flag {
+        'N' => "请求未携带持久化cookie",
+        'I' => "持久化cookie无效",
+        'D' => "cookie指向的服务器已宕机",
+        'V' => "持久化cookie有效",
+        'E' => "持久化cookie已过期",
+        'O' => "持久化cookie过旧",
+        'U' => "未使用cookie（使用了其他持久化机制）",
+        _ => return None,
+    })
+}
+
+/// 终止状态第四个字符：对持久化cookie的操作
+fn cookie_action(flag: char) -> Option<&'static str> {
+    Some(match flag {
+        'N' => "未设置cookie",
+        'I' => "插入了cookie",
+        'P' => "插入了持久化cookie",
+        'R' => "改写了cookie",
+        'U' => "更新了cookie",
+        'D' => "删除了cookie",
+        _ => return None,
+    })
+}
+
+/// 把终止状态解释为可读说明
+///
+/// # 参数
+/// - `state`: 四个字符的终止状态，如 `sH--`
+///
+/// # Returns
+/// - `Option<String>`: 各字符说明以分号连接；全部为 `-`（正常结束）时返回None
+pub fn explain_termination_state(state: &str) -> Option<String> {
+    let flags: Vec<char> = state.chars().collect();
+    let decoders: [fn(char) -> Option<&'static str>; 4] = [termination_cause, termination_phase, cookie_state, cookie_action];
+    let parts: Vec<&str> = flags.iter().zip(decoders).filter_map(|(&flag, decode)| decode(flag)).collect();
+    (!parts.is_empty()).then(|| parts.join("；"))
+}
+
+/// HAProxy HTTP日志过滤器
+pub struct HaproxyLogFilter;
+
+impl HaproxyLogFilter {
+    /// 行是否为HAProxy HTTP日志行
+    pub fn matches_line(line: &str) -> bool {
+        HTTP_LOG.is_match(line)
+    }
+
+    /// 内容是否像HAProxy HTTP日志
+    pub fn looks_like_haproxy_log(content: &str) -> bool {
+        content.lines().take(200).any(Self::matches_line)
+    }
+
+    /// 按状态码和终止原因确定级别
+    fn level(status: &str, state: &str) -> &'static str {
+        match state.chars().next() {
+            Some('S' | 's' | 'P' | 'R' | 'I' | 'D' | 'U' | 'K') => return "ERROR",
+            _ if status.starts_with('5') => return "ERROR",
+            Some('C' | 'c') => return "WARN",
+            _ => {}
+        }
+        if status.starts_with('4') { "WARN" } else { "INFO" }
+    }
+
+    /// 处理单行，返回是否识别为HAProxy日志行
+    fn tag_line(line: &mut LogLine, strict: bool) -> bool {
+        let content = line.message_text().to_string();
+        let Some(caps) = HTTP_LOG.captures(&content) else {
+            return false;
+        };
+
+        let metadata = &mut line.metadata;
+        metadata.insert("client_ip".to_string(), caps[1].to_string());
+        metadata.insert("client_port".to_string(), caps[2].to_string());
+        metadata.insert("frontend".to_string(), caps[4].to_string());
+        metadata.insert("backend".to_string(), caps[5].to_string());
+        metadata.insert("server".to_string(), caps[6].to_string());
+        for (index, key) in TIMER_KEYS.iter().enumerate() {
+            metadata.insert(key.to_string(), caps[7 + index].to_string());
+        }
+        if !caps[11].starts_with('-') {
+            metadata.insert(DURATION_METADATA_KEY.to_string(), caps[11].to_string());
+        }
+        metadata.insert("status".to_string(), caps[12].to_string());
+        metadata.insert("bytes".to_string(), caps[13].to_string());
+        metadata.insert("retries".to_string(), caps[15].to_string());
+
+        let state = caps[14].to_string();
+        let mut flags = state.chars();
+        if let Some(cause) = flags.next().and_then(termination_cause) {
+            metadata.insert("termination_cause".to_string(), cause.to_string());
+        }
+        if let Some(phase) = flags.next().and_then(termination_phase) {
+            metadata.insert("termination_phase".to_string(), phase.to_string());
+        }
+        if let Some(explanation) = explain_termination_state(&state) {
+            metadata.insert("termination_explanation".to_string(), explanation);
+        }
+        metadata.insert("termination_state".to_string(), state.clone());
+
+        if let Some(request) = caps.get(16) {
+            metadata.insert("request".to_string(), request.as_str().to_string());
+        }
+        if let Ok(accepted) = NaiveDateTime::parse_from_str(&caps[3], "%d/%b/%Y:%H:%M:%S%.f") {
+            line.timestamp = Some(accepted.format("%Y-%m-%d %H:%M:%S").to_string());
+        }
+        line.level = Some(Self::level(&caps[12], &state).to_string());
+        line.set_message(content[caps.get(1).unwrap().start()..].to_string(), strict);
+        true
+    }
+}
+
+impl PluginFilter for HaproxyLogFilter {
+    fn name(&self) -> &str {
+        "haproxy"
+    }
+
+    fn description(&self) -> &str {
+        "HAProxy HTTP日志过滤器，提取前后端、计时器和状态码，并解释终止状态"
+    }
+
+    fn priority(&self) -> i32 {
+        20 // 格式解析，在ANSI清理之后执行
+    }
+
+    fn should_process(&self, context: &PluginChainContext) -> bool {
+        if context.current_lines.is_empty() {
+            return Self::looks_like_haproxy_log(&context.original_content);
+        }
+        context.current_lines.iter().take(200).any(|line| HTTP_LOG.is_match(line.message_text()))
+    }
+
+    fn process(&self, context: &mut PluginChainContext, request: &ParseRequest) -> Result<(), String> {
+        info!("⚖️ HAProxy日志过滤器开始处理");
+        let strict = request.is_strict();
+
+        let mut lines = if context.current_lines.is_empty() {
+            // 第一次处理，从原始内容创建行列表，过滤空行
+            context.original_content.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()).map(|(i, line)| {
+                LogLine {
+                    line_number: i + 1,
+                    content: line.to_string(),
+                    level: None,
+                    timestamp: None,
+                    formatted_content: None,
+                    message: None,
+                    metadata: HashMap::new(),
+                    processed_by: vec![],
+                }
+            }).collect()
+        } else {
+            std::mem::take(&mut context.current_lines)
+        };
+
+        let mut tagged = 0;
+        for line in &mut lines {
+            if Self::tag_line(line, strict) {
+                line.processed_by.push("haproxy_filter".to_string());
+                line.formatted_content = Some(line.message_text().to_string());
+                tagged += 1;
+            }
+        }
+
+        context.current_lines = lines;
+        info!("⚖️ HAProxy日志过滤器处理完成，识别了 {} 行", tagged);
+        Ok(())
+    }
+
+    fn can_handle(&self, content: &str, _file_path: Option<&str>) -> bool {
+        Self::looks_like_haproxy_log(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::chain::PluginChainManager;
+    use crate::plugins::presets::register_preset_chains;
+
+    #[test]
+    fn test_parses_http_log_and_termination_state() {
+        let content = r#"Feb  6 12:14:14 lb1 haproxy[14389]: 10.0.1.2:33317 [06/Feb/2024:12:14:14.655] http-in static/srv1 10/0/30/69/109 200 2750 - - ---- 1/1/1/1/0 0/0 "GET /index.html HTTP/1.1"
+Feb  6 12:14:45 lb1 haproxy[14389]: 10.0.1.3:40112 [06/Feb/2024:12:14:15.001] http-in api/srv2 5/0/1/-1/30006 504 194 - - sH-- 3/3/2/2/0 0/0 {example.com} "POST /api/orders HTTP/1.1"
+Feb  6 12:14:46 lb1 haproxy[14389]: 10.0.1.4:40113 [06/Feb/2024:12:14:46.100] http-in api/<NOSRV> 2/-1/-1/-1/2 503 212 - - SC-- 0/0/0/0/0 0/0 "GET /health HTTP/1.1""#;
+
+        let mut manager = PluginChainManager::new();
+        register_preset_chains(&mut manager);
+        let result = manager.process(content, &ParseRequest::default()).unwrap();
+        assert_eq!(result.detected_format.as_deref(), Some("haproxy"));
+
+        let lines = &result.lines;
+        let meta = |index: usize, key: &str| lines[index].metadata.get(key).map(String::as_str);
+        assert_eq!(lines[0].timestamp.as_deref(), Some("2024-02-06 12:14:14"));
+        assert_eq!(lines[0].level.as_deref(), Some("INFO"));
+        assert_eq!((meta(0, "frontend"), meta(0, "backend"), meta(0, "server")), (Some("http-in"), Some("static"), Some("srv1")));
+        assert_eq!((meta(0, "timer_tr"), meta(0, "duration_ms")), (Some("69"), Some("109")));
+        assert_eq!(meta(0, "termination_explanation"), None);
+        assert_eq!(meta(0, "request"), Some("GET /index.html HTTP/1.1"));
+
+        assert_eq!(lines[1].level.as_deref(), Some("ERROR"));
+        assert_eq!(meta(1, "timer_tr"), Some("-1"));
+        assert_eq!(meta(1, "termination_explanation"), Some("服务端超时；等待服务端响应头时"));
+        assert_eq!(meta(2, "server"), Some("<NOSRV>"));
+        assert_eq!(meta(2, "termination_cause"), Some("服务端中止或拒绝连接"));
+        assert_eq!(meta(2, "termination_phase"), Some("等待连接服务端时"));
+    }
+}
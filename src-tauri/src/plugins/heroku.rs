@@ -0,0 +1,292 @@
+/// Heroku / 12-factor 路由日志解析
+///
+/// `heroku logs` 输出的每行以 `<时间戳> <来源>[<进程>]: ` 开头，路由层日志的正文是logfmt键值对：
+///
+/// ```text
+/// 2024-01-15T10:30:25.123456+00:00 heroku[router]: at=info method=GET path="/api/users" host=app.herokuapp.com request_id=8f1c fwd="10.0.0.1" dyno=web.1 connect=1ms service=5ms status=200 bytes=1234 protocol=https
+/// 2024-01-15T10:30:26.000000+00:00 heroku[router]: at=error code=H12 desc="Request timeout" method=GET path="/slow" dyno=web.2 connect=0ms service=30000ms status=503 bytes=0
+/// 2024-01-15T10:30:27.000000+00:00 app[web.1]: Started GET "/api/users"
+/// ```
+///
+/// 没有行首前缀、直接包含 `at=` 和 `dyno=` 字段的路由日志（如日志转发服务导出的正文）同样识别。
+///
+/// # 级别
+/// `at=error` / `at=warning` 直接对应ERROR / WARN，否则按 `status` 映射：5xx为ERROR，4xx为WARN，其余为INFO。
+///
+/// # 元数据
+/// - `heroku_source` / `process`: 行首的来源（heroku / app）和进程（router、web.1）
+/// - `dyno`: 处理请求的dyno（路由日志的 `dyno=` 字段，应用日志取前缀中的进程名）
+/// - `method`、`path`、`host`、`request_id`、`fwd`、`status`、`protocol`、`code`、`desc`: 同名logfmt字段
+/// - `metric_connect` / `metric_service` / `metric_bytes`: 连接耗时、服务耗时和响应大小（带单位的度量）
+/// - `duration_ms`: 连接耗时与服务耗时之和，`find_slow_operations` 按 `operation`（`方法 路径`）分组
+
+use crate::plugins::chain::{PluginChainContext, PluginFilter};
+use crate::plugins::metrics::{duration_to_ms, parse_measurement, DURATION_METADATA_KEY, METRIC_METADATA_PREFIX, OPERATION_METADATA_KEY};
+use crate::plugins::{LogLine, ParseRequest};
+use log::info;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::HashMap;
+
+/// 行首前缀：`2024-01-15T10:30:25.123456+00:00 heroku[router]: `
+static HEROKU_PREFIX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2})(?:\.\d+)?(?:Z|[+-]\d{2}:?\d{2}) ([a-z]+)\[([\w.-]+)\]: ?").unwrap()
+});
+
+/// 路由日志特征字段：`at=info|warning|error`
+static ROUTER_AT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?:^|\s)at=(?:info|warning|error)(?:\s|$)").unwrap()
+});
+
+/// 路由日志特征字段：`dyno=`
+static ROUTER_DYNO: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?:^|\s)dyno=\S").unwrap()
+});
+
+/// 按原样写入元数据的logfmt字段
+const COPIED_FIELDS: &[&str] = &["method", "path", "host", "request_id", "fwd", "dyno", "status", "protocol", "code", "desc"];
+
+/// 作为度量写入的logfmt字段
+const METRIC_FIELDS: &[&str] = &["connect", "service", "bytes"];
+
+/// 解析logfmt键值对
+///
+/// 值可以是裸值或双引号包围的字符串（支持 `\"` 转义），没有 `=` 的单独键的值为空字符串。
+///
+/// # Returns
+/// - `Vec<(String, String)>`: 按出现顺序的 (键, 值)
+pub fn parse_logfmt(text: &str) -> Vec<(String, String)> {
+    let mut pairs = Vec::new();
+    let mut chars = text.chars().peekable();
+
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        let mut key = String::new();
+        while let Some(c) = chars.next_if(|&c| !c.is_whitespace() && c != '=') {
+            key.push(c);
+        }
+        if key.is_empty() {
+            // 行尾，或者没有键的 `=`（跳过）
+            if chars.next().is_none() {
+                break;
+            }
+            continue;
+        }
+
+        let mut value = String::new();
+        if chars.next_if_eq(&'=').is_some() {
+            if chars.next_if_eq(&'"').is_some() {
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => value.extend(chars.next()),
+                        _ => value.push(c),
+                    }
+                }
+            } else {
+                while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                    value.push(c);
+                }
+            }
+        }
+        pairs.push((key, value));
+    }
+    pairs
+}
+
+/// Heroku路由日志过滤器
+pub struct HerokuRouterFilter;
+
+impl HerokuRouterFilter {
+    /// 文本是否为路由日志正文（同时带有 `at=` 和 `dyno=` 字段）
+    fn is_router_payload(text: &str) -> bool {
+        ROUTER_AT.is_match(text) && ROUTER_DYNO.is_match(text)
+    }
+
+    /// 行是否带有Heroku日志前缀或是路由日志正文
+    pub fn matches_line(line: &str) -> bool {
+        HEROKU_PREFIX.is_match(line) || Self::is_router_payload(line)
+    }
+
+    /// 内容是否像Heroku日志
+    pub fn looks_like_heroku_log(content: &str) -> bool {
+        content.lines().take(200).any(Self::matches_line)
+    }
+
+    /// HTTP状态码对应的级别
+    fn status_level(status: &str) -> &'static str {
+        match status.as_bytes().first() {
+            Some(b'5') => "ERROR",
+            Some(b'4') => "WARN",
+            _ => "INFO",
+        }
+    }
+
+    /// 处理单行，返回是否识别为Heroku日志行
+    fn tag_line(line: &mut LogLine, strict: bool) -> bool {
+        let source = line.message_text().to_string();
+        let mut text = source.as_str();
+        let mut prefixed = false;
+        if let Some(caps) = HEROKU_PREFIX.captures(text) {
+            line.timestamp = Some(caps[1].to_string());
+            line.metadata.insert("heroku_source".to_string(), caps[2].to_string());
+            line.metadata.insert("process".to_string(), caps[3].to_string());
+            if &caps[2] == "app" {
+                line.metadata.insert("dyno".to_string(), caps[3].to_string());
+            }
+            text = &text[caps.get(0).unwrap().end()..];
+            prefixed = true;
+        }
+
+        if !Self::is_router_payload(text) {
+            if prefixed {
+                line.set_message(text.to_string(), strict);
+            }
+            return prefixed;
+        }
+
+        let fields: HashMap<String, String> = parse_logfmt(text).into_iter().collect();
+        for key in COPIED_FIELDS {
+            if let Some(value) = fields.get(*key).filter(|value| !value.is_empty()) {
+                line.metadata.insert(key.to_string(), value.clone());
+            }
+        }
+
+        let mut duration = None;
+        for key in METRIC_FIELDS {
+            let Some(value) = fields.get(*key) else { continue };
+            let Some((number, unit)) = parse_measurement(value) else { continue };
+            line.metadata.insert(format!("{}{}", METRIC_METADATA_PREFIX, key), value.clone());
+            if let Some(ms) = duration_to_ms(number, unit) {
+                *duration.get_or_insert(0.0) += ms;
+            }
+        }
+        if let Some(duration) = duration {
+            line.metadata.insert(DURATION_METADATA_KEY.to_string(), duration.to_string());
+        }
+        if let (Some(method), Some(path)) = (fields.get("method"), fields.get("path")) {
+            line.metadata.insert(OPERATION_METADATA_KEY.to_string(), format!("{} {}", method, path));
+        }
+
+        let level = match fields.get("at").map(String::as_str) {
+            Some("error") => "ERROR",
+            Some("warning") => "WARN",
+            _ => fields.get("status").map_or("INFO", |status| Self::status_level(status)),
+        };
+        line.level = Some(level.to_string());
+        line.set_message(text.to_string(), strict);
+        true
+    }
+}
+
+impl PluginFilter for HerokuRouterFilter {
+    fn name(&self) -> &str {
+        "heroku"
+    }
+
+    fn description(&self) -> &str {
+        "Heroku路由日志过滤器，解析logfmt字段、按状态码映射级别并提取dyno和耗时度量"
+    }
+
+    fn priority(&self) -> i32 {
+        20 // 格式解析，在ANSI清理之后执行
+    }
+
+    fn should_process(&self, context: &PluginChainContext) -> bool {
+        if context.current_lines.is_empty() {
+            return Self::looks_like_heroku_log(&context.original_content);
+        }
+        context.current_lines.iter().take(200).any(|line| Self::looks_like_heroku_log(line.message_text()))
+    }
+
+    fn process(&self, context: &mut PluginChainContext, request: &ParseRequest) -> Result<(), String> {
+        info!("🟣 Heroku路由日志过滤器开始处理");
+        let strict = request.is_strict();
+
+        let mut lines = if context.current_lines.is_empty() {
+            // 第一次处理，从原始内容创建行列表，过滤空行
+            context.original_content.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()).map(|(i, line)| {
+                LogLine {
+                    line_number: i + 1,
+                    content: line.to_string(),
+                    level: None,
+                    timestamp: None,
+                    formatted_content: None,
+                    message: None,
+                    metadata: HashMap::new(),
+                    processed_by: vec![],
+                }
+            }).collect()
+        } else {
+            std::mem::take(&mut context.current_lines)
+        };
+
+        let mut tagged = 0;
+        for line in &mut lines {
+            if Self::tag_line(line, strict) {
+                line.processed_by.push("heroku_filter".to_string());
+                line.formatted_content = Some(line.message_text().to_string());
+                tagged += 1;
+            }
+        }
+
+        context.current_lines = lines;
+        info!("🟣 Heroku路由日志过滤器处理完成，识别了 {} 行", tagged);
+        Ok(())
+    }
+
+    fn can_handle(&self, content: &str, _file_path: Option<&str>) -> bool {
+        Self::looks_like_heroku_log(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::chain::PluginChainManager;
+    use crate::plugins::presets::register_preset_chains;
+
+    fn meta<'a>(line: &'a LogLine, key: &str) -> Option<&'a str> {
+        line.metadata.get(key).map(String::as_str)
+    }
+
+    #[test]
+    fn test_parse_logfmt() {
+        let pairs = parse_logfmt(r#"at=error code=H12 desc="Request \"timeout\"" flag  path=/x"#);
+        assert_eq!(pairs, vec![
+            ("at".to_string(), "error".to_string()),
+            ("code".to_string(), "H12".to_string()),
+            ("desc".to_string(), "Request \"timeout\"".to_string()),
+            ("flag".to_string(), String::new()),
+            ("path".to_string(), "/x".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn test_router_lines_levels_and_metrics() {
+        let content = r#"2024-01-15T10:30:25.123456+00:00 heroku[router]: at=info method=GET path="/api/users" host=app.herokuapp.com dyno=web.1 connect=1ms service=5ms status=200 bytes=1234 protocol=https
+2024-01-15T10:30:26.000000+00:00 heroku[router]: at=info method=POST path="/api/login" dyno=web.2 connect=0ms service=12ms status=401 bytes=0
+2024-01-15T10:30:27.000000+00:00 heroku[router]: at=error code=H12 desc="Request timeout" method=GET path="/slow" dyno=web.2 connect=0ms service=30000ms status=503 bytes=0
+2024-01-15T10:30:28.000000+00:00 app[web.1]: Started GET "/api/users""#;
+        assert!(HerokuRouterFilter.can_handle(content, None));
+
+        let mut manager = PluginChainManager::new();
+        register_preset_chains(&mut manager);
+        let result = manager.process(content, &ParseRequest::default()).unwrap();
+        assert_eq!(result.detected_format.as_deref(), Some("heroku"));
+
+        let lines = &result.lines;
+        let levels: Vec<_> = lines.iter().map(|line| line.level.as_deref()).collect();
+        assert_eq!(levels[..3], [Some("INFO"), Some("WARN"), Some("ERROR")]);
+        assert_eq!(lines[0].timestamp.as_deref(), Some("2024-01-15T10:30:25"));
+        assert_eq!(meta(&lines[0], "dyno"), Some("web.1"));
+        assert_eq!(meta(&lines[0], "path"), Some("/api/users"));
+        assert_eq!(meta(&lines[0], "metric_connect"), Some("1ms"));
+        assert_eq!(meta(&lines[0], "metric_service"), Some("5ms"));
+        assert_eq!(meta(&lines[0], "duration_ms"), Some("6"));
+        assert_eq!(meta(&lines[2], "code"), Some("H12"));
+        assert_eq!(meta(&lines[2], "operation"), Some("GET /slow"));
+        assert_eq!(meta(&lines[3], "dyno"), Some("web.1"));
+        assert_eq!(lines[3].content, r#"Started GET "/api/users""#);
+    }
+}
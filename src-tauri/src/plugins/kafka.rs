@@ -0,0 +1,370 @@
+/// Kafka 服务端日志解析
+///
+/// 解析broker和controller的 `server.log` / `controller.log`：
+///
+/// ```text
+/// [2024-01-15 10:30:25,123] INFO [Partition orders-0 broker=1] Shrinking ISR from 1,2,3 to 1,2. Leader: (highWatermark: 100, endOffset: 120). (kafka.cluster.Partition)
+/// [2024-01-15 10:30:26,001] INFO [GroupCoordinator 1]: Preparing to rebalance group billing in state PreparingRebalance with old generation 5 (__consumer_offsets-12) (kafka.coordinator.group.GroupCoordinator)
+/// ```
+///
+/// # 元数据
+/// - `component`: 级别后方括号中的组件（如 `Partition orders-0 broker=1`），`component_type`: 组件类型（`Partition`）
+/// - `logger`: 行尾括号中的类名
+/// - `partition` / `topic`: 行中引用的第一个分区及其主题，`partitions`: 所有引用的分区（逗号分隔）
+/// - `consumer_group`: 消费组（再均衡事件）
+/// - `kafka_event`: 集群健康相关事件（见 `EVENT_PATTERNS`），`isr_from` / `isr_to`: ISR变化前后的副本列表
+///
+/// `summarize_broker_health` 按事件汇总ISR抖动、再均衡和控制器切换，用于broker健康分析。
+
+use crate::plugins::chain::{PluginChain, PluginChainContext, PluginFilter};
+use crate::plugins::{LogLine, ParseRequest};
+use log::info;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+/// 行首：`[2024-01-15 10:30:25,123] INFO `
+static KAFKA_LINE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\[(\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2}),(\d{3})\] (TRACE|DEBUG|INFO|WARN|ERROR|FATAL) (.*)$").unwrap()
+});
+
+/// 级别后的组件：`[Controller id=1] `、`[GroupCoordinator 1]: `
+static COMPONENT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\[([A-Za-z][^\]]*)\]:? ").unwrap()
+});
+
+/// 行尾的类名：`(kafka.cluster.Partition)`
+static LOGGER: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r" \(([a-z][\w$]*(?:\.[\w$]+)+)\)$").unwrap()
+});
+
+/// 分区引用：`Partition orders-0`、`partition=orders-0`、`(__consumer_offsets-12)`、`Set(orders-0, orders-1)`
+static PARTITION: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?:Partition |partition=|partition |\(|Set\(|List\(|, )([A-Za-z0-9_][\w.-]*-\d+)(?:[\s,)\]]|$)").unwrap()
+});
+
+/// 消费组：`group billing`、`group=billing`
+static GROUP: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\bgroup[= ]([\w.-]+)").unwrap()
+});
+
+/// ISR变化：`Shrinking ISR from 1,2,3 to 1,2`
+static ISR_CHANGE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"ISR from ([\d,]*) to ([\d,]*)").unwrap()
+});
+
+/// 健康相关事件：(消息特征, 事件名)，按顺序匹配
+const EVENT_PATTERNS: &[(&str, &str)] = &[
+    ("Shrinking ISR", "isr_shrink"),
+    ("Expanding ISR", "isr_expand"),
+    ("Preparing to rebalance", "rebalance_start"),
+    ("Stabilized group", "rebalance_complete"),
+    ("removing it from the group", "member_failed"),
+    ("has left group", "member_left"),
+    ("elected as the new controller", "controller_change"),
+    ("Controller moved to another broker", "controller_change"),
+    ("offline partition", "partition_offline"),
+    ("started (kafka.server.KafkaServer)", "broker_started"),
+    ("shutting down (kafka.server.KafkaServer)", "broker_shutdown"),
+];
+
+/// 健康汇总中保留的事件数上限
+const MAX_HEALTH_EVENTS: usize = 1000;
+
+/// Kafka服务端日志过滤器
+pub struct KafkaLogFilter;
+
+impl KafkaLogFilter {
+    /// 行是否为Kafka服务端日志行（行首格式和行尾的Kafka类名）
+    pub fn matches_line(line: &str) -> bool {
+        KAFKA_LINE.is_match(line) && (line.contains("(kafka.") || line.contains("(org.apache.kafka."))
+    }
+
+    /// 内容是否像Kafka服务端日志
+    pub fn looks_like_kafka_log(content: &str) -> bool {
+        content.lines().take(200).any(Self::matches_line)
+    }
+
+    /// 识别消息中的健康事件
+    fn classify_event(message: &str) -> Option<&'static str> {
+        let lower = message.to_lowercase();
+        EVENT_PATTERNS.iter()
+            .find(|(pattern, _)| lower.contains(&pattern.to_lowercase()))
+            .map(|(_, event)| *event)
+    }
+
+    /// 处理单行，返回是否识别为Kafka日志行
+    fn tag_line(line: &mut LogLine, strict: bool) -> bool {
+        let content = line.message_text().to_string();
+        let Some(caps) = KAFKA_LINE.captures(&content) else {
+            return false;
+        };
+        line.timestamp = Some(format!("{}.{}", &caps[1], &caps[2]));
+        line.level = Some(caps[3].to_string());
+
+        let mut message = &caps[4];
+        if let Some(component) = COMPONENT.captures(message) {
+            let name = component[1].to_string();
+            let kind = name.split(' ').next().unwrap_or(&name).to_string();
+            line.metadata.insert("component_type".to_string(), kind);
+            line.metadata.insert("component".to_string(), name);
+            message = &message[component.get(0).unwrap().end()..];
+        }
+        if let Some(logger) = LOGGER.captures(message) {
+            line.metadata.insert("logger".to_string(), logger[1].to_string());
+        }
+
+        let component = line.metadata.get("component").map(String::as_str).unwrap_or("");
+        let references = format!("{} {}", component, message);
+        let mut partitions: Vec<&str> = Vec::new();
+        for reference in PARTITION.captures_iter(&references) {
+            let partition = reference.get(1).unwrap().as_str();
+            if !partitions.contains(&partition) {
+                partitions.push(partition);
+            }
+        }
+        if let Some(first) = partitions.first() {
+            let topic = first.rsplit_once('-').map_or(*first, |(topic, _)| topic);
+            line.metadata.insert("topic".to_string(), topic.to_string());
+            line.metadata.insert("partition".to_string(), first.to_string());
+            line.metadata.insert("partitions".to_string(), partitions.join(","));
+        }
+
+        if let Some(event) = Self::classify_event(message) {
+            line.metadata.insert("kafka_event".to_string(), event.to_string());
+            if let Some(isr) = ISR_CHANGE.captures(message) {
+                line.metadata.insert("isr_from".to_string(), isr[1].to_string());
+                line.metadata.insert("isr_to".to_string(), isr[2].to_string());
+            }
+            if event.starts_with("rebalance") || event.starts_with("member") {
+                if let Some(group) = GROUP.captures(message) {
+                    line.metadata.insert("consumer_group".to_string(), group[1].to_string());
+                }
+            }
+        }
+
+        line.set_message(message.to_string(), strict);
+        true
+    }
+}
+
+impl PluginFilter for KafkaLogFilter {
+    fn name(&self) -> &str {
+        "kafka"
+    }
+
+    fn description(&self) -> &str {
+        "Kafka服务端日志过滤器，提取组件、分区引用，并标记ISR变化和再均衡等事件"
+    }
+
+    fn priority(&self) -> i32 {
+        20 // 格式解析，在ANSI清理之后、Java堆栈识别之前执行
+    }
+
+    fn should_process(&self, context: &PluginChainContext) -> bool {
+        if context.current_lines.is_empty() {
+            return Self::looks_like_kafka_log(&context.original_content);
+        }
+        context.current_lines.iter().take(200).any(|line| KAFKA_LINE.is_match(line.message_text()))
+    }
+
+    fn process(&self, context: &mut PluginChainContext, request: &ParseRequest) -> Result<(), String> {
+        info!("📨 Kafka日志过滤器开始处理");
+        let strict = request.is_strict();
+
+        let mut lines = if context.current_lines.is_empty() {
+            // 第一次处理，从原始内容创建行列表，过滤空行
+            context.original_content.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()).map(|(i, line)| {
+                LogLine {
+                    line_number: i + 1,
+                    content: line.to_string(),
+                    level: None,
+                    timestamp: None,
+                    formatted_content: None,
+                    message: None,
+                    metadata: HashMap::new(),
+                    processed_by: vec![],
+                }
+            }).collect()
+        } else {
+            std::mem::take(&mut context.current_lines)
+        };
+
+        let mut tagged = 0;
+        let mut events = 0;
+        for line in &mut lines {
+            if Self::tag_line(line, strict) {
+                line.processed_by.push("kafka_filter".to_string());
+                line.formatted_content = Some(line.message_text().to_string());
+                tagged += 1;
+                if line.metadata.contains_key("kafka_event") {
+                    events += 1;
+                }
+            }
+        }
+
+        context.current_lines = lines;
+        context.set_chain_metadata("kafka_events".to_string(), events.to_string());
+
+        info!("📨 Kafka日志过滤器处理完成，识别了 {} 行，其中健康事件 {} 个", tagged, events);
+        Ok(())
+    }
+
+    fn can_handle(&self, content: &str, _file_path: Option<&str>) -> bool {
+        Self::looks_like_kafka_log(content)
+    }
+}
+
+/// 一个健康事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KafkaHealthEvent {
+    /// 行号
+    pub line_number: usize,
+    /// 时间戳
+    pub timestamp: Option<String>,
+    /// 事件名（`kafka_event` 元数据）
+    pub event: String,
+    /// 涉及的分区
+    pub partition: Option<String>,
+    /// 涉及的消费组
+    pub consumer_group: Option<String>,
+    /// 消息
+    pub message: String,
+}
+
+/// broker健康汇总
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct KafkaHealthSummary {
+    /// 各事件的次数
+    pub event_counts: BTreeMap<String, usize>,
+    /// 各分区的ISR变化次数（收缩和扩张合计，按次数降序）
+    pub isr_changes_by_partition: Vec<(String, usize)>,
+    /// 各消费组的再均衡次数（按次数降序）
+    pub rebalances_by_group: Vec<(String, usize)>,
+    /// WARN级别的行数
+    pub warnings: usize,
+    /// ERROR/FATAL级别的行数
+    pub errors: usize,
+    /// 按出现顺序的事件（最多 `MAX_HEALTH_EVENTS` 个）
+    pub events: Vec<KafkaHealthEvent>,
+}
+
+/// 按次数降序、名称升序排列
+fn sorted_counts(counts: HashMap<String, usize>) -> Vec<(String, usize)> {
+    let mut counts: Vec<(String, usize)> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts
+}
+
+/// 根据Kafka过滤器标记的元数据汇总broker健康状况
+///
+/// # 参数
+/// - `lines`: 经过 `KafkaLogFilter` 处理的日志行
+///
+/// # Returns
+/// - `KafkaHealthSummary`: 健康汇总
+pub fn summarize_broker_health(lines: &[LogLine]) -> KafkaHealthSummary {
+    let mut summary = KafkaHealthSummary::default();
+    let mut isr_changes: HashMap<String, usize> = HashMap::new();
+    let mut rebalances: HashMap<String, usize> = HashMap::new();
+
+    for line in lines {
+        match line.level.as_deref() {
+            Some("WARN") => summary.warnings += 1,
+            Some("ERROR" | "FATAL") => summary.errors += 1,
+            _ => {}
+        }
+        let Some(event) = line.metadata.get("kafka_event") else {
+            continue;
+        };
+        *summary.event_counts.entry(event.clone()).or_insert(0) += 1;
+
+        let partition = line.metadata.get("partition").cloned();
+        let group = line.metadata.get("consumer_group").cloned();
+        match (event.as_str(), &partition, &group) {
+            ("isr_shrink" | "isr_expand", Some(partition), _) => *isr_changes.entry(partition.clone()).or_insert(0) += 1,
+            ("rebalance_start", _, Some(group)) => *rebalances.entry(group.clone()).or_insert(0) += 1,
+            _ => {}
+        }
+
+        if summary.events.len() < MAX_HEALTH_EVENTS {
+            summary.events.push(KafkaHealthEvent {
+                line_number: line.line_number,
+                timestamp: line.timestamp.clone(),
+                event: event.clone(),
+                partition,
+                consumer_group: group,
+                message: line.message_text().to_string(),
+            });
+        }
+    }
+
+    summary.isr_changes_by_partition = sorted_counts(isr_changes);
+    summary.rebalances_by_group = sorted_counts(rebalances);
+    summary
+}
+
+/// 解析Kafka服务端日志并汇总broker健康状况
+///
+/// 不经过自动链选择，直接使用Kafka过滤器处理内容。
+///
+/// # 参数
+/// - `content`: Kafka服务端日志内容
+///
+/// # Returns
+/// - `Ok(KafkaHealthSummary)`: 健康汇总
+/// - `Err(String)`: 处理失败时的错误信息
+pub fn analyze_kafka_output(content: &str) -> Result<KafkaHealthSummary, String> {
+    let mut chain = PluginChain::new("kafka".to_string(), "Kafka健康分析".to_string());
+    chain.add_filter(Arc::new(KafkaLogFilter));
+
+    let result = chain.process(content, &ParseRequest::default())?;
+    Ok(summarize_broker_health(&result.lines))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTENT: &str = "\
+[2024-01-15 10:30:25,123] INFO [Partition orders-0 broker=1] Shrinking ISR from 1,2,3 to 1,2. Leader: (highWatermark: 100, endOffset: 120). (kafka.cluster.Partition)
+[2024-01-15 10:30:26,001] INFO [GroupCoordinator 1]: Preparing to rebalance group billing in state PreparingRebalance with old generation 5 (__consumer_offsets-12) (reason: Adding new member) (kafka.coordinator.group.GroupCoordinator)
+[2024-01-15 10:30:27,500] INFO [GroupCoordinator 1]: Stabilized group billing generation 6 (__consumer_offsets-12) with 3 members (kafka.coordinator.group.GroupCoordinator)
+[2024-01-15 10:30:29,000] INFO [Partition orders-0 broker=1] Expanding ISR from 1,2 to 1,2,3 (kafka.cluster.Partition)
+[2024-01-15 10:30:30,000] WARN [ReplicaFetcher replicaId=1, leaderId=2, fetcherId=0] Error in response for fetch request (kafka.server.ReplicaFetcherThread)";
+
+    #[test]
+    fn test_tags_components_partitions_and_events() {
+        let mut chain = PluginChain::new("test".to_string(), "test".to_string());
+        chain.add_filter(Arc::new(KafkaLogFilter));
+        let lines = chain.process(CONTENT, &ParseRequest::default()).unwrap().lines;
+        let meta = |index: usize, key: &str| lines[index].metadata.get(key).map(String::as_str);
+
+        assert_eq!(lines[0].timestamp.as_deref(), Some("2024-01-15 10:30:25.123"));
+        assert_eq!(meta(0, "component"), Some("Partition orders-0 broker=1"));
+        assert_eq!(meta(0, "component_type"), Some("Partition"));
+        assert_eq!((meta(0, "topic"), meta(0, "partition")), (Some("orders"), Some("orders-0")));
+        assert_eq!(meta(0, "kafka_event"), Some("isr_shrink"));
+        assert_eq!((meta(0, "isr_from"), meta(0, "isr_to")), (Some("1,2,3"), Some("1,2")));
+        assert_eq!(meta(0, "logger"), Some("kafka.cluster.Partition"));
+        assert_eq!(meta(1, "consumer_group"), Some("billing"));
+        assert_eq!(meta(1, "partition"), Some("__consumer_offsets-12"));
+        assert_eq!(meta(4, "component_type"), Some("ReplicaFetcher"));
+        assert_eq!(meta(4, "kafka_event"), None);
+        assert!(lines[0].content.starts_with("Shrinking ISR"));
+    }
+
+    #[test]
+    fn test_summarize_broker_health() {
+        let summary = analyze_kafka_output(CONTENT).unwrap();
+
+        assert_eq!(summary.event_counts.get("isr_shrink"), Some(&1));
+        assert_eq!(summary.event_counts.get("rebalance_complete"), Some(&1));
+        assert_eq!(summary.isr_changes_by_partition, vec![("orders-0".to_string(), 2)]);
+        assert_eq!(summary.rebalances_by_group, vec![("billing".to_string(), 1)]);
+        assert_eq!((summary.warnings, summary.errors), (1, 0));
+        assert_eq!(summary.events.len(), 4);
+    }
+}
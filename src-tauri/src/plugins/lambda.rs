@@ -0,0 +1,410 @@
+/// AWS Lambda / CloudWatch Logs 解析
+///
+/// 识别Lambda运行时写入CloudWatch的日志约定：
+/// - 每次调用以 `START RequestId: <id> Version: $LATEST` 开始，`END RequestId: <id>` 结束，
+///   随后的 `REPORT RequestId: <id>\tDuration: 102.25 ms\tBilled Duration: 103 ms\t...` 给出耗时和内存
+/// - Node.js运行时的应用日志：`<时间戳>\t<RequestId>\t<级别>\t<消息>`
+/// - Python运行时的应用日志：`[<级别>]\t<时间戳>\t<RequestId>\t<消息>`
+/// - 超时：`<时间戳> <RequestId> Task timed out after 3.00 seconds`
+///
+/// CloudWatch导出文件（`aws logs` 导出到S3或控制台下载）的每行带有 `<时间戳> ` 前缀，解析前去掉。
+///
+/// # 元数据
+/// - `request_id`: 所属调用的RequestId（START..REPORT之间没有RequestId的行沿用当前调用）
+/// - `group_id` / `group_name` / `group_marker`: 按调用分组（与CI日志分组相同，前端据此折叠）
+/// - `lambda_event`: start / end / report / timeout
+/// - REPORT行：`lambda_duration_ms`、`billed_duration_ms`、`memory_size_mb`、`max_memory_used_mb`、
+///   `init_duration_ms`（仅冷启动），以及供慢操作分析使用的 `duration_ms`
+
+use crate::plugins::chain::{PluginChain, PluginChainContext, PluginFilter};
+use crate::plugins::metrics::DURATION_METADATA_KEY;
+use crate::plugins::{LogLine, ParseRequest};
+use log::info;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// CloudWatch导出文件的行首时间戳：`2024-01-15T10:30:25.123Z `
+static EXPORT_PREFIX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2})(?:\.\d+)?Z ").unwrap()
+});
+
+/// 调用开始/结束：`START RequestId: <id> Version: $LATEST`、`END RequestId: <id>`
+static START_END: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(START|END) RequestId: ([\w-]+)(?:\s+Version: (\S+))?").unwrap()
+});
+
+/// 调用报告：`REPORT RequestId: <id>\tDuration: ...`
+static REPORT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^REPORT RequestId: ([\w-]+)").unwrap()
+});
+
+/// 报告中的字段：`Billed Duration: 103 ms`、`Max Memory Used: 70 MB`
+static REPORT_FIELD: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(Init Duration|Billed Duration|Duration|Memory Size|Max Memory Used): ([\d.]+) (?:ms|MB)").unwrap()
+});
+
+/// Node.js运行时日志：`2024-01-15T10:30:25.123Z\t<RequestId>\tINFO\t消息`
+static NODE_LINE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2})(?:\.\d+)?Z\t([\w-]+)\t([A-Z]+)\t(.*)$").unwrap()
+});
+
+/// Python运行时日志：`[INFO]\t2024-01-15T10:30:25.123Z\t<RequestId>\t消息`
+static PYTHON_LINE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\[([A-Z]+)\]\t(\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2})(?:\.\d+)?Z\t([\w-]+)\t(.*)$").unwrap()
+});
+
+/// 调用超时：`2024-01-15T10:30:28.123Z <RequestId> Task timed out after 3.00 seconds`
+static TIMEOUT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(?:(\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2})(?:\.\d+)?Z\s+)?([\w-]+)?\s*Task timed out after ([\d.]+) seconds").unwrap()
+});
+
+/// REPORT字段对应的元数据键
+fn report_metadata_key(field: &str) -> &'static str {
+    match field {
+        "Init Duration" => "init_duration_ms",
+        "Billed Duration" => "billed_duration_ms",
+        "Memory Size" => "memory_size_mb",
+        "Max Memory Used" => "max_memory_used_mb",
+        _ => "lambda_duration_ms",
+    }
+}
+
+/// Lambda日志过滤器
+pub struct LambdaLogFilter;
+
+impl LambdaLogFilter {
+    /// 去掉CloudWatch导出文件的时间戳前缀（运行时日志本身以时间戳开头，不去掉）
+    fn strip_export_prefix(text: &str) -> (&str, Option<String>) {
+        if NODE_LINE.is_match(text) {
+            return (text, None);
+        }
+        match EXPORT_PREFIX.captures(text) {
+            Some(caps) => (&text[caps.get(0).unwrap().end()..], Some(caps[1].to_string())),
+            None => (text, None),
+        }
+    }
+
+    /// 行是否为 `START` / `END` / `REPORT` 调用标记
+    pub fn matches_line(line: &str) -> bool {
+        let text = Self::strip_export_prefix(line).0;
+        START_END.is_match(text) || REPORT.is_match(text)
+    }
+
+    /// 内容是否像Lambda日志
+    pub fn looks_like_lambda_log(content: &str) -> bool {
+        content.lines().take(200).any(Self::matches_line)
+    }
+
+    /// 处理单行，返回是否识别为Lambda日志行
+    fn tag_line(line: &mut LogLine, current: &mut Option<String>, strict: bool) -> bool {
+        let (text, exported_at) = Self::strip_export_prefix(line.message_text());
+        let mut text = text.to_string();
+        if let Some(timestamp) = exported_at {
+            line.timestamp = Some(timestamp);
+        }
+
+        let mut request_id = None;
+        let mut marker = "";
+        if let Some(caps) = START_END.captures(&text) {
+            request_id = Some(caps[2].to_string());
+            line.level = Some("INFO".to_string());
+            if &caps[1] == "START" {
+                *current = request_id.clone();
+                marker = "start";
+                line.metadata.insert("lambda_event".to_string(), "start".to_string());
+                if let Some(version) = caps.get(3) {
+                    line.metadata.insert("lambda_version".to_string(), version.as_str().to_string());
+                }
+            } else {
+                line.metadata.insert("lambda_event".to_string(), "end".to_string());
+            }
+        } else if let Some(caps) = REPORT.captures(&text) {
+            request_id = Some(caps[1].to_string());
+            marker = "end";
+            line.level = Some("INFO".to_string());
+            line.metadata.insert("lambda_event".to_string(), "report".to_string());
+            for field in REPORT_FIELD.captures_iter(&text) {
+                let key = report_metadata_key(&field[1]);
+                line.metadata.insert(key.to_string(), field[2].to_string());
+                if key == "lambda_duration_ms" {
+                    line.metadata.insert(DURATION_METADATA_KEY.to_string(), field[2].to_string());
+                }
+            }
+            text = text.replace('\t', "  ").trim_end().to_string();
+        } else if let Some(caps) = NODE_LINE.captures(&text) {
+            line.timestamp = Some(caps[1].to_string());
+            request_id = Some(caps[2].to_string()).filter(|id| id != "undefined");
+            line.level = Some(caps[3].to_string());
+            text = caps[4].to_string();
+        } else if let Some(caps) = PYTHON_LINE.captures(&text) {
+            line.level = Some(caps[1].to_string());
+            line.timestamp = Some(caps[2].to_string());
+            request_id = Some(caps[3].to_string());
+            text = caps[4].to_string();
+        } else if let Some(caps) = TIMEOUT.captures(&text) {
+            if let Some(timestamp) = caps.get(1) {
+                line.timestamp = Some(timestamp.as_str().to_string());
+            }
+            request_id = caps.get(2).map(|id| id.as_str().to_string());
+            line.level = Some("ERROR".to_string());
+            line.metadata.insert("lambda_event".to_string(), "timeout".to_string());
+            text = format!("Task timed out after {} seconds", &caps[3]);
+        }
+
+        let Some(request_id) = request_id.or_else(|| current.clone()) else {
+            return false;
+        };
+        line.metadata.insert("request_id".to_string(), request_id.clone());
+        line.metadata.insert("group_name".to_string(), format!("Invocation {}", request_id));
+        line.metadata.insert("group_id".to_string(), request_id);
+        if !marker.is_empty() {
+            line.metadata.insert("group_marker".to_string(), marker.to_string());
+        }
+        if marker == "end" {
+            *current = None;
+        }
+        line.set_message(text, strict);
+        true
+    }
+}
+
+impl PluginFilter for LambdaLogFilter {
+    fn name(&self) -> &str {
+        "lambda"
+    }
+
+    fn description(&self) -> &str {
+        "AWS Lambda日志过滤器，按START..REPORT分组调用并提取耗时和内存"
+    }
+
+    fn priority(&self) -> i32 {
+        20 // 格式解析，在ANSI清理之后执行
+    }
+
+    fn should_process(&self, context: &PluginChainContext) -> bool {
+        if context.current_lines.is_empty() {
+            return Self::looks_like_lambda_log(&context.original_content);
+        }
+        context.current_lines.iter().take(200).any(|line| Self::looks_like_lambda_log(line.message_text()))
+    }
+
+    fn process(&self, context: &mut PluginChainContext, request: &ParseRequest) -> Result<(), String> {
+        info!("λ Lambda日志过滤器开始处理");
+        let strict = request.is_strict();
+
+        let mut lines = if context.current_lines.is_empty() {
+            // 第一次处理，从原始内容创建行列表，过滤空行
+            context.original_content.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()).map(|(i, line)| {
+                LogLine {
+                    line_number: i + 1,
+                    content: line.to_string(),
+                    level: None,
+                    timestamp: None,
+                    formatted_content: None,
+                    message: None,
+                    metadata: HashMap::new(),
+                    processed_by: vec![],
+                }
+            }).collect()
+        } else {
+            std::mem::take(&mut context.current_lines)
+        };
+
+        let mut current = None;
+        let mut tagged = 0;
+        for line in &mut lines {
+            if Self::tag_line(line, &mut current, strict) {
+                line.processed_by.push("lambda_filter".to_string());
+                line.formatted_content = Some(line.message_text().to_string());
+                tagged += 1;
+            }
+        }
+
+        context.current_lines = lines;
+        info!("λ Lambda日志过滤器处理完成，标记了 {} 行", tagged);
+        Ok(())
+    }
+
+    fn can_handle(&self, content: &str, _file_path: Option<&str>) -> bool {
+        Self::looks_like_lambda_log(content)
+    }
+}
+
+/// 一次Lambda调用
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LambdaInvocation {
+    /// RequestId
+    pub request_id: String,
+    /// 函数版本（START行中的Version）
+    pub version: Option<String>,
+    /// 调用的第一行行号
+    pub start_line: usize,
+    /// REPORT行行号（日志被截断时为None）
+    pub report_line: Option<usize>,
+    /// 执行耗时（毫秒）
+    pub duration_ms: Option<f64>,
+    /// 计费耗时（毫秒）
+    pub billed_duration_ms: Option<f64>,
+    /// 初始化耗时（毫秒，仅冷启动）
+    pub init_duration_ms: Option<f64>,
+    /// 配置的内存（MB）
+    pub memory_size_mb: Option<u64>,
+    /// 最大内存使用（MB）
+    pub max_memory_used_mb: Option<u64>,
+    /// 是否冷启动（REPORT中有Init Duration）
+    pub cold_start: bool,
+    /// 是否超时
+    pub timed_out: bool,
+    /// 调用中ERROR/FATAL级别的行数
+    pub error_lines: usize,
+}
+
+/// Lambda调用汇总
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LambdaSummary {
+    /// 调用次数
+    pub total: usize,
+    /// 冷启动次数
+    pub cold_starts: usize,
+    /// 超时次数
+    pub timeouts: usize,
+    /// 失败的调用数（超时或包含错误行）
+    pub failed: usize,
+    /// 平均执行耗时（毫秒）
+    pub avg_duration_ms: Option<f64>,
+    /// 最大执行耗时（毫秒）
+    pub max_duration_ms: Option<f64>,
+    /// 总计费耗时（毫秒）
+    pub total_billed_ms: f64,
+    /// 所有调用中的最大内存使用（MB）
+    pub peak_memory_used_mb: Option<u64>,
+    /// 各次调用（按开始行排序）
+    pub invocations: Vec<LambdaInvocation>,
+}
+
+/// 根据Lambda过滤器标记的元数据汇总调用
+///
+/// # 参数
+/// - `lines`: 经过 `LambdaLogFilter` 处理的日志行
+///
+/// # Returns
+/// - `LambdaSummary`: 调用汇总
+pub fn summarize_invocations(lines: &[LogLine]) -> LambdaSummary {
+    let mut invocations: Vec<LambdaInvocation> = Vec::new();
+    let mut index_by_id: HashMap<&str, usize> = HashMap::new();
+
+    for line in lines {
+        let Some(request_id) = line.metadata.get("request_id") else {
+            continue;
+        };
+        let index = *index_by_id.entry(request_id.as_str()).or_insert_with(|| {
+            invocations.push(LambdaInvocation {
+                request_id: request_id.clone(),
+                start_line: line.line_number,
+                ..Default::default()
+            });
+            invocations.len() - 1
+        });
+        let invocation = &mut invocations[index];
+        let number = |key: &str| line.metadata.get(key).and_then(|v| v.parse::<f64>().ok());
+
+        match line.metadata.get("lambda_event").map(String::as_str) {
+            Some("start") => invocation.version = line.metadata.get("lambda_version").cloned(),
+            Some("timeout") => invocation.timed_out = true,
+            Some("report") => {
+                invocation.report_line = Some(line.line_number);
+                invocation.duration_ms = number("lambda_duration_ms");
+                invocation.billed_duration_ms = number("billed_duration_ms");
+                invocation.init_duration_ms = number("init_duration_ms");
+                invocation.memory_size_mb = number("memory_size_mb").map(|mb| mb as u64);
+                invocation.max_memory_used_mb = number("max_memory_used_mb").map(|mb| mb as u64);
+                invocation.cold_start = invocation.init_duration_ms.is_some();
+            }
+            _ => {}
+        }
+        if matches!(line.level.as_deref(), Some("ERROR" | "FATAL")) {
+            invocation.error_lines += 1;
+        }
+    }
+
+    let durations: Vec<f64> = invocations.iter().filter_map(|i| i.duration_ms).collect();
+    LambdaSummary {
+        total: invocations.len(),
+        cold_starts: invocations.iter().filter(|i| i.cold_start).count(),
+        timeouts: invocations.iter().filter(|i| i.timed_out).count(),
+        failed: invocations.iter().filter(|i| i.timed_out || i.error_lines > 0).count(),
+        avg_duration_ms: (!durations.is_empty()).then(|| durations.iter().sum::<f64>() / durations.len() as f64),
+        max_duration_ms: durations.iter().copied().reduce(f64::max),
+        total_billed_ms: invocations.iter().filter_map(|i| i.billed_duration_ms).sum(),
+        peak_memory_used_mb: invocations.iter().filter_map(|i| i.max_memory_used_mb).max(),
+        invocations,
+    }
+}
+
+/// 解析Lambda日志并汇总调用
+///
+/// 不经过自动链选择，直接使用Lambda过滤器处理内容。
+///
+/// # 参数
+/// - `content`: Lambda日志或CloudWatch导出内容
+///
+/// # Returns
+/// - `Ok(LambdaSummary)`: 调用汇总
+/// - `Err(String)`: 处理失败时的错误信息
+pub fn analyze_lambda_output(content: &str) -> Result<LambdaSummary, String> {
+    let mut chain = PluginChain::new("lambda".to_string(), "Lambda调用分析".to_string());
+    chain.add_filter(Arc::new(LambdaLogFilter));
+
+    let result = chain.process(content, &ParseRequest::default())?;
+    Ok(summarize_invocations(&result.lines))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTENT: &str = "\
+2024-01-15T10:30:25.000Z START RequestId: 3f1a2b3c-0000-4000-8000-000000000001 Version: $LATEST
+2024-01-15T10:30:25.050Z 2024-01-15T10:30:25.050Z\t3f1a2b3c-0000-4000-8000-000000000001\tINFO\tProcessing order 42
+2024-01-15T10:30:25.100Z END RequestId: 3f1a2b3c-0000-4000-8000-000000000001
+2024-01-15T10:30:25.100Z REPORT RequestId: 3f1a2b3c-0000-4000-8000-000000000001\tDuration: 102.25 ms\tBilled Duration: 103 ms\tMemory Size: 128 MB\tMax Memory Used: 70 MB\tInit Duration: 150.12 ms\t
+2024-01-15T10:30:26.000Z START RequestId: 3f1a2b3c-0000-4000-8000-000000000002 Version: $LATEST
+2024-01-15T10:30:29.000Z 2024-01-15T10:30:29.000Z 3f1a2b3c-0000-4000-8000-000000000002 Task timed out after 3.00 seconds
+2024-01-15T10:30:29.000Z END RequestId: 3f1a2b3c-0000-4000-8000-000000000002
+2024-01-15T10:30:29.000Z REPORT RequestId: 3f1a2b3c-0000-4000-8000-000000000002\tDuration: 3000.00 ms\tBilled Duration: 3000 ms\tMemory Size: 128 MB\tMax Memory Used: 90 MB\t";
+
+    #[test]
+    fn test_groups_invocations_and_reads_report() {
+        let mut chain = PluginChain::new("test".to_string(), "test".to_string());
+        chain.add_filter(Arc::new(LambdaLogFilter));
+        let lines = chain.process(CONTENT, &ParseRequest::default()).unwrap().lines;
+
+        let meta = |index: usize, key: &str| lines[index].metadata.get(key).cloned();
+        assert_eq!(meta(0, "group_marker").as_deref(), Some("start"));
+        assert_eq!(lines[1].content, "Processing order 42");
+        assert_eq!(lines[1].level.as_deref(), Some("INFO"));
+        assert_eq!(meta(1, "group_id"), meta(0, "request_id"));
+        assert_eq!(meta(3, "group_marker").as_deref(), Some("end"));
+        assert_eq!(meta(3, "billed_duration_ms").as_deref(), Some("103"));
+        assert_eq!(meta(3, "duration_ms").as_deref(), Some("102.25"));
+        assert_eq!(meta(3, "init_duration_ms").as_deref(), Some("150.12"));
+        assert_eq!(lines[5].level.as_deref(), Some("ERROR"));
+        assert_eq!(meta(5, "request_id").as_deref(), Some("3f1a2b3c-0000-4000-8000-000000000002"));
+    }
+
+    #[test]
+    fn test_summarize_invocations() {
+        let summary = analyze_lambda_output(CONTENT).unwrap();
+
+        assert_eq!((summary.total, summary.cold_starts, summary.timeouts, summary.failed), (2, 1, 1, 1));
+        assert_eq!(summary.max_duration_ms, Some(3000.0));
+        assert_eq!(summary.total_billed_ms, 3103.0);
+        assert_eq!(summary.peak_memory_used_mb, Some(90));
+        assert_eq!(summary.invocations[0].version.as_deref(), Some("$LATEST"));
+        assert_eq!(summary.invocations[1].report_line, Some(8));
+        assert!(LambdaLogFilter.can_handle(CONTENT, None));
+    }
+}
@@ -20,7 +20,7 @@
 /// - 每个插件都有唯一的名称标识符
 /// - 支持插件的热替换（未来功能）
 
-use crate::plugins::{LogParser, PluginInfo, ParseRequest, ParseResult};
+use crate::plugins::{detection, LogParser, PluginInfo, PluginKind, ParseRequest, ParseResult, PLUGIN_VERSION};
 use std::collections::HashMap;
 use std::sync::Arc;
 use log::{debug, error};
@@ -92,11 +92,32 @@ impl PluginManager {
                 name: parser.name().to_string(),
                 description: parser.description().to_string(),
                 supported_extensions: parser.supported_extensions(),
-                auto_detectable: true, // 当前所有插件都支持自动检测
+                auto_detectable: detection::is_detectable(parser.name()),
+                version: PLUGIN_VERSION.to_string(),
+                kind: PluginKind::Parser,
+                enabled: true,
             }
         }).collect()
     }
 
+    /// 按名称获取已注册的解析器
+    ///
+    /// # 参数
+    /// - `plugin_name`: 插件名称
+    ///
+    /// # Returns
+    /// - `Option<Arc<dyn LogParser + Send + Sync>>`: 找到时返回解析器实例
+    pub fn get_parser(&self, plugin_name: &str) -> Option<Arc<dyn LogParser + Send + Sync>> {
+        self.parsers.get(plugin_name).cloned()
+    }
+
+    /// 获取所有已注册插件的名称（按字母排序）
+    pub fn plugin_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.parsers.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
     /// 使用指定插件解析日志内容
     ///
     /// 根据插件名称查找对应的解析器并执行解析操作。
@@ -132,8 +153,8 @@ impl PluginManager {
     /// 这是推荐的使用方式，能够处理大多数常见的日志格式。
     ///
     /// # 检测策略
-    /// 1. 首先尝试专用解析器（mybatis, docker_json, springboot）
-    /// 2. 如果没有匹配，使用auto解析器进行通用检测
+    /// 1. 专用解析器（mybatis, docker_json, springboot）按置信度评分，最高分胜出
+    /// 2. 如果都没有达到阈值，使用auto解析器进行通用检测
     /// 3. 最后使用raw解析器作为回退选项
     ///
     /// # 参数
@@ -147,7 +168,7 @@ impl PluginManager {
     /// 专用解析器 > auto解析器 > raw解析器
     ///
     /// # 性能考虑
-    /// - 快速检测：使用can_parse()进行预检
+    /// - 采样评分：只对内容开头的样本行计算置信度
     /// - 早期退出：找到合适的解析器立即返回
     /// - 回退机制：确保总能解析某种格式的内容
     pub fn auto_detect_and_parse(&self, request: &ParseRequest) -> Result<ParseResult, String> {
@@ -156,17 +177,26 @@ impl PluginManager {
 
         debug!("🔍 开始自动检测日志格式，内容长度: {}", content.len());
 
-        // 第一步：尝试专用解析器
-        // 这些解析器针对特定格式进行了优化，准确性更高
-        for (name, parser) in &self.parsers {
-            // 跳过通用解析器，优先使用专用解析器
-            if name != "auto" && name != "raw" && parser.can_parse(content, file_path) {
-                debug!("✅ 检测到专用格式: {}", name);
-                let mut result = parser.parse(content, request)?;
-                result.detected_format = Some(name.clone());
-                return Ok(result);
+        // 第一步：专用解析器按置信度评分，得分最高且达到阈值的胜出
+        // 按名称排序后评分，得分相同时结果稳定（不依赖HashMap的遍历顺序）
+        let mut names: Vec<&String> = self.parsers.keys()
+            .filter(|name| name.as_str() != "auto" && name.as_str() != "raw")
+            .collect();
+        names.sort();
+        let mut best: Option<(&String, f32)> = None;
+        for name in names {
+            let confidence = self.parsers[name].confidence(content, file_path);
+            debug!("📊 格式 '{}' 置信度: {:.2}", name, confidence);
+            if best.is_none_or(|(_, best_confidence)| confidence > best_confidence) {
+                best = Some((name, confidence));
             }
         }
+        if let Some((name, confidence)) = best.filter(|(_, confidence)| *confidence >= detection::MIN_CONFIDENCE) {
+            debug!("✅ 检测到专用格式: {}（置信度 {:.2}）", name, confidence);
+            let mut result = self.parsers[name].parse(content, request)?;
+            result.detected_format = Some(name.clone());
+            return Ok(result);
+        }
 
         // 第二步：使用auto解析器
         // 这是一个万能解析器，可以处理大多数标准格式
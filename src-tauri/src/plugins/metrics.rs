@@ -0,0 +1,665 @@
+/// 数值指标提取
+///
+/// 识别日志行中的数值度量（如 `took=123ms`、`size=4567`、`latency: 45ms`、`"cost": 12.5`），
+/// 写入元数据 `metric_<名称>`（值保留原始单位，如 `123ms`），
+/// 再由 `collect_metric_series` 按名称汇总为带时间戳的序列，供界面绘制图表。
+///
+/// # 识别规则
+/// - 形如 `名称=数值[单位]` 或 `名称: 数值[单位]`，名称可包含 `.`、`-`、`_`
+/// - 单位限于常见的时间、大小和百分比单位，其他后缀（如 `id=12ab`）不视为度量
+/// - 数值后必须是行尾、空白或分隔符，版本号、UUID、`host:8080/path` 等不会被误识别
+///
+/// # 耗时
+/// 名称表示耗时的度量（`took`、`elapsed`、`latency`、`*_time` 等）以及
+/// `took 123ms`、`completed in 2.5 seconds` 之类的短语会统一换算为毫秒，
+/// 写入元数据 `duration_ms`，`find_slow_operations` 据此找出慢操作。
+///
+/// # 离群点
+/// `detect_metric_outliers` 按z-score或MAD（中位数绝对偏差）在每个序列中找出离群的数据点，
+/// 让埋在日志里的延迟尖峰自动浮现。
+
+use crate::plugins::chain::{PluginChainContext, PluginFilter};
+use crate::plugins::settings::PluginOptions;
+use crate::plugins::{LogLine, ParseRequest};
+use log::info;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// 度量元数据键前缀
+pub const METRIC_METADATA_PREFIX: &str = "metric_";
+
+/// 耗时元数据键（毫秒）
+pub const DURATION_METADATA_KEY: &str = "duration_ms";
+
+/// 操作名称元数据键
+pub const OPERATION_METADATA_KEY: &str = "operation";
+
+/// 单行最多提取的度量数量（避免键值对密集的行产生大量元数据）
+const MAX_METRICS_PER_LINE: usize = 16;
+
+/// `名称=数值[单位]` / `名称: 数值[单位]`
+static MEASUREMENT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"([A-Za-z_][\w.-]*)"?\s*[=:]\s*"?(-?\d+(?:\.\d+)?)"#).unwrap()
+});
+
+/// 耗时短语：`took 123ms`、`elapsed: 2.5s`、`in 45 milliseconds`
+static DURATION_PHRASE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b(?:took|elapsed|in|after|spent)\s*:?\s*(\d+(?:\.\d+)?)\s?(ns|µs|us|ms|milliseconds?|s|secs?|seconds?|mins?|minutes?|h|hours?)\b").unwrap()
+});
+
+/// 操作名称：`operation=createOrder`、`endpoint=/api/users`
+static OPERATION: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"\b(?:operation|op|endpoint|api|task|job)\s*[=:]\s*"?([^\s",;]+)"#).unwrap()
+});
+
+/// 可识别的单位
+const KNOWN_UNITS: &[&str] = &[
+    "ns", "us", "µs", "ms", "s", "sec", "secs", "m", "min", "mins", "h",
+    "b", "kb", "mb", "gb", "tb", "kib", "mib", "gib", "bytes",
+    "%", "rps", "qps",
+];
+
+/// 是否为可识别的单位（不区分大小写）
+fn is_known_unit(unit: &str) -> bool {
+    KNOWN_UNITS.contains(&unit.to_lowercase().as_str())
+}
+
+/// 从文本中提取数值度量
+///
+/// # Returns
+/// - `Vec<(String, String)>`: (名称, 带单位的原始值)，按出现顺序，同名只保留第一个
+pub fn extract_measurements(text: &str) -> Vec<(String, String)> {
+    let mut measurements: Vec<(String, String)> = Vec::new();
+
+    for caps in MEASUREMENT.captures_iter(text) {
+        let name = caps.get(1).unwrap();
+        let value = caps.get(2).unwrap();
+
+        // 名称前是 `/`、`.`、`:` 时通常是URL或路径的一部分（如 host:8080）
+        if text[..name.start()].ends_with(['/', '.', ':', '@']) {
+            continue;
+        }
+
+        // 数值后紧跟（或隔一个空格）的单位；`count=5 items` 中的单词不是单位，`id=12ab` 则不是度量
+        let rest = &text[value.end()..];
+        let spaced = rest.strip_prefix(' ');
+        let word_len = |s: &str| s.find(|c: char| !(c.is_ascii_alphabetic() || c == 'µ' || c == '%')).unwrap_or(s.len());
+        let attached = &rest[..word_len(rest)];
+        let unit = if !attached.is_empty() {
+            if !is_known_unit(attached) {
+                continue;
+            }
+            attached
+        } else {
+            spaced.map(|s| &s[..word_len(s)]).filter(|w| is_known_unit(w)).unwrap_or("")
+        };
+
+        // 数值（和单位）之后必须是行尾、空白或分隔符
+        let end = if unit.is_empty() { 0 } else { rest.find(unit).unwrap() + unit.len() };
+        let next = rest[end..].chars().next();
+        if !matches!(next, None | Some(' ' | '\t' | ',' | ';' | ')' | ']' | '}' | '"' | '\'' | '|')) {
+            continue;
+        }
+
+        let name = name.as_str().trim_end_matches(['.', '-']).to_string();
+        if measurements.iter().any(|(existing, _)| *existing == name) {
+            continue;
+        }
+        measurements.push((name, format!("{}{}", value.as_str(), unit)));
+        if measurements.len() == MAX_METRICS_PER_LINE {
+            break;
+        }
+    }
+    measurements
+}
+
+/// 把时间单位的数值换算为毫秒，非时间单位返回None
+pub fn duration_to_ms(value: f64, unit: &str) -> Option<f64> {
+    let factor = match unit.to_lowercase().as_str() {
+        "ns" => 0.000_001,
+        "us" | "µs" => 0.001,
+        "ms" | "millisecond" | "milliseconds" => 1.0,
+        "s" | "sec" | "secs" | "second" | "seconds" => 1_000.0,
+        "m" | "min" | "mins" | "minute" | "minutes" => 60_000.0,
+        "h" | "hour" | "hours" => 3_600_000.0,
+        _ => return None,
+    };
+    Some(value * factor)
+}
+
+/// 度量名称是否表示耗时
+fn is_duration_name(name: &str) -> bool {
+    let name = name.to_lowercase();
+    !name.contains("timeout") &&
+        ["took", "elapsed", "duration", "latency", "cost", "spent", "time"].iter().any(|k| name.contains(k))
+}
+
+/// 提取一行中的耗时（毫秒），有多个耗时时取最大值
+///
+/// # 参数
+/// - `text`: 日志正文
+/// - `measurements`: 已从该行提取的度量（见 `extract_measurements`）
+pub fn extract_duration_ms(text: &str, measurements: &[(String, String)]) -> Option<f64> {
+    let from_metrics = measurements.iter()
+        .filter(|(name, _)| is_duration_name(name))
+        .filter_map(|(name, value)| {
+            let (number, unit) = parse_measurement(value)?;
+            if unit.is_empty() {
+                // 无单位时只接受名称带毫秒后缀的度量（elapsed_ms、costMs）
+                name.to_lowercase().ends_with("ms").then_some(number)
+            } else {
+                duration_to_ms(number, unit)
+            }
+        });
+    let from_phrases = DURATION_PHRASE.captures_iter(text)
+        .filter_map(|caps| duration_to_ms(caps[1].parse().ok()?, &caps[2]));
+
+    from_metrics.chain(from_phrases).reduce(f64::max)
+}
+
+/// 拆分带单位的度量值，如 `"123.5ms"` → `(123.5, "ms")`
+pub fn parse_measurement(value: &str) -> Option<(f64, &str)> {
+    let split = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-'))
+        .unwrap_or(value.len());
+    let number = value[..split].parse::<f64>().ok()?;
+    Some((number, &value[split..]))
+}
+
+/// 度量序列中的一个点
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricPoint {
+    /// 所在行号
+    pub line_number: usize,
+    /// 所在条目的时间戳
+    pub timestamp: Option<String>,
+    /// 数值
+    pub value: f64,
+}
+
+/// 按名称汇总的度量序列
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricSeries {
+    /// 度量名称
+    pub name: String,
+    /// 单位（时间单位统一为 `ms`，其他为序列中第一个点的单位，无单位时为空）
+    pub unit: String,
+    /// 数据点数量
+    pub count: usize,
+    /// 最小值
+    pub min: f64,
+    /// 最大值
+    pub max: f64,
+    /// 平均值
+    pub avg: f64,
+    /// 数据点（按行号排序）
+    pub points: Vec<MetricPoint>,
+}
+
+/// 从解析后的条目汇总度量序列
+///
+/// # 参数
+/// - `entries`: 经过度量过滤器处理的日志条目
+/// - `name`: 只汇总指定名称的序列（None表示全部）
+///
+/// # Returns
+/// - `Vec<MetricSeries>`: 按名称排序的序列
+pub fn collect_metric_series(entries: &[LogLine], name: Option<&str>) -> Vec<MetricSeries> {
+    let mut series: BTreeMap<&str, MetricSeries> = BTreeMap::new();
+
+    for entry in entries {
+        for (key, value) in &entry.metadata {
+            let Some(metric) = key.strip_prefix(METRIC_METADATA_PREFIX) else {
+                continue;
+            };
+            if name.is_some_and(|name| name != metric) {
+                continue;
+            }
+            let Some((number, unit)) = parse_measurement(value) else {
+                continue;
+            };
+            // 时间单位统一换算为毫秒，避免同一序列中混用 ms 和 s
+            let (number, unit) = match duration_to_ms(number, unit) {
+                Some(ms) => (ms, "ms"),
+                None => (number, unit),
+            };
+
+            let s = series.entry(metric).or_insert_with(|| MetricSeries {
+                name: metric.to_string(),
+                unit: unit.to_string(),
+                count: 0,
+                min: f64::INFINITY,
+                max: f64::NEG_INFINITY,
+                avg: 0.0,
+                points: Vec::new(),
+            });
+            s.points.push(MetricPoint {
+                line_number: entry.line_number,
+                timestamp: entry.timestamp.clone(),
+                value: number,
+            });
+        }
+    }
+
+    series.into_values().map(|mut s| {
+        s.points.sort_by_key(|p| p.line_number);
+        s.count = s.points.len();
+        s.min = s.points.iter().map(|p| p.value).fold(f64::INFINITY, f64::min);
+        s.max = s.points.iter().map(|p| p.value).fold(f64::NEG_INFINITY, f64::max);
+        s.avg = s.points.iter().map(|p| p.value).sum::<f64>() / s.count as f64;
+        s
+    }).collect()
+}
+
+/// 格式化毫秒数（整数不带小数部分）
+fn format_ms(ms: f64) -> String {
+    if ms.fract() == 0.0 {
+        format!("{}", ms as i64)
+    } else {
+        format!("{:.3}", ms).trim_end_matches('0').to_string()
+    }
+}
+
+/// 慢操作分组
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlowOperationGroup {
+    /// 分组名称：操作名称、日志器或耗时度量名称
+    pub name: String,
+    /// 超过阈值的条目数
+    pub count: usize,
+    /// 最大耗时（毫秒）
+    pub max_ms: f64,
+    /// 平均耗时（毫秒）
+    pub avg_ms: f64,
+    /// 超过阈值的条目（按耗时降序）
+    pub entries: Vec<LogLine>,
+}
+
+/// 找出耗时超过阈值的条目，按操作分组
+///
+/// 分组名称依次取元数据中的 `operation`、`logger`，都没有时归入 `(unknown)`。
+///
+/// # 参数
+/// - `entries`: 经过度量过滤器处理的日志条目
+/// - `threshold_ms`: 耗时阈值（毫秒），严格大于阈值的条目才会返回
+///
+/// # Returns
+/// - `Vec<SlowOperationGroup>`: 按最大耗时降序排列的分组
+pub fn find_slow_operations(entries: &[LogLine], threshold_ms: f64) -> Vec<SlowOperationGroup> {
+    let mut groups: BTreeMap<String, Vec<(f64, &LogLine)>> = BTreeMap::new();
+
+    for entry in entries {
+        let Some(duration) = entry.metadata.get(DURATION_METADATA_KEY).and_then(|d| d.parse::<f64>().ok()) else {
+            continue;
+        };
+        if duration <= threshold_ms {
+            continue;
+        }
+        let name = entry.metadata.get(OPERATION_METADATA_KEY)
+            .or_else(|| entry.metadata.get("logger"))
+            .cloned()
+            .unwrap_or_else(|| "(unknown)".to_string());
+        groups.entry(name).or_default().push((duration, entry));
+    }
+
+    let mut groups: Vec<SlowOperationGroup> = groups.into_iter().map(|(name, mut items)| {
+        items.sort_by(|a, b| b.0.total_cmp(&a.0));
+        SlowOperationGroup {
+            name,
+            count: items.len(),
+            max_ms: items[0].0,
+            avg_ms: items.iter().map(|(d, _)| d).sum::<f64>() / items.len() as f64,
+            entries: items.into_iter().map(|(_, entry)| entry.clone()).collect(),
+        }
+    }).collect();
+    groups.sort_by(|a, b| b.max_ms.total_cmp(&a.max_ms));
+    groups
+}
+
+/// 离群点检测方法
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutlierMethod {
+    /// 标准分数：(x - 平均值) / 标准差，对已有离群点敏感
+    Zscore,
+    /// 修正z分数：0.6745 × (x - 中位数) / MAD，离群点较多时仍稳健
+    #[default]
+    Mad,
+}
+
+impl OutlierMethod {
+    /// 默认阈值（分数绝对值达到该值视为离群）
+    pub fn default_threshold(self) -> f64 {
+        match self {
+            Self::Zscore => 3.0,
+            Self::Mad => 3.5,
+        }
+    }
+}
+
+/// 参与离群点检测的序列至少需要的数据点数
+pub const MIN_OUTLIER_POINTS: usize = 8;
+
+/// 一个离群的数据点
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricOutlier {
+    /// 数据点
+    pub point: MetricPoint,
+    /// 分数（正数表示偏高，负数表示偏低）
+    pub score: f64,
+    /// 数据点所在的条目
+    pub entry: LogLine,
+    /// 前后的上下文条目（不含数据点所在的条目）
+    pub context: Vec<LogLine>,
+}
+
+/// 一个序列的离群点
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricOutliers {
+    /// 度量名称
+    pub name: String,
+    /// 单位
+    pub unit: String,
+    /// 检测方法
+    pub method: OutlierMethod,
+    /// 使用的阈值
+    pub threshold: f64,
+    /// 中心值（z-score为平均值，MAD为中位数）
+    pub center: f64,
+    /// 离散程度（z-score为标准差，MAD为中位数绝对偏差）
+    pub spread: f64,
+    /// 序列的数据点数
+    pub count: usize,
+    /// 离群点（按分数绝对值降序）
+    pub outliers: Vec<MetricOutlier>,
+}
+
+fn median(sorted: &[f64]) -> f64 {
+    let mid = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) { (sorted[mid - 1] + sorted[mid]) / 2.0 } else { sorted[mid] }
+}
+
+/// 计算每个数值的分数
+///
+/// # Returns
+/// - `(中心值, 离散程度, 分数)`；数值完全相同（离散程度为0）时分数全为0
+fn outlier_scores(values: &[f64], method: OutlierMethod) -> (f64, f64, Vec<f64>) {
+    let n = values.len() as f64;
+    let (center, spread, scale) = match method {
+        OutlierMethod::Zscore => {
+            let mean = values.iter().sum::<f64>() / n;
+            let std = (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n).sqrt();
+            (mean, std, 1.0)
+        }
+        OutlierMethod::Mad => {
+            let mut sorted = values.to_vec();
+            sorted.sort_by(f64::total_cmp);
+            let med = median(&sorted);
+            let mut deviations: Vec<f64> = values.iter().map(|v| (v - med).abs()).collect();
+            deviations.sort_by(f64::total_cmp);
+            let mad = median(&deviations);
+            if mad > 0.0 {
+                (med, mad, 0.6745)
+            } else {
+                // 一半以上的值相同时MAD为0，退回平均绝对偏差（换算为与MAD可比的尺度）
+                let mean_ad = deviations.iter().sum::<f64>() / n;
+                (med, mean_ad * 1.253314, 0.6745)
+            }
+        }
+    };
+    if spread <= 0.0 || !spread.is_finite() {
+        return (center, 0.0, vec![0.0; values.len()]);
+    }
+    (center, spread, values.iter().map(|v| scale * (v - center) / spread).collect())
+}
+
+/// 找出度量序列中的离群点
+///
+/// # 参数
+/// - `entries`: 经过度量过滤器处理的日志条目（按行号排序）
+/// - `name`: 只检测指定名称的序列（None表示全部）
+/// - `method`: 检测方法
+/// - `threshold`: 分数绝对值阈值
+/// - `context_lines`: 每个离群点前后附带的上下文条目数
+///
+/// # Returns
+/// - `Vec<MetricOutliers>`: 有离群点的序列（按名称排序），数据点少于 `MIN_OUTLIER_POINTS` 的序列不检测
+pub fn detect_metric_outliers(
+    entries: &[LogLine],
+    name: Option<&str>,
+    method: OutlierMethod,
+    threshold: f64,
+    context_lines: usize,
+) -> Vec<MetricOutliers> {
+    let index_by_line: HashMap<usize, usize> = entries.iter().enumerate().map(|(i, e)| (e.line_number, i)).collect();
+
+    collect_metric_series(entries, name).into_iter()
+        .filter(|series| series.count >= MIN_OUTLIER_POINTS)
+        .filter_map(|series| {
+            let values: Vec<f64> = series.points.iter().map(|p| p.value).collect();
+            let (center, spread, scores) = outlier_scores(&values, method);
+            let mut outliers: Vec<MetricOutlier> = series.points.iter().zip(scores)
+                .filter(|(_, score)| score.abs() >= threshold)
+                .filter_map(|(point, score)| {
+                    let index = *index_by_line.get(&point.line_number)?;
+                    let context = entries[index.saturating_sub(context_lines)..(index + context_lines + 1).min(entries.len())]
+                        .iter()
+                        .filter(|e| e.line_number != point.line_number)
+                        .cloned()
+                        .collect();
+                    Some(MetricOutlier { point: point.clone(), score, entry: entries[index].clone(), context })
+                })
+                .collect();
+            if outliers.is_empty() {
+                return None;
+            }
+            outliers.sort_by(|a, b| b.score.abs().total_cmp(&a.score.abs()));
+            Some(MetricOutliers {
+                name: series.name,
+                unit: series.unit,
+                method,
+                threshold,
+                center,
+                spread,
+                count: series.count,
+                outliers,
+            })
+        })
+        .collect()
+}
+
+/// 度量提取过滤器
+///
+/// # 配置项
+/// - `enabled`: 是否提取数值度量（默认开启）
+pub struct MetricsFilter {
+    /// 是否提取数值度量
+    pub enabled: bool,
+}
+
+impl MetricsFilter {
+    /// 使用插件设置创建过滤器
+    pub fn from_settings(options: PluginOptions) -> Self {
+        Self { enabled: options.bool("enabled", true) }
+    }
+}
+
+impl Default for MetricsFilter {
+    fn default() -> Self {
+        Self::from_settings(PluginOptions::default())
+    }
+}
+
+impl PluginFilter for MetricsFilter {
+    fn name(&self) -> &str {
+        "metrics"
+    }
+
+    fn description(&self) -> &str {
+        "度量提取过滤器，识别 key=value 形式的数值度量和耗时，并汇总为序列"
+    }
+
+    fn priority(&self) -> i32 {
+        70 // 在格式解析之后、内容增强之前执行，只处理日志正文
+    }
+
+    fn should_process(&self, context: &PluginChainContext) -> bool {
+        self.enabled && !context.current_lines.is_empty()
+    }
+
+    fn process(&self, context: &mut PluginChainContext, _request: &ParseRequest) -> Result<(), String> {
+        info!("📈 度量提取过滤器开始处理");
+
+        let mut names = BTreeSet::new();
+        let mut tagged = 0;
+        for line in &mut context.current_lines {
+            let measurements = extract_measurements(line.message_text());
+            let duration = extract_duration_ms(line.message_text(), &measurements);
+            if measurements.is_empty() && duration.is_none() {
+                continue;
+            }
+            if let Some(duration) = duration {
+                line.metadata.insert(DURATION_METADATA_KEY.to_string(), format_ms(duration));
+                if let Some(caps) = OPERATION.captures(line.message_text()) {
+                    line.metadata.insert(OPERATION_METADATA_KEY.to_string(), caps[1].to_string());
+                }
+            }
+            for (name, value) in measurements {
+                line.metadata.insert(format!("{}{}", METRIC_METADATA_PREFIX, name), value);
+                names.insert(name);
+            }
+            line.processed_by.push("metrics_filter".to_string());
+            tagged += 1;
+        }
+
+        if !names.is_empty() {
+            context.set_chain_metadata("metric_names".to_string(), names.into_iter().collect::<Vec<_>>().join(","));
+        }
+        info!("📈 度量提取过滤器处理完成，{} 行包含度量", tagged);
+        Ok(())
+    }
+
+    fn can_handle(&self, _content: &str, _file_path: Option<&str>) -> bool {
+        true // 可以处理任何内容，只标记包含度量的行
+    }
+
+    fn settings_schema(&self) -> Option<serde_json::Value> {
+        Some(json!({
+            "type": "object",
+            "properties": {
+                "enabled": { "type": "boolean", "default": true, "description": "提取 key=value 形式的数值度量和耗时" }
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_measurements() {
+        let found = extract_measurements(r#"GET /api took=123ms size=4567, latency: 45 ms "cost": 12.5 ratio=87%"#);
+        assert_eq!(found, vec![
+            ("took".to_string(), "123ms".to_string()),
+            ("size".to_string(), "4567".to_string()),
+            ("latency".to_string(), "45ms".to_string()),
+            ("cost".to_string(), "12.5".to_string()),
+            ("ratio".to_string(), "87%".to_string()),
+        ]);
+
+        assert!(extract_measurements("version=1.2.3 id=12ab url=http://host:8080/x at 10:30:25").is_empty());
+        assert_eq!(extract_measurements("count=5 items"), vec![("count".to_string(), "5".to_string())]);
+        assert_eq!(parse_measurement("123.5ms"), Some((123.5, "ms")));
+    }
+
+    #[test]
+    fn test_collect_metric_series() {
+        let entries: Vec<LogLine> = [(3, "took=30ms"), (1, "took=10ms size=1"), (2, "no metrics")]
+            .iter()
+            .map(|(n, content)| LogLine {
+                timestamp: Some(format!("2024-01-01T00:00:0{}", n)),
+                metadata: extract_measurements(content).into_iter()
+                    .map(|(name, value)| (format!("{}{}", METRIC_METADATA_PREFIX, name), value))
+                    .collect::<HashMap<_, _>>(),
+                ..LogLine::new(*n, *content)
+            })
+            .collect();
+
+        let series = collect_metric_series(&entries, None);
+        let names: Vec<&str> = series.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["size", "took"]);
+
+        let took = collect_metric_series(&entries, Some("took")).remove(0);
+        assert_eq!(took.unit, "ms");
+        assert_eq!(took.points.iter().map(|p| p.line_number).collect::<Vec<_>>(), vec![1, 3]);
+        assert_eq!((took.min, took.max, took.avg), (10.0, 30.0, 20.0));
+        assert_eq!(took.points[0].timestamp.as_deref(), Some("2024-01-01T00:00:01"));
+    }
+
+    #[test]
+    fn test_durations_and_slow_operations() {
+        let parse = |n: usize, content: &str, logger: &str| {
+            let measurements = extract_measurements(content);
+            let mut metadata = HashMap::from([("logger".to_string(), logger.to_string())]);
+            if let Some(ms) = extract_duration_ms(content, &measurements) {
+                metadata.insert(DURATION_METADATA_KEY.to_string(), format_ms(ms));
+            }
+            LogLine {
+                metadata,
+                ..LogLine::new(n, content)
+            }
+        };
+
+        assert_eq!(extract_duration_ms("query took 1.5s", &[]), Some(1500.0));
+        assert_eq!(extract_duration_ms("elapsed_ms=250", &extract_measurements("elapsed_ms=250")), Some(250.0));
+        assert_eq!(extract_duration_ms("timeout=30s retries=3", &extract_measurements("timeout=30s retries=3")), None);
+        assert_eq!(extract_duration_ms("done in 800 µs", &[]), Some(0.8));
+
+        let entries = vec![
+            parse(1, "SELECT users took=1200ms", "UserDao"),
+            parse(2, "Started Application in 2.5 seconds", "Application"),
+            parse(3, "SELECT orders latency: 80ms", "OrderDao"),
+            parse(4, "SELECT users took=3s", "UserDao"),
+        ];
+        let groups = find_slow_operations(&entries, 1000.0);
+        let summary: Vec<(&str, usize, f64)> = groups.iter().map(|g| (g.name.as_str(), g.count, g.max_ms)).collect();
+        assert_eq!(summary, vec![("UserDao", 2, 3000.0), ("Application", 1, 2500.0)]);
+        assert_eq!(groups[0].entries[0].line_number, 4);
+        assert_eq!(groups[0].avg_ms, 2100.0);
+    }
+
+    #[test]
+    fn test_detect_metric_outliers() {
+        let latencies = [20, 22, 19, 21, 20, 480, 23, 18, 20, 21, 22, 2];
+        let entries: Vec<LogLine> = latencies.iter().enumerate()
+            .map(|(i, ms)| {
+                let content = format!("GET /orders latency={}ms", ms);
+                LogLine {
+                    metadata: extract_measurements(&content).into_iter()
+                        .map(|(name, value)| (format!("{}{}", METRIC_METADATA_PREFIX, name), value))
+                        .collect(),
+                    ..LogLine::new(i + 1, content)
+                }
+            })
+            .collect();
+
+        let mad = detect_metric_outliers(&entries, None, OutlierMethod::Mad, OutlierMethod::Mad.default_threshold(), 1);
+        assert_eq!(mad.len(), 1);
+        assert_eq!(mad[0].center, 20.5);
+        let found: Vec<usize> = mad[0].outliers.iter().map(|o| o.point.line_number).collect();
+        assert_eq!(found, vec![6, 12]);
+        assert!(mad[0].outliers[0].score > 0.0 && mad[0].outliers[1].score < 0.0);
+        assert_eq!(mad[0].outliers[0].context.iter().map(|e| e.line_number).collect::<Vec<_>>(), vec![5, 7]);
+        assert_eq!(mad[0].outliers[1].context.len(), 1);
+
+        // 尖峰拉高了标准差，z-score只能发现最大的离群点
+        let zscore = detect_metric_outliers(&entries, Some("latency"), OutlierMethod::Zscore, 3.0, 0);
+        assert_eq!(zscore[0].outliers.len(), 1);
+        assert!(zscore[0].outliers[0].context.is_empty());
+        assert!(detect_metric_outliers(&entries[..6], None, OutlierMethod::Mad, 3.5, 0).is_empty());
+    }
+}
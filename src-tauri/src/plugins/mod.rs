@@ -37,11 +37,33 @@ pub mod springboot;  // SpringBoot日志解析器 - Java应用日志解析
 pub mod manager;     // 基础插件管理器 - 插件注册和调用核心
 pub mod core;        // 增强插件管理器 - 高级插件管理功能
 pub mod formatter;   // 格式化工具 - 统一日志格式化显示
+pub mod compact;     // 紧凑显示规则 - 可配置的线程名缩略
+pub mod validation;  // 解析器验证工具 - 样本逐行匹配调试
 
 // 插件链系统模块
 pub mod chain;       // 插件链核心实现 - Filter Chain机制
+pub mod capabilities; // 插件能力协商 - 文件大小上限与性能等级
 pub mod filters;     // 具体过滤器实现 - 各种日志处理过滤器
 pub mod presets;     // 预定义链配置 - 常用场景的链配置
+pub mod settings;    // 插件配置项 - Schema声明与用户设置
+pub mod test_runner; // 测试运行器输出解析 - cargo test / go test / pytest
+pub mod ci;          // CI日志解析 - GitHub Actions / GitLab CI
+pub mod compose;     // Docker Compose日志拆分 - 多服务合并输出的服务前缀
+pub mod heroku;      // Heroku路由日志解析 - logfmt字段、dyno与请求耗时
+pub mod lambda;      // AWS Lambda日志解析 - 按调用分组与REPORT耗时内存
+pub mod nginx;       // Nginx错误日志解析 - 上游字段与上游错误分类
+pub mod haproxy;     // HAProxy日志解析 - 计时器与终止状态解释
+pub mod envoy;       // Envoy/Istio访问日志解析 - 上游集群与响应标志解释
+pub mod kafka;       // Kafka服务端日志解析 - 组件、分区与broker健康事件
+pub mod elasticsearch; // Elasticsearch服务端日志解析 - 节点、索引、GC与慢日志
+pub mod freb; // IIS失败请求跟踪解析 - FREB XML展开为事件时间线
+pub mod cef; // CEF/LEEF安全日志解析 - 头部字段、严重程度与扩展键值对
+pub mod pattern_layout; // 布局模式解析 - 由Logback/Log4j布局模式生成解析器
+pub mod metrics;     // 度量提取 - key=value数值度量与序列汇总
+pub mod wrapped_lines; // 终端折行合并 - 拼接按控制台宽度折断的消息
+pub mod script;      // 脚本钩子 - 对每个条目执行用户的Rhai脚本
+pub mod detection;   // 格式检测评分 - 样本行结构匹配的置信度
+pub mod conformance; // 解析器一致性测试 - 样本与黄金文件比较
 
 // 测试模块
 #[cfg(test)]
@@ -56,130 +78,10 @@ mod debug_tests2; // 调试测试2 - 链选择问题调试
 // ============================================================================
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
 
-/// 日志条目类型别名 (向后兼容)
-///
-/// 为了保持API兼容性而保留的类型别名。
-/// 新代码应该直接使用 `LogLine` 类型。
-pub type LogEntry = LogLine;
-
-/// 日志行数据结构
-///
-/// 表示解析后的单行日志条目，包含原始内容和解析后的结构化信息。
-/// 这是插件系统的核心数据结构，用于在解析器之间传递数据。
-///
-/// # 字段说明
-/// - `line_number`: 在原文件中的行号（从1开始）
-/// - `content`: 原始日志内容
-/// - `level`: 解析出的日志级别（如INFO, ERROR等）
-/// - `timestamp`: 解析出的时间戳
-/// - `formatted_content`: 格式化后的显示内容
-/// - `metadata`: 附加元数据（如线程ID、类名等）
-/// - `processed_by`: 处理此条目的插件列表
-///
-/// # 设计特点
-/// - 支持多种日志格式的统一表示
-/// - 可选字段适应不同的解析需求
-/// - 元数据支持扩展信息存储
-/// - 处理链追踪用于调试和优化
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct LogLine {
-    /// 在原文件中的行号（从1开始）
-    pub line_number: usize,
-
-    /// 原始日志内容（保持不变）
-    pub content: String,
-
-    /// 解析出的日志级别（如INFO, ERROR, WARN等）
-    pub level: Option<String>,
-
-    /// 解析出的时间戳（ISO格式或原始格式）
-    pub timestamp: Option<String>,
-
-    /// 格式化后的显示内容（可能包含高亮、结构化信息）
-    pub formatted_content: Option<String>,
-
-    /// 附加元数据（如线程ID、类名、方法名等）
-    pub metadata: HashMap<String, String>,
-
-    /// 处理此条目的插件名称列表（用于追踪处理链）
-    pub processed_by: Vec<String>,
-}
-
-/// 解析结果数据结构
-///
-/// 包含日志解析的完整结果，包括解析的日志条目、统计信息和错误状态。
-/// 这是所有解析器必须返回的统一结果格式。
-///
-/// # 字段说明
-/// - `lines`: 解析后的日志行列表
-/// - `total_lines`: 原始内容的总行数
-/// - `detected_format`: 检测到的日志格式
-/// - `parsing_errors`: 解析过程中遇到的错误列表
-///
-/// # 错误处理策略
-/// - 非致命错误继续处理其他行
-/// - 收集所有错误信息供用户参考
-/// - 即使有错误也返回部分结果
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ParseResult {
-    /// 解析后的日志行列表
-    pub lines: Vec<LogLine>,
-
-    /// 原始内容的总行数（包括空行）
-    pub total_lines: usize,
-
-    /// 自动检测到的日志格式（如"SpringBoot", "DockerJson"等）
-    pub detected_format: Option<String>,
-
-    /// 解析过程中遇到的错误列表
-    pub parsing_errors: Vec<String>,
-}
-
-/// 解析请求数据结构
-///
-/// 封装日志解析请求的所有参数，提供给插件解析器使用。
-/// 支持多种解析模式和配置选项。
-///
-/// # 字段说明
-/// - `content`: 要解析的日志内容
-/// - `plugin`: 指定使用的插件名称（可选）
-/// - `file_path`: 源文件路径（用于格式检测）
-/// - `chunk_size`: 分块处理时的块大小
-///
-/// # 使用模式
-/// - 指定插件模式：设置plugin字段使用特定解析器
-/// - 自动检测模式：不设置plugin，让系统自动选择
-/// - 分块处理模式：设置chunk_size用于大文件处理
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ParseRequest {
-    /// 要解析的日志内容
-    pub content: String,
-
-    /// 指定使用的插件名称（可选，不指定则自动检测）
-    pub plugin: Option<String>,
-
-    /// 源文件路径（用于文件扩展名和格式检测）
-    pub file_path: Option<String>,
-
-    /// 分块处理时的块大小（用于大文件优化）
-    pub chunk_size: Option<usize>,
-}
-
-impl Default for ParseRequest {
-    /// 创建默认的解析请求
-    ///
-    /// 返回一个空的解析请求，适用于手动构建请求对象。
-    fn default() -> Self {
-        Self {
-            content: String::new(),
-            plugin: None,
-            file_path: None,
-            chunk_size: None,
-        }
-    }
-}
+// 日志条目、解析请求和解析结果定义在共享的数据模型模块中，
+// 前端命令和插件系统使用同一套结构，这里重新导出以保持原有路径可用
+pub use crate::models::{LogEntry, LogLine, ParseRequest, ParseResult};
 
 /// 插件信息数据结构
 ///
@@ -191,6 +93,9 @@ impl Default for ParseRequest {
 /// - `description`: 插件功能的用户友好描述
 /// - `supported_extensions`: 支持的文件扩展名列表
 /// - `auto_detectable`: 是否支持自动检测
+/// - `version`: 插件版本（内置插件与应用版本一致）
+/// - `kind`: 单插件解析器或插件链
+/// - `enabled`: 是否启用（未配置的自定义布局链注册但不启用）
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PluginInfo {
     /// 插件的唯一标识符（用于API调用）
@@ -204,8 +109,30 @@ pub struct PluginInfo {
 
     /// 是否支持自动格式检测
     pub auto_detectable: bool,
+
+    /// 插件版本号
+    pub version: String,
+
+    /// 插件类型
+    pub kind: PluginKind,
+
+    /// 是否启用
+    pub enabled: bool,
 }
 
+/// 插件类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PluginKind {
+    /// 单插件解析器（`LogParser`）
+    Parser,
+    /// 由多个过滤器组成的插件链
+    Chain,
+}
+
+/// 内置插件的版本号
+pub const PLUGIN_VERSION: &str = env!("CARGO_PKG_VERSION");
+
 /// 日志解析器特征
 ///
 /// 定义了所有日志解析插件必须实现的核心接口。
@@ -261,6 +188,26 @@ pub trait LogParser {
     /// - `bool`: true表示可以解析，false表示不适合
     fn can_parse(&self, content: &str, file_path: Option<&str>) -> bool;
 
+    /// 计算内容属于此格式的置信度
+    ///
+    /// 自动检测时每个专用解析器都会评分，得分最高且不低于
+    /// `detection::MIN_CONFIDENCE` 的解析器胜出。
+    /// 默认实现把 `can_parse()` 的结果换算为0.0或 `MIN_CONFIDENCE`。
+    ///
+    /// # 参数
+    /// - `content`: 日志内容的预览
+    /// - `file_path`: 可选的文件路径信息
+    ///
+    /// # Returns
+    /// - `f32`: 置信度（0.0 - 1.0）
+    fn confidence(&self, content: &str, file_path: Option<&str>) -> f32 {
+        if self.can_parse(content, file_path) {
+            detection::MIN_CONFIDENCE
+        } else {
+            0.0
+        }
+    }
+
     /// 执行日志解析
     ///
     /// 核心解析方法，将原始日志内容转换为结构化的LogLine列表。
@@ -29,7 +29,7 @@
 /// - 元数据标记：为SQL不同阶段添加类型标识
 /// - 格式统一：确保与其他插件的显示格式一致
 
-use crate::plugins::{LogParser, ParseRequest, ParseResult, LogLine};
+use crate::plugins::{detection, LogParser, ParseRequest, ParseResult, LogLine};
 use crate::plugins::formatter::UnifiedFormatter;
 use std::collections::HashMap;
 
@@ -101,6 +101,11 @@ impl LogParser for MyBatisParser {
         content.to_lowercase().contains("parameters:")
     }
 
+    /// 按样本行的结构匹配计算置信度（见 `detection::mybatis_confidence`）
+    fn confidence(&self, content: &str, _file_path: Option<&str>) -> f32 {
+        detection::mybatis_confidence(&detection::sample_lines(content))
+    }
+
     /// 执行MyBatis日志解析
     ///
     /// 这是MyBatis插件的核心解析功能，将原始MyBatis日志内容转换为结构化的LogLine列表。
@@ -121,7 +126,7 @@ impl LogParser for MyBatisParser {
     ///
     /// # 参数
     /// - `content`: 要解析的完整日志内容
-    /// - `_request`: 解析请求参数（当前未使用）
+    /// - `request`: 解析请求参数（使用其中的格式化风格）
     ///
     /// # Returns
     /// - `Ok(ParseResult)`: 解析成功的结构化日志结果
@@ -148,7 +153,7 @@ impl LogParser for MyBatisParser {
     /// # 日志级别处理
     /// 当前主要识别DEBUG级别的日志，这是MyBatis SQL调试的常见级别。
     /// 未来可扩展支持其他日志级别的识别。
-    fn parse(&self, content: &str, _request: &ParseRequest) -> Result<ParseResult, String> {
+    fn parse(&self, content: &str, request: &ParseRequest) -> Result<ParseResult, String> {
         let lines: Vec<LogLine> = content
             .lines()
             .enumerate()
@@ -180,7 +185,7 @@ impl LogParser for MyBatisParser {
                     "mybatis"
                 );
 
-                let formatted_content = UnifiedFormatter::format_display_string(&unified_format);
+                let formatted_content = UnifiedFormatter::format_display_string(&unified_format, request.formatting.unwrap_or_default());
 
                 LogLine {
                     line_number: i + 1,
@@ -188,6 +193,7 @@ impl LogParser for MyBatisParser {
                     level: unified_format.level.clone(),
                     timestamp: unified_format.timestamp.clone(),
                     formatted_content: Some(formatted_content),
+                    message: None,
                     metadata,
                     processed_by: vec!["mybatis_parser".to_string()],
                 }
@@ -199,6 +205,8 @@ impl LogParser for MyBatisParser {
             total_lines: content.lines().count(),
             detected_format: Some("mybatis".to_string()),
             parsing_errors: Vec::new(),
+            applied_filters: Vec::new(),
+            capabilities: None,
         })
     }
 }
\ No newline at end of file
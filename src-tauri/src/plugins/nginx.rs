@@ -0,0 +1,223 @@
+/// Nginx 错误日志解析
+///
+/// 错误日志（`error_log`）与访问日志格式不同：
+///
+/// ```text
+/// 2024/01/15 10:30:25 [error] 1234#0: *45 upstream timed out (110: Connection timed out) while reading response header from upstream, client: 10.0.0.1, server: example.com, request: "GET /api HTTP/1.1", upstream: "http://127.0.0.1:8080/api", host: "example.com"
+/// ```
+///
+/// 行首依次为时间、级别、`进程号#线程号`、可选的 `*连接号`，消息末尾附带 `client: ...` 等上下文字段。
+///
+/// # 元数据
+/// - `nginx_level`: 原始级别（crit / alert / emerg 统一映射为ERROR级别，原始值保留在这里）
+/// - `pid` / `tid` / `connection_id`: 进程号、线程号、连接号
+/// - `client`、`server`、`request`、`upstream`、`host`、`referrer`: 消息末尾的上下文字段
+/// - `upstream_phase`: 与上游交互的阶段（如 `reading response header`）
+/// - `error_class`: 上游错误分类（见 `classify_upstream_error`），错误聚类按此键分组，
+///   上游超时（`upstream_timeout`）与连接被拒、连接重置等其他上游错误区分开
+
+use crate::plugins::chain::{PluginChainContext, PluginFilter};
+use crate::plugins::{LogLine, ParseRequest};
+use log::info;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::HashMap;
+
+/// 行首：`2024/01/15 10:30:25 [error] 1234#0: *45 `
+static ERROR_LINE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(\d{4})/(\d{2})/(\d{2}) (\d{2}:\d{2}:\d{2}) \[(debug|info|notice|warn|error|crit|alert|emerg)\] (\d+)#(\d+): (?:\*(\d+) )?(.*)$").unwrap()
+});
+
+/// 消息末尾的上下文字段：`, client: 10.0.0.1`、`, upstream: "http://..."`
+static CONTEXT_FIELD: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#", (client|server|request|upstream|host|referrer): (?:"([^"]*)"|([^,]+))"#).unwrap()
+});
+
+/// 与上游交互的阶段：`while reading response header from upstream`
+static UPSTREAM_PHASE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"while (.+?) (?:from|to) upstream").unwrap()
+});
+
+/// 上游错误分类：(消息特征, 分类)，按顺序匹配
+const UPSTREAM_ERROR_CLASSES: &[(&str, &str)] = &[
+    ("upstream timed out", "upstream_timeout"),
+    ("no live upstreams", "no_live_upstreams"),
+    ("Connection refused", "upstream_refused"),
+    ("Connection reset by peer", "upstream_reset"),
+    ("upstream prematurely closed", "upstream_closed"),
+    ("upstream sent too big header", "upstream_header_too_big"),
+];
+
+/// 对上游相关的错误消息分类
+///
+/// # Returns
+/// - `Option<&str>`: 分类名称；消息与上游无关时返回None，与上游有关但不属于已知分类时返回 `upstream_error`
+pub fn classify_upstream_error(message: &str) -> Option<&'static str> {
+    if !message.contains("upstream") {
+        return None;
+    }
+    UPSTREAM_ERROR_CLASSES.iter()
+        .find(|(pattern, _)| message.contains(pattern))
+        .map(|(_, class)| *class)
+        .or(Some("upstream_error"))
+}
+
+/// Nginx错误日志过滤器
+pub struct NginxErrorFilter;
+
+impl NginxErrorFilter {
+    /// 行是否为Nginx错误日志行
+    pub fn matches_line(line: &str) -> bool {
+        ERROR_LINE.is_match(line)
+    }
+
+    /// 内容是否像Nginx错误日志
+    pub fn looks_like_nginx_error_log(content: &str) -> bool {
+        content.lines().take(200).any(Self::matches_line)
+    }
+
+    /// Nginx级别对应的日志级别
+    fn normalize_level(level: &str) -> &'static str {
+        match level {
+            "debug" => "DEBUG",
+            "info" | "notice" => "INFO",
+            "warn" => "WARN",
+            _ => "ERROR",
+        }
+    }
+
+    /// 处理单行，返回是否识别为Nginx错误日志行
+    fn tag_line(line: &mut LogLine, strict: bool) -> bool {
+        let content = line.message_text().to_string();
+        let Some(caps) = ERROR_LINE.captures(&content) else {
+            return false;
+        };
+
+        line.timestamp = Some(format!("{}-{}-{} {}", &caps[1], &caps[2], &caps[3], &caps[4]));
+        line.level = Some(Self::normalize_level(&caps[5]).to_string());
+        line.metadata.insert("nginx_level".to_string(), caps[5].to_string());
+        line.metadata.insert("pid".to_string(), caps[6].to_string());
+        line.metadata.insert("tid".to_string(), caps[7].to_string());
+        if let Some(connection) = caps.get(8) {
+            line.metadata.insert("connection_id".to_string(), connection.as_str().to_string());
+        }
+
+        let message = caps[9].to_string();
+        for field in CONTEXT_FIELD.captures_iter(&message) {
+            let value = field.get(2).or_else(|| field.get(3)).map_or("", |v| v.as_str().trim());
+            line.metadata.insert(field[1].to_string(), value.to_string());
+        }
+        if let Some(phase) = UPSTREAM_PHASE.captures(&message) {
+            line.metadata.insert("upstream_phase".to_string(), phase[1].to_string());
+        }
+        if let Some(class) = classify_upstream_error(&message) {
+            line.metadata.insert("error_class".to_string(), class.to_string());
+        }
+
+        line.set_message(message, strict);
+        true
+    }
+}
+
+impl PluginFilter for NginxErrorFilter {
+    fn name(&self) -> &str {
+        "nginx_error"
+    }
+
+    fn description(&self) -> &str {
+        "Nginx错误日志过滤器，提取级别、进程号、连接号和上游字段，并对上游错误分类"
+    }
+
+    fn priority(&self) -> i32 {
+        20 // 格式解析，在ANSI清理之后执行
+    }
+
+    fn should_process(&self, context: &PluginChainContext) -> bool {
+        if context.current_lines.is_empty() {
+            return Self::looks_like_nginx_error_log(&context.original_content);
+        }
+        context.current_lines.iter().take(200).any(|line| ERROR_LINE.is_match(line.message_text()))
+    }
+
+    fn process(&self, context: &mut PluginChainContext, request: &ParseRequest) -> Result<(), String> {
+        info!("🌐 Nginx错误日志过滤器开始处理");
+        let strict = request.is_strict();
+
+        let mut lines = if context.current_lines.is_empty() {
+            // 第一次处理，从原始内容创建行列表，过滤空行
+            context.original_content.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()).map(|(i, line)| {
+                LogLine {
+                    line_number: i + 1,
+                    content: line.to_string(),
+                    level: None,
+                    timestamp: None,
+                    formatted_content: None,
+                    message: None,
+                    metadata: HashMap::new(),
+                    processed_by: vec![],
+                }
+            }).collect()
+        } else {
+            std::mem::take(&mut context.current_lines)
+        };
+
+        let mut tagged = 0;
+        let mut upstream_timeouts = 0;
+        for line in &mut lines {
+            if Self::tag_line(line, strict) {
+                line.processed_by.push("nginx_error_filter".to_string());
+                line.formatted_content = Some(line.message_text().to_string());
+                tagged += 1;
+                if line.metadata.get("error_class").is_some_and(|class| class == "upstream_timeout") {
+                    upstream_timeouts += 1;
+                }
+            }
+        }
+
+        context.current_lines = lines;
+        context.set_chain_metadata("upstream_timeouts".to_string(), upstream_timeouts.to_string());
+
+        info!("🌐 Nginx错误日志过滤器处理完成，识别了 {} 行，其中上游超时 {} 行", tagged, upstream_timeouts);
+        Ok(())
+    }
+
+    fn can_handle(&self, content: &str, _file_path: Option<&str>) -> bool {
+        Self::looks_like_nginx_error_log(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::chain::PluginChainManager;
+    use crate::plugins::presets::register_preset_chains;
+
+    #[test]
+    fn test_parses_error_lines_and_upstream_fields() {
+        let content = r#"2024/01/15 10:30:25 [error] 1234#0: *45 upstream timed out (110: Connection timed out) while reading response header from upstream, client: 10.0.0.1, server: example.com, request: "GET /api HTTP/1.1", upstream: "http://127.0.0.1:8080/api", host: "example.com"
+2024/01/15 10:30:26 [error] 1234#0: *46 connect() failed (111: Connection refused) while connecting to upstream, client: 10.0.0.2, server: example.com, request: "POST /login HTTP/1.1", upstream: "http://127.0.0.1:8080/login", host: "example.com"
+2024/01/15 10:30:27 [notice] 1#1: signal process started
+2024/01/15 10:30:28 [crit] 1234#0: *47 open() "/var/www/index.html" failed (13: Permission denied)"#;
+
+        let mut manager = PluginChainManager::new();
+        register_preset_chains(&mut manager);
+        let result = manager.process(content, &ParseRequest::default()).unwrap();
+        assert_eq!(result.detected_format.as_deref(), Some("nginx_error"));
+
+        let lines = &result.lines;
+        let meta = |index: usize, key: &str| lines[index].metadata.get(key).map(String::as_str);
+        assert_eq!(lines[0].timestamp.as_deref(), Some("2024-01-15 10:30:25"));
+        assert_eq!(lines[0].level.as_deref(), Some("ERROR"));
+        assert_eq!((meta(0, "pid"), meta(0, "tid"), meta(0, "connection_id")), (Some("1234"), Some("0"), Some("45")));
+        assert_eq!(meta(0, "client"), Some("10.0.0.1"));
+        assert_eq!(meta(0, "request"), Some("GET /api HTTP/1.1"));
+        assert_eq!(meta(0, "upstream"), Some("http://127.0.0.1:8080/api"));
+        assert_eq!(meta(0, "upstream_phase"), Some("reading response header"));
+        assert_eq!(meta(0, "error_class"), Some("upstream_timeout"));
+        assert_eq!(meta(1, "error_class"), Some("upstream_refused"));
+        assert_eq!(lines[2].level.as_deref(), Some("INFO"));
+        assert_eq!(meta(2, "connection_id"), None);
+        assert_eq!(meta(3, "nginx_level"), Some("crit"));
+        assert_eq!(meta(3, "error_class"), None);
+    }
+}
@@ -0,0 +1,598 @@
+/// Logback / Log4j 布局模式解析
+///
+/// 用户粘贴应用日志配置中的布局模式（如 `%d{ISO8601} [%thread] %-5level %logger{36} - %msg%n`），
+/// 自动生成对应的正则表达式并按转换说明符提取字段，无需手写正则。
+///
+/// # 支持的转换说明符
+/// - `%d` / `%date{格式}`: 时间戳（SimpleDateFormat格式，或 ISO8601、ABSOLUTE、DATE 等命名格式）
+/// - `%p` / `%le` / `%level`: 日志级别
+/// - `%t` / `%thread`、`%c` / `%lo` / `%logger`、`%C` / `%class`、`%M` / `%method`、
+///   `%L` / `%line`、`%F` / `%file`: 线程、日志器、类、方法、行号、文件
+/// - `%m` / `%msg` / `%message`: 日志正文
+/// - `%X{key}` / `%mdc{key}`: MDC字段（元数据键为 `mdc_key`）
+/// - `%highlight(...)`、`%clr(...)`、`%highlight{...}` 等包装说明符按内部模式匹配
+/// - Spring Boot 的 `${PID:- }` 提取为 `pid`，`${LOG_LEVEL_PATTERN:-%5p}` 等占位符按默认布局匹配
+///
+/// 宽度修饰符（`%-5level`、`%15.15t`）会允许字段两侧的填充空格，
+/// 无法识别的说明符按任意文本匹配，不会导致整个模式失败。
+
+use crate::i18n::Message;
+use crate::plugins::chain::{PluginChainContext, PluginFilter};
+use crate::plugins::formatter::canonical_level;
+use crate::plugins::settings::PluginOptions;
+use crate::plugins::{LogLine, ParseRequest};
+use log::{info, warn};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+
+/// 未指定格式时的日期格式（Logback和Log4j2的默认值）
+const DEFAULT_DATE_FORMAT: &str = "yyyy-MM-dd HH:mm:ss,SSS";
+
+/// 布局中的一个字段
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LayoutField {
+    /// 字段名称（正则命名捕获组名称，也是元数据键）
+    pub name: String,
+    /// 原始转换说明符（如 `%-5level`）
+    pub specifier: String,
+}
+
+/// 编译后的布局模式
+#[derive(Debug, Clone)]
+pub struct PatternLayout {
+    pattern: String,
+    regex: Regex,
+    fields: Vec<LayoutField>,
+}
+
+/// 布局模式的编译结果，供界面预览生成的正则
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatternLayoutInfo {
+    /// 原始布局模式
+    pub pattern: String,
+    /// 生成的正则表达式
+    pub regex: String,
+    /// 提取的字段（按出现顺序）
+    pub fields: Vec<LayoutField>,
+}
+
+impl PatternLayout {
+    /// 从布局模式生成解析器
+    ///
+    /// # Returns
+    /// - `Ok(PatternLayout)`: 编译后的布局
+    /// - `Err(String)`: 模式为空、括号不匹配或不包含任何字段时的错误信息
+    pub fn compile(pattern: &str) -> Result<Self, String> {
+        let trimmed = pattern.trim();
+        if trimmed.is_empty() {
+            return Err(Message::new("layout.empty").text());
+        }
+
+        let mut compiler = Compiler::default();
+        let body = compiler.compile(trimmed)?;
+        if compiler.fields.is_empty() {
+            return Err(Message::new("layout.no_specifiers").with("pattern", trimmed).text());
+        }
+
+        let regex = Regex::new(&format!("^{}$", body))
+            .map_err(|e| Message::new("layout.invalid_regex").with("error", e).text())?;
+        Ok(Self { pattern: trimmed.to_string(), regex, fields: compiler.fields })
+    }
+
+    /// 生成的正则表达式
+    pub fn regex(&self) -> &Regex {
+        &self.regex
+    }
+
+    /// 布局的编译结果
+    pub fn info(&self) -> PatternLayoutInfo {
+        PatternLayoutInfo {
+            pattern: self.pattern.clone(),
+            regex: self.regex.as_str().to_string(),
+            fields: self.fields.clone(),
+        }
+    }
+
+    /// 按布局解析一行，返回字段名到值的映射；不匹配时返回None
+    pub fn parse_line(&self, line: &str) -> Option<HashMap<String, String>> {
+        let captures = self.regex.captures(line)?;
+        Some(self.fields.iter()
+            .filter_map(|field| {
+                captures.name(&field.name).map(|m| (field.name.clone(), m.as_str().trim().to_string()))
+            })
+            .collect())
+    }
+}
+
+/// 布局模式到正则的转换状态
+#[derive(Default)]
+struct Compiler {
+    fields: Vec<LayoutField>,
+}
+
+impl Compiler {
+    fn compile(&mut self, pattern: &str) -> Result<String, String> {
+        let chars: Vec<char> = pattern.chars().collect();
+        let mut out = String::new();
+        let mut i = 0;
+
+        while i < chars.len() {
+            match chars[i] {
+                '%' if chars.get(i + 1) == Some(&'%') => {
+                    out.push('%');
+                    i += 2;
+                }
+                '%' => {
+                    i = self.conversion(&chars, i, &mut out)?;
+                }
+                '$' if chars.get(i + 1) == Some(&'{') => {
+                    // Spring Boot 占位符：${NAME:-默认值}
+                    let end = find_closing(&chars, i + 1, '{', '}')
+                        .ok_or_else(|| Message::new("layout.unclosed_property").text())?;
+                    let inner: String = chars[i + 2..end].iter().collect();
+                    let (property, default) = inner.split_once(":-").unwrap_or((&inner, ""));
+                    if property == "PID" {
+                        let specifier: String = chars[i..=end].iter().collect();
+                        out.push_str(&self.capture("pid".to_string(), &specifier, r"\d*"));
+                    } else if default.contains('%') {
+                        // 未设置属性时使用默认布局，如 ${LOG_LEVEL_PATTERN:-%5p}
+                        out.push_str(&self.compile(default)?);
+                    } else {
+                        out.push_str(".*?");
+                    }
+                    i = end + 1;
+                }
+                c if c.is_whitespace() => {
+                    while i < chars.len() && chars[i].is_whitespace() {
+                        i += 1;
+                    }
+                    out.push_str(r"\s+");
+                }
+                '\\' if i + 1 < chars.len() => {
+                    out.push_str(&regex::escape(&chars[i + 1].to_string()));
+                    i += 2;
+                }
+                c => {
+                    out.push_str(&regex::escape(&c.to_string()));
+                    i += 1;
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    /// 转换从 `start`（`%` 位置）开始的一个说明符，返回说明符之后的位置
+    fn conversion(&mut self, chars: &[char], start: usize, out: &mut String) -> Result<usize, String> {
+        let mut i = start + 1;
+
+        // 宽度修饰符：-5、15.15、.-1
+        let modifier_start = i;
+        while i < chars.len() && (chars[i] == '-' || chars[i] == '.' || chars[i].is_ascii_digit()) {
+            i += 1;
+        }
+        let modifier: String = chars[modifier_start..i].iter().collect();
+        let width = modifier.split('.').next().unwrap_or("");
+        let (pad_left, pad_right) = match width {
+            "" | "-" => (false, false),
+            w if w.starts_with('-') => (false, true),
+            _ => (true, false),
+        };
+
+        let name_start = i;
+        while i < chars.len() && chars[i].is_ascii_alphabetic() {
+            i += 1;
+        }
+        let name: String = chars[name_start..i].iter().collect();
+
+        // 包装说明符：%highlight(...)、%clr(...){faint}，或无名称的分组 %(...)
+        let inner = if chars.get(i) == Some(&'(') {
+            let end = find_closing(chars, i, '(', ')')
+                .ok_or_else(|| Message::new("layout.unbalanced_parentheses").with("name", &name).text())?;
+            let inner: String = chars[i + 1..end].iter().collect();
+            i = end + 1;
+            Some(inner)
+        } else {
+            None
+        };
+
+        let mut options = Vec::new();
+        while chars.get(i) == Some(&'{') {
+            let end = find_closing(chars, i, '{', '}')
+                .ok_or_else(|| Message::new("layout.unclosed_option").with("name", &name).text())?;
+            options.push(chars[i + 1..end].iter().collect::<String>());
+            i = end + 1;
+        }
+
+        let specifier: String = chars[start..i].iter().collect();
+        let body = match inner {
+            Some(inner) => self.compile(&inner)?,
+            None => match self.field_for(&name, options.first().map(String::as_str)) {
+                Some((field, body)) => self.capture(field, &specifier, &body),
+                // Log4j2 的包装说明符使用花括号：%highlight{%-5level}{STYLE}
+                None => match options.first() {
+                    Some(option) if option.contains('%') => self.compile(option)?,
+                    _ => body_for_unnamed(&name),
+                },
+            },
+        };
+
+        if pad_left {
+            out.push_str(r"\s*");
+        }
+        out.push_str(&body);
+        if pad_right {
+            out.push_str(r"\s*");
+        }
+        Ok(i)
+    }
+
+    /// 说明符对应的字段名称和匹配模式
+    fn field_for(&self, name: &str, option: Option<&str>) -> Option<(String, String)> {
+        let (field, body) = match name {
+            "d" | "date" => ("timestamp".to_string(), date_regex(option)),
+            "p" | "le" | "level" => ("level".to_string(), r"[A-Za-z]+".to_string()),
+            "t" | "thread" => ("thread".to_string(), r".+?".to_string()),
+            "c" | "lo" | "logger" => ("logger".to_string(), r"\S+".to_string()),
+            "C" | "class" => ("class".to_string(), r"\S+".to_string()),
+            "M" | "method" => ("method".to_string(), r"\S+".to_string()),
+            "L" | "line" => ("line".to_string(), r"\d+|\?".to_string()),
+            "F" | "file" => ("file".to_string(), r"\S+".to_string()),
+            "m" | "msg" | "message" => ("message".to_string(), r".*".to_string()),
+            "r" | "relative" => ("relative".to_string(), r"\d+".to_string()),
+            "pid" | "processId" => ("pid".to_string(), r"\d+".to_string()),
+            "tid" | "threadId" => ("thread_id".to_string(), r"\d+".to_string()),
+            "marker" => ("marker".to_string(), r"\S*".to_string()),
+            "cn" | "contextName" => ("context".to_string(), r"\S+".to_string()),
+            "X" | "mdc" | "MDC" => match option.map(|key| key.split(":-").next().unwrap_or(key)) {
+                Some(key) if !key.is_empty() => (format!("mdc_{}", sanitize_name(key)), r".*?".to_string()),
+                _ => ("mdc".to_string(), r".*?".to_string()),
+            },
+            _ => return None,
+        };
+        Some((field, body))
+    }
+
+    /// 生成命名捕获组；同名字段重复出现时只捕获第一次
+    fn capture(&mut self, field: String, specifier: &str, body: &str) -> String {
+        if self.fields.iter().any(|f| f.name == field) {
+            return format!("(?:{})", body);
+        }
+        let group = format!("(?P<{}>{})", field, body);
+        self.fields.push(LayoutField { name: field, specifier: specifier.to_string() });
+        group
+    }
+}
+
+/// 不产生字段的说明符
+fn body_for_unnamed(name: &str) -> String {
+    match name {
+        // 换行和异常堆栈不出现在首行中
+        "n" | "ex" | "exception" | "throwable" | "xEx" | "xException" | "xThrowable" |
+        "rEx" | "rException" | "rThrowable" | "wEx" | "wex" | "nopex" | "nopexception" => String::new(),
+        "N" | "nano" | "sn" | "sequenceNumber" => r"\d+".to_string(),
+        _ => {
+            warn!("⚠️ 未识别的布局说明符 %{}，按任意文本匹配", name);
+            r".*?".to_string()
+        }
+    }
+}
+
+/// 查找与 `open` 位置匹配的闭合字符
+fn find_closing(chars: &[char], open: usize, open_char: char, close_char: char) -> Option<usize> {
+    let mut depth = 0;
+    for (i, &c) in chars.iter().enumerate().skip(open) {
+        if c == open_char {
+            depth += 1;
+        } else if c == close_char {
+            depth -= 1;
+            if depth == 0 {
+                return Some(i);
+            }
+        }
+    }
+    None
+}
+
+/// 把任意字符串转换为合法的捕获组名称
+fn sanitize_name(name: &str) -> String {
+    name.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }).collect()
+}
+
+/// 日期说明符选项转换为正则
+///
+/// 选项可以是命名格式（ISO8601、ABSOLUTE等）或SimpleDateFormat格式，
+/// 可选的时区部分（`{HH:mm:ss, UTC}`）会被忽略。
+fn date_regex(option: Option<&str>) -> String {
+    let format = match option.map(str::trim) {
+        None | Some("") | Some("DEFAULT") => DEFAULT_DATE_FORMAT.to_string(),
+        Some(option) => {
+            if let Some(quoted) = option.strip_prefix('"').and_then(|o| o.split('"').next()) {
+                quoted.to_string()
+            } else {
+                // 逗号后只有毫秒字母时属于格式本身，否则视为时区
+                match option.rsplit_once(',') {
+                    Some((format, rest)) if !rest.trim().chars().all(|c| c == 'S') => format.trim().to_string(),
+                    _ => option.to_string(),
+                }
+            }
+        }
+    };
+
+    match format.as_str() {
+        "ISO8601" => r"\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}:\d{2}[,.]\d{3}".to_string(),
+        "ISO8601_BASIC" => r"\d{8}T\d{6}[,.]\d{3}".to_string(),
+        "ISO8601_OFFSET_DATE_TIME_HHMM" | "ISO8601_OFFSET_DATE_TIME_HHCMM" =>
+            r"\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}[,.]\d{3}(?:Z|[+-]\d{2}:?\d{2})".to_string(),
+        "ABSOLUTE" => r"\d{2}:\d{2}:\d{2}[,.]\d{3}".to_string(),
+        "DATE" => r"\d{2} [A-Za-z]{3} \d{4} \d{2}:\d{2}:\d{2}[,.]\d{3}".to_string(),
+        "COMPACT" => r"\d{17}".to_string(),
+        "UNIX" | "UNIX_MILLIS" => r"\d+".to_string(),
+        format => simple_date_format_regex(format),
+    }
+}
+
+/// SimpleDateFormat 格式转换为正则
+fn simple_date_format_regex(format: &str) -> String {
+    let chars: Vec<char> = format.chars().collect();
+    let mut out = String::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\'' {
+            // 单引号内为字面量，'' 表示单引号本身
+            let end = chars[i + 1..].iter().position(|&c| c == '\'').map(|p| i + 1 + p).unwrap_or(chars.len());
+            let literal: String = chars[i + 1..end].iter().collect();
+            out.push_str(&regex::escape(if literal.is_empty() { "'" } else { &literal }));
+            i = end + 1;
+            continue;
+        }
+        if !c.is_ascii_alphabetic() {
+            out.push_str(&regex::escape(&c.to_string()));
+            i += 1;
+            continue;
+        }
+
+        let mut count = 1;
+        while i + count < chars.len() && chars[i + count] == c {
+            count += 1;
+        }
+        i += count;
+
+        let part = match (c, count) {
+            ('y' | 'u', 2) => r"\d{2}".to_string(),
+            ('y' | 'u', _) => r"\d{4}".to_string(),
+            ('M' | 'L', 1) => r"\d{1,2}".to_string(),
+            ('M' | 'L', 2) => r"\d{2}".to_string(),
+            ('M' | 'L', 3) => r"[A-Za-z]{3}".to_string(),
+            ('M' | 'L', _) => r"[A-Za-z]+".to_string(),
+            ('d' | 'H' | 'h' | 'k' | 'K' | 'm' | 's', 1) => r"\d{1,2}".to_string(),
+            ('d' | 'H' | 'h' | 'k' | 'K' | 'm' | 's', n) => format!(r"\d{{{}}}", n),
+            ('S', n) => format!(r"\d{{{}}}", n),
+            ('D', _) => r"\d{1,3}".to_string(),
+            ('a', _) => r"[AaPp][Mm]".to_string(),
+            ('E', 1..=3) => r"[A-Za-z]{3}".to_string(),
+            ('E', _) => r"[A-Za-z]+".to_string(),
+            ('z', _) => r"[A-Za-z0-9+:/_-]+".to_string(),
+            ('Z', _) => r"[+-]\d{4}".to_string(),
+            ('X' | 'x', _) => r"(?:Z|[+-]\d{2}(?::?\d{2})?)".to_string(),
+            (_, n) => format!(r"\w{{{}}}", n),
+        };
+        out.push_str(&part);
+    }
+    out
+}
+
+/// 布局模式过滤器
+///
+/// 按用户配置的布局模式（插件设置 `pattern_layout.pattern`）解析日志行。
+/// 未配置布局时不处理任何内容；配置后由 `select_best_chain` 在内容匹配时优先选择。
+pub struct PatternLayoutFilter {
+    layout: Option<PatternLayout>,
+}
+
+impl PatternLayoutFilter {
+    /// 参与格式检测的最大行数
+    const DETECTION_LINES: usize = 50;
+
+    /// 按插件设置创建过滤器，布局无效时视为未配置
+    pub fn from_settings(options: PluginOptions) -> Self {
+        let pattern = options.str("pattern", "");
+        let layout = if pattern.trim().is_empty() {
+            None
+        } else {
+            PatternLayout::compile(pattern)
+                .map_err(|e| warn!("⚠️ 布局模式无效，已忽略: {}", e))
+                .ok()
+        };
+        Self { layout }
+    }
+
+    /// 是否配置了有效的布局
+    pub fn is_configured(&self) -> bool {
+        self.layout.is_some()
+    }
+
+    /// 把解析出的字段写入日志行
+    fn apply_fields(line: &mut LogLine, mut fields: HashMap<String, String>, strict: bool) {
+        if let Some(timestamp) = fields.remove("timestamp") {
+            line.timestamp = Some(timestamp);
+        }
+        if let Some(level) = fields.remove("level") {
+            line.level = Some(canonical_level(&level).into_owned());
+        }
+        if let Some(message) = fields.remove("message") {
+            line.set_message(message, strict);
+        }
+        for (key, value) in fields {
+            if !value.is_empty() {
+                line.metadata.insert(key, value);
+            }
+        }
+        line.formatted_content = Some(line.message_text().to_string());
+    }
+}
+
+impl Default for PatternLayoutFilter {
+    fn default() -> Self {
+        Self::from_settings(PluginOptions::default())
+    }
+}
+
+impl PluginFilter for PatternLayoutFilter {
+    fn name(&self) -> &str {
+        "pattern_layout"
+    }
+
+    fn description(&self) -> &str {
+        "布局模式过滤器，按Logback/Log4j的布局模式自动生成解析规则"
+    }
+
+    fn priority(&self) -> i32 {
+        20 // 与SpringBoot过滤器同级的格式解析
+    }
+
+    fn should_process(&self, _context: &PluginChainContext) -> bool {
+        self.layout.is_some()
+    }
+
+    fn process(&self, context: &mut PluginChainContext, request: &ParseRequest) -> Result<(), String> {
+        let strict = request.is_strict();
+        let Some(layout) = &self.layout else {
+            return Ok(());
+        };
+        info!("🧩 布局模式过滤器开始处理: {}", layout.pattern);
+
+        if context.current_lines.is_empty() {
+            // 第一次处理，从原始内容创建行列表，过滤空行
+            context.current_lines = context.original_content.lines().enumerate()
+                .filter(|(_, line)| !line.trim().is_empty())
+                .map(|(i, line)| LogLine {
+                    line_number: i + 1,
+                    content: line.to_string(),
+                    level: None,
+                    timestamp: None,
+                    formatted_content: None,
+                    message: None,
+                    metadata: HashMap::new(),
+                    processed_by: vec![],
+                })
+                .collect();
+        }
+
+        let mut parsed_count = 0;
+        let mut last_level: Option<String> = None;
+        for line in &mut context.current_lines {
+            match layout.parse_line(line.message_text()) {
+                Some(fields) => {
+                    Self::apply_fields(line, fields, strict);
+                    line.processed_by.push("pattern_layout_filter".to_string());
+                    last_level = line.level.clone();
+                    parsed_count += 1;
+                }
+                None => {
+                    // 不匹配布局的行通常是上一条记录的续行（如异常堆栈），沿用其级别
+                    if line.level.is_none() {
+                        line.level = last_level.clone();
+                    }
+                    line.metadata.insert("type".to_string(), "unparsed".to_string());
+                }
+            }
+        }
+
+        context.set_chain_metadata("pattern_layout_processed".to_string(), parsed_count.to_string());
+        info!("🧩 布局模式过滤器处理完成，解析了 {} 行", parsed_count);
+        Ok(())
+    }
+
+    fn can_handle(&self, content: &str, _file_path: Option<&str>) -> bool {
+        let Some(layout) = &self.layout else {
+            return false;
+        };
+        content.lines()
+            .filter(|line| !line.trim().is_empty())
+            .take(Self::DETECTION_LINES)
+            .any(|line| layout.regex().is_match(line))
+    }
+
+    fn settings_schema(&self) -> Option<serde_json::Value> {
+        Some(json!({
+            "type": "object",
+            "properties": {
+                "pattern": {
+                    "type": "string",
+                    "format": "pattern_layout",
+                    "default": "",
+                    "description": "Logback/Log4j布局模式，如 %d{ISO8601} [%thread] %-5level %logger{36} - %msg%n"
+                }
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_logback_pattern() {
+        let layout = PatternLayout::compile("%d{ISO8601} [%thread] %-5level %logger{36} - %msg%n").unwrap();
+        let fields = layout.parse_line("2024-01-15 10:30:25,123 [http-nio-8080-exec-1] WARN  c.e.d.OrderService - 库存不足: sku=42").unwrap();
+
+        assert_eq!(fields["timestamp"], "2024-01-15 10:30:25,123");
+        assert_eq!(fields["thread"], "http-nio-8080-exec-1");
+        assert_eq!(fields["level"], "WARN");
+        assert_eq!(fields["logger"], "c.e.d.OrderService");
+        assert_eq!(fields["message"], "库存不足: sku=42");
+        assert!(layout.parse_line("\tat com.example.Foo.bar(Foo.java:10)").is_none());
+
+        let names: Vec<String> = layout.info().fields.into_iter().map(|f| f.name).collect();
+        assert_eq!(names, vec!["timestamp", "thread", "level", "logger", "message"]);
+    }
+
+    #[test]
+    fn test_spring_boot_and_log4j2_patterns() {
+        let spring = PatternLayout::compile(
+            "%clr(%d{yyyy-MM-dd HH:mm:ss.SSS}){faint} %clr(${LOG_LEVEL_PATTERN:-%5p}) %clr(${PID:- }){magenta} \
+             %clr(---){faint} %clr([%15.15t]){faint} %clr(%-40.40logger{39}){cyan} %clr(:){faint} %m%n",
+        ).unwrap();
+        let fields = spring.parse_line(
+            "2024-01-15 10:30:25.123  INFO 12345 --- [           main] com.example.Application                  : Started Application in 2.5 seconds",
+        ).unwrap();
+        assert_eq!(fields["level"], "INFO");
+        assert_eq!(fields["thread"], "main");
+        assert_eq!(fields["pid"], "12345");
+        assert_eq!(fields["message"], "Started Application in 2.5 seconds");
+
+        let log4j = PatternLayout::compile("%d{HH:mm:ss.SSS, UTC} %highlight{%-5level} %X{traceId} %c{1.}:%L - %m%n").unwrap();
+        let fields = log4j.parse_line("10:30:25.123 ERROR abc123 o.a.Foo:42 - boom").unwrap();
+        assert_eq!(fields["timestamp"], "10:30:25.123");
+        assert_eq!(fields["mdc_traceId"], "abc123");
+        assert_eq!(fields["line"], "42");
+
+        assert!(PatternLayout::compile("").is_err());
+        assert!(PatternLayout::compile("%d{yyyy").is_err());
+    }
+
+    #[test]
+    fn test_configured_layout_takes_precedence() {
+        use crate::plugins::chain::PluginChainManager;
+        use crate::plugins::presets::register_preset_chains_with_settings;
+        use crate::plugins::settings::PluginSettings;
+
+        let values = HashMap::from([("pattern_layout".to_string(), json!({ "pattern": "%d %-5p [%t] %c - %m%n" }))]);
+        let mut manager = PluginChainManager::new();
+        register_preset_chains_with_settings(&mut manager, &PluginSettings::new(values));
+
+        let content = "2024-01-15 10:30:25,123 ERROR [main] com.example.App - failed\njava.lang.IllegalStateException: boom";
+        let result = manager.process(content, &ParseRequest::default()).unwrap();
+
+        assert_eq!(result.detected_format.as_deref(), Some("pattern_layout"));
+        assert_eq!(result.lines[0].content, "failed");
+        assert_eq!(result.lines[0].metadata.get("logger").map(String::as_str), Some("com.example.App"));
+        assert_eq!(result.lines[1].level.as_deref(), Some("ERROR"));
+        assert_eq!(result.lines[1].metadata.get("type").map(String::as_str), Some("unparsed"));
+    }
+}
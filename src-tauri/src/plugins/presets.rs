@@ -9,6 +9,9 @@
 /// - **通用文本链**: 处理普通文本格式日志
 /// - **微服务链**: 处理微服务架构中的复杂日志格式
 /// - **数据库链**: 专门处理数据库相关的SQL日志
+/// - **测试运行器链**: 处理cargo test、go test、pytest的输出
+/// - **CI作业日志链**: 处理GitHub Actions和GitLab CI导出的作业日志
+/// - **布局模式链**: 按用户配置的Logback/Log4j布局模式解析
 ///
 /// # 使用方式
 /// ```rust
@@ -18,36 +21,108 @@
 /// ```
 
 use crate::plugins::chain::{PluginChain, ChainConditions, PluginChainManager};
+use crate::plugins::settings::PluginSettings;
+use crate::plugins::detection;
+use crate::plugins::test_runner::TestRunnerFilter;
+use crate::plugins::ci::CiLogFilter;
+use crate::plugins::compose::ComposeLogFilter;
+use crate::plugins::heroku::HerokuRouterFilter;
+use crate::plugins::lambda::LambdaLogFilter;
+use crate::plugins::nginx::NginxErrorFilter;
+use crate::plugins::haproxy::HaproxyLogFilter;
+use crate::plugins::envoy::EnvoyAccessLogFilter;
+use crate::plugins::kafka::KafkaLogFilter;
+use crate::plugins::elasticsearch::ElasticsearchLogFilter;
+use crate::plugins::freb::FrebTraceFilter;
+use crate::plugins::cef::CefLeefFilter;
+use crate::plugins::pattern_layout::PatternLayoutFilter;
+use crate::plugins::metrics::MetricsFilter;
+use crate::plugins::wrapped_lines::WrappedLineFilter;
+use crate::plugins::script::ScriptFilter;
 use crate::plugins::filters::{
     DockerJsonFilter, SpringBootFilter, MyBatisFilter, JavaLogFilter,
-    JsonStructureFilter, ContentEnhancerFilter
+    JsonStructureFilter, ContentEnhancerFilter, AnsiFilter, CarriageReturnFilter
 };
 use std::sync::Arc;
 use log::info;
 
-/// 注册所有预定义的插件链
+/// 注册所有预定义的插件链（使用过滤器默认设置）
 ///
 /// 创建并注册各种常用场景的插件链配置。
 ///
 /// # 参数
 /// - `manager`: 插件链管理器的可变引用
 pub fn register_preset_chains(manager: &mut PluginChainManager) {
+    register_preset_chains_with_settings(manager, &PluginSettings::default());
+}
+
+/// 使用插件设置注册所有预定义的插件链
+///
+/// 声明了配置项的过滤器（如 `docker_json`、`ansi`、`content_enhancer`）
+/// 会按 `settings` 中对应插件的设置构建。
+///
+/// # 参数
+/// - `manager`: 插件链管理器的可变引用
+/// - `settings`: 所有插件的用户设置
+pub fn register_preset_chains_with_settings(manager: &mut PluginChainManager, settings: &PluginSettings) {
     info!("🔧 注册预定义插件链...");
 
     // Docker容器日志处理链
-    register_docker_chain(manager);
+    register_docker_chain(manager, settings);
 
     // SpringBoot应用日志处理链
-    register_springboot_chain(manager);
+    register_springboot_chain(manager, settings);
 
     // 通用文本日志处理链
-    register_generic_chain(manager);
+    register_generic_chain(manager, settings);
 
     // 微服务架构日志处理链
-    register_microservice_chain(manager);
+    register_microservice_chain(manager, settings);
 
     // 数据库SQL日志处理链
-    register_database_chain(manager);
+    register_database_chain(manager, settings);
+
+    // 测试运行器输出处理链
+    register_test_runner_chain(manager, settings);
+
+    // CI作业日志处理链
+    register_ci_chain(manager, settings);
+
+    // Docker Compose多服务日志处理链
+    register_compose_chain(manager, settings);
+
+    // Heroku路由日志处理链
+    register_heroku_chain(manager, settings);
+
+    // AWS Lambda日志处理链
+    register_lambda_chain(manager, settings);
+
+    // Nginx错误日志处理链
+    register_nginx_error_chain(manager, settings);
+
+    // HAProxy日志处理链
+    register_haproxy_chain(manager, settings);
+
+    // Envoy/Istio访问日志处理链
+    register_envoy_chain(manager, settings);
+
+    // Kafka服务端日志处理链
+    register_kafka_chain(manager, settings);
+
+    // Elasticsearch服务端日志处理链
+    register_elasticsearch_chain(manager, settings);
+
+    // IIS失败请求跟踪处理链
+    register_freb_chain(manager, settings);
+
+    // CEF/LEEF安全日志处理链
+    register_cef_chain(manager, settings);
+
+    // 用户布局模式处理链
+    register_pattern_layout_chain(manager, settings);
+
+    // 用户脚本钩子对所有链生效（未启用时不处理任何条目）
+    manager.add_filter_to_all_chains(Arc::new(ScriptFilter::from_settings(settings.for_plugin("script"))));
 
     // 设置默认链
     manager.set_default_chain("generic".to_string());
@@ -68,7 +143,7 @@ pub fn register_preset_chains(manager: &mut PluginChainManager) {
 /// - SpringBoot应用的Docker容器日志
 /// - 包含SQL日志的微服务日志
 /// - 需要多层解析的容器化应用日志
-fn register_docker_chain(manager: &mut PluginChainManager) {
+fn register_docker_chain(manager: &mut PluginChainManager, settings: &PluginSettings) {
     let mut chain = PluginChain::new(
         "docker".to_string(),
         "Docker容器日志处理链，支持JSON格式解析和多层日志处理".to_string(),
@@ -86,11 +161,15 @@ fn register_docker_chain(manager: &mut PluginChainManager) {
     chain.conditions = Some(conditions);
 
     // 添加过滤器（按优先级顺序）
-    chain.add_filter(Arc::new(DockerJsonFilter));
+    chain.add_filter(Arc::new(DockerJsonFilter::from_settings(settings.for_plugin("docker_json"))));
+    chain.add_filter(Arc::new(CarriageReturnFilter));
+    chain.add_filter(Arc::new(AnsiFilter::from_settings(settings.for_plugin("ansi"))));
     chain.add_filter(Arc::new(SpringBootFilter));
+    chain.add_filter(Arc::new(WrappedLineFilter));
     chain.add_filter(Arc::new(JavaLogFilter));
     chain.add_filter(Arc::new(MyBatisFilter));
-    chain.add_filter(Arc::new(ContentEnhancerFilter));
+    chain.add_filter(Arc::new(ContentEnhancerFilter::from_settings(settings.for_plugin("content_enhancer"))));
+    chain.add_filter(Arc::new(MetricsFilter::from_settings(settings.for_plugin("metrics"))));
     chain.add_filter(Arc::new(JsonStructureFilter));
 
     manager.register_chain(chain);
@@ -111,7 +190,7 @@ fn register_docker_chain(manager: &mut PluginChainManager) {
 /// - SpringBoot应用的标准日志文件
 /// - 直接运行的Java应用日志
 /// - 不包含Docker JSON包装的应用日志
-fn register_springboot_chain(manager: &mut PluginChainManager) {
+fn register_springboot_chain(manager: &mut PluginChainManager, settings: &PluginSettings) {
     let mut chain = PluginChain::new(
         "springboot".to_string(),
         "SpringBoot应用日志处理链，专门处理SpringBoot标准日志格式".to_string(),
@@ -128,9 +207,13 @@ fn register_springboot_chain(manager: &mut PluginChainManager) {
 
     // 添加过滤器
     chain.add_filter(Arc::new(SpringBootFilter));
+    chain.add_filter(Arc::new(CarriageReturnFilter));
+    chain.add_filter(Arc::new(AnsiFilter::from_settings(settings.for_plugin("ansi"))));
+    chain.add_filter(Arc::new(WrappedLineFilter));
     chain.add_filter(Arc::new(JavaLogFilter));
     chain.add_filter(Arc::new(MyBatisFilter));
-    chain.add_filter(Arc::new(ContentEnhancerFilter));
+    chain.add_filter(Arc::new(ContentEnhancerFilter::from_settings(settings.for_plugin("content_enhancer"))));
+    chain.add_filter(Arc::new(MetricsFilter::from_settings(settings.for_plugin("metrics"))));
     chain.add_filter(Arc::new(JsonStructureFilter));
 
     manager.register_chain(chain);
@@ -150,7 +233,7 @@ fn register_springboot_chain(manager: &mut PluginChainManager) {
 /// - 不符合特定格式的应用日志
 /// - 系统日志和工具输出
 /// - 作为回退处理链
-fn register_generic_chain(manager: &mut PluginChainManager) {
+fn register_generic_chain(manager: &mut PluginChainManager, settings: &PluginSettings) {
     let mut chain = PluginChain::new(
         "generic".to_string(),
         "通用文本日志处理链，提供基础的日志解析和格式化功能".to_string(),
@@ -161,8 +244,12 @@ fn register_generic_chain(manager: &mut PluginChainManager) {
 
     // 添加过滤器
     chain.add_filter(Arc::new(SpringBootFilter));
+    chain.add_filter(Arc::new(CarriageReturnFilter));
+    chain.add_filter(Arc::new(AnsiFilter::from_settings(settings.for_plugin("ansi"))));
+    chain.add_filter(Arc::new(WrappedLineFilter));
     chain.add_filter(Arc::new(JavaLogFilter));
-    chain.add_filter(Arc::new(ContentEnhancerFilter));
+    chain.add_filter(Arc::new(ContentEnhancerFilter::from_settings(settings.for_plugin("content_enhancer"))));
+    chain.add_filter(Arc::new(MetricsFilter::from_settings(settings.for_plugin("metrics"))));
     chain.add_filter(Arc::new(JsonStructureFilter));
 
     manager.register_chain(chain);
@@ -183,7 +270,7 @@ fn register_generic_chain(manager: &mut PluginChainManager) {
 /// - 微服务架构的应用日志
 /// - 包含分布式追踪的日志
 /// - 多个服务调用的复合日志
-fn register_microservice_chain(manager: &mut PluginChainManager) {
+fn register_microservice_chain(manager: &mut PluginChainManager, settings: &PluginSettings) {
     let mut chain = PluginChain::new(
         "microservice".to_string(),
         "微服务架构日志处理链，支持分布式追踪和多服务日志处理".to_string(),
@@ -200,9 +287,13 @@ fn register_microservice_chain(manager: &mut PluginChainManager) {
 
     // 添加过滤器
     chain.add_filter(Arc::new(SpringBootFilter));
+    chain.add_filter(Arc::new(CarriageReturnFilter));
+    chain.add_filter(Arc::new(AnsiFilter::from_settings(settings.for_plugin("ansi"))));
+    chain.add_filter(Arc::new(WrappedLineFilter));
     chain.add_filter(Arc::new(JavaLogFilter));
     chain.add_filter(Arc::new(MyBatisFilter));
-    chain.add_filter(Arc::new(ContentEnhancerFilter));
+    chain.add_filter(Arc::new(ContentEnhancerFilter::from_settings(settings.for_plugin("content_enhancer"))));
+    chain.add_filter(Arc::new(MetricsFilter::from_settings(settings.for_plugin("metrics"))));
     chain.add_filter(Arc::new(JsonStructureFilter));
 
     manager.register_chain(chain);
@@ -224,7 +315,7 @@ fn register_microservice_chain(manager: &mut PluginChainManager) {
 /// - 数据库连接池日志
 /// - ORM框架的SQL输出日志
 /// - 数据库性能分析日志
-fn register_database_chain(manager: &mut PluginChainManager) {
+fn register_database_chain(manager: &mut PluginChainManager, settings: &PluginSettings) {
     let mut chain = PluginChain::new(
         "database".to_string(),
         "数据库SQL日志处理链，专门处理MyBatis等ORM框架的SQL日志".to_string(),
@@ -241,15 +332,450 @@ fn register_database_chain(manager: &mut PluginChainManager) {
 
     // 添加过滤器 - 首先添加能处理原始内容的过滤器
     chain.add_filter(Arc::new(SpringBootFilter)); // SpringBoot能处理原始内容
+    chain.add_filter(Arc::new(CarriageReturnFilter));
+    chain.add_filter(Arc::new(AnsiFilter::from_settings(settings.for_plugin("ansi"))));
     chain.add_filter(Arc::new(MyBatisFilter));
+    chain.add_filter(Arc::new(WrappedLineFilter));
     chain.add_filter(Arc::new(JavaLogFilter));
-    chain.add_filter(Arc::new(ContentEnhancerFilter));
+    chain.add_filter(Arc::new(ContentEnhancerFilter::from_settings(settings.for_plugin("content_enhancer"))));
+    chain.add_filter(Arc::new(MetricsFilter::from_settings(settings.for_plugin("metrics"))));
     chain.add_filter(Arc::new(JsonStructureFilter));
 
     manager.register_chain(chain);
     info!("✅ 注册数据库SQL日志链");
 }
 
+/// 测试运行器输出处理链
+///
+/// 处理CI中 `cargo test`、`go test -v`、`pytest` 的输出。
+///
+/// # 处理流程
+/// 1. ANSI清理 → 移除测试框架输出的颜色码
+/// 2. 测试运行器解析 → 标记测试名称、结果和捕获输出
+/// 3. 内容增强 → 标记错误行和链接
+///
+/// # 适用场景
+/// - CI构建日志中的测试步骤
+/// - 本地保存的测试输出
+fn register_test_runner_chain(manager: &mut PluginChainManager, settings: &PluginSettings) {
+    let mut chain = PluginChain::new(
+        "test_runner".to_string(),
+        "测试运行器输出处理链，识别cargo test、go test和pytest的测试结果".to_string(),
+    );
+
+    // 设置执行条件
+    let mut conditions = ChainConditions::new();
+    conditions.content_patterns.push("test result: ".to_string());
+    conditions.content_patterns.push("=== RUN ".to_string());
+    conditions.content_patterns.push("--- FAIL: ".to_string());
+    conditions.content_patterns.push("--- PASS: ".to_string());
+    conditions.content_patterns.push("test session starts".to_string());
+    conditions.content_patterns.push("short test summary info".to_string());
+    conditions.min_confidence = 0.6;
+    chain.conditions = Some(conditions);
+
+    // 添加过滤器
+    chain.add_filter(Arc::new(AnsiFilter::from_settings(settings.for_plugin("ansi"))));
+    chain.add_filter(Arc::new(TestRunnerFilter));
+    chain.add_filter(Arc::new(ContentEnhancerFilter::from_settings(settings.for_plugin("content_enhancer"))));
+    chain.add_filter(Arc::new(MetricsFilter::from_settings(settings.for_plugin("metrics"))));
+
+    manager.register_chain(chain);
+    info!("✅ 注册测试运行器输出链");
+}
+
+/// CI作业日志处理链
+///
+/// 处理GitHub Actions和GitLab CI的原始作业日志：
+/// 1. CI日志解析 → 去除时间戳前缀，识别分组和错误/警告标记
+/// 2. 回车折叠和ANSI清理 → 处理构建工具的进度条和颜色
+/// 3. 测试运行器解析 → 识别作业中的测试输出
+///
+/// 该链由 `select_best_chain` 在检测到CI日志特征时按格式评分选择（见 `detection::DETECTORS`）。
+fn register_ci_chain(manager: &mut PluginChainManager, settings: &PluginSettings) {
+    let mut chain = PluginChain::new(
+        "ci".to_string(),
+        "CI作业日志处理链，解析GitHub Actions和GitLab CI的分组与标记".to_string(),
+    );
+
+    // 设置执行条件
+    let mut conditions = ChainConditions::new();
+    conditions.content_patterns.push("##[group]".to_string());
+    conditions.content_patterns.push("##[error]".to_string());
+    conditions.content_patterns.push("section_start:".to_string());
+    conditions.min_confidence = 0.8;
+    chain.conditions = Some(conditions);
+
+    // 添加过滤器
+    chain.add_filter(Arc::new(CiLogFilter));
+    chain.add_filter(Arc::new(CarriageReturnFilter));
+    chain.add_filter(Arc::new(AnsiFilter::from_settings(settings.for_plugin("ansi"))));
+    chain.add_filter(Arc::new(TestRunnerFilter));
+    chain.add_filter(Arc::new(ContentEnhancerFilter::from_settings(settings.for_plugin("content_enhancer"))));
+    chain.add_filter(Arc::new(MetricsFilter::from_settings(settings.for_plugin("metrics"))));
+
+    manager.register_chain(chain);
+    info!("✅ 注册CI作业日志链");
+}
+
+/// Docker Compose多服务日志处理链
+///
+/// 处理 `docker compose logs` 合并输出的多服务日志：
+/// 1. 服务前缀拆分 → 去除 `服务名 | ` 前缀及其颜色，记录服务名
+/// 2. 回车折叠和ANSI清理 → 处理各服务输出中的进度条和颜色
+/// 3. SpringBoot/Java/SQL解析 → 解析前缀之后的应用日志
+///
+/// 条目的 `service` 元数据可直接用于按服务分组（`group_entries`）和筛选（`EntryFilter.metadata`）。
+/// 该链由 `select_best_chain` 在检测到服务前缀时按格式评分选择（见 `detection::DETECTORS`）。
+fn register_compose_chain(manager: &mut PluginChainManager, settings: &PluginSettings) {
+    let mut chain = PluginChain::new(
+        "compose".to_string(),
+        "Docker Compose多服务日志处理链，按服务前缀拆分合并输出".to_string(),
+    );
+
+    // 设置执行条件
+    let mut conditions = ChainConditions::new();
+    conditions.file_patterns.push("compose".to_string());
+    conditions.content_patterns.push("  | ".to_string());
+    conditions.min_confidence = 0.8;
+    chain.conditions = Some(conditions);
+
+    // 添加过滤器
+    chain.add_filter(Arc::new(ComposeLogFilter));
+    chain.add_filter(Arc::new(CarriageReturnFilter));
+    chain.add_filter(Arc::new(AnsiFilter::from_settings(settings.for_plugin("ansi"))));
+    chain.add_filter(Arc::new(SpringBootFilter));
+    chain.add_filter(Arc::new(WrappedLineFilter));
+    chain.add_filter(Arc::new(JavaLogFilter));
+    chain.add_filter(Arc::new(MyBatisFilter));
+    chain.add_filter(Arc::new(ContentEnhancerFilter::from_settings(settings.for_plugin("content_enhancer"))));
+    chain.add_filter(Arc::new(MetricsFilter::from_settings(settings.for_plugin("metrics"))));
+    chain.add_filter(Arc::new(JsonStructureFilter));
+
+    manager.register_chain(chain);
+    info!("✅ 注册Docker Compose日志链");
+}
+
+/// Heroku路由日志处理链
+///
+/// 处理 `heroku logs` 输出和12-factor应用的logfmt路由日志：
+/// 1. 路由日志解析 → 去除时间戳和来源前缀，解析logfmt字段，按状态码映射级别
+/// 2. ANSI清理 → 处理应用输出中的颜色
+/// 3. 度量提取 → 路由日志的连接/服务耗时已由解析器写入，这里补充其他数值字段
+///
+/// 该链由 `select_best_chain` 在检测到 `at=` / `dyno=` 特征时按格式评分选择（见 `detection::DETECTORS`）。
+fn register_heroku_chain(manager: &mut PluginChainManager, settings: &PluginSettings) {
+    let mut chain = PluginChain::new(
+        "heroku".to_string(),
+        "Heroku路由日志处理链，解析logfmt路由字段、dyno和请求耗时".to_string(),
+    );
+
+    // 设置执行条件
+    let mut conditions = ChainConditions::new();
+    conditions.file_patterns.push("heroku".to_string());
+    conditions.content_patterns.push("heroku[router]".to_string());
+    conditions.content_patterns.push("dyno=".to_string());
+    conditions.min_confidence = 0.8;
+    chain.conditions = Some(conditions);
+
+    // 添加过滤器
+    chain.add_filter(Arc::new(AnsiFilter::from_settings(settings.for_plugin("ansi"))));
+    chain.add_filter(Arc::new(HerokuRouterFilter));
+    chain.add_filter(Arc::new(JavaLogFilter));
+    chain.add_filter(Arc::new(ContentEnhancerFilter::from_settings(settings.for_plugin("content_enhancer"))));
+    chain.add_filter(Arc::new(MetricsFilter::from_settings(settings.for_plugin("metrics"))));
+    chain.add_filter(Arc::new(JsonStructureFilter));
+
+    manager.register_chain(chain);
+    info!("✅ 注册Heroku路由日志链");
+}
+
+/// AWS Lambda日志处理链
+///
+/// 处理Lambda函数写入CloudWatch的日志及其导出文件：
+/// 1. Lambda日志解析 → 去除导出时间戳，按START..REPORT分组调用，提取耗时和内存
+/// 2. ANSI清理 → 处理函数输出中的颜色
+/// 3. Java日志解析 → 识别Java运行时的异常堆栈
+///
+/// 该链由 `select_best_chain` 在检测到 `START/REPORT RequestId` 行时按格式评分选择（见 `detection::DETECTORS`）。
+fn register_lambda_chain(manager: &mut PluginChainManager, settings: &PluginSettings) {
+    let mut chain = PluginChain::new(
+        "lambda".to_string(),
+        "AWS Lambda日志处理链，按调用分组并提取REPORT中的耗时和内存".to_string(),
+    );
+
+    // 设置执行条件
+    let mut conditions = ChainConditions::new();
+    conditions.file_patterns.push("lambda".to_string());
+    conditions.file_patterns.push("cloudwatch".to_string());
+    conditions.content_patterns.push("START RequestId:".to_string());
+    conditions.content_patterns.push("REPORT RequestId:".to_string());
+    conditions.min_confidence = 0.8;
+    chain.conditions = Some(conditions);
+
+    // 添加过滤器
+    chain.add_filter(Arc::new(AnsiFilter::from_settings(settings.for_plugin("ansi"))));
+    chain.add_filter(Arc::new(LambdaLogFilter));
+    chain.add_filter(Arc::new(JavaLogFilter));
+    chain.add_filter(Arc::new(ContentEnhancerFilter::from_settings(settings.for_plugin("content_enhancer"))));
+    chain.add_filter(Arc::new(MetricsFilter::from_settings(settings.for_plugin("metrics"))));
+    chain.add_filter(Arc::new(JsonStructureFilter));
+
+    manager.register_chain(chain);
+    info!("✅ 注册AWS Lambda日志链");
+}
+
+/// Nginx错误日志处理链
+///
+/// 处理Nginx的 `error_log`（与访问日志格式不同）：
+/// 1. 错误日志解析 → 提取级别、进程号、连接号和 `client`/`upstream` 等上下文字段
+/// 2. 上游错误分类 → 写入 `error_class`，上游超时单独归类，错误聚类按此分组
+///
+/// 该链由 `select_best_chain` 在检测到错误日志行首格式时按格式评分选择（见 `detection::DETECTORS`）。
+fn register_nginx_error_chain(manager: &mut PluginChainManager, settings: &PluginSettings) {
+    let mut chain = PluginChain::new(
+        "nginx_error".to_string(),
+        "Nginx错误日志处理链，解析上游字段并对上游错误分类".to_string(),
+    );
+
+    // 设置执行条件
+    let mut conditions = ChainConditions::new();
+    conditions.file_patterns.push("error.log".to_string());
+    conditions.file_patterns.push("nginx".to_string());
+    conditions.content_patterns.push("while connecting to upstream".to_string());
+    conditions.content_patterns.push("upstream:".to_string());
+    conditions.min_confidence = 0.8;
+    chain.conditions = Some(conditions);
+
+    // 添加过滤器
+    chain.add_filter(Arc::new(NginxErrorFilter));
+    chain.add_filter(Arc::new(ContentEnhancerFilter::from_settings(settings.for_plugin("content_enhancer"))));
+    chain.add_filter(Arc::new(MetricsFilter::from_settings(settings.for_plugin("metrics"))));
+    chain.add_filter(Arc::new(JsonStructureFilter));
+
+    manager.register_chain(chain);
+    info!("✅ 注册Nginx错误日志链");
+}
+
+/// HAProxy日志处理链
+///
+/// 处理HAProxy的HTTP日志（`option httplog`）：
+/// 1. HTTP日志解析 → 提取前后端、服务器、五个计时器和状态码
+/// 2. 终止状态解释 → 把 `sH--` 之类的标志翻译为可读说明
+///
+/// 该链由 `select_best_chain` 在检测到HTTP日志格式时按格式评分选择（见 `detection::DETECTORS`）。
+fn register_haproxy_chain(manager: &mut PluginChainManager, settings: &PluginSettings) {
+    let mut chain = PluginChain::new(
+        "haproxy".to_string(),
+        "HAProxy日志处理链，解析计时器并解释终止状态".to_string(),
+    );
+
+    // 设置执行条件
+    let mut conditions = ChainConditions::new();
+    conditions.file_patterns.push("haproxy".to_string());
+    conditions.content_patterns.push("haproxy[".to_string());
+    conditions.min_confidence = 0.8;
+    chain.conditions = Some(conditions);
+
+    // 添加过滤器
+    chain.add_filter(Arc::new(HaproxyLogFilter));
+    chain.add_filter(Arc::new(ContentEnhancerFilter::from_settings(settings.for_plugin("content_enhancer"))));
+    chain.add_filter(Arc::new(JsonStructureFilter));
+
+    manager.register_chain(chain);
+    info!("✅ 注册HAProxy日志链");
+}
+
+/// Envoy/Istio访问日志处理链
+///
+/// 处理Envoy默认文本格式、Istio扩展格式和JSON格式的访问日志：
+/// 1. 访问日志解析 → 提取状态码、上游集群、耗时和请求ID
+/// 2. 响应标志解释 → 把 `UF`、`NR` 等标志翻译为可读说明，并据此确定级别
+///
+/// 该链由 `select_best_chain` 在检测到访问日志格式时按格式评分选择（见 `detection::DETECTORS`）。
+fn register_envoy_chain(manager: &mut PluginChainManager, settings: &PluginSettings) {
+    let mut chain = PluginChain::new(
+        "envoy".to_string(),
+        "Envoy/Istio访问日志处理链，解析上游字段并解释响应标志".to_string(),
+    );
+
+    // 设置执行条件
+    let mut conditions = ChainConditions::new();
+    conditions.file_patterns.push("envoy".to_string());
+    conditions.file_patterns.push("istio".to_string());
+    conditions.content_patterns.push("\"response_flags\"".to_string());
+    conditions.content_patterns.push("outbound|".to_string());
+    conditions.min_confidence = 0.8;
+    chain.conditions = Some(conditions);
+
+    // 添加过滤器
+    chain.add_filter(Arc::new(EnvoyAccessLogFilter));
+    chain.add_filter(Arc::new(ContentEnhancerFilter::from_settings(settings.for_plugin("content_enhancer"))));
+    chain.add_filter(Arc::new(JsonStructureFilter));
+
+    manager.register_chain(chain);
+    info!("✅ 注册Envoy访问日志链");
+}
+
+/// Kafka服务端日志处理链
+///
+/// 处理Kafka broker和controller的服务端日志：
+/// 1. Kafka日志解析 → 提取组件、类名和分区引用，标记ISR变化、再均衡等健康事件
+/// 2. Java日志解析 → 识别异常堆栈
+///
+/// 该链由 `select_best_chain` 在检测到Kafka日志行首和类名时按格式评分选择（见 `detection::DETECTORS`）。
+fn register_kafka_chain(manager: &mut PluginChainManager, settings: &PluginSettings) {
+    let mut chain = PluginChain::new(
+        "kafka".to_string(),
+        "Kafka服务端日志处理链，提取组件和分区并标记健康事件".to_string(),
+    );
+
+    // 设置执行条件
+    let mut conditions = ChainConditions::new();
+    conditions.file_patterns.push("kafka".to_string());
+    conditions.file_patterns.push("controller.log".to_string());
+    conditions.content_patterns.push("(kafka.".to_string());
+    conditions.min_confidence = 0.8;
+    chain.conditions = Some(conditions);
+
+    // 添加过滤器
+    chain.add_filter(Arc::new(KafkaLogFilter));
+    chain.add_filter(Arc::new(WrappedLineFilter));
+    chain.add_filter(Arc::new(JavaLogFilter));
+    chain.add_filter(Arc::new(ContentEnhancerFilter::from_settings(settings.for_plugin("content_enhancer"))));
+    chain.add_filter(Arc::new(MetricsFilter::from_settings(settings.for_plugin("metrics"))));
+    chain.add_filter(Arc::new(JsonStructureFilter));
+
+    manager.register_chain(chain);
+    info!("✅ 注册Kafka服务端日志链");
+}
+
+/// Elasticsearch服务端日志处理链
+///
+/// 处理Elasticsearch/OpenSearch的服务端日志和慢日志（方括号格式和JSON格式）：
+/// 1. 日志解析 → 提取节点、组件、索引和分片，标记GC和慢日志
+/// 2. 度量提取 → 慢日志的耗时已由解析器写入，慢操作分析可直接使用
+///
+/// 不使用Java日志过滤器：慢日志和GC日志的消息以 `[索引][分片]`、`[gc][young]` 开头，会被误当作Java日志的时间和级别。
+///
+/// 该链由 `select_best_chain` 在检测到Elasticsearch日志格式时按格式评分选择（见 `detection::DETECTORS`）。
+fn register_elasticsearch_chain(manager: &mut PluginChainManager, settings: &PluginSettings) {
+    let mut chain = PluginChain::new(
+        "elasticsearch".to_string(),
+        "Elasticsearch服务端日志处理链，提取节点和索引并标记GC与慢日志".to_string(),
+    );
+
+    // 设置执行条件
+    let mut conditions = ChainConditions::new();
+    conditions.file_patterns.push("elasticsearch".to_string());
+    conditions.file_patterns.push("opensearch".to_string());
+    conditions.file_patterns.push("slowlog".to_string());
+    conditions.content_patterns.push("][o.e.".to_string());
+    conditions.content_patterns.push("\"node.name\"".to_string());
+    conditions.min_confidence = 0.8;
+    chain.conditions = Some(conditions);
+
+    // 添加过滤器
+    chain.add_filter(Arc::new(ElasticsearchLogFilter));
+    chain.add_filter(Arc::new(ContentEnhancerFilter::from_settings(settings.for_plugin("content_enhancer"))));
+    chain.add_filter(Arc::new(MetricsFilter::from_settings(settings.for_plugin("metrics"))));
+    chain.add_filter(Arc::new(JsonStructureFilter));
+
+    manager.register_chain(chain);
+    info!("✅ 注册Elasticsearch服务端日志链");
+}
+
+/// IIS失败请求跟踪处理链
+///
+/// 处理IIS Failed Request Tracing生成的XML文件（`fr000001.xml`）：
+/// 1. XML展开 → 请求概要加上每个事件一条，记录事件名称、模块和状态码
+/// 2. 度量提取 → 每个步骤的耗时已由解析器写入，慢操作分析按事件名称分组
+///
+/// 该链由 `select_best_chain` 在检测到FREB根元素时按格式评分选择（见 `detection::DETECTORS`）。
+fn register_freb_chain(manager: &mut PluginChainManager, settings: &PluginSettings) {
+    let mut chain = PluginChain::new(
+        "freb".to_string(),
+        "IIS失败请求跟踪处理链，把FREB XML展开为带事件名称和耗时的时间线".to_string(),
+    );
+
+    // 设置执行条件
+    let mut conditions = ChainConditions::new();
+    conditions.file_patterns.push("fr0".to_string());
+    conditions.file_patterns.push("freb".to_string());
+    conditions.content_patterns.push("<failedRequest".to_string());
+    conditions.min_confidence = 0.8;
+    chain.conditions = Some(conditions);
+
+    // 添加过滤器
+    chain.add_filter(Arc::new(FrebTraceFilter));
+    chain.add_filter(Arc::new(MetricsFilter::from_settings(settings.for_plugin("metrics"))));
+    chain.add_filter(Arc::new(JsonStructureFilter));
+
+    manager.register_chain(chain);
+    info!("✅ 注册IIS失败请求跟踪链");
+}
+
+/// CEF/LEEF安全日志处理链
+///
+/// 处理SIEM和安全设备导出的CEF（Common Event Format）和LEEF事件：
+/// 1. ANSI清理 → 移除转发工具可能带上的颜色代码
+/// 2. 事件解析 → 头部的厂商、产品、签名ID和严重程度，扩展键值对写入元数据
+/// 3. 内容增强 → 识别消息中的IP、URL等
+///
+/// 该链由 `select_best_chain` 在检测到CEF/LEEF头部时按格式评分选择（见 `detection::DETECTORS`）。
+fn register_cef_chain(manager: &mut PluginChainManager, settings: &PluginSettings) {
+    let mut chain = PluginChain::new(
+        "cef".to_string(),
+        "CEF/LEEF安全日志处理链，解析头部字段和扩展键值对并按严重程度映射级别".to_string(),
+    );
+
+    // 设置执行条件
+    let mut conditions = ChainConditions::new();
+    conditions.file_patterns.push("cef".to_string());
+    conditions.file_patterns.push("leef".to_string());
+    conditions.file_patterns.push("siem".to_string());
+    conditions.content_patterns.push("CEF:".to_string());
+    conditions.content_patterns.push("LEEF:".to_string());
+    conditions.min_confidence = 0.8;
+    chain.conditions = Some(conditions);
+
+    // 添加过滤器
+    chain.add_filter(Arc::new(AnsiFilter::from_settings(settings.for_plugin("ansi"))));
+    chain.add_filter(Arc::new(CefLeefFilter));
+    chain.add_filter(Arc::new(ContentEnhancerFilter::from_settings(settings.for_plugin("content_enhancer"))));
+    chain.add_filter(Arc::new(MetricsFilter::from_settings(settings.for_plugin("metrics"))));
+    chain.add_filter(Arc::new(JsonStructureFilter));
+
+    manager.register_chain(chain);
+    info!("✅ 注册CEF/LEEF安全日志链");
+}
+
+/// 用户布局模式处理链
+///
+/// 按插件设置 `pattern_layout.pattern` 中的Logback/Log4j布局模式解析日志。
+/// 未配置布局时链保持禁用（仍然注册，以便界面获取该插件的配置项）；
+/// 配置后由 `select_best_chain` 在内容匹配布局时优先于格式评分选择。
+fn register_pattern_layout_chain(manager: &mut PluginChainManager, settings: &PluginSettings) {
+    let mut chain = PluginChain::new(
+        "pattern_layout".to_string(),
+        "布局模式处理链，按用户配置的Logback/Log4j布局模式解析日志".to_string(),
+    );
+
+    let filter = PatternLayoutFilter::from_settings(settings.for_plugin("pattern_layout"));
+    chain.enabled = filter.is_configured();
+
+    // 添加过滤器
+    chain.add_filter(Arc::new(AnsiFilter::from_settings(settings.for_plugin("ansi"))));
+    chain.add_filter(Arc::new(filter));
+    chain.add_filter(Arc::new(WrappedLineFilter));
+    chain.add_filter(Arc::new(MyBatisFilter));
+    chain.add_filter(Arc::new(ContentEnhancerFilter::from_settings(settings.for_plugin("content_enhancer"))));
+    chain.add_filter(Arc::new(MetricsFilter::from_settings(settings.for_plugin("metrics"))));
+
+    manager.register_chain(chain);
+    info!("✅ 注册布局模式链");
+}
+
 /// 自定义链构建器
 ///
 /// 提供便捷的API来构建自定义的插件链。
@@ -276,7 +802,7 @@ impl ChainBuilder {
 
     /// 添加Docker JSON过滤器
     pub fn with_docker_json(mut self) -> Self {
-        self.chain.add_filter(Arc::new(DockerJsonFilter));
+        self.chain.add_filter(Arc::new(DockerJsonFilter::default()));
         self
     }
 
@@ -292,6 +818,18 @@ impl ChainBuilder {
         self
     }
 
+    /// 添加回车覆盖行折叠过滤器
+    pub fn with_carriage_return(mut self) -> Self {
+        self.chain.add_filter(Arc::new(CarriageReturnFilter));
+        self
+    }
+
+    /// 添加ANSI转义序列过滤器
+    pub fn with_ansi(mut self) -> Self {
+        self.chain.add_filter(Arc::new(AnsiFilter::new()));
+        self
+    }
+
     /// 添加Java日志过滤器
     pub fn with_java_log(mut self) -> Self {
         self.chain.add_filter(Arc::new(JavaLogFilter));
@@ -300,7 +838,7 @@ impl ChainBuilder {
 
     /// 添加内容增强过滤器
     pub fn with_content_enhancer(mut self) -> Self {
-        self.chain.add_filter(Arc::new(ContentEnhancerFilter));
+        self.chain.add_filter(Arc::new(ContentEnhancerFilter::default()));
         self
     }
 
@@ -333,7 +871,7 @@ impl ChainBuilder {
 
 /// 获取推荐的链配置
 ///
-/// 根据日志内容和文件路径特征推荐最适合的链配置。
+/// 按日志内容样本的格式评分（见 `detection::score_formats`）推荐最适合的链配置，没有达到最低置信度的格式时推荐通用链。
 ///
 /// # 参数
 /// - `content`: 日志内容样本
@@ -341,44 +879,11 @@ impl ChainBuilder {
 ///
 /// # Returns
 /// - `String`: 推荐的链名称
-pub fn recommend_chain(content: &str, file_path: Option<&str>) -> String {
-    let content_lower = content.to_lowercase();
-    let _path_lower = file_path.unwrap_or("").to_lowercase();
-
-    // Docker容器日志特征 (最高优先级)
-    if content_lower.contains("{") &&
-       content_lower.contains("\"log\"") &&
-       content_lower.contains("\"stream\"") {
-        return "docker".to_string();
-    }
-
-    // 数据库SQL日志特征 (高优先级)
-    if content_lower.contains("preparing:") ||
-       content_lower.contains("parameters:") ||
-       content_lower.contains("==>") {
-        return "database".to_string();
-    }
-
-    // SpringBoot日志特征 (中高优先级) - 扩展检测范围
-    if content_lower.contains("spring") ||
-       content_lower.contains("application.start") ||
-       content_lower.contains("springframework") ||
-       content_lower.contains("com.example.") ||  // 常见的SpringBoot包名
-       content_lower.contains("http-nio-") ||     // Tomcat线程名
-       content_lower.contains("[main]") ||       // 主线程
-       (content_lower.contains("info") && content_lower.contains("error") && content_lower.contains("warn")) {
-        return "springboot".to_string();
-    }
-
-    // 微服务日志特征 (中等优先级) - 更严格的检测条件
-    if (content_lower.contains("trace") && content_lower.contains("span")) ||
-       (content_lower.contains("request") && content_lower.contains("service")) ||
-       (content_lower.contains("correlation") && content_lower.contains("id")) {
-        return "microservice".to_string();
-    }
-
-    // 默认返回通用链
-    "generic".to_string()
+pub fn recommend_chain(content: &str, _file_path: Option<&str>) -> String {
+    // 与 `PluginChainManager::select_best_chain` 使用相同的格式评分
+    detection::best_format(&detection::sample_lines(content))
+        .map(|score| score.chain)
+        .unwrap_or_else(|| "generic".to_string())
 }
 
 #[cfg(test)]
@@ -415,6 +920,26 @@ Another log line here"#;
         assert_eq!(recommended, "generic");
     }
 
+    #[test]
+    fn test_every_preset_filter_has_a_translated_description() {
+        let mut manager = PluginChainManager::new();
+        register_preset_chains(&mut manager);
+        let request = crate::plugins::ParseRequest::default();
+        // 已禁用的链（未配置布局模式）无法执行，其过滤器通过配置项Schema覆盖
+        let mut filters: Vec<String> = manager.settings_schemas().into_iter().map(|(name, _, _)| name).collect();
+        for chain in manager.get_available_chains() {
+            if let Ok(trace) = manager.explain(Some(&chain), "line", &request) {
+                filters.extend(trace.steps.into_iter().map(|step| step.filter));
+            }
+        }
+
+        let catalog = crate::i18n::catalog(crate::i18n::Locale::EnUs);
+        for filter in filters {
+            let code = format!("filter.{}", filter);
+            assert!(catalog.get(code.as_str()).is_some_and(|text| *text != code), "{}", code);
+        }
+    }
+
     #[test]
     fn test_chain_builder() {
         let chain = ChainBuilder::new(
@@ -112,7 +112,7 @@ impl LogParser for RawParser {
     /// 1. 逐行遍历日志内容，为每行创建LogLine结构
     /// 2. 行号处理：为每行分配从1开始的行号
     /// 3. 内容保留：完整保留原始文本内容，不做任何修改
-    /// 4. 元数据创建：创建空的元数据HashMap
+    /// 4. 元数据创建：创建空的元数据HashMap（请求 `preserve_blank_lines=false` 时跳过空行，行号不变）
     /// 5. 统一格式化：使用UnifiedFormatter确保输出格式一致
     /// 6. 结果封装：构建完整的ParseResult返回
     ///
@@ -132,7 +132,7 @@ impl LogParser for RawParser {
     ///
     /// # 参数
     /// - `content`: 要解析的完整文本内容
-    /// - `_request`: 解析请求参数（当前未使用）
+    /// - `request`: 解析请求参数（读取 `preserve_blank_lines` 和格式化风格，默认保留空行）
     ///
     /// # Returns
     /// - `Ok(ParseResult)`: 解析成功的结构化日志结果
@@ -161,10 +161,12 @@ impl LogParser for RawParser {
     /// - 不修改任何原始内容
     /// - 不添加任何解析偏见
     /// - 确保最大的兼容性和可靠性
-    fn parse(&self, content: &str, _request: &ParseRequest) -> Result<ParseResult, String> {
+    fn parse(&self, content: &str, request: &ParseRequest) -> Result<ParseResult, String> {
+        let preserve_blank_lines = request.preserve_blank_lines.unwrap_or(true);
         let lines: Vec<LogLine> = content
             .lines()
             .enumerate()
+            .filter(|(_, line)| preserve_blank_lines || !line.trim().is_empty())
             .map(|(i, line)| {
                 let metadata = HashMap::new();
 
@@ -178,7 +180,7 @@ impl LogParser for RawParser {
                     "raw"
                 );
 
-                let formatted_content = UnifiedFormatter::format_display_string(&unified_format);
+                let formatted_content = UnifiedFormatter::format_display_string(&unified_format, request.formatting.unwrap_or_default());
 
                 LogLine {
                     line_number: i + 1,
@@ -186,6 +188,7 @@ impl LogParser for RawParser {
                     level: unified_format.level.clone(),
                     timestamp: unified_format.timestamp.clone(),
                     formatted_content: Some(formatted_content),
+                    message: None,
                     metadata,
                     processed_by: vec!["raw_parser".to_string()],
                 }
@@ -197,6 +200,8 @@ impl LogParser for RawParser {
             total_lines: content.lines().count(),
             detected_format: Some("raw".to_string()),
             parsing_errors: Vec::new(),
+            applied_filters: Vec::new(),
+            capabilities: None,
         })
     }
 }
\ No newline at end of file
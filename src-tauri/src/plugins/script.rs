@@ -0,0 +1,302 @@
+/// 脚本钩子过滤器
+///
+/// 用户在插件设置中编写一段Rhai脚本，插件链处理完格式解析后对每个条目执行一次，
+/// 用于派生字段、改写消息或丢弃噪音行，比编写完整的插件更轻量。
+///
+/// # 脚本约定
+/// - 变量 `line` 是当前条目：`line_number`（只读）、`content`、`message`、`level`、`timestamp`、`metadata`
+///   （字符串键值对），未设置的字段为 `()`；脚本对 `line` 的修改会写回条目
+/// - 严格模式下原始内容保持不变，脚本对 `content` 的改写写入 `message`（见 `LogLine::set_message`）
+/// - 脚本的值为 `false` 时丢弃该条目
+///
+/// ```rhai
+/// if line.content.contains("/healthz") { return false; }
+/// if line.metadata.status == "503" { line.level = "ERROR"; }
+/// line.metadata.tenant = line.content.split("tenant=")[1];
+/// ```
+///
+/// # 执行预算
+/// 每个条目的执行步数不超过 `max_operations`，字符串、数组和对象大小也有上限；超出预算或出错的条目保持原样，
+/// 错误汇总到链的错误信息中。脚本不能导入模块，`print` / `debug` 只写入日志。
+
+use crate::i18n::Message;
+use crate::plugins::chain::{PluginChainContext, PluginFilter};
+use crate::plugins::settings::PluginOptions;
+use crate::plugins::{LogLine, ParseRequest};
+use log::{debug, info, warn};
+use rhai::{Dynamic, Engine, Map, Scope, AST};
+use serde_json::json;
+
+/// 每个条目默认的最大执行步数
+pub const DEFAULT_MAX_OPERATIONS: u64 = 10_000;
+
+/// 脚本中字符串的最大长度（字节）
+const MAX_STRING_SIZE: usize = 1024 * 1024;
+
+/// 脚本中数组和对象的最大元素数
+const MAX_COLLECTION_SIZE: usize = 10_000;
+
+/// 脚本函数的最大调用深度
+const MAX_CALL_LEVELS: usize = 32;
+
+/// 创建受限的脚本引擎
+///
+/// # 参数
+/// - `max_operations`: 每次执行的最大步数（0表示使用默认值）
+pub fn sandboxed_engine(max_operations: u64) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_module_resolver(rhai::module_resolvers::DummyModuleResolver::new());
+    engine.set_max_operations(if max_operations == 0 { DEFAULT_MAX_OPERATIONS } else { max_operations });
+    engine.set_max_string_size(MAX_STRING_SIZE);
+    engine.set_max_array_size(MAX_COLLECTION_SIZE);
+    engine.set_max_map_size(MAX_COLLECTION_SIZE);
+    engine.set_max_call_levels(MAX_CALL_LEVELS);
+    engine.on_print(|text| debug!("📜 脚本输出: {}", text));
+    engine.on_debug(|text, _, position| debug!("📜 脚本调试 {}: {}", position, text));
+    engine
+}
+
+/// 编译脚本，返回语法错误
+pub fn compile(source: &str) -> Result<AST, String> {
+    sandboxed_engine(0).compile(source).map_err(|e| e.to_string())
+}
+
+/// 可选字段转为脚本值（None为 `()`）
+fn optional(value: &Option<String>) -> Dynamic {
+    value.clone().map_or(Dynamic::UNIT, Dynamic::from)
+}
+
+/// 脚本值转为可选字段（`()` 为None，其他类型转为字符串）
+fn from_optional(value: Option<&Dynamic>) -> Option<String> {
+    value.filter(|value| !value.is_unit()).map(|value| value.to_string())
+}
+
+/// 条目转为脚本中的 `line` 对象
+fn to_map(line: &LogLine) -> Map {
+    let metadata: Map = line.metadata.iter()
+        .map(|(key, value)| (key.as_str().into(), Dynamic::from(value.clone())))
+        .collect();
+    let mut map = Map::new();
+    map.insert("line_number".into(), Dynamic::from(line.line_number as i64));
+    map.insert("content".into(), Dynamic::from(line.content.clone()));
+    map.insert("message".into(), optional(&line.message));
+    map.insert("level".into(), optional(&line.level));
+    map.insert("timestamp".into(), optional(&line.timestamp));
+    map.insert("metadata".into(), Dynamic::from(metadata));
+    map
+}
+
+/// 把脚本修改后的 `line` 对象写回条目
+///
+/// # 参数
+/// - `strict`: 是否为严格模式（改写的内容只写入 `message`）
+fn apply_map(line: &mut LogLine, map: &Map, strict: bool) {
+    line.message = from_optional(map.get("message"));
+    if let Some(content) = map.get("content").filter(|value| !value.is_unit()).map(|value| value.to_string()) {
+        if content != line.content {
+            line.set_message(content, strict);
+        }
+    }
+    line.level = from_optional(map.get("level"));
+    line.timestamp = from_optional(map.get("timestamp"));
+    if let Some(metadata) = map.get("metadata").and_then(|value| value.clone().try_cast::<Map>()) {
+        line.metadata = metadata.into_iter()
+            .filter(|(_, value)| !value.is_unit())
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+    }
+}
+
+/// 脚本钩子过滤器
+pub struct ScriptFilter {
+    engine: Engine,
+    ast: Option<AST>,
+}
+
+impl ScriptFilter {
+    /// 使用插件设置创建过滤器
+    ///
+    /// 脚本为空、已禁用或编译失败时不处理任何条目（保存设置时已校验语法，这里只记录警告）。
+    pub fn from_settings(options: PluginOptions) -> Self {
+        let source = options.str("script", "");
+        let ast = if options.bool("enabled", false) && !source.trim().is_empty() {
+            compile(source)
+                .inspect_err(|e| warn!("⚠️ 脚本钩子编译失败，已跳过: {}", e))
+                .ok()
+        } else {
+            None
+        };
+        Self { engine: sandboxed_engine(options.u64("max_operations", DEFAULT_MAX_OPERATIONS)), ast }
+    }
+
+    /// 对单个条目执行脚本
+    ///
+    /// # 参数
+    /// - `strict`: 是否为严格模式（见 `ParseRequest::is_strict`）
+    ///
+    /// # Returns
+    /// - `Ok(true)`: 保留条目（已写回脚本的修改）
+    /// - `Ok(false)`: 脚本要求丢弃条目
+    /// - `Err(String)`: 脚本出错或超出预算，条目保持原样
+    fn run(&self, ast: &AST, line: &mut LogLine, strict: bool) -> Result<bool, String> {
+        let mut scope = Scope::new();
+        scope.push("line", to_map(line));
+        let result = self.engine.eval_ast_with_scope::<Dynamic>(&mut scope, ast)
+            .map_err(|e| e.to_string())?;
+        if result.as_bool() == Ok(false) {
+            return Ok(false);
+        }
+        if let Some(map) = scope.get_value::<Map>("line") {
+            apply_map(line, &map, strict);
+        }
+        Ok(true)
+    }
+}
+
+impl Default for ScriptFilter {
+    fn default() -> Self {
+        Self::from_settings(PluginOptions::default())
+    }
+}
+
+impl PluginFilter for ScriptFilter {
+    fn name(&self) -> &str {
+        "script"
+    }
+
+    fn description(&self) -> &str {
+        "脚本钩子过滤器，对每个条目执行用户的Rhai脚本以派生字段、改写消息或丢弃噪音行"
+    }
+
+    fn priority(&self) -> i32 {
+        85 // 在内容增强之后、JSON结构化之前执行，脚本能看到所有解析出的字段
+    }
+
+    fn should_process(&self, context: &PluginChainContext) -> bool {
+        self.ast.is_some() && !context.current_lines.is_empty()
+    }
+
+    fn process(&self, context: &mut PluginChainContext, request: &ParseRequest) -> Result<(), String> {
+        let Some(ast) = &self.ast else {
+            return Ok(());
+        };
+        info!("📜 脚本钩子过滤器开始处理 {} 行", context.current_lines.len());
+
+        let lines = std::mem::take(&mut context.current_lines);
+        let mut kept = Vec::with_capacity(lines.len());
+        let mut dropped = 0;
+        let mut failures = Vec::new();
+        for mut line in lines {
+            let original = line.clone();
+            match self.run(ast, &mut line, request.is_strict()) {
+                Ok(true) => {
+                    line.processed_by.push("script_filter".to_string());
+                    kept.push(line);
+                }
+                Ok(false) => dropped += 1,
+                Err(e) => {
+                    failures.push((original.line_number, e));
+                    kept.push(original);
+                }
+            }
+        }
+        context.current_lines = kept;
+
+        if let Some((line_number, error)) = failures.first() {
+            context.add_error(Message::new("script.failed")
+                .with("count", failures.len())
+                .with("line", line_number)
+                .with("error", error)
+                .text());
+        }
+        info!("📜 脚本钩子过滤器处理完成，丢弃 {} 行，{} 行执行失败", dropped, failures.len());
+        Ok(())
+    }
+
+    fn can_handle(&self, _content: &str, _file_path: Option<&str>) -> bool {
+        true // 对任何内容都可以执行用户脚本
+    }
+
+    fn settings_schema(&self) -> Option<serde_json::Value> {
+        Some(json!({
+            "type": "object",
+            "properties": {
+                "enabled": { "type": "boolean", "default": false, "description": "对每个条目执行脚本" },
+                "script": { "type": "string", "format": "rhai", "default": "", "description": "Rhai脚本，变量 line 为当前条目，值为 false 时丢弃该条目" },
+                "max_operations": { "type": "integer", "default": DEFAULT_MAX_OPERATIONS, "description": "每个条目的最大执行步数" }
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+    use std::collections::HashMap;
+
+    fn filter(settings: Value) -> ScriptFilter {
+        let settings = crate::plugins::settings::PluginSettings::new(HashMap::from([("script".to_string(), settings)]));
+        ScriptFilter::from_settings(settings.for_plugin("script"))
+    }
+
+    fn line(line_number: usize, content: &str) -> LogLine {
+        LogLine {
+            level: Some("INFO".to_string()),
+            metadata: HashMap::from([("status".to_string(), "503".to_string())]),
+            ..LogLine::new(line_number, content)
+        }
+    }
+
+    #[test]
+    fn test_script_derives_fields_drops_lines_and_respects_budget() {
+        let script = r#"
+            if line.content.contains("/healthz") { return false; }
+            if line.content.contains("spin") { loop {} }
+            if line.metadata.status == "503" { line.level = "ERROR"; }
+            line.metadata.tenant = line.content.split("tenant=")[1];
+            line.metadata.remove("status");
+        "#;
+        let filter = filter(json!({ "enabled": true, "script": script, "max_operations": 1000 }));
+        let mut context = PluginChainContext::new("");
+        context.current_lines = vec![line(1, "GET /api tenant=acme"), line(2, "GET /healthz"), line(3, "spin tenant=x")];
+        assert!(filter.should_process(&context));
+        filter.process(&mut context, &ParseRequest::default()).unwrap();
+
+        let lines = &context.current_lines;
+        assert_eq!(lines.iter().map(|line| line.line_number).collect::<Vec<_>>(), vec![1, 3]);
+        assert_eq!(lines[0].level.as_deref(), Some("ERROR"));
+        assert_eq!(lines[0].metadata, HashMap::from([("tenant".to_string(), "acme".to_string())]));
+        // 超出预算的条目保持原样并记录错误
+        assert_eq!(lines[1].level.as_deref(), Some("INFO"));
+        assert!(lines[1].processed_by.is_empty());
+        assert!(context.errors[0].contains("第 3 行"));
+
+        // 未启用或语法错误时不处理
+        assert!(!filter_is_active(json!({ "script": script })));
+        assert!(!filter_is_active(json!({ "enabled": true, "script": "if (" })));
+        assert!(compile("if (").is_err());
+    }
+
+    #[test]
+    fn test_strict_mode_keeps_raw_content() {
+        let filter = filter(json!({ "enabled": true, "script": r#"line.content = "rewritten";"# }));
+        let mut context = PluginChainContext::new("");
+        context.current_lines = vec![line(1, "raw line")];
+        let strict = ParseRequest { strict_content: Some(true), ..Default::default() };
+        filter.process(&mut context, &strict).unwrap();
+        assert_eq!(context.current_lines[0].content, "raw line");
+        assert_eq!(context.current_lines[0].message.as_deref(), Some("rewritten"));
+
+        context.current_lines = vec![line(1, "raw line")];
+        filter.process(&mut context, &ParseRequest::default()).unwrap();
+        assert_eq!(context.current_lines[0].content, "rewritten");
+        assert_eq!(context.current_lines[0].message, None);
+    }
+
+    fn filter_is_active(settings: Value) -> bool {
+        let mut context = PluginChainContext::new("");
+        context.current_lines = vec![line(1, "x")];
+        filter(settings).should_process(&context)
+    }
+}
@@ -0,0 +1,196 @@
+/// 插件配置项机制
+///
+/// 过滤器通过 `PluginFilter::settings_schema` 声明自己的可配置项（JSON Schema），
+/// 用户设置持久化在 `PluginConfig.plugin_settings` 中（按插件名称索引），
+/// 构建插件链时通过 `PluginSettings` 把对应的设置交给各个过滤器。
+///
+/// # 支持的Schema子集
+/// - 顶层为 `{"type": "object", "properties": {...}}`
+/// - 属性类型：`boolean`、`integer`、`number`、`string`
+/// - 字符串属性可声明 `"format": "regex"`，保存时会校验正则表达式
+/// - 字符串属性可声明 `"format": "pattern_layout"`，保存时会校验Logback/Log4j布局模式
+/// - 字符串属性可声明 `"format": "rhai"`，保存时会编译Rhai脚本
+/// - 属性的 `default` 即过滤器的内置默认值
+
+use crate::i18n::Message;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// 单个插件的配置项描述
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginSettingsSchema {
+    /// 插件（过滤器）名称
+    pub plugin: String,
+
+    /// 插件描述
+    pub description: String,
+
+    /// 配置项的JSON Schema
+    pub schema: Value,
+
+    /// 当前生效的设置值（未设置时为空对象）
+    pub values: Value,
+}
+
+/// 所有插件的设置
+///
+/// 对 `PluginConfig.plugin_settings` 的只读包装，构建插件链时使用。
+#[derive(Debug, Clone, Default)]
+pub struct PluginSettings {
+    values: HashMap<String, Value>,
+}
+
+impl PluginSettings {
+    /// 从持久化的插件设置创建
+    ///
+    /// # 参数
+    /// - `values`: 插件名称到设置对象的映射
+    pub fn new(values: HashMap<String, Value>) -> Self {
+        Self { values }
+    }
+
+    /// 获取指定插件的设置
+    pub fn for_plugin(&self, plugin: &str) -> PluginOptions<'_> {
+        PluginOptions {
+            values: self.values.get(plugin).and_then(Value::as_object),
+        }
+    }
+}
+
+/// 单个插件的设置视图，读取时缺失或类型不符的项返回默认值
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PluginOptions<'a> {
+    values: Option<&'a serde_json::Map<String, Value>>,
+}
+
+impl<'a> PluginOptions<'a> {
+    fn get(&self, key: &str) -> Option<&'a Value> {
+        self.values.and_then(|values| values.get(key))
+    }
+
+    /// 读取布尔设置
+    pub fn bool(&self, key: &str, default: bool) -> bool {
+        self.get(key).and_then(Value::as_bool).unwrap_or(default)
+    }
+
+    /// 读取字符串设置
+    pub fn str(&self, key: &str, default: &'a str) -> &'a str {
+        self.get(key).and_then(Value::as_str).unwrap_or(default)
+    }
+
+    /// 读取非负整数设置
+    pub fn u64(&self, key: &str, default: u64) -> u64 {
+        self.get(key).and_then(Value::as_u64).unwrap_or(default)
+    }
+}
+
+/// 按Schema校验插件设置
+///
+/// # 参数
+/// - `schema`: 插件声明的JSON Schema
+/// - `settings`: 待保存的设置对象
+///
+/// # Returns
+/// - `Ok(())`: 设置合法
+/// - `Err(String)`: 未知配置项、类型不符或正则无效时的错误信息
+pub fn validate_settings(schema: &Value, settings: &Value) -> Result<(), String> {
+    let settings = settings.as_object().ok_or_else(|| Message::new("plugin_settings.not_object").text())?;
+    let properties = schema.get("properties").and_then(Value::as_object);
+
+    for (key, value) in settings {
+        let property = properties
+            .and_then(|p| p.get(key))
+            .ok_or_else(|| Message::new("plugin_settings.unknown_option").with("key", key).text())?;
+
+        let expected = property.get("type").and_then(Value::as_str).unwrap_or("string");
+        let type_matches = match expected {
+            "boolean" => value.is_boolean(),
+            "integer" => value.is_u64() || value.is_i64(),
+            "number" => value.is_number(),
+            "string" => value.is_string(),
+            _ => true,
+        };
+        if !type_matches {
+            return Err(Message::new("plugin_settings.wrong_type").with("key", key).with("expected", expected).text());
+        }
+
+        if property.get("format").and_then(Value::as_str) == Some("regex") {
+            let pattern = value.as_str().unwrap_or_default();
+            regex::Regex::new(pattern)
+                .map_err(|e| Message::new("plugin_settings.invalid_regex").with("key", key).with("error", e).text())?;
+        }
+
+        if property.get("format").and_then(Value::as_str) == Some("pattern_layout") {
+            let pattern = value.as_str().unwrap_or_default();
+            if !pattern.trim().is_empty() {
+                crate::plugins::pattern_layout::PatternLayout::compile(pattern)
+                    .map_err(|e| Message::new("plugin_settings.invalid_layout").with("key", key).with("error", e).text())?;
+            }
+        }
+
+        if property.get("format").and_then(Value::as_str) == Some("rhai") {
+            crate::plugins::script::compile(value.as_str().unwrap_or_default())
+                .map_err(|e| Message::new("plugin_settings.invalid_script").with("key", key).with("error", e).text())?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "enabled": { "type": "boolean", "default": true },
+                "pattern": { "type": "string", "format": "regex", "default": "^x" }
+            }
+        })
+    }
+
+    #[test]
+    fn test_options_fall_back_to_defaults() {
+        let mut values = HashMap::new();
+        values.insert("demo".to_string(), json!({ "enabled": false, "pattern": 3 }));
+        let settings = PluginSettings::new(values);
+
+        let options = settings.for_plugin("demo");
+        assert!(!options.bool("enabled", true));
+        assert_eq!(options.str("pattern", "^x"), "^x");
+        assert!(settings.for_plugin("other").bool("enabled", true));
+    }
+
+    #[test]
+    fn test_validate_settings() {
+        assert!(validate_settings(&schema(), &json!({ "enabled": false })).is_ok());
+        assert!(validate_settings(&schema(), &json!({ "unknown": 1 })).is_err());
+        assert!(validate_settings(&schema(), &json!({ "enabled": "no" })).is_err());
+        assert!(validate_settings(&schema(), &json!({ "pattern": "(" })).is_err());
+        assert!(validate_settings(&schema(), &json!([])).is_err());
+    }
+
+    #[test]
+    fn test_preset_chains_receive_settings() {
+        use crate::plugins::chain::PluginChainManager;
+        use crate::plugins::presets::register_preset_chains_with_settings;
+        use crate::plugins::ParseRequest;
+
+        let mut values = HashMap::new();
+        values.insert("docker_json".to_string(), json!({ "strip_gc_prefix": false }));
+        let mut manager = PluginChainManager::new();
+        register_preset_chains_with_settings(&mut manager, &PluginSettings::new(values));
+
+        let names: Vec<String> = manager.settings_schemas().into_iter().map(|(name, _, _)| name).collect();
+        assert_eq!(names, vec!["ansi", "content_enhancer", "docker_json", "metrics", "pattern_layout", "script"]);
+
+        let content = r#"{"log":"[0.001s][warning][gc] deprecated\n","stream":"stdout","time":"2024-01-01T00:00:00Z"}"#;
+        let result = manager.process(content, &ParseRequest::default()).unwrap();
+        assert_eq!(result.lines[0].level.as_deref(), Some("WARN"));
+        assert_eq!(result.lines[0].formatted_content.as_deref(), Some("[0.001s][warning][gc] deprecated"));
+    }
+}
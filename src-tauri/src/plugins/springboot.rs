@@ -22,7 +22,11 @@
 /// - 快速级别标准化，避免不必要的字符串操作
 /// - 智能格式化选择，平衡信息完整性和显示效果
 
-use crate::plugins::{LogParser, ParseRequest, ParseResult, LogLine};
+use crate::plugins::compact::{ThreadCompactor, THREAD_SHORT_METADATA_KEY};
+use crate::plugins::formatter::canonical_level;
+use crate::plugins::{detection, LogParser, ParseRequest, ParseResult, LogLine};
+use crate::utils::string_utils;
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::time::Instant;
 use regex::Regex;
@@ -127,6 +131,11 @@ impl LogParser for SpringBootParser {
         false
     }
 
+    /// 按样本行的结构匹配计算置信度（见 `detection::springboot_confidence`）
+    fn confidence(&self, content: &str, _file_path: Option<&str>) -> f32 {
+        detection::springboot_confidence(&detection::sample_lines(content))
+    }
+
     /// 执行SpringBoot日志解析
     ///
     /// 这是SpringBoot插件的核心解析功能，将原始日志内容转换为结构化的LogLine列表。
@@ -142,7 +151,7 @@ impl LogParser for SpringBootParser {
     ///
     /// # 参数
     /// - `content`: 要解析的完整日志内容
-    /// - `_request`: 解析请求参数（当前未使用）
+    /// - `request`: 解析请求参数（使用其中的紧凑显示设置，未设置时使用默认缩略规则）
     ///
     /// # Returns
     /// - `Ok(ParseResult)`: 解析成功的结构化日志结果
@@ -158,8 +167,10 @@ impl LogParser for SpringBootParser {
     /// - 非致命错误继续处理其他行
     /// - 收集解析错误但不中断处理
     /// - 提供详细的性能统计信息
-    fn parse(&self, content: &str, _request: &ParseRequest) -> Result<ParseResult, String> {
+    fn parse(&self, content: &str, request: &ParseRequest) -> Result<ParseResult, String> {
         let parse_start = Instant::now();
+        let compactor = request.compact.as_ref().map(ThreadCompactor::new);
+        let compactor = compactor.as_ref().unwrap_or_else(|| ThreadCompactor::default_rules());
         let total_lines = content.lines().count();
         let mut lines = Vec::with_capacity(total_lines);
         let parsing_errors = Vec::new();
@@ -200,6 +211,9 @@ impl LogParser for SpringBootParser {
                 // 添加元数据
                 if let Some(t) = thread {
                     metadata.insert("thread".to_string(), t.to_string());
+                    if compactor.enabled() {
+                        metadata.insert(THREAD_SHORT_METADATA_KEY.to_string(), compactor.compact(t));
+                    }
                 }
                 if let Some(l) = logger {
                     metadata.insert("logger".to_string(), l.to_string());
@@ -210,13 +224,14 @@ impl LogParser for SpringBootParser {
 
                 let format_start = Instant::now();
                 // 智能选择格式化方式：紧凑格式用于减少冗余信息
-                let formatted_content = if should_use_compact_format(thread, logger, message) {
+                let formatted_content = if compactor.enabled() && should_use_compact_format(thread, logger, message) {
                     build_compact_formatted_content(
                         normalized_timestamp.as_deref(),
                         normalized_level.as_deref(),
                         thread,
                         logger,
-                        message
+                        message,
+                        compactor,
                     )
                 } else {
                     build_formatted_content_fast(
@@ -231,10 +246,11 @@ impl LogParser for SpringBootParser {
                 let final_string_start = Instant::now();
                 lines.push(LogLine {
                     line_number: line_num,
-                    content: message.to_string(),
-                    level: normalized_level.clone(),
+                    content: if request.is_strict() { line.to_string() } else { message.to_string() },
+                    level: normalized_level.as_deref().map(str::to_string),
                     timestamp: normalized_timestamp.clone(),
                     formatted_content: Some(formatted_content),
+                    message: request.is_strict().then(|| message.to_string()),
                     metadata,
                     processed_by: vec!["springboot_parser".to_string()],
                 });
@@ -262,6 +278,7 @@ impl LogParser for SpringBootParser {
                     level: Some("ERROR".to_string()), // 堆栈跟踪标记为错误级别
                     timestamp: None, // 堆栈跟踪没有时间戳
                     formatted_content: Some(formatted_content),
+                    message: None,
                     metadata,
                     processed_by: vec!["springboot_parser".to_string()],
                 });
@@ -287,6 +304,8 @@ impl LogParser for SpringBootParser {
             total_lines,
             detected_format: Some("springboot".to_string()),
             parsing_errors,
+            applied_filters: Vec::new(),
+            capabilities: None,
         })
     }
 }
@@ -377,27 +396,14 @@ fn normalize_timestamp_fast(timestamp: Option<&str>) -> Option<String> {
 ///
 /// # 示例
 /// ```rust
-/// assert_eq!(normalize_level_fast(Some("error")), Some("ERROR".to_string()));
-/// assert_eq!(normalize_level_fast(Some("WARN")), Some("WARN".to_string()));
-/// assert_eq!(normalize_level_fast(Some("trace")), Some("DEBUG".to_string()));
-/// assert_eq!(normalize_level_fast(Some("custom")), Some("CUSTOM".to_string()));
+/// assert_eq!(normalize_level_fast(Some("error")).as_deref(), Some("ERROR"));
+/// assert_eq!(normalize_level_fast(Some("WARN")).as_deref(), Some("WARN"));
+/// assert_eq!(normalize_level_fast(Some("trace")).as_deref(), Some("DEBUG"));
+/// assert_eq!(normalize_level_fast(Some("custom")).as_deref(), Some("CUSTOM"));
 /// ```
-fn normalize_level_fast(level: Option<&str>) -> Option<String> {
-    level.map(|l| {
-        // 避免to_uppercase()，直接进行字符比较
-        match l {
-            "ERROR" | "ERR" | "FATAL" | "SEVERE" |
-            "error" | "err" | "fatal" | "severe" => "ERROR".to_string(),
-            "WARN" | "WARNING" | "ALERT" |
-            "warn" | "warning" | "alert" => "WARN".to_string(),
-            "INFO" | "INFORMATION" | "NOTE" |
-            "info" | "information" | "note" => "INFO".to_string(),
-            "DEBUG" | "TRACE" | "VERBOSE" |
-            "debug" | "trace" | "verbose" => "DEBUG".to_string(),
-            // 如果不匹配常见级别，才进行to_uppercase转换
-            _ => l.to_uppercase(),
-        }
-    })
+fn normalize_level_fast(level: Option<&str>) -> Option<Cow<'static, str>> {
+    // 已知级别返回静态字符串，只有不匹配常见级别时才分配
+    level.map(canonical_level)
 }
 
 /// 根据日志级别确定输出流类型
@@ -547,6 +553,7 @@ fn build_formatted_content_fast(
 /// - `thread`: 线程名称的可选引用
 /// - `logger`: Logger类名的可选引用
 /// - `message`: 日志消息内容的引用
+/// - `compactor`: 线程名缩略规则
 ///
 /// # Returns
 /// - `String`: 智能缩略格式化后的日志字符串
@@ -566,7 +573,8 @@ fn build_compact_formatted_content(
     level: Option<&str>,
     thread: Option<&str>,
     logger: Option<&str>,
-    message: &str
+    message: &str,
+    compactor: &ThreadCompactor,
 ) -> String {
     let mut result = String::new();
 
@@ -588,7 +596,7 @@ fn build_compact_formatted_content(
     result.push_str("] ");
 
     // 智能缩略显示 - 只显示关键信息
-    let compact_info = build_compact_prefix(thread, logger);
+    let compact_info = build_compact_prefix(thread, logger, compactor);
     if !compact_info.is_empty() {
         result.push_str(&compact_info);
         result.push_str(" | ");
@@ -672,10 +680,10 @@ fn should_use_compact_format(thread: Option<&str>, logger: Option<&str>, message
 /// 使用 " · " 分隔符连接各个部分，保持视觉上的清晰度。
 ///
 /// # 缩略规则
-/// - **线程名缩略**：调用`compact_thread_name`函数处理
+/// - **线程名缩略**：按解析配置中的缩略规则处理（见 `ThreadCompactor`）
 ///   - 常见线程名进行模式化缩略（如http-nio-8080-exec-1 → H80801）
 ///   - 主线程（main）不显示，减少冗余
-///   - 过长线程名进行截断处理
+///   - 没有规则匹配的过长线程名进行截断处理
 ///
 /// - **类名缩略**：调用`compact_class_name`函数处理
 ///   - 长包名进行首字母缩略（如com.example.service → c.e.service）
@@ -691,6 +699,7 @@ fn should_use_compact_format(thread: Option<&str>, logger: Option<&str>, message
 /// # 参数
 /// - `thread`: 线程名称的可选引用
 /// - `logger`: Logger类名的可选引用
+/// - `compactor`: 线程名缩略规则
 ///
 /// # Returns
 /// - `String`: 缩略后的前缀字符串，可能为空
@@ -711,12 +720,12 @@ fn should_use_compact_format(thread: Option<&str>, logger: Option<&str>, message
 /// - 空字符串结果会被过滤掉，不参与最终拼接
 /// - 如果所有部分都被过滤，函数返回空字符串
 /// - 这避免了无意义的分隔符显示
-fn build_compact_prefix(thread: Option<&str>, logger: Option<&str>) -> String {
+fn build_compact_prefix(thread: Option<&str>, logger: Option<&str>, compactor: &ThreadCompactor) -> String {
     let mut parts = Vec::new();
 
     // 缩略线程名 - 只保留关键部分
     if let Some(t) = thread {
-        let compact_thread = compact_thread_name(t);
+        let compact_thread = compactor.compact(t);
         if !compact_thread.is_empty() {
             parts.push(compact_thread);
         }
@@ -733,123 +742,6 @@ fn build_compact_prefix(thread: Option<&str>, logger: Option<&str>) -> String {
     parts.join(" · ")
 }
 
-/// 智能缩略线程名称
-///
-/// 针对SpringBoot应用中常见的线程命名模式进行智能缩略，保持可识别性的同时显著减少显示长度。
-/// 支持多种常见的线程类型，包括HTTP服务器线程、工作线程、定时任务线程等。
-///
-/// # 缩略规则
-///
-/// ## 1. 特殊线程处理
-/// - `main` → 空字符串（主线程不显示，减少冗余）
-///
-/// ## 2. HTTP服务器线程
-/// - `http-nio-8080-exec-1` → `H80801`
-/// - `http-nio-8080-exec-10` → `H808010`
-/// - `nio-8080-exec-1` → `H80801`
-///
-/// ## 3. 工作线程
-/// - `worker-thread-1` → `W1`
-/// - `worker-thread-10` → `W10`
-///
-/// ## 4. Redis连接线程
-/// - `redis-thread-1` → `R1`
-/// - `redis-thread-2` → `R2`
-///
-/// ## 5. 定时任务线程
-/// - `scheduling-1` → `S1`
-/// - `scheduling-10` → `S10`
-///
-/// ## 6. 其他线程
-/// - 长度超过15字符的线程名 → 截断前12字符 + "..."
-/// - 其他线程名 → 保持原样
-///
-/// # 缩略策略
-/// - **可读性优先**：缩略后仍能识别线程类型和编号
-/// - **一致性原则**：相同类型的线程使用相同的缩略规则
-/// - **简洁高效**：最大程度减少显示长度
-/// - **模式识别**：基于SpringBoot/common框架的线程命名约定
-///
-/// # 参数
-/// - `thread`: 原始线程名称的字符串引用
-///
-/// # Returns
-/// - `String`: 缩略后的线程名称，可能为空字符串
-///
-/// # 实际应用示例
-/// ```rust
-/// assert_eq!(compact_thread_name("main"), "");                    // 主线程隐藏
-/// assert_eq!(compact_thread_name("http-nio-8080-exec-1"), "H80801");  // HTTP线程
-/// assert_eq!(compact_thread_name("worker-thread-5"), "W5");      // 工作线程
-/// assert_eq!(compact_thread_name("redis-thread-2"), "R2");       // Redis线程
-/// assert_eq!(compact_thread_name("scheduling-1"), "S1");         // 定时任务线程
-/// assert_eq!(compact_thread_name("very-long-thread-name"), "very-long-th..."); // 截断
-/// ```
-fn compact_thread_name(thread: &str) -> String {
-    // 常见线程名的缩略映射
-    match thread {
-        "main" => String::new(), // 主线程不显示
-        t if t.starts_with("http-nio-") => {
-            // http-nio-8080-exec-1 -> H80801
-            if let Some(exec_pos) = t.find("-exec-") {
-                let prefix = &t[9..exec_pos]; // 提取端口号
-                if let Some(exec_num) = t.get(exec_pos + 5..exec_pos + 6) {
-                    format!("H{}{}", prefix, exec_num)
-                } else {
-                    format!("H{}", prefix)
-                }
-            } else {
-                String::new()
-            }
-        }
-        t if t.starts_with("nio-") => {
-            // nio-8080-exec-1 -> H80801
-            if let Some(exec_pos) = t.find("-exec-") {
-                let prefix = &t[4..exec_pos]; // 提取端口号
-                if let Some(exec_num) = t.get(exec_pos + 5..exec_pos + 6) {
-                    format!("H{}{}", prefix, exec_num)
-                } else {
-                    format!("H{}", prefix)
-                }
-            } else {
-                String::new()
-            }
-        }
-        t if t.starts_with("worker-thread-") => {
-            // worker-thread-1 -> W1
-            if let Some(num) = t.strip_prefix("worker-thread-") {
-                format!("W{}", num)
-            } else {
-                String::new()
-            }
-        }
-        t if t.starts_with("redis-thread-") => {
-            // redis-thread-1 -> R1
-            if let Some(num) = t.strip_prefix("redis-thread-") {
-                format!("R{}", num)
-            } else {
-                String::new()
-            }
-        }
-        t if t.starts_with("scheduling-") => {
-            // scheduling-1 -> S1
-            if let Some(num) = t.strip_prefix("scheduling-") {
-                format!("S{}", num)
-            } else {
-                String::new()
-            }
-        }
-        _ => {
-            // 其他线程名 - 截断过长名称
-            if thread.len() > 15 {
-                format!("{}...", &thread[..12])
-            } else {
-                thread.to_string()
-            }
-        }
-    }
-}
-
 /// 智能缩略Java类名
 ///
 /// 对完整的Java类名进行智能缩略处理，减少包名的冗余显示，同时保持关键信息的可读性。
@@ -931,16 +823,16 @@ fn compact_class_name(class_name: &str) -> String {
                 result.push_str(part);
                 result.push('.');
             } else {
-                result.push_str(&part[..1]);
+                result.push_str(string_utils::prefix_chars(part, 1));
                 result.push('.');
             }
         }
 
         // 类名缩略
         if let Some(last_part) = parts.last() {
-            if last_part.len() > 8 {
+            if last_part.chars().nth(8).is_some() {
                 // 过长的类名取前几个字符
-                result.push_str(&last_part[..6]);
+                result.push_str(string_utils::prefix_chars(last_part, 6));
                 result.push_str("..");
             } else {
                 result.push_str(last_part);
@@ -1188,7 +1080,7 @@ mod springboot_format_tests {
         println!("  紧凑: {}", first_line.formatted_content.as_ref().unwrap());
 
         // 验证包含缩略的线程名和类名
-        assert!(first_line.formatted_content.as_ref().unwrap().contains("H8080-")); // nio-8080-exec-1
+        assert!(first_line.formatted_content.as_ref().unwrap().contains("H80801")); // nio-8080-exec-1
         assert!(first_line.formatted_content.as_ref().unwrap().contains("s.i.HolidayAnalyzeAttachmentsServiceImpl")); // 类名
 
         // 验证第四行不使用紧凑格式（main线程不显示）
@@ -0,0 +1,623 @@
+/// 测试运行器输出解析
+///
+/// 识别 `cargo test`、`go test -v` 和 `pytest` 的输出，为每条日志标记所属的测试名称和结果，
+/// 并将失败测试的输出块（cargo的 `---- name stdout ----`、go的 `=== RUN` 区间、
+/// pytest的 `____ name ____` 区段）归属到对应的测试，便于CI日志排查。
+///
+/// # 元数据
+/// - `test_framework`: cargo / go / pytest
+/// - `test_name`: 测试名称
+/// - `test_status`: passed / failed / skipped（测试结果行）
+/// - `test_duration_ms`: 测试耗时（go测试结果行）
+/// - `test_output`: "true" 表示该行是测试的捕获输出，"header" 表示输出块标题行
+/// - `test_summary`: "true"，表示该行是整个测试运行的汇总行
+
+use crate::plugins::capabilities::{PerformanceRating, PluginCapabilities};
+use crate::plugins::chain::{PluginChain, PluginChainContext, PluginFilter};
+use crate::plugins::filters::AnsiFilter;
+use crate::plugins::{LogLine, ParseRequest};
+use log::info;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// 能处理的最大测试输出（512MB）
+const MAX_TEST_OUTPUT_SIZE: u64 = 512 * 1024 * 1024;
+
+/// cargo test 结果行：`test module::name ... ok`
+static CARGO_TEST: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^test (\S+) \.\.\. (ok|FAILED|ignored)\b").unwrap()
+});
+
+/// cargo test 捕获输出块标题：`---- module::name stdout ----`
+static CARGO_OUTPUT_HEADER: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^---- (\S+) (?:stdout|stderr) ----$").unwrap()
+});
+
+/// cargo test 汇总行：`test result: FAILED. 1 passed; 1 failed; 0 ignored; ...`
+static CARGO_RESULT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^test result: (ok|FAILED)\. (\d+) passed; (\d+) failed; (\d+) ignored").unwrap()
+});
+
+/// go test 开始/恢复执行行：`=== RUN   TestName`
+static GO_RUN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^=== (?:RUN|CONT)\s+(\S+)$").unwrap()
+});
+
+/// go test 结果行：`--- FAIL: TestName (0.01s)`
+static GO_RESULT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\s*--- (PASS|FAIL|SKIP): (\S+) \(([\d.]+)s\)$").unwrap()
+});
+
+/// go test 包汇总行：`ok  \texample.com/pkg\t0.012s` / `FAIL\texample.com/pkg\t0.012s`
+static GO_PACKAGE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(ok|FAIL)\s+(\S+)\s+(?:[\d.]+s|\(cached\))").unwrap()
+});
+
+/// pytest -v 结果行：`tests/test_x.py::test_a PASSED   [ 50%]`
+static PYTEST_VERBOSE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(\S+::\S+) (PASSED|FAILED|SKIPPED|ERROR|XFAIL|XPASS)\b").unwrap()
+});
+
+/// pytest 简要汇总行：`FAILED tests/test_x.py::test_a - AssertionError`
+static PYTEST_SHORT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(FAILED|ERROR) (\S+::\S+)").unwrap()
+});
+
+/// pytest 失败详情区段标题：`_____ test_a _____`
+static PYTEST_SECTION: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^_{3,} (\S.*?) _{3,}$").unwrap()
+});
+
+/// pytest 汇总行：`===== 1 failed, 2 passed in 0.12s =====`
+static PYTEST_RESULT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^=+ (.*\b(?:passed|failed|errors?|skipped)\b.*) in [\d.]+s.*=+$").unwrap()
+});
+
+/// 汇总行中的 `N passed` / `N failed` 等计数
+static PYTEST_COUNT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(\d+) (passed|failed|errors?|skipped)").unwrap()
+});
+
+/// 测试结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TestStatus {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+impl TestStatus {
+    fn as_str(&self) -> &'static str {
+        match self {
+            TestStatus::Passed => "passed",
+            TestStatus::Failed => "failed",
+            TestStatus::Skipped => "skipped",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "passed" => Some(TestStatus::Passed),
+            "failed" => Some(TestStatus::Failed),
+            "skipped" => Some(TestStatus::Skipped),
+            _ => None,
+        }
+    }
+
+    /// 测试结果对应的日志级别
+    fn level(&self) -> &'static str {
+        match self {
+            TestStatus::Passed => "INFO",
+            TestStatus::Failed => "ERROR",
+            TestStatus::Skipped => "WARN",
+        }
+    }
+}
+
+/// 测试运行器输出过滤器
+///
+/// # 链中位置
+/// 优先级20：在ANSI清理之后执行，与SpringBoot等格式解析器同级。
+pub struct TestRunnerFilter;
+
+/// 逐行解析时的状态
+#[derive(Default)]
+struct ParseState {
+    /// 当前捕获输出所属的测试
+    current_test: Option<String>,
+    /// 当前识别到的测试框架
+    framework: Option<&'static str>,
+    /// pytest 是否处于 FAILURES/ERRORS 详情区
+    in_pytest_failures: bool,
+    /// go 当前测试是否已输出结果行（之后只有缩进行属于该测试）
+    go_result_seen: bool,
+}
+
+impl TestRunnerFilter {
+    /// 行是否为测试运行器的测试行或结果行
+    pub fn matches_line(line: &str) -> bool {
+        CARGO_TEST.is_match(line) || CARGO_RESULT.is_match(line) ||
+        GO_RUN.is_match(line) || GO_RESULT.is_match(line) ||
+        PYTEST_VERBOSE.is_match(line) || PYTEST_RESULT.is_match(line)
+    }
+
+    /// 内容是否像测试运行器输出
+    pub fn looks_like_test_output(content: &str) -> bool {
+        content.lines().take(2000).any(Self::matches_line)
+    }
+
+    /// 标记单行，返回是否识别为测试相关行
+    fn tag_line(line: &mut LogLine, state: &mut ParseState) -> bool {
+        let text = line.message_text().trim_end().to_string();
+
+        // ---------- cargo test ----------
+        if let Some(caps) = CARGO_TEST.captures(&text) {
+            let status = match &caps[2] {
+                "ok" => TestStatus::Passed,
+                "FAILED" => TestStatus::Failed,
+                _ => TestStatus::Skipped,
+            };
+            state.framework = Some("cargo");
+            state.current_test = None;
+            Self::mark_result(line, "cargo", &caps[1], status);
+            return true;
+        }
+        if let Some(caps) = CARGO_OUTPUT_HEADER.captures(&text) {
+            state.framework = Some("cargo");
+            state.current_test = Some(caps[1].to_string());
+            Self::mark_output_header(line, "cargo", &caps[1]);
+            return true;
+        }
+        if let Some(caps) = CARGO_RESULT.captures(&text) {
+            state.current_test = None;
+            let status = if &caps[1] == "ok" { TestStatus::Passed } else { TestStatus::Failed };
+            Self::mark_summary(line, "cargo", status);
+            return true;
+        }
+        if state.framework == Some("cargo") && text == "failures:" {
+            state.current_test = None;
+            return true;
+        }
+
+        // ---------- go test ----------
+        if let Some(caps) = GO_RUN.captures(&text) {
+            state.framework = Some("go");
+            state.current_test = Some(caps[1].to_string());
+            state.go_result_seen = false;
+            line.metadata.insert("test_framework".to_string(), "go".to_string());
+            line.metadata.insert("test_name".to_string(), caps[1].to_string());
+            return true;
+        }
+        if let Some(caps) = GO_RESULT.captures(&text) {
+            let status = match &caps[1] {
+                "PASS" => TestStatus::Passed,
+                "FAIL" => TestStatus::Failed,
+                _ => TestStatus::Skipped,
+            };
+            state.framework = Some("go");
+            // 旧版本go在结果行之后输出缩进的日志，仍归属于该测试
+            state.current_test = Some(caps[2].to_string());
+            state.go_result_seen = true;
+            Self::mark_result(line, "go", &caps[2], status);
+            if let Ok(seconds) = caps[3].parse::<f64>() {
+                line.metadata.insert("test_duration_ms".to_string(), ((seconds * 1000.0).round() as u64).to_string());
+            }
+            return true;
+        }
+        if let Some(caps) = GO_PACKAGE.captures(&text) {
+            state.current_test = None;
+            let status = if &caps[1] == "ok" { TestStatus::Passed } else { TestStatus::Failed };
+            Self::mark_summary(line, "go", status);
+            line.metadata.insert("test_package".to_string(), caps[2].to_string());
+            return true;
+        }
+        if state.framework == Some("go") && (text == "PASS" || text == "FAIL") {
+            state.current_test = None;
+            let status = if text == "PASS" { TestStatus::Passed } else { TestStatus::Failed };
+            Self::mark_summary(line, "go", status);
+            return true;
+        }
+
+        // ---------- pytest ----------
+        if let Some(caps) = PYTEST_VERBOSE.captures(&text) {
+            let status = match &caps[2] {
+                "PASSED" | "XFAIL" => TestStatus::Passed,
+                "SKIPPED" => TestStatus::Skipped,
+                _ => TestStatus::Failed,
+            };
+            state.framework = Some("pytest");
+            Self::mark_result(line, "pytest", &caps[1], status);
+            return true;
+        }
+        if let Some(caps) = PYTEST_RESULT.captures(&text) {
+            state.current_test = None;
+            state.in_pytest_failures = false;
+            let failed = PYTEST_COUNT.captures_iter(&caps[1])
+                .any(|c| &c[2] != "passed" && &c[2] != "skipped" && &c[1] != "0");
+            let status = if failed { TestStatus::Failed } else { TestStatus::Passed };
+            Self::mark_summary(line, "pytest", status);
+            return true;
+        }
+        if text.starts_with("===") && text.ends_with("===") {
+            // pytest 区段分隔线：FAILURES / ERRORS 开始捕获，其余区段结束捕获
+            state.in_pytest_failures = text.contains(" FAILURES ") || text.contains(" ERRORS ");
+            state.current_test = None;
+            return false;
+        }
+        if let Some(caps) = PYTEST_SHORT.captures(&text) {
+            state.framework = Some("pytest");
+            line.metadata.insert("test_framework".to_string(), "pytest".to_string());
+            line.metadata.insert("test_name".to_string(), caps[2].to_string());
+            line.level = Some("ERROR".to_string());
+            return true;
+        }
+        if state.in_pytest_failures {
+            if let Some(caps) = PYTEST_SECTION.captures(&text) {
+                state.framework = Some("pytest");
+                state.current_test = Some(caps[1].to_string());
+                Self::mark_output_header(line, "pytest", &caps[1]);
+                return true;
+            }
+        }
+
+        // ---------- 捕获输出 ----------
+        if let (Some(test), Some(framework)) = (&state.current_test, state.framework) {
+            // go的结果行之后只有缩进行属于该测试
+            let indented = line.message_text().starts_with(' ') || line.message_text().starts_with('\t');
+            if framework != "go" || !state.go_result_seen || indented {
+                Self::mark_output(line, framework, test);
+                if line.message_text().contains("panicked at") || line.message_text().contains("Error") {
+                    line.level.get_or_insert_with(|| "ERROR".to_string());
+                }
+                return true;
+            }
+        }
+
+        false
+    }
+
+    fn mark_result(line: &mut LogLine, framework: &str, name: &str, status: TestStatus) {
+        line.metadata.insert("test_framework".to_string(), framework.to_string());
+        line.metadata.insert("test_name".to_string(), name.to_string());
+        line.metadata.insert("test_status".to_string(), status.as_str().to_string());
+        line.level = Some(status.level().to_string());
+    }
+
+    fn mark_output(line: &mut LogLine, framework: &str, name: &str) {
+        line.metadata.insert("test_framework".to_string(), framework.to_string());
+        line.metadata.insert("test_name".to_string(), name.to_string());
+        line.metadata.insert("test_output".to_string(), "true".to_string());
+    }
+
+    fn mark_output_header(line: &mut LogLine, framework: &str, name: &str) {
+        Self::mark_output(line, framework, name);
+        line.metadata.insert("test_output".to_string(), "header".to_string());
+    }
+
+    fn mark_summary(line: &mut LogLine, framework: &str, status: TestStatus) {
+        line.metadata.insert("test_framework".to_string(), framework.to_string());
+        line.metadata.insert("test_summary".to_string(), "true".to_string());
+        line.metadata.insert("test_status".to_string(), status.as_str().to_string());
+        line.level = Some(status.level().to_string());
+    }
+}
+
+impl PluginFilter for TestRunnerFilter {
+    fn name(&self) -> &str {
+        "test_runner"
+    }
+
+    fn description(&self) -> &str {
+        "测试运行器输出过滤器，识别cargo test、go test和pytest的测试名称与结果"
+    }
+
+    fn priority(&self) -> i32 {
+        20 // 在ANSI清理之后执行
+    }
+
+    fn should_process(&self, context: &PluginChainContext) -> bool {
+        if context.current_lines.is_empty() {
+            return true;
+        }
+        context.current_lines.iter().any(|line| Self::looks_like_test_output(line.message_text()))
+    }
+
+    fn process(&self, context: &mut PluginChainContext, _request: &ParseRequest) -> Result<(), String> {
+        info!("🧪 测试运行器过滤器开始处理");
+
+        let mut lines = if context.current_lines.is_empty() {
+            // 第一次处理，从原始内容创建行列表，过滤空行
+            context.original_content.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()).map(|(i, line)| {
+                LogLine {
+                    line_number: i + 1,
+                    content: line.to_string(),
+                    level: None,
+                    timestamp: None,
+                    formatted_content: None,
+                    message: None,
+                    metadata: HashMap::new(),
+                    processed_by: vec![],
+                }
+            }).collect()
+        } else {
+            std::mem::take(&mut context.current_lines)
+        };
+
+        let mut state = ParseState::default();
+        let mut tagged = 0;
+        for line in &mut lines {
+            if Self::tag_line(line, &mut state) {
+                line.processed_by.push("test_runner_filter".to_string());
+                tagged += 1;
+            }
+            if line.formatted_content.is_none() {
+                line.formatted_content = Some(line.message_text().to_string());
+            }
+        }
+
+        context.current_lines = lines;
+        context.set_chain_metadata("test_runner_tagged".to_string(), tagged.to_string());
+
+        info!("🧪 测试运行器过滤器处理完成，标记了 {} 行", tagged);
+        Ok(())
+    }
+
+    fn can_handle(&self, content: &str, _file_path: Option<&str>) -> bool {
+        Self::looks_like_test_output(content)
+    }
+
+    /// 失败测试的输出块需要整段缓冲后才能归属到测试
+    fn capabilities(&self) -> PluginCapabilities {
+        PluginCapabilities::new(Some(MAX_TEST_OUTPUT_SIZE), PerformanceRating::Slow)
+    }
+}
+
+/// 单个测试用例
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestCase {
+    /// 测试名称
+    pub name: String,
+    /// 测试结果
+    pub status: TestStatus,
+    /// 结果行行号
+    pub line_number: usize,
+    /// 耗时（毫秒，框架提供时）
+    pub duration_ms: Option<u64>,
+}
+
+/// 失败测试及其捕获输出
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestFailure {
+    /// 测试名称
+    pub name: String,
+    /// 结果行行号（未找到结果行时为输出块起始行）
+    pub line_number: usize,
+    /// 捕获的输出行
+    pub output: Vec<String>,
+    /// 输出块起始行号
+    pub output_start_line: Option<usize>,
+}
+
+/// 测试运行汇总
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TestRunSummary {
+    /// 识别到的测试框架
+    pub frameworks: Vec<String>,
+    /// 通过的测试数
+    pub passed: usize,
+    /// 失败的测试数
+    pub failed: usize,
+    /// 跳过的测试数
+    pub skipped: usize,
+    /// 所有测试用例（按出现顺序）
+    pub tests: Vec<TestCase>,
+    /// 失败的测试及其输出
+    pub failures: Vec<TestFailure>,
+}
+
+/// 根据测试运行器过滤器标记的元数据汇总测试结果
+///
+/// 同一测试出现多次结果行时（如pytest的 `-v` 结果行和简要汇总）以最后一次为准。
+///
+/// # 参数
+/// - `lines`: 经过 `TestRunnerFilter` 处理的日志行
+///
+/// # Returns
+/// - `TestRunSummary`: 测试运行汇总
+pub fn summarize_test_run(lines: &[LogLine]) -> TestRunSummary {
+    let mut summary = TestRunSummary::default();
+    let mut index_by_name: HashMap<String, usize> = HashMap::new();
+    let mut outputs: Vec<(String, usize, Vec<String>)> = Vec::new();
+
+    for line in lines {
+        let Some(framework) = line.metadata.get("test_framework") else {
+            continue;
+        };
+        if !summary.frameworks.contains(framework) {
+            summary.frameworks.push(framework.clone());
+        }
+        let Some(name) = line.metadata.get("test_name") else {
+            continue;
+        };
+
+        if let Some(kind) = line.metadata.get("test_output") {
+            // 标题行开始新的输出块，其余输出行追加到同一测试的当前块
+            match outputs.last_mut() {
+                Some((last, _, output)) if last == name && kind != "header" => output.push(line.message_text().to_string()),
+                _ if kind == "header" => outputs.push((name.clone(), line.line_number, Vec::new())),
+                _ => outputs.push((name.clone(), line.line_number, vec![line.message_text().to_string()])),
+            }
+            continue;
+        }
+
+        if let Some(status) = line.metadata.get("test_status").and_then(|s| TestStatus::parse(s)) {
+            let case = TestCase {
+                name: name.clone(),
+                status,
+                line_number: line.line_number,
+                duration_ms: line.metadata.get("test_duration_ms").and_then(|d| d.parse().ok()),
+            };
+            match index_by_name.get(name) {
+                Some(&index) => summary.tests[index] = case,
+                None => {
+                    index_by_name.insert(name.clone(), summary.tests.len());
+                    summary.tests.push(case);
+                }
+            }
+        }
+    }
+
+    for case in &summary.tests {
+        match case.status {
+            TestStatus::Passed => summary.passed += 1,
+            TestStatus::Failed => summary.failed += 1,
+            TestStatus::Skipped => summary.skipped += 1,
+        }
+    }
+
+    // 失败测试按出现顺序列出，并附上归属于它的输出块
+    for case in summary.tests.iter().filter(|c| c.status == TestStatus::Failed) {
+        let block = outputs.iter().find(|(name, _, _)| test_names_match(&case.name, name));
+        summary.failures.push(TestFailure {
+            name: case.name.clone(),
+            line_number: case.line_number,
+            output: block.map(|(_, _, output)| output.clone()).unwrap_or_default(),
+            output_start_line: block.map(|(_, start, _)| *start),
+        });
+    }
+
+    summary
+}
+
+/// 解析测试运行器输出并汇总结果
+///
+/// 不经过自动链选择，直接使用ANSI清理和测试运行器过滤器处理内容，
+/// 即使测试输出夹杂在其他构建日志中也能识别。
+///
+/// # 参数
+/// - `content`: 测试运行器输出
+///
+/// # Returns
+/// - `Ok(TestRunSummary)`: 测试运行汇总
+/// - `Err(String)`: 处理失败时的错误信息
+pub fn analyze_test_output(content: &str) -> Result<TestRunSummary, String> {
+    let mut chain = PluginChain::new("test_runner".to_string(), "测试运行器输出分析".to_string());
+    chain.add_filter(Arc::new(AnsiFilter::new()));
+    chain.add_filter(Arc::new(TestRunnerFilter));
+
+    let result = chain.process(content, &ParseRequest::default())?;
+    Ok(summarize_test_run(&result.lines))
+}
+
+/// 判断结果行中的测试名与输出块标题中的测试名是否指同一个测试
+///
+/// pytest 区段标题只包含函数名（或 `Class.method`），结果行使用完整的节点ID。
+fn test_names_match(case_name: &str, block_name: &str) -> bool {
+    if case_name == block_name {
+        return true;
+    }
+    let node_suffix = format!("::{}", block_name.replace('.', "::"));
+    case_name.ends_with(&node_suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(content: &str) -> Vec<LogLine> {
+        let mut chain = PluginChain::new("test".to_string(), "test".to_string());
+        chain.add_filter(Arc::new(TestRunnerFilter));
+        chain.process(content, &ParseRequest::default()).unwrap().lines
+    }
+
+    #[test]
+    fn test_cargo_failures_include_captured_output() {
+        let content = "\
+running 2 tests
+test tests::adds ... ok
+test tests::divides ... FAILED
+
+failures:
+
+---- tests::divides stdout ----
+thread 'tests::divides' panicked at src/lib.rs:10:5:
+attempt to divide by zero
+
+failures:
+    tests::divides
+
+test result: FAILED. 1 passed; 1 failed; 0 ignored; 0 measured; 0 filtered out";
+        let summary = summarize_test_run(&parse(content));
+
+        assert_eq!(summary.frameworks, vec!["cargo"]);
+        assert_eq!((summary.passed, summary.failed), (1, 1));
+        assert_eq!(summary.failures[0].name, "tests::divides");
+        assert_eq!(summary.failures[0].output_start_line, Some(7));
+        assert_eq!(summary.failures[0].output, vec![
+            "thread 'tests::divides' panicked at src/lib.rs:10:5:",
+            "attempt to divide by zero",
+        ]);
+    }
+
+    #[test]
+    fn test_go_verbose_output() {
+        let content = "\
+=== RUN   TestAdd
+--- PASS: TestAdd (0.00s)
+=== RUN   TestDiv
+    div_test.go:12: expected 2, got 0
+--- FAIL: TestDiv (0.01s)
+=== RUN   TestSkip
+--- SKIP: TestSkip (0.00s)
+FAIL
+FAIL\texample.com/calc\t0.015s";
+        let lines = parse(content);
+        let summary = summarize_test_run(&lines);
+
+        assert_eq!((summary.passed, summary.failed, summary.skipped), (1, 1, 1));
+        assert_eq!(summary.tests[1].duration_ms, Some(10));
+        assert_eq!(summary.failures[0].output, vec!["    div_test.go:12: expected 2, got 0"]);
+        assert_eq!(lines.last().unwrap().metadata.get("test_summary").map(String::as_str), Some("true"));
+    }
+
+    #[test]
+    fn test_pytest_failure_sections() {
+        let content = "\
+tests/test_calc.py::test_add PASSED                                      [ 50%]
+tests/test_calc.py::TestDiv::test_zero FAILED                            [100%]
+=================================== FAILURES ===================================
+______________________________ TestDiv.test_zero _______________________________
+    def test_zero(self):
+>       assert div(1, 0) == 0
+E       ZeroDivisionError: division by zero
+=========================== short test summary info ============================
+FAILED tests/test_calc.py::TestDiv::test_zero - ZeroDivisionError: division by zero
+========================= 1 failed, 1 passed in 0.05s ==========================";
+        let lines = parse(content);
+        let summary = summarize_test_run(&lines);
+
+        assert_eq!((summary.passed, summary.failed), (1, 1));
+        assert_eq!(summary.failures[0].name, "tests/test_calc.py::TestDiv::test_zero");
+        assert_eq!(summary.failures[0].output.len(), 3);
+        assert_eq!(lines.last().unwrap().level.as_deref(), Some("ERROR"));
+        assert!(TestRunnerFilter.can_handle(content, None));
+        assert!(!TestRunnerFilter.can_handle("2024-01-15 10:30:25.123 [main] INFO App - started", None));
+    }
+
+    #[test]
+    fn test_preset_selects_test_runner_chain() {
+        use crate::plugins::chain::PluginChainManager;
+        use crate::plugins::presets::register_preset_chains;
+
+        let mut manager = PluginChainManager::new();
+        register_preset_chains(&mut manager);
+
+        let content = "running 1 test\ntest it_works ... ok\n\ntest result: ok. 1 passed; 0 failed; 0 ignored";
+        let result = manager.process(content, &ParseRequest::default()).unwrap();
+        assert_eq!(result.detected_format.as_deref(), Some("test_runner"));
+    }
+}
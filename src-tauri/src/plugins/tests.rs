@@ -39,7 +39,7 @@ mod tests {
             content: combined_content.clone(),
             plugin: None, // Auto-detect
             file_path: Some("container.log".to_string()),
-            chunk_size: None,
+            ..Default::default()
         };
 
         let result = enhanced_manager.auto_detect_and_parse(&request);
@@ -66,7 +66,7 @@ mod tests {
             content: combined_content.clone(),
             plugin: None,
             file_path: Some("docker-container.log".to_string()),
-            chunk_size: None,
+            ..Default::default()
         };
 
         let chain_result = chain_manager.process(&combined_content, &request);
@@ -131,7 +131,7 @@ mod tests {
         println!("🔍 Test 3: Individual Filter Behavior");
 
         // Test DockerJsonFilter
-        let docker_filter = DockerJsonFilter;
+        let docker_filter = DockerJsonFilter::default();
         let docker_content = r#"{"log":"[0.000s][warning][gc] Test message\n","stream":"stdout","time":"2025-09-16T08:17:32.172897326Z"}"#;
         assert!(docker_filter.can_handle(docker_content, None), "DockerJsonFilter should handle Docker JSON content");
 
@@ -181,7 +181,7 @@ mod tests {
             content: gc_warning_content.to_string(),
             plugin: None,
             file_path: Some("gc.log".to_string()),
-            chunk_size: None,
+            ..Default::default()
         };
 
         let result = enhanced_manager.auto_detect_and_parse(&request);
@@ -201,7 +201,7 @@ mod tests {
             content: gc_info_content.to_string(),
             plugin: None,
             file_path: Some("gc.log".to_string()),
-            chunk_size: None,
+            ..Default::default()
         };
 
         let result = enhanced_manager.auto_detect_and_parse(&request);
@@ -215,4 +215,213 @@ mod tests {
             }
         }
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_process_with_named_chain_reports_applied_filters() {
+        let enhanced_manager = EnhancedPluginManager::new();
+        enhanced_manager.initialize().await.expect("Failed to initialize enhanced plugin manager");
+
+        let request = ParseRequest {
+            content: "2024-01-15 10:30:25.123 [main] ERROR com.example.App - failed took=1500ms".to_string(),
+            ..Default::default()
+        };
+
+        let result = enhanced_manager.process_with_chain("generic", &request).expect("generic chain should run");
+        assert_eq!(result.detected_format.as_deref(), Some("generic"));
+        assert_eq!(result.applied_filters.first().map(String::as_str), Some("springboot"));
+        assert!(result.applied_filters.contains(&"metrics".to_string()));
+
+        assert!(enhanced_manager.process_with_chain("missing", &request).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_explain_traces_each_filter_decision() {
+        use crate::plugins::chain::StepStatus;
+
+        let enhanced_manager = EnhancedPluginManager::new();
+        enhanced_manager.initialize().await.expect("Failed to initialize enhanced plugin manager");
+
+        let request = ParseRequest {
+            content: "2024-01-15 10:30:25.123 [main] ERROR com.example.App - failed took=1500ms".to_string(),
+            ..Default::default()
+        };
+
+        let trace = enhanced_manager.explain(Some("generic"), "", &request).expect("generic chain should be traced");
+        assert_eq!(trace.chain, "generic");
+
+        let springboot = trace.steps.iter().find(|step| step.filter == "springboot").expect("springboot step");
+        assert_eq!(springboot.status, StepStatus::Applied);
+        assert!(springboot.changes.iter().any(|change| change.field == "level" && change.after.as_deref() == Some("ERROR")));
+        assert_eq!(trace.result.as_ref().and_then(|line| line.level.as_deref()), Some("ERROR"));
+
+        // 智能选择与解析时一致；不存在的链返回错误
+        let auto = enhanced_manager.explain(None, &request.content, &request).expect("auto selection should work");
+        assert!(!auto.steps.is_empty());
+        assert!(enhanced_manager.explain(Some("missing"), "", &request).is_err());
+    }
+
+    #[test]
+    fn test_detection_sample_limits_lines_and_bytes() {
+        use crate::plugins::chain::DetectionSample;
+
+        let content = "第一行\nsecond\nthird\nfourth";
+        assert_eq!(DetectionSample { max_bytes: 0, max_lines: 2 }.apply(content), "第一行\nsecond");
+        assert_eq!(DetectionSample { max_bytes: 0, max_lines: 0 }.apply(content), content);
+        // 按字节截断时退回到最后一个完整行
+        assert_eq!(DetectionSample { max_bytes: 18, max_lines: 0 }.apply(content), "第一行\nsecond");
+        // 单行超长时在字符边界截断
+        assert_eq!(DetectionSample { max_bytes: 4, max_lines: 0 }.apply(content), "第");
+    }
+
+    #[tokio::test]
+    async fn test_parse_with_format_ignores_chain_conditions() {
+        let enhanced_manager = EnhancedPluginManager::new();
+        enhanced_manager.initialize().await.expect("Failed to initialize enhanced plugin manager");
+
+        let request = ParseRequest {
+            content: r#"{"log":"2024-01-15 10:30:25.123 [main] ERROR com.example.App - failed\n","stream":"stdout","time":"2024-01-15T10:30:25.123Z"}"#.to_string(),
+            file_path: Some("/var/log/app.log".to_string()),
+            ..Default::default()
+        };
+
+        // docker链的文件名条件不匹配，但用户指定的格式优先
+        assert!(enhanced_manager.has_format("docker"));
+        let result = enhanced_manager.parse_with_format("docker", &request).expect("forced docker chain should run");
+        assert_eq!(result.detected_format.as_deref(), Some("docker"));
+        assert_eq!(result.lines.len(), 1);
+
+        assert!(enhanced_manager.has_format("raw"));
+        assert!(!enhanced_manager.has_format("missing"));
+        assert!(enhanced_manager.parse_with_format("missing", &request).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_resolve_plugin_lists_available_names_for_unknown_plugin() {
+        let enhanced_manager = EnhancedPluginManager::new();
+        enhanced_manager.initialize().await.expect("Failed to initialize enhanced plugin manager");
+
+        assert_eq!(enhanced_manager.resolve_plugin(None), Ok(None));
+        assert_eq!(enhanced_manager.resolve_plugin(Some("auto")), Ok(None));
+        assert_eq!(enhanced_manager.resolve_plugin(Some("docker")), Ok(Some("docker".to_string())));
+        assert_eq!(enhanced_manager.resolve_plugin(Some("mybatis")), Ok(Some("mybatis".to_string())));
+
+        let error = enhanced_manager.resolve_plugin(Some("log4j")).unwrap_err();
+        assert!(error.contains("log4j"));
+        for name in enhanced_manager.format_names() {
+            assert!(error.contains(&name), "错误信息缺少 {}: {}", name, error);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_available_plugins_include_chains_and_custom_layout() {
+        use crate::plugins::PluginKind;
+
+        let enhanced_manager = EnhancedPluginManager::new();
+        enhanced_manager.initialize().await.expect("Failed to initialize enhanced plugin manager");
+        let find = |manager: &EnhancedPluginManager, name: &str| manager.get_available_plugins()
+            .into_iter()
+            .find(|plugin| plugin.name == name)
+            .unwrap_or_else(|| panic!("缺少插件 {}", name));
+
+        let names: Vec<String> = enhanced_manager.get_available_plugins().into_iter().map(|plugin| plugin.name).collect();
+        assert_eq!(names, enhanced_manager.format_names());
+
+        let springboot = find(&enhanced_manager, "springboot");
+        assert!(springboot.auto_detectable && springboot.enabled);
+        let raw = find(&enhanced_manager, "raw");
+        assert_eq!(raw.kind, PluginKind::Parser);
+        assert!(!raw.auto_detectable);
+        assert_eq!(raw.version, env!("CARGO_PKG_VERSION"));
+
+        let nginx = find(&enhanced_manager, "nginx_error");
+        assert_eq!(nginx.kind, PluginKind::Chain);
+        assert!(nginx.supported_extensions.contains(&"nginx".to_string()));
+
+        // 未配置布局模式时自定义布局链已注册但不启用，配置后启用
+        let layout = find(&enhanced_manager, "pattern_layout");
+        assert!(!layout.enabled && !layout.auto_detectable);
+        let settings = std::collections::HashMap::from([
+            ("pattern_layout".to_string(), serde_json::json!({ "pattern": "%d %-5p %m%n" })),
+        ]);
+        enhanced_manager.apply_plugin_settings(&settings).unwrap();
+        let layout = find(&enhanced_manager, "pattern_layout");
+        assert!(layout.enabled && layout.auto_detectable);
+    }
+
+    #[test]
+    fn test_chunk_position_drops_overlap_and_offsets_line_numbers() {
+        use crate::plugins::chain::{ChunkPosition, END_LINE_METADATA_KEY};
+
+        let request = ParseRequest {
+            chunk_size: Some(100),
+            chunk_index: Some(2),
+            chunk_overlap: Some(3),
+            ..Default::default()
+        };
+        let chunk = ChunkPosition::from_request(&request).expect("chunk_index is set");
+        assert_eq!(chunk.line_offset(), 197);
+
+        let line = |line_number: usize, end_line: Option<usize>| {
+            let mut metadata = std::collections::HashMap::new();
+            if let Some(end) = end_line {
+                metadata.insert(END_LINE_METADATA_KEY.to_string(), end.to_string());
+            }
+            LogLine { metadata, ..LogLine::new(line_number, format!("line {}", line_number)) }
+        };
+        // 第1行完全位于重叠区；第2行的多行记录延续到当前块
+        let mut lines = vec![line(1, None), line(2, Some(5)), line(6, None)];
+        chunk.finish(&mut lines);
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].line_number, 199);
+        assert_eq!(lines[0].metadata.get("chunk_continued").map(String::as_str), Some("true"));
+        assert_eq!(lines[0].metadata.get(END_LINE_METADATA_KEY).map(String::as_str), Some("202"));
+        assert_eq!(lines[1].line_number, 203);
+        assert!(!lines[1].metadata.contains_key("chunk_continued"));
+    }
+
+    #[test]
+    fn test_select_best_chain_scores_mixed_logs() {
+        let mut chain_manager = PluginChainManager::new();
+        register_preset_chains(&mut chain_manager);
+        let select = |content: &str, path: Option<&str>| {
+            chain_manager.select_best_chain(content, path, 0).0.map(|chain| chain.name.clone())
+        };
+
+        // SpringBoot日志中夹杂SQL、堆栈和一行打印出来的Docker JSON负载，按记录行的比例仍是SpringBoot
+        let springboot = r#"2024-01-15 10:30:25.123  INFO 4242 --- [main] c.e.Application : Started Application
+2024-01-15 10:30:26.001 DEBUG 4242 --- [http-1] c.e.UserMapper.select : ==>  Preparing: SELECT * FROM users WHERE id = ?
+2024-01-15 10:30:26.002 DEBUG 4242 --- [http-1] c.e.UserMapper.select : ==> Parameters: 42(Long)
+2024-01-15 10:30:26.005  INFO 4242 --- [http-1] c.e.LogForwarder : forwarding payload:
+{"log":"hello\n","stream":"stdout","time":"2024-01-15T10:30:26Z"}
+2024-01-15 10:30:26.010 ERROR 4242 --- [http-1] c.e.UserController : request failed
+java.lang.IllegalStateException: user locked
+	at com.example.UserService.load(UserService.java:42)
+	... 48 more
+2024-01-15 10:30:27.000  WARN 4242 --- [scheduler-1] c.e.Job : retrying"#;
+        assert_eq!(select(springboot, Some("/var/log/app.log")).as_deref(), Some("springboot"));
+
+        // 以Nginx错误日志为主、夹杂一行Heroku路由日志时不被单行特征抢走
+        let nginx = r#"2024/01/15 10:30:25 [error] 1234#0: *5 connect() failed (111: Connection refused) while connecting to upstream, client: 10.0.0.1, upstream: "http://127.0.0.1:8080/"
+2024/01/15 10:30:26 [warn] 1234#0: *6 an upstream response is buffered to a temporary file
+2024-01-15T10:30:27.123456+00:00 heroku[router]: at=info method=GET path="/" host=example.com dyno=web.1 status=200
+2024/01/15 10:30:28 [error] 1234#0: *7 upstream timed out (110: Connection timed out)"#;
+        assert_eq!(select(nginx, None).as_deref(), Some("nginx_error"));
+
+        // 真正的Docker JSON日志，文件名不含docker时也能选中并处理
+        let docker = r#"{"log":"2024-01-15 10:30:25.123  INFO 1 --- [main] c.e.App : started\n","stream":"stdout","time":"2024-01-15T10:30:25Z"}
+{"log":"2024-01-15 10:30:26.000 ERROR 1 --- [main] c.e.App : failed\n","stream":"stderr","time":"2024-01-15T10:30:26Z"}"#;
+        assert_eq!(select(docker, Some("/srv/app/current.log")).as_deref(), Some("docker"));
+        let request = ParseRequest {
+            content: docker.to_string(),
+            file_path: Some("/srv/app/current.log".to_string()),
+            ..Default::default()
+        };
+        let result = chain_manager.process(docker, &request).expect("docker chain should run");
+        assert_eq!(result.lines.len(), 2);
+
+        // 只有关键词的自由文本使用默认链
+        let free_text = "user reported an ERROR in the INFO panel\nsee \"log\" and \"stream\" settings {here}";
+        assert_eq!(select(free_text, None).as_deref(), Some("generic"));
+    }
+}
@@ -0,0 +1,217 @@
+/// 解析器样本验证工具
+///
+/// 面向插件开发者和自定义正则解析器用户的调试工具：
+/// 用指定解析器逐行处理样本内容，报告哪些行被识别、哪些行未被识别，
+/// 以及每行提取出的字段（捕获组）。
+///
+/// # 验证模式
+/// - **插件模式**: 使用已注册的解析器，以解析结果中的时间戳、级别和元数据作为捕获字段
+/// - **正则模式**: 额外提供正则表达式时，直接报告每行的命名/编号捕获组
+
+use crate::i18n::Message;
+use crate::plugins::{LogParser, ParseRequest};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// 单行验证结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LineValidation {
+    /// 样本中的行号（从1开始，包括空行）
+    pub line_number: usize,
+
+    /// 原始行内容
+    pub content: String,
+
+    /// 该行是否被解析器识别
+    pub matched: bool,
+
+    /// 提取出的字段（正则模式下为捕获组，插件模式下为解析出的字段）
+    pub captures: HashMap<String, String>,
+}
+
+/// 解析器验证报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParserValidationReport {
+    /// 被验证的解析器名称
+    pub plugin: String,
+
+    /// 使用的正则表达式（仅正则模式）
+    pub pattern: Option<String>,
+
+    /// 解析器的 can_parse 是否接受整个样本
+    pub can_parse_sample: bool,
+
+    /// 参与验证的非空行数
+    pub total_lines: usize,
+
+    /// 被识别的行数
+    pub matched_lines: usize,
+
+    /// 未被识别的行号列表
+    pub unmatched_line_numbers: Vec<usize>,
+
+    /// 逐行验证结果
+    pub lines: Vec<LineValidation>,
+}
+
+/// 使用解析器逐行验证样本内容
+///
+/// 每一行单独交给解析器处理，因此多行记录的续行（如堆栈）会显示为未识别，
+/// 这正是调试正则时需要看到的信息。
+///
+/// # 参数
+/// - `parser`: 要验证的解析器
+/// - `content`: 样本内容
+/// - `file_path`: 样本文件路径（传给 can_parse 用于扩展名判断）
+/// - `pattern`: 可选的自定义正则表达式
+///
+/// # Returns
+/// - `Ok(ParserValidationReport)`: 逐行验证报告
+/// - `Err(String)`: 正则表达式无效时的错误信息
+pub fn validate_parser(
+    parser: &dyn LogParser,
+    content: &str,
+    file_path: Option<&str>,
+    pattern: Option<&str>,
+) -> Result<ParserValidationReport, String> {
+    let regex = match pattern {
+        Some(p) => Some(Regex::new(p).map_err(|e| Message::new("validation.invalid_regex").with("error", e).text())?),
+        None => None,
+    };
+
+    let mut lines = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let (matched, captures) = match &regex {
+            Some(re) => capture_with_regex(re, line),
+            None => capture_with_parser(parser, line, file_path),
+        };
+
+        lines.push(LineValidation {
+            line_number: i + 1,
+            content: line.to_string(),
+            matched,
+            captures,
+        });
+    }
+
+    let unmatched_line_numbers: Vec<usize> = lines.iter()
+        .filter(|line| !line.matched)
+        .map(|line| line.line_number)
+        .collect();
+
+    Ok(ParserValidationReport {
+        plugin: parser.name().to_string(),
+        pattern: pattern.map(|p| p.to_string()),
+        can_parse_sample: parser.can_parse(content, file_path),
+        total_lines: lines.len(),
+        matched_lines: lines.len() - unmatched_line_numbers.len(),
+        unmatched_line_numbers,
+        lines,
+    })
+}
+
+/// 正则模式：返回命名捕获组，没有命名组时按编号返回
+fn capture_with_regex(re: &Regex, line: &str) -> (bool, HashMap<String, String>) {
+    let mut captures = HashMap::new();
+    let Some(caps) = re.captures(line) else {
+        return (false, captures);
+    };
+
+    let has_named_groups = re.capture_names().flatten().next().is_some();
+    for (index, name) in re.capture_names().enumerate().skip(1) {
+        let Some(value) = caps.get(index) else { continue };
+        match name {
+            Some(name) => {
+                captures.insert(name.to_string(), value.as_str().to_string());
+            }
+            None if !has_named_groups => {
+                captures.insert(index.to_string(), value.as_str().to_string());
+            }
+            None => {}
+        }
+    }
+
+    (true, captures)
+}
+
+/// 插件模式：单行解析后以时间戳、级别和元数据作为提取字段
+fn capture_with_parser(parser: &dyn LogParser, line: &str, file_path: Option<&str>) -> (bool, HashMap<String, String>) {
+    let request = ParseRequest {
+        content: line.to_string(),
+        plugin: Some(parser.name().to_string()),
+        file_path: file_path.map(|p| p.to_string()),
+        ..Default::default()
+    };
+
+    let parsed = match parser.parse(line, &request) {
+        Ok(result) => result.lines.into_iter().next(),
+        Err(e) => {
+            log::debug!("🧪 解析器 {} 处理单行失败: {}", parser.name(), e);
+            None
+        }
+    };
+
+    let Some(parsed) = parsed else {
+        return (false, HashMap::new());
+    };
+
+    let mut captures = parsed.metadata;
+    if let Some(timestamp) = parsed.timestamp {
+        captures.insert("timestamp".to_string(), timestamp);
+    }
+    if let Some(level) = parsed.level {
+        captures.insert("level".to_string(), level);
+    }
+
+    (!captures.is_empty(), captures)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::raw::RawParser;
+    use crate::plugins::springboot::SpringBootParser;
+
+    #[test]
+    fn test_regex_mode_reports_named_groups_and_fallthrough() {
+        let sample = "2024-01-01 10:00:00 INFO started\n\n    at com.example.Foo\n2024-01-01 10:00:01 WARN slow";
+        let report = validate_parser(
+            &RawParser,
+            sample,
+            None,
+            Some(r"^(?P<ts>\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2}) (?P<level>\w+) (?P<msg>.*)$"),
+        ).unwrap();
+
+        assert_eq!(report.total_lines, 3);
+        assert_eq!(report.matched_lines, 2);
+        assert_eq!(report.unmatched_line_numbers, vec![3]);
+        assert_eq!(report.lines[0].captures.get("level").map(String::as_str), Some("INFO"));
+        assert_eq!(report.lines[2].captures.get("msg").map(String::as_str), Some("slow"));
+    }
+
+    #[test]
+    fn test_regex_mode_uses_numbered_groups_without_names() {
+        let report = validate_parser(&RawParser, "a=1", None, Some(r"(\w)=(\d)")).unwrap();
+        assert_eq!(report.lines[0].captures.get("2").map(String::as_str), Some("1"));
+    }
+
+    #[test]
+    fn test_invalid_pattern_is_rejected() {
+        assert!(validate_parser(&RawParser, "line", None, Some("(")).is_err());
+    }
+
+    #[test]
+    fn test_plugin_mode_extracts_parsed_fields() {
+        let sample = "2024-01-15 14:30:25.123 [main] INFO com.example.Application - Started\nplain continuation";
+        let report = validate_parser(&SpringBootParser, sample, None, None).unwrap();
+
+        assert!(report.lines[0].matched);
+        assert_eq!(report.lines[0].captures.get("level").map(String::as_str), Some("INFO"));
+        assert_eq!(report.total_lines, 2);
+    }
+}
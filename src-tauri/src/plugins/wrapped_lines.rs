@@ -0,0 +1,166 @@
+/// 终端折行合并
+///
+/// 从终端复制出来的日志会按控制台宽度被硬折行，一条消息被切成多行，
+/// 后面的片段没有时间戳，被当作独立的条目显示。此过滤器按启发式规则把这些片段
+/// 重新拼接到前一个条目，并在元数据 `wrapped_lines` 中记录合并的行数。
+///
+/// # 判断规则
+/// 一行是前一行的折行片段，当且仅当同时满足：
+/// - 与前一行相邻（中间没有空行），且没有时间戳
+/// - 不以空白字符开头，也不是堆栈帧或 `Caused by:` 之类的结构化续行
+/// - 前一行至少 `MIN_WRAP_WIDTH` 个字符（短行不会被折行）
+/// - 前一行不以句末标点结尾
+///
+/// 默认不启用，由解析配置 `merge_wrapped_lines` 控制。
+
+use crate::plugins::capabilities::{PerformanceRating, PluginCapabilities};
+use crate::plugins::chain::{PluginChainContext, PluginFilter, END_LINE_METADATA_KEY};
+use crate::plugins::{LogLine, ParseRequest};
+use crate::records::record_end_line;
+use log::info;
+
+/// 记录合并行数的元数据键
+pub const WRAPPED_LINES_METADATA_KEY: &str = "wrapped_lines";
+
+/// 被折行的行至少包含的字符数
+const MIN_WRAP_WIDTH: usize = 40;
+
+/// 句末标点：以这些字符结尾的行视为完整的消息
+const SENTENCE_END: &[char] = &['.', '!', '?', ';', '。', '！', '？', '；'];
+
+/// 结构化续行的开头（由多行记录分组处理，不合并）
+const STRUCTURED_PREFIXES: &[&str] = &["at ", "Caused by:", "Suppressed:", "... "];
+
+/// 终端折行合并过滤器
+pub struct WrappedLineFilter;
+
+impl WrappedLineFilter {
+    /// 判断 `line` 是否为前一行的折行片段
+    ///
+    /// # 参数
+    /// - `previous`: 前一个条目
+    /// - `last_segment`: 前一个条目最后一个物理行的内容
+    /// - `line`: 当前行
+    pub fn is_wrapped(previous: &LogLine, last_segment: &str, line: &LogLine) -> bool {
+        let previous_end = record_end_line(previous).unwrap_or(previous.line_number);
+        line.line_number == previous_end + 1
+            && line.timestamp.is_none()
+            && !line.message_text().starts_with(char::is_whitespace)
+            && line.metadata.get("type").map(String::as_str) != Some("stacktrace")
+            && !STRUCTURED_PREFIXES.iter().any(|prefix| line.message_text().starts_with(prefix))
+            && last_segment.chars().count() >= MIN_WRAP_WIDTH
+            && !last_segment.trim_end().ends_with(SENTENCE_END)
+    }
+}
+
+impl PluginFilter for WrappedLineFilter {
+    fn name(&self) -> &str {
+        "wrapped_lines"
+    }
+
+    fn description(&self) -> &str {
+        "终端折行合并过滤器，将按控制台宽度折断的消息片段拼接回前一个条目"
+    }
+
+    fn priority(&self) -> i32 {
+        22 // 在格式解析提取时间戳之后、Java日志和SQL识别之前执行
+    }
+
+    fn should_process(&self, context: &PluginChainContext) -> bool {
+        context.merge_wrapped_lines && context.current_lines.len() > 1
+    }
+
+    fn process(&self, context: &mut PluginChainContext, _request: &ParseRequest) -> Result<(), String> {
+        info!("🧵 终端折行合并过滤器开始处理");
+
+        let lines = std::mem::take(&mut context.current_lines);
+        let mut merged: Vec<LogLine> = Vec::with_capacity(lines.len());
+        let mut last_segment = String::new();
+        let mut merged_count = 0;
+
+        for line in lines {
+            let Some(previous) = merged.last_mut().filter(|previous| Self::is_wrapped(previous, &last_segment, &line)) else {
+                last_segment = line.message_text().to_string();
+                merged.push(line);
+                continue;
+            };
+
+            // 折行发生在任意字符处，片段之间不插入分隔符
+            let segment = line.message_text();
+            previous.content.push_str(segment);
+            if let Some(message) = previous.message.as_mut() {
+                message.push_str(segment);
+            }
+            if let Some(formatted) = previous.formatted_content.as_mut() {
+                formatted.push_str(segment);
+            }
+            let wrapped = previous.metadata.get(WRAPPED_LINES_METADATA_KEY)
+                .and_then(|count| count.parse::<usize>().ok())
+                .unwrap_or(0);
+            previous.metadata.insert(WRAPPED_LINES_METADATA_KEY.to_string(), (wrapped + 1).to_string());
+            previous.metadata.insert(END_LINE_METADATA_KEY.to_string(), line.line_number.to_string());
+            if wrapped == 0 {
+                previous.processed_by.push("wrapped_line_filter".to_string());
+            }
+            last_segment = line.message_text().to_string();
+            merged_count += 1;
+        }
+
+        context.current_lines = merged;
+        context.set_chain_metadata("wrapped_lines_merged".to_string(), merged_count.to_string());
+
+        info!("🧵 终端折行合并完成，合并了 {} 行", merged_count);
+        Ok(())
+    }
+
+    fn can_handle(&self, _content: &str, _file_path: Option<&str>) -> bool {
+        true
+    }
+
+    fn capabilities(&self) -> PluginCapabilities {
+        PluginCapabilities::new(None, PerformanceRating::Moderate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(line_number: usize, content: &str, timestamp: bool) -> LogLine {
+        LogLine {
+            level: Some("INFO".to_string()),
+            timestamp: timestamp.then(|| "2024-01-15 10:30:25".to_string()),
+            formatted_content: Some(content.to_string()),
+            ..LogLine::new(line_number, content)
+        }
+    }
+
+    #[test]
+    fn test_merges_wrapped_fragments_into_previous_entry() {
+        let mut context = PluginChainContext::new("");
+        context.merge_wrapped_lines = true;
+        context.current_lines = vec![
+            line(1, "Request from client 10.0.0.15 failed with upstream connecti", true),
+            line(2, "on reset after 3 retries", false),
+            line(3, "Retrying with a different upstream host in the pool", true),
+            line(4, "    at com.example.Client.send(Client.java:42)", false),
+            line(5, "Shutdown requested by the operator, closing connections.", true),
+            line(6, "Bye", false),
+        ];
+
+        let filter = WrappedLineFilter;
+        assert!(filter.should_process(&context));
+        filter.process(&mut context, &ParseRequest::default()).unwrap();
+
+        let lines = &context.current_lines;
+        let numbers: Vec<usize> = lines.iter().map(|line| line.line_number).collect();
+        assert_eq!(numbers, vec![1, 3, 4, 5, 6]);
+        assert_eq!(lines[0].content, "Request from client 10.0.0.15 failed with upstream connection reset after 3 retries");
+        assert_eq!(lines[0].metadata.get(WRAPPED_LINES_METADATA_KEY).map(String::as_str), Some("1"));
+        assert_eq!(record_end_line(&lines[0]), Some(2));
+
+        // 未启用时不处理
+        context.merge_wrapped_lines = false;
+        assert!(!filter.should_process(&context));
+    }
+}
@@ -0,0 +1,268 @@
+/// 压缩包浏览
+///
+/// 直接列出 zip / tar / tar.gz 压缩包中的文件，并流式读取其中的单个文件，
+/// 无需先解压到磁盘。客户提供的支持包（如 `support-bundle.tar.gz`）中通常有多个日志，
+/// 用户选中其中一个后，以 `<压缩包路径>!/<成员路径>` 形式的虚拟路径交给 `parse_log` 解析。
+///
+/// 单文件的 `.gz` 也按只有一个成员的压缩包处理。
+
+use crate::i18n::Message;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+/// 虚拟路径中压缩包路径与成员路径之间的分隔符
+pub const ARCHIVE_MEMBER_SEPARATOR: &str = "!/";
+
+/// 默认最多读取的成员大小（解压后）
+const DEFAULT_MAX_MEMBER_BYTES: u64 = 512 * 1024 * 1024;
+
+/// 压缩包类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchiveKind {
+    /// zip
+    Zip,
+    /// 未压缩的tar
+    Tar,
+    /// gzip压缩的tar（.tar.gz / .tgz）
+    TarGz,
+    /// 单个gzip压缩文件
+    Gz,
+}
+
+impl ArchiveKind {
+    /// 按文件扩展名识别压缩包类型，不是压缩包时返回None
+    pub fn detect<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let name = path.as_ref().file_name()?.to_string_lossy().to_lowercase();
+        if name.ends_with(".zip") {
+            Some(Self::Zip)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(Self::TarGz)
+        } else if name.ends_with(".tar") {
+            Some(Self::Tar)
+        } else if name.ends_with(".gz") {
+            Some(Self::Gz)
+        } else {
+            None
+        }
+    }
+}
+
+/// 压缩包中的文件
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchiveEntry {
+    /// 成员在压缩包中的路径
+    pub path: String,
+    /// 解压后的大小（字节，gzip单文件未知时为None）
+    pub size: Option<u64>,
+    /// 用于 `parse_log` 等命令的虚拟路径
+    pub virtual_path: String,
+    /// 按扩展名和文件名判断是否像日志文件
+    pub likely_log: bool,
+}
+
+/// 拆分虚拟路径，返回压缩包路径和成员路径
+///
+/// 只有分隔符前的部分是可识别的压缩包时才视为虚拟路径。
+pub fn split_member_path(path: &str) -> Option<(&str, &str)> {
+    let (archive, member) = path.split_once(ARCHIVE_MEMBER_SEPARATOR)?;
+    if member.is_empty() || ArchiveKind::detect(archive).is_none() {
+        return None;
+    }
+    Some((archive, member))
+}
+
+/// 构建成员的虚拟路径
+pub fn member_path(archive_path: &str, member: &str) -> String {
+    format!("{}{}{}", archive_path, ARCHIVE_MEMBER_SEPARATOR, member)
+}
+
+fn is_likely_log(member: &str) -> bool {
+    let name = member.rsplit('/').next().unwrap_or(member).to_lowercase();
+    name.contains("log") || name.ends_with(".out") || name.ends_with(".txt")
+}
+
+fn open(archive_path: &str) -> Result<(ArchiveKind, File), String> {
+    let kind = ArchiveKind::detect(archive_path)
+        .ok_or_else(|| Message::new("archive.unsupported_format").with("path", archive_path).text())?;
+    let file = File::open(archive_path).map_err(|e| Message::new("archive.open_failed").with("error", e).text())?;
+    Ok((kind, file))
+}
+
+fn tar_reader(kind: ArchiveKind, file: File) -> tar::Archive<Box<dyn Read>> {
+    let reader: Box<dyn Read> = match kind {
+        ArchiveKind::TarGz => Box::new(flate2::read::GzDecoder::new(BufReader::new(file))),
+        _ => Box::new(BufReader::new(file)),
+    };
+    tar::Archive::new(reader)
+}
+
+/// 列出压缩包中的文件（不包括目录）
+///
+/// # 参数
+/// - `archive_path`: 压缩包路径
+///
+/// # Returns
+/// - `Ok(Vec<ArchiveEntry>)`: 按压缩包中的顺序排列的文件
+/// - `Err(String)`: 格式不支持或压缩包损坏时的错误信息
+pub fn list_entries(archive_path: &str) -> Result<Vec<ArchiveEntry>, String> {
+    let (kind, file) = open(archive_path)?;
+    let mut entries = Vec::new();
+    let mut push = |path: String, size: Option<u64>| {
+        entries.push(ArchiveEntry {
+            virtual_path: member_path(archive_path, &path),
+            likely_log: is_likely_log(&path),
+            path,
+            size,
+        });
+    };
+
+    match kind {
+        ArchiveKind::Zip => {
+            let mut zip = zip::ZipArchive::new(BufReader::new(file))
+                .map_err(|e| Message::new("archive.zip_read_failed").with("error", e).text())?;
+            for i in 0..zip.len() {
+                let member = zip.by_index(i).map_err(|e| Message::new("archive.zip_read_failed").with("error", e).text())?;
+                if member.is_file() {
+                    push(member.name().to_string(), Some(member.size()));
+                }
+            }
+        }
+        ArchiveKind::Tar | ArchiveKind::TarGz => {
+            let mut archive = tar_reader(kind, file);
+            for member in archive.entries().map_err(|e| Message::new("archive.tar_read_failed").with("error", e).text())? {
+                let member = member.map_err(|e| Message::new("archive.tar_read_failed").with("error", e).text())?;
+                if !member.header().entry_type().is_file() {
+                    continue;
+                }
+                let path = member.path().map_err(|e| Message::new("archive.tar_read_failed").with("error", e).text())?;
+                push(path.to_string_lossy().into_owned(), Some(member.size()));
+            }
+        }
+        ArchiveKind::Gz => {
+            // 单文件gzip的成员名取自去掉 .gz 后的文件名
+            let name = Path::new(archive_path).file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            push(name, None);
+        }
+    }
+
+    Ok(entries)
+}
+
+/// 流式读取压缩包中的单个文件
+///
+/// tar.gz 需要从头解压直到找到该成员，但不会把其他成员写入磁盘或内存。
+/// 内容按UTF-8解码，无效字节替换为U+FFFD。
+///
+/// # 参数
+/// - `archive_path`: 压缩包路径
+/// - `member`: 成员路径（`list_entries` 返回的 `path`）
+/// - `max_bytes`: 最多读取的解压后字节数（可选，默认512MB）
+///
+/// # Returns
+/// - `Ok(String)`: 成员内容
+/// - `Err(String)`: 成员不存在、超出大小限制或压缩包损坏时的错误信息
+pub fn read_member(archive_path: &str, member: &str, max_bytes: Option<u64>) -> Result<String, String> {
+    let (kind, file) = open(archive_path)?;
+    let limit = max_bytes.unwrap_or(DEFAULT_MAX_MEMBER_BYTES);
+
+    let read_limited = |reader: &mut dyn Read| -> Result<String, String> {
+        let mut bytes = Vec::new();
+        reader.take(limit + 1).read_to_end(&mut bytes)
+            .map_err(|e| Message::new("archive.extract_failed").with("member", member).with("error", e).text())?;
+        if bytes.len() as u64 > limit {
+            return Err(Message::new("archive.member_too_large").with("member", member).with("limit", limit).text());
+        }
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    };
+
+    match kind {
+        ArchiveKind::Zip => {
+            let mut zip = zip::ZipArchive::new(BufReader::new(file))
+                .map_err(|e| Message::new("archive.zip_read_failed").with("error", e).text())?;
+            let mut entry = zip.by_name(member)
+                .map_err(|_| Message::new("archive.member_not_found").with("member", member).text())?;
+            read_limited(&mut entry)
+        }
+        ArchiveKind::Tar | ArchiveKind::TarGz => {
+            let mut archive = tar_reader(kind, file);
+            for entry in archive.entries().map_err(|e| Message::new("archive.tar_read_failed").with("error", e).text())? {
+                let mut entry = entry.map_err(|e| Message::new("archive.tar_read_failed").with("error", e).text())?;
+                let matches = entry.path()
+                    .map(|path| path.to_string_lossy() == member)
+                    .unwrap_or(false);
+                if matches && entry.header().entry_type().is_file() {
+                    return read_limited(&mut entry);
+                }
+            }
+            Err(Message::new("archive.member_not_found").with("member", member).text())
+        }
+        ArchiveKind::Gz => read_limited(&mut flate2::read::GzDecoder::new(BufReader::new(file))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn temp_path(name: &str) -> String {
+        std::env::temp_dir()
+            .join(format!("log_whisper_{}_{}", std::process::id(), name))
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[test]
+    fn test_tar_gz_list_and_read_member() {
+        let path = temp_path("bundle.tar.gz");
+        {
+            let encoder = flate2::write::GzEncoder::new(File::create(&path).unwrap(), flate2::Compression::default());
+            let mut builder = tar::Builder::new(encoder);
+            for (name, content) in [("logs/app.log", "2024-01-01 INFO started\n"), ("config/app.yml", "port: 80\n")] {
+                let mut header = tar::Header::new_gnu();
+                header.set_size(content.len() as u64);
+                header.set_mode(0o644);
+                header.set_cksum();
+                builder.append_data(&mut header, name, content.as_bytes()).unwrap();
+            }
+            builder.into_inner().unwrap().finish().unwrap();
+        }
+
+        let entries = list_entries(&path).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].path, "logs/app.log");
+        assert!(entries[0].likely_log);
+        assert!(!entries[1].likely_log);
+
+        let (archive, member) = split_member_path(&entries[0].virtual_path).unwrap();
+        assert_eq!(read_member(archive, member, None).unwrap(), "2024-01-01 INFO started\n");
+        assert!(read_member(archive, "missing.log", None).is_err());
+        assert!(read_member(archive, member, Some(4)).is_err());
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_zip_list_and_read_member() {
+        let path = temp_path("bundle.zip");
+        {
+            let mut zip = zip::ZipWriter::new(File::create(&path).unwrap());
+            zip.start_file("server.log", zip::write::FileOptions::default()).unwrap();
+            zip.write_all(b"ERROR boom\n").unwrap();
+            zip.finish().unwrap();
+        }
+
+        let entries = list_entries(&path).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].size, Some(11));
+        assert_eq!(read_member(&path, "server.log", None).unwrap(), "ERROR boom\n");
+        assert!(split_member_path("/var/log/app.log!/x").is_none());
+
+        std::fs::remove_file(path).ok();
+    }
+}
@@ -0,0 +1,277 @@
+/// 缓存内存预算
+///
+/// 增量解析缓存（列式条目）和行索引缓存都按文件缓存，打开的文件越多占用越大。
+/// `MemoryBudget` 记录每个文件最近一次使用的先后顺序，两类缓存的总占用超过预算时，
+/// 按最近最少使用的顺序淘汰整个文件的缓存（解析结果和行索引一起移除），
+/// 被淘汰的文件下次使用时重新建立。淘汰记录由 `get_cache_stats` 返回。
+///
+/// 行索引统一通过 `MemoryBudget::line_index` 获取，每次建立或使用索引都会计入预算并触发淘汰。
+
+use super::{IncrementalParseCache, LineIndex, LineIndexCache};
+use crate::runtime_stats::CacheUsage;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// 保留的最近淘汰记录数
+const MAX_RECENT_EVICTIONS: usize = 50;
+
+/// 一次淘汰
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Eviction {
+    /// 文件路径
+    pub path: String,
+    /// 释放的内存（估算字节数）
+    pub bytes: u64,
+    /// 淘汰时间（RFC 3339）
+    pub evicted_at: String,
+}
+
+/// 单个文件的缓存占用
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileCacheUsage {
+    /// 文件路径
+    pub path: String,
+    /// 解析结果和行索引的估算内存（字节）
+    pub bytes: u64,
+    /// 最近使用的顺序（越大越近，从未使用为0）
+    pub last_used: u64,
+}
+
+/// 缓存统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheStats {
+    /// 内存预算（字节）
+    pub budget_bytes: u64,
+    /// 当前总占用（估算字节数）
+    pub used_bytes: u64,
+    /// 增量解析缓存占用
+    pub parse_cache: CacheUsage,
+    /// 行索引缓存占用
+    pub line_index_cache: CacheUsage,
+    /// 各文件的占用（按最近使用排序）
+    pub files: Vec<FileCacheUsage>,
+    /// 启动以来淘汰的文件数
+    pub evictions: u64,
+    /// 启动以来淘汰释放的内存（估算字节数）
+    pub evicted_bytes: u64,
+    /// 最近的淘汰记录（最新的在前）
+    pub recent_evictions: Vec<Eviction>,
+}
+
+#[derive(Default)]
+struct BudgetState {
+    clock: u64,
+    last_used: HashMap<PathBuf, u64>,
+    evictions: u64,
+    evicted_bytes: u64,
+    recent: VecDeque<Eviction>,
+}
+
+/// 缓存内存预算
+pub struct MemoryBudget {
+    limit: AtomicU64,
+    state: Mutex<BudgetState>,
+}
+
+/// 合并两类缓存中每个文件的占用
+fn merge_usage(parse_cache: &IncrementalParseCache, line_index_cache: &LineIndexCache) -> HashMap<PathBuf, u64> {
+    let mut files: HashMap<PathBuf, u64> = HashMap::new();
+    for (path, bytes) in parse_cache.file_usage().into_iter().chain(line_index_cache.file_usage()) {
+        *files.entry(path).or_default() += bytes;
+    }
+    files
+}
+
+/// 选出需要淘汰的文件：按最近使用顺序从旧到新淘汰，直到总占用不超过预算
+///
+/// # 参数
+/// - `files`: 各文件的占用
+/// - `last_used`: 各文件最近使用的顺序（没有记录的文件最先淘汰）
+/// - `limit`: 预算（字节）
+/// - `keep`: 不淘汰的文件（正在使用的文件）
+pub fn select_evictions(
+    files: &HashMap<PathBuf, u64>,
+    last_used: &HashMap<PathBuf, u64>,
+    limit: u64,
+    keep: Option<&Path>,
+) -> Vec<(PathBuf, u64)> {
+    let mut used: u64 = files.values().sum();
+    if used <= limit {
+        return Vec::new();
+    }
+    let mut candidates: Vec<(&PathBuf, u64)> = files.iter()
+        .filter(|(path, _)| Some(path.as_path()) != keep)
+        .map(|(path, &bytes)| (path, bytes))
+        .collect();
+    candidates.sort_by_key(|(path, _)| (last_used.get(*path).copied().unwrap_or(0), (*path).clone()));
+
+    let mut evicted = Vec::new();
+    for (path, bytes) in candidates {
+        if used <= limit {
+            break;
+        }
+        used -= bytes;
+        evicted.push((path.clone(), bytes));
+    }
+    evicted
+}
+
+impl MemoryBudget {
+    /// 创建内存预算
+    ///
+    /// # 参数
+    /// - `limit_bytes`: 预算（字节）
+    pub fn new(limit_bytes: u64) -> Self {
+        Self { limit: AtomicU64::new(limit_bytes), state: Mutex::new(BudgetState::default()) }
+    }
+
+    /// 当前预算（字节）
+    pub fn limit(&self) -> u64 {
+        self.limit.load(Ordering::Relaxed)
+    }
+
+    /// 修改预算（超出部分在下次 `enforce` 时淘汰）
+    pub fn set_limit(&self, limit_bytes: u64) {
+        self.limit.store(limit_bytes, Ordering::Relaxed);
+    }
+
+    /// 记录文件被使用
+    pub fn touch<P: AsRef<Path>>(&self, path: P) {
+        let mut state = self.state.lock().unwrap();
+        state.clock += 1;
+        let clock = state.clock;
+        state.last_used.insert(path.as_ref().to_path_buf(), clock);
+    }
+
+    /// 获取文件的行索引并计入预算
+    ///
+    /// 通过 `LineIndexCache::get_or_build` 获取（必要时建立）索引后记录文件被使用，
+    /// 总占用超过预算时淘汰其他文件的缓存。
+    ///
+    /// # 参数
+    /// - `parse_cache`: 增量解析缓存
+    /// - `line_index_cache`: 行索引缓存
+    /// - `path`: 文件路径
+    ///
+    /// # Returns
+    /// - `Ok(Arc<LineIndex>)`: 与当前文件内容一致的索引
+    /// - `Err(String)`: 构建索引失败时的错误信息
+    pub fn line_index<P: AsRef<Path>>(&self, parse_cache: &IncrementalParseCache, line_index_cache: &LineIndexCache, path: P) -> Result<Arc<LineIndex>, String> {
+        let index = line_index_cache.get_or_build(path.as_ref())?;
+        self.touch(path.as_ref());
+        self.enforce(parse_cache, line_index_cache, Some(path.as_ref()));
+        Ok(index)
+    }
+
+    /// 总占用超过预算时按LRU淘汰文件缓存
+    ///
+    /// # 参数
+    /// - `parse_cache`: 增量解析缓存
+    /// - `line_index_cache`: 行索引缓存
+    /// - `keep`: 不淘汰的文件（刚刚使用的文件，即使单独超过预算也保留）
+    ///
+    /// # Returns
+    /// - `Vec<Eviction>`: 本次淘汰的文件
+    pub fn enforce(&self, parse_cache: &IncrementalParseCache, line_index_cache: &LineIndexCache, keep: Option<&Path>) -> Vec<Eviction> {
+        let files = merge_usage(parse_cache, line_index_cache);
+        let mut state = self.state.lock().unwrap();
+        let selected = select_evictions(&files, &state.last_used, self.limit(), keep);
+
+        let mut evictions = Vec::new();
+        for (path, bytes) in selected {
+            parse_cache.invalidate(&path);
+            line_index_cache.invalidate(&path);
+            state.last_used.remove(&path);
+            log::info!("🧹 内存预算已满，淘汰文件缓存: {} ({} KB)", path.display(), bytes / 1024);
+            let eviction = Eviction {
+                path: path.to_string_lossy().into_owned(),
+                bytes,
+                evicted_at: chrono::Utc::now().to_rfc3339(),
+            };
+            state.evictions += 1;
+            state.evicted_bytes += bytes;
+            state.recent.push_front(eviction.clone());
+            state.recent.truncate(MAX_RECENT_EVICTIONS);
+            evictions.push(eviction);
+        }
+        evictions
+    }
+
+    /// 缓存统计
+    pub fn stats(&self, parse_cache: &IncrementalParseCache, line_index_cache: &LineIndexCache) -> CacheStats {
+        let files = merge_usage(parse_cache, line_index_cache);
+        let state = self.state.lock().unwrap();
+        let mut file_usage: Vec<FileCacheUsage> = files.iter()
+            .map(|(path, &bytes)| FileCacheUsage {
+                path: path.to_string_lossy().into_owned(),
+                bytes,
+                last_used: state.last_used.get(path).copied().unwrap_or(0),
+            })
+            .collect();
+        file_usage.sort_by(|a, b| b.last_used.cmp(&a.last_used).then(a.path.cmp(&b.path)));
+
+        CacheStats {
+            budget_bytes: self.limit(),
+            used_bytes: files.values().sum(),
+            parse_cache: parse_cache.usage(),
+            line_index_cache: line_index_cache.usage(),
+            files: file_usage,
+            evictions: state.evictions,
+            evicted_bytes: state.evicted_bytes,
+            recent_evictions: state.recent.iter().cloned().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_evictions_least_recently_used_first() {
+        let files: HashMap<PathBuf, u64> = [("a", 40), ("b", 30), ("c", 50), ("d", 10)]
+            .iter()
+            .map(|(path, bytes)| (PathBuf::from(path), *bytes))
+            .collect();
+        let last_used: HashMap<PathBuf, u64> = [("a", 3), ("b", 1), ("c", 2)]
+            .iter()
+            .map(|(path, clock)| (PathBuf::from(path), *clock))
+            .collect();
+        let names = |evicted: Vec<(PathBuf, u64)>| evicted.into_iter()
+            .map(|(path, _)| path.to_string_lossy().into_owned())
+            .collect::<Vec<_>>();
+
+        assert!(select_evictions(&files, &last_used, 130, None).is_empty());
+        // d从未使用最先淘汰，然后是b
+        assert_eq!(names(select_evictions(&files, &last_used, 90, None)), vec!["d", "b"]);
+        // 正在使用的b即使最旧也保留
+        assert_eq!(names(select_evictions(&files, &last_used, 60, Some(Path::new("b")))), vec!["d", "c", "a"]);
+        assert_eq!(names(select_evictions(&files, &last_used, 0, Some(Path::new("a")))), vec!["d", "b", "c"]);
+    }
+
+    #[test]
+    fn test_line_index_is_counted_and_evicted() {
+        let dir = std::env::temp_dir().join(format!("log_whisper_budget_index_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (first, second) = (dir.join("first.log"), dir.join("second.log"));
+        std::fs::write(&first, "a\nb\nc\n").unwrap();
+        std::fs::write(&second, "d\ne\n").unwrap();
+
+        let parse_cache = IncrementalParseCache::new();
+        let line_index_cache = LineIndexCache::new();
+        let budget = MemoryBudget::new(1);
+        assert_eq!(budget.line_index(&parse_cache, &line_index_cache, &first).unwrap().line_count(), 3);
+        assert_eq!(budget.line_index(&parse_cache, &line_index_cache, &second).unwrap().line_count(), 2);
+
+        // 只保留刚使用的文件，之前的索引被淘汰并记录
+        let stats = budget.stats(&parse_cache, &line_index_cache);
+        assert_eq!(stats.line_index_cache.files, 1);
+        assert_eq!(stats.files[0].path, second.to_string_lossy());
+        assert_eq!(stats.recent_evictions[0].path, first.to_string_lossy());
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
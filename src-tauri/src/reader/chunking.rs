@@ -0,0 +1,211 @@
+/// 自适应分块
+///
+/// 固定1000行一块对短行日志太小（请求次数多），对巨型JSON行又太大（单块解析过慢）。
+/// `ChunkPlanner` 按字节预算决定每块的行数：从块起点开始累加行长度直到达到预算，
+/// 并根据上一块实测的解析速度调整预算，使每块的解析耗时接近目标值。
+///
+/// 块的起点取决于之前各块的大小，因此每个文件记录已规划块的起点，
+/// 自适应分块必须从第0块开始按顺序请求（重新请求已规划过的块也可以）。
+
+use crate::i18n::Message;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// 默认每块字节预算（1MB）
+pub const DEFAULT_BUDGET_BYTES: usize = 1024 * 1024;
+
+/// 默认每块目标解析耗时（毫秒）
+pub const DEFAULT_TARGET_PARSE_MS: u64 = 200;
+
+/// 每块最少行数
+pub const MIN_CHUNK_LINES: usize = 50;
+
+/// 每块最多行数
+pub const MAX_CHUNK_LINES: usize = 50_000;
+
+/// 根据解析速度调整后的预算相对默认预算的最小/最大倍数
+const BUDGET_SCALE_RANGE: (f64, f64) = (0.125, 4.0);
+
+/// 固定行数分块的第 `chunk_index` 块的范围 `(start_index, end_index)`
+///
+/// 超出文件末尾的块起点和终点都为 `total_lines`（空块），调用方据此返回空结果而不是越界。
+pub fn fixed_chunk(chunk_index: usize, chunk_size: usize, total_lines: usize) -> (usize, usize) {
+    let chunk_size = chunk_size.max(1);
+    let start_index = chunk_index.saturating_mul(chunk_size).min(total_lines);
+    (start_index, start_index.saturating_add(chunk_size).min(total_lines))
+}
+
+/// 一个块的规划结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChunkPlan {
+    /// 块的第一行在非空行中的下标（从0开始）
+    pub start_index: usize,
+    /// 块结束位置（不含）
+    pub end_index: usize,
+    /// 本块使用的字节预算
+    pub budget_bytes: usize,
+    /// 估算的总块数（按剩余字节和当前预算估算，随解析速度变化）
+    pub estimated_total_chunks: usize,
+}
+
+/// 文件的分块状态
+#[derive(Debug, Clone, Default)]
+struct FilePlan {
+    /// 各块的起点（第i个元素为第i块的起点）
+    starts: Vec<usize>,
+    /// 上一块实测的解析速度（字节/毫秒）
+    bytes_per_ms: Option<f64>,
+}
+
+/// 自适应分块规划器
+pub struct ChunkPlanner {
+    budget_bytes: usize,
+    target_parse_ms: u64,
+    files: Mutex<HashMap<String, FilePlan>>,
+}
+
+impl ChunkPlanner {
+    /// 创建规划器
+    ///
+    /// # 参数
+    /// - `budget_bytes`: 每块字节预算（未测得解析速度时使用）
+    /// - `target_parse_ms`: 每块目标解析耗时（毫秒）
+    pub fn new(budget_bytes: usize, target_parse_ms: u64) -> Self {
+        Self {
+            budget_bytes: budget_bytes.max(1),
+            target_parse_ms: target_parse_ms.max(1),
+            files: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 未测得解析速度时的每块字节预算
+    pub fn budget_bytes(&self) -> usize {
+        self.budget_bytes
+    }
+
+    /// 规划文件的第 `chunk_index` 块
+    ///
+    /// 第0块会重置该文件之前的规划和测得的解析速度。
+    ///
+    /// # 参数
+    /// - `key`: 文件标识（通常为文件路径）
+    /// - `chunk_index`: 块索引（从0开始）
+    /// - `line_lengths`: 文件中每个非空行的字节数
+    ///
+    /// # Returns
+    /// - `Ok(ChunkPlan)`: 块的范围和使用的预算
+    /// - `Err(String)`: 跳过了尚未规划的块时的错误信息
+    pub fn plan(&self, key: &str, chunk_index: usize, line_lengths: &[usize]) -> Result<ChunkPlan, String> {
+        let mut files = self.files.lock().map_err(|_| Message::new("chunk.lock_failed").text())?;
+        if chunk_index == 0 {
+            files.insert(key.to_string(), FilePlan { starts: vec![0], bytes_per_ms: None });
+        }
+        let plan = files.get_mut(key).ok_or_else(|| Message::new("chunk.must_start_at_zero").text())?;
+        let start_index = *plan.starts.get(chunk_index)
+            .ok_or_else(|| Message::new("chunk.not_planned").with("chunk", chunk_index).text())?;
+        let start_index = start_index.min(line_lengths.len());
+
+        let budget_bytes = self.budget_for(plan.bytes_per_ms);
+        let mut end_index = start_index;
+        let mut bytes = 0;
+        while end_index < line_lengths.len() && end_index - start_index < MAX_CHUNK_LINES
+            && (bytes < budget_bytes || end_index - start_index < MIN_CHUNK_LINES)
+        {
+            // 换行符也计入预算
+            bytes += line_lengths[end_index] + 1;
+            end_index += 1;
+        }
+
+        plan.starts.truncate(chunk_index + 1);
+        plan.starts.push(end_index);
+
+        // 起点已在文件末尾时是空块，之前的块已覆盖全部内容
+        let remaining: usize = line_lengths[end_index..].iter().map(|len| len + 1).sum();
+        let estimated_total_chunks = if start_index == line_lengths.len() {
+            chunk_index
+        } else {
+            chunk_index + 1 + remaining.div_ceil(budget_bytes)
+        };
+        Ok(ChunkPlan { start_index, end_index, budget_bytes, estimated_total_chunks })
+    }
+
+    /// 记录一块的实测解析耗时，用于调整下一块的预算
+    ///
+    /// # 参数
+    /// - `key`: 文件标识
+    /// - `bytes`: 块的字节数
+    /// - `elapsed`: 解析耗时
+    pub fn record(&self, key: &str, bytes: usize, elapsed: Duration) {
+        let Ok(mut files) = self.files.lock() else { return };
+        if let Some(plan) = files.get_mut(key) {
+            let ms = elapsed.as_secs_f64() * 1000.0;
+            if ms > 0.0 && bytes > 0 {
+                plan.bytes_per_ms = Some(bytes as f64 / ms);
+            }
+        }
+    }
+
+    /// 按解析速度计算字节预算
+    fn budget_for(&self, bytes_per_ms: Option<f64>) -> usize {
+        let Some(speed) = bytes_per_ms else {
+            return self.budget_bytes;
+        };
+        let (min, max) = BUDGET_SCALE_RANGE;
+        let budget = (speed * self.target_parse_ms as f64)
+            .clamp(self.budget_bytes as f64 * min, self.budget_bytes as f64 * max);
+        budget as usize
+    }
+}
+
+impl Default for ChunkPlanner {
+    fn default() -> Self {
+        Self::new(DEFAULT_BUDGET_BYTES, DEFAULT_TARGET_PARSE_MS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunks_follow_byte_budget_and_parse_speed() {
+        let planner = ChunkPlanner::new(10_000, 100);
+        // 前1000行为短行（99字节），之后为巨型JSON行（9999字节）
+        let lengths: Vec<usize> = (0..1000).map(|_| 99).chain((0..200).map(|_| 9999)).collect();
+
+        let first = planner.plan("app.log", 0, &lengths).unwrap();
+        assert_eq!((first.start_index, first.end_index), (0, 100));
+        assert_eq!(first.budget_bytes, 10_000);
+
+        // 解析速度为每毫秒200字节，目标100ms → 预算20000字节
+        planner.record("app.log", 10_000, Duration::from_millis(50));
+        let second = planner.plan("app.log", 1, &lengths).unwrap();
+        assert_eq!((second.start_index, second.end_index, second.budget_bytes), (100, 300, 20_000));
+
+        // 巨型行：至少包含最少行数
+        let mut plan = second;
+        for index in 2.. {
+            plan = planner.plan("app.log", index, &lengths).unwrap();
+            if plan.start_index >= 1000 {
+                break;
+            }
+        }
+        assert_eq!(plan.end_index - plan.start_index, MIN_CHUNK_LINES);
+
+        // 跳过未规划的块
+        assert!(planner.plan("app.log", 99, &lengths).is_err());
+
+        // 超出文件末尾的块为空块
+        let last = (0..).map(|index| planner.plan("app.log", index, &lengths).unwrap())
+            .take_while(|plan| plan.start_index < lengths.len())
+            .count();
+        let past_end = planner.plan("app.log", last, &lengths).unwrap();
+        assert_eq!((past_end.start_index, past_end.end_index, past_end.estimated_total_chunks), (1200, 1200, last));
+        assert_eq!(fixed_chunk(5, 1000, 4200), (4200, 4200));
+        assert_eq!(fixed_chunk(4, 1000, 4200), (4000, 4200));
+        assert_eq!(fixed_chunk(usize::MAX, 0, 10), (10, 10));
+        assert!(planner.plan("other.log", 1, &lengths).is_err());
+    }
+}
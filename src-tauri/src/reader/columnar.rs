@@ -0,0 +1,331 @@
+/// 列式条目存储
+///
+/// 每条 `LogLine` 都持有多个独立分配的 `String` 和一个 `HashMap`，上千万行时内存占用非常大。
+/// `ColumnarEntries` 按列保存解析结果：
+/// - 内容、格式化内容、时间戳和高基数元数据值追加到连续的文本缓冲区，每条只记录结束偏移
+/// - 级别、元数据键和低基数元数据值（日志器、线程、Pod等）在存储自己的驻留表中只保存一份，每条只记录编号，
+///   驻留表随存储一起释放
+/// - 处理链（`processed_by`）整体驻留，同一解析器产生的条目共享一份
+///
+/// 对外的 `LogLine` 只在需要时按行构造（`get` / `iter` / `to_vec`）。
+
+use crate::models::LogLine;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// 值驻留保存的元数据键（其余键的值追加到文本缓冲区）
+pub const INTERNED_METADATA_KEYS: &[&str] = &[
+    "logger", "thread", "class", "pod", "namespace", "container", "stream", "service", "host", "source", "format",
+];
+
+/// 文本列（可选值）：所有值依次追加到一个缓冲区
+#[derive(Debug, Clone, Default)]
+struct TextColumn {
+    buf: String,
+    ends: Vec<usize>,
+    present: Vec<bool>,
+}
+
+impl TextColumn {
+    fn push(&mut self, value: Option<&str>) {
+        self.buf.push_str(value.unwrap_or(""));
+        self.ends.push(self.buf.len());
+        self.present.push(value.is_some());
+    }
+
+    fn start(&self, i: usize) -> usize {
+        if i == 0 { 0 } else { self.ends[i - 1] }
+    }
+
+    fn get(&self, i: usize) -> Option<&str> {
+        self.present[i].then(|| &self.buf[self.start(i)..self.ends[i]])
+    }
+
+    fn truncate(&mut self, len: usize) {
+        if len < self.ends.len() {
+            self.buf.truncate(self.start(len));
+            self.ends.truncate(len);
+            self.present.truncate(len);
+        }
+    }
+
+    fn heap_bytes(&self) -> usize {
+        self.buf.capacity() + self.ends.capacity() * std::mem::size_of::<usize>() + self.present.capacity()
+    }
+}
+
+/// 驻留字符串在所属驻留表中的编号
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Symbol(u32);
+
+/// 字符串驻留表
+///
+/// 级别、日志器、线程名等值在大文件中重复出现上百万次，驻留后每个不同的值只保存一份。
+/// 驻留表归单个存储所有，编号只在该表中有效。
+#[derive(Debug, Clone, Default)]
+struct Interner {
+    strings: Vec<Arc<str>>,
+    ids: HashMap<Arc<str>, Symbol>,
+}
+
+impl Interner {
+    /// 驻留字符串（已驻留时直接返回已有编号，不分配内存）
+    fn intern(&mut self, value: &str) -> Symbol {
+        if let Some(&symbol) = self.ids.get(value) {
+            return symbol;
+        }
+        let symbol = Symbol(self.strings.len() as u32);
+        let value: Arc<str> = Arc::from(value);
+        self.strings.push(value.clone());
+        self.ids.insert(value, symbol);
+        symbol
+    }
+
+    fn resolve(&self, symbol: Symbol) -> &str {
+        &self.strings[symbol.0 as usize]
+    }
+
+    fn heap_bytes(&self) -> usize {
+        let text: usize = self.strings.iter().map(|value| value.len() + 16).sum();
+        text + self.strings.capacity() * std::mem::size_of::<Arc<str>>()
+            + self.ids.capacity() * (std::mem::size_of::<(Arc<str>, Symbol)>() + 1)
+    }
+}
+
+/// 元数据值
+#[derive(Debug, Clone, Copy)]
+enum MetaValue {
+    /// 驻留字符串
+    Interned(Symbol),
+    /// 元数据文本缓冲区中的范围
+    Text(usize, usize),
+}
+
+/// 列式保存的日志条目
+///
+/// 条目按追加顺序保存，行号应递增（解析结果本身就是按行号排序的）。
+#[derive(Debug, Clone, Default)]
+pub struct ColumnarEntries {
+    line_numbers: Vec<usize>,
+    contents: TextColumn,
+    formatted: TextColumn,
+    messages: TextColumn,
+    timestamps: TextColumn,
+    levels: Vec<Option<Symbol>>,
+    chains: Vec<u32>,
+    /// 每条条目元数据在 `meta_pairs` 中的结束位置
+    meta_ends: Vec<usize>,
+    meta_pairs: Vec<(Symbol, MetaValue)>,
+    meta_text: String,
+    chain_table: Vec<Vec<Symbol>>,
+    chain_ids: HashMap<Vec<Symbol>, u32>,
+    symbols: Interner,
+}
+
+impl ColumnarEntries {
+    /// 创建空存储
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 条目数
+    pub fn len(&self) -> usize {
+        self.line_numbers.len()
+    }
+
+    /// 追加一条条目
+    pub fn push(&mut self, entry: &LogLine) {
+        self.line_numbers.push(entry.line_number);
+        self.contents.push(Some(&entry.content));
+        self.formatted.push(entry.formatted_content.as_deref());
+        self.messages.push(entry.message.as_deref());
+        self.timestamps.push(entry.timestamp.as_deref());
+        self.levels.push(entry.level.as_deref().map(|level| self.symbols.intern(level)));
+
+        let chain: Vec<Symbol> = entry.processed_by.iter().map(|name| self.symbols.intern(name)).collect();
+        let chain_id = match self.chain_ids.get(&chain) {
+            Some(&id) => id,
+            None => {
+                let id = self.chain_table.len() as u32;
+                self.chain_table.push(chain.clone());
+                self.chain_ids.insert(chain, id);
+                id
+            }
+        };
+        self.chains.push(chain_id);
+
+        // 按键排序，保证同一条目每次构造出的视图一致
+        let mut metadata: Vec<(&String, &String)> = entry.metadata.iter().collect();
+        metadata.sort();
+        for (key, value) in metadata {
+            let value = if INTERNED_METADATA_KEYS.contains(&key.as_str()) {
+                MetaValue::Interned(self.symbols.intern(value))
+            } else {
+                let start = self.meta_text.len();
+                self.meta_text.push_str(value);
+                MetaValue::Text(start, self.meta_text.len())
+            };
+            self.meta_pairs.push((self.symbols.intern(key), value));
+        }
+        self.meta_ends.push(self.meta_pairs.len());
+    }
+
+    /// 追加多条条目
+    pub fn extend<'a, I: IntoIterator<Item = &'a LogLine>>(&mut self, entries: I) {
+        for entry in entries {
+            self.push(entry);
+        }
+    }
+
+    /// 第 `i` 条条目的行号
+    pub fn line_number(&self, i: usize) -> Option<usize> {
+        self.line_numbers.get(i).copied()
+    }
+
+    /// 第 `i` 条条目的级别（不构造完整条目）
+    pub fn level(&self, i: usize) -> Option<&str> {
+        self.levels.get(i).copied().flatten().map(|symbol| self.symbols.resolve(symbol))
+    }
+
+    /// 构造第 `i` 条条目的 `LogLine` 视图
+    pub fn get(&self, i: usize) -> Option<LogLine> {
+        let line_number = self.line_number(i)?;
+        let meta_start = if i == 0 { 0 } else { self.meta_ends[i - 1] };
+        let metadata = self.meta_pairs[meta_start..self.meta_ends[i]].iter().map(|&(key, value)| {
+            let value = match value {
+                MetaValue::Interned(symbol) => self.symbols.resolve(symbol),
+                MetaValue::Text(start, end) => &self.meta_text[start..end],
+            };
+            (self.symbols.resolve(key).to_string(), value.to_string())
+        }).collect();
+
+        Some(LogLine {
+            line_number,
+            content: self.contents.get(i).unwrap_or_default().to_string(),
+            level: self.level(i).map(str::to_string),
+            timestamp: self.timestamps.get(i).map(str::to_string),
+            formatted_content: self.formatted.get(i).map(str::to_string),
+            message: self.messages.get(i).map(str::to_string),
+            metadata,
+            processed_by: self.chain_table[self.chains[i] as usize].iter()
+                .map(|&symbol| self.symbols.resolve(symbol).to_string())
+                .collect(),
+        })
+    }
+
+    /// 依次构造每条条目的视图
+    pub fn iter(&self) -> impl Iterator<Item = LogLine> + '_ {
+        (0..self.len()).filter_map(|i| self.get(i))
+    }
+
+    /// 构造全部条目
+    pub fn to_vec(&self) -> Vec<LogLine> {
+        self.iter().collect()
+    }
+
+    /// 构造行号在 `first_line..=last_line` 范围内的条目，以及范围前后各 `padding` 条条目
+    ///
+    /// 按行号二分查找，只构造范围内的条目。
+    pub fn range_by_line(&self, first_line: usize, last_line: usize, padding: usize) -> Vec<LogLine> {
+        let start = self.line_numbers.partition_point(|&n| n < first_line).saturating_sub(padding);
+        let end = (self.line_numbers.partition_point(|&n| n <= last_line) + padding).min(self.len());
+        (start..end.max(start)).filter_map(|i| self.get(i)).collect()
+    }
+
+    /// 删除行号不小于 `line_number` 的条目
+    pub fn truncate_from_line(&mut self, line_number: usize) {
+        let len = self.line_numbers.partition_point(|&n| n < line_number);
+        if len >= self.len() {
+            return;
+        }
+        let meta_len = if len == 0 { 0 } else { self.meta_ends[len - 1] };
+        if let Some(&(_, MetaValue::Text(start, _))) =
+            self.meta_pairs[meta_len..].iter().find(|(_, value)| matches!(value, MetaValue::Text(..)))
+        {
+            self.meta_text.truncate(start);
+        }
+        self.meta_pairs.truncate(meta_len);
+        self.meta_ends.truncate(len);
+        self.line_numbers.truncate(len);
+        self.contents.truncate(len);
+        self.formatted.truncate(len);
+        self.messages.truncate(len);
+        self.timestamps.truncate(len);
+        self.levels.truncate(len);
+        self.chains.truncate(len);
+    }
+
+    /// 估算的内存占用（字节）
+    pub fn heap_bytes(&self) -> u64 {
+        let per_entry = self.line_numbers.capacity() * std::mem::size_of::<usize>()
+            + self.levels.capacity() * std::mem::size_of::<Option<Symbol>>()
+            + self.chains.capacity() * 4
+            + self.meta_ends.capacity() * std::mem::size_of::<usize>();
+        let chains: usize = self.chain_table.iter().map(|chain| chain.len() * 4 * 2 + 48).sum();
+        (per_entry
+            + self.contents.heap_bytes()
+            + self.formatted.heap_bytes()
+            + self.messages.heap_bytes()
+            + self.timestamps.heap_bytes()
+            + self.meta_pairs.capacity() * std::mem::size_of::<(Symbol, MetaValue)>()
+            + self.meta_text.capacity()
+            + self.symbols.heap_bytes()
+            + chains) as u64
+    }
+}
+
+impl From<&[LogLine]> for ColumnarEntries {
+    fn from(entries: &[LogLine]) -> Self {
+        let mut columns = Self::new();
+        columns.extend(entries);
+        columns
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(line_number: usize, level: Option<&str>, request_id: &str) -> LogLine {
+        LogLine {
+            level: level.map(str::to_string),
+            timestamp: Some(format!("2024-01-15 10:30:{:02}", line_number)),
+            formatted_content: line_number.is_multiple_of(2).then(|| "formatted".to_string()),
+            metadata: HashMap::from([
+                ("thread".to_string(), "main".to_string()),
+                ("request_id".to_string(), request_id.to_string()),
+            ]),
+            processed_by: vec!["springboot_parser".to_string(), "json_formatter".to_string()],
+            ..LogLine::new(line_number, format!("request {} handled", request_id))
+        }
+    }
+
+    fn json(entries: &[LogLine]) -> serde_json::Value {
+        serde_json::to_value(entries).unwrap()
+    }
+
+    #[test]
+    fn test_round_trip_and_truncate_from_line() {
+        let entries: Vec<LogLine> = (1..=6)
+            .map(|n| entry(n * 2, if n % 3 == 0 { None } else { Some("INFO") }, &format!("r-{}", n)))
+            .collect();
+        let mut columns = ColumnarEntries::from(entries.as_slice());
+        assert_eq!(json(&columns.to_vec()), json(&entries));
+        assert_eq!((columns.level(2), columns.level(3)), (None, Some("INFO")));
+        assert_eq!(json(&columns.range_by_line(3, 8, 0)), json(&entries[1..4]));
+        assert_eq!(json(&columns.range_by_line(5, 5, 1)), json(&entries[1..3]));
+        // 级别、线程和处理链只保存一份，驻留表归存储所有
+        assert_eq!(columns.chain_table.len(), 1);
+        assert_eq!(columns.symbols.strings.len(), 6);
+        assert!(ColumnarEntries::new().symbols.strings.is_empty());
+
+        columns.truncate_from_line(7);
+        assert_eq!(json(&columns.to_vec()), json(&entries[..3]));
+        columns.push(&entries[5]);
+        assert_eq!(json(&columns.to_vec()), json(&[entries[0].clone(), entries[1].clone(), entries[2].clone(), entries[5].clone()]));
+
+        columns.truncate_from_line(1);
+        assert_eq!(columns.len(), 0);
+        assert!(columns.meta_text.is_empty() && columns.contents.buf.is_empty());
+    }
+}
@@ -0,0 +1,310 @@
+/// HTTP(S) 日志下载
+///
+/// 允许直接打开 `http://` / `https://` 链接（如CI构建产物中的日志）：
+/// 流式下载到应用数据目录下的缓存文件，之后与本地文件一样按行索引、分块解析。
+///
+/// # 断点续传
+/// 下载中的内容先写入 `.part` 文件，同时记录服务器返回的 ETag / Last-Modified。
+/// 再次下载同一URL时，如果服务器支持 `Accept-Ranges: bytes`，使用 `Range` 从已下载的位置继续，
+/// 并携带 `If-Range`：文件在服务器上已变化时服务器返回完整内容，从头下载。
+
+use crate::i18n::Message;
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// 两次进度回调之间下载的字节数
+const PROGRESS_INTERVAL: u64 = 1024 * 1024;
+
+/// 读缓冲区大小
+const DOWNLOAD_BUFFER_SIZE: usize = 256 * 1024;
+
+/// 判断路径是否为HTTP(S)链接
+pub fn is_http_url(path: &str) -> bool {
+    let lower = path.trim_start().to_ascii_lowercase();
+    lower.starts_with("https://") || lower.starts_with("http://")
+}
+
+/// 下载进度
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadProgress {
+    /// 下载链接
+    pub url: String,
+    /// 已下载的字节数（包括续传前已有的部分）
+    pub bytes_downloaded: u64,
+    /// 总字节数（服务器未提供长度时为None）
+    pub total_bytes: Option<u64>,
+}
+
+/// 下载结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadResult {
+    /// 下载链接
+    pub url: String,
+    /// 本地缓存文件路径
+    pub local_path: String,
+    /// 文件字节数
+    pub bytes: u64,
+    /// 是否从上次中断的位置续传
+    pub resumed: bool,
+    /// 是否直接使用了已完成的缓存
+    pub from_cache: bool,
+}
+
+/// 续传所需的服务器信息（与 `.part` 文件一起保存）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct PartialMeta {
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    accepts_ranges: bool,
+    total_bytes: Option<u64>,
+}
+
+/// HTTP下载器
+pub struct HttpDownloader {
+    cache_dir: PathBuf,
+    agent: ureq::Agent,
+}
+
+impl HttpDownloader {
+    /// 创建下载器
+    ///
+    /// # 参数
+    /// - `cache_dir`: 下载缓存目录
+    pub fn new<P: AsRef<Path>>(cache_dir: P) -> Self {
+        let agent = ureq::AgentBuilder::new()
+            .timeout_connect(Duration::from_secs(15))
+            .timeout_read(Duration::from_secs(60))
+            .user_agent(concat!("LogWhisper/", env!("CARGO_PKG_VERSION")))
+            .build();
+        Self {
+            cache_dir: cache_dir.as_ref().to_path_buf(),
+            agent,
+        }
+    }
+
+    /// URL对应的缓存文件路径
+    ///
+    /// 文件名由URL的FNV-1a哈希和URL最后一段组成，保留扩展名以便按扩展名检测格式。
+    pub fn cache_path(&self, url: &str) -> PathBuf {
+        let hash = url.bytes().fold(0xcbf29ce484222325u64, |h, b| (h ^ b as u64).wrapping_mul(0x100000001b3));
+        let last_segment = url.split(['?', '#']).next().unwrap_or(url)
+            .rsplit('/')
+            .find(|s| !s.is_empty())
+            .unwrap_or("download");
+        let name: String = last_segment.chars()
+            .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') { c } else { '_' })
+            .take(64)
+            .collect();
+        self.cache_dir.join(format!("{:016x}-{}", hash, name))
+    }
+
+    /// 下载URL到缓存文件
+    ///
+    /// # 参数
+    /// - `url`: HTTP(S)链接
+    /// - `refresh`: 为true时忽略已完成的缓存重新下载
+    /// - `on_progress`: 进度回调（约每1MB调用一次，完成时再调用一次）
+    ///
+    /// # Returns
+    /// - `Ok(DownloadResult)`: 缓存文件路径和下载信息
+    /// - `Err(String)`: 网络错误、服务器返回错误状态或写入失败时的错误信息
+    pub fn download<F>(&self, url: &str, refresh: bool, mut on_progress: F) -> Result<DownloadResult, String>
+    where
+        F: FnMut(&DownloadProgress),
+    {
+        if !is_http_url(url) {
+            return Err(Message::new("download.invalid_url").with("url", url).text());
+        }
+        let target = self.cache_path(url);
+        if !refresh {
+            if let Ok(metadata) = std::fs::metadata(&target) {
+                log::info!("📦 使用已下载的缓存: {}", target.display());
+                return Ok(DownloadResult {
+                    url: url.to_string(),
+                    local_path: target.to_string_lossy().into_owned(),
+                    bytes: metadata.len(),
+                    resumed: false,
+                    from_cache: true,
+                });
+            }
+        }
+
+        std::fs::create_dir_all(&self.cache_dir).map_err(|e| Message::new("download.create_directory_failed").with("error", e).text())?;
+        let part_path = target.with_extension("part");
+        let meta_path = target.with_extension("part.json");
+
+        // 已有未完成的下载且服务器支持Range时续传
+        let previous: Option<PartialMeta> = std::fs::read_to_string(&meta_path).ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .filter(|meta: &PartialMeta| meta.url == url && meta.accepts_ranges);
+        let existing = std::fs::metadata(&part_path).map(|m| m.len()).unwrap_or(0);
+        let resume_from = if previous.is_some() { existing } else { 0 };
+
+        let mut request = self.agent.get(url);
+        if resume_from > 0 {
+            request = request.set("Range", &format!("bytes={}-", resume_from));
+            if let Some(validator) = previous.as_ref().and_then(|m| m.etag.clone().or(m.last_modified.clone())) {
+                request = request.set("If-Range", &validator);
+            }
+        }
+
+        let response = match request.call() {
+            Ok(response) => response,
+            Err(ureq::Error::Status(416, _)) if resume_from > 0 => {
+                // 已下载部分超出服务器文件长度，放弃续传重新下载
+                std::fs::remove_file(&part_path).ok();
+                std::fs::remove_file(&meta_path).ok();
+                return self.download(url, refresh, on_progress);
+            }
+            Err(ureq::Error::Status(code, response)) => {
+                return Err(Message::new("download.http_error").with("status", code).with("reason", response.status_text()).text());
+            }
+            Err(e) => return Err(Message::new("download.failed").with("error", e).text()),
+        };
+
+        let resumed = resume_from > 0 && response.status() == 206;
+        let offset = if resumed { resume_from } else { 0 };
+        let content_length = response.header("Content-Length").and_then(|v| v.parse::<u64>().ok());
+        let meta = PartialMeta {
+            url: url.to_string(),
+            etag: response.header("ETag").map(str::to_string),
+            last_modified: response.header("Last-Modified").map(str::to_string),
+            accepts_ranges: resumed || response.header("Accept-Ranges").is_some_and(|v| v.eq_ignore_ascii_case("bytes")),
+            total_bytes: content_length.map(|len| len + offset),
+        };
+        if let Ok(text) = serde_json::to_string(&meta) {
+            std::fs::write(&meta_path, text).ok();
+        }
+        if resumed {
+            log::info!("⏯️ 从 {} 字节处续传: {}", resume_from, url);
+        }
+
+        let mut file = if resumed {
+            OpenOptions::new().append(true).open(&part_path)
+        } else {
+            File::create(&part_path)
+        }.map_err(|e| Message::new("download.create_file_failed").with("error", e).text())?;
+
+        let mut reader = response.into_reader();
+        let mut buffer = vec![0u8; DOWNLOAD_BUFFER_SIZE];
+        let mut downloaded = offset;
+        let mut next_report = downloaded + PROGRESS_INTERVAL;
+        loop {
+            let n = reader.read(&mut buffer).map_err(|e| Message::new("download.interrupted").with("error", e).text())?;
+            if n == 0 {
+                break;
+            }
+            file.write_all(&buffer[..n]).map_err(|e| Message::new("download.write_failed").with("error", e).text())?;
+            downloaded += n as u64;
+            if downloaded >= next_report {
+                on_progress(&DownloadProgress { url: url.to_string(), bytes_downloaded: downloaded, total_bytes: meta.total_bytes });
+                next_report = downloaded + PROGRESS_INTERVAL;
+            }
+        }
+        file.sync_all().map_err(|e| Message::new("download.write_failed").with("error", e).text())?;
+        drop(file);
+
+        if let Some(total) = meta.total_bytes {
+            if downloaded < total {
+                return Err(Message::new("download.incomplete").with("downloaded", downloaded).with("total", total).text());
+            }
+        }
+        on_progress(&DownloadProgress { url: url.to_string(), bytes_downloaded: downloaded, total_bytes: Some(downloaded) });
+
+        std::fs::rename(&part_path, &target).map_err(|e| Message::new("download.save_failed").with("error", e).text())?;
+        std::fs::remove_file(&meta_path).ok();
+        log::info!("✅ 下载完成: {} ({} bytes)", url, downloaded);
+
+        Ok(DownloadResult {
+            url: url.to_string(),
+            local_path: target.to_string_lossy().into_owned(),
+            bytes: downloaded,
+            resumed,
+            from_cache: false,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader};
+    use std::net::TcpListener;
+
+    /// 在本地启动只处理一个请求的HTTP服务器，支持 `Range: bytes=N-`
+    fn serve_once(body: &'static [u8]) -> (String, std::thread::JoinHandle<Option<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/logs/build.log", listener.local_addr().unwrap());
+        let handle = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut range = None;
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                if let Some(value) = line.strip_prefix("range: bytes=").or(line.strip_prefix("Range: bytes=")) {
+                    range = Some(value.trim().trim_end_matches('-').to_string());
+                }
+            }
+            let start: usize = range.as_deref().map(|r| r.parse().unwrap()).unwrap_or(0);
+            let status = if range.is_some() { "206 Partial Content" } else { "200 OK" };
+            let header = format!(
+                "HTTP/1.1 {}\r\nContent-Length: {}\r\nAccept-Ranges: bytes\r\nETag: \"v1\"\r\nConnection: close\r\n\r\n",
+                status, body.len() - start
+            );
+            stream.write_all(header.as_bytes()).unwrap();
+            stream.write_all(&body[start..]).unwrap();
+            range
+        });
+        (url, handle)
+    }
+
+    fn downloader(name: &str) -> HttpDownloader {
+        let dir = std::env::temp_dir().join(format!("log_whisper_{}_{}", name, std::process::id()));
+        std::fs::remove_dir_all(&dir).ok();
+        HttpDownloader::new(dir)
+    }
+
+    #[test]
+    fn test_download_then_use_cache() {
+        let downloader = downloader("http_download");
+        let (url, server) = serve_once(b"line 1\nline 2\n");
+
+        let result = downloader.download(&url, false, |_| {}).unwrap();
+        assert!(!result.from_cache);
+        assert_eq!(std::fs::read_to_string(&result.local_path).unwrap(), "line 1\nline 2\n");
+        assert!(result.local_path.ends_with("-build.log"));
+        assert_eq!(server.join().unwrap(), None);
+
+        // 已完成的缓存不会再次请求
+        assert!(downloader.download(&url, false, |_| {}).unwrap().from_cache);
+        std::fs::remove_dir_all(&downloader.cache_dir).ok();
+    }
+
+    #[test]
+    fn test_resumes_partial_download_with_range() {
+        let downloader = downloader("http_resume");
+        let (url, server) = serve_once(b"0123456789");
+
+        let target = downloader.cache_path(&url);
+        std::fs::create_dir_all(&downloader.cache_dir).unwrap();
+        std::fs::write(target.with_extension("part"), b"0123").unwrap();
+        let meta = PartialMeta { url: url.clone(), etag: Some("\"v1\"".to_string()), accepts_ranges: true, ..Default::default() };
+        std::fs::write(target.with_extension("part.json"), serde_json::to_string(&meta).unwrap()).unwrap();
+
+        let mut last_progress = None;
+        let result = downloader.download(&url, false, |p| last_progress = Some(p.bytes_downloaded)).unwrap();
+        assert!(result.resumed);
+        assert_eq!(server.join().unwrap().as_deref(), Some("4"));
+        assert_eq!(std::fs::read_to_string(&result.local_path).unwrap(), "0123456789");
+        assert_eq!(last_progress, Some(10));
+        std::fs::remove_dir_all(&downloader.cache_dir).ok();
+    }
+}
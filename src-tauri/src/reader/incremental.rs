@@ -0,0 +1,393 @@
+use crate::plugins::chain::END_LINE_METADATA_KEY;
+use crate::plugins::{LogLine, ParseResult};
+use crate::reader::columnar::ColumnarEntries;
+use crate::reader::line_index::{prefix_fingerprint, LineIndex};
+use crate::records::record_end_line;
+use crate::runtime_stats::CacheUsage;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// 已解析文件的快照
+///
+/// 记录上一次解析时文件的状态（大小、前缀指纹、行数）和解析结果，
+/// 刷新时据此判断文件是否只是在末尾追加了内容。
+#[derive(Debug, Clone)]
+struct ParseSnapshot {
+    /// 已解析部分的字节数
+    file_size: u64,
+    /// 已解析部分的前缀指纹
+    fingerprint: u64,
+    /// 已解析部分的总行数（包括空行）
+    line_count: usize,
+    /// 已解析部分是否以换行符结尾
+    ends_with_newline: bool,
+    /// 缓存的解析结果（列式保存）
+    entries: ColumnarEntries,
+    /// 检测到的日志格式
+    detected_format: Option<String>,
+}
+
+/// 刷新方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RefreshMode {
+    /// 首次解析或文件被改写/截断，完整重新解析
+    Full,
+    /// 文件只在末尾追加了内容，只解析新增部分
+    Appended,
+    /// 文件未变化
+    Unchanged,
+}
+
+/// 刷新结果（增量）
+///
+/// 前端应删除 `line_number >= replace_from_line` 的已有条目，再追加 `entries`。
+/// `Full` 模式下 `replace_from_line` 为1，即替换全部条目。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParseDelta {
+    /// 文件路径
+    pub file_path: String,
+    /// 本次刷新的方式
+    pub mode: RefreshMode,
+    /// 从该行号开始的条目被 `entries` 替换
+    pub replace_from_line: usize,
+    /// 新解析出的条目
+    pub entries: Vec<LogLine>,
+    /// 文件当前总行数（包括空行）
+    pub total_lines: usize,
+    /// 刷新后缓存中的条目总数
+    pub total_entries: usize,
+    /// 本次实际解析的字节数
+    pub parsed_bytes: u64,
+    /// 检测到的日志格式
+    pub detected_format: Option<String>,
+}
+
+/// 增量解析缓存
+///
+/// 按文件路径缓存解析结果。文件增长时只解析追加的区域并修补缓存；
+/// 如果上次解析的最后一行没有换行符（仍在写入中），该行会与新内容一起重新解析。
+///
+/// 追加区域单独进行格式检测，跨越边界的多行记录（如堆栈的续行）不会与之前的条目合并。
+pub struct IncrementalParseCache {
+    snapshots: Mutex<HashMap<PathBuf, ParseSnapshot>>,
+}
+
+impl IncrementalParseCache {
+    /// 创建空缓存
+    pub fn new() -> Self {
+        Self {
+            snapshots: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 根据最新的行索引刷新文件的解析结果
+    ///
+    /// # 参数
+    /// - `index`: 与磁盘文件一致的行索引（通常来自 `MemoryBudget::line_index`）
+    /// - `parse`: 解析函数，输入一段内容，返回的行号相对于该内容从1开始
+    ///
+    /// # Returns
+    /// - `Ok(ParseDelta)`: 本次刷新产生的增量
+    /// - `Err(String)`: 读取或解析失败时的错误信息
+    pub fn refresh<F>(&self, index: &LineIndex, parse: F) -> Result<ParseDelta, String>
+    where
+        F: FnOnce(&str) -> Result<ParseResult, String>,
+    {
+        let path = index.file_path.clone();
+        let previous = self.snapshots.lock().unwrap().get(&path).cloned();
+
+        let mut snapshot = match previous {
+            Some(snapshot) if snapshot.file_size == index.file_size && snapshot.fingerprint == index.fingerprint => {
+                return Ok(Self::delta(&path, RefreshMode::Unchanged, index.line_count() + 1, Vec::new(), 0, &snapshot));
+            }
+            Some(snapshot) if Self::is_append_of(&snapshot, index)? => snapshot,
+            _ => {
+                let snapshot = Self::parse_full(index, parse)?;
+                let delta = Self::delta(&path, RefreshMode::Full, 1, snapshot.entries.to_vec(), index.file_size, &snapshot);
+                self.snapshots.lock().unwrap().insert(path, snapshot);
+                return Ok(delta);
+            }
+        };
+
+        // 上次最后一行未以换行结尾时，新内容是该行的延续，需要从该行重新解析
+        let replace_from_line = if snapshot.ends_with_newline || snapshot.line_count == 0 {
+            snapshot.line_count + 1
+        } else {
+            snapshot.line_count
+        };
+        let parsed_bytes = index.line_span(replace_from_line)
+            .map(|(start, _)| index.file_size - start)
+            .unwrap_or(0);
+
+        let mut new_entries = Vec::new();
+        if replace_from_line <= index.line_count() {
+            let content = index.read_lines(replace_from_line, index.line_count())?.join("\n");
+            let result = parse(&content)?;
+            new_entries = result.lines;
+            for entry in &mut new_entries {
+                entry.line_number += replace_from_line - 1;
+                if let Some(end) = record_end_line(entry) {
+                    entry.metadata.insert(END_LINE_METADATA_KEY.to_string(), (end + replace_from_line - 1).to_string());
+                }
+            }
+            index.mark_offsets(&mut new_entries);
+            if snapshot.detected_format.is_none() {
+                snapshot.detected_format = result.detected_format;
+            }
+        }
+
+        log::info!("➕ 增量解析: {:?} 从第{}行起新增 {} 条 ({} bytes)",
+                   path, replace_from_line, new_entries.len(), parsed_bytes);
+
+        snapshot.entries.truncate_from_line(replace_from_line);
+        snapshot.entries.extend(&new_entries);
+        snapshot.file_size = index.file_size;
+        snapshot.fingerprint = index.fingerprint;
+        snapshot.line_count = index.line_count();
+        snapshot.ends_with_newline = index.ends_with_newline;
+
+        let delta = Self::delta(&path, RefreshMode::Appended, replace_from_line, new_entries, parsed_bytes, &snapshot);
+        self.snapshots.lock().unwrap().insert(path, snapshot);
+        Ok(delta)
+    }
+
+    /// 获取文件缓存的全部解析条目（未解析过时返回None）
+    ///
+    /// 条目在缓存中按列保存，返回时才构造 `LogLine`。
+    pub fn entries<P: AsRef<Path>>(&self, path: P) -> Option<Vec<LogLine>> {
+        self.snapshots.lock().unwrap().get(path.as_ref()).map(|snapshot| snapshot.entries.to_vec())
+    }
+
+    /// 获取文件缓存中行号在 `first_line..=last_line` 范围内的条目，以及前后各 `padding` 条条目（未解析过时返回None）
+    ///
+    /// 只构造范围内的条目，适合只需要少量条目的命令。
+    pub fn entries_in_lines<P: AsRef<Path>>(&self, path: P, first_line: usize, last_line: usize, padding: usize) -> Option<Vec<LogLine>> {
+        self.snapshots.lock().unwrap().get(path.as_ref())
+            .map(|snapshot| snapshot.entries.range_by_line(first_line, last_line, padding))
+    }
+
+    /// 用文件缓存的条目执行 `f`（未解析过时返回None）
+    ///
+    /// 不构造全部条目，适合扫描后只保留少量条目的命令；执行期间持有缓存锁。
+    pub fn with_entries<P: AsRef<Path>, R>(&self, path: P, f: impl FnOnce(&ColumnarEntries) -> R) -> Option<R> {
+        self.snapshots.lock().unwrap().get(path.as_ref()).map(|snapshot| f(&snapshot.entries))
+    }
+
+    /// 用保存的解析结果填充缓存（如会话数据库中持久化的条目）
+    ///
+    /// 只有当前文件与保存时的大小和前缀指纹一致时才会填充，否则返回false，
+    /// 下次刷新时按正常流程解析。
+    ///
+    /// # 参数
+    /// - `index`: 文件当前的行索引
+    /// - `file_size`: 保存时的文件大小
+    /// - `fingerprint`: 保存时的前缀指纹
+    /// - `entries`: 保存的解析条目
+    /// - `detected_format`: 保存时检测到的日志格式
+    pub fn restore(
+        &self,
+        index: &LineIndex,
+        file_size: u64,
+        fingerprint: u64,
+        entries: Vec<LogLine>,
+        detected_format: Option<String>,
+    ) -> bool {
+        if index.file_size != file_size || index.fingerprint != fingerprint {
+            return false;
+        }
+        let snapshot = ParseSnapshot {
+            file_size,
+            fingerprint,
+            line_count: index.line_count(),
+            ends_with_newline: index.ends_with_newline,
+            entries: ColumnarEntries::from(entries.as_slice()),
+            detected_format,
+        };
+        self.snapshots.lock().unwrap().insert(index.file_path.clone(), snapshot);
+        true
+    }
+
+    /// 缓存占用（文件数、条目数和估算的内存字节数）
+    pub fn usage(&self) -> CacheUsage {
+        let snapshots = self.snapshots.lock().unwrap();
+        let mut usage = CacheUsage { files: snapshots.len(), ..Default::default() };
+        for snapshot in snapshots.values() {
+            usage.items += snapshot.entries.len();
+            usage.bytes += snapshot.entries.heap_bytes();
+        }
+        usage
+    }
+
+    /// 每个文件缓存的解析结果占用的内存（估算字节数）
+    pub fn file_usage(&self) -> Vec<(PathBuf, u64)> {
+        self.snapshots.lock().unwrap().iter()
+            .map(|(path, snapshot)| (path.clone(), snapshot.entries.heap_bytes()))
+            .collect()
+    }
+
+    /// 移除文件的缓存解析结果，下次刷新时完整重新解析
+    pub fn invalidate<P: AsRef<Path>>(&self, path: P) {
+        self.snapshots.lock().unwrap().remove(path.as_ref());
+    }
+
+    /// 移除所有文件的缓存解析结果（解析规则变化、影响的文件不确定时使用）
+    pub fn clear(&self) {
+        self.snapshots.lock().unwrap().clear();
+    }
+
+    /// 判断当前文件是否是快照内容追加新数据后的结果
+    fn is_append_of(snapshot: &ParseSnapshot, index: &LineIndex) -> Result<bool, String> {
+        if index.file_size < snapshot.file_size {
+            return Ok(false);
+        }
+        Ok(prefix_fingerprint(&index.file_path, snapshot.file_size)? == snapshot.fingerprint)
+    }
+
+    /// 完整解析整个文件
+    fn parse_full<F>(index: &LineIndex, parse: F) -> Result<ParseSnapshot, String>
+    where
+        F: FnOnce(&str) -> Result<ParseResult, String>,
+    {
+        log::info!("🔄 完整解析: {:?} ({} 行)", index.file_path, index.line_count());
+
+        let (entries, detected_format) = if index.line_count() == 0 {
+            (Vec::new(), None)
+        } else {
+            let content = index.read_lines(1, index.line_count())?.join("\n");
+            let mut result = parse(&content)?;
+            index.mark_offsets(&mut result.lines);
+            (result.lines, result.detected_format)
+        };
+        let entries = ColumnarEntries::from(entries.as_slice());
+
+        Ok(ParseSnapshot {
+            file_size: index.file_size,
+            fingerprint: index.fingerprint,
+            line_count: index.line_count(),
+            ends_with_newline: index.ends_with_newline,
+            entries,
+            detected_format,
+        })
+    }
+
+    fn delta(
+        path: &Path,
+        mode: RefreshMode,
+        replace_from_line: usize,
+        entries: Vec<LogLine>,
+        parsed_bytes: u64,
+        snapshot: &ParseSnapshot,
+    ) -> ParseDelta {
+        ParseDelta {
+            file_path: path.to_string_lossy().into_owned(),
+            mode,
+            replace_from_line,
+            entries,
+            total_lines: snapshot.line_count,
+            total_entries: snapshot.entries.len(),
+            parsed_bytes,
+            detected_format: snapshot.detected_format.clone(),
+        }
+    }
+}
+
+impl Default for IncrementalParseCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn write_temp_file(name: &str, content: &[u8]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("log_whisper_{}_{}.log", name, std::process::id()));
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn append(path: &Path, content: &[u8]) {
+        std::fs::OpenOptions::new().append(true).open(path).unwrap().write_all(content).unwrap();
+    }
+
+    /// 模拟插件链：每个非空行一条记录，行号相对于输入内容
+    fn parse_lines(content: &str) -> Result<ParseResult, String> {
+        let lines = content.lines().enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| LogLine::new(i + 1, line))
+            .collect();
+        Ok(ParseResult {
+            lines,
+            total_lines: content.lines().count(),
+            detected_format: Some("Test".to_string()),
+            parsing_errors: Vec::new(),
+            applied_filters: Vec::new(),
+            capabilities: None,
+        })
+    }
+
+    fn refresh(cache: &IncrementalParseCache, path: &Path) -> ParseDelta {
+        let index = LineIndex::build(path).unwrap();
+        cache.refresh(&index, parse_lines).unwrap()
+    }
+
+    #[test]
+    fn test_append_parses_only_new_lines() {
+        let path = write_temp_file("incremental_append", b"one\ntwo\n");
+        let cache = IncrementalParseCache::new();
+
+        let first = refresh(&cache, &path);
+        assert_eq!(first.mode, RefreshMode::Full);
+        assert_eq!(first.entries.len(), 2);
+
+        assert_eq!(refresh(&cache, &path).mode, RefreshMode::Unchanged);
+
+        append(&path, b"\nthree\n");
+        let delta = refresh(&cache, &path);
+        assert_eq!(delta.mode, RefreshMode::Appended);
+        assert_eq!(delta.replace_from_line, 3);
+        assert_eq!(delta.entries.len(), 1);
+        assert_eq!(delta.entries[0].line_number, 4);
+        assert_eq!(delta.entries[0].content, "three");
+        assert_eq!(delta.total_entries, 3);
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_partial_last_line_is_reparsed() {
+        let path = write_temp_file("incremental_partial", b"one\ntw");
+        let cache = IncrementalParseCache::new();
+        refresh(&cache, &path);
+
+        append(&path, b"o\nthree\n");
+        let delta = refresh(&cache, &path);
+        assert_eq!(delta.mode, RefreshMode::Appended);
+        assert_eq!(delta.replace_from_line, 2);
+        let contents: Vec<&str> = delta.entries.iter().map(|e| e.content.as_str()).collect();
+        assert_eq!(contents, vec!["two", "three"]);
+        assert_eq!(delta.total_entries, 3);
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_rewritten_file_falls_back_to_full_parse() {
+        let path = write_temp_file("incremental_rewrite", b"one\ntwo\n");
+        let cache = IncrementalParseCache::new();
+        refresh(&cache, &path);
+
+        std::fs::write(&path, b"uno\ndos\ntres\n").unwrap();
+        let delta = refresh(&cache, &path);
+        assert_eq!(delta.mode, RefreshMode::Full);
+        assert_eq!(delta.replace_from_line, 1);
+        assert_eq!(delta.entries.len(), 3);
+
+        std::fs::remove_file(path).ok();
+    }
+}
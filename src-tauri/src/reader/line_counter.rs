@@ -0,0 +1,135 @@
+use crate::i18n::Message;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// 统计行数时使用的读缓冲区大小
+const COUNT_BUFFER_SIZE: usize = 256 * 1024;
+
+/// 两次进度回调之间扫描的字节数
+const PROGRESS_INTERVAL: u64 = 64 * 1024 * 1024;
+
+/// 行数统计结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LineCount {
+    /// 行数（与 `str::lines().count()` 一致，估算时为推算值）
+    pub lines: usize,
+    /// 实际扫描的字节数
+    pub bytes_scanned: u64,
+    /// 文件总字节数
+    pub total_bytes: u64,
+    /// 是否只扫描了部分内容并按比例估算
+    pub estimated: bool,
+}
+
+/// 行数统计进度
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanProgress {
+    /// 已扫描的字节数
+    pub bytes_scanned: u64,
+    /// 文件总字节数
+    pub total_bytes: u64,
+    /// 目前已统计到的换行符数量
+    pub lines_counted: usize,
+}
+
+/// 流式统计文件行数
+///
+/// 按固定大小的块读取文件并统计换行符，内存占用与文件大小无关。
+/// 指定 `max_scan_bytes` 时只扫描文件开头的这部分内容，
+/// 再按扫描部分的平均行长推算总行数。
+///
+/// # 参数
+/// - `path`: 文件路径
+/// - `max_scan_bytes`: 最多扫描的字节数（可选，不指定时扫描整个文件）
+/// - `on_progress`: 进度回调，每扫描约64MB调用一次
+///
+/// # Returns
+/// - `Ok(LineCount)`: 行数统计结果
+/// - `Err(String)`: 文件无法打开或读取时的错误信息
+pub fn count_lines<P, F>(path: P, max_scan_bytes: Option<u64>, mut on_progress: F) -> Result<LineCount, String>
+where
+    P: AsRef<Path>,
+    F: FnMut(&ScanProgress),
+{
+    let mut file = File::open(path.as_ref())
+        .map_err(|e| Message::new("io.open_failed").with("error", e).text())?;
+    let total_bytes = file.metadata()
+        .map_err(|e| Message::new("io.metadata_failed").with("error", e).text())?
+        .len();
+    let scan_limit = max_scan_bytes.unwrap_or(u64::MAX).min(total_bytes);
+
+    let mut buffer = vec![0u8; COUNT_BUFFER_SIZE];
+    let mut newlines = 0usize;
+    let mut bytes_scanned = 0u64;
+    let mut last_byte = None;
+    let mut next_progress = PROGRESS_INTERVAL;
+
+    while bytes_scanned < scan_limit {
+        let want = (scan_limit - bytes_scanned).min(buffer.len() as u64) as usize;
+        let read = file.read(&mut buffer[..want])
+            .map_err(|e| Message::new("io.read_failed").with("error", e).text())?;
+        if read == 0 {
+            break;
+        }
+
+        let block = &buffer[..read];
+        newlines += block.iter().filter(|b| **b == b'\n').count();
+        last_byte = block.last().copied();
+        bytes_scanned += read as u64;
+
+        if bytes_scanned >= next_progress {
+            on_progress(&ScanProgress { bytes_scanned, total_bytes, lines_counted: newlines });
+            next_progress += PROGRESS_INTERVAL;
+        }
+    }
+
+    if bytes_scanned < total_bytes && bytes_scanned > 0 {
+        // 只扫描了部分内容：按扫描部分的平均行长推算
+        let estimated_lines = (newlines.max(1) as f64 * total_bytes as f64 / bytes_scanned as f64).round() as usize;
+        return Ok(LineCount { lines: estimated_lines, bytes_scanned, total_bytes, estimated: true });
+    }
+
+    // 最后一行没有换行符时也计为一行，与 `str::lines()` 保持一致
+    let lines = match last_byte {
+        Some(b'\n') | None => newlines,
+        Some(_) => newlines + 1,
+    };
+    Ok(LineCount { lines, bytes_scanned, total_bytes, estimated: false })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_temp_file(name: &str, content: &[u8]) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("log_whisper_{}_{}.log", name, std::process::id()));
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_count_matches_str_lines() {
+        for (name, content) in [("count_empty", ""), ("count_trailing", "a\nb\n"), ("count_partial", "a\n\nb")] {
+            let path = write_temp_file(name, content.as_bytes());
+            let count = count_lines(&path, None, |_| {}).unwrap();
+            assert_eq!(count.lines, content.lines().count(), "{:?}", content);
+            assert!(!count.estimated);
+            std::fs::remove_file(path).ok();
+        }
+    }
+
+    #[test]
+    fn test_sampled_count_is_estimated() {
+        let content = "0123456789\n".repeat(1000);
+        let path = write_temp_file("count_sampled", content.as_bytes());
+
+        let count = count_lines(&path, Some(1100), |_| {}).unwrap();
+        assert!(count.estimated);
+        assert_eq!(count.bytes_scanned, 1100);
+        assert_eq!(count.lines, 1000);
+
+        std::fs::remove_file(path).ok();
+    }
+}
@@ -0,0 +1,421 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fs::File;
+use std::hash::Hasher;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use crate::i18n::Message;
+use crate::models::LogLine;
+use crate::records::record_end_line;
+use crate::runtime_stats::CacheUsage;
+
+/// 构建索引时使用的读缓冲区大小
+const INDEX_BUFFER_SIZE: usize = 64 * 1024;
+
+/// 计算前缀指纹时读取的头部/尾部块大小
+const FINGERPRINT_BLOCK_SIZE: u64 = 4 * 1024;
+
+/// 条目元数据中记录源文件起始字节偏移的键
+pub const START_OFFSET_METADATA_KEY: &str = "start_offset";
+
+/// 条目元数据中记录源文件结束字节偏移（不包含）的键
+pub const END_OFFSET_METADATA_KEY: &str = "end_offset";
+
+/// 计算文件前缀 `[0, end)` 的指纹
+///
+/// 只读取前缀的头部和尾部各一个小块进行哈希，代价与文件大小无关。
+/// 用于判断文件增长时已处理的前缀是否被改写（如日志轮转、原地编辑）。
+///
+/// # 参数
+/// - `path`: 文件路径
+/// - `end`: 前缀结束位置（字节，不包含）
+pub fn prefix_fingerprint<P: AsRef<Path>>(path: P, end: u64) -> Result<u64, String> {
+    let mut file = File::open(path.as_ref())
+        .map_err(|e| Message::new("io.open_failed").with("error", e).text())?;
+    let mut hasher = DefaultHasher::new();
+    hasher.write_u64(end);
+
+    let head_len = end.min(FINGERPRINT_BLOCK_SIZE);
+    let tail_start = end.saturating_sub(FINGERPRINT_BLOCK_SIZE).max(head_len);
+    for (start, len) in [(0, head_len), (tail_start, end - tail_start)] {
+        let mut block = Vec::with_capacity(len as usize);
+        file.seek(SeekFrom::Start(start))
+            .map_err(|e| Message::new("io.seek_failed").with("error", e).text())?;
+        (&mut file).take(len).read_to_end(&mut block)
+            .map_err(|e| Message::new("io.read_failed").with("error", e).text())?;
+        hasher.write(&block);
+    }
+
+    Ok(hasher.finish())
+}
+
+/// 行偏移索引
+///
+/// 记录文件中每一行起始位置的字节偏移量。构建时只做一次顺序扫描，
+/// 之后即可通过 seek 直接读取任意行区间，而无需加载整个文件。
+///
+/// 行号从1开始，与解析结果中的 `line_number` 保持一致（包括空行）。
+#[derive(Debug, Clone)]
+pub struct LineIndex {
+    /// 被索引的文件路径
+    pub file_path: PathBuf,
+    /// 构建索引时的文件大小（字节）
+    pub file_size: u64,
+    /// 构建索引时的文件修改时间
+    pub modified: Option<SystemTime>,
+    /// 已索引内容的前缀指纹，见 [`prefix_fingerprint`]
+    pub fingerprint: u64,
+    /// 已索引内容是否以换行符结尾（否则最后一行可能仍在写入中）
+    pub ends_with_newline: bool,
+    /// 每一行起始位置的字节偏移量，`offsets[i]` 对应第 `i + 1` 行
+    offsets: Vec<u64>,
+}
+
+impl LineIndex {
+    /// 扫描文件并构建行偏移索引
+    ///
+    /// # 参数
+    /// - `path`: 要索引的文件路径
+    ///
+    /// # Returns
+    /// - `Ok(LineIndex)`: 构建完成的索引
+    /// - `Err(String)`: 文件无法打开或读取时的错误信息
+    pub fn build<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let path = path.as_ref();
+        let mut index = Self {
+            file_path: path.to_path_buf(),
+            file_size: 0,
+            modified: None,
+            fingerprint: 0,
+            ends_with_newline: true,
+            offsets: Vec::new(),
+        };
+        index.scan_from_end()?;
+
+        log::debug!("📇 行索引构建完成: {:?} ({} 行, {} bytes)", path, index.line_count(), index.file_size);
+        Ok(index)
+    }
+
+    /// 根据磁盘上的最新文件刷新索引
+    ///
+    /// 文件只是在末尾追加了内容时，只扫描新增部分并追加行偏移；
+    /// 文件变小或已索引的前缀被改写时，重新构建完整索引。
+    ///
+    /// # Returns
+    /// - `Ok(LineIndex)`: 与当前文件内容一致的新索引
+    /// - `Err(String)`: 文件无法读取时的错误信息
+    pub fn refresh(&self) -> Result<Self, String> {
+        let metadata = std::fs::metadata(&self.file_path)
+            .map_err(|e| Message::new("io.metadata_failed").with("error", e).text())?;
+
+        if metadata.len() < self.file_size
+            || prefix_fingerprint(&self.file_path, self.file_size)? != self.fingerprint
+        {
+            log::debug!("🔄 文件前缀已变化，重建行索引: {:?}", self.file_path);
+            return Self::build(&self.file_path);
+        }
+
+        let mut index = self.clone();
+        index.scan_from_end()?;
+        log::debug!("📇 行索引增量更新: {:?} ({} -> {} 行)", self.file_path, self.line_count(), index.line_count());
+        Ok(index)
+    }
+
+    /// 从当前已索引的位置继续扫描到文件末尾，追加新行的偏移量
+    fn scan_from_end(&mut self) -> Result<(), String> {
+        let mut file = File::open(&self.file_path)
+            .map_err(|e| Message::new("io.open_failed").with("error", e).text())?;
+        let metadata = file.metadata()
+            .map_err(|e| Message::new("io.metadata_failed").with("error", e).text())?;
+        file.seek(SeekFrom::Start(self.file_size))
+            .map_err(|e| Message::new("io.seek_failed").with("error", e).text())?;
+
+        let mut reader = BufReader::with_capacity(INDEX_BUFFER_SIZE, file);
+        let offsets = &mut self.offsets;
+        let mut position = self.file_size;
+        let mut at_line_start = self.ends_with_newline;
+
+        loop {
+            let buffer = reader.fill_buf()
+                .map_err(|e| Message::new("io.read_failed").with("error", e).text())?;
+            if buffer.is_empty() {
+                break;
+            }
+
+            for (i, byte) in buffer.iter().enumerate() {
+                if at_line_start {
+                    offsets.push(position + i as u64);
+                    at_line_start = false;
+                }
+                if *byte == b'\n' {
+                    at_line_start = true;
+                }
+            }
+
+            let consumed = buffer.len();
+            position += consumed as u64;
+            reader.consume(consumed);
+        }
+
+        self.file_size = position;
+        self.modified = metadata.modified().ok();
+        self.ends_with_newline = at_line_start;
+        self.fingerprint = prefix_fingerprint(&self.file_path, position)?;
+        Ok(())
+    }
+
+    /// 文件总行数（包括空行）
+    pub fn line_count(&self) -> usize {
+        self.offsets.len()
+    }
+
+    /// 获取指定行的字节区间 `[start, end)`，区间包含行尾换行符
+    ///
+    /// # 参数
+    /// - `line_number`: 行号（从1开始）
+    pub fn line_span(&self, line_number: usize) -> Option<(u64, u64)> {
+        if line_number == 0 || line_number > self.offsets.len() {
+            return None;
+        }
+        let start = self.offsets[line_number - 1];
+        let end = self.offsets.get(line_number).copied().unwrap_or(self.file_size);
+        Some((start, end))
+    }
+
+    /// 在条目元数据中写入其在文件中的字节区间
+    ///
+    /// 区间从条目首行开头到记录最后一行（`end_line`）的行尾，包含行尾换行符，
+    /// 与 [`line_span`](Self::line_span) 一致。行号超出索引范围的条目不写入。
+    ///
+    /// # 参数
+    /// - `lines`: 行号为文件中行号的解析条目
+    pub fn mark_offsets(&self, lines: &mut [LogLine]) {
+        for line in lines {
+            let end_line = record_end_line(line).unwrap_or(line.line_number);
+            if let (Some((start, _)), Some((_, end))) = (self.line_span(line.line_number), self.line_span(end_line)) {
+                line.metadata.insert(START_OFFSET_METADATA_KEY.to_string(), start.to_string());
+                line.metadata.insert(END_OFFSET_METADATA_KEY.to_string(), end.to_string());
+            }
+        }
+    }
+
+    /// 检查索引是否仍与磁盘上的文件一致
+    ///
+    /// 通过比较文件大小和修改时间判断，文件被修改或删除时返回false。
+    pub fn is_fresh(&self) -> bool {
+        match std::fs::metadata(&self.file_path) {
+            Ok(metadata) => metadata.len() == self.file_size && metadata.modified().ok() == self.modified,
+            Err(_) => false,
+        }
+    }
+
+    /// 读取闭区间 `[first_line, last_line]` 内的原始行
+    ///
+    /// 只 seek 到起始偏移并读取所需的字节，返回的行已去除行尾的 `\r\n`，
+    /// 非UTF-8字节会被替换为U+FFFD。
+    ///
+    /// # 参数
+    /// - `first_line`: 起始行号（从1开始，包含）
+    /// - `last_line`: 结束行号（包含），超出文件末尾时自动截断
+    ///
+    /// # Returns
+    /// - `Ok(Vec<String>)`: 区间内的原始行内容
+    /// - `Err(String)`: 行号无效或读取失败时的错误信息
+    pub fn read_lines(&self, first_line: usize, last_line: usize) -> Result<Vec<String>, String> {
+        let last_line = last_line.min(self.line_count());
+        if first_line == 0 || first_line > last_line {
+            return Err(Message::new("line.invalid_range")
+                .with("first", first_line)
+                .with("last", last_line)
+                .with("total", self.line_count())
+                .text());
+        }
+
+        let (start, _) = self.line_span(first_line).ok_or_else(|| Message::new("line.start_out_of_range").text())?;
+        let (_, end) = self.line_span(last_line).ok_or_else(|| Message::new("line.end_out_of_range").text())?;
+
+        let mut file = File::open(&self.file_path)
+            .map_err(|e| Message::new("io.open_failed").with("error", e).text())?;
+        file.seek(SeekFrom::Start(start))
+            .map_err(|e| Message::new("io.seek_failed").with("error", e).text())?;
+
+        let mut bytes = Vec::with_capacity((end - start) as usize);
+        file.take(end - start).read_to_end(&mut bytes)
+            .map_err(|e| Message::new("io.read_failed").with("error", e).text())?;
+
+        Ok(split_raw_lines(&bytes))
+    }
+}
+
+/// 将原始字节按 `\n` 拆分为行，并去除行尾的 `\r`
+fn split_raw_lines(bytes: &[u8]) -> Vec<String> {
+    let bytes = bytes.strip_suffix(b"\n").unwrap_or(bytes);
+    bytes
+        .split(|b| *b == b'\n')
+        .map(|line| {
+            let line = line.strip_suffix(b"\r").unwrap_or(line);
+            String::from_utf8_lossy(line).into_owned()
+        })
+        .collect()
+}
+
+/// 行索引缓存
+///
+/// 按文件路径缓存已构建的 [`LineIndex`]，文件大小或修改时间变化后自动重建。
+/// 应用中通过 `MemoryBudget::line_index` 获取索引，使索引的内存计入缓存预算。
+#[derive(Default)]
+pub struct LineIndexCache {
+    indexes: Mutex<HashMap<PathBuf, Arc<LineIndex>>>,
+}
+
+impl LineIndexCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 获取文件的行索引，不存在时构建，过期时增量刷新
+    ///
+    /// # 参数
+    /// - `path`: 文件路径
+    ///
+    /// # Returns
+    /// - `Ok(Arc<LineIndex>)`: 与当前文件内容一致的索引
+    /// - `Err(String)`: 构建索引失败时的错误信息
+    pub fn get_or_build<P: AsRef<Path>>(&self, path: P) -> Result<Arc<LineIndex>, String> {
+        let path = path.as_ref();
+
+        let cached = self.indexes.lock().unwrap().get(path).cloned();
+
+        // 在锁外构建或刷新索引，避免大文件扫描阻塞其他文件的查询
+        let index = match cached {
+            Some(index) if index.is_fresh() => return Ok(index),
+            Some(index) => Arc::new(index.refresh()?),
+            None => Arc::new(LineIndex::build(path)?),
+        };
+        self.indexes.lock().unwrap().insert(path.to_path_buf(), index.clone());
+        Ok(index)
+    }
+
+    /// 缓存占用（文件数、已索引的行数和偏移量占用的内存字节数）
+    pub fn usage(&self) -> CacheUsage {
+        let indexes = self.indexes.lock().unwrap();
+        CacheUsage {
+            files: indexes.len(),
+            items: indexes.values().map(|index| index.line_count()).sum(),
+            bytes: indexes.values()
+                .map(|index| (std::mem::size_of::<LineIndex>() + index.offsets.capacity() * std::mem::size_of::<u64>()) as u64)
+                .sum(),
+        }
+    }
+
+    /// 每个文件的索引占用的内存（字节）
+    pub fn file_usage(&self) -> Vec<(PathBuf, u64)> {
+        self.indexes.lock().unwrap().iter()
+            .map(|(path, index)| (path.clone(), (std::mem::size_of::<LineIndex>() + index.offsets.capacity() * std::mem::size_of::<u64>()) as u64))
+            .collect()
+    }
+
+    /// 移除指定文件的缓存索引
+    pub fn invalidate<P: AsRef<Path>>(&self, path: P) {
+        self.indexes.lock().unwrap().remove(path.as_ref());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn write_temp_file(name: &str, content: &[u8]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("log_whisper_{}_{}", name, std::process::id()));
+        let mut file = File::create(&path).unwrap();
+        file.write_all(content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_index_counts_blank_lines_and_missing_trailing_newline() {
+        let path = write_temp_file("index_count", b"first\n\nthird\r\nfourth");
+        let index = LineIndex::build(&path).unwrap();
+
+        assert_eq!(index.line_count(), 4);
+        assert_eq!(index.line_span(1), Some((0, 6)));
+        assert_eq!(index.line_span(2), Some((6, 7)));
+        assert_eq!(index.line_span(4), Some((14, 20)));
+        assert_eq!(index.line_span(5), None);
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_mark_offsets_covers_whole_record() {
+        let path = write_temp_file("index_offsets", b"head
+  at frame
+next
+");
+        let index = LineIndex::build(&path).unwrap();
+        let line = |line_number: usize, end_line: Option<usize>| LogLine {
+            metadata: end_line
+                .map(|end| HashMap::from([(crate::plugins::chain::END_LINE_METADATA_KEY.to_string(), end.to_string())]))
+                .unwrap_or_default(),
+            ..LogLine::new(line_number, "")
+        };
+        let mut lines = vec![line(1, Some(2)), line(3, None), line(9, None)];
+        index.mark_offsets(&mut lines);
+
+        let offsets = |line: &LogLine| {
+            (line.metadata.get(START_OFFSET_METADATA_KEY).cloned(), line.metadata.get(END_OFFSET_METADATA_KEY).cloned())
+        };
+        assert_eq!(offsets(&lines[0]), (Some("0".to_string()), Some("16".to_string())));
+        assert_eq!(offsets(&lines[1]), (Some("16".to_string()), Some("21".to_string())));
+        assert_eq!(offsets(&lines[2]), (None, None));
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_read_lines_window() {
+        let path = write_temp_file("index_read", "一\n二\r\n三\n四\n".as_bytes());
+        let index = LineIndex::build(&path).unwrap();
+
+        assert_eq!(index.line_count(), 4);
+        assert_eq!(index.read_lines(2, 3).unwrap(), vec!["二", "三"]);
+        assert_eq!(index.read_lines(3, 100).unwrap(), vec!["三", "四"]);
+        assert!(index.read_lines(0, 1).is_err());
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_refresh_extends_appended_partial_line() {
+        let path = write_temp_file("index_refresh", b"a\nb");
+        let index = LineIndex::build(&path).unwrap();
+        assert!(!index.ends_with_newline);
+
+        std::fs::OpenOptions::new().append(true).open(&path).unwrap().write_all(b"c\nd\n").unwrap();
+        let refreshed = index.refresh().unwrap();
+        assert_eq!(refreshed.line_count(), 3);
+        assert_eq!(refreshed.read_lines(2, 3).unwrap(), vec!["bc", "d"]);
+        assert_eq!(refreshed.fingerprint, LineIndex::build(&path).unwrap().fingerprint);
+
+        std::fs::write(&path, b"x\n").unwrap();
+        assert_eq!(refreshed.refresh().unwrap().line_count(), 1);
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_cache_rebuilds_stale_index() {
+        let path = write_temp_file("index_cache", b"a\nb\n");
+        let cache = LineIndexCache::new();
+        assert_eq!(cache.get_or_build(&path).unwrap().line_count(), 2);
+
+        std::fs::OpenOptions::new().append(true).open(&path).unwrap().write_all(b"c\n").unwrap();
+        assert_eq!(cache.get_or_build(&path).unwrap().line_count(), 3);
+
+        std::fs::remove_file(path).ok();
+    }
+}
@@ -0,0 +1,30 @@
+/// LogWhisper 文件读取模块
+///
+/// 提供面向大文件的随机访问能力，避免为了读取少量行而把整个文件加载进内存。
+///
+/// # 架构组件
+/// - **行索引**: 记录每一行起始字节偏移量的索引，支持按行号快速定位
+/// - **索引缓存**: 按文件路径缓存已构建的索引，文件追加内容时增量更新
+/// - **增量解析**: 缓存解析结果，文件增长时只解析新增部分并返回增量
+/// - **列式存储**: 按列保存缓存的解析结果，驻留重复字符串以降低内存占用
+/// - **行数统计**: 分块流式统计行数，支持进度回调和采样估算
+/// - **自适应分块**: 按字节预算和实测解析速度决定分块解析的块大小
+/// - **HTTP下载**: 流式下载HTTP(S)链接到缓存文件，支持断点续传
+/// - **压缩包浏览**: 列出 zip/tar/tar.gz 中的文件并流式读取单个成员
+/// - **跟踪事件流**: 跟踪文件时批量发送新条目，前端滞后时切换为摘要
+/// - **内存预算**: 缓存总占用超过预算时按最近最少使用淘汰文件的缓存
+
+pub mod line_index;  // 行偏移索引 - 按行号随机读取文件内容
+pub mod incremental; // 增量解析缓存 - 文件追加时只解析新增部分
+pub mod columnar;    // 列式条目存储 - 字符串驻留与连续文本缓冲区
+pub mod line_counter; // 流式行数统计 - 不加载整个文件
+pub mod chunking;    // 自适应分块 - 按字节预算和解析速度选择块大小
+pub mod http;        // HTTP下载 - 缓存与Range续传
+pub mod archive;     // 压缩包浏览 - 不解压直接读取成员
+pub mod tail;        // 跟踪事件流 - 批次合并与背压控制
+pub mod budget;      // 内存预算 - 按LRU淘汰文件缓存
+
+pub use budget::{CacheStats, MemoryBudget};
+pub use incremental::{IncrementalParseCache, ParseDelta};
+pub use line_index::{LineIndex, LineIndexCache};
+pub use tail::{TailOptions, TailRegistry, TailStats};
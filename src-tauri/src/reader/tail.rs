@@ -0,0 +1,486 @@
+/// 日志跟踪（tail）事件流
+///
+/// 跟踪文件时新解析出的条目不会逐条作为事件发送，而是合并为批次：
+/// 缓冲区达到 `max_batch_size` 条或距第一条缓冲条目超过 `max_batch_interval_ms` 时发送一批。
+///
+/// 前端处理完每一批后调用 `ack_tail_batch` 确认。未确认的批次超过 `max_pending_batches` 时
+/// 认为前端跟不上，进入摘要模式：新条目不再发送，只统计数量、行号范围和级别分布，
+/// 前端追上后随下一批发送一次丢弃摘要，界面据此提示并可按行号范围重新加载。
+///
+/// 跟踪可以附带筛选条件（如只看ERROR或匹配正则的行），在后端筛选后再发送；
+/// 被过滤掉的条目数随每批发送，长时间没有匹配条目时也会定期单独发送一次计数。
+
+use crate::i18n::Message;
+use crate::models::LogLine;
+use crate::search::{EntryFilter, EntryMatcher};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// 跟踪选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TailOptions {
+    /// 每批最多条目数
+    #[serde(default = "default_max_batch_size")]
+    pub max_batch_size: usize,
+
+    /// 缓冲条目最长等待时间（毫秒），超时即使未满也发送
+    #[serde(default = "default_max_batch_interval_ms")]
+    pub max_batch_interval_ms: u64,
+
+    /// 允许的未确认批次数，超过后进入摘要模式
+    #[serde(default = "default_max_pending_batches")]
+    pub max_pending_batches: u64,
+
+    /// 检查文件变化的间隔（毫秒）
+    #[serde(default = "default_poll_interval_ms")]
+    pub poll_interval_ms: u64,
+
+    /// 筛选条件（可选，只发送匹配的条目）
+    #[serde(default)]
+    pub filter: Option<EntryFilter>,
+
+    /// 没有匹配条目时单独发送过滤计数的间隔（毫秒）
+    #[serde(default = "default_suppressed_report_interval_ms")]
+    pub suppressed_report_interval_ms: u64,
+}
+
+fn default_max_batch_size() -> usize {
+    500
+}
+
+fn default_max_batch_interval_ms() -> u64 {
+    200
+}
+
+fn default_max_pending_batches() -> u64 {
+    4
+}
+
+fn default_poll_interval_ms() -> u64 {
+    500
+}
+
+fn default_suppressed_report_interval_ms() -> u64 {
+    2000
+}
+
+impl Default for TailOptions {
+    fn default() -> Self {
+        Self {
+            max_batch_size: default_max_batch_size(),
+            max_batch_interval_ms: default_max_batch_interval_ms(),
+            max_pending_batches: default_max_pending_batches(),
+            poll_interval_ms: default_poll_interval_ms(),
+            filter: None,
+            suppressed_report_interval_ms: default_suppressed_report_interval_ms(),
+        }
+    }
+}
+
+/// 摘要模式期间丢弃的条目摘要
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DroppedSummary {
+    /// 丢弃的条目数
+    pub count: usize,
+    /// 第一条丢弃条目的行号
+    pub first_line: usize,
+    /// 最后一条丢弃条目的行号
+    pub last_line: usize,
+    /// 按级别统计的条目数（无级别的计入 `UNKNOWN`）
+    pub levels: BTreeMap<String, usize>,
+}
+
+impl DroppedSummary {
+    fn record(&mut self, entry: &LogLine) {
+        if self.count == 0 {
+            self.first_line = entry.line_number;
+        }
+        self.count += 1;
+        self.last_line = entry.line_number;
+        let level = entry.level.clone().unwrap_or_else(|| "UNKNOWN".to_string());
+        *self.levels.entry(level).or_insert(0) += 1;
+    }
+}
+
+/// 发送给前端的一批条目（`tail-batch` 事件）
+///
+/// 条目的行号可能与已有条目重复（文件最后一行在写入过程中被重新解析），前端应按行号覆盖。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TailBatch {
+    /// 跟踪ID
+    pub tail_id: String,
+    /// 批次序号（从1开始），确认时回传
+    pub seq: u64,
+    /// 新条目
+    pub entries: Vec<LogLine>,
+    /// 摘要模式期间丢弃的条目（仅在前端追上后的第一批中提供）
+    pub dropped: Option<DroppedSummary>,
+    /// 自上一批以来被筛选条件过滤掉的条目数
+    #[serde(default)]
+    pub suppressed: usize,
+}
+
+/// 跟踪统计
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TailStats {
+    /// 已发送的批次数
+    pub batches_sent: u64,
+    /// 已发送的条目数
+    pub entries_sent: u64,
+    /// 摘要模式下丢弃的条目数
+    pub entries_dropped: u64,
+    /// 被筛选条件过滤掉的条目数
+    pub entries_suppressed: u64,
+    /// 进入摘要模式的次数
+    pub lag_events: u64,
+    /// 当前未确认的批次数
+    pub pending_batches: u64,
+    /// 当前是否处于摘要模式
+    pub lagging: bool,
+}
+
+/// 批次合并与背压控制
+pub struct TailBatcher {
+    tail_id: String,
+    options: TailOptions,
+    buffer: Vec<LogLine>,
+    buffered_since: Option<Instant>,
+    last_seq: u64,
+    acked_seq: u64,
+    dropped: Option<DroppedSummary>,
+    matcher: Option<EntryMatcher>,
+    suppressed: usize,
+    suppressed_since: Option<Instant>,
+    stats: TailStats,
+}
+
+impl TailBatcher {
+    /// 创建批次合并器
+    ///
+    /// # Returns
+    /// - `Ok(TailBatcher)`: 批次合并器
+    /// - `Err(String)`: 筛选条件无效时的错误信息
+    pub fn new(tail_id: String, options: TailOptions) -> Result<Self, String> {
+        let matcher = options.filter.as_ref().map(EntryFilter::compile).transpose()?;
+        Ok(Self {
+            tail_id,
+            options,
+            buffer: Vec::new(),
+            buffered_since: None,
+            last_seq: 0,
+            acked_seq: 0,
+            dropped: None,
+            matcher,
+            suppressed: 0,
+            suppressed_since: None,
+            stats: TailStats::default(),
+        })
+    }
+
+    /// 更换筛选条件（None表示不筛选），只影响之后加入的条目
+    pub fn set_filter(&mut self, filter: Option<EntryFilter>) -> Result<(), String> {
+        self.matcher = filter.as_ref().map(EntryFilter::compile).transpose()?;
+        self.options.filter = filter;
+        Ok(())
+    }
+
+    fn pending_batches(&self) -> u64 {
+        self.last_seq - self.acked_seq
+    }
+
+    fn is_lagging(&self) -> bool {
+        self.pending_batches() >= self.options.max_pending_batches.max(1)
+    }
+
+    /// 加入新解析出的条目；不匹配筛选条件的只计数，前端跟不上时只记录摘要
+    pub fn push(&mut self, entries: Vec<LogLine>, now: Instant) {
+        let entries = match &self.matcher {
+            Some(matcher) => {
+                let total = entries.len();
+                let matched: Vec<LogLine> = entries.into_iter().filter(|entry| matcher.matches(entry)).collect();
+                let suppressed = total - matched.len();
+                if suppressed > 0 {
+                    self.suppressed += suppressed;
+                    self.suppressed_since.get_or_insert(now);
+                    self.stats.entries_suppressed += suppressed as u64;
+                }
+                matched
+            }
+            None => entries,
+        };
+        if entries.is_empty() {
+            return;
+        }
+        if self.is_lagging() {
+            if self.dropped.is_none() {
+                self.stats.lag_events += 1;
+                log::warn!("⚠️ 跟踪 {} 的前端处理滞后（{} 批未确认），进入摘要模式", self.tail_id, self.pending_batches());
+            }
+            let summary = self.dropped.get_or_insert_with(DroppedSummary::default);
+            for entry in &entries {
+                summary.record(entry);
+            }
+            self.stats.entries_dropped += entries.len() as u64;
+            return;
+        }
+        self.buffered_since.get_or_insert(now);
+        self.buffer.extend(entries);
+    }
+
+    /// 取出下一批待发送的条目
+    ///
+    /// 前端滞后、缓冲区未满且未超时时返回None。
+    pub fn next_batch(&mut self, now: Instant) -> Option<TailBatch> {
+        if self.is_lagging() {
+            return None;
+        }
+        let interval = Duration::from_millis(self.options.max_batch_interval_ms);
+        let batch_size = self.options.max_batch_size.max(1);
+        let timed_out = self.buffered_since.is_some_and(|since| now.duration_since(since) >= interval);
+        let report_interval = Duration::from_millis(self.options.suppressed_report_interval_ms);
+        let report_suppressed = self.suppressed_since.is_some_and(|since| now.duration_since(since) >= report_interval);
+        if self.buffer.len() < batch_size && !timed_out && self.dropped.is_none() && !report_suppressed {
+            return None;
+        }
+
+        let take = self.buffer.len().min(batch_size);
+        let entries: Vec<LogLine> = self.buffer.drain(..take).collect();
+        self.buffered_since = if self.buffer.is_empty() { None } else { Some(now) };
+        // 丢弃摘要跟在缓冲条目之后发送，保持行号顺序
+        let dropped = if self.buffer.is_empty() { self.dropped.take() } else { None };
+        if entries.is_empty() && dropped.is_none() && self.suppressed == 0 {
+            return None;
+        }
+        let suppressed = std::mem::take(&mut self.suppressed);
+        self.suppressed_since = None;
+
+        self.last_seq += 1;
+        self.stats.batches_sent += 1;
+        self.stats.entries_sent += entries.len() as u64;
+        Some(TailBatch {
+            tail_id: self.tail_id.clone(),
+            seq: self.last_seq,
+            entries,
+            dropped,
+            suppressed,
+        })
+    }
+
+    /// 确认前端已处理到 `seq` 为止的批次
+    pub fn ack(&mut self, seq: u64) {
+        self.acked_seq = self.acked_seq.max(seq.min(self.last_seq));
+    }
+
+    /// 当前统计
+    pub fn stats(&self) -> TailStats {
+        TailStats {
+            pending_batches: self.pending_batches(),
+            lagging: self.dropped.is_some(),
+            ..self.stats.clone()
+        }
+    }
+}
+
+/// 正在运行的跟踪
+#[derive(Clone)]
+pub struct TailHandle {
+    /// 跟踪ID
+    pub tail_id: String,
+    /// 被跟踪的文件
+    pub file_path: String,
+    /// 跟踪选项
+    pub options: TailOptions,
+    /// 批次合并器
+    pub batcher: Arc<Mutex<TailBatcher>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl TailHandle {
+    /// 跟踪是否已停止
+    pub fn is_stopped(&self) -> bool {
+        self.stop.load(Ordering::Relaxed)
+    }
+}
+
+/// 跟踪注册表
+///
+/// 按跟踪ID管理正在运行的跟踪，后台任务通过 `TailHandle` 检查是否已停止。
+pub struct TailRegistry {
+    tails: Mutex<HashMap<String, TailHandle>>,
+}
+
+impl TailRegistry {
+    /// 创建空的注册表
+    pub fn new() -> Self {
+        Self {
+            tails: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 注册新的跟踪
+    ///
+    /// # Returns
+    /// - `Ok(TailHandle)`: 跟踪句柄
+    /// - `Err(String)`: 筛选条件无效时的错误信息
+    pub fn start(&self, file_path: &str, options: TailOptions) -> Result<TailHandle, String> {
+        let tail_id = uuid::Uuid::new_v4().to_string();
+        let batcher = TailBatcher::new(tail_id.clone(), options.clone())?;
+        let handle = TailHandle {
+            tail_id: tail_id.clone(),
+            file_path: file_path.to_string(),
+            options,
+            batcher: Arc::new(Mutex::new(batcher)),
+            stop: Arc::new(AtomicBool::new(false)),
+        };
+        if let Ok(mut tails) = self.tails.lock() {
+            tails.insert(tail_id, handle.clone());
+        }
+        Ok(handle)
+    }
+
+    /// 正在运行的跟踪数
+    pub fn active_count(&self) -> usize {
+        self.tails.lock().map(|tails| tails.len()).unwrap_or(0)
+    }
+
+    fn get(&self, tail_id: &str) -> Result<TailHandle, String> {
+        self.tails.lock()
+            .map_err(|_| Message::new("tail.registry_lock_failed").text())?
+            .get(tail_id)
+            .cloned()
+            .ok_or_else(|| Message::new("tail.not_found").with("id", tail_id).text())
+    }
+
+    /// 确认批次
+    pub fn ack(&self, tail_id: &str, seq: u64) -> Result<(), String> {
+        let handle = self.get(tail_id)?;
+        handle.batcher.lock().map_err(|_| Message::new("tail.state_lock_failed").text())?.ack(seq);
+        Ok(())
+    }
+
+    /// 更换跟踪的筛选条件
+    pub fn set_filter(&self, tail_id: &str, filter: Option<EntryFilter>) -> Result<(), String> {
+        let handle = self.get(tail_id)?;
+        let result = handle.batcher.lock().map_err(|_| Message::new("tail.state_lock_failed").text())?.set_filter(filter);
+        result
+    }
+
+    /// 获取跟踪统计
+    pub fn stats(&self, tail_id: &str) -> Result<TailStats, String> {
+        let handle = self.get(tail_id)?;
+        let stats = handle.batcher.lock().map_err(|_| Message::new("tail.state_lock_failed").text())?.stats();
+        Ok(stats)
+    }
+
+    /// 停止并移除跟踪，返回最终统计
+    pub fn stop(&self, tail_id: &str) -> Result<TailStats, String> {
+        let stats = self.stats(tail_id)?;
+        if let Ok(mut tails) = self.tails.lock() {
+            if let Some(handle) = tails.remove(tail_id) {
+                handle.stop.store(true, Ordering::Relaxed);
+            }
+        }
+        Ok(stats)
+    }
+}
+
+impl Default for TailRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::{SearchMode, SearchOptions};
+
+    fn entries(from: usize, count: usize) -> Vec<LogLine> {
+        (from..from + count)
+            .map(|n| LogLine {
+                level: Some("INFO".to_string()),
+                ..LogLine::new(n, format!("line {}", n))
+            })
+            .collect()
+    }
+
+    fn batcher() -> TailBatcher {
+        TailBatcher::new("t".to_string(), TailOptions {
+            max_batch_size: 10,
+            max_batch_interval_ms: 100,
+            max_pending_batches: 2,
+            poll_interval_ms: 100,
+            ..Default::default()
+        }).unwrap()
+    }
+
+    #[test]
+    fn test_batches_by_size_and_interval() {
+        let mut batcher = batcher();
+        let start = Instant::now();
+
+        batcher.push(entries(1, 25), start);
+        assert_eq!(batcher.next_batch(start).unwrap().entries.len(), 10);
+        assert_eq!(batcher.next_batch(start).unwrap().entries.len(), 10);
+        // 剩余5条未满一批，超时后才发送（此时已有2批未确认，先确认）
+        batcher.ack(2);
+        assert!(batcher.next_batch(start).is_none());
+        let batch = batcher.next_batch(start + Duration::from_millis(100)).unwrap();
+        assert_eq!((batch.seq, batch.entries.len()), (3, 5));
+    }
+
+    #[test]
+    fn test_drops_to_summary_when_consumer_lags() {
+        let mut batcher = batcher();
+        let now = Instant::now();
+
+        batcher.push(entries(1, 20), now);
+        batcher.next_batch(now).unwrap();
+        batcher.next_batch(now).unwrap();
+
+        // 2批未确认：新条目只计入摘要
+        batcher.push(entries(21, 30), now);
+        assert!(batcher.next_batch(now).is_none());
+        let stats = batcher.stats();
+        assert!(stats.lagging);
+        assert_eq!((stats.entries_dropped, stats.pending_batches, stats.lag_events), (30, 2, 1));
+
+        batcher.ack(2);
+        let batch = batcher.next_batch(now).unwrap();
+        assert!(batch.entries.is_empty());
+        let dropped = batch.dropped.unwrap();
+        assert_eq!((dropped.count, dropped.first_line, dropped.last_line), (30, 21, 50));
+        assert_eq!(dropped.levels.get("INFO"), Some(&30));
+        assert!(!batcher.stats().lagging);
+    }
+
+    #[test]
+    fn test_filter_suppresses_entries_and_reports_counts() {
+        let mut batcher = batcher();
+        let now = Instant::now();
+        let mut lines = entries(1, 6);
+        lines[2].level = Some("ERROR".to_string());
+        lines[4].content = "connection timeout".to_string();
+        batcher.set_filter(Some(EntryFilter { levels: vec!["ERROR".to_string()], ..Default::default() })).unwrap();
+
+        // 只保留ERROR，过滤计数随批次发送
+        batcher.push(lines, now);
+        let batch = batcher.next_batch(now + Duration::from_millis(100)).unwrap();
+        assert_eq!(batch.entries.iter().map(|e| e.line_number).collect::<Vec<_>>(), vec![3]);
+        assert_eq!(batch.suppressed, 5);
+
+        // 没有匹配条目时，到达报告间隔才单独发送计数
+        batcher.push(entries(7, 4), now);
+        assert!(batcher.next_batch(now + Duration::from_millis(100)).is_none());
+        let batch = batcher.next_batch(now + Duration::from_millis(2000)).unwrap();
+        assert!(batch.entries.is_empty());
+        assert_eq!(batch.suppressed, 4);
+        assert_eq!(batcher.stats().entries_suppressed, 9);
+        // 无效的筛选条件
+        let invalid = SearchOptions { query: "(".to_string(), mode: SearchMode::Regex, ..Default::default() };
+        assert!(batcher.set_filter(Some(EntryFilter { search: Some(invalid), ..Default::default() })).is_err());
+    }
+}
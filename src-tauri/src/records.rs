@@ -0,0 +1,223 @@
+/// 多行日志记录
+///
+/// 将解析后的物理行分组为逻辑记录：记录头之后没有时间戳的续行
+/// （异常堆栈、格式化输出的JSON、多行SQL等）归入同一条记录。
+///
+/// # 分组规则
+/// 一行是上一条记录的续行，当且仅当它没有时间戳，并且满足以下任一条件：
+/// - 解析器将其标记为堆栈或无法识别的行（元数据 `type` 为 `stacktrace` / `unparsed`）
+/// - 记录头有时间戳，且该行没有日志级别或以空白字符开头
+///
+/// 插件链执行完成后会调用 `mark_record_spans`，在多行记录的每一行写入元数据 `end_line`，
+/// 分块解析、分页和导出都据此按记录而不是按物理行处理。
+
+use crate::models::{LogLine, LogRecord};
+use crate::plugins::chain::END_LINE_METADATA_KEY;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// 判断 `line` 是否为以 `head` 开头的记录的续行
+pub fn is_continuation(head: &LogLine, line: &LogLine) -> bool {
+    if line.timestamp.is_some() {
+        return false;
+    }
+    if matches!(line.metadata.get("type").map(String::as_str), Some("stacktrace") | Some("unparsed")) {
+        return true;
+    }
+    head.timestamp.is_some()
+        && (line.level.is_none() || line.message_text().starts_with(char::is_whitespace))
+}
+
+/// 读取行元数据中记录最后一行的行号
+pub fn record_end_line(line: &LogLine) -> Option<usize> {
+    line.metadata.get(END_LINE_METADATA_KEY)?.parse().ok()
+}
+
+/// 按分组规则计算每条记录在 `lines` 中的下标范围（左闭右开）
+fn record_ranges(lines: &[LogLine]) -> Vec<(usize, usize)> {
+    let mut ranges = Vec::new();
+    let mut start = 0;
+
+    for i in 1..lines.len() {
+        let head = &lines[start];
+        let continues = match record_end_line(head) {
+            Some(end) if lines[i].line_number <= end => true,
+            _ => is_continuation(head, &lines[i]),
+        };
+        if !continues {
+            ranges.push((start, i));
+            start = i;
+        }
+    }
+    if !lines.is_empty() {
+        ranges.push((start, lines.len()));
+    }
+    ranges
+}
+
+/// 在多行记录的每一行写入元数据 `end_line`（记录最后一行的行号）
+///
+/// 单行记录不写入。分块解析依据该值保留从重叠区延续到当前块的记录。
+pub fn mark_record_spans(lines: &mut [LogLine]) {
+    for (start, end) in record_ranges(lines) {
+        if end - start < 2 {
+            continue;
+        }
+        // 最后一个条目可能自身合并了后续行（如终端折行），以其 `end_line` 为准
+        let end_line = record_end_line(&lines[end - 1]).unwrap_or(lines[end - 1].line_number).to_string();
+        for line in &mut lines[start..end] {
+            line.metadata.insert(END_LINE_METADATA_KEY.to_string(), end_line.clone());
+        }
+    }
+}
+
+/// 为空行插入空条目，使条目与文件中的行一一对应
+///
+/// 已有条目的行和多行记录覆盖范围（`end_line`）内的空行不插入。
+///
+/// # 参数
+/// - `lines`: 按行号排序的条目
+/// - `blank_lines`: 按升序排列的空行行号（从1开始）
+pub fn fill_blank_lines(lines: &mut Vec<LogLine>, blank_lines: &[usize]) {
+    if blank_lines.is_empty() {
+        return;
+    }
+
+    let mut merged = Vec::with_capacity(lines.len() + blank_lines.len());
+    let mut covered_until = 0;
+    let mut entries = std::mem::take(lines).into_iter().peekable();
+    for &blank in blank_lines {
+        while let Some(entry) = entries.next_if(|entry| entry.line_number <= blank) {
+            covered_until = covered_until.max(record_end_line(&entry).unwrap_or(entry.line_number));
+            merged.push(entry);
+        }
+        if blank > covered_until {
+            merged.push(LogLine {
+                line_number: blank,
+                content: String::new(),
+                level: None,
+                timestamp: None,
+                formatted_content: Some(String::new()),
+                message: None,
+                metadata: HashMap::new(),
+                processed_by: vec!["blank_line".to_string()],
+            });
+        }
+    }
+    merged.extend(entries);
+    *lines = merged;
+}
+
+/// 将物理行分组为逻辑记录
+pub fn group_records(lines: &[LogLine]) -> Vec<LogRecord> {
+    record_ranges(lines)
+        .into_iter()
+        .map(|(start, end)| LogRecord::from_lines(lines[start..end].to_vec()))
+        .collect()
+}
+
+/// 记录分页结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordPage {
+    /// 当前页的记录
+    pub records: Vec<LogRecord>,
+    /// 记录总数
+    pub total_records: usize,
+    /// 当前页第一条记录的序号（从0开始）
+    pub offset: usize,
+    /// 是否还有后续记录
+    pub has_more: bool,
+}
+
+/// 按记录分页，多行记录不会被拆分到两页
+///
+/// # 参数
+/// - `lines`: 解析后的物理行
+/// - `offset`: 起始记录序号（从0开始）
+/// - `limit`: 每页最多记录数
+pub fn paginate_records(lines: &[LogLine], offset: usize, limit: usize) -> RecordPage {
+    let ranges = record_ranges(lines);
+    let total_records = ranges.len();
+    let records = ranges
+        .into_iter()
+        .skip(offset)
+        .take(limit)
+        .map(|(start, end)| LogRecord::from_lines(lines[start..end].to_vec()))
+        .collect();
+
+    RecordPage {
+        records,
+        total_records,
+        offset,
+        has_more: offset.saturating_add(limit) < total_records,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(line_number: usize, content: &str, timestamp: bool) -> LogLine {
+        LogLine {
+            level: timestamp.then(|| "ERROR".to_string()),
+            timestamp: timestamp.then(|| "2024-01-15 10:30:25".to_string()),
+            ..LogLine::new(line_number, content)
+        }
+    }
+
+    fn sample() -> Vec<LogLine> {
+        vec![
+            line(1, "ERROR Request failed", true),
+            line(2, "java.lang.IllegalStateException: boom", false),
+            line(3, "    at com.example.App.run(App.java:42)", false),
+            line(4, "INFO Recovered", true),
+            line(5, "INFO Done", true),
+        ]
+    }
+
+    #[test]
+    fn test_groups_stack_trace_into_one_record() {
+        let records = group_records(&sample());
+        assert_eq!(records.len(), 3);
+        assert_eq!((records[0].start_line, records[0].end_line), (1, 3));
+        assert_eq!(records[0].lines.len(), 3);
+        assert_eq!(records[0].content.lines().count(), 3);
+        assert_eq!(records[0].level.as_deref(), Some("ERROR"));
+        assert_eq!((records[1].start_line, records[1].end_line), (4, 4));
+
+        let page = paginate_records(&sample(), 1, 1);
+        assert_eq!(page.total_records, 3);
+        assert_eq!(page.records[0].start_line, 4);
+        assert!(page.has_more);
+    }
+
+    #[test]
+    fn test_mark_record_spans_only_marks_multi_line_records() {
+        let mut lines = sample();
+        mark_record_spans(&mut lines);
+        for line in &lines[..3] {
+            assert_eq!(record_end_line(line), Some(3));
+        }
+        assert_eq!(record_end_line(&lines[3]), None);
+
+        // 标记后即使续行带有级别也按 end_line 分组
+        lines[1].level = Some("ERROR".to_string());
+        assert_eq!(group_records(&lines).len(), 3);
+    }
+
+    #[test]
+    fn test_fill_blank_lines_skips_lines_inside_records() {
+        let mut lines = vec![
+            line(2, "ERROR Request failed", true),
+            line(4, "    at com.example.App.run(App.java:42)", false),
+            line(7, "INFO Done", true),
+        ];
+        mark_record_spans(&mut lines);
+        fill_blank_lines(&mut lines, &[1, 3, 5, 6, 9]);
+
+        let numbers: Vec<usize> = lines.iter().map(|line| line.line_number).collect();
+        assert_eq!(numbers, vec![1, 2, 4, 5, 6, 7, 9]);
+        assert_eq!(lines[0].processed_by, vec!["blank_line".to_string()]);
+        assert!(lines[3].content.is_empty());
+    }
+}
@@ -0,0 +1,245 @@
+/// 远程日志（SSH）
+///
+/// 通过系统自带的OpenSSH客户端（`ssh`）访问远程服务器上的日志：
+/// 列出远程目录、读取远程文件交给解析流程，或以 `tail -F` 持续跟踪。
+/// 认证完全交给OpenSSH（私钥、ssh-agent、`~/.ssh/config`），应用不保存密码；
+/// 使用 `BatchMode` 禁止交互式提示，需要密码的主机会直接返回错误。
+///
+/// Unix平台上启用OpenSSH连接复用（ControlMaster），同一主机的多次操作共用一条连接。
+/// 远程主机需要提供 POSIX shell 以及 GNU `find`（列目录）、`head` 和 `tail`。
+
+use crate::config::RemoteHost;
+use crate::i18n::Message;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+
+/// 连接超时（秒）
+const CONNECT_TIMEOUT_SECS: u32 = 10;
+
+/// 连接复用的保持时间（秒）
+const CONTROL_PERSIST_SECS: u32 = 120;
+
+/// 默认最多读取的远程文件大小
+const DEFAULT_MAX_REMOTE_BYTES: u64 = 256 * 1024 * 1024;
+
+/// 远程目录中的条目
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RemoteFileEntry {
+    /// 文件名
+    pub name: String,
+    /// 完整的远程路径
+    pub path: String,
+    /// 是否为目录
+    pub is_dir: bool,
+    /// 文件大小（字节）
+    pub size: u64,
+    /// 修改时间（Unix时间戳，秒）
+    pub modified: Option<i64>,
+}
+
+/// 按POSIX shell规则给参数加单引号
+pub fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+/// 远程文件的显示标签（`ssh://user@host:port/path`），用作跟踪和缓存的文件标识
+pub fn remote_label(host: &RemoteHost, path: &str) -> String {
+    let user = host.user.as_deref().map(|u| format!("{}@", u)).unwrap_or_default();
+    let separator = if path.starts_with('/') { "" } else { "/" };
+    format!("ssh://{}{}:{}{}{}", user, host.host, host.port, separator, path)
+}
+
+/// 解析 `find -printf '%y\t%s\t%T@\t%f\n'` 的输出
+fn parse_find_output(dir: &str, output: &str) -> Vec<RemoteFileEntry> {
+    let dir = dir.trim_end_matches('/');
+    let mut entries: Vec<RemoteFileEntry> = output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(4, '\t');
+            let kind = fields.next()?;
+            let size = fields.next()?.parse().unwrap_or(0);
+            let modified = fields.next()?.split('.').next().and_then(|s| s.parse().ok());
+            let name = fields.next()?.to_string();
+            Some(RemoteFileEntry {
+                path: format!("{}/{}", dir, name),
+                is_dir: kind == "d",
+                size,
+                modified,
+                name,
+            })
+        })
+        .collect();
+    entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.cmp(&b.name)));
+    entries
+}
+
+/// SSH连接管理器
+pub struct SshConnectionManager {
+    /// 连接复用控制套接字所在目录（None表示不复用）
+    control_dir: Option<PathBuf>,
+}
+
+impl SshConnectionManager {
+    /// 创建连接管理器
+    ///
+    /// # 参数
+    /// - `control_dir`: 连接复用控制套接字目录（仅Unix平台使用）
+    pub fn new(control_dir: Option<PathBuf>) -> Self {
+        let control_dir = if cfg!(unix) { control_dir } else { None };
+        if let Some(dir) = &control_dir {
+            if let Err(e) = std::fs::create_dir_all(dir) {
+                log::warn!("⚠️ 创建SSH控制目录失败，禁用连接复用: {}", e);
+                return Self { control_dir: None };
+            }
+        }
+        Self { control_dir }
+    }
+
+    /// 构建ssh命令行参数（不含程序名）
+    pub fn ssh_args(&self, host: &RemoteHost, remote_command: &str) -> Vec<String> {
+        let mut args = vec![
+            "-o".to_string(), "BatchMode=yes".to_string(),
+            "-o".to_string(), format!("ConnectTimeout={}", CONNECT_TIMEOUT_SECS),
+            "-p".to_string(), host.port.to_string(),
+        ];
+        if let Some(dir) = &self.control_dir {
+            args.extend([
+                "-o".to_string(), "ControlMaster=auto".to_string(),
+                "-o".to_string(), format!("ControlPath={}", dir.join("%C").display()),
+                "-o".to_string(), format!("ControlPersist={}", CONTROL_PERSIST_SECS),
+            ]);
+        }
+        if let Some(identity) = &host.identity_file {
+            args.extend(["-i".to_string(), identity.clone()]);
+        }
+        let target = match &host.user {
+            Some(user) => format!("{}@{}", user, host.host),
+            None => host.host.clone(),
+        };
+        args.extend([target, "--".to_string(), remote_command.to_string()]);
+        args
+    }
+
+    fn command(&self, host: &RemoteHost, remote_command: &str) -> Result<Command, String> {
+        host.validate()?;
+        let mut command = Command::new("ssh");
+        command.args(self.ssh_args(host, remote_command)).stdin(Stdio::null());
+        Ok(command)
+    }
+
+    /// 执行远程命令并返回标准输出
+    fn run(&self, host: &RemoteHost, remote_command: &str) -> Result<Vec<u8>, String> {
+        log::debug!("🔐 SSH {}: {}", host.name, remote_command);
+        let output = self.command(host, remote_command)?
+            .output()
+            .map_err(|e| Message::new("ssh.spawn_failed").with("error", e).text())?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Message::new("ssh.command_failed").with("host", &host.name).with("error", stderr.trim()).text());
+        }
+        Ok(output.stdout)
+    }
+
+    /// 列出远程目录（目录在前，按名称排序）
+    ///
+    /// # Returns
+    /// - `Ok(Vec<RemoteFileEntry>)`: 目录中的条目
+    /// - `Err(String)`: 连接失败或目录不可读时的错误信息
+    pub fn list_dir(&self, host: &RemoteHost, path: &str) -> Result<Vec<RemoteFileEntry>, String> {
+        let command = format!(
+            "find {} -mindepth 1 -maxdepth 1 -printf '%y\\t%s\\t%T@\\t%f\\n'",
+            shell_quote(path)
+        );
+        let stdout = self.run(host, &command)?;
+        Ok(parse_find_output(path, &String::from_utf8_lossy(&stdout)))
+    }
+
+    /// 读取远程文件
+    ///
+    /// # 参数
+    /// - `max_bytes`: 最多读取的字节数（可选，默认256MB），超出时返回错误
+    pub fn read_file(&self, host: &RemoteHost, path: &str, max_bytes: Option<u64>) -> Result<String, String> {
+        let limit = max_bytes.unwrap_or(DEFAULT_MAX_REMOTE_BYTES);
+        let command = format!("head -c {} -- {}", limit + 1, shell_quote(path));
+        let stdout = self.run(host, &command)?;
+        if stdout.len() as u64 > limit {
+            return Err(Message::new("ssh.file_too_large").with("path", path).with("limit", limit).text());
+        }
+        Ok(String::from_utf8_lossy(&stdout).into_owned())
+    }
+
+    /// 启动远程 `tail -F`，新行通过通道逐行送出
+    ///
+    /// 调用方停止跟踪时应kill返回的子进程，读取线程随之结束。
+    pub fn spawn_tail(&self, host: &RemoteHost, path: &str) -> Result<(Child, Receiver<String>), String> {
+        let command = format!("tail -n 0 -F -- {}", shell_quote(path));
+        let mut child = self.command(host, &command)?
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| Message::new("ssh.spawn_failed").with("error", e).text())?;
+
+        let stdout = child.stdout.take().ok_or_else(|| Message::new("ssh.no_output").text())?;
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            let mut reader = BufReader::new(stdout);
+            let mut buf = Vec::new();
+            // 按字节读取行，非UTF-8内容替换为U+FFFD而不是中断跟踪
+            while let Ok(n) = reader.read_until(b'\n', &mut buf) {
+                if n == 0 {
+                    break;
+                }
+                let line = String::from_utf8_lossy(&buf).trim_end_matches(['\r', '\n']).to_string();
+                if sender.send(line).is_err() {
+                    break;
+                }
+                buf.clear();
+            }
+        });
+        Ok((child, receiver))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host() -> RemoteHost {
+        RemoteHost {
+            name: "prod".to_string(),
+            host: "10.0.0.5".to_string(),
+            port: 2222,
+            user: Some("ops".to_string()),
+            identity_file: Some("/home/me/.ssh/id_ed25519".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_ssh_args_and_quoting() {
+        let manager = SshConnectionManager::new(None);
+        let args = manager.ssh_args(&host(), "cat -- 'a.log'");
+        assert_eq!(&args[args.len() - 3..], ["ops@10.0.0.5", "--", "cat -- 'a.log'"]);
+        assert!(args.windows(2).any(|w| w == ["-p", "2222"]));
+        assert!(args.windows(2).any(|w| w == ["-i", "/home/me/.ssh/id_ed25519"]));
+
+        assert_eq!(shell_quote("it's.log"), r"'it'\''s.log'");
+        assert_eq!(remote_label(&host(), "/var/log/app.log"), "ssh://ops@10.0.0.5:2222/var/log/app.log");
+
+        let mut bad = host();
+        bad.host = "-oProxyCommand=x".to_string();
+        assert!(bad.validate().is_err());
+    }
+
+    #[test]
+    fn test_parse_find_output_sorts_directories_first() {
+        let output = "f\t1024\t1700000000.5\tapp.log\nd\t4096\t1700000001.0\tarchive\n";
+        let entries = parse_find_output("/var/log/", output);
+        assert_eq!(entries[0].name, "archive");
+        assert!(entries[0].is_dir);
+        assert_eq!(entries[1].path, "/var/log/app.log");
+        assert_eq!((entries[1].size, entries[1].modified), (1024, Some(1700000000)));
+    }
+}
@@ -0,0 +1,236 @@
+/// 运行时统计
+///
+/// 记录应用启动以来的解析次数、解析行数、最近一段时间的解析吞吐量（行/秒）、
+/// 增量解析缓存的命中情况，供 `health_check` 返回，用于排查用户现场的性能问题。
+///
+/// 吞吐量按最近1/5/15分钟内每次解析的行数和耗时计算（只统计解析耗时，不含读取文件和序列化）。
+
+use crate::reader::incremental::RefreshMode;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 吞吐量统计窗口（秒）
+pub const THROUGHPUT_WINDOWS_SECS: [u64; 3] = [60, 300, 900];
+
+/// 最多保留的解析记录数（超出时丢弃最早的记录）
+const MAX_SAMPLES: usize = 10_000;
+
+/// 一个窗口内的解析吞吐量
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Throughput {
+    /// 窗口长度（秒）
+    pub window_secs: u64,
+    /// 窗口内的解析次数
+    pub parses: usize,
+    /// 窗口内解析的行数
+    pub lines: u64,
+    /// 平均吞吐量（行/秒，窗口内没有解析时为0）
+    pub lines_per_sec: f64,
+}
+
+/// 增量解析缓存的命中统计
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CacheHitStats {
+    /// 文件未变化，直接使用缓存
+    pub hits: u64,
+    /// 文件追加了内容，只解析新增部分
+    pub incremental: u64,
+    /// 首次解析或文件被改写，完整解析
+    pub misses: u64,
+    /// 命中率（不需要完整解析的比例，没有访问时为0）
+    pub hit_ratio: f64,
+}
+
+/// 缓存占用（估算值）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CacheUsage {
+    /// 缓存的文件数
+    pub files: usize,
+    /// 缓存的条目数（解析缓存为日志条目，行索引为行数）
+    pub items: usize,
+    /// 估算的内存占用（字节）
+    pub bytes: u64,
+}
+
+/// 运行时统计快照
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuntimeSnapshot {
+    /// 启动以来的秒数
+    pub uptime_secs: u64,
+    /// 完成的解析次数（每次解析文件、分块或新增内容计一次）
+    pub parses: u64,
+    /// 解析的文件数（完整解析文件或第一个分块时计一次）
+    pub files_parsed: u64,
+    /// 解析的总行数
+    pub lines_parsed: u64,
+    /// 最近1/5/15分钟的吞吐量
+    pub throughput: Vec<Throughput>,
+    /// 增量解析缓存命中统计
+    pub parse_cache_hits: CacheHitStats,
+    /// 增量解析缓存占用
+    pub parse_cache: CacheUsage,
+    /// 行索引缓存占用
+    pub line_index_cache: CacheUsage,
+    /// 正在运行的跟踪数
+    pub active_tails: usize,
+}
+
+/// 一次解析的记录
+#[derive(Debug, Clone, Copy)]
+struct ParseSample {
+    at: Instant,
+    lines: u64,
+    elapsed: Duration,
+}
+
+/// 运行时统计
+pub struct RuntimeStats {
+    started_at: Instant,
+    parses: AtomicU64,
+    files_parsed: AtomicU64,
+    lines_parsed: AtomicU64,
+    cache_hits: AtomicU64,
+    cache_incremental: AtomicU64,
+    cache_misses: AtomicU64,
+    samples: Mutex<VecDeque<ParseSample>>,
+}
+
+impl RuntimeStats {
+    /// 创建统计，启动时间为当前时间
+    pub fn new() -> Self {
+        Self {
+            started_at: Instant::now(),
+            parses: AtomicU64::new(0),
+            files_parsed: AtomicU64::new(0),
+            lines_parsed: AtomicU64::new(0),
+            cache_hits: AtomicU64::new(0),
+            cache_incremental: AtomicU64::new(0),
+            cache_misses: AtomicU64::new(0),
+            samples: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// 记录一次解析
+    ///
+    /// # 参数
+    /// - `lines`: 解析的行数
+    /// - `elapsed`: 解析耗时
+    /// - `new_file`: 是否开始解析一个文件（完整解析或第一个分块）
+    pub fn record_parse(&self, lines: usize, elapsed: Duration, new_file: bool) {
+        self.record_parse_at(Instant::now(), lines, elapsed, new_file);
+    }
+
+    fn record_parse_at(&self, at: Instant, lines: usize, elapsed: Duration, new_file: bool) {
+        self.parses.fetch_add(1, Ordering::Relaxed);
+        self.lines_parsed.fetch_add(lines as u64, Ordering::Relaxed);
+        if new_file {
+            self.files_parsed.fetch_add(1, Ordering::Relaxed);
+        }
+
+        let Ok(mut samples) = self.samples.lock() else { return };
+        samples.push_back(ParseSample { at, lines: lines as u64, elapsed });
+        let oldest = Duration::from_secs(THROUGHPUT_WINDOWS_SECS[THROUGHPUT_WINDOWS_SECS.len() - 1]);
+        while samples.front().is_some_and(|s| at.duration_since(s.at) > oldest) || samples.len() > MAX_SAMPLES {
+            samples.pop_front();
+        }
+    }
+
+    /// 记录一次增量解析缓存访问（完整解析同时计为解析了一个文件）
+    pub fn record_cache(&self, mode: RefreshMode) {
+        let counter = match mode {
+            RefreshMode::Unchanged => &self.cache_hits,
+            RefreshMode::Appended => &self.cache_incremental,
+            RefreshMode::Full => {
+                self.files_parsed.fetch_add(1, Ordering::Relaxed);
+                &self.cache_misses
+            }
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// 计算各窗口的吞吐量
+    fn throughput(&self, now: Instant) -> Vec<Throughput> {
+        let samples = self.samples.lock().map(|s| s.clone()).unwrap_or_default();
+        THROUGHPUT_WINDOWS_SECS.iter().map(|&window_secs| {
+            let window = Duration::from_secs(window_secs);
+            let recent: Vec<&ParseSample> = samples.iter().filter(|s| now.saturating_duration_since(s.at) <= window).collect();
+            let lines: u64 = recent.iter().map(|s| s.lines).sum();
+            let busy: f64 = recent.iter().map(|s| s.elapsed.as_secs_f64()).sum();
+            Throughput {
+                window_secs,
+                parses: recent.len(),
+                lines,
+                lines_per_sec: if busy > 0.0 { lines as f64 / busy } else { 0.0 },
+            }
+        }).collect()
+    }
+
+    /// 生成统计快照
+    ///
+    /// # 参数
+    /// - `parse_cache`: 增量解析缓存占用
+    /// - `line_index_cache`: 行索引缓存占用
+    /// - `active_tails`: 正在运行的跟踪数
+    pub fn snapshot(&self, parse_cache: CacheUsage, line_index_cache: CacheUsage, active_tails: usize) -> RuntimeSnapshot {
+        let hits = self.cache_hits.load(Ordering::Relaxed);
+        let incremental = self.cache_incremental.load(Ordering::Relaxed);
+        let misses = self.cache_misses.load(Ordering::Relaxed);
+        let total = hits + incremental + misses;
+
+        RuntimeSnapshot {
+            uptime_secs: self.started_at.elapsed().as_secs(),
+            parses: self.parses.load(Ordering::Relaxed),
+            files_parsed: self.files_parsed.load(Ordering::Relaxed),
+            lines_parsed: self.lines_parsed.load(Ordering::Relaxed),
+            throughput: self.throughput(Instant::now()),
+            parse_cache_hits: CacheHitStats {
+                hits,
+                incremental,
+                misses,
+                hit_ratio: if total > 0 { (hits + incremental) as f64 / total as f64 } else { 0.0 },
+            },
+            parse_cache,
+            line_index_cache,
+            active_tails,
+        }
+    }
+}
+
+impl Default for RuntimeStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_throughput_windows_and_cache_ratio() {
+        let stats = RuntimeStats::new();
+        let now = Instant::now() + Duration::from_secs(1000);
+        // 10分钟前：1000行用时1秒；刚才：2000行用时0.5秒
+        stats.record_parse_at(now - Duration::from_secs(600), 1000, Duration::from_secs(1), true);
+        stats.record_parse_at(now, 2000, Duration::from_millis(500), false);
+        stats.record_cache(RefreshMode::Full);
+        stats.record_cache(RefreshMode::Unchanged);
+        stats.record_cache(RefreshMode::Appended);
+        stats.record_cache(RefreshMode::Unchanged);
+
+        let throughput = stats.throughput(now);
+        assert_eq!((throughput[0].parses, throughput[0].lines), (1, 2000));
+        assert!((throughput[0].lines_per_sec - 4000.0).abs() < 1e-6);
+        assert_eq!((throughput[2].window_secs, throughput[2].parses), (900, 2));
+        assert!((throughput[2].lines_per_sec - 2000.0).abs() < 1e-6);
+
+        let snapshot = stats.snapshot(CacheUsage::default(), CacheUsage::default(), 2);
+        assert_eq!((snapshot.parses, snapshot.files_parsed, snapshot.lines_parsed), (2, 2, 3000));
+        assert_eq!((snapshot.parse_cache_hits.hits, snapshot.parse_cache_hits.misses), (2, 1));
+        assert!((snapshot.parse_cache_hits.hit_ratio - 0.75).abs() < 1e-9);
+        assert_eq!(snapshot.active_tails, 2);
+    }
+}
@@ -0,0 +1,284 @@
+/// 条目抽样
+///
+/// 在千万行级的文件上预览筛选结果时，不构造全部匹配条目，而是扫描条目流并只保留 `n` 条代表性条目：
+/// 随机抽样（蓄水池抽样）、按级别分层抽样（每个出现的级别至少一条，其余按比例分配），
+/// 或按时间均匀抽样（把时间范围等分为 `n` 段，每段取第一条）。
+
+use crate::models::LogLine;
+use crate::startup::timestamp_millis;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// 单次抽样的最大条目数
+pub const MAX_SAMPLE_SIZE: usize = 10_000;
+
+/// 没有级别的条目所在的分层
+const UNKNOWN_LEVEL: &str = "UNKNOWN";
+
+/// 抽样策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SampleStrategy {
+    /// 随机抽样
+    #[default]
+    Random,
+    /// 按级别分层抽样
+    Stratified,
+    /// 按时间均匀抽样（没有可解析时间戳时按位置均匀抽样）
+    TimeSpaced,
+}
+
+/// 抽样结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntrySample {
+    /// 使用的策略
+    pub strategy: SampleStrategy,
+    /// 匹配筛选条件的条目总数
+    pub matched: usize,
+    /// 抽样条目（按行号排序）
+    pub entries: Vec<LogLine>,
+    /// 分层抽样时各级别的匹配数和抽样数
+    pub strata: Vec<SampleStratum>,
+}
+
+/// 一个分层
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SampleStratum {
+    /// 级别
+    pub level: String,
+    /// 匹配的条目数
+    pub matched: usize,
+    /// 抽样的条目数
+    pub sampled: usize,
+}
+
+/// xorshift64*伪随机数，同一种子得到相同的抽样结果
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self(seed.max(1))
+    }
+
+    /// `0..bound` 范围内的随机数
+    fn below(&mut self, bound: usize) -> usize {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        (self.0.wrapping_mul(0x2545_f491_4f6c_dd1d) % bound as u64) as usize
+    }
+}
+
+/// 蓄水池：等概率保留流中的 `capacity` 条条目
+struct Reservoir {
+    capacity: usize,
+    seen: usize,
+    items: Vec<LogLine>,
+}
+
+impl Reservoir {
+    fn new(capacity: usize) -> Self {
+        Self { capacity, seen: 0, items: Vec::new() }
+    }
+
+    fn offer(&mut self, entry: LogLine, rng: &mut Rng) {
+        self.seen += 1;
+        if self.items.len() < self.capacity {
+            self.items.push(entry);
+        } else {
+            let slot = rng.below(self.seen);
+            if slot < self.capacity {
+                self.items[slot] = entry;
+            }
+        }
+    }
+}
+
+fn level_of(entry: &LogLine) -> String {
+    entry.level.as_deref()
+        .map(|level| level.trim().to_uppercase())
+        .filter(|level| !level.is_empty())
+        .unwrap_or_else(|| UNKNOWN_LEVEL.to_string())
+}
+
+/// 按比例分配各层的抽样数：每层至少一条（`n` 不足时优先较大的层），其余按最大余数法分配
+fn allocate(counts: &[usize], n: usize) -> Vec<usize> {
+    let total: usize = counts.iter().sum();
+    let mut quotas = vec![0; counts.len()];
+    if total <= n {
+        return counts.to_vec();
+    }
+
+    let mut order: Vec<usize> = (0..counts.len()).collect();
+    order.sort_by(|&a, &b| counts[b].cmp(&counts[a]));
+    let mut remaining = n;
+    for &i in &order {
+        if remaining == 0 {
+            return quotas;
+        }
+        quotas[i] = 1;
+        remaining -= 1;
+    }
+
+    let mut remainders = Vec::new();
+    let base = remaining;
+    for &i in &order {
+        let share = counts[i] * base;
+        let extra = (share / total).min(counts[i] - quotas[i]);
+        quotas[i] += extra;
+        remaining -= extra;
+        remainders.push((share % total, i));
+    }
+    remainders.sort_by_key(|&(remainder, _)| std::cmp::Reverse(remainder));
+    for &(_, i) in &remainders {
+        if remaining > 0 && quotas[i] < counts[i] {
+            quotas[i] += 1;
+            remaining -= 1;
+        }
+    }
+    for &(_, i) in &remainders {
+        let extra = remaining.min(counts[i] - quotas[i]);
+        quotas[i] += extra;
+        remaining -= extra;
+    }
+    quotas
+}
+
+/// 抽取代表性条目
+///
+/// 随机抽样扫描一遍条目，分层抽样和按时间抽样扫描两遍；无论匹配多少条，最多只保留 `n` 条。
+/// 按时间抽样时时间段内没有条目的段不产生结果，因此结果可能少于 `n` 条。
+///
+/// # 参数
+/// - `entries`: 每次调用返回一遍条目流（按行号顺序）
+/// - `matches`: 筛选条件
+/// - `n`: 抽样条目数（上限 `MAX_SAMPLE_SIZE`）
+/// - `strategy`: 抽样策略
+/// - `seed`: 随机种子（相同种子得到相同结果）
+pub fn sample_entries<E, I, M>(entries: E, matches: M, n: usize, strategy: SampleStrategy, seed: u64) -> EntrySample
+where
+    E: Fn() -> I,
+    I: Iterator<Item = LogLine>,
+    M: Fn(&LogLine) -> bool,
+{
+    let n = n.min(MAX_SAMPLE_SIZE);
+    let mut rng = Rng::new(seed);
+    let mut strata = Vec::new();
+    let (matched, mut sampled) = match strategy {
+        SampleStrategy::Random => {
+            let mut reservoir = Reservoir::new(n);
+            entries().filter(|entry| matches(entry)).for_each(|entry| reservoir.offer(entry, &mut rng));
+            (reservoir.seen, reservoir.items)
+        }
+        SampleStrategy::Stratified => {
+            let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+            entries().filter(|entry| matches(entry)).for_each(|entry| *counts.entry(level_of(&entry)).or_default() += 1);
+            let quotas = allocate(&counts.values().copied().collect::<Vec<_>>(), n);
+            let mut reservoirs: BTreeMap<String, Reservoir> = counts.keys().cloned()
+                .zip(quotas.iter().map(|&quota| Reservoir::new(quota)))
+                .collect();
+            for entry in entries().filter(|entry| matches(entry)) {
+                if let Some(reservoir) = reservoirs.get_mut(&level_of(&entry)) {
+                    reservoir.offer(entry, &mut rng);
+                }
+            }
+            let mut sampled = Vec::new();
+            for (level, reservoir) in reservoirs {
+                strata.push(SampleStratum { level, matched: reservoir.seen, sampled: reservoir.items.len() });
+                sampled.extend(reservoir.items);
+            }
+            (counts.values().sum(), sampled)
+        }
+        SampleStrategy::TimeSpaced => time_spaced(&entries, &matches, n),
+    };
+    sampled.sort_by_key(|entry| entry.line_number);
+    EntrySample { strategy, matched, entries: sampled, strata }
+}
+
+/// 按时间均匀抽样；没有可解析时间戳的匹配条目时按位置均匀抽样
+fn time_spaced<E, I, M>(entries: &E, matches: &M, n: usize) -> (usize, Vec<LogLine>)
+where
+    E: Fn() -> I,
+    I: Iterator<Item = LogLine>,
+    M: Fn(&LogLine) -> bool,
+{
+    let millis = |entry: &LogLine| entry.timestamp.as_deref().and_then(timestamp_millis);
+    let mut matched = 0;
+    let mut range: Option<(i64, i64)> = None;
+    for entry in entries().filter(|entry| matches(entry)) {
+        matched += 1;
+        if let Some(time) = millis(&entry) {
+            range = Some(range.map_or((time, time), |(min, max)| (min.min(time), max.max(time))));
+        }
+    }
+    if n == 0 || matched == 0 {
+        return (matched, Vec::new());
+    }
+
+    let mut buckets: Vec<Option<LogLine>> = vec![None; n];
+    let matching = entries().filter(|entry| matches(entry));
+    match range {
+        Some((min, max)) => {
+            let span = (max - min) as u128 + 1;
+            for entry in matching {
+                let Some(time) = millis(&entry) else { continue };
+                let bucket = ((time - min) as u128 * n as u128 / span) as usize;
+                buckets[bucket].get_or_insert(entry);
+            }
+        }
+        None => {
+            for (position, entry) in matching.enumerate() {
+                let bucket = position * n / matched;
+                buckets[bucket].get_or_insert(entry);
+            }
+        }
+    }
+    (matched, buckets.into_iter().flatten().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(line_number: usize, level: &str, timestamp: &str) -> LogLine {
+        LogLine {
+            level: Some(level.to_string()),
+            timestamp: Some(timestamp.to_string()),
+            ..LogLine::new(line_number, format!("line {}", line_number))
+        }
+    }
+
+    #[test]
+    fn test_sample_strategies() {
+        // 1000条：998条INFO、2条ERROR；前990条在第0秒，最后10条在1小时后
+        let entries: Vec<LogLine> = (1..=1000)
+            .map(|i| {
+                let level = if i == 10 || i == 500 { "ERROR" } else { "INFO" };
+                let time = if i <= 990 { "2024-01-15 10:00:00" } else { "2024-01-15 11:00:00" };
+                entry(i, level, time)
+            })
+            .collect();
+        let all = || entries.iter().cloned();
+        let not_line_3 = |entry: &LogLine| entry.line_number != 3;
+
+        let random = sample_entries(all, not_line_3, 20, SampleStrategy::Random, 42);
+        assert_eq!((random.matched, random.entries.len()), (999, 20));
+        assert!(random.entries.windows(2).all(|w| w[0].line_number < w[1].line_number));
+        let again = sample_entries(all, not_line_3, 20, SampleStrategy::Random, 42);
+        assert_eq!(random.entries.iter().map(|e| e.line_number).collect::<Vec<_>>(),
+                   again.entries.iter().map(|e| e.line_number).collect::<Vec<_>>());
+
+        let stratified = sample_entries(all, not_line_3, 10, SampleStrategy::Stratified, 7);
+        assert_eq!(stratified.strata, vec![
+            SampleStratum { level: "ERROR".to_string(), matched: 2, sampled: 1 },
+            SampleStratum { level: "INFO".to_string(), matched: 997, sampled: 9 },
+        ]);
+
+        let spaced = sample_entries(all, not_line_3, 4, SampleStrategy::TimeSpaced, 0);
+        assert_eq!(spaced.entries.iter().map(|e| e.line_number).collect::<Vec<_>>(), vec![1, 991]);
+
+        assert_eq!(allocate(&[997, 2, 1], 2), vec![1, 1, 0]);
+        assert_eq!(allocate(&[5, 3], 20), vec![5, 3]);
+        assert_eq!(allocate(&[50, 30, 20], 10).iter().sum::<usize>(), 10);
+    }
+}
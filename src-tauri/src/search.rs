@@ -0,0 +1,333 @@
+/// 日志搜索
+///
+/// 在后端执行搜索并返回每个匹配条目中命中的位置（字节偏移和字符偏移），
+/// 前端直接按位置高亮，无需在JS中对超长字符串重新执行正则。
+///
+/// # 搜索模式
+/// - `text`: 普通文本（特殊字符按字面匹配）
+/// - `regex`: 正则表达式（Rust regex语法）
+///
+/// 两种模式都支持忽略大小写和全词匹配。
+///
+/// `EntryFilter` 把搜索与日志级别、元数据条件组合起来，
+/// 供分组统计和跟踪等需要在后端筛选条目的功能使用。
+
+use crate::i18n::Message;
+use crate::models::LogLine;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::BufRead;
+use std::path::Path;
+
+/// 默认最多返回的匹配条目数
+const DEFAULT_MAX_RESULTS: usize = 10_000;
+
+/// 搜索模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchMode {
+    /// 普通文本
+    #[default]
+    Text,
+    /// 正则表达式
+    Regex,
+}
+
+/// 搜索选项
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SearchOptions {
+    /// 搜索关键字或正则表达式
+    pub query: String,
+
+    /// 搜索模式
+    #[serde(default)]
+    pub mode: SearchMode,
+
+    /// 是否区分大小写（默认不区分）
+    #[serde(default)]
+    pub case_sensitive: bool,
+
+    /// 是否全词匹配
+    #[serde(default)]
+    pub whole_word: bool,
+
+    /// 最多返回的匹配条目数（默认10000）
+    #[serde(default)]
+    pub max_results: Option<usize>,
+}
+
+/// 单个命中位置
+///
+/// 字节偏移用于后端/按字节切片，字符偏移（Unicode标量）用于前端高亮。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MatchSpan {
+    /// 起始字节偏移（包含）
+    pub start: usize,
+    /// 结束字节偏移（不包含）
+    pub end: usize,
+    /// 起始字符偏移（包含）
+    pub char_start: usize,
+    /// 结束字符偏移（不包含）
+    pub char_end: usize,
+}
+
+/// 单个匹配条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchHit {
+    /// 条目行号
+    pub line_number: usize,
+    /// 命中位置是否针对格式化内容（否则针对原始内容）
+    pub in_formatted: bool,
+    /// 条目中的所有命中位置
+    pub spans: Vec<MatchSpan>,
+}
+
+/// 搜索结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchResult {
+    /// 匹配的条目
+    pub hits: Vec<SearchHit>,
+    /// 命中位置总数（仅统计返回的条目）
+    pub total_spans: usize,
+    /// 是否因达到 `max_results` 而提前结束
+    pub truncated: bool,
+}
+
+/// 已编译的搜索
+pub struct Searcher {
+    regex: Regex,
+    max_results: usize,
+}
+
+impl Searcher {
+    /// 按选项编译搜索
+    ///
+    /// # Returns
+    /// - `Ok(Searcher)`: 编译后的搜索
+    /// - `Err(String)`: 关键字为空或正则表达式无效时的错误信息
+    pub fn new(options: &SearchOptions) -> Result<Self, String> {
+        if options.query.is_empty() {
+            return Err(Message::new("search.empty_query").text());
+        }
+
+        let pattern = match options.mode {
+            SearchMode::Text => regex::escape(&options.query),
+            SearchMode::Regex => options.query.clone(),
+        };
+        let pattern = if options.whole_word {
+            format!(r"\b(?:{})\b", pattern)
+        } else {
+            pattern
+        };
+
+        let regex = RegexBuilder::new(&pattern)
+            .case_insensitive(!options.case_sensitive)
+            .build()
+            .map_err(|e| Message::new("search.invalid_regex").with("error", e).text())?;
+
+        Ok(Self {
+            regex,
+            max_results: options.max_results.unwrap_or(DEFAULT_MAX_RESULTS),
+        })
+    }
+
+    /// 文本是否包含命中
+    pub fn is_match(&self, text: &str) -> bool {
+        self.regex.is_match(text)
+    }
+
+    /// 查找文本中的所有命中位置（忽略空匹配）
+    pub fn find_spans(&self, text: &str) -> Vec<MatchSpan> {
+        let mut spans = Vec::new();
+        let mut chars_before = 0;
+        let mut last_byte = 0;
+
+        for m in self.regex.find_iter(text).filter(|m| !m.as_str().is_empty()) {
+            chars_before += text[last_byte..m.start()].chars().count();
+            let char_len = m.as_str().chars().count();
+            spans.push(MatchSpan {
+                start: m.start(),
+                end: m.end(),
+                char_start: chars_before,
+                char_end: chars_before + char_len,
+            });
+            chars_before += char_len;
+            last_byte = m.end();
+        }
+        spans
+    }
+
+    /// 搜索解析后的日志条目（优先搜索格式化内容）
+    pub fn search_entries(&self, entries: &[LogLine]) -> SearchResult {
+        self.collect(entries.iter().map(|entry| match &entry.formatted_content {
+            Some(formatted) => (entry.line_number, true, formatted.as_str()),
+            None => (entry.line_number, false, entry.content.as_str()),
+        }))
+    }
+
+    /// 逐行搜索文件原始内容，行号包括空行
+    ///
+    /// # Returns
+    /// - `Ok(SearchResult)`: 搜索结果
+    /// - `Err(String)`: 文件无法读取时的错误信息
+    pub fn search_file<P: AsRef<Path>>(&self, path: P) -> Result<SearchResult, String> {
+        let file = std::fs::File::open(path.as_ref())
+            .map_err(|e| Message::new("io.open_failed").with("error", e).text())?;
+        let reader = std::io::BufReader::new(file);
+
+        let mut lines = Vec::new();
+        for (i, line) in reader.lines().enumerate() {
+            let line = line.map_err(|e| Message::new("io.read_failed").with("error", e).text())?;
+            if !self.regex.is_match(&line) {
+                continue;
+            }
+            lines.push((i + 1, line));
+            if lines.len() > self.max_results {
+                break;
+            }
+        }
+        Ok(self.collect(lines.iter().map(|(n, line)| (*n, false, line.as_str()))))
+    }
+
+    fn collect<'a, I>(&self, texts: I) -> SearchResult
+    where
+        I: Iterator<Item = (usize, bool, &'a str)>,
+    {
+        let mut hits = Vec::new();
+        let mut total_spans = 0;
+        let mut truncated = false;
+
+        for (line_number, in_formatted, text) in texts {
+            let spans = self.find_spans(text);
+            if spans.is_empty() {
+                continue;
+            }
+            if hits.len() == self.max_results {
+                truncated = true;
+                break;
+            }
+            total_spans += spans.len();
+            hits.push(SearchHit { line_number, in_formatted, spans });
+        }
+
+        SearchResult { hits, total_spans, truncated }
+    }
+}
+
+/// 条目筛选条件
+///
+/// 所有条件同时满足时条目才匹配；未设置的条件不做限制。
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EntryFilter {
+    /// 允许的日志级别（如 `["ERROR", "WARN"]`，不区分大小写）
+    #[serde(default)]
+    pub levels: Vec<String>,
+
+    /// 内容搜索条件（优先匹配格式化内容）
+    #[serde(default)]
+    pub search: Option<SearchOptions>,
+
+    /// 元数据条件（键 -> 值，精确匹配）
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+}
+
+impl EntryFilter {
+    /// 是否没有任何条件
+    pub fn is_empty(&self) -> bool {
+        self.levels.is_empty() && self.search.is_none() && self.metadata.is_empty()
+    }
+
+    /// 编译筛选条件
+    ///
+    /// # Returns
+    /// - `Ok(EntryMatcher)`: 编译后的筛选
+    /// - `Err(String)`: 搜索关键字为空或正则表达式无效时的错误信息
+    pub fn compile(&self) -> Result<EntryMatcher, String> {
+        Ok(EntryMatcher {
+            levels: self.levels.iter().map(|level| level.to_uppercase()).collect(),
+            searcher: self.search.as_ref().map(Searcher::new).transpose()?,
+            metadata: self.metadata.clone(),
+        })
+    }
+}
+
+/// 编译后的条目筛选
+pub struct EntryMatcher {
+    levels: Vec<String>,
+    searcher: Option<Searcher>,
+    metadata: HashMap<String, String>,
+}
+
+impl EntryMatcher {
+    /// 条目是否满足所有条件
+    pub fn matches(&self, entry: &LogLine) -> bool {
+        if !self.levels.is_empty() {
+            let level = entry.level.as_deref().map(str::to_uppercase);
+            if !level.is_some_and(|level| self.levels.contains(&level)) {
+                return false;
+            }
+        }
+        if !self.metadata.iter().all(|(key, value)| entry.metadata.get(key) == Some(value)) {
+            return false;
+        }
+        match &self.searcher {
+            Some(searcher) => searcher.is_match(entry.formatted_content.as_deref().unwrap_or(&entry.content)),
+            None => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn searcher(query: &str, mode: SearchMode, case_sensitive: bool, whole_word: bool) -> Searcher {
+        Searcher::new(&SearchOptions {
+            query: query.to_string(),
+            mode,
+            case_sensitive,
+            whole_word,
+            max_results: None,
+        }).unwrap()
+    }
+
+    #[test]
+    fn test_spans_report_byte_and_char_offsets() {
+        let spans = searcher("error", SearchMode::Text, false, false).find_spans("日志 ERROR: error");
+        assert_eq!(spans, vec![
+            MatchSpan { start: 7, end: 12, char_start: 3, char_end: 8 },
+            MatchSpan { start: 14, end: 19, char_start: 10, char_end: 15 },
+        ]);
+    }
+
+    #[test]
+    fn test_modes() {
+        assert!(searcher("a.b", SearchMode::Text, false, false).find_spans("axb").is_empty());
+        assert_eq!(searcher("a.b", SearchMode::Regex, false, false).find_spans("axb").len(), 1);
+        assert!(searcher("Error", SearchMode::Text, true, false).find_spans("error").is_empty());
+        assert_eq!(searcher("err", SearchMode::Text, false, true).find_spans("err error").len(), 1);
+        assert!(Searcher::new(&SearchOptions { query: "(".to_string(), mode: SearchMode::Regex, ..Default::default() }).is_err());
+    }
+
+    #[test]
+    fn test_entry_filter_combines_conditions() {
+        let entry = |level: &str, content: &str, thread: &str| LogLine {
+            level: Some(level.to_string()),
+            metadata: HashMap::from([("thread".to_string(), thread.to_string())]),
+            ..LogLine::new(1, content)
+        };
+        let matcher = EntryFilter {
+            levels: vec!["error".to_string()],
+            search: Some(SearchOptions { query: "timeout".to_string(), ..Default::default() }),
+            metadata: HashMap::from([("thread".to_string(), "main".to_string())]),
+        }.compile().unwrap();
+
+        assert!(matcher.matches(&entry("ERROR", "db Timeout", "main")));
+        assert!(!matcher.matches(&entry("WARN", "db Timeout", "main")));
+        assert!(!matcher.matches(&entry("ERROR", "db refused", "main")));
+        assert!(!matcher.matches(&entry("ERROR", "db Timeout", "worker-1")));
+        assert!(EntryFilter::default().compile().unwrap().matches(&entry("INFO", "", "")));
+    }
+}
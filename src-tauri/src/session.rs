@@ -0,0 +1,244 @@
+/// 工作区会话
+///
+/// 把整个排查现场（打开的文件、各文件的过滤条件和格式选择、滚动位置、固定条目）
+/// 保存为应用数据目录下 `sessions/<名称>.json` 的会话文件，之后可以恢复继续排查，
+/// 也可以把会话文件发给同事，由对方通过路径加载。
+///
+/// 过滤条件由前端定义，后端只原样保存（`serde_json::Value`）；
+/// 固定条目随会话一起保存，加载时按会话名称写回固定列表。
+
+use crate::config::PinnedEntry;
+use crate::i18n::Message;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// 当前会话文件格式版本
+pub const SESSION_VERSION: u32 = 1;
+
+/// 会话中的一个打开文件
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionFile {
+    /// 文件路径（本地路径、压缩包虚拟路径或HTTP(S)链接）
+    pub path: String,
+    /// 选择的解析插件（None表示自动检测）
+    #[serde(default)]
+    pub plugin: Option<String>,
+    /// 该文件的过滤条件（前端定义的结构）
+    #[serde(default)]
+    pub filters: serde_json::Value,
+    /// 滚动位置（可见区域第一行的行号）
+    #[serde(default)]
+    pub scroll_line: Option<usize>,
+    /// 固定条目
+    #[serde(default)]
+    pub bookmarks: Vec<PinnedEntry>,
+    /// 加载时文件已不存在（仅加载结果中使用，保存时忽略）
+    #[serde(default, skip_serializing)]
+    pub missing: bool,
+}
+
+/// 工作区会话
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    /// 会话名称
+    pub name: String,
+    /// 会话文件格式版本
+    #[serde(default = "default_version")]
+    pub version: u32,
+    /// 保存时间（RFC 3339，保存时自动填写）
+    #[serde(default)]
+    pub saved_at: String,
+    /// 打开的文件（按标签页顺序）
+    #[serde(default)]
+    pub files: Vec<SessionFile>,
+    /// 当前激活的文件路径
+    #[serde(default)]
+    pub active_file: Option<String>,
+    /// 全局过滤条件（前端定义的结构）
+    #[serde(default)]
+    pub filters: serde_json::Value,
+}
+
+fn default_version() -> u32 {
+    SESSION_VERSION
+}
+
+/// 已保存会话的摘要
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionSummary {
+    /// 会话名称
+    pub name: String,
+    /// 保存时间
+    pub saved_at: String,
+    /// 打开的文件数
+    pub file_count: usize,
+    /// 会话文件路径
+    pub path: String,
+}
+
+/// 把会话名称转换为安全的文件名（只保留字母、数字、`-`、`_`，其他字符替换为 `_`）
+fn file_stem(name: &str) -> String {
+    name.trim()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || matches!(c, '-' | '_') { c } else { '_' })
+        .collect()
+}
+
+/// 会话文件存储
+pub struct SessionStore {
+    dir: PathBuf,
+}
+
+impl SessionStore {
+    /// 创建会话存储
+    ///
+    /// # 参数
+    /// - `dir`: 会话文件目录（不存在时在首次保存时创建）
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// 会话名称对应的文件路径
+    pub fn session_path(&self, name: &str) -> Result<PathBuf, String> {
+        let stem = file_stem(name);
+        if stem.is_empty() {
+            return Err(Message::new("session.empty_name").text());
+        }
+        Ok(self.dir.join(format!("{}.json", stem)))
+    }
+
+    /// 会话名称对应的解析结果数据库路径（会话文件旁的 `<会话名>.entries.db`）
+    pub fn entries_db_path(&self, name: &str) -> Result<PathBuf, String> {
+        Ok(self.session_path(name)?.with_extension("entries.db"))
+    }
+
+    /// 保存会话（同名会话会被覆盖），自动填写版本和保存时间
+    ///
+    /// # Returns
+    /// - `Ok(PathBuf)`: 会话文件路径
+    /// - `Err(String)`: 名称无效或写入失败时的错误信息
+    pub fn save(&self, session: &mut Session) -> Result<PathBuf, String> {
+        let path = self.session_path(&session.name)?;
+        session.version = SESSION_VERSION;
+        session.saved_at = chrono::Utc::now().to_rfc3339();
+
+        std::fs::create_dir_all(&self.dir).map_err(|e| Message::new("session.create_directory_failed").with("error", e).text())?;
+        let json = serde_json::to_string_pretty(session).map_err(|e| Message::new("session.serialize_failed").with("error", e).text())?;
+        crate::write_guard::atomic_write(&path, json.as_bytes())?;
+        Ok(path)
+    }
+
+    /// 按名称加载会话
+    pub fn load(&self, name: &str) -> Result<Session, String> {
+        load_file(&self.session_path(name)?)
+    }
+
+    /// 列出已保存的会话（按保存时间倒序）
+    pub fn list(&self) -> Result<Vec<SessionSummary>, String> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(Message::new("session.read_directory_failed").with("error", e).text()),
+        };
+
+        let mut sessions: Vec<SessionSummary> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|path| match load_file(&path) {
+                Ok(session) => Some(SessionSummary {
+                    name: session.name,
+                    saved_at: session.saved_at,
+                    file_count: session.files.len(),
+                    path: path.to_string_lossy().into_owned(),
+                }),
+                Err(e) => {
+                    log::warn!("⚠️ 跳过无效的会话文件 {}: {}", path.display(), e);
+                    None
+                }
+            })
+            .collect();
+        sessions.sort_by(|a, b| b.saved_at.cmp(&a.saved_at));
+        Ok(sessions)
+    }
+}
+
+/// 从会话文件加载会话，并标记已不存在的本地文件
+///
+/// # 参数
+/// - `path`: 会话文件路径（可以是同事分享的、不在会话目录中的文件）
+///
+/// # Returns
+/// - `Ok(Session)`: 会话内容
+/// - `Err(String)`: 文件不可读、格式无效或版本过新时的错误信息
+pub fn load_file(path: &Path) -> Result<Session, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| Message::new("session.read_failed").with("path", path.display()).with("error", e).text())?;
+    let mut session: Session = serde_json::from_str(&text)
+        .map_err(|e| Message::new("session.invalid_format").with("error", e).text())?;
+    if session.version > SESSION_VERSION {
+        return Err(Message::new("session.version_too_new").with("version", session.version).with("supported", SESSION_VERSION).text());
+    }
+
+    for file in &mut session.files {
+        let local = !file.path.contains("://");
+        let on_disk = crate::reader::archive::split_member_path(&file.path)
+            .map_or(file.path.as_str(), |(archive, _)| archive);
+        file.missing = local && !Path::new(on_disk).exists();
+    }
+    Ok(session)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_list_and_load_session() {
+        let dir = std::env::temp_dir().join(format!("log_whisper_sessions_{}", std::process::id()));
+        let store = SessionStore::new(dir.clone());
+        let existing = dir.join("app.log");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(&existing, "INFO ok\n").unwrap();
+
+        let mut session = Session {
+            name: "支付超时/排查".to_string(),
+            version: 0,
+            saved_at: String::new(),
+            files: vec![
+                SessionFile {
+                    path: existing.to_string_lossy().into_owned(),
+                    plugin: Some("springboot".to_string()),
+                    filters: serde_json::json!({"levels": ["ERROR"]}),
+                    scroll_line: Some(120),
+                    bookmarks: Vec::new(),
+                    missing: false,
+                },
+                SessionFile {
+                    path: dir.join("gone.log").to_string_lossy().into_owned(),
+                    plugin: None,
+                    filters: serde_json::Value::Null,
+                    scroll_line: None,
+                    bookmarks: Vec::new(),
+                    missing: false,
+                },
+            ],
+            active_file: None,
+            filters: serde_json::Value::Null,
+        };
+        let path = store.save(&mut session).unwrap();
+        assert_eq!(path.file_name().unwrap(), "支付超时_排查.json");
+
+        let loaded = store.load("支付超时/排查").unwrap();
+        assert_eq!(loaded.version, SESSION_VERSION);
+        assert_eq!(loaded.files[0].scroll_line, Some(120));
+        assert_eq!(loaded.files[0].filters["levels"][0], "ERROR");
+        assert!(!loaded.files[0].missing);
+        assert!(loaded.files[1].missing);
+
+        let sessions = store.list().unwrap();
+        assert_eq!((sessions.len(), sessions[0].file_count), (1, 2));
+        assert!(store.session_path("  ").is_err());
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
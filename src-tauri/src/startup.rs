@@ -0,0 +1,484 @@
+/// SpringBoot启动分析
+///
+/// 按启动过程中的关键节点（banner → `Starting ...` → 上下文初始化 → JPA初始化 →
+/// Web服务器启动 → `Started ... in X seconds`）计算各阶段耗时，
+/// 开发者可以直接看出启动慢在哪个阶段。
+///
+/// 一个文件中有多次启动（如应用重启）时，每次启动单独统计。
+/// 没有时间戳的节点（如banner）按其后第一条带时间戳的条目计时。
+///
+/// 启动失败时，`FailureAnalyzer` 输出的 `APPLICATION FAILED TO START` 区块和
+/// `CONDITIONS EVALUATION REPORT` 解析为结构化的失败报告（见 `find_startup_failure`）。
+
+use crate::models::LogLine;
+use chrono::{DateTime, NaiveDateTime};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// 启动节点：(类型, 标题, 匹配规则)，同一次启动中每种节点只取第一次出现
+static STARTUP_MILESTONES: Lazy<Vec<(&'static str, &'static str, Regex)>> = Lazy::new(|| {
+    [
+        ("banner", "Banner", r":: Spring Boot ::"),
+        ("starting", "开始启动", r"\bStarting (\S+) (?:v\S+ )?(?:using Java|on |with PID)"),
+        ("profiles", "激活配置", r"No active profile set|The following (?:\d+ )?profiles? (?:are|is) active"),
+        ("repositories", "仓库扫描完成", r"Finished Spring Data repository scanning in \d+ ?ms"),
+        ("web_server_initialized", "Web服务器初始化", r"\b(?:Tomcat|Jetty|Netty|Undertow) initialized with port"),
+        ("context_initialized", "上下文初始化完成", r"Root WebApplicationContext: initialization completed in \d+ ms"),
+        ("datasource_started", "连接池启动", r"HikariPool-\d+ - Start completed"),
+        ("jpa_initialized", "JPA初始化完成", r"Initialized JPA EntityManagerFactory"),
+        ("web_server_started", "Web服务器启动", r"\b(?:Tomcat|Jetty|Netty|Undertow) started on port"),
+        ("started", "启动完成", r"\bStarted (\S+) in ([\d.]+) seconds"),
+    ]
+    .into_iter()
+    .map(|(kind, title, pattern)| (kind, title, Regex::new(pattern).unwrap()))
+    .collect()
+});
+
+/// 启动节点
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartupMilestone {
+    /// 节点类型（如 `context_initialized`）
+    pub kind: String,
+    /// 显示标题
+    pub title: String,
+    /// 行号
+    pub line_number: usize,
+    /// 时间戳（没有时间戳时为其后第一条带时间戳条目的时间）
+    pub timestamp: Option<String>,
+    /// 距本次启动第一个节点的毫秒数
+    pub elapsed_ms: Option<i64>,
+    /// 从上一个节点到该节点的耗时（毫秒），即该阶段的耗时
+    pub phase_ms: Option<i64>,
+}
+
+/// 一次启动的耗时分解
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartupBreakdown {
+    /// 应用名称（`Starting` / `Started` 行中的类名）
+    pub application: Option<String>,
+    /// 按出现顺序排列的节点
+    pub milestones: Vec<StartupMilestone>,
+    /// 第一个节点到最后一个节点的耗时（毫秒）
+    pub measured_ms: Option<i64>,
+    /// `Started ... in X seconds` 报告的启动耗时（毫秒）
+    pub reported_ms: Option<i64>,
+    /// 耗时最长的阶段（节点类型）
+    pub slowest_phase: Option<String>,
+    /// 是否出现了 `Started ...`（启动失败或日志被截断时为false）
+    pub completed: bool,
+}
+
+/// 把条目时间戳转换为毫秒时间戳
+///
+/// 支持 `2024-01-15 10:30:25.123`、`2024-01-15T10:30:25,123` 和带时区的RFC 3339时间。
+pub(crate) fn timestamp_millis(timestamp: &str) -> Option<i64> {
+    let normalized = timestamp.trim().replace(',', ".");
+    if let Ok(time) = DateTime::parse_from_rfc3339(&normalized) {
+        return Some(time.timestamp_millis());
+    }
+    let normalized = normalized.trim_end_matches('Z').replacen('T', " ", 1);
+    NaiveDateTime::parse_from_str(&normalized, "%Y-%m-%d %H:%M:%S%.f")
+        .ok()
+        .map(|time| time.and_utc().timestamp_millis())
+}
+
+/// 失败报告的结束行：新的日志行（以日期开头）或分隔线
+static BLOCK_END: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(?:\d{4}-\d{2}-\d{2}[T ]|={5,}|\*{5,})").unwrap()
+});
+
+/// 失败分类：(描述特征, 分类)，按顺序匹配
+static FAILURE_KINDS: Lazy<Vec<(Regex, &'static str)>> = Lazy::new(|| {
+    [
+        (r"required a bean of type '[^']+' that could not be found", "missing_bean"),
+        (r"required a single bean, but \d+ were found", "ambiguous_bean"),
+        (r"form a cycle|circular reference", "circular_dependency"),
+        (r"could not be registered\. A bean with that name has already been defined", "bean_override"),
+        (r"Port \d+ was already in use", "port_in_use"),
+        (r"Failed to configure a DataSource", "datasource"),
+        (r"Failed to bind properties", "property_binding"),
+    ]
+    .into_iter()
+    .map(|(pattern, kind)| (Regex::new(pattern).unwrap(), kind))
+    .collect()
+});
+
+/// 缺少的bean类型：`required a bean of type 'com.example.Client' that could not be found`
+static MISSING_BEAN_TYPE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"required a (?:single )?bean(?: of type '([^']+)')?").unwrap()
+});
+
+/// 需要该bean的类：`Parameter 0 of constructor in com.example.Service required ...`
+static REQUIRING_CLASS: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?:Parameter \d+ of (?:constructor|method \S+) in|Field \S+ in) (\S+) required").unwrap()
+});
+
+/// 条件评估报告中的一个自动配置类及其条件说明
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConditionOutcome {
+    /// 自动配置类（或其方法）名称
+    pub name: String,
+    /// 条件说明（`- @ConditionalOnClass did not find required class ...`）
+    pub reasons: Vec<String>,
+}
+
+/// 条件评估报告（`CONDITIONS EVALUATION REPORT`）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConditionReport {
+    /// 条件满足的自动配置
+    pub positive_matches: Vec<ConditionOutcome>,
+    /// 条件不满足的自动配置
+    pub negative_matches: Vec<ConditionOutcome>,
+    /// 被排除的自动配置类
+    pub exclusions: Vec<String>,
+    /// 无条件加载的类
+    pub unconditional_classes: Vec<String>,
+}
+
+/// 启动失败报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartupFailure {
+    /// `APPLICATION FAILED TO START` 所在行号
+    pub line_number: usize,
+    /// 失败分类（如 `missing_bean`、`port_in_use`），无法归类时为 `other`
+    pub kind: String,
+    /// `Description:` 部分
+    pub description: String,
+    /// `Action:` 部分
+    pub action: String,
+    /// 需要该bean的类（缺少或存在多个候选bean时）
+    pub requiring_class: Option<String>,
+    /// 缺少的bean类型
+    pub missing_type: Option<String>,
+    /// 条件评估报告（以debug模式运行时才会输出）
+    pub condition_report: Option<ConditionReport>,
+    /// 日志是否提示以debug模式重新运行以查看条件评估报告
+    pub debug_hint: bool,
+}
+
+/// 读取 `start` 之后的区块内容，直到下一个日志行或分隔线
+fn block_lines<'a, 'b>(lines: &'b [&'a str], start: usize) -> &'b [&'a str] {
+    let end = lines[start..].iter()
+        .position(|line| BLOCK_END.is_match(line.trim_start()))
+        .map_or(lines.len(), |offset| start + offset);
+    &lines[start..end]
+}
+
+/// 拆分 `Description:` 和 `Action:` 部分，各自去掉首尾空行
+fn failure_sections(block: &[&str]) -> (String, String) {
+    let mut description = Vec::new();
+    let mut action = Vec::new();
+    let mut section = "";
+    for line in block {
+        match line.trim() {
+            "Description:" | "Action:" => section = line.trim(),
+            _ if section == "Description:" => description.push(line.trim_end().to_string()),
+            _ if section == "Action:" => action.push(line.trim_end().to_string()),
+            _ => {}
+        }
+    }
+    let join = |lines: Vec<String>| lines.join("\n").trim().to_string();
+    (join(description), join(action))
+}
+
+/// 解析条件评估报告
+fn parse_condition_report(block: &[&str]) -> ConditionReport {
+    let mut report = ConditionReport::default();
+    let mut section = "";
+    for line in block {
+        let trimmed = line.trim();
+        match trimmed {
+            "Positive matches:" | "Negative matches:" | "Exclusions:" | "Unconditional classes:" => {
+                section = trimmed;
+                continue;
+            }
+            "" | "None" => continue,
+            _ if trimmed.chars().all(|c| c == '-') => continue,
+            _ => {}
+        }
+
+        let outcomes = match section {
+            "Positive matches:" => &mut report.positive_matches,
+            "Negative matches:" => &mut report.negative_matches,
+            "Exclusions:" => {
+                report.exclusions.push(trimmed.to_string());
+                continue;
+            }
+            "Unconditional classes:" => {
+                report.unconditional_classes.push(trimmed.to_string());
+                continue;
+            }
+            _ => continue,
+        };
+        if let Some(reason) = trimmed.strip_prefix("- ") {
+            if let Some(outcome) = outcomes.last_mut() {
+                outcome.reasons.push(reason.to_string());
+            }
+        } else if !matches!(trimmed, "Matched:" | "Did not match:") {
+            let name = trimmed.trim_end_matches(':').trim_end_matches(" matched").to_string();
+            outcomes.push(ConditionOutcome { name, reasons: Vec::new() });
+        }
+    }
+    report
+}
+
+/// 查找启动失败报告
+///
+/// 有多次启动失败时返回最后一次；条件评估报告取失败之前最近的一份。
+///
+/// # 参数
+/// - `content`: 日志原始内容（需要保留空行以划分区块）
+///
+/// # Returns
+/// - `Some(StartupFailure)`: 找到 `APPLICATION FAILED TO START` 区块
+/// - `None`: 日志中没有启动失败报告
+pub fn find_startup_failure(content: &str) -> Option<StartupFailure> {
+    let lines: Vec<&str> = content.lines().collect();
+    let failure_index = lines.iter().rposition(|line| line.trim() == "APPLICATION FAILED TO START")?;
+
+    // 跳过标题下方的 `****` 分隔线
+    let body_start = lines[failure_index + 1..].iter()
+        .position(|line| !line.trim().starts_with('*'))
+        .map_or(lines.len(), |offset| failure_index + 1 + offset);
+    let (description, action) = failure_sections(block_lines(&lines, body_start));
+
+    let kind = FAILURE_KINDS.iter()
+        .find(|(pattern, _)| pattern.is_match(&description))
+        .map_or("other", |(_, kind)| kind);
+    let missing_type = MISSING_BEAN_TYPE.captures(&description)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str().to_string());
+    let requiring_class = REQUIRING_CLASS.captures(&description).map(|caps| caps[1].to_string());
+
+    let condition_report = lines[..failure_index].iter()
+        .rposition(|line| line.trim() == "CONDITIONS EVALUATION REPORT")
+        .map(|index| {
+            let start = lines[index + 1..].iter()
+                .position(|line| !line.trim().starts_with('='))
+                .map_or(lines.len(), |offset| index + 1 + offset);
+            parse_condition_report(block_lines(&lines, start))
+        });
+    let debug_hint = lines.iter().any(|line| line.contains("re-run your application with 'debug' enabled"));
+
+    Some(StartupFailure {
+        line_number: failure_index + 1,
+        kind: kind.to_string(),
+        description,
+        action,
+        requiring_class,
+        missing_type,
+        condition_report,
+        debug_hint,
+    })
+}
+
+fn display_text(entry: &LogLine) -> &str {
+    entry.formatted_content.as_deref().unwrap_or(&entry.content)
+}
+
+/// 节点及其计时用的条目下标
+struct PendingMilestone {
+    kind: &'static str,
+    title: &'static str,
+    index: usize,
+}
+
+fn finish_run(entries: &[LogLine], application: Option<String>, pending: Vec<PendingMilestone>, reported_ms: Option<i64>) -> StartupBreakdown {
+    let mut milestones = Vec::with_capacity(pending.len());
+    let mut first_time = None;
+    let mut previous_time: Option<i64> = None;
+    for milestone in pending {
+        // 没有时间戳的节点按其后第一条带时间戳的条目计时
+        let timed = entries[milestone.index..].iter()
+            .find_map(|entry| entry.timestamp.as_deref().and_then(|t| timestamp_millis(t).map(|ms| (t, ms))));
+        let time = timed.map(|(_, ms)| ms);
+        if first_time.is_none() {
+            first_time = time;
+        }
+        milestones.push(StartupMilestone {
+            kind: milestone.kind.to_string(),
+            title: milestone.title.to_string(),
+            line_number: entries[milestone.index].line_number,
+            timestamp: timed.map(|(t, _)| t.to_string()),
+            elapsed_ms: time.zip(first_time).map(|(time, first)| time - first),
+            phase_ms: time.zip(previous_time).map(|(time, previous)| (time - previous).max(0)),
+        });
+        if time.is_some() {
+            previous_time = time;
+        }
+    }
+
+    let slowest_phase = milestones.iter()
+        .filter_map(|m| m.phase_ms.map(|ms| (ms, &m.kind)))
+        .max_by_key(|(ms, _)| *ms)
+        .map(|(_, kind)| kind.clone());
+    StartupBreakdown {
+        application,
+        measured_ms: milestones.iter().filter_map(|m| m.elapsed_ms).max(),
+        completed: milestones.iter().any(|m| m.kind == "started"),
+        milestones,
+        reported_ms,
+        slowest_phase,
+    }
+}
+
+/// 分析启动耗时
+///
+/// # 参数
+/// - `entries`: 解析后的条目（按行号排列）
+///
+/// # Returns
+/// - `Vec<StartupBreakdown>`: 按出现顺序排列的每次启动；没有识别到启动节点时为空
+pub fn analyze_startup(entries: &[LogLine]) -> Vec<StartupBreakdown> {
+    let mut runs = Vec::new();
+    let mut pending: Vec<PendingMilestone> = Vec::new();
+    let mut application = None;
+    let mut reported_ms = None;
+
+    for (index, entry) in entries.iter().enumerate() {
+        let text = display_text(entry);
+        let Some((kind, title, pattern)) = STARTUP_MILESTONES.iter().find(|(_, _, pattern)| pattern.is_match(text)) else {
+            continue;
+        };
+
+        // 再次出现banner / Starting时开始统计下一次启动，启动完成后的其他节点不再计入
+        let restarted = match *kind {
+            "banner" => !pending.is_empty(),
+            "starting" => pending.iter().any(|m| m.kind != "banner"),
+            _ => false,
+        };
+        if restarted {
+            runs.push(finish_run(entries, application.take(), std::mem::take(&mut pending), reported_ms.take()));
+        }
+        if pending.iter().any(|m| m.kind == *kind || m.kind == "started") {
+            continue;
+        }
+
+        if let Some(caps) = pattern.captures(text) {
+            if let Some(name) = caps.get(1) {
+                application.get_or_insert_with(|| name.as_str().to_string());
+            }
+            if let Some(seconds) = caps.get(2).and_then(|s| s.as_str().parse::<f64>().ok()) {
+                reported_ms = Some((seconds * 1000.0).round() as i64);
+            }
+        }
+        pending.push(PendingMilestone { kind, title, index });
+    }
+    if !pending.is_empty() {
+        runs.push(finish_run(entries, application, pending, reported_ms));
+    }
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(line_number: usize, timestamp: Option<&str>, content: &str) -> LogLine {
+        LogLine {
+            level: Some("INFO".to_string()),
+            timestamp: timestamp.map(str::to_string),
+            ..LogLine::new(line_number, content)
+        }
+    }
+
+    #[test]
+    fn test_startup_breakdown_per_run() {
+        let entries = vec![
+            entry(1, None, " :: Spring Boot ::                (v3.2.0)"),
+            entry(2, Some("2024-01-15 10:30:20.000"), "Starting OrderApplication using Java 17.0.9 with PID 4242"),
+            entry(3, Some("2024-01-15 10:30:20.100"), "No active profile set, falling back to 1 default profile: \"default\""),
+            entry(4, Some("2024-01-15 10:30:21.000"), "Tomcat initialized with port 8080 (http)"),
+            entry(5, Some("2024-01-15 10:30:21.300"), "Root WebApplicationContext: initialization completed in 1200 ms"),
+            entry(6, Some("2024-01-15 10:30:24.300"), "Initialized JPA EntityManagerFactory for persistence unit 'default'"),
+            entry(7, Some("2024-01-15 10:30:24,800"), "Tomcat started on port 8080 (http) with context path ''"),
+            entry(8, Some("2024-01-15T10:30:24.900"), "Started OrderApplication in 5.1 seconds (process running for 5.6)"),
+            entry(20, Some("2024-01-15 11:00:00.000"), "Starting OrderApplication using Java 17.0.9 with PID 4343"),
+            entry(21, Some("2024-01-15 11:00:01.000"), "Root WebApplicationContext: initialization completed in 900 ms"),
+        ];
+
+        let runs = analyze_startup(&entries);
+        assert_eq!(runs.len(), 2);
+
+        let first = &runs[0];
+        assert_eq!(first.application.as_deref(), Some("OrderApplication"));
+        assert!(first.completed);
+        assert_eq!((first.measured_ms, first.reported_ms), (Some(4900), Some(5100)));
+        assert_eq!(first.slowest_phase.as_deref(), Some("jpa_initialized"));
+        let phases: Vec<_> = first.milestones.iter().map(|m| (m.kind.as_str(), m.phase_ms)).collect();
+        assert_eq!(phases, vec![
+            ("banner", None),
+            ("starting", Some(0)),
+            ("profiles", Some(100)),
+            ("web_server_initialized", Some(900)),
+            ("context_initialized", Some(300)),
+            ("jpa_initialized", Some(3000)),
+            ("web_server_started", Some(500)),
+            ("started", Some(100)),
+        ]);
+        assert_eq!(first.milestones[0].timestamp.as_deref(), Some("2024-01-15 10:30:20.000"));
+
+        assert!(!runs[1].completed);
+        assert_eq!(runs[1].measured_ms, Some(1000));
+    }
+
+    #[test]
+    fn test_startup_failure_report() {
+        let content = "2024-01-15 10:30:21.000  INFO 4242 --- [main] o.s.b.a.l.ConditionEvaluationReportLogger :
+
+============================
+CONDITIONS EVALUATION REPORT
+============================
+
+
+Positive matches:
+-----------------
+
+   AopAutoConfiguration matched:
+      - @ConditionalOnProperty (spring.aop.auto=true) matched (OnPropertyCondition)
+
+Negative matches:
+-----------------
+
+   ActiveMQAutoConfiguration:
+      Did not match:
+         - @ConditionalOnClass did not find required class 'jakarta.jms.ConnectionFactory' (OnClassCondition)
+
+Exclusions:
+-----------
+
+    None
+
+2024-01-15 10:30:21.500 ERROR 4242 --- [main] o.s.b.d.LoggingFailureAnalysisReporter   :
+
+***************************
+APPLICATION FAILED TO START
+***************************
+
+Description:
+
+Parameter 0 of constructor in com.example.OrderService required a bean of type 'com.example.PaymentClient' that could not be found.
+
+
+Action:
+
+Consider defining a bean of type 'com.example.PaymentClient' in your configuration.
+";
+
+        let failure = find_startup_failure(content).unwrap();
+        assert_eq!(failure.line_number, 29);
+        assert_eq!(failure.kind, "missing_bean");
+        assert_eq!(failure.requiring_class.as_deref(), Some("com.example.OrderService"));
+        assert_eq!(failure.missing_type.as_deref(), Some("com.example.PaymentClient"));
+        assert!(failure.description.starts_with("Parameter 0 of constructor"));
+        assert_eq!(failure.action, "Consider defining a bean of type 'com.example.PaymentClient' in your configuration.");
+
+        let report = failure.condition_report.unwrap();
+        assert_eq!(report.positive_matches[0].name, "AopAutoConfiguration");
+        assert_eq!(report.negative_matches[0].name, "ActiveMQAutoConfiguration");
+        assert_eq!(report.negative_matches[0].reasons.len(), 1);
+        assert!(report.exclusions.is_empty());
+        assert!(find_startup_failure("Started OrderApplication in 3.2 seconds").is_none());
+    }
+}
@@ -0,0 +1,257 @@
+/// AI辅助日志摘要（本地Ollama / OpenAI兼容接口）
+///
+/// 选中的条目先经过脱敏，再按字符数分批：只有一批时直接请求摘要；
+/// 多批时先逐批摘要，再把各批摘要合并成最终结果（map-reduce），避免超出模型的上下文长度。
+
+use crate::config::summarizer::{SummarizerProvider, SummarizerSettings};
+use crate::i18n::Message;
+use crate::i18n::{current_locale, Locale};
+use crate::models::LogLine;
+use crate::utils::redact::redact_entry;
+use crate::utils::string_utils::truncate_chars;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::time::Duration;
+
+/// 单条日志在提示词中的最大字符数（堆栈等超长条目被截断）
+const MAX_ENTRY_CHARS: usize = 2_000;
+
+/// 系统提示词
+const SYSTEM_PROMPT: &str = "You are an experienced site reliability engineer. \
+You read application log excerpts and explain what happened: the main events, errors and their likely root cause, \
+and concrete next steps. Refer to log lines by their #line numbers. Be concise and do not invent details that are not in the logs.";
+
+/// 摘要结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntrySummary {
+    /// 摘要文本（Markdown）
+    pub summary: String,
+    /// 使用的模型
+    pub model: String,
+    /// 发送的条目数
+    pub entries: usize,
+    /// 分批数
+    pub batches: usize,
+    /// 是否因超过最大条目数而省略了部分条目
+    pub truncated: bool,
+}
+
+/// 回答使用的语言
+fn answer_language() -> &'static str {
+    match current_locale() {
+        Locale::EnUs => "English",
+        _ => "Simplified Chinese",
+    }
+}
+
+/// 把一条日志渲染为提示词中的一行
+fn render_entry(entry: &LogLine) -> String {
+    let mut line = format!("#{}", entry.line_number);
+    if let Some(timestamp) = &entry.timestamp {
+        line.push(' ');
+        line.push_str(timestamp);
+    }
+    if let Some(level) = &entry.level {
+        line.push_str(&format!(" [{}]", level));
+    }
+    line.push(' ');
+    line.push_str(&truncate_chars(entry.content.trim_end(), MAX_ENTRY_CHARS));
+    line
+}
+
+/// 脱敏并按字符数分批
+///
+/// # 参数
+/// - `entries`: 选中的条目
+/// - `batch_chars`: 每批的最大字符数（单条超长时独占一批）
+///
+/// # Returns
+/// - `Vec<String>`: 每批的日志文本
+pub fn batch_entries(entries: &[LogLine], batch_chars: usize) -> Vec<String> {
+    let mut batches = Vec::new();
+    let mut current = String::new();
+    for entry in entries {
+        let line = render_entry(&redact_entry(entry));
+        if !current.is_empty() && current.chars().count() + line.chars().count() + 1 > batch_chars {
+            batches.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push('\n');
+        }
+        current.push_str(&line);
+    }
+    if !current.is_empty() {
+        batches.push(current);
+    }
+    batches
+}
+
+/// 单批的提示词
+fn batch_prompt(logs: &str, question: Option<&str>, part: Option<(usize, usize)>) -> String {
+    let mut prompt = match part {
+        Some((index, total)) => format!("This is part {} of {} of a log excerpt. Summarize this part.\n", index, total),
+        None => "Summarize the following log excerpt.\n".to_string(),
+    };
+    if let Some(question) = question {
+        prompt.push_str(&format!("Focus on this question: {}\n", question));
+    }
+    prompt.push_str(&format!("Respond in {}.\n\n```\n{}\n```", answer_language(), logs));
+    prompt
+}
+
+/// 合并各批摘要的提示词
+fn merge_prompt(partials: &[String], question: Option<&str>) -> String {
+    let mut prompt = "The following are summaries of consecutive parts of one log excerpt. \
+Combine them into a single summary with the overall timeline, the errors and their likely root cause, and next steps.\n"
+        .to_string();
+    if let Some(question) = question {
+        prompt.push_str(&format!("Focus on this question: {}\n", question));
+    }
+    prompt.push_str(&format!("Respond in {}.\n", answer_language()));
+    for (i, partial) in partials.iter().enumerate() {
+        prompt.push_str(&format!("\n## Part {}\n{}\n", i + 1, partial.trim()));
+    }
+    prompt
+}
+
+/// 对话请求的地址和JSON负载
+pub fn chat_request(settings: &SummarizerSettings, prompt: &str) -> (String, Value) {
+    let messages = json!([
+        { "role": "system", "content": SYSTEM_PROMPT },
+        { "role": "user", "content": prompt },
+    ]);
+    match settings.provider {
+        SummarizerProvider::Ollama => (
+            format!("{}/api/chat", settings.base_url()),
+            json!({ "model": settings.model, "messages": messages, "stream": false, "options": { "temperature": 0.2 } }),
+        ),
+        SummarizerProvider::Openai => (
+            format!("{}/chat/completions", settings.base_url()),
+            json!({ "model": settings.model, "messages": messages, "temperature": 0.2 }),
+        ),
+    }
+}
+
+/// 从响应中取出回答文本
+pub fn response_text(provider: SummarizerProvider, response: &Value) -> Result<String, String> {
+    let content = match provider {
+        SummarizerProvider::Ollama => &response["message"]["content"],
+        SummarizerProvider::Openai => &response["choices"][0]["message"]["content"],
+    };
+    content.as_str()
+        .map(|text| text.trim().to_string())
+        .filter(|text| !text.is_empty())
+        .ok_or_else(|| match response["error"].as_str().or(response["error"]["message"].as_str()) {
+            Some(error) => Message::new("summary.model_error").with("error", error).text(),
+            None => Message::new("summary.unrecognized_response").text(),
+        })
+}
+
+/// 发送一次对话请求（阻塞调用）
+fn chat(agent: &ureq::Agent, settings: &SummarizerSettings, api_key: Option<&str>, prompt: &str) -> Result<String, String> {
+    let (url, payload) = chat_request(settings, prompt);
+    let mut request = agent.post(&url)
+        .set("Accept", "application/json")
+        .set("Content-Type", "application/json");
+    if let Some(key) = api_key {
+        request = request.set("Authorization", &format!("Bearer {}", key));
+    }
+    let body = match request.send_string(&payload.to_string()) {
+        Ok(response) => response.into_string().map_err(|e| Message::new("summary.read_response_failed").with("error", e).text())?,
+        Err(ureq::Error::Status(code, response)) => {
+            let detail = response.into_string().unwrap_or_default();
+            return Err(Message::new("summary.http_error").with("status", code).with("detail", truncate_chars(detail.trim(), 300)).text());
+        }
+        Err(e) => return Err(Message::new("summary.connect_failed").with("url", settings.base_url()).with("error", e).text()),
+    };
+    let response: Value = serde_json::from_str(&body).map_err(|e| Message::new("summary.invalid_json").with("error", e).text())?;
+    response_text(settings.provider, &response)
+}
+
+/// 摘要选中的条目（阻塞调用）
+///
+/// # 参数
+/// - `settings`: 摘要设置
+/// - `api_key`: API密钥（本地服务可为None）
+/// - `entries`: 选中的条目（未脱敏，发送前统一脱敏）
+/// - `question`: 用户的问题（可选，如“为什么支付失败”）
+///
+/// # Returns
+/// - `Ok(EntrySummary)`: 摘要文本和分批信息
+/// - `Err(String)`: 没有条目、网络错误或模型返回错误时的错误信息
+pub fn summarize(
+    settings: &SummarizerSettings,
+    api_key: Option<&str>,
+    entries: &[LogLine],
+    question: Option<&str>,
+) -> Result<EntrySummary, String> {
+    let truncated = entries.len() > settings.max_entries;
+    let entries = &entries[..entries.len().min(settings.max_entries)];
+    let batches = batch_entries(entries, settings.batch_chars);
+    if batches.is_empty() {
+        return Err(Message::new("summary.no_entries").text());
+    }
+    let question = question.map(str::trim).filter(|question| !question.is_empty());
+    let agent = ureq::AgentBuilder::new()
+        .timeout_connect(Duration::from_secs(15))
+        .timeout_read(Duration::from_secs(settings.timeout_secs))
+        .user_agent(concat!("LogWhisper/", env!("CARGO_PKG_VERSION")))
+        .build();
+
+    let summary = if batches.len() == 1 {
+        chat(&agent, settings, api_key, &batch_prompt(&batches[0], question, None))?
+    } else {
+        let partials = batches.iter().enumerate()
+            .map(|(i, logs)| chat(&agent, settings, api_key, &batch_prompt(logs, question, Some((i + 1, batches.len())))))
+            .collect::<Result<Vec<_>, _>>()?;
+        chat(&agent, settings, api_key, &merge_prompt(&partials, question))?
+    };
+    Ok(EntrySummary {
+        summary,
+        model: settings.model.clone(),
+        entries: entries.len(),
+        batches: batches.len(),
+        truncated,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batches_are_redacted_and_bounded() {
+        let entries: Vec<LogLine> = (1..=30)
+            .map(|n| LogLine {
+                level: Some("ERROR".to_string()),
+                ..LogLine::new(n, format!("payment failed for ops@example.com password=hunter2 attempt {} {}", n, "x".repeat(40)))
+            })
+            .collect();
+        let batches = batch_entries(&entries, 1000);
+        assert!(batches.len() > 1);
+        assert!(batches.iter().all(|batch| batch.chars().count() <= 1000));
+        assert_eq!(batches.iter().map(|batch| batch.lines().count()).sum::<usize>(), 30);
+        assert!(batches[0].starts_with("#1 [ERROR] payment failed for [EMAIL] password=[REDACTED]"));
+        assert!(!batches.concat().contains("hunter2"));
+    }
+
+    #[test]
+    fn test_chat_requests_and_responses() {
+        let mut settings = SummarizerSettings::default();
+        let (url, payload) = chat_request(&settings, "hi");
+        assert_eq!(url, "http://localhost:11434/api/chat");
+        assert_eq!(payload["stream"], false);
+        assert_eq!(response_text(settings.provider, &json!({"message": {"content": " ok "}})).unwrap(), "ok");
+
+        settings.provider = SummarizerProvider::Openai;
+        settings.endpoint = Some("http://localhost:1234/v1/".to_string());
+        let (url, payload) = chat_request(&settings, "hi");
+        assert_eq!(url, "http://localhost:1234/v1/chat/completions");
+        assert_eq!(payload["messages"][1]["content"], "hi");
+        assert_eq!(response_text(settings.provider, &json!({"choices": [{"message": {"content": "done"}}]})).unwrap(), "done");
+        assert_eq!(
+            response_text(settings.provider, &json!({"error": {"message": "model not found"}})).unwrap_err(),
+            "模型返回错误: model not found"
+        );
+    }
+}
@@ -0,0 +1,215 @@
+/// 表格视图数据
+///
+/// 按指定的列（内置字段或元数据键）把解析结果转换为按列顺序排列的行数组，
+/// 并给出每列的类型提示，表格视图不需要在JS中逐条整理元数据。
+///
+/// # 内置列
+/// - `line_number`: 行号
+/// - `timestamp`: 时间戳
+/// - `level`: 日志级别
+/// - `message`: 显示内容（优先格式化内容）
+/// - `content`: 原始内容
+///
+/// 其他列名按同名元数据读取（如 `logger`、`thread`、`pod`）。
+
+use crate::i18n::Message;
+use crate::models::LogLine;
+use crate::search::EntryMatcher;
+use serde::{Deserialize, Serialize};
+
+/// 每页默认行数
+pub const DEFAULT_PAGE_SIZE: usize = 500;
+
+/// 列的类型提示
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColumnType {
+    /// 整数（行号、状态码等）
+    Integer,
+    /// 数值（耗时等，可能带小数）
+    Number,
+    /// 时间戳
+    Timestamp,
+    /// 日志级别
+    Level,
+    /// 文本
+    Text,
+}
+
+/// 表格列
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableColumn {
+    /// 列名（内置字段或元数据键）
+    pub key: String,
+    /// 类型提示，元数据列按筛选后所有条目的取值推断，翻页时保持不变
+    pub column_type: ColumnType,
+}
+
+/// 表格行
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableRow {
+    /// 条目行号，用于跳转和选中
+    pub line_number: usize,
+    /// 按列顺序排列的单元格（条目没有该字段时为None）
+    pub cells: Vec<Option<String>>,
+}
+
+/// 表格分页
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TablePage {
+    /// 列定义（与请求的列顺序相同，重复的列只保留第一次出现）
+    pub columns: Vec<TableColumn>,
+    /// 当前页的行
+    pub rows: Vec<TableRow>,
+    /// 满足条件的行总数
+    pub total_rows: usize,
+    /// 当前页第一行的序号（从0开始）
+    pub offset: usize,
+    /// 是否还有后续行
+    pub has_more: bool,
+}
+
+/// 读取条目在某列的值
+fn cell(entry: &LogLine, key: &str) -> Option<String> {
+    match key {
+        "line_number" => Some(entry.line_number.to_string()),
+        "timestamp" => entry.timestamp.clone(),
+        "level" => entry.level.clone(),
+        "message" => Some(entry.formatted_content.clone().unwrap_or_else(|| entry.message_text().to_string())),
+        "content" => Some(entry.content.clone()),
+        _ => entry.metadata.get(key).cloned(),
+    }
+}
+
+/// 推断列的类型：内置列固定，元数据列的非空取值全部为整数或数值时给出对应类型
+fn column_type(entries: &[&LogLine], key: &str) -> ColumnType {
+    match key {
+        "line_number" => return ColumnType::Integer,
+        "timestamp" => return ColumnType::Timestamp,
+        "level" => return ColumnType::Level,
+        "message" | "content" => return ColumnType::Text,
+        _ => {}
+    }
+
+    let mut values = entries.iter()
+        .filter_map(|entry| entry.metadata.get(key))
+        .map(|value| value.trim())
+        .filter(|value| !value.is_empty())
+        .peekable();
+    if values.peek().is_none() {
+        return ColumnType::Text;
+    }
+    let mut column_type = ColumnType::Integer;
+    for value in values {
+        if value.parse::<i64>().is_ok() {
+            continue;
+        }
+        if value.parse::<f64>().is_ok_and(f64::is_finite) {
+            column_type = ColumnType::Number;
+        } else {
+            return ColumnType::Text;
+        }
+    }
+    column_type
+}
+
+/// 生成表格分页
+///
+/// # 参数
+/// - `entries`: 解析后的条目
+/// - `columns`: 列名（内置字段或元数据键）
+/// - `matcher`: 筛选条件（None表示不筛选）
+/// - `offset`: 起始行序号（从0开始）
+/// - `limit`: 每页最多行数
+///
+/// # Returns
+/// - `Ok(TablePage)`: 当前页的行和列定义
+/// - `Err(String)`: 没有指定任何列时的错误信息
+pub fn table_rows(
+    entries: &[LogLine],
+    columns: &[String],
+    matcher: Option<&EntryMatcher>,
+    offset: usize,
+    limit: usize,
+) -> Result<TablePage, String> {
+    let mut keys: Vec<&str> = Vec::new();
+    for key in columns.iter().map(|key| key.trim()).filter(|key| !key.is_empty()) {
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+    if keys.is_empty() {
+        return Err(Message::new("table.no_columns").text());
+    }
+
+    let matched: Vec<&LogLine> = entries.iter()
+        .filter(|entry| matcher.is_none_or(|m| m.matches(entry)))
+        .collect();
+    let columns = keys.iter()
+        .map(|key| TableColumn { key: key.to_string(), column_type: column_type(&matched, key) })
+        .collect();
+    let rows = matched.iter()
+        .skip(offset)
+        .take(limit)
+        .map(|entry| TableRow {
+            line_number: entry.line_number,
+            cells: keys.iter().map(|key| cell(entry, key)).collect(),
+        })
+        .collect();
+
+    Ok(TablePage {
+        columns,
+        rows,
+        total_rows: matched.len(),
+        offset,
+        has_more: offset.saturating_add(limit) < matched.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::EntryFilter;
+
+    fn entry(line_number: usize, level: &str, metadata: &[(&str, &str)]) -> LogLine {
+        LogLine {
+            level: Some(level.to_string()),
+            timestamp: Some(format!("2024-01-15 10:30:{:02}", line_number)),
+            formatted_content: Some(format!("line {}", line_number)),
+            metadata: metadata.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            ..LogLine::new(line_number, format!("raw {}", line_number))
+        }
+    }
+
+    #[test]
+    fn test_table_rows_with_type_hints_and_paging() {
+        let entries = vec![
+            entry(1, "INFO", &[("logger", "c.e.Api"), ("status", "200"), ("duration_ms", "12")]),
+            entry(2, "ERROR", &[("logger", "c.e.Db"), ("status", "500"), ("duration_ms", "3.5")]),
+            entry(3, "ERROR", &[("status", "503")]),
+            entry(4, "INFO", &[("logger", "c.e.Api")]),
+        ];
+        let columns: Vec<String> = ["timestamp", "level", "logger", "status", "duration_ms", "level", "message"]
+            .iter().map(|c| c.to_string()).collect();
+
+        let page = table_rows(&entries, &columns, None, 1, 2).unwrap();
+        let types: Vec<_> = page.columns.iter().map(|c| (c.key.as_str(), c.column_type)).collect();
+        assert_eq!(types, vec![
+            ("timestamp", ColumnType::Timestamp),
+            ("level", ColumnType::Level),
+            ("logger", ColumnType::Text),
+            ("status", ColumnType::Integer),
+            ("duration_ms", ColumnType::Number),
+            ("message", ColumnType::Text),
+        ]);
+        assert_eq!((page.total_rows, page.offset, page.has_more), (4, 1, true));
+        assert_eq!(page.rows[0].line_number, 2);
+        assert_eq!(page.rows[1].cells[2], None);
+        assert_eq!(page.rows[1].cells[5].as_deref(), Some("line 3"));
+
+        let matcher = EntryFilter { levels: vec!["ERROR".to_string()], ..Default::default() }.compile().unwrap();
+        let page = table_rows(&entries, &columns, Some(&matcher), 0, DEFAULT_PAGE_SIZE).unwrap();
+        assert_eq!((page.total_rows, page.has_more), (2, false));
+        assert!(table_rows(&entries, &[" ".to_string()], None, 0, 10).is_err());
+    }
+}
@@ -0,0 +1,347 @@
+/// 日志模板挖掘（Drain风格）
+///
+/// 把消息归纳为模板（可变部分替换为 `<*>`），返回每个模板的条目数、级别分布和参数示例，
+/// 比简单去重更能看出日志里"有哪几类消息"。
+///
+/// 算法按Drain的思路：消息取第一行按空白分词，含数字的词先替换为 `<*>`；
+/// 按（词数, 第一个词）分桶，在桶内找相似度（位置相同且相等的非通配词占比）最高的模板，
+/// 达到阈值时合并（不同的位置改为 `<*>`），否则新建模板。
+
+use crate::models::LogLine;
+use crate::plugins::formatter::UnifiedFormatter;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// 模板中的通配符
+pub const WILDCARD: &str = "<*>";
+
+/// 默认相似度阈值
+pub const DEFAULT_SIMILARITY: f64 = 0.5;
+
+/// 每个参数位置最多保留的不同示例数
+pub const MAX_PARAMETER_EXAMPLES: usize = 3;
+
+/// 每个模板保留的示例行号数
+const MAX_SAMPLE_LINES: usize = 3;
+
+/// 两次运行对比时，默认频率变化倍数达到该值才算"剧烈变化"
+pub const DEFAULT_CHANGE_FACTOR: f64 = 3.0;
+
+/// 频率变化的模板在两次运行中至少有一次出现的次数（避免少量条目的偶然波动）
+const MIN_CHANGED_COUNT: usize = 5;
+
+/// 一个消息模板
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogTemplate {
+    /// 模板文本（可变部分为 `<*>`）
+    pub template: String,
+    /// 条目数
+    pub count: usize,
+    /// 第一条条目的行号
+    pub first_line: usize,
+    /// 最后一条条目的行号
+    pub last_line: usize,
+    /// 级别分布（级别 -> 条目数）
+    pub levels: BTreeMap<String, usize>,
+    /// 每个 `<*>` 位置的参数示例（按模板中出现的顺序）
+    pub parameters: Vec<Vec<String>>,
+    /// 示例行号
+    pub sample_lines: Vec<usize>,
+}
+
+/// 模板挖掘结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateSummary {
+    /// 条目总数
+    pub total_entries: usize,
+    /// 模板（按条目数降序，相同时按首次出现的行号）
+    pub templates: Vec<LogTemplate>,
+}
+
+/// 两次运行中同一模板的对比
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateDiff {
+    /// 模板文本
+    pub template: String,
+    /// 在运行A中的条目数
+    pub count_a: usize,
+    /// 在运行B中的条目数
+    pub count_b: usize,
+    /// 在运行A中每万条的出现次数
+    pub rate_a: f64,
+    /// 在运行B中每万条的出现次数
+    pub rate_b: f64,
+    /// 示例消息（优先取运行B中的）
+    pub sample: String,
+}
+
+/// 两次运行的模板对比
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunComparison {
+    /// 运行A的条目数
+    pub entries_a: usize,
+    /// 运行B的条目数
+    pub entries_b: usize,
+    /// 只在运行B中出现的模板（按运行B中的条目数降序）
+    pub new_templates: Vec<TemplateDiff>,
+    /// 只在运行A中出现、运行B中消失的模板（按运行A中的条目数降序）
+    pub disappeared_templates: Vec<TemplateDiff>,
+    /// 两次都出现但频率变化超过倍数的模板（按变化倍数降序）
+    pub changed_templates: Vec<TemplateDiff>,
+}
+
+/// 消息的第一行按空白分词
+fn tokenize(text: &str) -> Vec<&str> {
+    text.lines().next().unwrap_or("").split_whitespace().collect()
+}
+
+/// 含数字的词视为参数
+fn mask(token: &str) -> &str {
+    if token.bytes().any(|b| b.is_ascii_digit()) { WILDCARD } else { token }
+}
+
+/// 模板与消息的相似度：位置相同且相等的非通配词占比
+fn similarity(template: &[String], tokens: &[&str]) -> f64 {
+    if template.is_empty() {
+        return 1.0;
+    }
+    let equal = template.iter().zip(tokens)
+        .filter(|(t, token)| t.as_str() != WILDCARD && t.as_str() == mask(token))
+        .count();
+    equal as f64 / template.len() as f64
+}
+
+/// Drain风格的模板挖掘器，可逐条添加消息
+pub struct TemplateMiner {
+    similarity: f64,
+    templates: Vec<Vec<String>>,
+    buckets: HashMap<(usize, String), Vec<usize>>,
+}
+
+impl TemplateMiner {
+    /// 创建挖掘器
+    ///
+    /// # 参数
+    /// - `similarity`: 合并到已有模板的相似度阈值（0-1，越大模板越细）
+    pub fn new(similarity: f64) -> Self {
+        Self {
+            similarity: similarity.clamp(0.0, 1.0),
+            templates: Vec::new(),
+            buckets: HashMap::new(),
+        }
+    }
+
+    /// 添加一条消息，返回其所属模板的ID
+    pub fn add(&mut self, text: &str) -> usize {
+        let tokens = tokenize(text);
+        let key = (tokens.len(), tokens.first().map_or(String::new(), |token| mask(token).to_string()));
+        let bucket = self.buckets.entry(key).or_default();
+
+        let best = bucket.iter()
+            .map(|&id| (id, similarity(&self.templates[id], &tokens)))
+            .filter(|&(_, score)| score >= self.similarity)
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        match best {
+            Some((id, _)) => {
+                for (t, token) in self.templates[id].iter_mut().zip(&tokens) {
+                    if t != WILDCARD && t != mask(token) {
+                        *t = WILDCARD.to_string();
+                    }
+                }
+                id
+            }
+            None => {
+                let id = self.templates.len();
+                self.templates.push(tokens.iter().map(|token| mask(token).to_string()).collect());
+                bucket.push(id);
+                id
+            }
+        }
+    }
+
+    /// 模板的词
+    pub fn template(&self, id: usize) -> &[String] {
+        &self.templates[id]
+    }
+
+    /// 模板数
+    pub fn template_count(&self) -> usize {
+        self.templates.len()
+    }
+}
+
+/// 挖掘条目的消息模板
+///
+/// # 参数
+/// - `entries`: 解析后的条目（按行号排列）
+/// - `similarity`: 相似度阈值（0-1）
+///
+/// # Returns
+/// - `TemplateSummary`: 按条目数降序排列的模板
+pub fn mine_templates(entries: &[LogLine], similarity: f64) -> TemplateSummary {
+    let mut miner = TemplateMiner::new(similarity);
+    let ids: Vec<usize> = entries.iter().map(|entry| miner.add(entry.message_text())).collect();
+
+    // 模板在合并过程中会继续变化，全部添加后再统计参数
+    let mut templates: Vec<Option<LogTemplate>> = vec![None; miner.template_count()];
+    for (entry, &id) in entries.iter().zip(&ids) {
+        let words = miner.template(id);
+        let template = templates[id].get_or_insert_with(|| LogTemplate {
+            template: words.join(" "),
+            count: 0,
+            first_line: entry.line_number,
+            last_line: entry.line_number,
+            levels: BTreeMap::new(),
+            parameters: vec![Vec::new(); words.iter().filter(|w| w.as_str() == WILDCARD).count()],
+            sample_lines: Vec::new(),
+        });
+        template.count += 1;
+        template.last_line = entry.line_number;
+        if let Some(level) = UnifiedFormatter::normalize_level(entry.level.clone()) {
+            *template.levels.entry(level).or_default() += 1;
+        }
+        if template.sample_lines.len() < MAX_SAMPLE_LINES {
+            template.sample_lines.push(entry.line_number);
+        }
+
+        let values = words.iter().zip(tokenize(entry.message_text()))
+            .filter(|(word, _)| word.as_str() == WILDCARD)
+            .map(|(_, token)| token);
+        for (examples, value) in template.parameters.iter_mut().zip(values) {
+            if examples.len() < MAX_PARAMETER_EXAMPLES && !examples.iter().any(|e| e == value) {
+                examples.push(value.to_string());
+            }
+        }
+    }
+
+    let mut templates: Vec<LogTemplate> = templates.into_iter().flatten().collect();
+    templates.sort_by(|a, b| b.count.cmp(&a.count).then(a.first_line.cmp(&b.first_line)));
+    TemplateSummary { total_entries: entries.len(), templates }
+}
+
+/// 对比两次运行（如正常运行A和异常运行B）的消息模板
+///
+/// 两次运行的消息用同一个挖掘器归纳，保证模板一致；频率按每万条计算，消除两次运行条目数不同的影响。
+///
+/// # 参数
+/// - `run_a`: 运行A的条目（通常为正常的基线）
+/// - `run_b`: 运行B的条目（通常为异常的运行）
+/// - `similarity`: 相似度阈值（0-1）
+/// - `change_factor`: 频率变化倍数阈值（大于1）
+///
+/// # Returns
+/// - `RunComparison`: 新出现、消失和频率剧烈变化的模板
+pub fn compare_runs(run_a: &[LogLine], run_b: &[LogLine], similarity: f64, change_factor: f64) -> RunComparison {
+    let mut miner = TemplateMiner::new(similarity);
+    let mut counts: Vec<(usize, usize)> = Vec::new();
+    let mut samples: Vec<(Option<&str>, Option<&str>)> = Vec::new();
+    for (run, entries) in [(0, run_a), (1, run_b)] {
+        for entry in entries {
+            let id = miner.add(entry.message_text());
+            if id == counts.len() {
+                counts.push((0, 0));
+                samples.push((None, None));
+            }
+            let text = entry.message_text().lines().next().unwrap_or("");
+            if run == 0 {
+                counts[id].0 += 1;
+                samples[id].0.get_or_insert(text);
+            } else {
+                counts[id].1 += 1;
+                samples[id].1.get_or_insert(text);
+            }
+        }
+    }
+
+    let rate = |count: usize, total: usize| if total == 0 { 0.0 } else { count as f64 * 10_000.0 / total as f64 };
+    let change_factor = change_factor.max(1.0);
+    let mut comparison = RunComparison {
+        entries_a: run_a.len(),
+        entries_b: run_b.len(),
+        new_templates: Vec::new(),
+        disappeared_templates: Vec::new(),
+        changed_templates: Vec::new(),
+    };
+    for (id, &(count_a, count_b)) in counts.iter().enumerate() {
+        let diff = TemplateDiff {
+            template: miner.template(id).join(" "),
+            count_a,
+            count_b,
+            rate_a: rate(count_a, run_a.len()),
+            rate_b: rate(count_b, run_b.len()),
+            sample: samples[id].1.or(samples[id].0).unwrap_or("").to_string(),
+        };
+        if count_a == 0 {
+            comparison.new_templates.push(diff);
+        } else if count_b == 0 {
+            comparison.disappeared_templates.push(diff);
+        } else if count_a.max(count_b) >= MIN_CHANGED_COUNT {
+            let ratio = diff.rate_b / diff.rate_a;
+            if ratio >= change_factor || ratio <= 1.0 / change_factor {
+                comparison.changed_templates.push(diff);
+            }
+        }
+    }
+
+    comparison.new_templates.sort_by_key(|diff| std::cmp::Reverse(diff.count_b));
+    comparison.disappeared_templates.sort_by_key(|diff| std::cmp::Reverse(diff.count_a));
+    let change = |diff: &TemplateDiff| (diff.rate_b / diff.rate_a).ln().abs();
+    comparison.changed_templates.sort_by(|a, b| change(b).total_cmp(&change(a)));
+    comparison
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mine_templates_with_parameters() {
+        let entries = vec![
+            LogLine::new(1, "User alice logged in"),
+            LogLine::new(2, "Connection to 10.0.0.1 failed after 3 retries"),
+            LogLine::new(3, "User bob logged in"),
+            LogLine::new(4, "Connection to 10.0.0.2 failed after 5 retries"),
+            LogLine::new(5, "User carol logged in"),
+            LogLine::new(6, "Started OrderApplication in 4.2 seconds"),
+        ];
+        let summary = mine_templates(&entries, DEFAULT_SIMILARITY);
+        let templates: Vec<(&str, usize)> = summary.templates.iter().map(|t| (t.template.as_str(), t.count)).collect();
+        assert_eq!(templates, vec![
+            ("User <*> logged in", 3),
+            ("Connection to <*> failed after <*> retries", 2),
+            ("Started OrderApplication in <*> seconds", 1),
+        ]);
+
+        assert_eq!(summary.templates[0].parameters, vec![vec!["alice", "bob", "carol"]]);
+        assert_eq!(summary.templates[1].parameters, vec![vec!["10.0.0.1", "10.0.0.2"], vec!["3", "5"]]);
+        assert_eq!((summary.templates[1].first_line, summary.templates[1].last_line), (2, 4));
+
+        // 提高阈值后只差一个词的消息也不再合并
+        assert_eq!(mine_templates(&entries, 0.8).templates.len(), 6);
+    }
+
+    #[test]
+    fn test_compare_runs() {
+        let mut good = Vec::new();
+        let mut bad = Vec::new();
+        for i in 0..20 {
+            good.push(LogLine::new(i, format!("Handled request {} in {} ms", i, 10 + i)));
+            bad.push(LogLine::new(i, format!("Handled request {} in {} ms", i, 900 + i)));
+        }
+        good.push(LogLine::new(20, "Cache warmed up"));
+        for i in 0..3 {
+            good.push(LogLine::new(21 + i, format!("Retrying payment {}", i)));
+        }
+        for i in 0..20 {
+            bad.push(LogLine::new(20 + i, format!("Retrying payment {}", i)));
+        }
+        bad.push(LogLine::new(40, "Circuit breaker OPEN for inventory"));
+
+        let comparison = compare_runs(&good, &bad, DEFAULT_SIMILARITY, DEFAULT_CHANGE_FACTOR);
+        let templates = |diffs: &[TemplateDiff]| diffs.iter().map(|d| d.template.clone()).collect::<Vec<_>>();
+        assert_eq!(templates(&comparison.new_templates), vec!["Circuit breaker OPEN for inventory"]);
+        assert_eq!(templates(&comparison.disappeared_templates), vec!["Cache warmed up"]);
+        assert_eq!(templates(&comparison.changed_templates), vec!["Retrying payment <*>"]);
+        assert_eq!((comparison.changed_templates[0].count_a, comparison.changed_templates[0].count_b), (3, 20));
+    }
+}
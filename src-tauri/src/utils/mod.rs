@@ -0,0 +1,6 @@
+/// 通用工具
+///
+/// 与具体功能无关、被多个模块共用的小工具函数。
+
+pub mod string_utils;  // UTF-8安全的字符串截断
+pub mod redact;  // 发送到外部系统前的敏感信息脱敏
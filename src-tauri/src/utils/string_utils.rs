@@ -0,0 +1,69 @@
+/// UTF-8安全的字符串截断
+///
+/// 日志中经常包含中文等多字节字符，按字节下标切片（如 `&text[..100]`）
+/// 在下标落到字符中间时会panic。这里的函数都按字符计数，结果总在字符边界上。
+
+/// 省略标记（格式化显示用）
+pub const ELLIPSIS: &str = "...";
+
+/// 取开头最多 `max_chars` 个字符
+pub fn prefix_chars(text: &str, max_chars: usize) -> &str {
+    match text.char_indices().nth(max_chars) {
+        Some((byte_index, _)) => &text[..byte_index],
+        None => text,
+    }
+}
+
+/// 取末尾最多 `max_chars` 个字符
+pub fn suffix_chars(text: &str, max_chars: usize) -> &str {
+    if max_chars == 0 {
+        return "";
+    }
+    match text.char_indices().nth_back(max_chars - 1) {
+        Some((byte_index, _)) => &text[byte_index..],
+        None => text,
+    }
+}
+
+/// 按字符数截断字符串并追加省略号 `…`
+///
+/// 保留开头 `max_chars` 个字符，未超出时原样返回。
+pub fn truncate_chars(text: &str, max_chars: usize) -> String {
+    let prefix = prefix_chars(text, max_chars);
+    if prefix.len() == text.len() {
+        text.to_string()
+    } else {
+        format!("{}…", prefix)
+    }
+}
+
+/// 把字符串收起到最多 `max_chars` 个字符（含末尾的 `...`）
+///
+/// 用于格式化显示中的缩略（如 `ellipsize("ExampleLogger", 8)` 得到 `"Examp..."`），
+/// 未超出时原样返回。
+pub fn ellipsize(text: &str, max_chars: usize) -> String {
+    if text.chars().nth(max_chars).is_none() {
+        return text.to_string();
+    }
+    let keep = max_chars.saturating_sub(ELLIPSIS.len());
+    format!("{}{}", prefix_chars(text, keep), ELLIPSIS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multibyte_text_is_cut_on_char_boundaries() {
+        let text = "数据库连接超时，重试中";
+        assert_eq!(prefix_chars(text, 3), "数据库");
+        assert_eq!(suffix_chars(text, 3), "重试中");
+        assert_eq!(suffix_chars(text, 0), "");
+        assert_eq!(suffix_chars("ab", 5), "ab");
+        assert_eq!(truncate_chars(text, 5), "数据库连接…");
+        assert_eq!(truncate_chars("short", 10), "short");
+        assert_eq!(ellipsize(text, 8), "数据库连接...");
+        assert_eq!(ellipsize("ExampleLogger", 8), "Examp...");
+        assert_eq!(ellipsize("Logger", 8), "Logger");
+    }
+}
@@ -0,0 +1,183 @@
+/// 窗口状态持久化
+///
+/// 窗口移动、缩放时记录当前的位置、大小和最大化状态（逻辑像素），停止变化 `SAVE_DEBOUNCE` 后才写入
+/// `WindowConfig`，拖动过程中的大量事件只触发一次保存。最大化时只记录最大化标记，保留还原后的位置和大小。
+/// 启动时按保存的状态恢复窗口：大小限制在最小尺寸和所在显示器之间，
+/// 保存的位置不在任何当前显示器上（显示器已断开或布局变化）时改为居中。
+
+use crate::config::WindowConfig;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// 窗口停止移动、缩放多久后保存
+pub const SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// 标题栏至少有这么宽的部分落在某个显示器内，才认为保存的位置可用（逻辑像素）
+const MIN_VISIBLE_WIDTH: i64 = 100;
+
+/// 标题栏高度，这部分需要完整落在显示器内（逻辑像素）
+const TITLE_BAR_HEIGHT: i64 = 40;
+
+/// 显示器区域（逻辑像素）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MonitorArea {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl MonitorArea {
+    /// 窗口标题栏是否足够多地落在该显示器内（可以拖动）
+    ///
+    /// # 参数
+    /// - `x`, `y`: 窗口左上角位置
+    /// - `width`: 窗口宽度
+    fn holds_title_bar(&self, x: i32, y: i32, width: u32) -> bool {
+        let (x, y, width) = (x as i64, y as i64, width as i64);
+        let (left, top) = (self.x as i64, self.y as i64);
+        let (right, bottom) = (left + self.width as i64, top + self.height as i64);
+
+        let visible_width = (x + width).min(right) - x.max(left);
+        y >= top && y + TITLE_BAR_HEIGHT <= bottom && visible_width >= MIN_VISIBLE_WIDTH.min(width)
+    }
+}
+
+/// 窗口当前的几何信息（逻辑像素）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WindowGeometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub maximized: bool,
+}
+
+/// 启动时要恢复的窗口状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RestoredWindow {
+    /// 窗口位置（None表示居中）
+    pub position: Option<(i32, i32)>,
+    pub width: u32,
+    pub height: u32,
+    pub maximized: bool,
+}
+
+/// 计算启动时要恢复的窗口状态
+///
+/// # 参数
+/// - `config`: 保存的窗口配置
+/// - `monitors`: 当前可用的显示器（第一个视为主显示器）
+///
+/// # Returns
+/// - `Option<RestoredWindow>`: 要恢复的状态；从未保存过窗口状态时返回None，保持默认窗口
+pub fn restore(config: &WindowConfig, monitors: &[MonitorArea]) -> Option<RestoredWindow> {
+    let (x, y) = (config.x?, config.y?);
+    let monitor = monitors.iter().find(|monitor| monitor.holds_title_bar(x, y, config.width));
+    let position = monitor.filter(|_| config.remember_position).map(|_| (x, y));
+    if monitor.is_none() {
+        log::warn!("⚠️ 保存的窗口位置 ({}, {}) 不在任何显示器上，改为居中", x, y);
+    }
+
+    let (max_width, max_height) = position
+        .and(monitor)
+        .or(monitors.first())
+        .map_or((u32::MAX, u32::MAX), |monitor| (monitor.width, monitor.height));
+    Some(RestoredWindow {
+        position,
+        width: config.width.max(config.min_width).min(max_width),
+        height: config.height.max(config.min_height).min(max_height),
+        maximized: config.maximized,
+    })
+}
+
+/// 把窗口几何信息写入窗口配置
+///
+/// 最大化时只更新最大化标记，保留还原后的位置和大小；最小化时窗口大小为0，不记录。
+///
+/// # Returns
+/// - `bool`: 配置是否有变化
+pub fn apply(config: &mut WindowConfig, geometry: &WindowGeometry) -> bool {
+    if geometry.width == 0 || geometry.height == 0 {
+        return false;
+    }
+    let before = (config.x, config.y, config.width, config.height, config.maximized);
+    config.maximized = geometry.maximized;
+    if !geometry.maximized {
+        config.x = Some(geometry.x);
+        config.y = Some(geometry.y);
+        config.width = geometry.width;
+        config.height = geometry.height;
+    }
+    before != (config.x, config.y, config.width, config.height, config.maximized)
+}
+
+/// 窗口状态保存的防抖
+///
+/// 每次窗口事件调用 `record` 记录最新的几何信息并得到序号，等待 `SAVE_DEBOUNCE` 后调用 `take_if_latest`，
+/// 期间有更新的记录时返回None，由最后一次记录负责保存。
+#[derive(Default)]
+pub struct WindowStateSaver {
+    generation: AtomicU64,
+    pending: Mutex<Option<WindowGeometry>>,
+}
+
+impl WindowStateSaver {
+    /// 记录最新的窗口几何信息
+    ///
+    /// # Returns
+    /// - `u64`: 本次记录的序号
+    pub fn record(&self, geometry: WindowGeometry) -> u64 {
+        let mut pending = self.pending.lock().unwrap();
+        *pending = Some(geometry);
+        self.generation.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    /// 序号仍是最新时取出待保存的几何信息
+    pub fn take_if_latest(&self, generation: u64) -> Option<WindowGeometry> {
+        let mut pending = self.pending.lock().unwrap();
+        if self.generation.load(Ordering::SeqCst) != generation {
+            return None;
+        }
+        pending.take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restore_and_debounce_window_state() {
+        let monitors = [
+            MonitorArea { x: 0, y: 0, width: 1920, height: 1080 },
+            MonitorArea { x: 1920, y: 0, width: 1280, height: 1024 },
+        ];
+        let mut config = WindowConfig::default();
+        assert_eq!(restore(&config, &monitors), None);
+
+        let saver = WindowStateSaver::default();
+        let first = saver.record(WindowGeometry { x: 2000, y: 100, width: 1000, height: 700, maximized: false });
+        let second = saver.record(WindowGeometry { x: 2100, y: 120, width: 1400, height: 1200, maximized: false });
+        assert_eq!(saver.take_if_latest(first), None);
+        let geometry = saver.take_if_latest(second).unwrap();
+        assert!(apply(&mut config, &geometry));
+        assert!(!apply(&mut config, &WindowGeometry { width: 0, height: 0, ..geometry }));
+
+        // 最大化只改标记，位置和大小保持还原后的值
+        assert!(apply(&mut config, &WindowGeometry { x: 1920, y: 0, width: 1280, height: 1024, maximized: true }));
+        assert_eq!((config.x, config.width, config.maximized), (Some(2100), 1400, true));
+
+        // 副屏上的窗口，大小限制在副屏之内
+        let restored = restore(&config, &monitors).unwrap();
+        assert_eq!(restored, RestoredWindow { position: Some((2100, 120)), width: 1280, height: 1024, maximized: true });
+
+        // 副屏断开后改为居中，大小限制在主屏之内
+        let restored = restore(&config, &monitors[..1]).unwrap();
+        assert_eq!((restored.position, restored.width, restored.height), (None, 1400, 1080));
+
+        config.remember_position = false;
+        assert_eq!(restore(&config, &monitors).unwrap().position, None);
+    }
+}
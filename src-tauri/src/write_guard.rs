@@ -0,0 +1,293 @@
+/// 文件写入安全层
+///
+/// `write_file` 命令默认只允许写入“可信目录”：应用的导出目录、系统下载目录，
+/// 以及用户在后端弹出的保存/选择目录对话框中选中的目录（前端不能直接添加可信目录）。
+/// 写入可信目录以外的路径时需要一次性确认令牌：第一次请求返回令牌，
+/// 前端向用户确认后携带该令牌再次请求才会真正写入。
+///
+/// 所有写入都先写入同目录下的临时文件并同步到磁盘，再原子重命名为目标文件，
+/// 写入过程中崩溃不会留下被截断的目标文件。
+
+use crate::i18n::Message;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 确认令牌的有效期
+const CONFIRMATION_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// 写入检查结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WriteCheck {
+    /// 允许写入，携带规范化后的目标路径
+    Allowed(PathBuf),
+    /// 需要用户确认
+    ConfirmationRequired(WriteConfirmation),
+}
+
+/// 写入确认请求
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WriteConfirmation {
+    /// 规范化后的目标路径
+    pub path: String,
+    /// 一次性确认令牌，只对该路径有效
+    pub token: String,
+    /// 需要确认的原因（消息代码和参数，由前端按当前语言显示）
+    pub reason: Message,
+}
+
+/// 待确认的写入
+#[derive(Debug, Clone)]
+struct PendingConfirmation {
+    path: PathBuf,
+    issued_at: Instant,
+}
+
+/// 文件写入守卫
+pub struct WriteGuard {
+    /// 可信目录（已规范化）
+    allowed_dirs: Mutex<Vec<PathBuf>>,
+    /// 已签发但尚未使用的确认令牌
+    pending: Mutex<HashMap<String, PendingConfirmation>>,
+}
+
+impl WriteGuard {
+    /// 创建写入守卫
+    ///
+    /// # 参数
+    /// - `allowed_dirs`: 初始的可信目录（不存在的目录会被忽略）
+    pub fn new<I, P>(allowed_dirs: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        let guard = Self {
+            allowed_dirs: Mutex::new(Vec::new()),
+            pending: Mutex::new(HashMap::new()),
+        };
+        for dir in allowed_dirs {
+            if let Err(e) = guard.allow_directory(dir.as_ref()) {
+                log::debug!("跳过可信目录 {:?}: {}", dir.as_ref(), e);
+            }
+        }
+        guard
+    }
+
+    /// 将目录加入可信目录（只用于启动时的默认目录和用户在对话框中选中的目录）
+    ///
+    /// # Returns
+    /// - `Ok(PathBuf)`: 规范化后的目录路径
+    /// - `Err(String)`: 目录不存在或不是目录时的错误信息
+    pub fn allow_directory<P: AsRef<Path>>(&self, dir: P) -> Result<PathBuf, String> {
+        let dir = dir.as_ref()
+            .canonicalize()
+            .map_err(|e| Message::new("write.directory_not_found").with("path", dir.as_ref().display()).with("error", e).text())?;
+        if !dir.is_dir() {
+            return Err(Message::new("write.not_a_directory").with("path", dir.display()).text());
+        }
+
+        let mut allowed = self.allowed_dirs.lock().unwrap();
+        if !allowed.contains(&dir) {
+            log::info!("🔓 添加可信写入目录: {}", dir.display());
+            allowed.push(dir.clone());
+        }
+        Ok(dir)
+    }
+
+    /// 当前的可信目录
+    pub fn allowed_directories(&self) -> Vec<PathBuf> {
+        self.allowed_dirs.lock().unwrap().clone()
+    }
+
+    /// 检查目标路径是否允许写入
+    ///
+    /// # 参数
+    /// - `path`: 目标文件路径（必须是绝对路径）
+    /// - `token`: 之前签发的确认令牌（可选）
+    ///
+    /// # Returns
+    /// - `Ok(WriteCheck::Allowed)`: 目标位于可信目录中，或令牌有效
+    /// - `Ok(WriteCheck::ConfirmationRequired)`: 需要携带返回的令牌再次请求
+    /// - `Err(String)`: 路径无效时的错误信息
+    pub fn check<P: AsRef<Path>>(&self, path: P, token: Option<&str>) -> Result<WriteCheck, String> {
+        let target = normalize_target(path.as_ref())?;
+
+        if self.is_allowed(&target) {
+            return Ok(WriteCheck::Allowed(target));
+        }
+
+        let mut pending = self.pending.lock().unwrap();
+        pending.retain(|_, p| p.issued_at.elapsed() < CONFIRMATION_TTL);
+
+        if let Some(token) = token {
+            // 令牌只能使用一次，且必须与签发时的路径一致
+            if pending.get(token).map(|p| p.path == target).unwrap_or(false) {
+                pending.remove(token);
+                log::warn!("⚠️ 已确认写入可信目录以外的路径: {}", target.display());
+                return Ok(WriteCheck::Allowed(target));
+            }
+            log::warn!("⚠️ 确认令牌无效或已过期: {}", target.display());
+        }
+
+        let token = uuid::Uuid::new_v4().to_string();
+        pending.insert(token.clone(), PendingConfirmation { path: target.clone(), issued_at: Instant::now() });
+
+        Ok(WriteCheck::ConfirmationRequired(WriteConfirmation {
+            path: target.to_string_lossy().into_owned(),
+            token,
+            reason: Message::new("write.outside_trusted_directories").with("path", target.display()),
+        }))
+    }
+
+    fn is_allowed(&self, target: &Path) -> bool {
+        self.allowed_dirs.lock().unwrap().iter().any(|dir| target.starts_with(dir))
+    }
+}
+
+/// 规范化目标路径
+///
+/// 目标文件可能尚不存在：解析最近的已存在祖先目录（展开符号链接和 `..`），
+/// 再拼接剩余部分。不存在的部分中出现 `..` 时拒绝，避免绕过目录检查。
+fn normalize_target(path: &Path) -> Result<PathBuf, String> {
+    if !path.is_absolute() {
+        return Err(Message::new("write.path_not_absolute").with("path", path.display()).text());
+    }
+    if path.file_name().is_none() {
+        return Err(Message::new("write.missing_file_name").with("path", path.display()).text());
+    }
+
+    let mut existing = path;
+    let mut rest = Vec::new();
+    while !existing.exists() {
+        let name = existing.file_name()
+            .ok_or_else(|| Message::new("write.invalid_path").with("path", path.display()).text())?;
+        rest.push(name.to_os_string());
+        existing = existing.parent()
+            .ok_or_else(|| Message::new("write.invalid_path").with("path", path.display()).text())?;
+    }
+
+    let mut target = existing.canonicalize()
+        .map_err(|e| Message::new("write.resolve_failed").with("path", path.display()).with("error", e).text())?;
+    for name in rest.into_iter().rev() {
+        target.push(name);
+    }
+
+    if target.is_dir() {
+        return Err(Message::new("write.target_is_directory").with("path", target.display()).text());
+    }
+    Ok(target)
+}
+
+/// 原子写入文件
+///
+/// 先写入同目录下的临时文件并同步到磁盘，再重命名为目标文件。
+/// 父目录不存在时自动创建。
+///
+/// # 参数
+/// - `path`: 目标文件路径
+/// - `contents`: 文件内容
+///
+/// # Returns
+/// - `Ok(())`: 写入成功
+/// - `Err(String)`: 创建目录、写入或重命名失败时的错误信息
+pub fn atomic_write<P: AsRef<Path>>(path: P, contents: &[u8]) -> Result<(), String> {
+    let path = path.as_ref();
+    let parent = path.parent()
+        .ok_or_else(|| Message::new("write.invalid_path").with("path", path.display()).text())?;
+    std::fs::create_dir_all(parent)
+        .map_err(|e| Message::new("write.create_directory_failed").with("error", e).text())?;
+
+    let file_name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    let temp_path = parent.join(format!(".{}.{}.tmp", file_name, uuid::Uuid::new_v4().simple()));
+
+    let result = (|| {
+        let mut file = std::fs::File::create(&temp_path)
+            .map_err(|e| Message::new("write.create_temp_failed").with("error", e).text())?;
+        file.write_all(contents)
+            .map_err(|e| Message::new("write.write_failed").with("error", e).text())?;
+        file.sync_all()
+            .map_err(|e| Message::new("write.sync_failed").with("error", e).text())?;
+        drop(file);
+
+        std::fs::rename(&temp_path, path)
+            .map_err(|e| Message::new("write.replace_failed").with("error", e).text())
+    })();
+
+    if result.is_err() {
+        std::fs::remove_file(&temp_path).ok();
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("log_whisper_{}_{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_allowed_directory_needs_no_confirmation() {
+        let dir = temp_dir("guard_allowed");
+        let guard = WriteGuard::new([&dir]);
+
+        let target = dir.join("exports").join("out.log");
+        let check = guard.check(&target, None).unwrap();
+        assert_eq!(check, WriteCheck::Allowed(dir.canonicalize().unwrap().join("exports").join("out.log")));
+
+        let escaped = guard.check(dir.join("..").join("escape.log"), None).unwrap();
+        assert!(matches!(escaped, WriteCheck::ConfirmationRequired(_)));
+        assert!(guard.check(dir.join("missing").join("..").join("..").join("escape.log"), None).is_err());
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_outside_path_requires_matching_token() {
+        let allowed = temp_dir("guard_inside");
+        let outside = temp_dir("guard_outside");
+        let guard = WriteGuard::new([&allowed]);
+        let target = outside.join("out.log");
+
+        let confirmation = match guard.check(&target, None).unwrap() {
+            WriteCheck::ConfirmationRequired(confirmation) => confirmation,
+            other => panic!("unexpected {:?}", other),
+        };
+        assert_eq!(confirmation.reason.code, "write.outside_trusted_directories");
+
+        // 令牌与路径绑定
+        let other = guard.check(outside.join("other.log"), Some(&confirmation.token)).unwrap();
+        assert!(matches!(other, WriteCheck::ConfirmationRequired(_)));
+
+        let check = guard.check(&target, Some(&confirmation.token)).unwrap();
+        assert!(matches!(check, WriteCheck::Allowed(_)));
+
+        // 令牌只能使用一次
+        let again = guard.check(&target, Some(&confirmation.token)).unwrap();
+        assert!(matches!(again, WriteCheck::ConfirmationRequired(_)));
+
+        std::fs::remove_dir_all(allowed).ok();
+        std::fs::remove_dir_all(outside).ok();
+    }
+
+    #[test]
+    fn test_atomic_write_replaces_file() {
+        let dir = temp_dir("guard_atomic");
+        let target = dir.join("nested").join("out.log");
+
+        atomic_write(&target, b"first").unwrap();
+        atomic_write(&target, b"second").unwrap();
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "second");
+
+        let leftovers = std::fs::read_dir(dir.join("nested")).unwrap().count();
+        assert_eq!(leftovers, 1);
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
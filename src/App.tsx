@@ -158,7 +158,7 @@ function App() {
         multiple: false,
         filters: [{
           name: '日志文件',
-          extensions: ['log', 'txt', 'out']
+          extensions: ['log', 'txt', 'out', 'xml']
         }]
       })
 
@@ -176,10 +176,10 @@ function App() {
   const convertLogEntriesToLogLines = (entries: LogEntry[]): LogLine[] => {
     return entries.map(entry => ({
       id: `log-${entry.line_number}`,
-      content: entry.formatted_content || entry.content,
+      content: entry.formatted_content || entry.message || entry.content,
       level: entry.level || 'info',
       timestamp: entry.timestamp || '',
-      formatted: entry.formatted_content || entry.content,
+      formatted: entry.formatted_content || entry.message || entry.content,
       lineNumber: entry.line_number
     }))
   }